@@ -0,0 +1,231 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const MESSAGE_MAX_CHARS: usize = 160;
+const ERROR_BODY_LIMIT: usize = 4 * 1024 * 1024;
+
+/// Error shape every API failure is reported in: a short `message` for inline display, the full
+/// `detail` (usually an anyhow chain) for the expandable section, and an optional remediation hint.
+/// The title and message are derived when it is serialized, which keeps handler `Result`s small.
+#[derive(Debug, Clone)]
+pub struct AppError {
+    pub status: StatusCode,
+    pub kind: &'static str,
+    pub detail: String,
+    pub hint: Option<&'static str>,
+    /// Filled in from the request path by `structure_error_responses` when the handler leaves it empty.
+    pub component: String,
+    /// Machine-readable fields some failures add, e.g. `error: "index_locked"` with the `lock` it found.
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    kind: &'a str,
+    title: &'a str,
+    message: String,
+    detail: &'a str,
+    hint: Option<&'a str>,
+    component: &'a str,
+    #[serde(flatten)]
+    extra: &'a Map<String, Value>,
+}
+
+impl Serialize for AppError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorBody {
+            kind: self.kind,
+            title: title_for(self.kind),
+            message: short_message(&self.detail),
+            detail: &self.detail,
+            hint: self.hint,
+            component: &self.component,
+            extra: &self.extra,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl AppError {
+    /// Classifies a plain error string by status code and well-known failure text.
+    pub fn from_detail(status: StatusCode, component: &str, detail: &str) -> Self {
+        let detail = detail.trim();
+        let lower = detail.to_lowercase();
+        let (kind, hint) = if lower.contains("index.lock") {
+            ("locked", Some("Another git process holds the index; wait for it or remove the stale lock."))
+        } else if lower.contains("not a git repository") || lower.contains("does not appear to be a git repo") {
+            ("git", Some("Check that the repository path points at a git working tree."))
+        } else if lower.contains("git not found") {
+            ("git", Some("Install git and make sure it is on PATH."))
+        } else if lower.contains("error sending request") || lower.contains("connection refused") || lower.contains("timed out") {
+            ("network", Some("Check the service is running and review the proxy and CA settings under Network."))
+        } else if lower.contains("no such file or directory") || lower.contains("cannot find the path") {
+            ("io", Some("Check that the path exists and is readable."))
+        } else if lower.starts_with("git ") || lower.contains("fatal:") {
+            ("git", None)
+        } else {
+            match status {
+                StatusCode::NOT_FOUND => ("not_found", None),
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ("forbidden", None),
+                StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => ("invalid_request", None),
+                StatusCode::CONFLICT => ("conflict", Some("Refresh and try again; the data changed in the meantime.")),
+                StatusCode::LOCKED => ("locked", None),
+                _ => ("internal", None),
+            }
+        };
+        Self {
+            status,
+            kind,
+            detail: detail.to_string(),
+            hint,
+            component: component.to_string(),
+            extra: Map::new(),
+        }
+    }
+
+    pub fn with_extra(mut self, key: &str, value: impl Serialize) -> Self {
+        self.extra.insert(key.to_string(), serde_json::to_value(value).unwrap_or(Value::Null));
+        self
+    }
+}
+
+/// Handler helpers still build `(status, message)` pairs; `?` turns them into an `AppError`.
+impl From<(StatusCode, String)> for AppError {
+    fn from((status, detail): (StatusCode, String)) -> Self {
+        let detail = if detail.trim().is_empty() { status.canonical_reason().unwrap_or("request failed") } else { &detail };
+        Self::from_detail(status, "", detail)
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, Json(&self)).into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Gives handler `AppError`s the component of the route that failed, and lifts the remaining
+/// plain-text failures (such as extractor rejections) into `AppError` JSON. Other JSON responses
+/// pass through untouched.
+pub async fn structure_error_responses(req: Request, next: Next) -> Response {
+    let component = component_for_path(req.uri().path());
+    let mut response = next.run(req).await;
+    if let Some(error) = response.extensions_mut().remove::<AppError>() {
+        if !error.component.is_empty() {
+            return response;
+        }
+        return AppError { component, ..error }.into_response();
+    }
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json") || value.starts_with("text/event-stream"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, ERROR_BODY_LIMIT).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let detail = String::from_utf8_lossy(&bytes);
+    let detail = if detail.trim().is_empty() { status.canonical_reason().unwrap_or("request failed").to_string() } else { detail.to_string() };
+    AppError::from_detail(status, &component, &detail).into_response()
+}
+
+fn component_for_path(path: &str) -> String {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match (segments.next(), segments.next()) {
+        (Some("api"), Some(component)) if !component.is_empty() => component.to_string(),
+        (Some(first), _) => first.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// First clause of an anyhow `{:#}` chain, e.g. "git add -- a failed" out of
+/// "git add -- a failed: fatal: pathspec 'a' did not match any files".
+fn short_message(detail: &str) -> String {
+    let first_line = detail.lines().next().unwrap_or_default();
+    let clause = first_line.split(": ").next().unwrap_or(first_line).trim();
+    if clause.chars().count() <= MESSAGE_MAX_CHARS {
+        return clause.to_string();
+    }
+    format!("{}…", clause.chars().take(MESSAGE_MAX_CHARS).collect::<String>())
+}
+
+fn title_for(kind: &str) -> &'static str {
+    match kind {
+        "locked" => "Repository is locked",
+        "git" => "Git command failed",
+        "network" => "Network request failed",
+        "io" => "File access failed",
+        "not_found" => "Not found",
+        "forbidden" => "Not allowed",
+        "invalid_request" => "Invalid request",
+        "conflict" => "Changed elsewhere",
+        _ => "Something went wrong",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{middleware::from_fn, routing::get, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn body_json(response: Response) -> Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), ERROR_BODY_LIMIT).await.unwrap()).unwrap()
+    }
+
+    fn get_request(path: &str) -> Request {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn handler_errors_keep_their_extra_fields() {
+        let app = Router::new()
+            .route(
+                "/api/review/stage",
+                get(|| async {
+                    Err::<(), _>(
+                        AppError::from_detail(StatusCode::LOCKED, "", "index.lock is held")
+                            .with_extra("error", "index_locked")
+                            .with_extra("lock", serde_json::json!({ "pid": 42 })),
+                    )
+                }),
+            )
+            .layer(from_fn(structure_error_responses));
+
+        let response = app.oneshot(get_request("/api/review/stage")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::LOCKED);
+        let body = body_json(response).await;
+        assert_eq!(body["error"], "index_locked");
+        assert_eq!(body["lock"]["pid"], 42);
+        assert_eq!(body["message"], "index.lock is held");
+        assert_eq!(body["component"], "review");
+    }
+
+    #[tokio::test]
+    async fn plain_text_failures_become_app_errors() {
+        let app = Router::new()
+            .route("/api/review/stage", get(|| async { (StatusCode::BAD_REQUEST, "missing repo_ref") }))
+            .layer(from_fn(structure_error_responses));
+
+        let response = app.oneshot(get_request("/api/review/stage")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_json(response).await;
+        assert_eq!(body["kind"], "invalid_request");
+        assert_eq!(body["detail"], "missing repo_ref");
+    }
+}
@@ -1,23 +1,46 @@
+use std::sync::{Arc, Mutex};
+
 use sqlx::SqlitePool;
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
-use crate::models::WorkflowEventStreamItem;
+use crate::{
+    engine::{
+        capabilities::{
+            analysis::commands::ProjectCommandCache, context_export::ContextExportJobs, inference::scheduler::InferenceScheduler,
+        },
+        save_hooks::SaveHooks,
+    },
+    models::WorkflowEventStreamItem,
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: SqlitePool,
     workflow_events_tx: broadcast::Sender<WorkflowEventStreamItem>,
     process_session_id: String,
+    inference_scheduler: Arc<InferenceScheduler>,
+    open_requests_tx: broadcast::Sender<String>,
+    pending_open: Arc<Mutex<Option<String>>>,
+    project_commands: Arc<ProjectCommandCache>,
+    context_exports: Arc<ContextExportJobs>,
+    save_hooks: Arc<SaveHooks>,
 }
 
 impl AppState {
     pub fn new(db: SqlitePool) -> Self {
         let (workflow_events_tx, _) = broadcast::channel(4096);
+        let (open_requests_tx, _) = broadcast::channel(16);
         Self {
             db,
             workflow_events_tx,
             process_session_id: Uuid::new_v4().to_string(),
+            inference_scheduler: Arc::new(InferenceScheduler::default()),
+            open_requests_tx,
+            pending_open: Arc::new(Mutex::new(None)),
+            project_commands: Arc::new(ProjectCommandCache::default()),
+            context_exports: Arc::new(ContextExportJobs::default()),
+            save_hooks: Arc::new(SaveHooks::default()),
         }
     }
 
@@ -32,4 +55,33 @@ impl AppState {
     pub fn process_session_id(&self) -> &str {
         &self.process_session_id
     }
+
+    pub fn inference_scheduler(&self) -> &Arc<InferenceScheduler> {
+        &self.inference_scheduler
+    }
+
+    pub fn project_commands(&self) -> &ProjectCommandCache {
+        &self.project_commands
+    }
+
+    pub fn context_exports(&self) -> &ContextExportJobs {
+        &self.context_exports
+    }
+
+    pub fn save_hooks(&self) -> &Arc<SaveHooks> {
+        &self.save_hooks
+    }
+
+    /// Asks open windows to switch to `path`. Kept as pending when no window is listening yet,
+    /// so the first one to connect picks it up.
+    pub fn request_open(&self, path: String) {
+        if self.open_requests_tx.send(path.clone()).is_err() {
+            *self.pending_open.lock().unwrap() = Some(path);
+        }
+    }
+
+    pub fn subscribe_open_requests(&self) -> (Option<String>, broadcast::Receiver<String>) {
+        let rx = self.open_requests_tx.subscribe();
+        (self.pending_open.lock().unwrap().take(), rx)
+    }
 }
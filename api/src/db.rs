@@ -4,7 +4,14 @@ use std::collections::HashMap;
 use sqlx::{sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions}, Row, SqlitePool};
 use uuid::Uuid;
 
-use crate::engine::capabilities::changeset::persistence::{CHANGESET_ATTEMPTS_TABLE_SQL, CHANGESET_FILE_EFFECTS_TABLE_SQL};
+use crate::engine::{
+    analysis_snapshots::ANALYSIS_SNAPSHOTS_TABLE_SQL,
+    capabilities::changeset::persistence::{CHANGESET_ATTEMPTS_TABLE_SQL, CHANGESET_FILE_EFFECTS_TABLE_SQL},
+    code_review::{CODE_REVIEWS_TABLE_SQL, CODE_REVIEW_COMMENTS_TABLE_SQL},
+    editor_state::EDITOR_FILE_STATE_TABLE_SQL,
+    export_destinations::EXPORT_DESTINATIONS_TABLE_SQL,
+    history::APP_HISTORY_TABLE_SQL,
+};
 
 pub fn repo_basename_for_workflow_key(repo_ref: &str) -> String {
     let normalized = repo_ref.trim().replace('\\', "/");
@@ -84,6 +91,17 @@ async fn backfill_changeset_workflow_keys(db: &SqlitePool) -> anyhow::Result<()>
     Ok(())
 }
 
+pub async fn load_app_settings_section(db: &SqlitePool, key: &str) -> anyhow::Result<Option<serde_json::Value>> {
+    let row = sqlx::query("SELECT settings_json FROM app_settings WHERE id = 'global'")
+        .fetch_optional(db)
+        .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let value: serde_json::Value = serde_json::from_str(row.get::<String, _>("settings_json").as_str())?;
+    Ok(value.get(key).cloned())
+}
+
 pub async fn connect(url: &str) -> anyhow::Result<SqlitePool> {
     let options = SqliteConnectOptions::from_str(url)?
         .create_if_missing(true)
@@ -95,6 +113,19 @@ pub async fn connect(url: &str) -> anyhow::Result<SqlitePool> {
         .await?)
 }
 
+/// Migrated in-memory database whose global settings row holds `settings`.
+#[cfg(test)]
+pub async fn test_db_with_settings(settings: serde_json::Value) -> SqlitePool {
+    let db = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+    migrate(&db).await.unwrap();
+    sqlx::query("INSERT INTO app_settings (id, settings_json, created_at, updated_at) VALUES ('global', ?, '', '')")
+        .bind(settings.to_string())
+        .execute(&db)
+        .await
+        .unwrap();
+    db
+}
+
 pub async fn migrate(db: &SqlitePool) -> anyhow::Result<()> {
     sqlx::query(
         r#"
@@ -178,6 +209,38 @@ pub async fn migrate(db: &SqlitePool) -> anyhow::Result<()> {
         .execute(db)
         .await?;
 
+    sqlx::query(APP_HISTORY_TABLE_SQL)
+        .execute(db)
+        .await?;
+
+    sqlx::query(CODE_REVIEWS_TABLE_SQL)
+        .execute(db)
+        .await?;
+
+    sqlx::query(CODE_REVIEW_COMMENTS_TABLE_SQL)
+        .execute(db)
+        .await?;
+
+    sqlx::query(EDITOR_FILE_STATE_TABLE_SQL)
+        .execute(db)
+        .await?;
+
+    sqlx::query(EXPORT_DESTINATIONS_TABLE_SQL)
+        .execute(db)
+        .await?;
+
+    sqlx::query(ANALYSIS_SNAPSHOTS_TABLE_SQL)
+        .execute(db)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_analysis_snapshots_repo ON analysis_snapshots (repo_ref, created_at)")
+        .execute(db)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_code_review_comments_review ON code_review_comments (review_id)")
+        .execute(db)
+        .await?;
+
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_changeset_attempts_repo_created ON changeset_attempts (repo_ref, created_at)")
     .execute(db)
     .await?;
@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    engine::capabilities::{
+        git::git::{resolve_read_ref, run_git},
+        inference::{api::oai::OpenAIInferenceClient, scheduler::load_inference_limits, InferenceConfig},
+    },
+    http_client::{build_http_client, load_network_settings},
+};
+
+/// Diffs beyond this are cut before being sent, so one huge commit can't blow the context window.
+const MAX_DIFF_CHARS: usize = 60_000;
+
+const EXPLAIN_COMMIT_INSTRUCTIONS: &str = "Explain this git commit to a reviewer. Start with a one-sentence summary, \
+then list the notable changes grouped by intent, and call out anything risky or surprising. Answer in Markdown.";
+
+const SUMMARIZE_DIFF_INSTRUCTIONS: &str = "Summarize these pending changes in a git working tree. Start with a \
+one-sentence summary, then list what changed per area, and finish with a suggested commit message. Answer in Markdown.";
+
+const EDIT_SELECTION_INSTRUCTIONS: &str = "You edit one selected region of a source file. Apply the user's instruction \
+to the SELECTION only and reply with the replacement text for that region: no explanation, no Markdown fences, and \
+nothing from the surrounding lines. Keep the selection's indentation style.";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SelectionEdit {
+    pub path: String,
+    pub selection: String,
+    pub instruction: String,
+    /// A few lines before and after the selection, sent as read-only context.
+    #[serde(default)]
+    pub before: String,
+    #[serde(default)]
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AiActionResult {
+    pub text: String,
+    pub model: String,
+    /// True when the diff was cut at `MAX_DIFF_CHARS` before sending.
+    pub truncated: bool,
+    pub total_tokens: Option<u64>,
+}
+
+/// Sends one self-contained request to the API transport, outside any run.
+/// Ad-hoc requests share the inference scheduler under the nil run id so they respect the same limits.
+pub async fn complete(state: &AppState, config: &InferenceConfig, instructions: &str, input: String) -> Result<(String, Option<u64>)> {
+    let limits = load_inference_limits(&state.db).await;
+    let _permit = state.inference_scheduler().enqueue(Uuid::nil()).acquire(limits).await;
+
+    let network = load_network_settings(&state.db).await?;
+    let client = OpenAIInferenceClient::from_env().with_http(build_http_client(&network)?);
+    let generation = config.generation.for_model(&config.model);
+    let (text, _conversation_id, total_tokens) = client
+        .chat_in_conversation(
+            &config.model,
+            None,
+            Vec::new(),
+            vec![("developer".to_string(), instructions.to_string()), ("user".to_string(), input)],
+            &generation,
+        )
+        .await?;
+    Ok((text, total_tokens))
+}
+
+/// The `git show` text of `commit`, resolved to a commit id first. Errors here are the caller's input;
+/// [`explain_commit`] only fails on the provider side.
+pub fn commit_to_explain(repo: &Path, commit: &str) -> Result<String> {
+    if commit.trim().is_empty() {
+        bail!("commit is required");
+    }
+    let commit = resolve_read_ref(repo, commit)?;
+    if commit == "WORKTREE" {
+        bail!("pick a commit to explain");
+    }
+    let show = run_git(repo, &["show", "--stat", "--patch", "--format=fuller", "--end-of-options", &commit])?;
+    Ok(String::from_utf8_lossy(&show).to_string())
+}
+
+pub async fn explain_commit(state: &AppState, config: &InferenceConfig, show: &str) -> Result<AiActionResult> {
+    summarize(state, config, EXPLAIN_COMMIT_INSTRUCTIONS, show).await
+}
+
+/// The pending `staged` or `unstaged` diff, refusing an empty one.
+pub fn diff_to_summarize(repo: &Path, scope: &str) -> Result<String> {
+    let args: &[&str] = match scope {
+        "staged" => &["diff", "--cached", "--stat", "--patch"],
+        "unstaged" => &["diff", "--stat", "--patch"],
+        other => bail!("unsupported diff scope {other}"),
+    };
+    let diff = String::from_utf8_lossy(&run_git(repo, args)?).to_string();
+    if diff.trim().is_empty() {
+        bail!("there are no {scope} changes to summarize");
+    }
+    Ok(diff)
+}
+
+pub async fn summarize_diff(state: &AppState, config: &InferenceConfig, diff: &str) -> Result<AiActionResult> {
+    summarize(state, config, SUMMARIZE_DIFF_INSTRUCTIONS, diff).await
+}
+
+pub fn check_selection_edit(edit: &SelectionEdit) -> Result<()> {
+    if edit.instruction.trim().is_empty() {
+        bail!("an instruction is required");
+    }
+    if edit.selection.is_empty() {
+        bail!("select some text to edit");
+    }
+    Ok(())
+}
+
+/// Returns the model's replacement for `edit.selection` as `text`, ready to swap into the editor.
+/// The edit is checked with [`check_selection_edit`] first.
+pub async fn edit_selection(state: &AppState, config: &InferenceConfig, edit: &SelectionEdit) -> Result<AiActionResult> {
+    let input = format!(
+        "File: {}\n\nInstruction: {}\n\n<BEFORE>\n{}\n</BEFORE>\n<SELECTION>\n{}\n</SELECTION>\n<AFTER>\n{}\n</AFTER>",
+        edit.path,
+        edit.instruction.trim(),
+        edit.before,
+        edit.selection,
+        edit.after
+    );
+    let (text, total_tokens) = complete(state, config, EDIT_SELECTION_INSTRUCTIONS, input).await?;
+    let mut replacement = strip_code_fence(&text).to_string();
+    if edit.selection.ends_with('\n') && !replacement.ends_with('\n') {
+        replacement.push('\n');
+    }
+    Ok(AiActionResult { text: replacement, model: config.model.clone(), truncated: false, total_tokens })
+}
+
+/// Models often wrap code in a fence despite being told not to; keep only the fenced body.
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return text.trim_matches('\n');
+    };
+    let body = rest.split_once('\n').map(|(_, body)| body).unwrap_or("");
+    body.strip_suffix("```").unwrap_or(body).trim_end_matches('\n')
+}
+
+async fn summarize(state: &AppState, config: &InferenceConfig, instructions: &str, diff: &str) -> Result<AiActionResult> {
+    let (input, truncated) = truncate_chars(diff, MAX_DIFF_CHARS);
+    let input = if truncated {
+        format!("{input}\n\n[diff truncated after {MAX_DIFF_CHARS} characters]")
+    } else {
+        input.to_string()
+    };
+    let (text, total_tokens) = complete(state, config, instructions, input).await?;
+    Ok(AiActionResult { text: text.trim().to_string(), model: config.model.clone(), truncated, total_tokens })
+}
+
+pub(crate) fn truncate_chars(text: &str, max: usize) -> (&str, bool) {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => (&text[..index], true),
+        None => (text, false),
+    }
+}
@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::engine::capabilities::{
+    analysis::sizes::{collect_file_sizes, collect_file_sizes_at, FileSizeEntry},
+    git::git::{resolve_read_ref, run_git_allow_fail},
+};
+
+pub const ANALYSIS_SNAPSHOTS_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS analysis_snapshots (
+    id TEXT PRIMARY KEY,
+    repo_ref TEXT NOT NULL,
+    name TEXT NOT NULL,
+    git_ref TEXT NOT NULL,
+    commit_sha TEXT,
+    total_files INTEGER NOT NULL,
+    total_bytes INTEGER NOT NULL,
+    total_lines INTEGER NOT NULL,
+    files_json TEXT NOT NULL,
+    created_at TEXT NOT NULL
+)
+"#;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisSnapshot {
+    pub id: String,
+    pub repo_ref: String,
+    pub name: String,
+    pub git_ref: String,
+    /// Commit the snapshot was taken at; for `WORKTREE` this is HEAD at the time, plus local changes.
+    pub commit: Option<String>,
+    pub total_files: i64,
+    pub total_bytes: i64,
+    pub total_lines: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryDelta {
+    pub directory: String,
+    pub base_files: u64,
+    pub head_files: u64,
+    pub base_lines: u64,
+    pub head_lines: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageShare {
+    pub language: String,
+    pub base_lines: u64,
+    pub head_lines: u64,
+    /// Share of all counted lines, 0..=1.
+    pub base_share: f64,
+    pub head_share: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotComparison {
+    pub base: AnalysisSnapshot,
+    pub head: AnalysisSnapshot,
+    pub added: Vec<FileSizeEntry>,
+    pub removed: Vec<FileSizeEntry>,
+    /// Files present in both whose size changed.
+    pub modified: usize,
+    pub directories: Vec<DirectoryDelta>,
+    pub languages: Vec<LanguageShare>,
+}
+
+/// Records stats and the file inventory of `git_ref` (or the worktree) under `name`.
+pub async fn create(db: &SqlitePool, repo_ref: &str, name: &str, git_ref: &str) -> Result<AnalysisSnapshot> {
+    let repo_ref = repo_ref.trim().to_string();
+    let name = name.trim().to_string();
+    if repo_ref.is_empty() || name.is_empty() {
+        bail!("repo_ref and name are required");
+    }
+    let git_ref = match git_ref.trim() {
+        "" => "WORKTREE".to_string(),
+        other => other.to_string(),
+    };
+
+    let (files, commit) = {
+        let repo_ref = repo_ref.clone();
+        let git_ref = git_ref.clone();
+        tokio::task::spawn_blocking(move || -> Result<_> {
+            let repo = Path::new(&repo_ref);
+            if git_ref == "WORKTREE" {
+                let (code, stdout, _) = run_git_allow_fail(repo, &["rev-parse", "--verify", "--quiet", "HEAD"])?;
+                let commit = (code == 0).then(|| String::from_utf8_lossy(&stdout).trim().to_string());
+                return Ok((collect_file_sizes(repo)?, commit));
+            }
+            let commit = resolve_read_ref(repo, &git_ref)?;
+            Ok((collect_file_sizes_at(repo, &commit)?, Some(commit)))
+        })
+        .await??
+    };
+
+    let snapshot = AnalysisSnapshot {
+        id: Uuid::new_v4().to_string(),
+        total_files: files.len() as i64,
+        total_bytes: files.iter().map(|file| file.bytes as i64).sum(),
+        total_lines: files.iter().map(|file| file.lines as i64).sum(),
+        created_at: Utc::now().to_rfc3339(),
+        repo_ref,
+        name,
+        git_ref,
+        commit,
+    };
+    sqlx::query(
+        "INSERT INTO analysis_snapshots (id, repo_ref, name, git_ref, commit_sha, total_files, total_bytes, total_lines, files_json, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&snapshot.id)
+    .bind(&snapshot.repo_ref)
+    .bind(&snapshot.name)
+    .bind(&snapshot.git_ref)
+    .bind(&snapshot.commit)
+    .bind(snapshot.total_files)
+    .bind(snapshot.total_bytes)
+    .bind(snapshot.total_lines)
+    .bind(serde_json::to_string(&files)?)
+    .bind(&snapshot.created_at)
+    .execute(db)
+    .await?;
+    Ok(snapshot)
+}
+
+pub async fn list(db: &SqlitePool, repo_ref: &str) -> Result<Vec<AnalysisSnapshot>> {
+    let rows = sqlx::query("SELECT * FROM analysis_snapshots WHERE repo_ref = ? ORDER BY created_at DESC")
+        .bind(repo_ref.trim())
+        .fetch_all(db)
+        .await?;
+    Ok(rows.iter().map(snapshot_from_row).collect())
+}
+
+pub async fn delete(db: &SqlitePool, id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM analysis_snapshots WHERE id = ?").bind(id).execute(db).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Diffs two snapshots. Directories are grouped by their first `depth` path segments.
+pub async fn compare(db: &SqlitePool, base_id: &str, head_id: &str, depth: usize) -> Result<SnapshotComparison> {
+    let (base, base_files) = load(db, base_id).await?;
+    let (head, head_files) = load(db, head_id).await?;
+
+    let base_by_path: BTreeMap<&str, &FileSizeEntry> = base_files.iter().map(|file| (file.path.as_str(), file)).collect();
+    let head_by_path: BTreeMap<&str, &FileSizeEntry> = head_files.iter().map(|file| (file.path.as_str(), file)).collect();
+    let added = head_files.iter().filter(|file| !base_by_path.contains_key(file.path.as_str())).cloned().collect();
+    let removed = base_files.iter().filter(|file| !head_by_path.contains_key(file.path.as_str())).cloned().collect();
+    let modified = head_files
+        .iter()
+        .filter(|file| base_by_path.get(file.path.as_str()).is_some_and(|old| old.bytes != file.bytes))
+        .count();
+
+    let mut directories: BTreeMap<String, DirectoryDelta> = BTreeMap::new();
+    for (files, is_head) in [(&base_files, false), (&head_files, true)] {
+        for file in files.iter() {
+            let directory = directory_key(&file.path, depth.max(1));
+            let entry = directories.entry(directory.clone()).or_insert_with(|| DirectoryDelta {
+                directory,
+                base_files: 0,
+                head_files: 0,
+                base_lines: 0,
+                head_lines: 0,
+            });
+            if is_head {
+                entry.head_files += 1;
+                entry.head_lines += file.lines;
+            } else {
+                entry.base_files += 1;
+                entry.base_lines += file.lines;
+            }
+        }
+    }
+
+    let mut languages: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for file in &base_files {
+        languages.entry(file.language.clone()).or_default().0 += file.lines;
+    }
+    for file in &head_files {
+        languages.entry(file.language.clone()).or_default().1 += file.lines;
+    }
+    let share = |lines: u64, total: i64| if total > 0 { lines as f64 / total as f64 } else { 0.0 };
+    let mut languages: Vec<LanguageShare> = languages
+        .into_iter()
+        .map(|(language, (base_lines, head_lines))| LanguageShare {
+            base_share: share(base_lines, base.total_lines),
+            head_share: share(head_lines, head.total_lines),
+            language,
+            base_lines,
+            head_lines,
+        })
+        .collect();
+    languages.sort_by(|a, b| b.head_lines.cmp(&a.head_lines).then(b.base_lines.cmp(&a.base_lines)));
+
+    Ok(SnapshotComparison {
+        added,
+        removed,
+        modified,
+        directories: directories.into_values().collect(),
+        languages,
+        base,
+        head,
+    })
+}
+
+async fn load(db: &SqlitePool, id: &str) -> Result<(AnalysisSnapshot, Vec<FileSizeEntry>)> {
+    let row = sqlx::query("SELECT * FROM analysis_snapshots WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .with_context(|| format!("analysis snapshot {id} not found"))?;
+    let files = serde_json::from_str(row.get::<String, _>("files_json").as_str()).context("corrupt snapshot inventory")?;
+    Ok((snapshot_from_row(&row), files))
+}
+
+fn snapshot_from_row(row: &SqliteRow) -> AnalysisSnapshot {
+    AnalysisSnapshot {
+        id: row.get("id"),
+        repo_ref: row.get("repo_ref"),
+        name: row.get("name"),
+        git_ref: row.get("git_ref"),
+        commit: row.get("commit_sha"),
+        total_files: row.get("total_files"),
+        total_bytes: row.get("total_bytes"),
+        total_lines: row.get("total_lines"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn directory_key(path: &str, depth: usize) -> String {
+    let segments: Vec<&str> = path.split('/').collect();
+    if segments.len() == 1 {
+        return ".".to_string();
+    }
+    segments[..(segments.len() - 1).min(depth)].join("/")
+}
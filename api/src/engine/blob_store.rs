@@ -0,0 +1,127 @@
+//! Content-addressed file contents under `<data dir>/blobs`, named by SHA-256. The undo history and
+//! the trash keep only these ids, so large or repeated snapshots don't bloat the database.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{bail, Context, Result};
+use ring::digest::{digest, SHA256};
+use uuid::Uuid;
+
+use crate::runtime_env::data_dir;
+
+/// Unreferenced blobs younger than this survive a sweep; their snapshot may not be recorded yet.
+const SWEEP_GRACE: Duration = Duration::from_secs(60 * 60);
+
+fn blobs_root() -> PathBuf {
+    data_dir().join("blobs")
+}
+
+fn blob_path(id: &str) -> Result<PathBuf> {
+    if id.len() != 64 || !id.bytes().all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte)) {
+        bail!("invalid blob id {id}");
+    }
+    Ok(blobs_root().join(&id[..2]).join(&id[2..]))
+}
+
+/// Stores `bytes` unless an identical blob exists, and returns its id.
+pub fn put(bytes: &[u8]) -> Result<String> {
+    let id = digest(&SHA256, bytes).as_ref().iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    let path = blob_path(&id)?;
+    if path.is_file() {
+        // Reused contents count as new, so a sweep doesn't take them before their record lands.
+        let _ = fs::File::options().append(true).open(&path).and_then(|file| file.set_modified(SystemTime::now()));
+        return Ok(id);
+    }
+    let dir = path.parent().context("blob path has no parent")?;
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let temp = dir.join(format!(".{}.tmp", Uuid::new_v4().simple()));
+    fs::write(&temp, bytes).with_context(|| format!("failed to write {}", temp.display()))?;
+    if let Err(err) = fs::rename(&temp, &path) {
+        let _ = fs::remove_file(&temp);
+        if !path.is_file() {
+            return Err(err).with_context(|| format!("failed to store blob {id}"));
+        }
+    }
+    Ok(id)
+}
+
+pub fn get(id: &str) -> Result<Vec<u8>> {
+    let path = blob_path(id)?;
+    fs::read(&path).with_context(|| format!("blob {id} is missing from {}", blobs_root().display()))
+}
+
+pub fn size(id: &str) -> Result<u64> {
+    Ok(fs::metadata(blob_path(id)?).with_context(|| format!("blob {id} is missing"))?.len())
+}
+
+/// Deletes blobs not in `keep` once they are older than the grace period. Returns how many went.
+pub fn sweep(keep: &HashSet<String>) -> Result<usize> {
+    let root = blobs_root();
+    if !root.is_dir() {
+        return Ok(0);
+    }
+    let cutoff = SystemTime::now() - SWEEP_GRACE;
+    let mut removed = 0;
+    for shard in fs::read_dir(&root)?.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        let Some(prefix) = shard.file_name().and_then(|name| name.to_str()).map(ToString::to_string) else { continue };
+        if !shard.is_dir() {
+            continue;
+        }
+        for blob in fs::read_dir(&shard)?.filter_map(|entry| entry.ok()) {
+            let name = blob.file_name().to_string_lossy().to_string();
+            if keep.contains(&format!("{prefix}{name}")) {
+                continue;
+            }
+            let stale = blob.metadata().and_then(|meta| meta.modified()).is_ok_and(|modified| modified < cutoff);
+            if stale && fs::remove_file(blob.path()).is_ok() {
+                removed += 1;
+            }
+        }
+        if fs::read_dir(&shard).map(|mut items| items.next().is_none()).unwrap_or(false) {
+            let _ = fs::remove_dir(&shard);
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blobs_are_shared_by_content_and_named_by_hash_only() {
+        crate::runtime_env::use_test_data_dir();
+        let contents = format!("blob {}", Uuid::new_v4());
+        let id = put(contents.as_bytes()).unwrap();
+        assert_eq!(put(contents.as_bytes()).unwrap(), id);
+        assert_eq!(get(&id).unwrap(), contents.as_bytes());
+        assert_eq!(size(&id).unwrap(), contents.len() as u64);
+
+        let traversal = format!("../../{}", &id[6..]);
+        for bad in ["", "../../etc/passwd", traversal.as_str(), &id.to_uppercase(), &id[1..]] {
+            assert!(get(bad).is_err(), "{bad:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn sweeps_keep_referenced_and_recent_blobs() {
+        crate::runtime_env::use_test_data_dir();
+        let kept = put(format!("kept {}", Uuid::new_v4()).as_bytes()).unwrap();
+        let stale = put(format!("stale {}", Uuid::new_v4()).as_bytes()).unwrap();
+        let fresh = put(format!("fresh {}", Uuid::new_v4()).as_bytes()).unwrap();
+        let old = SystemTime::now() - SWEEP_GRACE * 2;
+        for id in [&kept, &stale] {
+            fs::File::options().append(true).open(blob_path(id).unwrap()).unwrap().set_modified(old).unwrap();
+        }
+
+        sweep(&HashSet::from([kept.clone()])).unwrap();
+        assert!(get(&kept).is_ok());
+        assert!(get(&stale).is_err());
+        assert!(get(&fresh).is_ok());
+    }
+}
@@ -0,0 +1,264 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::models::{WorkflowRun, WorkflowStepDefinition};
+
+use super::{ensure_engine_root, stages::StageOutcome};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutoStopConditions {
+    #[serde(default)]
+    pub max_iterations: Option<u64>,
+    #[serde(default)]
+    pub max_total_tokens: Option<u64>,
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub usd_per_million_tokens: Option<f64>,
+    #[serde(default)]
+    pub max_wall_clock_secs: Option<u64>,
+    #[serde(default)]
+    pub stop_after_consecutive_passes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoBudget {
+    pub started_at: DateTime<Utc>,
+    /// Completed passes through the loop: times the run went back to a step it had already run.
+    #[serde(default)]
+    pub iterations: u64,
+    /// Steps run since the last loop-back.
+    #[serde(default)]
+    pub iteration_steps: Vec<String>,
+    #[serde(default)]
+    pub total_tokens: u64,
+    #[serde(default)]
+    pub consecutive_passes: u64,
+}
+
+impl AutoBudget {
+    fn new() -> Self {
+        Self {
+            started_at: Utc::now(),
+            iterations: 0,
+            iteration_steps: Vec::new(),
+            total_tokens: 0,
+            consecutive_passes: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GuardTrip {
+    pub guard: &'static str,
+    pub limit: Value,
+    pub observed: Value,
+    pub message: String,
+}
+
+pub fn load_stop_conditions(run: &WorkflowRun) -> AutoStopConditions {
+    run.context
+        .get("workflow_engine")
+        .and_then(|v| v.get("global_state"))
+        .and_then(|v| v.get("automation"))
+        .and_then(|v| v.get("stop_conditions"))
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn reset_auto_budget(run: &mut WorkflowRun) {
+    let root = ensure_engine_root(&mut run.context);
+    let run_state = root.entry("run_state".to_string()).or_insert_with(|| json!({}));
+    if !run_state.is_object() {
+        *run_state = json!({});
+    }
+    run_state
+        .as_object_mut()
+        .expect("run_state must be object")
+        .insert("auto_budget".to_string(), json!(AutoBudget::new()));
+}
+
+fn load_auto_budget(run: &WorkflowRun) -> AutoBudget {
+    run.context
+        .get("workflow_engine")
+        .and_then(|v| v.get("run_state"))
+        .and_then(|v| v.get("auto_budget"))
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_else(AutoBudget::new)
+}
+
+fn stage_tokens(outcome: &StageOutcome) -> u64 {
+    outcome
+        .capability_results
+        .iter()
+        .filter(|item| item.get("key").and_then(Value::as_str) == Some("inference"))
+        .filter_map(|item| item.get("result"))
+        .map(|payload| {
+            let reported = payload
+                .get("result")
+                .and_then(|v| v.get("total_tokens"))
+                .and_then(Value::as_u64);
+            reported.unwrap_or_else(|| {
+                let prompt = payload.get("prompt").and_then(Value::as_str).unwrap_or("").len();
+                let text = payload
+                    .get("result")
+                    .and_then(|v| v.get("text"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("")
+                    .len();
+                ((prompt + text) as u64).div_ceil(4)
+            })
+        })
+        .sum()
+}
+
+/// Folds the stage outcome into the run's auto budget and returns the first
+/// stop condition it exceeds, if any. Moving on to `next_step` when it already ran
+/// since the last loop-back completes an iteration.
+pub fn record_stage(
+    run: &mut WorkflowRun,
+    step: &WorkflowStepDefinition,
+    outcome: &StageOutcome,
+    next_step: Option<&str>,
+) -> Option<GuardTrip> {
+    let conditions = load_stop_conditions(run);
+    let mut budget = load_auto_budget(run);
+
+    if !budget.iteration_steps.contains(&step.id) {
+        budget.iteration_steps.push(step.id.clone());
+    }
+    if next_step.is_some_and(|next| budget.iteration_steps.iter().any(|id| id == next)) {
+        budget.iterations += 1;
+        budget.iteration_steps.clear();
+    }
+    budget.total_tokens += stage_tokens(outcome);
+    if step.step_type == "compile" {
+        budget.consecutive_passes = if outcome.ok { budget.consecutive_passes + 1 } else { 0 };
+    }
+
+    let root = ensure_engine_root(&mut run.context);
+    let run_state = root.entry("run_state".to_string()).or_insert_with(|| json!({}));
+    if let Some(run_state) = run_state.as_object_mut() {
+        run_state.insert("auto_budget".to_string(), json!(budget));
+    }
+
+    let elapsed_secs = (Utc::now() - budget.started_at).num_seconds().max(0) as u64;
+    let cost_usd = conditions
+        .usd_per_million_tokens
+        .map(|rate| budget.total_tokens as f64 / 1_000_000.0 * rate);
+
+    if let Some(limit) = conditions.max_iterations.filter(|v| *v > 0) {
+        if budget.iterations >= limit {
+            return Some(GuardTrip {
+                guard: "max_iterations",
+                limit: json!(limit),
+                observed: json!(budget.iterations),
+                message: format!("Stopped after {} automatic iterations (limit {}).", budget.iterations, limit),
+            });
+        }
+    }
+    if let Some(limit) = conditions.max_total_tokens.filter(|v| *v > 0) {
+        if budget.total_tokens >= limit {
+            return Some(GuardTrip {
+                guard: "max_total_tokens",
+                limit: json!(limit),
+                observed: json!(budget.total_tokens),
+                message: format!("Stopped after using {} tokens (limit {}).", budget.total_tokens, limit),
+            });
+        }
+    }
+    if let (Some(limit), Some(cost)) = (conditions.max_cost_usd.filter(|v| *v > 0.0), cost_usd) {
+        if cost >= limit {
+            return Some(GuardTrip {
+                guard: "max_cost_usd",
+                limit: json!(limit),
+                observed: json!(cost),
+                message: format!("Stopped after an estimated ${:.4} of inference (limit ${:.2}).", cost, limit),
+            });
+        }
+    }
+    if let Some(limit) = conditions.max_wall_clock_secs.filter(|v| *v > 0) {
+        if elapsed_secs >= limit {
+            return Some(GuardTrip {
+                guard: "max_wall_clock_secs",
+                limit: json!(limit),
+                observed: json!(elapsed_secs),
+                message: format!("Stopped after {}s of wall-clock time (limit {}s).", elapsed_secs, limit),
+            });
+        }
+    }
+    if let Some(limit) = conditions.stop_after_consecutive_passes.filter(|v| *v > 0) {
+        if budget.consecutive_passes >= limit {
+            return Some(GuardTrip {
+                guard: "consecutive_passes",
+                limit: json!(limit),
+                observed: json!(budget.consecutive_passes),
+                message: format!("Stopped after postprocess passed {} consecutive times.", budget.consecutive_passes),
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::stages::StageDisposition;
+
+    fn step(id: &str) -> WorkflowStepDefinition {
+        serde_json::from_value(json!({ "id": id, "name": id, "step_type": "inference", "automation_mode": "automatic" })).unwrap()
+    }
+
+    fn run_with_max_iterations(limit: u64) -> WorkflowRun {
+        serde_json::from_value(json!({
+            "id": uuid::Uuid::nil(),
+            "template_id": null,
+            "definition": { "version": 1, "steps": [] },
+            "status": "running",
+            "current_step_id": null,
+            "title": "",
+            "repo_ref": "",
+            "context": { "workflow_engine": { "global_state": { "automation": { "stop_conditions": { "max_iterations": limit } } } } },
+            "created_at": Utc::now(),
+            "updated_at": Utc::now(),
+        }))
+        .unwrap()
+    }
+
+    fn passed() -> StageOutcome {
+        StageOutcome {
+            ok: true,
+            disposition: StageDisposition::Success,
+            message: String::new(),
+            capability_results: Vec::new(),
+            local_state: json!({}),
+        }
+    }
+
+    #[test]
+    fn iterations_count_loop_backs_not_stage_runs() {
+        let mut run = run_with_max_iterations(2);
+        let (write, test) = (step("write"), step("test"));
+        assert!(record_stage(&mut run, &write, &passed(), Some("test")).is_none());
+        assert!(record_stage(&mut run, &test, &passed(), Some("write")).is_none());
+        assert!(record_stage(&mut run, &write, &passed(), Some("test")).is_none());
+        let trip = record_stage(&mut run, &test, &passed(), Some("write")).unwrap();
+        assert_eq!(trip.guard, "max_iterations");
+        assert_eq!(trip.observed, json!(2));
+    }
+
+    #[test]
+    fn a_linear_run_never_completes_an_iteration() {
+        let mut run = run_with_max_iterations(1);
+        let steps = ["plan", "write", "test", "review", "commit"].map(step);
+        for (index, current) in steps.iter().enumerate() {
+            let next = steps.get(index + 1).map(|next| next.id.as_str());
+            assert!(record_stage(&mut run, current, &passed(), next).is_none());
+        }
+        assert_eq!(load_auto_budget(&run).iterations, 0);
+    }
+}
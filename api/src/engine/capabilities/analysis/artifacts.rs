@@ -0,0 +1,142 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::Write,
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::engine::capabilities::git::git::{ensure_git_repo, run_git, run_git_allow_fail};
+
+pub const DEFAULT_LARGE_BLOB_BYTES: u64 = 5 * 1024 * 1024;
+
+const ARTIFACT_DIR_NAMES: &[&str] = &[
+    "target",
+    "node_modules",
+    "dist",
+    "build",
+    ".next",
+    ".turbo",
+    "__pycache__",
+    ".venv",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactFinding {
+    pub path: String,
+    pub kind: String,
+    pub bytes: u64,
+    pub file_count: usize,
+    pub tracked: bool,
+    pub gitignored: bool,
+    pub exclude_regex: String,
+    pub gitignore_pattern: String,
+}
+
+pub fn detect_build_artifacts(repo: &Path, large_blob_bytes: u64) -> Result<Vec<ArtifactFinding>> {
+    ensure_git_repo(repo)?;
+
+    let tracked = git_ls_files(repo, &["ls-files", "-z"])?;
+    let untracked = git_ls_files(repo, &["ls-files", "-z", "--others", "--exclude-standard"])?;
+    let tracked_set: HashSet<&str> = tracked.iter().map(String::as_str).collect();
+
+    let mut dirs: BTreeMap<String, (u64, usize, bool)> = BTreeMap::new();
+    let mut out = Vec::new();
+
+    for rel in tracked.iter().chain(untracked.iter()) {
+        let is_tracked = tracked_set.contains(rel.as_str());
+        let bytes = fs::metadata(repo.join(rel)).map(|meta| meta.len()).unwrap_or(0);
+
+        if let Some(dir) = artifact_dir_of(rel) {
+            let entry = dirs.entry(dir).or_insert((0, 0, false));
+            entry.0 += bytes;
+            entry.1 += 1;
+            entry.2 |= is_tracked;
+            continue;
+        }
+
+        if bytes > large_blob_bytes {
+            out.push(ArtifactFinding {
+                path: rel.clone(),
+                kind: "large_blob".to_string(),
+                bytes,
+                file_count: 1,
+                tracked: is_tracked,
+                gitignored: is_tracked && is_gitignored_path(repo, rel),
+                exclude_regex: format!("^{}$", regex::escape(rel)),
+                gitignore_pattern: format!("/{}", rel),
+            });
+        }
+    }
+
+    for (dir, (bytes, file_count, is_tracked)) in dirs {
+        out.push(ArtifactFinding {
+            gitignored: is_tracked && is_gitignored_path(repo, &dir),
+            exclude_regex: format!("^{}/", regex::escape(&dir)),
+            gitignore_pattern: format!("/{}/", dir),
+            path: dir,
+            kind: "build_artifact_dir".to_string(),
+            bytes,
+            file_count,
+            tracked: is_tracked,
+        });
+    }
+
+    out.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    Ok(out)
+}
+
+pub fn append_gitignore_pattern(repo: &Path, pattern: &str) -> Result<bool> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() || pattern.contains('\n') {
+        bail!("invalid .gitignore pattern");
+    }
+
+    let path = repo.join(".gitignore");
+    let existing = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+    };
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(false);
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        file.write_all(b"\n")?;
+    }
+    file.write_all(format!("{}\n", pattern).as_bytes())?;
+    Ok(true)
+}
+
+fn artifact_dir_of(rel: &str) -> Option<String> {
+    let parts: Vec<&str> = rel.split('/').collect();
+    let last = parts.len().saturating_sub(1);
+    parts[..last]
+        .iter()
+        .position(|part| ARTIFACT_DIR_NAMES.contains(part))
+        .map(|idx| parts[..=idx].join("/"))
+}
+
+fn git_ls_files(repo: &Path, args: &[&str]) -> Result<Vec<String>> {
+    let out = run_git(repo, args)?;
+    Ok(out
+        .split(|b| *b == 0)
+        .filter(|chunk| !chunk.is_empty())
+        .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+        .collect())
+}
+
+fn is_gitignored_path(repo: &Path, rel: &str) -> bool {
+    matches!(
+        run_git_allow_fail(repo, &["check-ignore", "-q", "--no-index", rel]),
+        Ok((0, _, _))
+    )
+}
@@ -0,0 +1,207 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::engine::capabilities::git::git::list_worktree_files;
+
+/// Manifests deeper than this are usually fixtures or vendored code, not something to run.
+const MAX_MANIFEST_DEPTH: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectCommand {
+    pub label: String,
+    /// Shell command, run from the repo root.
+    pub command: String,
+    /// One of `build`, `test`, `run`, `lint` or `other`.
+    pub kind: &'static str,
+    /// Manifest the command was inferred from.
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedProjectCommands {
+    pub repo_ref: String,
+    pub detected_at: DateTime<Utc>,
+    /// True when served from the per-repo cache instead of a fresh scan.
+    pub cached: bool,
+    pub commands: Vec<ProjectCommand>,
+}
+
+/// Detection results per repo. Scans walk the whole worktree, so they are kept until a refresh is asked for.
+#[derive(Debug, Default)]
+pub struct ProjectCommandCache {
+    by_repo: Mutex<HashMap<String, DetectedProjectCommands>>,
+}
+
+impl ProjectCommandCache {
+    pub fn get_or_detect(&self, repo_ref: &str, refresh: bool) -> Result<DetectedProjectCommands> {
+        if !refresh {
+            if let Some(hit) = self.by_repo.lock().unwrap().get(repo_ref) {
+                return Ok(DetectedProjectCommands { cached: true, ..hit.clone() });
+            }
+        }
+        let detected = DetectedProjectCommands {
+            repo_ref: repo_ref.to_string(),
+            detected_at: Utc::now(),
+            cached: false,
+            commands: detect_project_commands(Path::new(repo_ref))?,
+        };
+        self.by_repo.lock().unwrap().insert(repo_ref.to_string(), detected.clone());
+        Ok(detected)
+    }
+}
+
+/// Infers the conventional build, test, run and lint commands from the manifests in the worktree.
+pub fn detect_project_commands(repo: &Path) -> Result<Vec<ProjectCommand>> {
+    if !repo.is_dir() {
+        bail!("repo_ref is not a directory: {}", repo.display());
+    }
+    let files = list_worktree_files(repo)?;
+    let present: HashSet<&str> = files.iter().map(String::as_str).collect();
+
+    let mut out = Vec::new();
+    for rel in &files {
+        if rel.matches('/').count() >= MAX_MANIFEST_DEPTH || rel.split('/').any(|part| part == "vendor" || part == "third_party") {
+            continue;
+        }
+        let (dir, file_name) = rel.rsplit_once('/').unwrap_or(("", rel.as_str()));
+        let Ok(text) = fs::read_to_string(repo.join(rel)) else { continue };
+        match file_name {
+            "Cargo.toml" => cargo_commands(&present, rel, dir, &text, &mut out),
+            "package.json" => npm_commands(&present, rel, dir, &text, &mut out),
+            "Makefile" | "makefile" | "GNUmakefile" => make_commands(rel, dir, &text, &mut out),
+            "pyproject.toml" | "setup.py" | "requirements.txt" => python_commands(&present, rel, dir, &text, &mut out),
+            "go.mod" => go_commands(rel, dir, &mut out),
+            _ => {}
+        }
+    }
+
+    let mut seen = HashSet::new();
+    out.retain(|command| seen.insert(command.command.clone()));
+    Ok(out)
+}
+
+fn cargo_commands(present: &HashSet<&str>, rel: &str, dir: &str, text: &str, out: &mut Vec<ProjectCommand>) {
+    // Members of a workspace are covered by the workspace root's commands.
+    let mut ancestor = dir.to_string();
+    while !ancestor.is_empty() {
+        ancestor = ancestor.rsplit_once('/').map(|(parent, _)| parent.to_string()).unwrap_or_default();
+        if present.contains(join(&ancestor, "Cargo.toml").as_str()) {
+            return;
+        }
+    }
+    if !text.contains("[package]") && !text.contains("[workspace]") {
+        return;
+    }
+    let manifest = if dir.is_empty() { String::new() } else { format!(" --manifest-path {rel}") };
+    let has_binary = present.contains(join(dir, "src/main.rs").as_str()) || text.contains("[[bin]]");
+    for (verb, kind) in [("build", "build"), ("test", "test"), ("clippy", "lint"), ("run", "run")] {
+        if verb == "run" && (!has_binary || text.contains("[workspace]")) {
+            continue;
+        }
+        push(out, &format!("cargo {verb}"), format!("cargo {verb}{manifest}"), kind, rel);
+    }
+}
+
+fn npm_commands(present: &HashSet<&str>, rel: &str, dir: &str, text: &str, out: &mut Vec<ProjectCommand>) {
+    if rel.split('/').any(|part| part == "node_modules") {
+        return;
+    }
+    let Ok(manifest) = serde_json::from_str::<Value>(text) else { return };
+    let Some(scripts) = manifest.get("scripts").and_then(Value::as_object) else { return };
+    let runner = [("pnpm-lock.yaml", "pnpm"), ("yarn.lock", "yarn"), ("bun.lockb", "bun")]
+        .iter()
+        .find(|(lock, _)| present.contains(join(dir, lock).as_str()))
+        .map(|(_, runner)| *runner)
+        .unwrap_or("npm");
+    for name in scripts.keys() {
+        let invocation = match (runner, name.as_str()) {
+            (_, "test") | ("npm", "start") => format!("{runner} {name}"),
+            ("npm", _) => format!("npm run {name}"),
+            _ => format!("{runner} {name}"),
+        };
+        push(out, &invocation, in_dir(dir, &invocation), kind_for(name), rel);
+    }
+}
+
+fn make_commands(rel: &str, dir: &str, text: &str, out: &mut Vec<ProjectCommand>) {
+    static TARGET: OnceLock<Regex> = OnceLock::new();
+    let pattern = TARGET.get_or_init(|| Regex::new(r"^([A-Za-z][A-Za-z0-9_-]*)\s*:([^=]|$)").expect("make target pattern must compile"));
+    for line in text.lines() {
+        let Some(caps) = pattern.captures(line) else { continue };
+        let target = &caps[1];
+        let label = format!("make {target}");
+        let command = if dir.is_empty() { label.clone() } else { format!("make -C {dir} {target}") };
+        push(out, &label, command, kind_for(target), rel);
+    }
+}
+
+fn python_commands(present: &HashSet<&str>, rel: &str, dir: &str, text: &str, out: &mut Vec<ProjectCommand>) {
+    let has_tests = present.iter().any(|file| {
+        file.starts_with(&join(dir, "")) && file.rsplit('/').next().is_some_and(|name| name.starts_with("test_") && name.ends_with(".py"))
+    });
+    if has_tests || text.contains("pytest") {
+        push(out, "pytest", in_dir(dir, "python -m pytest"), "test", rel);
+    }
+    if text.contains("ruff") {
+        push(out, "ruff check", in_dir(dir, "ruff check ."), "lint", rel);
+    }
+    if text.contains("mypy") {
+        push(out, "mypy", in_dir(dir, "mypy ."), "lint", rel);
+    }
+    if present.contains(join(dir, "manage.py").as_str()) {
+        push(out, "manage.py runserver", in_dir(dir, "python manage.py runserver"), "run", rel);
+    }
+}
+
+fn go_commands(rel: &str, dir: &str, out: &mut Vec<ProjectCommand>) {
+    for (verb, kind) in [("build", "build"), ("test", "test"), ("vet", "lint")] {
+        let label = format!("go {verb} ./...");
+        push(out, &label, in_dir(dir, &label), kind, rel);
+    }
+}
+
+fn kind_for(name: &str) -> &'static str {
+    let name = name.to_ascii_lowercase();
+    let has = |words: &[&str]| words.iter().any(|word| name.split([':', '-', '_']).any(|part| part == *word));
+    if has(&["test", "tests", "spec", "e2e", "coverage"]) {
+        "test"
+    } else if has(&["build", "compile", "release", "dist", "bundle"]) {
+        "build"
+    } else if has(&["lint", "fmt", "format", "check", "typecheck", "clippy", "vet"]) {
+        "lint"
+    } else if has(&["dev", "start", "serve", "run", "watch", "preview"]) {
+        "run"
+    } else {
+        "other"
+    }
+}
+
+fn push(out: &mut Vec<ProjectCommand>, label: &str, command: String, kind: &'static str, source: &str) {
+    out.push(ProjectCommand { label: label.to_string(), command, kind, source: source.to_string() });
+}
+
+fn in_dir(dir: &str, command: &str) -> String {
+    if dir.is_empty() {
+        command.to_string()
+    } else {
+        format!("cd {dir} && {command}")
+    }
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
@@ -0,0 +1,215 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::engine::capabilities::git::git::list_worktree_files;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyEntry {
+    pub ecosystem: String,
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub dev: bool,
+    pub source_file: String,
+}
+
+/// Packages from every lockfile that could be read; each one that could not is a warning instead.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyInventory {
+    pub dependencies: Vec<DependencyEntry>,
+    pub warnings: Vec<String>,
+}
+
+pub fn collect_dependency_inventory(repo: &Path) -> Result<DependencyInventory> {
+    if !repo.is_dir() {
+        bail!("repo_ref is not a directory: {}", repo.display());
+    }
+
+    let mut out = Vec::new();
+    let mut warnings = Vec::new();
+    for rel in list_worktree_files(repo)? {
+        let file_name = rel.rsplit('/').next().unwrap_or(rel.as_str());
+        let parser: fn(&str, &str) -> Result<Vec<DependencyEntry>> = match file_name {
+            "Cargo.lock" => parse_cargo_lock,
+            "package-lock.json" => parse_package_lock,
+            "requirements.txt" => parse_requirements_txt,
+            _ => continue,
+        };
+        let parsed = fs::read_to_string(repo.join(&rel))
+            .with_context(|| format!("failed to read {}", rel))
+            .and_then(|text| parser(&rel, &text).with_context(|| format!("failed to parse {}", rel)));
+        match parsed {
+            Ok(entries) => out.extend(entries),
+            Err(err) => warnings.push(format!("{:#}; skipped", err)),
+        }
+    }
+
+    out.sort_by(|a, b| {
+        a.ecosystem
+            .cmp(&b.ecosystem)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.version.cmp(&b.version))
+            .then_with(|| a.source_file.cmp(&b.source_file))
+    });
+    Ok(DependencyInventory { dependencies: out, warnings })
+}
+
+fn parse_cargo_lock(source_file: &str, text: &str) -> Result<Vec<DependencyEntry>> {
+    let mut packages: Vec<(String, String)> = Vec::new();
+    let mut in_package = false;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[[package]]";
+            if in_package {
+                packages.push((String::new(), String::new()));
+            }
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        let (Some((key, value)), Some((name, version))) = (line.split_once('='), packages.last_mut()) else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim() {
+            "name" => *name = value,
+            "version" => *version = value,
+            _ => {}
+        }
+    }
+
+    Ok(packages
+        .into_iter()
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, version)| DependencyEntry {
+            ecosystem: "cargo".to_string(),
+            name,
+            version,
+            license: None,
+            dev: false,
+            source_file: source_file.to_string(),
+        })
+        .collect())
+}
+
+fn parse_package_lock(source_file: &str, text: &str) -> Result<Vec<DependencyEntry>> {
+    let root: Value = serde_json::from_str(text)?;
+    let mut out = Vec::new();
+
+    if let Some(packages) = root.get("packages").and_then(Value::as_object) {
+        for (key, pkg) in packages {
+            let Some(idx) = key.rfind("node_modules/") else {
+                continue;
+            };
+            let name = pkg
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or(&key[idx + "node_modules/".len()..]);
+            out.push(package_lock_entry(source_file, name, pkg));
+        }
+    } else if let Some(deps) = root.get("dependencies").and_then(Value::as_object) {
+        for (name, pkg) in deps {
+            out.push(package_lock_entry(source_file, name, pkg));
+        }
+    }
+
+    Ok(out)
+}
+
+fn package_lock_entry(source_file: &str, name: &str, pkg: &Value) -> DependencyEntry {
+    let license = match pkg.get("license") {
+        Some(Value::String(value)) => Some(value.clone()),
+        Some(Value::Object(obj)) => obj.get("type").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    };
+    DependencyEntry {
+        ecosystem: "npm".to_string(),
+        name: name.to_string(),
+        version: pkg.get("version").and_then(Value::as_str).unwrap_or_default().to_string(),
+        license,
+        dev: pkg.get("dev").and_then(Value::as_bool).unwrap_or(false),
+        source_file: source_file.to_string(),
+    }
+}
+
+fn parse_requirements_txt(source_file: &str, text: &str) -> Result<Vec<DependencyEntry>> {
+    let mut out = Vec::new();
+
+    for raw in text.lines() {
+        let line = raw.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('-') {
+            continue;
+        }
+        let line = line.split(';').next().unwrap_or_default().trim();
+        let split_at = line.find(['=', '<', '>', '~', '!', ' ']).unwrap_or(line.len());
+        let name = line[..split_at].split('[').next().unwrap_or_default().trim();
+        if name.is_empty() {
+            continue;
+        }
+        out.push(DependencyEntry {
+            ecosystem: "pypi".to_string(),
+            name: name.to_string(),
+            version: line[split_at..].trim().to_string(),
+            license: None,
+            dev: false,
+            source_file: source_file.to_string(),
+        });
+    }
+
+    Ok(out)
+}
+
+pub fn render_dependency_csv(entries: &[DependencyEntry]) -> String {
+    let mut out = String::from("ecosystem,name,version,license,dev,source_file\n");
+    for entry in entries {
+        let row = [
+            entry.ecosystem.as_str(),
+            entry.name.as_str(),
+            entry.version.as_str(),
+            entry.license.as_deref().unwrap_or(""),
+            if entry.dev { "true" } else { "false" },
+            entry.source_file.as_str(),
+        ];
+        let cells: Vec<String> = row.iter().map(|cell| csv_cell(cell)).collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::capabilities::git::git::run_git;
+
+    #[test]
+    fn unparseable_lockfiles_become_warnings() {
+        let repo = std::env::temp_dir().join(format!("dependency-inventory-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(repo.join("web")).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        fs::write(repo.join("Cargo.lock"), "[[package]]\nname = \"serde\"\nversion = \"1.0.0\"\n").unwrap();
+        fs::write(repo.join("web/package-lock.json"), "{ not json").unwrap();
+        fs::write(repo.join("requirements.txt"), "# tools\nrequests[socks]>=2.0 ; python_version > '3'\n-r base.txt\n").unwrap();
+
+        let inventory = collect_dependency_inventory(&repo).unwrap();
+        let names = inventory.dependencies.iter().map(|entry| (entry.name.as_str(), entry.version.as_str())).collect::<Vec<_>>();
+        assert_eq!(names, [("serde", "1.0.0"), ("requests", ">=2.0")]);
+        assert_eq!(inventory.warnings.len(), 1);
+        assert!(inventory.warnings[0].starts_with("failed to parse web/package-lock.json"));
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::engine::capabilities::git::git::{ensure_git_repo, run_git, run_git_allow_fail};
+
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdx"];
+/// Docs that are entry points by convention and are never expected to be linked to.
+const ENTRY_POINT_STEMS: &[&str] = &["readme", "index", "changelog", "license", "contributing", "code_of_conduct", "security"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokenDocLink {
+    pub source: String,
+    /// 1-based line of the link in `source`.
+    pub line: usize,
+    /// The link as written, without any `#fragment` or `?query`.
+    pub target: String,
+    /// Repo-relative path the link resolves to; `None` when it climbs out of the repository.
+    pub resolved: Option<String>,
+    pub image: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocLinkReport {
+    pub markdown_files: usize,
+    pub broken: Vec<BrokenDocLink>,
+    /// Markdown files no other file links to or mentions by name.
+    pub orphans: Vec<String>,
+}
+
+/// Relative links and images in Markdown that do not resolve inside the repo, plus docs nothing points at.
+pub fn scan_doc_links(repo: &Path) -> Result<DocLinkReport> {
+    ensure_git_repo(repo)?;
+
+    let mut files = BTreeSet::new();
+    for args in [&["ls-files", "-z"][..], &["ls-files", "-z", "--others", "--exclude-standard"][..]] {
+        let stdout = run_git(repo, args)?;
+        files.extend(stdout.split(|b| *b == 0).filter(|chunk| !chunk.is_empty()).map(|chunk| String::from_utf8_lossy(chunk).to_string()));
+    }
+    let directories: HashSet<String> = files.iter().flat_map(|file| parent_dirs(file)).collect();
+    let docs: Vec<&String> = files.iter().filter(|file| is_markdown(file)).collect();
+
+    let link_re = Regex::new(r#"(!?)\[[^\]]*\]\(\s*<?([^)\s>]+)>?(?:\s+["'(][^)]*)?\)"#)?;
+    let reference_re = Regex::new(r#"^\s{0,3}\[[^\]]+\]:\s*<?([^\s>]+)>?"#)?;
+    let html_re = Regex::new(r#"<(img|a)\b[^>]*?\b(?:src|href)\s*=\s*["']([^"']+)["']"#)?;
+
+    let mut broken = Vec::new();
+    let mut linked: HashSet<String> = HashSet::new();
+    for doc in &docs {
+        let Ok(contents) = fs::read_to_string(repo.join(doc)) else { continue };
+        let mut in_fence = false;
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+                in_fence = !in_fence;
+                continue;
+            }
+            if in_fence {
+                continue;
+            }
+            let line = strip_code_spans(line);
+            let mut targets: Vec<(&str, bool)> = Vec::new();
+            targets.extend(link_re.captures_iter(&line).map(|caps| (caps.get(2).map_or("", |m| m.as_str()), &caps[1] == "!")));
+            targets.extend(reference_re.captures_iter(&line).map(|caps| (caps.get(1).map_or("", |m| m.as_str()), false)));
+            targets.extend(html_re.captures_iter(&line).map(|caps| (caps.get(2).map_or("", |m| m.as_str()), &caps[1] == "img")));
+
+            for (raw, image) in targets {
+                let Some(target) = local_target(raw) else { continue };
+                let resolved = resolve(doc, &target);
+                match &resolved {
+                    Some(path) if path.is_empty() || files.contains(path) || directories.contains(path) || repo.join(path).exists() => {
+                        linked.insert(path.clone());
+                    }
+                    _ => broken.push(BrokenDocLink { source: doc.to_string(), line: index + 1, target, resolved, image }),
+                }
+            }
+        }
+    }
+
+    let orphans = find_orphans(repo, &docs, &linked)?;
+    Ok(DocLinkReport { markdown_files: docs.len(), broken, orphans })
+}
+
+/// A doc counts as referenced when a Markdown link resolves to it or any other file mentions its file name
+/// (build configs, `mkdocs.yml`, sidebars). Two docs sharing a name therefore vouch for each other.
+fn find_orphans(repo: &Path, docs: &[&String], linked: &HashSet<String>) -> Result<Vec<String>> {
+    let candidates: Vec<&String> = docs
+        .iter()
+        .copied()
+        .filter(|doc| !linked.contains(*doc) && !is_entry_point(doc) && !doc.starts_with(".github/"))
+        .collect();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let names: BTreeSet<&str> = candidates.iter().map(|doc| file_name(doc)).collect();
+    let mut args = vec!["grep", "--untracked", "-I", "-o", "-z", "-F"];
+    for name in &names {
+        args.extend(["-e", name]);
+    }
+    let (code, stdout, stderr) = run_git_allow_fail(repo, &args)?;
+    if code > 1 {
+        bail!("git grep failed: {}", String::from_utf8_lossy(&stderr).trim());
+    }
+    // name -> files mentioning it
+    let mut mentions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for record in String::from_utf8_lossy(&stdout).lines() {
+        let Some((path, name)) = record.split_once('\0') else { continue };
+        mentions.entry(name.to_string()).or_default().insert(path.to_string());
+    }
+
+    Ok(candidates
+        .into_iter()
+        .filter(|doc| !mentions.get(file_name(doc)).is_some_and(|paths| paths.iter().any(|path| path != *doc)))
+        .cloned()
+        .collect())
+}
+
+/// Drops external URLs, mail links and in-page anchors; strips fragments, queries and `%20`-style escapes.
+fn local_target(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.starts_with('#') || raw.starts_with("//") || raw.contains("://") {
+        return None;
+    }
+    if let Some((scheme, _)) = raw.split_once(':') {
+        if !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphabetic()) && scheme.len() > 1 {
+            return None;
+        }
+    }
+    let path = raw.split(['#', '?']).next().unwrap_or_default();
+    if path.is_empty() {
+        return None;
+    }
+    Some(percent_decode(path))
+}
+
+/// Repo-relative path for `target` as linked from `source`; a leading `/` means the repo root.
+fn resolve(source: &str, target: &str) -> Option<String> {
+    let mut segments: Vec<&str> = if target.starts_with('/') { Vec::new() } else { source.split('/').collect() };
+    if !target.starts_with('/') {
+        segments.pop();
+    }
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            other => segments.push(other),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+fn strip_code_spans(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut in_code = false;
+    for c in line.chars() {
+        if c == '`' {
+            in_code = !in_code;
+        } else if !in_code {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn parent_dirs(path: &str) -> Vec<String> {
+    let segments: Vec<&str> = path.split('/').collect();
+    (1..segments.len()).map(|depth| segments[..depth].join("/")).collect()
+}
+
+fn is_markdown(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| MARKDOWN_EXTENSIONS.contains(&ext.to_string_lossy().to_ascii_lowercase().as_str()))
+}
+
+fn is_entry_point(path: &str) -> bool {
+    Path::new(path)
+        .file_stem()
+        .is_some_and(|stem| ENTRY_POINT_STEMS.contains(&stem.to_string_lossy().to_ascii_lowercase().as_str()))
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
@@ -0,0 +1,209 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::DefaultHasher, BTreeMap, HashSet},
+    fs,
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use super::sizes::collect_file_sizes;
+use crate::engine::capabilities::git::git::{read_worktree_file, run_git_allow_fail, run_git_with_input};
+
+pub const DEFAULT_NEAR_DUPLICATE_THRESHOLD: f64 = 0.8;
+/// Tiny files (licence stubs, `__init__.py`, empty configs) match each other constantly and are not worth flagging.
+const MIN_DUPLICATE_BYTES: u64 = 64;
+/// Near-duplicate comparison reads and shingles every candidate, so large generated files are left out.
+const MAX_NEAR_DUPLICATE_BYTES: u64 = 512 * 1024;
+/// Files whose sizes differ by more than this ratio are never compared.
+const MIN_SIZE_RATIO: f64 = 0.7;
+const SHINGLE_TOKENS: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExactDuplicateCluster {
+    pub blob: String,
+    pub bytes: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarPair {
+    pub left: String,
+    pub right: String,
+    /// Jaccard similarity of the two files' token shingles, 0.0 to 1.0.
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NearDuplicateCluster {
+    pub paths: Vec<String>,
+    /// Every pair above the threshold; a cluster is connected through these, not necessarily all-to-all.
+    pub pairs: Vec<SimilarPair>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReport {
+    pub threshold: f64,
+    pub files_scanned: usize,
+    pub exact: Vec<ExactDuplicateCluster>,
+    pub near: Vec<NearDuplicateCluster>,
+}
+
+/// Exact duplicates share a blob hash; near duplicates have similar sizes and overlapping token shingles.
+/// Only one file of each exact cluster takes part in the near-duplicate pass so copies are not reported twice.
+pub fn find_duplicates(repo: &Path, threshold: f64) -> Result<DuplicateReport> {
+    let threshold = threshold.clamp(0.1, 1.0);
+    let files: Vec<_> = collect_file_sizes(repo)?
+        .into_iter()
+        .filter(|file| file.bytes >= MIN_DUPLICATE_BYTES && !file.path.contains('\n'))
+        .collect();
+
+    let stdin = files.iter().map(|file| format!("{}\n", file.path)).collect::<String>();
+    let hashes = run_git_with_input(repo, &["hash-object", "--stdin-paths"], stdin.as_bytes())?;
+    let hashes: Vec<String> = String::from_utf8_lossy(&hashes).lines().map(str::to_string).collect();
+    if hashes.len() != files.len() {
+        bail!("git hash-object returned {} hashes for {} files", hashes.len(), files.len());
+    }
+
+    let mut by_blob: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, blob) in hashes.iter().enumerate() {
+        by_blob.entry(blob).or_default().push(index);
+    }
+    let mut exact: Vec<ExactDuplicateCluster> = by_blob
+        .iter()
+        .filter(|(_, indexes)| indexes.len() > 1)
+        .map(|(blob, indexes)| ExactDuplicateCluster {
+            blob: blob.to_string(),
+            bytes: files[indexes[0]].bytes,
+            paths: indexes.iter().map(|index| files[*index].path.clone()).collect(),
+        })
+        .collect();
+    exact.sort_by_key(|group| Reverse(group.bytes * group.paths.len() as u64));
+
+    // One representative per blob, text only (line count is zero for binaries), sorted by size for the window below.
+    let mut candidates: Vec<(u64, &str, HashSet<u64>)> = by_blob
+        .values()
+        .map(|indexes| &files[indexes[0]])
+        .filter(|file| file.lines > 0 && file.bytes <= MAX_NEAR_DUPLICATE_BYTES)
+        .filter_map(|file| {
+            let contents = fs::read(repo.join(&file.path)).ok()?;
+            let shingles = shingles(&String::from_utf8_lossy(&contents));
+            (!shingles.is_empty()).then_some((file.bytes, file.path.as_str(), shingles))
+        })
+        .collect();
+    candidates.sort_by_key(|(bytes, path, _)| (*bytes, *path));
+
+    let mut pairs = Vec::new();
+    for (i, (bytes, path, shingles)) in candidates.iter().enumerate() {
+        for (other_bytes, other_path, other_shingles) in &candidates[i + 1..] {
+            if (*bytes as f64) < *other_bytes as f64 * MIN_SIZE_RATIO {
+                break;
+            }
+            let shared = shingles.intersection(other_shingles).count();
+            let similarity = shared as f64 / (shingles.len() + other_shingles.len() - shared) as f64;
+            if similarity >= threshold {
+                pairs.push((path.to_string(), other_path.to_string(), similarity));
+            }
+        }
+    }
+
+    Ok(DuplicateReport { threshold, files_scanned: files.len(), exact, near: cluster_pairs(pairs) })
+}
+
+/// Unified diff between two worktree files, for reviewing a flagged pair side by side.
+pub fn diff_worktree_files(repo: &Path, left: &str, right: &str) -> Result<String> {
+    // Reading first applies the usual checks against `..` and symlinks leaving the repo.
+    read_worktree_file(repo, left)?;
+    read_worktree_file(repo, right)?;
+    let (code, stdout, stderr) = run_git_allow_fail(repo, &["diff", "--no-index", "--no-color", "--", left, right])?;
+    if code > 1 {
+        bail!("git diff failed: {}", String::from_utf8_lossy(&stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&stdout).to_string())
+}
+
+/// Hashes of every run of `SHINGLE_TOKENS` consecutive word tokens, so whitespace, punctuation and
+/// reformatting do not matter but reordered or edited code does.
+fn shingles(text: &str) -> HashSet<u64> {
+    let tokens: Vec<&str> = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|token| !token.is_empty())
+        .collect();
+    tokens
+        .windows(SHINGLE_TOKENS.min(tokens.len().max(1)))
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// Groups pairs into connected clusters, largest first.
+fn cluster_pairs(pairs: Vec<(String, String, f64)>) -> Vec<NearDuplicateCluster> {
+    let mut cluster_of: BTreeMap<String, usize> = BTreeMap::new();
+    let mut clusters: Vec<NearDuplicateCluster> = Vec::new();
+    for (left, right, similarity) in pairs {
+        let target = match (cluster_of.get(&left).copied(), cluster_of.get(&right).copied()) {
+            (Some(a), Some(b)) if a != b => {
+                let merged = std::mem::replace(&mut clusters[b], NearDuplicateCluster { paths: Vec::new(), pairs: Vec::new() });
+                for path in &merged.paths {
+                    cluster_of.insert(path.clone(), a);
+                }
+                clusters[a].paths.extend(merged.paths);
+                clusters[a].pairs.extend(merged.pairs);
+                a
+            }
+            (Some(a), _) | (_, Some(a)) => a,
+            (None, None) => {
+                clusters.push(NearDuplicateCluster { paths: Vec::new(), pairs: Vec::new() });
+                clusters.len() - 1
+            }
+        };
+        for path in [&left, &right] {
+            if cluster_of.insert(path.clone(), target).is_none() {
+                clusters[target].paths.push(path.clone());
+            }
+        }
+        clusters[target].pairs.push(SimilarPair { left, right, similarity: (similarity * 1000.0).round() / 1000.0 });
+    }
+
+    let mut clusters: Vec<NearDuplicateCluster> = clusters.into_iter().filter(|cluster| !cluster.paths.is_empty()).collect();
+    for cluster in &mut clusters {
+        cluster.paths.sort();
+        cluster.pairs.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    }
+    clusters.sort_by(|a, b| b.paths.len().cmp(&a.paths.len()).then_with(|| a.paths.cmp(&b.paths)));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::capabilities::git::git::run_git;
+
+    #[test]
+    fn exact_groups_are_ordered_by_the_bytes_they_waste() {
+        let repo = std::env::temp_dir().join(format!("duplicates-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        let small = "small duplicate line\n".repeat(5);
+        let large = "a much larger duplicated block of text\n".repeat(20);
+        for name in ["s1.txt", "s2.txt", "s3.txt"] {
+            fs::write(repo.join(name), &small).unwrap();
+        }
+        for name in ["l1.txt", "l2.txt"] {
+            fs::write(repo.join(name), &large).unwrap();
+        }
+        fs::write(repo.join("tiny1.txt"), "x").unwrap();
+        fs::write(repo.join("tiny2.txt"), "x").unwrap();
+
+        let report = find_duplicates(&repo, DEFAULT_NEAR_DUPLICATE_THRESHOLD).unwrap();
+        let groups = report.exact.iter().map(|group| group.paths.clone()).collect::<Vec<_>>();
+        assert_eq!(groups, [vec!["l1.txt", "l2.txt"], vec!["s1.txt", "s2.txt", "s3.txt"]]);
+        assert!(report.near.is_empty());
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,10 @@
+pub mod artifacts;
+pub mod commands;
+pub mod dependencies;
+pub mod doc_links;
+pub mod duplicates;
+pub mod outline;
+pub mod ownership;
+pub mod range;
+pub mod report;
+pub mod sizes;
@@ -0,0 +1,362 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlineSymbol {
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Rust,
+    Script,
+    Go,
+    CLike,
+    Python,
+}
+
+fn syntax_for_path(path: &str) -> Option<Syntax> {
+    let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase())?;
+    match ext.as_str() {
+        "rs" => Some(Syntax::Rust),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Syntax::Script),
+        "go" => Some(Syntax::Go),
+        "java" | "kt" | "cs" | "c" | "h" | "cc" | "cpp" | "hpp" | "swift" | "php" | "scala" => Some(Syntax::CLike),
+        "py" => Some(Syntax::Python),
+        _ => None,
+    }
+}
+
+fn declaration_pattern(syntax: Syntax) -> &'static Regex {
+    static RUST: OnceLock<Regex> = OnceLock::new();
+    static SCRIPT: OnceLock<Regex> = OnceLock::new();
+    static GO: OnceLock<Regex> = OnceLock::new();
+    static CLIKE: OnceLock<Regex> = OnceLock::new();
+    static PYTHON: OnceLock<Regex> = OnceLock::new();
+    let compile = |pattern: &str| Regex::new(pattern).expect("outline pattern must compile");
+    match syntax {
+        Syntax::Rust => RUST.get_or_init(|| compile(
+            r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:async|const|unsafe|default)\s+|extern\s+"[^"]*"\s+)*((?:fn|struct|enum|union|trait|impl|mod|type|const|static)\b|macro_rules!)\s*(?:<[^>]*>\s*)?([A-Za-z_][\w:<>, ]*?)\s*(?:[({<;=:]|\bfor\b|\bwhere\b|$)"#,
+        )),
+        Syntax::Script => SCRIPT.get_or_init(|| compile(
+            r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(function\*?|class|interface|type|enum|const|let|var|namespace)\s+([A-Za-z_$][\w$]*)",
+        )),
+        Syntax::Go => GO.get_or_init(|| compile(r"^(func|type)\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)")),
+        Syntax::CLike => CLIKE.get_or_init(|| compile(
+            r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|sealed|partial|open|data|override|virtual|async|inline|export)\s+)*(class|interface|struct|enum|record|object|fun|func|function|namespace)\s+([A-Za-z_]\w*)",
+        )),
+        Syntax::Python => PYTHON.get_or_init(|| compile(r"^\s*(async\s+def|def|class)\s+([A-Za-z_]\w*)")),
+    }
+}
+
+/// Heuristic outline of top-level and nested declarations. Ranges are 1-based and inclusive,
+/// and include leading doc comments, attributes and decorators.
+pub fn outline_source(path: &str, text: &str) -> Vec<OutlineSymbol> {
+    let Some(syntax) = syntax_for_path(path) else {
+        return Vec::new();
+    };
+    let lines = text.lines().collect::<Vec<_>>();
+    let pattern = declaration_pattern(syntax);
+
+    let mut symbols: Vec<OutlineSymbol> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(caps) = pattern.captures(line) else { continue };
+        let kind = caps.get(1).map(|m| m.as_str().trim()).unwrap_or_default();
+        let kind = if kind.ends_with("def") { "def" } else { kind };
+        let name = if syntax == Syntax::Rust && kind == "impl" {
+            impl_target(line)
+        } else {
+            caps.get(2).map(|m| m.as_str().trim().to_string()).unwrap_or_default()
+        };
+        if name.is_empty() || (syntax == Syntax::Script && matches!(kind, "const" | "let" | "var" | "type") && line.starts_with(char::is_whitespace)) {
+            continue;
+        }
+
+        let end = if syntax == Syntax::Python {
+            indented_block_end(&lines, index)
+        } else {
+            match brace_block_end(&lines, index, syntax, pattern) {
+                Some(end) => end,
+                None => continue,
+            }
+        };
+        let start = leading_trivia_start(&lines, index, syntax);
+        symbols.push(OutlineSymbol {
+            qualified_name: name.clone(),
+            name,
+            kind: kind.to_string(),
+            start_line: start + 1,
+            end_line: end + 1,
+            signature: line.trim().trim_end_matches('{').trim().to_string(),
+        });
+    }
+
+    for index in 0..symbols.len() {
+        let parent = symbols[..index]
+            .iter()
+            .rev()
+            .find(|outer| outer.start_line <= symbols[index].start_line && outer.end_line >= symbols[index].end_line)
+            .map(|outer| outer.qualified_name.clone());
+        if let Some(parent) = parent {
+            symbols[index].qualified_name = format!("{parent}::{}", symbols[index].name);
+        }
+    }
+    symbols
+}
+
+/// Returns the file reduced to its header (everything before the first declaration) plus the
+/// selected symbols, with elided regions marked. `None` when nothing matched.
+pub fn extract_symbols(path: &str, text: &str, names: &[String]) -> Option<String> {
+    let symbols = outline_source(path, text);
+    let mut ranges = symbols
+        .iter()
+        .filter(|symbol| names.iter().any(|name| name == &symbol.qualified_name || name == &symbol.name))
+        .map(|symbol| (symbol.start_line - 1, symbol.end_line - 1))
+        .collect::<Vec<_>>();
+    if ranges.is_empty() {
+        return None;
+    }
+
+    let lines = text.lines().collect::<Vec<_>>();
+    let header_end = symbols.first().map(|symbol| symbol.start_line - 1).unwrap_or(0);
+    if header_end > 0 {
+        ranges.push((0, header_end - 1));
+    }
+    ranges.sort();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let comment = comment_prefix(path).unwrap_or("//");
+    let mut out = String::new();
+    let mut cursor = 0;
+    for (start, end) in merged {
+        if start > cursor {
+            out.push_str(&format!("{comment} ... {} lines omitted ...\n", start - cursor));
+        }
+        for line in &lines[start..=end.min(lines.len() - 1)] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        cursor = end + 1;
+    }
+    if cursor < lines.len() {
+        out.push_str(&format!("{comment} ... {} lines omitted ...\n", lines.len() - cursor));
+    }
+    Some(out)
+}
+
+/// Line-comment prefix for elision markers in languages the outline understands.
+pub fn comment_prefix(path: &str) -> Option<&'static str> {
+    syntax_for_path(path).map(|syntax| if syntax == Syntax::Python { "#" } else { "//" })
+}
+
+/// The file with every function body replaced by an elision marker: imports, type definitions and
+/// signatures stay intact. `None` when the language is unknown or there is nothing to elide.
+pub fn signature_skeleton(path: &str, text: &str) -> Option<String> {
+    let comment = comment_prefix(path)?;
+    let lines = text.lines().collect::<Vec<_>>();
+    let symbols = outline_source(path, text);
+
+    // (first body line, last body line) for functions not already inside an elided function
+    let mut bodies: Vec<(usize, usize)> = Vec::new();
+    for symbol in &symbols {
+        let is_function = matches!(symbol.kind.as_str(), "fn" | "function" | "function*" | "def" | "func" | "fun")
+            || (matches!(symbol.kind.as_str(), "const" | "let" | "var") && symbol.signature.contains("=>"));
+        let (start, end) = (symbol.start_line - 1, symbol.end_line - 1);
+        if !is_function || bodies.last().is_some_and(|(_, last)| start <= *last) {
+            continue;
+        }
+        let Some(declaration) = (start..=end).find(|index| lines[*index].trim().starts_with(symbol.signature.as_str())) else { continue };
+        let opener = if comment == "#" { ':' } else { '{' };
+        let Some(signature_end) = (declaration..=end).find(|index| lines[*index].trim_end().ends_with(opener)) else { continue };
+        // Python bodies run to the last indented line; brace bodies stop before the closing brace.
+        let body_end = if comment == "#" { end } else { end.saturating_sub(1) };
+        if body_end > signature_end + 1 {
+            bodies.push((signature_end + 1, body_end));
+        }
+    }
+    if bodies.is_empty() {
+        return None;
+    }
+
+    let mut out = String::new();
+    let mut cursor = 0;
+    for (start, end) in bodies {
+        for line in &lines[cursor..start] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        let first = lines[start..=end].iter().find(|line| !line.trim().is_empty()).unwrap_or(&lines[start]);
+        let indent = &first[..first.len() - first.trim_start().len()];
+        out.push_str(&format!("{indent}{comment} ... {} lines elided ...\n", end - start + 1));
+        cursor = end + 1;
+    }
+    for line in &lines[cursor.min(lines.len())..] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    Some(out)
+}
+
+fn impl_target(line: &str) -> String {
+    let rest = line.trim_start().trim_start_matches("unsafe ").trim_start_matches("impl").trim_start();
+    let rest = if rest.starts_with('<') {
+        let mut depth = 0;
+        let close = rest.char_indices().find_map(|(index, ch)| {
+            match ch {
+                '<' => depth += 1,
+                '>' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                }
+                _ => {}
+            }
+            None
+        });
+        close.map(|index| &rest[index + 1..]).unwrap_or(rest)
+    } else {
+        rest
+    };
+    let rest = rest.split(" where").next().unwrap_or(rest);
+    rest.split('{').next().unwrap_or(rest).trim().to_string()
+}
+
+fn leading_trivia_start(lines: &[&str], index: usize, syntax: Syntax) -> usize {
+    let mut start = index;
+    while start > 0 {
+        let previous = lines[start - 1].trim_start();
+        let trivia = match syntax {
+            Syntax::Python => previous.starts_with('@'),
+            Syntax::Rust => previous.starts_with("///") || previous.starts_with("//!") || previous.starts_with("#["),
+            _ => {
+                previous.starts_with("//")
+                    || previous.starts_with("/*")
+                    || previous.starts_with('*')
+                    || previous.starts_with('@')
+                    || previous.starts_with('[')
+            }
+        };
+        if !trivia {
+            break;
+        }
+        start -= 1;
+    }
+    start
+}
+
+fn indented_block_end(lines: &[&str], index: usize) -> usize {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let base = indent(lines[index]);
+    let mut end = index;
+    for (offset, line) in lines.iter().enumerate().skip(index + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent(line) <= base {
+            break;
+        }
+        end = offset;
+    }
+    end
+}
+
+/// Finds the line closing the first brace block opened at or after `index`, skipping strings
+/// and comments. A `;`, blank line or new declaration before any `{` ends the declaration.
+fn brace_block_end(lines: &[&str], index: usize, syntax: Syntax, pattern: &Regex) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut opened = false;
+    let mut in_block_comment = false;
+    let mut quote: Option<char> = None;
+    let mut raw_hashes: Option<usize> = None;
+
+    for (offset, line) in lines.iter().enumerate().skip(index) {
+        if offset > index && !opened && quote.is_none() && raw_hashes.is_none() && (line.trim().is_empty() || pattern.is_match(line)) {
+            return Some(offset - 1);
+        }
+        let chars = line.chars().collect::<Vec<_>>();
+        let mut at = 0;
+        while at < chars.len() {
+            let ch = chars[at];
+            let next = chars.get(at + 1).copied();
+            at += 1;
+            if in_block_comment {
+                if ch == '*' && next == Some('/') {
+                    at += 1;
+                    in_block_comment = false;
+                }
+                continue;
+            }
+            if let Some(hashes) = raw_hashes {
+                if ch == '"' && chars[at..].iter().take(hashes).filter(|c| **c == '#').count() == hashes {
+                    at += hashes;
+                    raw_hashes = None;
+                }
+                continue;
+            }
+            if let Some(q) = quote {
+                if ch == '\\' {
+                    at += 1;
+                } else if ch == q {
+                    quote = None;
+                }
+                continue;
+            }
+            match ch {
+                '/' if next == Some('/') => break,
+                '/' if next == Some('*') => {
+                    at += 1;
+                    in_block_comment = true;
+                }
+                'r' if syntax == Syntax::Rust && matches!(next, Some('#') | Some('"')) => {
+                    let hashes = chars[at..].iter().take_while(|c| **c == '#').count();
+                    if chars.get(at + hashes) == Some(&'"') {
+                        at += hashes + 1;
+                        raw_hashes = Some(hashes);
+                    }
+                }
+                '"' | '`' => quote = Some(ch),
+                '\'' if syntax != Syntax::Rust => quote = Some(ch),
+                '\'' if chars.get(at + 1) == Some(&'\'') => at += 2,
+                '\'' if next == Some('\\') => {
+                    if let Some(close) = chars[at + 1..].iter().position(|c| *c == '\'') {
+                        at += close + 2;
+                    }
+                }
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' if opened => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return Some(offset);
+                    }
+                }
+                ';' if !opened => return Some(offset),
+                _ => {}
+            }
+        }
+        if matches!(quote, Some('"') | Some('\'')) && syntax != Syntax::Rust {
+            quote = None;
+        }
+        if !opened && offset > index + 8 {
+            return None;
+        }
+    }
+    opened.then_some(lines.len().saturating_sub(1))
+}
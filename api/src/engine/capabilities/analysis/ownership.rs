@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::engine::capabilities::git::git::{ensure_git_repo, run_git, run_git_allow_fail};
+
+pub const DEFAULT_OWNERSHIP_WINDOW_DAYS: u32 = 180;
+const DEFAULT_TOP_OWNERS: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryOwner {
+    pub name: String,
+    pub email: String,
+    pub commits: u64,
+    /// Lines added plus deleted under the directory.
+    pub lines: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnershipMap {
+    pub window_days: u32,
+    /// Top committers for every directory touched in the window, keyed by path; `.` is the repository root.
+    pub directories: BTreeMap<String, Vec<DirectoryOwner>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewerSuggestion {
+    pub name: String,
+    pub email: String,
+    /// Sum of the reviewer's commits in the nearest owned directory of each changed path.
+    pub score: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewerSuggestions {
+    pub window_days: u32,
+    pub changed_paths: Vec<String>,
+    pub reviewers: Vec<ReviewerSuggestion>,
+}
+
+/// Who committed most under each directory in the last `window_days`, ranked by commit count.
+pub fn ownership_map(repo: &Path, window_days: u32, top: Option<usize>) -> Result<OwnershipMap> {
+    ensure_git_repo(repo)?;
+    let window_days = window_days.max(1);
+    let since = format!("--since={window_days}.days");
+    let log = run_git(repo, &["log", "--no-merges", "--numstat", "--format=%x1e%an%x1f%ae", &since])?;
+
+    // directory -> lowercased email -> running totals
+    let mut stats: BTreeMap<String, BTreeMap<String, DirectoryOwner>> = BTreeMap::new();
+    for commit in String::from_utf8_lossy(&log).split('\u{1e}').filter(|chunk| !chunk.trim().is_empty()) {
+        let mut lines = commit.lines();
+        let Some((name, email)) = lines.next().and_then(|header| header.split_once('\u{1f}')) else { continue };
+        let mut touched: BTreeMap<String, u64> = BTreeMap::new();
+        for line in lines {
+            let mut parts = line.splitn(3, '\t');
+            let (Some(additions), Some(deletions), Some(path)) = (parts.next(), parts.next(), parts.next()) else { continue };
+            let changed = additions.parse::<u64>().unwrap_or(0) + deletions.parse::<u64>().unwrap_or(0);
+            for directory in ancestors(&rename_target(path)) {
+                *touched.entry(directory).or_default() += changed;
+            }
+        }
+        for (directory, changed) in touched {
+            let owner = stats.entry(directory).or_default().entry(email.to_ascii_lowercase()).or_insert_with(|| DirectoryOwner {
+                name: name.to_string(),
+                email: email.to_string(),
+                commits: 0,
+                lines: 0,
+            });
+            owner.commits += 1;
+            owner.lines += changed;
+        }
+    }
+
+    let top = top.unwrap_or(DEFAULT_TOP_OWNERS).max(1);
+    let directories = stats
+        .into_iter()
+        .map(|(directory, owners)| {
+            let mut owners: Vec<DirectoryOwner> = owners.into_values().collect();
+            owners.sort_by(|a, b| b.commits.cmp(&a.commits).then(b.lines.cmp(&a.lines)));
+            owners.truncate(top);
+            (directory, owners)
+        })
+        .collect();
+    Ok(OwnershipMap { window_days, directories })
+}
+
+/// Owners of the directories closest to each path changed in the working tree, excluding the current git user.
+pub fn suggest_reviewers(repo: &Path, window_days: u32, limit: Option<usize>) -> Result<ReviewerSuggestions> {
+    let map = ownership_map(repo, window_days, Some(5))?;
+    let changed_paths = worktree_changed_paths(repo)?;
+    let (_, me, _) = run_git_allow_fail(repo, &["config", "user.email"])?;
+    let me = String::from_utf8_lossy(&me).trim().to_ascii_lowercase();
+
+    let mut reviewers: BTreeMap<String, ReviewerSuggestion> = BTreeMap::new();
+    for path in &changed_paths {
+        let Some(owners) = ancestors(path).into_iter().rev().find_map(|directory| map.directories.get(&directory)) else { continue };
+        for owner in owners.iter().filter(|owner| owner.email.to_ascii_lowercase() != me) {
+            let entry = reviewers.entry(owner.email.to_ascii_lowercase()).or_insert_with(|| ReviewerSuggestion {
+                name: owner.name.clone(),
+                email: owner.email.clone(),
+                score: 0,
+                paths: Vec::new(),
+            });
+            entry.score += owner.commits;
+            entry.paths.push(path.clone());
+        }
+    }
+    let mut reviewers: Vec<ReviewerSuggestion> = reviewers.into_values().collect();
+    reviewers.sort_by(|a, b| b.score.cmp(&a.score).then(b.paths.len().cmp(&a.paths.len())));
+    reviewers.truncate(limit.unwrap_or(5).max(1));
+    Ok(ReviewerSuggestions { window_days: map.window_days, changed_paths, reviewers })
+}
+
+fn worktree_changed_paths(repo: &Path) -> Result<Vec<String>> {
+    let status = run_git(repo, &["status", "--porcelain=v1", "-z", "--untracked-files=all"])?;
+    let mut out = Vec::new();
+    let mut records = status.split(|b| *b == 0);
+    while let Some(record) = records.next() {
+        if record.len() <= 3 {
+            continue;
+        }
+        let record = String::from_utf8_lossy(record);
+        out.push(record[3..].to_string());
+        // Renames and copies are followed by their source path, which is not a change of its own.
+        if matches!(record.as_bytes()[0], b'R' | b'C') {
+            records.next();
+        }
+    }
+    out.sort();
+    out.dedup();
+    Ok(out)
+}
+
+/// `.` followed by every parent directory of `path`, outermost first.
+fn ancestors(path: &str) -> Vec<String> {
+    let mut out = vec![".".to_string()];
+    let segments: Vec<&str> = path.split('/').collect();
+    for depth in 1..segments.len() {
+        out.push(segments[..depth].join("/"));
+    }
+    out
+}
+
+/// Numstat writes renames as `dir/{old => new}/file` or `old => new`; ownership goes to the new location.
+fn rename_target(path: &str) -> String {
+    if let (Some(open), Some(close)) = (path.find('{'), path.find('}')) {
+        if let Some((_, new)) = path[open + 1..close].split_once(" => ") {
+            return format!("{}{}{}", &path[..open], new, &path[close + 1..]).replace("//", "/");
+        }
+    }
+    path.split_once(" => ").map(|(_, new)| new.to_string()).unwrap_or_else(|| path.to_string())
+}
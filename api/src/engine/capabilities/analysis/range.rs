@@ -0,0 +1,209 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use super::sizes::language_for_path;
+use crate::engine::capabilities::git::git::{ensure_git_repo, resolve_read_ref, run_git};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeFileChange {
+    pub path: String,
+    /// Set for renames and copies.
+    pub old_path: Option<String>,
+    /// First letter of git's name-status: A, M, D, R, C or T.
+    pub status: String,
+    /// `None` for binary files.
+    pub additions: Option<u64>,
+    pub deletions: Option<u64>,
+    pub language: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeDirectoryChurn {
+    pub directory: String,
+    pub files: u64,
+    pub additions: u64,
+    pub deletions: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeAuthor {
+    pub name: String,
+    pub email: String,
+    pub commits: u64,
+    pub additions: u64,
+    pub deletions: u64,
+    /// Distinct files this author touched in the range, including ones later reverted.
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeAnalysis {
+    pub from_ref: String,
+    pub to_ref: String,
+    pub commits: u64,
+    pub additions: u64,
+    pub deletions: u64,
+    pub files: Vec<RangeFileChange>,
+    pub directories: Vec<RangeDirectoryChurn>,
+    pub authors: Vec<RangeAuthor>,
+}
+
+/// Only the files that differ between `from_ref` and `to_ref`, with churn grouped by directory
+/// (first `depth` segments) and by the authors of the non-merge commits in `from_ref..to_ref`. Both refs
+/// are resolved to commit ids before any of them reaches git.
+pub fn analyze_range(repo: &Path, from_ref: &str, to_ref: &str, depth: usize) -> Result<RangeAnalysis> {
+    ensure_git_repo(repo)?;
+    let (from_ref, to_ref) = (from_ref.trim(), to_ref.trim());
+    if from_ref.is_empty() || to_ref.is_empty() {
+        bail!("from_ref and to_ref are required");
+    }
+
+    let from = resolve_range_end(repo, from_ref)?;
+    let to = resolve_range_end(repo, to_ref)?;
+    let files = changed_files(repo, &from, &to)?;
+
+    let mut directories: BTreeMap<String, RangeDirectoryChurn> = BTreeMap::new();
+    for file in &files {
+        let directory = directory_key(&file.path, depth.max(1));
+        let entry = directories.entry(directory.clone()).or_insert(RangeDirectoryChurn { directory, files: 0, additions: 0, deletions: 0 });
+        entry.files += 1;
+        entry.additions += file.additions.unwrap_or(0);
+        entry.deletions += file.deletions.unwrap_or(0);
+    }
+    let mut directories: Vec<RangeDirectoryChurn> = directories.into_values().collect();
+    directories.sort_by(|a, b| (b.additions + b.deletions).cmp(&(a.additions + a.deletions)).then_with(|| a.directory.cmp(&b.directory)));
+
+    let range = format!("{from}..{to}");
+    let commits = String::from_utf8_lossy(&run_git(repo, &["rev-list", "--count", "--end-of-options", &range])?)
+        .trim()
+        .parse()
+        .unwrap_or(0);
+
+    Ok(RangeAnalysis {
+        from_ref: from_ref.to_string(),
+        to_ref: to_ref.to_string(),
+        commits,
+        additions: files.iter().filter_map(|file| file.additions).sum(),
+        deletions: files.iter().filter_map(|file| file.deletions).sum(),
+        authors: range_authors(repo, &range)?,
+        directories,
+        files,
+    })
+}
+
+fn resolve_range_end(repo: &Path, git_ref: &str) -> Result<String> {
+    let commit = resolve_read_ref(repo, git_ref)?;
+    if commit == "WORKTREE" {
+        bail!("a range needs two commits; {git_ref} is not one");
+    }
+    Ok(commit)
+}
+
+fn changed_files(repo: &Path, from: &str, to: &str) -> Result<Vec<RangeFileChange>> {
+    let name_status = run_git(repo, &["diff", "--name-status", "-M", "-z", "--end-of-options", from, to])?;
+    let numstat = run_git(repo, &["diff", "--numstat", "-M", "-z", "--end-of-options", from, to])?;
+
+    let mut stats: BTreeMap<String, (Option<u64>, Option<u64>)> = BTreeMap::new();
+    let mut fields = numstat.split(|b| *b == 0).map(|field| String::from_utf8_lossy(field).to_string());
+    while let Some(record) = fields.next() {
+        let mut parts = record.splitn(3, '\t');
+        let (Some(additions), Some(deletions), Some(path)) = (parts.next(), parts.next(), parts.next()) else { continue };
+        // Renames leave the path empty and follow with the old and new paths as separate fields.
+        let path = if path.is_empty() {
+            fields.next();
+            fields.next().unwrap_or_default()
+        } else {
+            path.to_string()
+        };
+        stats.insert(path, (additions.parse().ok(), deletions.parse().ok()));
+    }
+
+    let mut out = Vec::new();
+    let mut fields = name_status.split(|b| *b == 0).map(|field| String::from_utf8_lossy(field).to_string());
+    while let Some(status) = fields.next().filter(|status| !status.is_empty()) {
+        let status = status[..1].to_string();
+        let old_path = if matches!(status.as_str(), "R" | "C") { fields.next() } else { None };
+        let Some(path) = fields.next() else { break };
+        let (additions, deletions) = stats.get(&path).copied().unwrap_or((None, None));
+        out.push(RangeFileChange {
+            language: language_for_path(&path).to_string(),
+            path,
+            old_path,
+            status,
+            additions,
+            deletions,
+        });
+    }
+    Ok(out)
+}
+
+fn range_authors(repo: &Path, range: &str) -> Result<Vec<RangeAuthor>> {
+    let log = run_git(repo, &["log", "--no-merges", "--numstat", "--format=%x1e%an%x1f%ae", "--end-of-options", range])?;
+    let mut authors: BTreeMap<String, (RangeAuthor, BTreeSet<String>)> = BTreeMap::new();
+    for commit in String::from_utf8_lossy(&log).split('\u{1e}').filter(|chunk| !chunk.trim().is_empty()) {
+        let mut lines = commit.lines();
+        let Some((name, email)) = lines.next().and_then(|header| header.split_once('\u{1f}')) else { continue };
+        let (author, paths) = authors.entry(email.to_ascii_lowercase()).or_insert_with(|| {
+            (RangeAuthor { name: name.to_string(), email: email.to_string(), commits: 0, additions: 0, deletions: 0, files: 0 }, BTreeSet::new())
+        });
+        author.commits += 1;
+        for line in lines {
+            let mut parts = line.splitn(3, '\t');
+            let (Some(additions), Some(deletions), Some(path)) = (parts.next(), parts.next(), parts.next()) else { continue };
+            author.additions += additions.parse::<u64>().unwrap_or(0);
+            author.deletions += deletions.parse::<u64>().unwrap_or(0);
+            paths.insert(path.to_string());
+        }
+    }
+    let mut out: Vec<RangeAuthor> = authors
+        .into_values()
+        .map(|(mut author, paths)| {
+            author.files = paths.len() as u64;
+            author
+        })
+        .collect();
+    out.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| (b.additions + b.deletions).cmp(&(a.additions + a.deletions))));
+    Ok(out)
+}
+
+fn directory_key(path: &str, depth: usize) -> String {
+    let segments: Vec<&str> = path.split('/').collect();
+    if segments.len() == 1 {
+        return ".".to_string();
+    }
+    segments[..(segments.len() - 1).min(depth)].join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_ends_are_resolved_to_commits() {
+        let repo = std::env::temp_dir().join(format!("range-refs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        for (n, text) in ["a\n", "a\nb\n"].into_iter().enumerate() {
+            std::fs::write(repo.join("src.rs"), text).unwrap();
+            run_git(&repo, &["add", "src.rs"]).unwrap();
+            run_git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", &format!("c{n}")]).unwrap();
+        }
+
+        let analysis = analyze_range(&repo, "HEAD~1", "HEAD", 1).unwrap();
+        assert_eq!((analysis.from_ref.as_str(), analysis.commits, analysis.additions), ("HEAD~1", 1, 1));
+        assert_eq!(analysis.authors.len(), 1);
+
+        let written = repo.join("written");
+        let option = format!("--output={}", written.display());
+        assert!(analyze_range(&repo, &option, "HEAD", 1).is_err());
+        assert!(analyze_range(&repo, "HEAD", &option, 1).is_err());
+        assert!(analyze_range(&repo, "WORKTREE", "HEAD", 1).is_err());
+        assert!(!written.exists());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, path::Path};
+
+use chrono::Utc;
+use serde::Serialize;
+
+use super::sizes::FileSizeEntry;
+
+/// Files, bytes and lines aggregated under one key (a language, extension or directory).
+#[derive(Debug, Clone, Serialize)]
+pub struct StatRow {
+    pub key: String,
+    pub files: u64,
+    pub bytes: u64,
+    pub lines: u64,
+}
+
+/// Everything the size analysis knows about a repo, in the shape the exporters write out.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisReport {
+    pub repo_ref: String,
+    pub generated_at: String,
+    pub total_files: u64,
+    pub total_bytes: u64,
+    pub total_lines: u64,
+    pub languages: Vec<StatRow>,
+    pub extensions: Vec<StatRow>,
+    /// Grouped by top-level directory; files at the root are under `.`.
+    pub directories: Vec<StatRow>,
+    pub files: Vec<FileSizeEntry>,
+}
+
+pub fn build_report(repo_ref: &str, files: Vec<FileSizeEntry>) -> AnalysisReport {
+    let group = |key_of: &dyn Fn(&FileSizeEntry) -> String| {
+        let mut rows: BTreeMap<String, StatRow> = BTreeMap::new();
+        for file in &files {
+            let key = key_of(file);
+            let row = rows.entry(key.clone()).or_insert(StatRow { key, files: 0, bytes: 0, lines: 0 });
+            row.files += 1;
+            row.bytes += file.bytes;
+            row.lines += file.lines;
+        }
+        let mut rows: Vec<StatRow> = rows.into_values().collect();
+        rows.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.key.cmp(&b.key)));
+        rows
+    };
+
+    AnalysisReport {
+        repo_ref: repo_ref.to_string(),
+        generated_at: Utc::now().to_rfc3339(),
+        total_files: files.len() as u64,
+        total_bytes: files.iter().map(|file| file.bytes).sum(),
+        total_lines: files.iter().map(|file| file.lines).sum(),
+        languages: group(&|file| file.language.clone()),
+        extensions: group(&|file| {
+            Path::new(&file.path)
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy().to_ascii_lowercase()))
+                .unwrap_or_else(|| "(none)".to_string())
+        }),
+        directories: group(&|file| file.path.split_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_else(|| ".".to_string())),
+        files,
+    }
+}
+
+pub fn render_stats_csv(key_header: &str, rows: &[StatRow]) -> String {
+    let mut out = format!("{key_header},files,bytes,lines\n");
+    for row in rows {
+        let key = if row.key.contains([',', '"', '\n']) { format!("\"{}\"", row.key.replace('"', "\"\"")) } else { row.key.clone() };
+        out.push_str(&format!("{key},{},{},{}\n", row.files, row.bytes, row.lines));
+    }
+    out
+}
+
+/// Self-contained page (inline CSS, no scripts) with the totals and bar charts for languages, directories and extensions.
+pub fn render_html(report: &AnalysisReport) -> String {
+    let title = format!("Repository analysis: {}", repo_name(&report.repo_ref));
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{}</title>\n", escape(&title)));
+    out.push_str(
+        "<style>\n\
+         body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:960px;color:#222;padding:0 1rem}\n\
+         h1{font-size:1.5rem}h2{font-size:1.1rem;margin-top:2rem}\n\
+         .totals{display:flex;gap:2rem}.totals div{font-size:1.4rem;font-weight:600}.totals span{display:block;font-size:.8rem;color:#666;font-weight:400}\n\
+         table{border-collapse:collapse;width:100%;font-size:.85rem}td,th{padding:.25rem .5rem;text-align:left;border-bottom:1px solid #eee}\n\
+         td.num,th.num{text-align:right;font-variant-numeric:tabular-nums}\n\
+         .bar{background:#e7f0fd;height:.8rem;min-width:1px}.bar span{display:block;height:100%;background:#3b82f6}\n\
+         footer{margin-top:2rem;font-size:.75rem;color:#888}\n\
+         </style>\n</head>\n<body>\n",
+    );
+    out.push_str(&format!("<h1>{}</h1>\n", escape(&title)));
+    out.push_str(&format!(
+        "<div class=\"totals\"><div>{}<span>files</span></div><div>{}<span>size</span></div><div>{}<span>lines</span></div></div>\n",
+        report.total_files,
+        format_bytes(report.total_bytes),
+        report.total_lines
+    ));
+    push_chart(&mut out, "Languages", "Language", &report.languages, |row| row.lines, report.total_lines, "lines");
+    push_chart(&mut out, "Directories", "Directory", &report.directories, |row| row.bytes, report.total_bytes, "size");
+    push_chart(&mut out, "Extensions", "Extension", &report.extensions, |row| row.bytes, report.total_bytes, "size");
+    out.push_str(&format!(
+        "<footer>{} · generated {}</footer>\n</body>\n</html>\n",
+        escape(&report.repo_ref),
+        escape(&report.generated_at)
+    ));
+    out
+}
+
+/// Tables are capped so a huge monorepo still produces a readable page; the CSV/JSON exports carry every row.
+const HTML_CHART_ROWS: usize = 25;
+
+fn push_chart(out: &mut String, heading: &str, key_header: &str, rows: &[StatRow], metric: fn(&StatRow) -> u64, total: u64, metric_label: &str) {
+    let mut rows: Vec<&StatRow> = rows.iter().collect();
+    rows.sort_by_key(|row| std::cmp::Reverse(metric(row)));
+    out.push_str(&format!("<h2>{heading}</h2>\n<table>\n<tr><th>{key_header}</th><th class=\"num\">Files</th><th class=\"num\">Size</th><th class=\"num\">Lines</th><th>Share of {metric_label}</th></tr>\n"));
+    for row in rows.iter().take(HTML_CHART_ROWS) {
+        let share = if total > 0 { metric(row) as f64 * 100.0 / total as f64 } else { 0.0 };
+        out.push_str(&format!(
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td><div class=\"bar\" title=\"{share:.1}%\"><span style=\"width:{share:.1}%\"></span></div></td></tr>\n",
+            escape(&row.key),
+            row.files,
+            format_bytes(row.bytes),
+            row.lines
+        ));
+    }
+    if rows.len() > HTML_CHART_ROWS {
+        out.push_str(&format!("<tr><td colspan=\"5\">… {} more</td></tr>\n", rows.len() - HTML_CHART_ROWS));
+    }
+    out.push_str("</table>\n");
+}
+
+fn repo_name(repo_ref: &str) -> String {
+    Path::new(repo_ref.trim_end_matches('/'))
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| repo_ref.to_string())
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{b} B"),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
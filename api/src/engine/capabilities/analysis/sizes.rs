@@ -0,0 +1,146 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::capabilities::git::git::{ensure_git_repo, resolve_read_ref, run_git, run_git_allow_fail};
+
+const MAX_LINE_COUNT_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSizeEntry {
+    pub path: String,
+    pub bytes: u64,
+    pub lines: u64,
+    pub language: String,
+}
+
+/// Per-file weight of the worktree (tracked plus untracked, not ignored).
+/// Binary files and files above the line-count limit report zero lines.
+pub fn collect_file_sizes(repo: &Path) -> Result<Vec<FileSizeEntry>> {
+    ensure_git_repo(repo)?;
+
+    let mut out = Vec::new();
+    for args in [&["ls-files", "-z"][..], &["ls-files", "-z", "--others", "--exclude-standard"][..]] {
+        let stdout = run_git(repo, args)?;
+        for chunk in stdout.split(|b| *b == 0).filter(|chunk| !chunk.is_empty()) {
+            let rel = String::from_utf8_lossy(chunk).to_string();
+            let Ok(meta) = fs::metadata(repo.join(&rel)) else { continue };
+            if !meta.is_file() {
+                continue;
+            }
+            let bytes = meta.len();
+            let lines = if bytes <= MAX_LINE_COUNT_BYTES {
+                fs::read(repo.join(&rel))
+                    .ok()
+                    .filter(|contents| !contents.iter().take(8000).any(|b| *b == 0))
+                    .map(|contents| contents.iter().filter(|b| **b == b'\n').count() as u64)
+                    .unwrap_or(0)
+            } else {
+                0
+            };
+            out.push(FileSizeEntry {
+                language: language_for_path(&rel).to_string(),
+                path: rel,
+                bytes,
+                lines,
+            });
+        }
+    }
+
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    out.dedup_by(|a, b| a.path == b.path);
+    Ok(out)
+}
+
+/// The same inventory for a committed tree. Line counts come from `git grep`, which skips binary blobs.
+/// `git_ref` is resolved to a commit id first; `WORKTREE` falls back to [`collect_file_sizes`].
+pub fn collect_file_sizes_at(repo: &Path, git_ref: &str) -> Result<Vec<FileSizeEntry>> {
+    ensure_git_repo(repo)?;
+    let commit = resolve_read_ref(repo, git_ref)?;
+    if commit == "WORKTREE" {
+        return collect_file_sizes(repo);
+    }
+
+    // git grep takes no --end-of-options; `commit` is a resolved id, so it cannot read as an option.
+    let (code, stdout, stderr) = run_git_allow_fail(repo, &["grep", "-I", "-c", "-z", "-e", "", &commit, "--"])?;
+    if code > 1 {
+        bail!("git grep failed: {}", String::from_utf8_lossy(&stderr).trim());
+    }
+    let prefix = format!("{commit}:");
+    let mut lines = HashMap::new();
+    for record in String::from_utf8_lossy(&stdout).lines() {
+        let Some((path, count)) = record.split_once('\0') else { continue };
+        lines.insert(path.strip_prefix(&prefix).unwrap_or(path).to_string(), count.trim().parse::<u64>().unwrap_or(0));
+    }
+
+    let listing = run_git(repo, &["ls-tree", "-r", "-l", "-z", "--end-of-options", &commit])?;
+    let mut out = Vec::new();
+    for record in listing.split(|b| *b == 0).filter(|record| !record.is_empty()) {
+        let record = String::from_utf8_lossy(record);
+        let Some((meta, path)) = record.split_once('\t') else { continue };
+        let mut fields = meta.split_whitespace();
+        if fields.nth(1) != Some("blob") {
+            continue;
+        }
+        let Some(Ok(bytes)) = fields.nth(1).map(str::parse::<u64>) else { continue };
+        out.push(FileSizeEntry {
+            language: language_for_path(path).to_string(),
+            lines: lines.get(path).copied().unwrap_or(0),
+            path: path.to_string(),
+            bytes,
+        });
+    }
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(out)
+}
+
+pub fn language_for_path(path: &str) -> &'static str {
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "rs" => "Rust",
+        "ts" | "tsx" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "py" => "Python",
+        "go" => "Go",
+        "java" | "kt" => "JVM",
+        "c" | "h" | "cc" | "cpp" | "hpp" => "C/C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "abap" => "ABAP",
+        "md" | "txt" | "rst" => "Docs",
+        "json" | "toml" | "yaml" | "yml" | "xml" | "ini" => "Config",
+        "html" | "css" | "scss" => "Web",
+        "sh" | "bash" | "ps1" => "Shell",
+        "sql" => "SQL",
+        _ => "Other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn committed_sizes_resolve_the_ref_first() {
+        let repo = std::env::temp_dir().join(format!("sizes-ref-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        fs::write(repo.join("a.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        run_git(&repo, &["add", "a.rs"]).unwrap();
+        run_git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]).unwrap();
+
+        let files = collect_file_sizes_at(&repo, "HEAD").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].path.as_str(), files[0].lines), ("a.rs", 2));
+
+        let marker = repo.join("pwned");
+        let option = format!("--open-files-in-pager=touch {}", marker.display());
+        assert!(collect_file_sizes_at(&repo, &option).is_err());
+        assert!(!marker.exists());
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use super::{
+    context_export::{normalize_context_export_payload, render_context_export_text},
+    filesystem::{read_text_file_at_ref, resolve_workspace_path},
+    git::git::{resolve_read_ref, run_git, run_git_allow_fail},
+};
+
+/// Read-only repository capabilities for callers outside a workflow run, such as MCP clients. Every call
+/// is checked against the caller's grant: which capabilities it may use and which repositories it may read.
+/// Client refs are resolved to commit ids before they reach git.
+pub struct CapabilityBroker {
+    capabilities: Vec<String>,
+    /// Canonical repository roots; empty shares nothing.
+    repo_roots: Vec<PathBuf>,
+}
+
+impl CapabilityBroker {
+    pub fn new(capabilities: Vec<String>, allowed_repos: &[String]) -> Self {
+        let repo_roots = allowed_repos.iter().filter_map(|root| std::fs::canonicalize(root.trim()).ok()).collect();
+        Self { capabilities, repo_roots }
+    }
+
+    fn authorize(&self, capability: &str, repo_ref: &str) -> Result<PathBuf> {
+        if !self.capabilities.iter().any(|allowed| allowed == capability) {
+            bail!("{capability} is not enabled");
+        }
+        let repo = std::fs::canonicalize(repo_ref.trim()).with_context(|| format!("cannot open {repo_ref}"))?;
+        if self.repo_roots.is_empty() {
+            bail!("no repositories are shared; add one to the allowed repositories first");
+        }
+        if !self.repo_roots.iter().any(|root| repo.starts_with(root)) {
+            bail!("{} is outside the shared repositories", repo.display());
+        }
+        Ok(repo)
+    }
+
+    /// Tracked and untracked (not ignored) files at `git_ref`.
+    pub fn repo_tree(&self, repo_ref: &str, git_ref: &str) -> Result<Vec<String>> {
+        let repo = self.authorize("repo_tree", repo_ref)?;
+        let git_ref = resolve_read_ref(&repo, git_ref)?;
+        let listing = if git_ref == "WORKTREE" {
+            run_git(&repo, &["ls-files", "--cached", "--others", "--exclude-standard"])?
+        } else {
+            run_git(&repo, &["ls-tree", "-r", "--name-only", "--end-of-options", &git_ref])?
+        };
+        Ok(String::from_utf8_lossy(&listing).lines().map(ToString::to_string).collect())
+    }
+
+    pub fn read_file(&self, repo_ref: &str, path: &str, git_ref: &str) -> Result<String> {
+        let repo = self.authorize("read_file", repo_ref)?;
+        let git_ref = resolve_read_ref(&repo, git_ref)?;
+        read_text_file_at_ref(&repo.to_string_lossy(), path.trim_start_matches('/'), &git_ref)
+    }
+
+    /// `path:line:text` matches of an extended regular expression.
+    pub fn search(&self, repo_ref: &str, pattern: &str, git_ref: &str) -> Result<Vec<String>> {
+        let repo = self.authorize("search", repo_ref)?;
+        let git_ref = resolve_read_ref(&repo, git_ref)?;
+        let mut args = vec!["grep", "-n", "-I", "-E", "-e", pattern];
+        if git_ref == "WORKTREE" {
+            args.insert(1, "--untracked");
+        } else {
+            args.push(&git_ref);
+        }
+        args.push("--");
+        let (code, stdout, stderr) = run_git_allow_fail(&repo, &args)?;
+        match code {
+            0 => {
+                let prefix = format!("{git_ref}:");
+                Ok(String::from_utf8_lossy(&stdout)
+                    .lines()
+                    .map(|line| line.strip_prefix(&prefix).unwrap_or(line).to_string())
+                    .collect())
+            }
+            1 => Ok(Vec::new()),
+            _ => bail!("git grep failed: {}", String::from_utf8_lossy(&stderr).trim()),
+        }
+    }
+
+    /// `scope` is `unstaged`, `staged` or `all` (against HEAD).
+    pub fn diff(&self, repo_ref: &str, scope: &str, path: Option<&str>) -> Result<String> {
+        let repo = self.authorize("diff", repo_ref)?;
+        let mut args = match scope {
+            "staged" => vec!["diff", "--cached"],
+            "unstaged" => vec!["diff"],
+            "all" => vec!["diff", "HEAD"],
+            other => bail!("unknown scope {other}; expected unstaged, staged or all"),
+        };
+        args.push("--");
+        args.extend(path);
+        Ok(String::from_utf8_lossy(&run_git(&repo, &args)?).to_string())
+    }
+
+    pub fn context_export(&self, repo_ref: &str, git_ref: &str, files: Value, include_unstaged_diff: bool) -> Result<String> {
+        let repo = self.authorize("context_export", repo_ref)?;
+        let git_ref = resolve_read_ref(&repo, git_ref)?;
+        let repo_ref = repo.to_string_lossy().to_string();
+        let Some(requested) = files.as_array() else {
+            bail!("files must be an array of paths");
+        };
+        let mut files = Vec::with_capacity(requested.len());
+        for file in requested {
+            let path = file.as_str().context("files must be an array of paths")?.trim_start_matches('/');
+            resolve_workspace_path(&repo_ref, path)?;
+            files.push(path.to_string());
+        }
+        let payload = normalize_context_export_payload(
+            json!({
+                "repo_ref": repo_ref,
+                "git_ref": git_ref,
+                "include_files": files,
+                "include_unstaged_diff": include_unstaged_diff,
+            }),
+            None,
+            &repo_ref,
+        );
+        render_context_export_text(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_repo(name: &str) -> PathBuf {
+        let repo = std::env::temp_dir().join(format!("{name}-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        repo
+    }
+
+    #[test]
+    fn repositories_must_be_shared_explicitly() {
+        let shared = init_repo("broker-shared");
+        let other = init_repo("broker-other");
+        let tools = vec!["read_file".to_string()];
+
+        let nothing_shared = CapabilityBroker::new(tools.clone(), &[]);
+        assert!(nothing_shared.read_file(&shared.to_string_lossy(), "a.txt", "WORKTREE").is_err());
+
+        let broker = CapabilityBroker::new(tools, &[shared.to_string_lossy().to_string()]);
+        assert_eq!(broker.read_file(&shared.to_string_lossy(), "a.txt", "WORKTREE").unwrap(), "a\n");
+        assert!(broker.read_file(&other.to_string_lossy(), "a.txt", "WORKTREE").is_err());
+        assert!(broker.repo_tree(&shared.to_string_lossy(), "WORKTREE").is_err());
+
+        let _ = std::fs::remove_dir_all(shared);
+        let _ = std::fs::remove_dir_all(other);
+    }
+
+    #[test]
+    fn client_refs_and_export_paths_stay_inside_the_grant() {
+        let repo = init_repo("broker-refs");
+        let outside = repo.with_extension("secret");
+        std::fs::write(&outside, "top secret contents\n").unwrap();
+        let tools = vec!["read_file".to_string(), "search".to_string(), "context_export".to_string()];
+        let broker = CapabilityBroker::new(tools, &[repo.to_string_lossy().to_string()]);
+        let repo_ref = repo.to_string_lossy().to_string();
+
+        assert!(broker.read_file(&repo_ref, "a.txt", "--output=/tmp/x").is_err());
+        assert!(broker.search(&repo_ref, "a", "--open-files-in-pager=touch pwned").is_err());
+        assert!(!repo.join("pwned").exists());
+
+        let escape = format!("../{}", outside.file_name().unwrap().to_string_lossy());
+        assert!(broker.context_export(&repo_ref, "WORKTREE", json!([escape]), false).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, repo.join("leak.txt")).unwrap();
+            assert!(broker.context_export(&repo_ref, "WORKTREE", json!(["leak.txt"]), false).is_err());
+        }
+        // Absolute paths are read relative to the repository root.
+        let export = broker.context_export(&repo_ref, "WORKTREE", json!([outside.to_string_lossy(), "a.txt"]), false).unwrap();
+        assert!(export.contains("a.txt") && !export.contains("top secret"));
+
+        std::fs::remove_dir_all(&repo).unwrap();
+        std::fs::remove_file(&outside).unwrap();
+    }
+}
@@ -4,6 +4,7 @@ use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use crate::engine::capabilities::changeset::lint::{lint_changeset, LintSeverity};
 use crate::engine::capabilities::changeset::persistence::{
     insert_changeset_log_from_result,
     ChangesetAttemptContext,
@@ -15,6 +16,8 @@ use crate::engine::capabilities::registry::{
     CapabilityInvocationRequest,
     CapabilityResult,
 };
+use crate::engine::capabilities::filesystem::{repo_lock, resolve_workspace_path, write_many, BatchItemResult, BatchWrite};
+use crate::engine::history::{self, FileSnapshot};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ApplyChangesetTarget {
@@ -84,11 +87,11 @@ fn resolve_apply_changeset_target(ctx: &CapabilityContext<'_>, config: Value) ->
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-struct ChangeSetPayload {
-    version: u32,
+pub(super) struct ChangeSetPayload {
+    pub(super) version: u32,
     #[serde(default)]
-    description: String,
-    operations: Vec<Operation>,
+    pub(super) description: String,
+    pub(super) operations: Vec<Operation>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -101,7 +104,7 @@ struct FailingFileReport {
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "op", rename_all = "snake_case")]
-enum Operation {
+pub(super) enum Operation {
     Write { path: String, contents: String },
     Delete { path: String },
     Move { from: String, to: String },
@@ -109,24 +112,36 @@ enum Operation {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-struct EditAction {
-    action: String,
-    #[serde(rename = "match")]
-    match_spec: LiteralMatch,
+pub(super) struct EditAction {
+    pub(super) action: String,
+    #[serde(rename = "match", default, skip_serializing_if = "Option::is_none")]
+    pub(super) match_spec: Option<LiteralMatch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) range: Option<LineRange>,
     #[serde(default)]
-    text: Option<String>,
+    pub(super) text: Option<String>,
     #[serde(default)]
-    replacement: Option<String>,
+    pub(super) replacement: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-struct LiteralMatch {
+pub(super) struct LiteralMatch {
     #[serde(rename = "type")]
-    match_type: String,
-    mode: String,
-    must_match: String,
-    occurrence: usize,
-    text: String,
+    pub(super) match_type: String,
+    pub(super) mode: String,
+    pub(super) must_match: String,
+    pub(super) occurrence: usize,
+    pub(super) text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LineRange {
+    pub(super) start_line: usize,
+    pub(super) end_line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) expected_text: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -177,11 +192,13 @@ pub async fn execute(
     let target = resolve_apply_changeset_target(ctx, config)?;
     let started = Instant::now();
 
-    let result = match execute_changeset_apply(
-        PathBuf::from(&target.repo_ref).as_path(),
-        &payload_text,
-        &target.git_ref,
-    ) {
+    let applied = {
+        let (repo, payload_text, git_ref) = (PathBuf::from(&target.repo_ref), payload_text.clone(), target.git_ref.clone());
+        tokio::task::spawn_blocking(move || execute_changeset_apply(&repo, &payload_text, &git_ref))
+            .await
+            .unwrap_or_else(|err| Err(anyhow!("changeset apply task failed: {err}")))
+    };
+    let result = match applied {
         Ok(result) => result,
         Err(err) => json!({
             "ok": false,
@@ -246,11 +263,26 @@ pub async fn execute(
     })
 }
 
+/// Lints the changeset, then applies it under the repository write lock. Any lint error rejects the
+/// whole changeset and nothing is written; only once it passes do individual operations run, and an
+/// operation that still fails is reported while the others stay applied. Lint warnings never block.
 pub fn execute_changeset_apply(repo: &Path, payload_text: &str, git_ref: &str) -> Result<Value> {
+    Ok(apply_payload(repo, payload_text, git_ref, false)?.0)
+}
+
+/// Same as `execute_changeset_apply`, and also returns the prior state of every path the changeset
+/// touches. It is captured under the repository write lock once lint passed, so it is exactly what the
+/// apply overwrote; a rejected changeset writes nothing and gets no snapshot.
+pub fn execute_changeset_apply_with_snapshot(repo: &Path, payload_text: &str, git_ref: &str) -> Result<(Value, Vec<FileSnapshot>)> {
+    apply_payload(repo, payload_text, git_ref, true)
+}
+
+fn apply_payload(repo: &Path, payload_text: &str, git_ref: &str, snapshot: bool) -> Result<(Value, Vec<FileSnapshot>)> {
     let normalized = normalize_changeset_payload_text(payload_text)?;
     let payload: ChangeSetPayload = serde_json::from_str(&normalized)
         .context("failed to decode normalized changeset")?;
 
+    let lint = lint_changeset(repo, &payload);
     let total_operations = payload.operations.len();
     let total_actions: usize = payload
         .operations
@@ -261,13 +293,93 @@ pub fn execute_changeset_apply(repo: &Path, payload_text: &str, git_ref: &str) -
         })
         .sum();
 
-    let mut lines = Vec::new();
+    if !lint.ok {
+        return Ok((json!({
+            "ok": false,
+            "mode": "changeset_apply",
+            "summary": format!(
+                "ChangeSet rejected by lint: {} errors, {} warnings. Nothing was applied.",
+                lint.error_count,
+                lint.warning_count
+            ),
+            "status": lint
+                .findings
+                .iter()
+                .find(|finding| finding.severity == LintSeverity::Error)
+                .map(|finding| format!("Lint error in operation {}: {}", finding.operation_index, finding.message))
+                .unwrap_or_else(|| "ChangeSet rejected by lint.".to_string()),
+            "target": {
+                "repo_ref": repo.to_string_lossy(),
+                "git_ref": git_ref,
+            },
+            "stats": {
+                "successful_operations": 0,
+                "failed_operations": total_operations,
+                "total_operations": total_operations,
+                "successful_actions": 0,
+                "failed_actions": total_actions,
+                "total_actions": total_actions,
+                "failed_files": 0
+            },
+            "lines": lint.lines(),
+            "lint": lint,
+            "touched_files": [],
+            "failing_files": [],
+            "normalized_payload": normalized,
+        }), Vec::new()));
+    }
+
+    let repo_lock = repo_lock(&repo.to_string_lossy());
+    let applying = repo_lock.write().unwrap_or_else(|err| err.into_inner());
+    let before = if snapshot { snapshot_touched_paths(repo, &payload) } else { Vec::new() };
+    let mut lines = lint.lines();
     let mut successful_operations = 0usize;
     let mut successful_actions = 0usize;
     let mut first_error = None::<String>;
 
-    for (idx, op) in payload.operations.iter().enumerate() {
+    let mut idx = 0usize;
+    while idx < payload.operations.len() {
+        let op = &payload.operations[idx];
         let index = idx + 1;
+
+        if matches!(op, Operation::Write { .. }) {
+            let run_len = payload.operations[idx..]
+                .iter()
+                .take_while(|op| matches!(op, Operation::Write { .. }))
+                .count();
+            let run = &payload.operations[idx..idx + run_len];
+            let outcome = apply_write_batch(repo, run);
+            for (offset, op) in run.iter().enumerate() {
+                let index = idx + offset + 1;
+                lines.push(operation_label_with_index(index, op));
+                match &outcome {
+                    Ok(results) if results[offset].ok => {
+                        successful_operations += 1;
+                        successful_actions += 1;
+                        lines.push("  - PASS write[1] write".to_string());
+                        lines.push(format!("[{}] ok", index));
+                    }
+                    Ok(results) => {
+                        let err_text = results[offset].error.clone().unwrap_or_default();
+                        if first_error.is_none() {
+                            first_error = Some(err_text.clone());
+                        }
+                        lines.push(format!("[{}] FAILED: {}", index, err_text));
+                    }
+                    Err(err) => {
+                        let err_text = format!("{:#}", err);
+                        if first_error.is_none() {
+                            first_error = Some(err_text.clone());
+                        }
+                        lines.push(format!("[{}] FAILED: {}", index, err_text));
+                    }
+                }
+            }
+            idx += run_len;
+            continue;
+        }
+        idx += 1;
+
         let label = operation_label_with_index(index, op);
         lines.push(label.clone());
 
@@ -318,6 +430,8 @@ pub fn execute_changeset_apply(repo: &Path, payload_text: &str, git_ref: &str) -
         }
     }
 
+    drop(applying);
+
     let failed_operations = total_operations.saturating_sub(successful_operations);
     let failed_actions = total_actions.saturating_sub(successful_actions);
     let summary = format_apply_summary(
@@ -414,7 +528,7 @@ pub fn execute_changeset_apply(repo: &Path, payload_text: &str, git_ref: &str) -
         })
         .collect::<Vec<_>>();
 
-    Ok(json!({
+    Ok((json!({
         "ok": failed_operations == 0,
         "mode": "changeset_apply",
         "summary": summary,
@@ -435,8 +549,9 @@ pub fn execute_changeset_apply(repo: &Path, payload_text: &str, git_ref: &str) -
         "lines": lines,
         "touched_files": touched_files,
         "failing_files": failing_files,
+        "lint": lint,
         "normalized_payload": normalized,
-    }))
+    }), before))
 }
 
 async fn log_changeset_attempt(
@@ -615,7 +730,7 @@ fn edit_action_error(op_index: usize, action_index: usize, result: &Value) -> Op
         })
 }
 
-fn extract_json_object_slice(text: &str) -> Option<&str> {
+pub(super) fn extract_json_object_slice(text: &str) -> Option<&str> {
     let bytes = text.as_bytes();
     let mut start = None;
     let mut depth = 0usize;
@@ -664,7 +779,7 @@ fn extract_json_object_slice(text: &str) -> Option<&str> {
     None
 }
 
-fn normalize_changeset_payload_text(payload_text: &str) -> Result<String> {
+pub(super) fn normalize_changeset_payload_text(payload_text: &str) -> Result<String> {
     let mut text = payload_text.trim().to_string();
 
     if text.starts_with("```") {
@@ -697,10 +812,59 @@ fn normalize_changeset_payload_text(payload_text: &str) -> Result<String> {
     Ok(normalized)
 }
 
+pub(super) fn payload_paths_and_description(normalized: &str) -> Result<(Vec<String>, String)> {
+    let payload: ChangeSetPayload = serde_json::from_str(normalized)
+        .context("failed to decode normalized changeset")?;
+    Ok((payload_paths(&payload), payload.description))
+}
+
+fn payload_paths(payload: &ChangeSetPayload) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for op in &payload.operations {
+        let op_paths = match op {
+            Operation::Write { path, .. } | Operation::Delete { path } | Operation::Edit { path, .. } => vec![path],
+            Operation::Move { from, to } => vec![from, to],
+        };
+        for path in op_paths {
+            if !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+    }
+    paths
+}
+
+/// Lint already resolved every path inside the repository; if the snapshot still fails, the apply goes
+/// ahead without an undo entry rather than being refused.
+fn snapshot_touched_paths(repo: &Path, payload: &ChangeSetPayload) -> Vec<FileSnapshot> {
+    history::snapshot_paths(repo, &payload_paths(payload)).unwrap_or_else(|err| {
+        tracing::warn!(error = %format!("{err:#}"), "failed to snapshot changeset paths before apply");
+        Vec::new()
+    })
+}
+
+/// Runs of consecutive `write` operations go through one batched write: every path is validated before any
+/// file is written, so a bad path fails the run instead of leaving it half applied.
+fn apply_write_batch(repo: &Path, run: &[Operation]) -> Result<Vec<BatchItemResult>> {
+    let items = run
+        .iter()
+        .filter_map(|op| match op {
+            Operation::Write { path, contents } => Some(BatchWrite { path: path.clone(), contents: contents.clone() }),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let started = Instant::now();
+    let results = write_many(&repo.to_string_lossy(), &items, |progress| {
+        tracing::debug!(done = progress.done, total = progress.total, path = progress.path, "changeset write batch");
+    })?;
+    tracing::info!(files = items.len(), duration_ms = started.elapsed().as_millis() as u64, "changeset write batch finished");
+    Ok(results)
+}
+
 fn apply_operation(repo: &Path, op: &Operation) -> Result<EditSequenceReport> {
     match op {
         Operation::Write { path, contents } => {
-            let full = repo.join(path);
+            let full = resolve_workspace_path(&repo.to_string_lossy(), path)?;
             if let Some(parent) = full.parent() {
                 fs::create_dir_all(parent)?;
             }
@@ -712,7 +876,7 @@ fn apply_operation(repo: &Path, op: &Operation) -> Result<EditSequenceReport> {
             })
         }
         Operation::Delete { path } => {
-            let full = repo.join(path);
+            let full = resolve_workspace_path(&repo.to_string_lossy(), path)?;
             if full.exists() {
                 fs::remove_file(&full).or_else(|_| fs::remove_dir_all(&full))?;
             }
@@ -723,8 +887,8 @@ fn apply_operation(repo: &Path, op: &Operation) -> Result<EditSequenceReport> {
             })
         }
         Operation::Move { from, to } => {
-            let src = repo.join(from);
-            let dst = repo.join(to);
+            let src = resolve_workspace_path(&repo.to_string_lossy(), from)?;
+            let dst = resolve_workspace_path(&repo.to_string_lossy(), to)?;
             if let Some(parent) = dst.parent() {
                 fs::create_dir_all(parent)?;
             }
@@ -739,32 +903,349 @@ fn apply_operation(repo: &Path, op: &Operation) -> Result<EditSequenceReport> {
     }
 }
 
-fn apply_edit_change(input: &str, change: &EditAction) -> Result<String> {
-    if change.match_spec.match_type != "literal" {
-        bail!("unsupported match.type {}", change.match_spec.match_type);
+fn line_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut offset = 0usize;
+    for line in text.split('\n') {
+        spans.push((offset, offset + line.len()));
+        offset += line.len() + 1;
+    }
+    spans
+}
+
+fn trimmed_needle_lines(needle: &str) -> Vec<&str> {
+    let lines: Vec<&str> = needle.split('\n').map(str::trim).collect();
+    let first = lines.iter().position(|l| !l.is_empty()).unwrap_or(lines.len());
+    let last = lines.iter().rposition(|l| !l.is_empty()).map(|i| i + 1).unwrap_or(first);
+    lines[first..last].to_vec()
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Finds blocks whose lines equal the needle's lines after trimming each
+/// side. Spans cover whole lines, including the file's indentation.
+fn find_fuzzy_whitespace_matches(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let wanted = trimmed_needle_lines(needle);
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+    let spans = line_spans(haystack);
+    let mut matches = Vec::new();
+    let mut i = 0usize;
+    while i + wanted.len() <= spans.len() {
+        let hit = wanted
+            .iter()
+            .enumerate()
+            .all(|(k, w)| haystack[spans[i + k].0..spans[i + k].1].trim() == *w);
+        if hit {
+            matches.push((spans[i].0, spans[i + wanted.len() - 1].1));
+            i += wanted.len();
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
+
+/// Maps each replacement line from the needle's indentation onto the
+/// indentation of the corresponding matched line in the file.
+fn reindent_replacement(replacement: &str, needle: &str, matched: &str) -> String {
+    let needle_lines: Vec<&str> = needle.split('\n').skip_while(|l| l.trim().is_empty()).collect();
+    let matched_lines: Vec<&str> = matched.split('\n').collect();
+
+    replacement
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            if line.trim().is_empty() {
+                return line.to_string();
+            }
+            let j = i.min(matched_lines.len().saturating_sub(1));
+            let needle_indent = needle_lines.get(j).map(|l| leading_whitespace(l)).unwrap_or("");
+            let file_indent = matched_lines.get(j).map(|l| leading_whitespace(l)).unwrap_or("");
+            match line.strip_prefix(needle_indent) {
+                Some(rest) => format!("{}{}", file_indent, rest),
+                None => format!("{}{}", file_indent, line.trim_start()),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn line_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (a.trim(), b.trim());
+    if a == b {
+        return 1.0;
+    }
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    let prefix = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+    let suffix = a
+        .chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .count()
+        .min(longest - prefix);
+    (prefix + suffix) as f64 / longest as f64
+}
+
+/// Describes the block in the file that most resembles the needle, so a
+/// failed match says where the model was probably aiming.
+fn describe_near_miss(haystack: &str, needle: &str) -> Option<String> {
+    let wanted: Vec<&str> = needle.trim_matches('\n').split('\n').collect();
+    let lines: Vec<&str> = haystack.split('\n').collect();
+    if wanted.is_empty() || lines.len() < wanted.len() {
+        return None;
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    for start in 0..=lines.len() - wanted.len() {
+        let score = wanted
+            .iter()
+            .enumerate()
+            .map(|(k, w)| line_similarity(lines[start + k], w))
+            .sum::<f64>()
+            / wanted.len() as f64;
+        if best.map(|(_, b)| score > b).unwrap_or(true) {
+            best = Some((start, score));
+        }
+    }
+
+    let (start, score) = best?;
+    if score < 0.5 {
+        return None;
+    }
+
+    let mut message = format!("closest candidate at line {} ({:.0}% similar)", start + 1, score * 100.0);
+    if let Some((k, w)) = wanted.iter().enumerate().find(|(k, w)| lines[start + k] != **w) {
+        let found = lines[start + k];
+        message.push_str(&format!("; first difference at match line {}: expected {:?}, found {:?}", k + 1, w, found));
+        if found.trim() == w.trim() {
+            message.push_str(" (whitespace only; mode=fuzzy_whitespace would match)");
+        }
+    }
+    Some(message)
+}
+
+/// FNV-1a over the range text with lines joined by `\n`, as 16 hex digits.
+pub(super) fn line_range_checksum(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn describe_range_mismatch(range: &LineRange, expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.split('\n').collect();
+    let actual_lines: Vec<&str> = actual.split('\n').collect();
+    let mut diff = Vec::new();
+    for k in 0..expected_lines.len().max(actual_lines.len()) {
+        let line_no = range.start_line + k;
+        match (expected_lines.get(k), actual_lines.get(k)) {
+            (Some(e), Some(a)) if e == a => diff.push(format!("   {:>5} | {}", line_no, a)),
+            (e, a) => {
+                if let Some(e) = e {
+                    diff.push(format!("-  {:>5} | {}", line_no, e));
+                }
+                if let Some(a) = a {
+                    diff.push(format!("+  {:>5} | {}", line_no, a));
+                }
+            }
+        }
+    }
+    diff.join("\n")
+}
+
+/// Resolves a 1-based inclusive line range to a byte span that excludes the
+/// last line's newline, after checking the expected content.
+fn resolve_line_range(haystack: &str, range: &LineRange) -> Result<(usize, usize)> {
+    let spans = line_spans(haystack);
+    let line_count = if haystack.ends_with('\n') { spans.len() - 1 } else { spans.len() };
+    if range.start_line == 0 || range.end_line < range.start_line || range.end_line > line_count {
+        bail!(
+            "Invalid line range {}-{} (file has {} lines)",
+            range.start_line,
+            range.end_line,
+            line_count
+        );
+    }
+    if range.checksum.is_none() && range.expected_text.is_none() {
+        bail!("Line range edits require checksum or expected_text");
+    }
+
+    let (start, end) = (spans[range.start_line - 1].0, spans[range.end_line - 1].1);
+    let actual = &haystack[start..end];
+
+    if let Some(expected) = range.expected_text.as_deref() {
+        let expected = expected.replace("\r\n", "\n");
+        let expected = expected.strip_suffix('\n').unwrap_or(&expected);
+        if expected != actual {
+            bail!(
+                "Lines {}-{} do not match expected_text:\n{}",
+                range.start_line,
+                range.end_line,
+                describe_range_mismatch(range, expected, actual)
+            );
+        }
+    }
+    if let Some(checksum) = range.checksum.as_deref() {
+        let computed = line_range_checksum(actual);
+        if !checksum.trim().eq_ignore_ascii_case(&computed) {
+            let numbered = actual
+                .split('\n')
+                .enumerate()
+                .map(|(k, line)| format!("+  {:>5} | {}", range.start_line + k, line))
+                .collect::<Vec<_>>()
+                .join("\n");
+            bail!(
+                "Checksum mismatch for lines {}-{}: expected {}, found {}. Actual lines:\n{}",
+                range.start_line,
+                range.end_line,
+                checksum.trim(),
+                computed,
+                numbered
+            );
+        }
+    }
+
+    Ok((start, end))
+}
+
+/// Order in which an edit operation's changes run: line-range edits first, bottom-up, so every range
+/// refers to the file as it was before the operation; then literal matches in the order given. A range
+/// overlapping one that already ran comes with the error it fails with.
+pub(super) fn edit_order(changes: &[EditAction]) -> Vec<(usize, Option<anyhow::Error>)> {
+    let mut ranged: Vec<(usize, &LineRange)> =
+        changes.iter().enumerate().filter_map(|(idx, change)| Some((idx, change.range.as_ref()?))).collect();
+    ranged.sort_by_key(|(_, range)| std::cmp::Reverse((range.start_line, range.end_line)));
+
+    let mut order = Vec::with_capacity(changes.len());
+    let mut floor: Option<(usize, usize)> = None;
+    for (idx, range) in ranged {
+        match floor {
+            Some((start_line, other)) if range.end_line >= start_line => order.push((
+                idx,
+                Some(anyhow!(
+                    "Lines {}-{} overlap edit[{}], which starts at line {}",
+                    range.start_line,
+                    range.end_line,
+                    other + 1,
+                    start_line
+                )),
+            )),
+            _ => {
+                floor = Some((range.start_line, idx));
+                order.push((idx, None));
+            }
+        }
+    }
+    order.extend(changes.iter().enumerate().filter(|(_, change)| change.range.is_none()).map(|(idx, _)| (idx, None)));
+    order
+}
+
+/// Applies one change. Lines the change leaves alone keep their own endings, so files with CRLF or
+/// mixed endings are not rewritten; new lines take the ending of the line they replace.
+pub(super) fn apply_edit_change(input: &str, change: &EditAction) -> Result<String> {
+    let edited = edit_text(input, change)?;
+    Ok(if input.contains("\r\n") { restore_line_endings(input, &edited) } else { edited })
+}
+
+/// Lines shared with `original` at the start and the end of `edited` are taken verbatim from
+/// `original`; the lines in between get the ending of the first original line they replaced (or the
+/// line before them, at the end of the file).
+fn restore_line_endings(original: &str, edited: &str) -> String {
+    fn normalized(line: &str) -> std::borrow::Cow<'_, str> {
+        match line.strip_suffix("\r\n") {
+            Some(body) => format!("{body}\n").into(),
+            None => line.into(),
+        }
+    }
+    let before: Vec<&str> = original.split_inclusive('\n').collect();
+    let after: Vec<&str> = edited.split_inclusive('\n').collect();
+    let prefix = before.iter().zip(&after).take_while(|(a, b)| normalized(a) == normalized(b)).count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| normalized(a) == normalized(b))
+        .count();
+    let ending = before
+        .get(prefix)
+        .filter(|line| line.ends_with('\n'))
+        .or_else(|| prefix.checked_sub(1).and_then(|idx| before.get(idx)))
+        .map_or("\n", |line| if line.ends_with("\r\n") { "\r\n" } else { "\n" });
+
+    let mut out = String::with_capacity(edited.len());
+    out.extend(before[..prefix].iter().copied());
+    for line in &after[prefix..after.len() - suffix] {
+        match line.strip_suffix('\n') {
+            Some(body) => {
+                out.push_str(body.strip_suffix('\r').unwrap_or(body));
+                out.push_str(ending);
+            }
+            None => out.push_str(line),
+        }
+    }
+    out.extend(before[before.len() - suffix..].iter().copied());
+    out
+}
+
+fn edit_text(input: &str, change: &EditAction) -> Result<String> {
+    if let Some(range) = change.range.as_ref() {
+        let haystack = input.replace("\r\n", "\n");
+        let (target, end) = resolve_line_range(&haystack, range)?;
+        return splice_edit(&haystack, target, end, change, None);
     }
 
-    let needle = if change.match_spec.mode == "normalized_newlines" {
-        change.match_spec.text.replace("\r\n", "\n")
+    let match_spec = change
+        .match_spec
+        .as_ref()
+        .ok_or_else(|| anyhow!("edit needs either match or range"))?;
+    if match_spec.match_type != "literal" {
+        bail!("unsupported match.type {}", match_spec.match_type);
+    }
+
+    let mode = match_spec.mode.as_str();
+    let normalize = matches!(mode, "normalized_newlines" | "fuzzy_whitespace");
+
+    let needle = if normalize {
+        match_spec.text.replace("\r\n", "\n")
     } else {
-        change.match_spec.text.clone()
+        match_spec.text.clone()
     };
 
-    let haystack = if change.match_spec.mode == "normalized_newlines" {
+    let haystack = if normalize {
         input.replace("\r\n", "\n")
     } else {
         input.to_string()
     };
 
-    let mut matches = Vec::new();
-    let mut start = 0usize;
-    while let Some(idx) = haystack[start..].find(&needle) {
-        let abs = start + idx;
-        matches.push(abs);
-        start = abs + needle.len();
+    let matches: Vec<(usize, usize)> = match mode {
+        "fuzzy_whitespace" => find_fuzzy_whitespace_matches(&haystack, &needle),
+        _ => {
+            let mut found = Vec::new();
+            let mut start = 0usize;
+            while let Some(idx) = haystack[start..].find(&needle) {
+                let abs = start + idx;
+                found.push((abs, abs + needle.len()));
+                start = abs + needle.len();
+            }
+            found
+        }
+    };
+
+    if matches.is_empty() {
+        if let Some(near_miss) = describe_near_miss(&haystack, &needle) {
+            bail!("Expected {} match, found none; {}", match_spec.must_match.replace('_', " "), near_miss);
+        }
     }
 
-    match change.match_spec.must_match.as_str() {
+    match match_spec.must_match.as_str() {
         "exactly_one" if matches.len() != 1 => {
             bail!("Expected exactly one match, found {}", matches.len());
         }
@@ -775,16 +1256,23 @@ fn apply_edit_change(input: &str, change: &EditAction) -> Result<String> {
         other => bail!("Unsupported must_match '{}'", other),
     }
 
-    let occurrence = change.match_spec.occurrence.max(1);
-    let target = matches
+    let occurrence = match_spec.occurrence.max(1);
+    let (target, end) = matches
         .get(occurrence.saturating_sub(1))
         .copied()
         .context("Requested occurrence not found")?;
-    let end = target + needle.len();
 
+    let reindent_from = (mode == "fuzzy_whitespace").then_some(needle.as_str());
+    splice_edit(&haystack, target, end, change, reindent_from)
+}
+
+fn splice_edit(haystack: &str, target: usize, end: usize, change: &EditAction, reindent_from: Option<&str>) -> Result<String> {
     match change.action.as_str() {
         "replace_block" => {
-            let replacement = change.replacement.clone().ok_or_else(|| anyhow!("replacement is required"))?;
+            let mut replacement = change.replacement.clone().ok_or_else(|| anyhow!("replacement is required"))?;
+            if let Some(needle) = reindent_from {
+                replacement = reindent_replacement(&replacement, needle, &haystack[target..end]);
+            }
             Ok(format!("{}{}{}", &haystack[..target], replacement, &haystack[end..]))
         }
         "insert_before" => {
@@ -810,28 +1298,36 @@ fn operation_primary_path(op: &Operation) -> Option<String> {
 }
 
 fn describe_edit_change(index: usize, change: &EditAction) -> String {
-    format!(
-        "edit[{}] {} (occurrence={}, must_match={}, mode={})",
-        index,
-        change.action,
-        change.match_spec.occurrence.max(1),
-        change.match_spec.must_match,
-        change.match_spec.mode
-    )
+    match (change.range.as_ref(), change.match_spec.as_ref()) {
+        (Some(range), _) => format!(
+            "edit[{}] {} (lines={}-{})",
+            index, change.action, range.start_line, range.end_line
+        ),
+        (None, Some(spec)) => format!(
+            "edit[{}] {} (occurrence={}, must_match={}, mode={})",
+            index,
+            change.action,
+            spec.occurrence.max(1),
+            spec.must_match,
+            spec.mode
+        ),
+        (None, None) => format!("edit[{}] {} (no match)", index, change.action),
+    }
 }
 
 fn apply_edit_sequence(repo: &Path, path: &str, changes: &[EditAction]) -> Result<EditSequenceReport> {
-    let full = repo.join(path);
+    let full = resolve_workspace_path(&repo.to_string_lossy(), path)?;
     let mut report = EditSequenceReport::default();
 
     let mut text = fs::read_to_string(&full)
         .with_context(|| format!("Failed to read {path} for edit"))?;
 
-    for (idx, change) in changes.iter().enumerate() {
+    for (idx, conflict) in edit_order(changes) {
+        let change = &changes[idx];
         let descriptor = describe_edit_change(idx + 1, change);
         let pass_descriptor = descriptor.replacen("edit[", "PASS edit[", 1);
 
-        match apply_edit_change(&text, change) {
+        match conflict.map_or_else(|| apply_edit_change(&text, change), Err) {
             Ok(next_text) => {
                 text = next_text;
                 report.successful_actions += 1;
@@ -859,6 +1355,35 @@ fn apply_edit_sequence(repo: &Path, path: &str, changes: &[EditAction]) -> Resul
     Ok(report)
 }
 
+/// Runs a changeset's edit operations in memory and returns `(path, edited text)` per file, without
+/// writing anything. Any failing action fails the preview, so what is shown is exactly what apply would write.
+pub fn preview_changeset_edits(repo: &Path, payload_text: &str) -> Result<Vec<(String, String)>> {
+    let normalized = normalize_changeset_payload_text(payload_text)?;
+    let payload: ChangeSetPayload = serde_json::from_str(&normalized)
+        .context("failed to decode normalized changeset")?;
+    let mut files: Vec<(String, String)> = Vec::new();
+    for op in &payload.operations {
+        let Operation::Edit { path, changes } = op else {
+            bail!("only edit operations can be previewed");
+        };
+        let mut text = match files.iter().position(|(existing, _)| existing == path) {
+            Some(index) => files.remove(index).1,
+            None => {
+                let full = resolve_workspace_path(&repo.to_string_lossy(), path)?;
+                fs::read_to_string(full).with_context(|| format!("Failed to read {path} for preview"))?
+            }
+        };
+        for (idx, conflict) in edit_order(changes) {
+            let change = &changes[idx];
+            text = conflict
+                .map_or_else(|| apply_edit_change(&text, change), Err)
+                .with_context(|| format!("{} would fail", describe_edit_change(idx + 1, change)))?;
+        }
+        files.push((path.clone(), text));
+    }
+    Ok(files)
+}
+
 fn format_apply_summary(
     successful_operations: usize,
     total_operations: usize,
@@ -901,3 +1426,239 @@ fn operation_label_with_index(index: usize, op: &Operation) -> String {
         Operation::Edit { path, .. } => format!("[{}] edit {}", index, path),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(value: Value) -> EditAction {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn range_replace(start_line: usize, end_line: usize, expected_text: &str, replacement: &str) -> EditAction {
+        change(json!({
+            "action": "replace_block",
+            "range": { "start_line": start_line, "end_line": end_line, "expected_text": expected_text },
+            "replacement": replacement,
+        }))
+    }
+
+    fn apply_all(input: &str, changes: &[EditAction]) -> Result<String> {
+        let mut text = input.to_string();
+        for (idx, conflict) in edit_order(changes) {
+            text = conflict.map_or_else(|| apply_edit_change(&text, &changes[idx]), Err)?;
+        }
+        Ok(text)
+    }
+
+    #[test]
+    fn range_edits_check_expected_text_and_checksum() {
+        let input = "a\nb\nc\n";
+        assert_eq!(apply_edit_change(input, &range_replace(2, 2, "b", "B")).unwrap(), "a\nB\nc\n");
+        let err = apply_edit_change(input, &range_replace(2, 2, "x", "B")).unwrap_err();
+        assert!(format!("{err:#}").contains("do not match expected_text"));
+        assert!(apply_edit_change(input, &range_replace(3, 4, "c", "C")).is_err());
+
+        let checksum = line_range_checksum("b\nc");
+        let by_checksum = change(json!({
+            "action": "delete_block",
+            "range": { "start_line": 2, "end_line": 3, "checksum": checksum },
+        }));
+        assert_eq!(apply_edit_change(input, &by_checksum).unwrap(), "a\n\n");
+        let stale = change(json!({
+            "action": "delete_block",
+            "range": { "start_line": 1, "end_line": 2, "checksum": checksum },
+        }));
+        assert!(format!("{:#}", apply_edit_change(input, &stale).unwrap_err()).contains("Checksum mismatch"));
+        let unchecked = change(json!({ "action": "delete_block", "range": { "start_line": 1, "end_line": 1 } }));
+        assert!(apply_edit_change(input, &unchecked).is_err());
+    }
+
+    #[test]
+    fn range_edits_run_bottom_up_against_original_lines() {
+        let changes = [range_replace(1, 1, "one", "ONE\nextra"), range_replace(3, 3, "three", "THREE")];
+        let order = edit_order(&changes).into_iter().map(|(idx, conflict)| (idx, conflict.is_some())).collect::<Vec<_>>();
+        assert_eq!(order, [(1, false), (0, false)]);
+        assert_eq!(apply_all("one\ntwo\nthree\n", &changes).unwrap(), "ONE\nextra\ntwo\nTHREE\n");
+
+        let overlapping = [range_replace(1, 2, "one\ntwo", "x"), range_replace(2, 3, "two\nthree", "y")];
+        let order = edit_order(&overlapping);
+        assert_eq!(order[0].0, 1);
+        assert!(order[1].1.as_ref().is_some_and(|err| err.to_string().contains("overlap edit[2]")));
+    }
+
+    #[test]
+    fn fuzzy_whitespace_matches_and_reindents() {
+        let input = "fn main() {\n        let x = 1;\n        call(x);\n}\n";
+        let fuzzy = change(json!({
+            "action": "replace_block",
+            "match": { "type": "literal", "mode": "fuzzy_whitespace", "must_match": "exactly_one", "occurrence": 1,
+                       "text": "let x = 1;\ncall(x);" },
+            "replacement": "let x = 2;\n    call(x);",
+        }));
+        assert_eq!(apply_edit_change(input, &fuzzy).unwrap(), "fn main() {\n        let x = 2;\n            call(x);\n}\n");
+
+        let strict = change(json!({
+            "action": "delete_block",
+            "match": { "type": "literal", "mode": "normalized_newlines", "must_match": "exactly_one", "occurrence": 1,
+                       "text": "let x = 1;\ncall(x);" },
+        }));
+        let err = format!("{:#}", apply_edit_change(input, &strict).unwrap_err());
+        assert!(err.contains("mode=fuzzy_whitespace would match"), "{err}");
+    }
+
+    #[test]
+    fn edits_keep_each_lines_own_ending() {
+        let mixed = "a\r\nb\nc\r\nd\n";
+        assert_eq!(apply_edit_change(mixed, &range_replace(3, 3, "c", "C\nC2")).unwrap(), "a\r\nb\nC\r\nC2\r\nd\n");
+        assert_eq!(apply_edit_change(mixed, &range_replace(2, 2, "b", "B")).unwrap(), "a\r\nB\nc\r\nd\n");
+
+        let crlf = "x\r\ny\r\n";
+        let append = change(json!({
+            "action": "insert_after",
+            "match": { "type": "literal", "mode": "normalized_newlines", "must_match": "exactly_one", "occurrence": 1, "text": "y\n" },
+            "text": "z\n",
+        }));
+        assert_eq!(apply_edit_change(crlf, &append).unwrap(), "x\r\ny\r\nz\r\n");
+        assert_eq!(apply_edit_change("p\nq\n", &range_replace(1, 1, "p", "P")).unwrap(), "P\nq\n");
+    }
+
+    fn temp_repo(files: &[(&str, &str)]) -> PathBuf {
+        let repo = std::env::temp_dir().join(format!("changeset-apply-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&repo).unwrap();
+        for (path, contents) in files {
+            let full = repo.join(path);
+            fs::create_dir_all(full.parent().unwrap()).unwrap();
+            fs::write(full, contents).unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn snapshot_holds_what_a_clean_apply_overwrote() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_repo(&[("a.txt", "one\n"), ("b.txt", "keep\n")]);
+        let payload = json!({
+            "version": 1,
+            "operations": [
+                { "op": "delete", "path": "a.txt" },
+                { "op": "write", "path": "b.txt", "contents": "new\n" },
+            ],
+        });
+
+        let (result, before) = execute_changeset_apply_with_snapshot(&repo, &payload.to_string(), "WORKTREE").unwrap();
+        assert_eq!(result["ok"], json!(true));
+        let contents = before.iter().map(|file| (file.path.as_str(), file.read().unwrap())).collect::<Vec<_>>();
+        assert_eq!(contents, [("a.txt", b"one\n".to_vec()), ("b.txt", b"keep\n".to_vec())]);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn rejected_changesets_take_no_snapshot() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_repo(&[("b.txt", "keep\n")]);
+        let payload = json!({
+            "version": 1,
+            "operations": [
+                { "op": "write", "path": "b.txt", "contents": "new\n" },
+                { "op": "delete", "path": "../outside.txt" },
+            ],
+        });
+
+        let (result, before) = execute_changeset_apply_with_snapshot(&repo, &payload.to_string(), "WORKTREE").unwrap();
+        assert_eq!(result["ok"], json!(false));
+        assert!(before.is_empty());
+        assert_eq!(fs::read_to_string(repo.join("b.txt")).unwrap(), "keep\n");
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn any_lint_error_rejects_the_whole_changeset() {
+        let repo = temp_repo(&[("a.txt", "one\n")]);
+        let payload = json!({
+            "version": 1,
+            "operations": [
+                { "op": "write", "path": "new.txt", "contents": "new\n" },
+                { "op": "edit", "path": "a.txt", "changes": [{
+                    "action": "replace_block",
+                    "match": { "type": "literal", "mode": "normalized_newlines", "must_match": "exactly_one", "occurrence": 1, "text": "missing" },
+                    "replacement": "x",
+                }] },
+            ],
+        });
+
+        let result = execute_changeset_apply(&repo, &payload.to_string(), "WORKTREE").unwrap();
+        assert_eq!(result["ok"], json!(false));
+        assert_eq!(result["stats"]["successful_operations"], json!(0));
+        assert!(result["summary"].as_str().unwrap().contains("Nothing was applied"));
+        assert!(!repo.join("new.txt").exists());
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn previews_only_read_files_inside_the_repo() {
+        let repo = temp_repo(&[("a.txt", "one\n")]);
+        let outside = repo.with_extension("outside.txt");
+        fs::write(&outside, "secret\n").unwrap();
+        let preview = |path: &str| {
+            let payload = json!({
+                "version": 1,
+                "operations": [{
+                    "op": "edit",
+                    "path": path,
+                    "changes": [{
+                        "action": "insert_after",
+                        "match": { "type": "literal", "mode": "normalized_newlines", "must_match": "exactly_one", "occurrence": 1, "text": "one\n" },
+                        "text": "two\n",
+                    }],
+                }],
+            });
+            preview_changeset_edits(&repo, &payload.to_string())
+        };
+
+        assert_eq!(preview("a.txt").unwrap(), [("a.txt".to_string(), "one\ntwo\n".to_string())]);
+        assert!(preview(&format!("../{}", outside.file_name().unwrap().to_string_lossy())).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, repo.join("leak.txt")).unwrap();
+            assert!(preview("leak.txt").is_err());
+        }
+        fs::remove_dir_all(&repo).unwrap();
+        fs::remove_file(&outside).unwrap();
+    }
+
+    #[test]
+    fn operations_refuse_paths_outside_the_repo() {
+        let repo = temp_repo(&[("a.txt", "one\n")]);
+        let outside = repo.with_extension("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("victim.txt"), "keep\n").unwrap();
+        let escape = |name: &str| format!("../{}/{name}", outside.file_name().unwrap().to_string_lossy());
+        let operations = [
+            Operation::Write { path: escape("new.txt"), contents: "x".to_string() },
+            Operation::Delete { path: escape("victim.txt") },
+            Operation::Move { from: "a.txt".to_string(), to: escape("moved.txt") },
+            Operation::Move { from: escape("victim.txt"), to: "stolen.txt".to_string() },
+            Operation::Edit { path: escape("victim.txt"), changes: vec![range_replace(1, 1, "keep", "gone")] },
+        ];
+        #[cfg(unix)]
+        let linked = {
+            std::os::unix::fs::symlink(&outside, repo.join("out")).unwrap();
+            vec![
+                Operation::Write { path: "out/new.txt".to_string(), contents: "x".to_string() },
+                Operation::Delete { path: "out/victim.txt".to_string() },
+            ]
+        };
+        #[cfg(not(unix))]
+        let linked = Vec::new();
+
+        for op in operations.iter().chain(&linked) {
+            assert!(apply_operation(&repo, op).is_err(), "{op:?}");
+        }
+        assert_eq!(fs::read_to_string(outside.join("victim.txt")).unwrap(), "keep\n");
+        assert_eq!(fs::read_dir(&outside).unwrap().count(), 1);
+        assert!(repo.join("a.txt").exists() && !repo.join("stolen.txt").exists());
+        fs::remove_dir_all(&repo).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+}
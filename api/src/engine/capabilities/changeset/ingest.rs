@@ -0,0 +1,89 @@
+use serde::Serialize;
+
+use super::apply::{extract_json_object_slice, ChangeSetPayload};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangesetIngestError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangesetIngestReport {
+    pub ok: bool,
+    pub cleaned_text: String,
+    pub stripped: Vec<String>,
+    pub error: Option<ChangesetIngestError>,
+}
+
+const COPY_LABELS: &[&str] = &["json", "jsonc", "copy", "copy code", "code"];
+
+/// Cleans a changeset copied out of a chat tool (prose, fences, "json" / "Copy code" labels)
+/// and validates the remainder against the changeset schema, reporting line/column on failure.
+pub fn ingest_pasted_changeset(payload_text: &str) -> ChangesetIngestReport {
+    let mut stripped = Vec::new();
+    let mut text = payload_text.replace("\r\n", "\n").trim().to_string();
+
+    if let Some(start) = text.find(['{', '`']) {
+        if start > 0 {
+            stripped.push("leading chat text".to_string());
+            text = text[start..].to_string();
+        }
+    }
+
+    if text.starts_with("```") {
+        stripped.push("markdown code fence".to_string());
+        let body = text.split_once('\n').map(|(_, rest)| rest).unwrap_or_default();
+        text = match body.rfind("```") {
+            Some(end) => body[..end].to_string(),
+            None => body.to_string(),
+        };
+    }
+
+    let mut lines = text.trim().lines().peekable();
+    let mut labels = 0;
+    while let Some(line) = lines.peek() {
+        if !COPY_LABELS.contains(&line.trim().to_ascii_lowercase().as_str()) {
+            break;
+        }
+        lines.next();
+        labels += 1;
+    }
+    if labels > 0 {
+        stripped.push("language / copy labels".to_string());
+    }
+    text = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+
+    if let Some(slice) = extract_json_object_slice(&text) {
+        if slice.len() != text.len() {
+            stripped.push("text outside the JSON object".to_string());
+            text = slice.to_string();
+        }
+    }
+
+    let error = if text.is_empty() {
+        Some(ChangesetIngestError { message: "clipboard did not contain a changeset".to_string(), line: 0, column: 0 })
+    } else {
+        match serde_json::from_str::<ChangeSetPayload>(&text) {
+            Ok(payload) if payload.version != 1 => Some(ChangesetIngestError {
+                message: format!("unsupported changeset version {} (expected 1)", payload.version),
+                line: 1,
+                column: 1,
+            }),
+            Ok(_) => None,
+            Err(err) => Some(ChangesetIngestError {
+                message: err.to_string(),
+                line: err.line(),
+                column: err.column(),
+            }),
+        }
+    };
+
+    ChangesetIngestReport {
+        ok: error.is_none(),
+        cleaned_text: text,
+        stripped,
+        error,
+    }
+}
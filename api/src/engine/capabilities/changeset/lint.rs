@@ -0,0 +1,352 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::apply::{apply_edit_change, edit_order, normalize_changeset_payload_text, ChangeSetPayload, EditAction, Operation};
+use crate::engine::capabilities::filesystem::resolve_workspace_path;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LintFinding {
+    pub severity: LintSeverity,
+    pub code: &'static str,
+    pub operation_index: usize,
+    pub action_index: Option<usize>,
+    pub path: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ChangesetLintReport {
+    pub ok: bool,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub findings: Vec<LintFinding>,
+}
+
+impl ChangesetLintReport {
+    fn push(
+        &mut self,
+        severity: LintSeverity,
+        code: &'static str,
+        operation_index: usize,
+        action_index: Option<usize>,
+        path: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        match severity {
+            LintSeverity::Error => self.error_count += 1,
+            LintSeverity::Warning => self.warning_count += 1,
+        }
+        self.findings.push(LintFinding {
+            severity,
+            code,
+            operation_index,
+            action_index,
+            path: path.map(ToOwned::to_owned),
+            message: message.into(),
+        });
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.findings
+            .iter()
+            .map(|finding| {
+                let location = match finding.action_index {
+                    Some(action) => format!("[{}] edit[{}]", finding.operation_index, action),
+                    None => format!("[{}]", finding.operation_index),
+                };
+                let severity = match finding.severity {
+                    LintSeverity::Error => "LINT ERROR",
+                    LintSeverity::Warning => "LINT WARNING",
+                };
+                format!("{} {} {}: {}", location, severity, finding.code, finding.message)
+            })
+            .collect()
+    }
+}
+
+pub fn lint_changeset_text(repo: &Path, payload_text: &str) -> Result<ChangesetLintReport> {
+    let normalized = normalize_changeset_payload_text(payload_text)?;
+    let payload: ChangeSetPayload = serde_json::from_str(&normalized)
+        .context("failed to decode normalized changeset")?;
+    Ok(lint_changeset(repo, &payload))
+}
+
+fn path_escape_reason(path: &str) -> Option<&'static str> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Some("path is empty");
+    }
+    let candidate = Path::new(trimmed);
+    if candidate.is_absolute() || trimmed.starts_with('/') || trimmed.starts_with('\\') || trimmed.chars().nth(1) == Some(':') {
+        return Some("path must be relative to the repository root");
+    }
+
+    let mut depth = 0i32;
+    for component in candidate.components() {
+        match component {
+            Component::ParentDir => {
+                depth -= 1;
+                if depth < 0 {
+                    return Some("path escapes the repository root");
+                }
+            }
+            Component::Normal(part) => {
+                if depth == 0 && part == ".git" {
+                    return Some("path targets the .git directory");
+                }
+                depth += 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn operation_paths(op: &Operation) -> Vec<&str> {
+    match op {
+        Operation::Write { path, .. } | Operation::Delete { path } | Operation::Edit { path, .. } => vec![path.as_str()],
+        Operation::Move { from, to } => vec![from.as_str(), to.as_str()],
+    }
+}
+
+fn resolved(repo: &Path, path: &str) -> Option<PathBuf> {
+    resolve_workspace_path(&repo.to_string_lossy(), path.trim()).ok()
+}
+
+fn current_contents(contents: &mut HashMap<String, Option<String>>, repo: &Path, path: &str) -> Option<String> {
+    contents
+        .entry(path.to_string())
+        .or_insert_with(|| resolved(repo, path).and_then(|full| fs::read_to_string(full).ok()))
+        .clone()
+}
+
+fn lint_edit_action(report: &mut ChangesetLintReport, index: usize, action_index: usize, path: &str, change: &EditAction) {
+    match (change.match_spec.as_ref(), change.range.as_ref()) {
+        (Some(_), Some(_)) => report.push(
+            LintSeverity::Warning,
+            "match_and_range",
+            index,
+            Some(action_index),
+            Some(path),
+            "both match and range are set; range takes precedence",
+        ),
+        (Some(spec), None) if spec.text.is_empty() => {
+            report.push(LintSeverity::Error, "empty_match", index, Some(action_index), Some(path), "match text is empty")
+        }
+        (None, None) => report.push(LintSeverity::Error, "missing_match", index, Some(action_index), Some(path), "edit needs either match or range"),
+        _ => {}
+    }
+    match change.action.as_str() {
+        "replace_block" => match change.replacement.as_deref() {
+            None => report.push(LintSeverity::Error, "missing_replacement", index, Some(action_index), Some(path), "replace_block has no replacement"),
+            Some("") => report.push(
+                LintSeverity::Warning,
+                "empty_replacement",
+                index,
+                Some(action_index),
+                Some(path),
+                "replacement is empty; use delete_block if removing the match is intended",
+            ),
+            Some(_) => {}
+        },
+        "insert_before" | "insert_after" => match change.text.as_deref() {
+            None => report.push(LintSeverity::Error, "missing_text", index, Some(action_index), Some(path), format!("{} has no text", change.action)),
+            Some("") => report.push(LintSeverity::Warning, "empty_insert", index, Some(action_index), Some(path), "inserted text is empty"),
+            Some(_) => {}
+        },
+        "delete_block" => {}
+        other => report.push(LintSeverity::Error, "unknown_action", index, Some(action_index), Some(path), format!("unsupported edit action {}", other)),
+    }
+}
+
+/// Checks a parsed ChangeSet against the repository without writing anything.
+/// Edits are simulated in order so later operations see earlier writes.
+pub(super) fn lint_changeset(repo: &Path, payload: &ChangeSetPayload) -> ChangesetLintReport {
+    let mut report = ChangesetLintReport::default();
+    let mut touched: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut contents: HashMap<String, Option<String>> = HashMap::new();
+
+    if payload.operations.is_empty() {
+        report.push(LintSeverity::Warning, "no_operations", 0, None, None, "changeset contains no operations");
+    }
+
+    for (idx, op) in payload.operations.iter().enumerate() {
+        let index = idx + 1;
+
+        let mut escaped = false;
+        for path in operation_paths(op) {
+            if let Some(reason) = path_escape_reason(path) {
+                report.push(LintSeverity::Error, "path_outside_repo", index, None, Some(path), reason);
+                escaped = true;
+            } else if let Err(err) = resolve_workspace_path(&repo.to_string_lossy(), path.trim()) {
+                report.push(LintSeverity::Error, "path_outside_repo", index, None, Some(path), format!("{:#}", err));
+                escaped = true;
+            } else {
+                touched.entry(path.trim().to_string()).or_default().push(index);
+            }
+        }
+        if escaped {
+            continue;
+        }
+
+        match op {
+            Operation::Write { path, contents: body } => {
+                if body.is_empty() {
+                    report.push(LintSeverity::Warning, "empty_write", index, None, Some(path), "write leaves the file empty");
+                }
+                contents.insert(path.clone(), Some(body.clone()));
+            }
+            Operation::Delete { path } => {
+                if current_contents(&mut contents, repo, path).is_none() && !resolved(repo, path).is_some_and(|full| full.exists()) {
+                    report.push(LintSeverity::Warning, "missing_target", index, None, Some(path), "delete target does not exist");
+                }
+                contents.insert(path.clone(), None);
+            }
+            Operation::Move { from, to } => {
+                let source = current_contents(&mut contents, repo, from);
+                if source.is_none() && !resolved(repo, from).is_some_and(|full| full.exists()) {
+                    report.push(LintSeverity::Error, "missing_target", index, None, Some(from), "move source does not exist");
+                }
+                contents.insert(from.clone(), None);
+                contents.insert(to.clone(), source);
+            }
+            Operation::Edit { path, changes } => {
+                if changes.is_empty() {
+                    report.push(LintSeverity::Error, "empty_edit", index, None, Some(path), "edit has no changes");
+                    continue;
+                }
+                let Some(mut text) = current_contents(&mut contents, repo, path) else {
+                    report.push(LintSeverity::Error, "missing_target", index, None, Some(path), "edit target does not exist");
+                    continue;
+                };
+                for (change_idx, change) in changes.iter().enumerate() {
+                    lint_edit_action(&mut report, index, change_idx + 1, path, change);
+                }
+                for (change_idx, conflict) in edit_order(changes) {
+                    let change = &changes[change_idx];
+                    match conflict.map_or_else(|| apply_edit_change(&text, change), Err) {
+                        Ok(next) => text = next,
+                        Err(err) => report.push(
+                            LintSeverity::Error,
+                            "match_not_found",
+                            index,
+                            Some(change_idx + 1),
+                            Some(path),
+                            format!("{:#}", err),
+                        ),
+                    }
+                }
+                contents.insert(path.clone(), Some(text));
+            }
+        }
+    }
+
+    for (path, indices) in touched {
+        if indices.len() > 1 {
+            let list = indices.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", ");
+            report.push(
+                LintSeverity::Warning,
+                "duplicate_path",
+                *indices.last().unwrap_or(&0),
+                None,
+                Some(&path),
+                format!("path is targeted by operations {}", list),
+            );
+        }
+    }
+
+    report.ok = report.error_count == 0;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lint(repo: &Path, operations: serde_json::Value) -> ChangesetLintReport {
+        lint_changeset_text(repo, &json!({ "version": 1, "operations": operations }).to_string()).unwrap()
+    }
+
+    fn codes(report: &ChangesetLintReport, severity: LintSeverity) -> Vec<&'static str> {
+        report.findings.iter().filter(|finding| finding.severity == severity).map(|finding| finding.code).collect()
+    }
+
+    fn temp_repo() -> PathBuf {
+        let repo = std::env::temp_dir().join(format!("changeset-lint-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("a.txt"), "alpha\nbeta\n").unwrap();
+        repo
+    }
+
+    #[test]
+    fn flags_paths_outside_the_repo() {
+        let repo = temp_repo();
+        let report = lint(&repo, json!([
+            { "op": "write", "path": "../escape.txt", "contents": "x" },
+            { "op": "delete", "path": "/etc/passwd" },
+            { "op": "write", "path": ".git/config", "contents": "x" },
+            { "op": "write", "path": "./src/ok.txt", "contents": "x" },
+        ]));
+        assert_eq!(codes(&report, LintSeverity::Error), ["path_outside_repo"; 3]);
+        assert!(!report.ok);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn flags_paths_under_a_linked_directory() {
+        let repo = temp_repo();
+        std::os::unix::fs::symlink(std::env::temp_dir(), repo.join("tmp")).unwrap();
+        let report = lint(&repo, json!([{ "op": "write", "path": "tmp/planted.txt", "contents": "x" }]));
+        assert_eq!(codes(&report, LintSeverity::Error), ["path_outside_repo"]);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn simulates_operations_in_order() {
+        let repo = temp_repo();
+        let report = lint(&repo, json!([
+            { "op": "write", "path": "new.txt", "contents": "fresh\n" },
+            { "op": "edit", "path": "new.txt", "changes": [{
+                "action": "replace_block",
+                "match": { "type": "literal", "mode": "normalized_newlines", "must_match": "exactly_one", "occurrence": 1, "text": "fresh" },
+                "replacement": "edited",
+            }] },
+            { "op": "move", "from": "a.txt", "to": "b.txt" },
+            { "op": "edit", "path": "a.txt", "changes": [{ "action": "delete_block", "range": { "start_line": 1, "end_line": 1, "expected_text": "alpha" } }] },
+        ]));
+        assert_eq!(codes(&report, LintSeverity::Error), ["missing_target"]);
+        assert_eq!(report.findings[0].operation_index, 4);
+        assert_eq!(codes(&report, LintSeverity::Warning), ["duplicate_path", "duplicate_path"]);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn checks_edit_actions() {
+        let repo = temp_repo();
+        let report = lint(&repo, json!([{ "op": "edit", "path": "a.txt", "changes": [
+            { "action": "replace_block", "range": { "start_line": 2, "end_line": 2, "expected_text": "beta" },
+              "match": { "type": "literal", "mode": "normalized_newlines", "must_match": "exactly_one", "occurrence": 1, "text": "beta" },
+              "replacement": "BETA" },
+            { "action": "insert_after", "match": { "type": "literal", "mode": "normalized_newlines", "must_match": "exactly_one", "occurrence": 1, "text": "gamma" }, "text": "x" },
+            { "action": "rename_block", "range": { "start_line": 1, "end_line": 1, "expected_text": "alpha" } },
+        ] }]));
+        assert_eq!(codes(&report, LintSeverity::Warning), ["match_and_range"]);
+        assert_eq!(codes(&report, LintSeverity::Error), ["unknown_action", "match_not_found", "match_not_found"]);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -1,15 +1,23 @@
-use std::{path::PathBuf, time::Instant};
+use std::{path::{Path, PathBuf}, time::Instant};
 
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use serde::Serialize;
 use serde_json::{json, Value};
 use sqlx::SqlitePool;
 
 pub mod apply;
+pub mod ingest;
+pub mod lint;
 pub mod persistence;
 pub mod schema;
 
 pub use persistence::ChangesetAttemptRecord;
+use crate::engine::{
+    capabilities::{
+        git::{git::run_git_allow_fail, index_lock::run_git_index_op_allow_fail},
+    },
+    history::{self, FileSnapshot},
+};
 use persistence::{insert_changeset_attempt_from_result, row_to_summary, ChangesetAttemptContext};
 
 #[derive(Debug, Clone)]
@@ -21,6 +29,9 @@ pub struct ChangesetRequest {
     pub workflow_key: Option<String>,
     pub run_id: Option<String>,
     pub step_id: Option<String>,
+    pub stage_after_apply: bool,
+    pub commit_after_apply: bool,
+    pub commit_message: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -59,14 +70,18 @@ pub async fn apply_changeset(db: &SqlitePool, request: ChangesetRequest) -> Resu
         bail!("payload_text is required");
     }
 
+    let repo = PathBuf::from(&request.repo_ref);
+
     let started = Instant::now();
-    let result = match apply::execute_changeset_apply(
-        PathBuf::from(&request.repo_ref).as_path(),
-        request.payload_text.as_str(),
-        request.git_ref.as_str(),
-    ) {
-        Ok(result) => result,
-        Err(err) => json!({
+    let applied = {
+        let (repo, payload_text, git_ref) = (repo.clone(), request.payload_text.clone(), request.git_ref.clone());
+        tokio::task::spawn_blocking(move || apply::execute_changeset_apply_with_snapshot(&repo, &payload_text, &git_ref))
+            .await
+            .unwrap_or_else(|err| Err(anyhow!("changeset apply task failed: {err}")))
+    };
+    let (result, before) = match applied {
+        Ok(applied) => applied,
+        Err(err) => (json!({
             "ok": false,
             "mode": "changeset_apply",
             "summary": format!("ChangeSet apply failed: {:#}", err),
@@ -90,10 +105,27 @@ pub async fn apply_changeset(db: &SqlitePool, request: ChangesetRequest) -> Resu
                 "failed_files": 0
             },
             "touched_files": []
-        }),
+        }), Vec::new()),
     };
 
     let mut result = result;
+    let applied = result.get("ok").and_then(Value::as_bool).unwrap_or(false);
+    record_removed_files(db, &request, &repo, before).await;
+    if request.stage_after_apply || request.commit_after_apply {
+        let git = if applied {
+            // git add/commit back off while the index is locked; keep that sleep off the async workers.
+            let (request, result) = (request.clone(), result.clone());
+            tokio::task::spawn_blocking(move || stage_and_commit_applied_paths(&request, &result))
+                .await
+                .unwrap_or_else(|err| json!({ "error": format!("staging task failed: {err}") }))
+        } else {
+            json!({ "skipped": "ChangeSet did not apply cleanly; nothing was staged." })
+        };
+        if let Some(obj) = result.as_object_mut() {
+            obj.insert("git".to_string(), git);
+        }
+    }
+
     if let Some(obj) = result.as_object_mut() {
         obj.insert("target".to_string(), json!({
             "repo_ref": request.repo_ref.as_str(),
@@ -127,6 +159,95 @@ pub async fn apply_changeset(db: &SqlitePool, request: ChangesetRequest) -> Resu
     Ok(result)
 }
 
+/// Keeps an undo snapshot when the apply removed files that existed beforehand, so deletes and
+/// moves made through a changeset can be reverted from the app history.
+async fn record_removed_files(db: &SqlitePool, request: &ChangesetRequest, repo: &Path, before: Vec<FileSnapshot>) {
+    let removed = before.iter().filter(|file| file.existed && !repo.join(&file.path).exists()).count();
+    if removed == 0 {
+        return;
+    }
+    let label = format!("ChangeSet removed {} file{}", removed, if removed == 1 { "" } else { "s" });
+    if let Err(err) = history::record_files(db, "changeset", &label, &request.repo_ref, before).await {
+        tracing::warn!(error = %err, "failed to record changeset undo snapshot");
+    }
+}
+
+/// Paths a changeset payload writes, edits, deletes or moves, in operation order.
+pub fn changeset_touched_paths(payload_text: &str) -> Result<Vec<String>> {
+    let normalized = apply::normalize_changeset_payload_text(payload_text)?;
+    Ok(apply::payload_paths_and_description(&normalized)?.0)
+}
+
+/// Stages the paths a clean apply touched and optionally commits exactly those
+/// paths, using the payload description when no message is given.
+fn stage_and_commit_applied_paths(request: &ChangesetRequest, result: &Value) -> Value {
+    let repo = PathBuf::from(&request.repo_ref);
+    let normalized = result.get("normalized_payload").and_then(Value::as_str).unwrap_or("");
+    let (paths, description) = match apply::payload_paths_and_description(normalized) {
+        Ok(found) => found,
+        Err(err) => return json!({ "error": format!("{:#}", err) }),
+    };
+
+    let paths: Vec<String> = paths
+        .into_iter()
+        .filter(|path| {
+            repo.join(path).exists()
+                || run_git_allow_fail(&repo, &["ls-files", "--", path])
+                    .map(|(_, stdout, _)| !stdout.is_empty())
+                    .unwrap_or(false)
+        })
+        .collect();
+
+    if paths.is_empty() {
+        return json!({ "staged": [], "stage_output": "No paths to stage." });
+    }
+
+    let mut add_args = vec!["add", "-A", "--"];
+    add_args.extend(paths.iter().map(String::as_str));
+    let (code, stdout, stderr) = match run_git_index_op_allow_fail(&repo, &add_args) {
+        Ok(out) => out,
+        Err(err) => return json!({ "error": format!("{:#}", err) }),
+    };
+    let stage_output = format!("{}{}", String::from_utf8_lossy(&stdout), String::from_utf8_lossy(&stderr));
+    if code != 0 {
+        return json!({ "staged": [], "stage_output": stage_output, "error": "git add failed" });
+    }
+
+    let mut git = json!({ "staged": paths, "stage_output": stage_output });
+    if !request.commit_after_apply {
+        return git;
+    }
+
+    let message = request
+        .commit_message
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(ToOwned::to_owned)
+        .or_else(|| Some(description.trim().to_string()).filter(|d| !d.is_empty()))
+        .unwrap_or_else(|| "Apply ChangeSet".to_string());
+
+    let mut commit_args = vec!["commit", "-m", message.as_str(), "--"];
+    commit_args.extend(paths.iter().map(String::as_str));
+    let commit = match run_git_index_op_allow_fail(&repo, &commit_args) {
+        Ok((code, stdout, stderr)) => {
+            let sha = (code == 0)
+                .then(|| run_git_allow_fail(&repo, &["rev-parse", "HEAD"]).ok())
+                .flatten()
+                .map(|(_, out, _)| String::from_utf8_lossy(&out).trim().to_string());
+            json!({
+                "ok": code == 0,
+                "sha": sha,
+                "message": message,
+                "output": format!("{}{}", String::from_utf8_lossy(&stdout), String::from_utf8_lossy(&stderr)),
+            })
+        }
+        Err(err) => json!({ "ok": false, "message": message, "output": format!("{:#}", err) }),
+    };
+    git["commit"] = commit;
+    git
+}
+
 pub async fn list_changesets(
     db: &SqlitePool,
     workflow_key: &str,
@@ -43,6 +43,15 @@ pub const CHANGESET_SCHEMA_EXAMPLE: &str = r#"{
             \"text\": \"egui::ScrollArea::vertical().id_source(\\\"example_scroll_id\\\")\"
           },
           \"text\": \"\\n                .id_source(\\\"example_scroll_id\\\")\"
+        },
+        {
+          \"action\": \"replace_block\",
+          \"range\": {
+            \"start_line\": 12,
+            \"end_line\": 13,
+            \"expected_text\": \"let a = 1;\\nlet b = 2;\"
+          },
+          \"replacement\": \"let a = 10;\\nlet b = 20;\"
         }
       ]
     },
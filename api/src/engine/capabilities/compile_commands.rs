@@ -1,17 +1,72 @@
-use std::{path::Path, path::PathBuf, process::Command};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant},
+};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde_json::{json, Value};
+use sqlx::SqlitePool;
 
 use super::registry::{CapabilityContext, CapabilityInvocationRequest, CapabilityResult};
+use crate::{db::load_app_settings_section, engine::processes};
+
+/// Shells a terminal pane may ask for instead of the platform default.
+#[cfg(target_os = "windows")]
+const SHELLS: &[&str] = &["cmd", "powershell", "pwsh"];
+#[cfg(not(target_os = "windows"))]
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "fish"];
+
+#[derive(Debug, Default)]
+struct EnvProfile {
+    name: Option<String>,
+    env: Vec<(String, String)>,
+    working_dir: Option<PathBuf>,
+}
+
+/// Per-run choices from `capabilities.compile_commands` (or the invocation config): commands to
+/// leave out, and how long each command may run before its process group is killed.
+#[derive(Debug, Default)]
+struct RunOptions {
+    skip: Vec<String>,
+    timeout: Option<Duration>,
+}
+
+impl RunOptions {
+    fn resolve(config: &Value, local_state: &Value) -> Self {
+        let saved = local_state.get("capabilities").and_then(|v| v.get("compile_commands"));
+        let setting = |key: &str| config.get(key).or_else(|| saved.and_then(|v| v.get(key)));
+        Self {
+            skip: setting("skip")
+                .and_then(Value::as_array)
+                .map(|rows| rows.iter().filter_map(Value::as_str).map(|command| command.trim().to_string()).collect())
+                .unwrap_or_default(),
+            timeout: setting("timeout_secs").and_then(Value::as_u64).filter(|secs| *secs > 0).map(Duration::from_secs),
+        }
+    }
+
+    fn skips(&self, command: &str, label: &str) -> bool {
+        self.skip.iter().any(|skipped| skipped == command || skipped == label)
+    }
+}
 
 pub async fn execute(
     ctx: &CapabilityContext<'_>,
     _prior_results: &[CapabilityResult],
     config: Value,
 ) -> Result<CapabilityResult> {
+    let requested_profile = config
+        .get("env_profile")
+        .or_else(|| {
+            ctx.local_state
+                .get("execution")
+                .and_then(|v| v.get("compile_checks"))
+                .and_then(|v| v.get("env_profile"))
+        })
+        .and_then(Value::as_str)
+        .map(str::to_string);
     let commands = resolve_compile_commands(
-        config,
+        config.clone(),
         ctx.local_state,
         ctx.step.execution.compile_checks.clone(),
         ctx.step.execution_logic.clone(),
@@ -31,11 +86,36 @@ pub async fn execute(
         .and_then(Value::as_str)
         .unwrap_or(ctx.repo_ref);
 
-    let result = execute_terminal_command(
-        PathBuf::from(repo_ref).as_path(),
-        commands,
-        execution_mode,
-    )?;
+    let terminal_settings = load_app_settings_section(&ctx.state.db, "terminal")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| json!({}));
+    let mut profile = resolve_env_profile(&terminal_settings, repo_ref, requested_profile.as_deref());
+    // Terminal panes pick their own directory and shell, on top of the env profile.
+    if let Some(dir) = config.get("working_dir").and_then(Value::as_str).map(str::trim).filter(|dir| !dir.is_empty()) {
+        profile.working_dir = Some(PathBuf::from(dir));
+    }
+    let shell = config.get("shell").and_then(Value::as_str).map(str::trim).filter(|shell| !shell.is_empty()).map(str::to_string);
+    let options = RunOptions::resolve(&config, ctx.local_state);
+
+    if config.get("simulate").and_then(Value::as_bool).unwrap_or(false) {
+        let plan = simulate_terminal_command(Path::new(repo_ref), commands, &profile, shell.as_deref(), &options)?;
+        return Ok(CapabilityResult {
+            ok: true,
+            capability: "compile_commands".to_string(),
+            payload: plan,
+            follow_ups: CapabilityInvocationRequest::None,
+        });
+    }
+
+    // Off the async workers, so a long command can still be listed and killed from the process panel.
+    let repo = PathBuf::from(repo_ref);
+    let execution_mode = execution_mode.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        execute_terminal_command(&repo, commands, &execution_mode, &profile, shell.as_deref(), &options)
+    })
+    .await??;
 
     Ok(CapabilityResult {
         ok: result.get("ok").and_then(Value::as_bool).unwrap_or(false),
@@ -45,6 +125,26 @@ pub async fn execute(
     })
 }
 
+/// Runs one command the way a terminal pane would: the repo's active env profile, with the pane's
+/// own directory and shell on top.
+pub(crate) async fn run_pane_command(
+    db: &SqlitePool,
+    repo_ref: &str,
+    command: &str,
+    working_dir: Option<&str>,
+    shell: Option<&str>,
+) -> Result<Value> {
+    let terminal_settings = load_app_settings_section(db, "terminal").await.ok().flatten().unwrap_or_else(|| json!({}));
+    let mut profile = resolve_env_profile(&terminal_settings, repo_ref, None);
+    if let Some(dir) = working_dir.map(str::trim).filter(|dir| !dir.is_empty()) {
+        profile.working_dir = Some(PathBuf::from(dir));
+    }
+    let repo = PathBuf::from(repo_ref);
+    let commands = json!([command]);
+    let shell = shell.map(str::trim).filter(|shell| !shell.is_empty()).map(str::to_string);
+    tokio::task::spawn_blocking(move || execute_terminal_command(&repo, commands, "manual", &profile, shell.as_deref(), &RunOptions::default())).await?
+}
+
 fn resolve_compile_commands(config: Value, local_state: &Value, step_compile_checks: Value, execution_logic: Value) -> Value {
     non_empty_commands(config.get("commands").cloned())
         .or_else(|| commands_text_to_rows(config.get("commands_text")))
@@ -98,6 +198,46 @@ fn resolve_compile_commands(config: Value, local_state: &Value, step_compile_che
         .unwrap_or_else(|| json!([]))
 }
 
+fn resolve_env_profile(terminal_settings: &Value, repo_ref: &str, requested: Option<&str>) -> EnvProfile {
+    let repo_settings = terminal_settings.get("by_repo").and_then(|v| v.get(repo_ref));
+    let name = requested
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .or_else(|| repo_settings.and_then(|v| v.get("active_profile")).and_then(Value::as_str));
+    let Some(name) = name else {
+        return EnvProfile::default();
+    };
+    let Some(profile) = repo_settings.and_then(|v| v.get("profiles")).and_then(|v| v.get(name)) else {
+        return EnvProfile::default();
+    };
+
+    let env = profile
+        .get("env")
+        .and_then(Value::as_object)
+        .map(|vars| {
+            vars.iter()
+                .filter(|(key, _)| !key.trim().is_empty())
+                .map(|(key, value)| {
+                    let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                    (key.trim().to_string(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let working_dir = profile
+        .get("working_dir")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+
+    EnvProfile {
+        name: Some(name.to_string()),
+        env,
+        working_dir,
+    }
+}
+
 fn non_empty_commands(commands: Option<Value>) -> Option<Value> {
     match commands {
         Some(Value::Array(rows)) if !rows.is_empty() => Some(Value::Array(rows)),
@@ -126,55 +266,60 @@ fn commands_text_to_rows(value: Option<&Value>) -> Option<Value> {
     }
 }
 
-fn execute_terminal_command(repo: &Path, commands: Value, execution_mode: &str) -> Result<Value> {
-    let rows = commands.as_array().cloned().unwrap_or_default();
+fn execute_terminal_command(
+    repo: &Path,
+    commands: Value,
+    execution_mode: &str,
+    profile: &EnvProfile,
+    shell: Option<&str>,
+    options: &RunOptions,
+) -> Result<Value> {
+    validate_shell(shell)?;
+    let cwd = resolve_cwd(repo, profile);
+    if !cwd.is_dir() {
+        bail!("working directory {} does not exist", cwd.display());
+    }
     let mut results = Vec::new();
     let mut ok = true;
     let mut executed_any = false;
 
-    for item in rows {
-        let (command, label) = match item {
-            Value::String(command) => {
-                let trimmed = command.trim().to_string();
-                (trimmed.clone(), trimmed)
-            }
-            Value::Object(obj) => {
-                let command = obj
-                    .get("command")
-                    .and_then(Value::as_str)
-                    .unwrap_or("")
-                    .trim()
-                    .to_string();
-                let label = obj
-                    .get("label")
-                    .and_then(Value::as_str)
-                    .unwrap_or(command.as_str())
-                    .trim()
-                    .to_string();
-                (command, label)
-            }
-            _ => (String::new(), String::new()),
-        };
-
-        if command.is_empty() {
+    for (command, label) in command_rows(&commands) {
+        executed_any = true;
+        if options.skips(&command, &label) {
+            results.push(json!({
+                "label": label,
+                "command": command,
+                "status": null,
+                "skipped": true,
+                "duration_ms": 0,
+                "stdout": "",
+                "stderr": "",
+            }));
             continue;
         }
 
-        executed_any = true;
-        let output = shell_command(repo, &command)
+        let started = Instant::now();
+        let (output, timed_out) = shell_command(&cwd, &label, &command, &profile.env, shell, options.timeout)
             .with_context(|| format!("failed to run compile command '{}'", command))?;
 
-        let status = output.status.code().unwrap_or(-1);
+        let status = if timed_out { -1 } else { output.status.code().unwrap_or(-1) };
         if status != 0 {
             ok = false;
         }
+        let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        if timed_out {
+            let secs = options.timeout.map(|timeout| timeout.as_secs()).unwrap_or_default();
+            stderr.push_str(&format!("\n[killed after the {secs}s timeout]\n"));
+        }
 
         results.push(json!({
             "label": label,
             "command": command,
             "status": status,
+            "duration_ms": started.elapsed().as_millis() as u64,
+            "timed_out": timed_out,
             "stdout": String::from_utf8_lossy(&output.stdout).to_string(),
-            "stderr": String::from_utf8_lossy(&output.stderr).to_string(),
+            "stderr": stderr,
         }));
     }
 
@@ -190,28 +335,141 @@ fn execute_terminal_command(repo: &Path, commands: Value, execution_mode: &str)
             },
             "no_commands_configured": true,
             "skipped": automatic,
+            "env_profile": profile.name,
         }));
     }
 
     Ok(json!({
         "ok": ok,
         "results": results,
+        "env_profile": profile.name,
+        "working_dir": cwd.to_string_lossy(),
+        "shell": shell,
+    }))
+}
+
+/// Describes what the commands would run with, without running anything: the shell binary and its
+/// arguments, the working directory, the profile's environment on top of the app's own, and which
+/// commands are skipped.
+fn simulate_terminal_command(
+    repo: &Path,
+    commands: Value,
+    profile: &EnvProfile,
+    shell: Option<&str>,
+    options: &RunOptions,
+) -> Result<Value> {
+    validate_shell(shell)?;
+    let cwd = resolve_cwd(repo, profile);
+    let (program, args) = shell_invocation(shell);
+    let rows = command_rows(&commands)
+        .into_iter()
+        .map(|(command, label)| json!({ "label": label, "command": command, "skipped": options.skips(&command, &label) }))
+        .collect::<Vec<_>>();
+    let env = profile
+        .env
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": display_env_value(key, value) }))
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "simulated": true,
+        "ok": true,
+        "commands": rows,
+        "shell": program,
+        "shell_path": find_on_path(program).map(|path| path.to_string_lossy().to_string()),
+        "shell_args": args,
+        "working_dir": cwd.to_string_lossy(),
+        "working_dir_exists": cwd.is_dir(),
+        "env_profile": profile.name,
+        "env": env,
+        "timeout_secs": options.timeout.map(|timeout| timeout.as_secs()),
     }))
 }
 
-fn shell_command(repo: &Path, command: &str) -> Result<std::process::Output> {
+fn validate_shell(shell: Option<&str>) -> Result<()> {
+    if let Some(shell) = shell {
+        if !SHELLS.contains(&shell) {
+            bail!("unsupported shell '{shell}'; expected one of {}", SHELLS.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn resolve_cwd(repo: &Path, profile: &EnvProfile) -> PathBuf {
+    match &profile.working_dir {
+        Some(dir) if dir.is_absolute() => dir.clone(),
+        Some(dir) => repo.join(dir),
+        None => repo.to_path_buf(),
+    }
+}
+
+/// (command, label) for every non-empty row; rows are plain strings or `{command, label}` objects.
+fn command_rows(commands: &Value) -> Vec<(String, String)> {
+    commands
+        .as_array()
+        .map(|rows| rows.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| {
+            let (command, label) = match item {
+                Value::String(command) => (command.trim().to_string(), command.trim().to_string()),
+                Value::Object(obj) => {
+                    let command = obj.get("command").and_then(Value::as_str).unwrap_or("").trim().to_string();
+                    let label = obj.get("label").and_then(Value::as_str).unwrap_or(command.as_str()).trim().to_string();
+                    (command, label)
+                }
+                _ => return None,
+            };
+            (!command.is_empty()).then_some((command, label))
+        })
+        .collect()
+}
+
+/// Env profile values whose names look like credentials are masked in the simulation.
+fn display_env_value(key: &str, value: &str) -> String {
+    let key = key.to_ascii_uppercase();
+    if ["TOKEN", "SECRET", "PASSWORD", "PASSWD", "API_KEY", "PRIVATE_KEY", "CREDENTIAL"].iter().any(|marker| key.contains(marker)) {
+        "••••••".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn find_on_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    let names: Vec<String> = if cfg!(target_os = "windows") {
+        vec![format!("{program}.exe"), program.to_string()]
+    } else {
+        vec![program.to_string()]
+    };
+    std::env::split_paths(&path).flat_map(|dir| names.iter().map(move |name| dir.join(name))).find(|candidate| candidate.is_file())
+}
+
+/// The shell program and the arguments placed before the command.
+fn shell_invocation(shell: Option<&str>) -> (&str, &'static [&'static str]) {
     #[cfg(target_os = "windows")]
     {
-        Ok(Command::new("cmd")
-            .args(["/C", command])
-            .current_dir(repo)
-            .output()?)
+        match shell.unwrap_or("cmd") {
+            program @ ("powershell" | "pwsh") => (program, &["-NoProfile", "-Command"]),
+            program => (program, &["/C"]),
+        }
     }
     #[cfg(not(target_os = "windows"))]
     {
-        Ok(Command::new("sh")
-            .args(["-lc", command])
-            .current_dir(repo)
-            .output()?)
+        (shell.unwrap_or("sh"), &["-lc"])
     }
 }
+
+fn shell_command(
+    cwd: &Path,
+    label: &str,
+    command: &str,
+    env: &[(String, String)],
+    shell: Option<&str>,
+    timeout: Option<Duration>,
+) -> Result<(std::process::Output, bool)> {
+    let (program, args) = shell_invocation(shell);
+    let mut cmd = Command::new(program);
+    cmd.args(args).arg(command).current_dir(cwd).envs(env.iter().map(|(key, value)| (key, value)));
+    processes::run_tracked_with_timeout("terminal", label, cmd, timeout)
+}
@@ -1,11 +1,38 @@
-use std::{fs, path::{Path, PathBuf}, process::Command};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use anyhow::{bail, Context, Result};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use uuid::Uuid;
 
-use super::registry::{CapabilityContext, CapabilityInvocationRequest, CapabilityResult};
+use super::{
+    analysis::outline::{comment_prefix, extract_symbols, signature_skeleton},
+    context_injection::{
+        load_context_export_state, plan_injection, recent_inference_text, record_injection, resolve_strategy, InjectionMode, InjectionPlan,
+        LastInjection,
+    },
+    filesystem::{normalize_rel_path, repo_lock, resolve_workspace_path},
+    git::git::{effective_read_ref, git_command},
+    registry::{CapabilityContext, CapabilityInvocationRequest, CapabilityResult},
+};
+
+/// Archive folder for the manifest and diffs, kept apart from the exported repo files.
+const ZIP_META_DIR: &str = "_mdev";
+/// A finished export nobody has fetched yet is kept this long for a client that polls late.
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(30 * 60);
+const MAX_FINISHED_JOBS: usize = 20;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ContextExportPayload {
@@ -24,20 +51,151 @@ pub struct ContextExportPayload {
     pub skip_gitignore: bool,
     #[serde(default)]
     pub exclude_regex: Vec<String>,
+    /// May contain `{repo}`, `{ref}`, `{timestamp}` and `{date}`; a directory gets [`DEFAULT_FILENAME_TEMPLATE`].
     #[serde(default)]
     pub save_path: String,
+    #[serde(default)]
+    pub if_exists: ContextExportIfExists,
+    #[serde(default)]
+    pub format: ContextExportFormat,
+    /// Per-file symbol selections; listed files are reduced to their header plus these symbols.
+    #[serde(default)]
+    pub symbols: HashMap<String, Vec<String>>,
+    /// Text files larger than this are cut down; `None` or 0 exports files whole.
+    #[serde(default)]
+    pub max_bytes_per_file: Option<usize>,
+    /// Before falling back to head and tail, try keeping imports and signatures with bodies elided.
+    #[serde(default)]
+    pub keep_signatures: bool,
+    /// Per-path overrides of `max_bytes_per_file`, keyed by git ref and then by file or directory path.
+    #[serde(default)]
+    pub file_budgets_by_ref: HashMap<String, HashMap<String, FileBudget>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FileBudget {
+    /// Export the file whole regardless of any cap.
+    #[serde(default)]
+    pub full: bool,
+    #[serde(default)]
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextExportFormat {
+    #[default]
+    Text,
+    Zip,
+}
+
+/// What to do when the resolved save path already holds a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextExportIfExists {
+    #[default]
+    Overwrite,
+    /// Write `name-2.ext`, `name-3.ext`, ... next to it instead.
+    NewVersion,
 }
 
+impl ContextExportIfExists {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Overwrite => "overwrite",
+            Self::NewVersion => "new_version",
+        }
+    }
+}
+
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{repo}-{ref}-{timestamp}.md";
+
 fn default_git_ref() -> String {
     "WORKTREE".to_string()
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ContextManifestEntry {
+    pub path: String,
+    pub bytes: usize,
+    pub blob: String,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ContextManifest {
+    pub total_files: usize,
+    pub total_bytes: usize,
+    pub files: Vec<ContextManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextDriftEntry {
+    pub path: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextDriftReport {
+    pub repo_ref: String,
+    pub export_ref: String,
+    pub current_ref: String,
+    pub files_checked: usize,
+    pub unchanged: usize,
+    pub changed: Vec<ContextDriftEntry>,
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcludePatternReport {
+    pub pattern: String,
+    pub enabled: bool,
+    pub error: Option<String>,
+    /// Character offset into `pattern` where parsing failed, when the parser can tell.
+    pub error_offset: Option<usize>,
+    /// Candidate files this pattern matches on its own, whether or not another pattern also does.
+    pub excluded: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcludePreview {
+    pub candidate_files: usize,
+    /// Files the enabled, valid patterns exclude together.
+    pub excluded_files: usize,
+    pub patterns: Vec<ExcludePatternReport>,
+}
+
 pub async fn execute(
     ctx: &CapabilityContext<'_>,
     _prior_results: &[CapabilityResult],
     config: Value,
 ) -> Result<CapabilityResult> {
-    let result = execute_context_export(ctx.run_id, resolve_context_export_payload(ctx, config)?)?;
+    let mut payload = resolve_context_export_payload(ctx, config)?;
+    let saved = load_context_export_state(ctx.state, ctx.run_id).await?;
+    let strategy = resolve_strategy(&payload, &saved);
+    let last = saved.get("last_injection").cloned().and_then(|value| serde_json::from_value::<LastInjection>(value).ok());
+    let recent_text = if strategy.mode == InjectionMode::RecentMessages {
+        recent_inference_text(ctx.state, ctx.run_id, strategy.recent_messages).await?
+    } else {
+        String::new()
+    };
+    let req = parse_context_export_payload(payload.clone())?;
+    let previous = last.clone();
+    let mut plan = tokio::task::spawn_blocking(move || plan_injection(&req, &strategy, last.as_ref(), &recent_text)).await??;
+    if payload.get("refresh").and_then(Value::as_bool).unwrap_or(false) {
+        plan.refresh = true;
+        plan.note = format!("{} previously sent files changed by the last apply", plan.files.len());
+    }
+    let pending = saved
+        .get("pending_refresh")
+        .and_then(Value::as_array)
+        .map(|paths| paths.iter().filter_map(Value::as_str).map(ToString::to_string).collect::<Vec<_>>())
+        .unwrap_or_default();
+    include_pending_refresh(&mut plan, &mut payload, pending);
+
+    let mut result = execute_context_export(ctx.run_id, payload)?;
+    record_injection(ctx.state, ctx.run_id, &plan, previous.as_ref()).await?;
+    result["injection"] = json!(plan);
 
     Ok(CapabilityResult {
         ok: result.get("ok").and_then(Value::as_bool).unwrap_or(true),
@@ -98,6 +256,7 @@ pub fn normalize_context_export_payload(payload: Value, repo_resource: Option<Va
     obj.entry("include_files".to_string()).or_insert_with(|| json!([]));
     obj.entry("include_staged_diff".to_string()).or_insert_with(|| Value::Bool(false));
     obj.entry("include_unstaged_diff".to_string()).or_insert_with(|| Value::Bool(false));
+    obj.entry("format".to_string()).or_insert_with(|| Value::String("text".to_string()));
     obj.entry("skip_binary".to_string()).or_insert_with(|| Value::Bool(true));
     obj.entry("skip_gitignore".to_string()).or_insert_with(|| Value::Bool(true));
     obj.entry("save_path".to_string()).or_insert_with(|| Value::String("/tmp/repo_context.txt".to_string()));
@@ -105,38 +264,199 @@ pub fn normalize_context_export_payload(payload: Value, repo_resource: Option<Va
 }
 
 pub fn parse_context_export_payload(payload: Value) -> Result<ContextExportPayload> {
-    serde_json::from_value(payload).context("invalid context export payload")
+    let req: ContextExportPayload = serde_json::from_value(payload).context("invalid context export payload")?;
+    if req.git_ref.trim().starts_with('-') {
+        bail!("invalid git ref {:?}", req.git_ref);
+    }
+    Ok(req)
 }
 
 pub fn render_context_export_text(payload: Value) -> Result<String> {
     let req = parse_context_export_payload(payload)?;
     let repo = PathBuf::from(&req.repo_ref);
-    build_context_export_text(&repo, &req)
+    build_context_export_text(&repo, &req, None)
 }
 
-pub fn execute_context_export(run_id: uuid::Uuid, payload: Value) -> Result<Value> {
+pub fn execute_context_export(run_id: Uuid, payload: Value) -> Result<Value> {
     let req = parse_context_export_payload(payload)?;
 
     tracing::info!(%run_id, repo = %req.repo_ref, git_ref = %req.git_ref, save_path = %req.save_path, "context export started");
 
+    let (out_path, bytes_written) = write_context_export(&req, None)?;
+    let result = json!({
+        "ok": true,
+        "output_path": out_path.to_string_lossy().replace('\\', "/"),
+        "bytes_written": bytes_written,
+        "format": req.format,
+    });
+
+    tracing::info!(%run_id, output_path = %out_path.display(), bytes_written, "context export completed");
+    Ok(result)
+}
+
+/// Builds the export in memory and writes it through a temporary sibling file, so a failed or
+/// cancelled export never leaves a partial file at the save path.
+fn write_context_export(req: &ContextExportPayload, job: Option<&ContextExportJob>) -> Result<(PathBuf, usize)> {
     let repo = PathBuf::from(&req.repo_ref);
-    let out_path = resolve_context_export_save_path(&req);
+    let out_path = resolve_context_export_save_path(req);
     if let Some(parent) = out_path.parent() {
         fs::create_dir_all(parent).with_context(|| format!("failed to create parent dir {}", parent.display()))?;
     }
 
-    let export_text = build_context_export_text(&repo, &req)?;
-    fs::write(&out_path, export_text.as_bytes())
-        .with_context(|| format!("failed to write {}", out_path.display()))?;
+    let contents = match req.format {
+        ContextExportFormat::Text => build_context_export_text(&repo, req, job)?.into_bytes(),
+        ContextExportFormat::Zip => build_context_export_zip(&repo, req, job)?,
+    };
+    if job.is_some_and(ContextExportJob::is_cancelled) {
+        bail!("context export cancelled");
+    }
 
-    let result = json!({
-        "ok": true,
-        "output_path": out_path.to_string_lossy().replace('\\', "/"),
-        "bytes_written": export_text.len(),
+    let mut partial = out_path.clone().into_os_string();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let written = fs::write(&partial, &contents)
+        .with_context(|| format!("failed to write {}", partial.display()))
+        .and_then(|_| fs::rename(&partial, &out_path).with_context(|| format!("failed to move export to {}", out_path.display())));
+    if let Err(err) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(err);
+    }
+    Ok((out_path, contents.len()))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextExportProgress {
+    pub id: Uuid,
+    /// `running`, `done`, `cancelled` or `failed`.
+    pub status: String,
+    pub files_done: usize,
+    pub files_total: usize,
+    /// Bytes collected so far while running; the size of the written file once done.
+    pub bytes_written: usize,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct ContextExportJob {
+    cancelled: AtomicBool,
+    /// Set once a client has seen the final status.
+    fetched: AtomicBool,
+    finished_at: Mutex<Option<Instant>>,
+    progress: Mutex<ContextExportProgress>,
+}
+
+impl ContextExportJob {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn finished_at(&self) -> Option<Instant> {
+        *self.finished_at.lock().unwrap()
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut ContextExportProgress)) {
+        apply(&mut self.progress.lock().unwrap());
+    }
+
+    pub fn snapshot(&self) -> ContextExportProgress {
+        self.progress.lock().unwrap().clone()
+    }
+}
+
+/// Exports started from the UI, polled for progress until they finish.
+#[derive(Default)]
+pub struct ContextExportJobs {
+    jobs: Mutex<HashMap<Uuid, Arc<ContextExportJob>>>,
+}
+
+impl ContextExportJobs {
+    /// Runs the export on a blocking thread and returns its id right away. Earlier jobs that
+    /// finished are dropped once fetched, expired or past the limit.
+    pub fn start(&self, req: ContextExportPayload) -> Uuid {
+        let id = Uuid::new_v4();
+        let job = Arc::new(ContextExportJob {
+            cancelled: AtomicBool::new(false),
+            fetched: AtomicBool::new(false),
+            finished_at: Mutex::new(None),
+            progress: Mutex::new(ContextExportProgress {
+                id,
+                status: "running".to_string(),
+                files_done: 0,
+                files_total: 0,
+                bytes_written: 0,
+                output_path: None,
+                error: None,
+            }),
+        });
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            prune_finished_jobs(&mut jobs);
+            jobs.insert(id, job.clone());
+        }
+
+        tokio::task::spawn_blocking(move || {
+            let result = write_context_export(&req, Some(&job));
+            job.update(|progress| match result {
+                Ok((out_path, bytes)) => {
+                    progress.status = "done".to_string();
+                    progress.files_done = progress.files_total;
+                    progress.bytes_written = bytes;
+                    progress.output_path = Some(out_path.to_string_lossy().replace('\\', "/"));
+                }
+                Err(_) if job.is_cancelled() => progress.status = "cancelled".to_string(),
+                Err(err) => {
+                    progress.status = "failed".to_string();
+                    progress.error = Some(format!("{err:#}"));
+                }
+            });
+            *job.finished_at.lock().unwrap() = Some(Instant::now());
+        });
+        id
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<ContextExportProgress> {
+        let job = self.jobs.lock().unwrap().get(&id).cloned()?;
+        let snapshot = job.snapshot();
+        if job.finished_at().is_some() {
+            job.fetched.store(true, Ordering::Relaxed);
+        }
+        Some(snapshot)
+    }
+
+    /// Asks a running export to stop; it notices between files.
+    pub fn cancel(&self, id: Uuid) -> Option<ContextExportProgress> {
+        let job = self.jobs.lock().unwrap().get(&id).cloned()?;
+        job.cancelled.store(true, Ordering::Relaxed);
+        Some(job.snapshot())
+    }
+}
+
+fn prune_finished_jobs(jobs: &mut HashMap<Uuid, Arc<ContextExportJob>>) {
+    jobs.retain(|_, job| {
+        job.finished_at()
+            .is_none_or(|at| !job.fetched.load(Ordering::Relaxed) && at.elapsed() < FINISHED_JOB_TTL)
     });
+    let mut finished: Vec<(Instant, Uuid)> = jobs.iter().filter_map(|(id, job)| Some((job.finished_at()?, *id))).collect();
+    if finished.len() > MAX_FINISHED_JOBS {
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            jobs.remove(id);
+        }
+    }
+}
 
-    tracing::info!(%run_id, output_path = %out_path.display(), bytes_written = export_text.len(), "context export completed");
-    Ok(result)
+/// Adds files the last apply changed to the plan and limits the export to the plan's files. A full
+/// export with no explicit file list already sends every file, so it stays unrestricted.
+fn include_pending_refresh(plan: &mut InjectionPlan, payload: &mut Value, pending: Vec<String>) {
+    let whole_repo = plan.mode == InjectionMode::Full && !payload.get("include_files").is_some_and(Value::is_array);
+    let missing = pending.into_iter().filter(|path| !whole_repo && !plan.files.contains(path)).collect::<Vec<_>>();
+    if !missing.is_empty() {
+        plan.note = format!("{}, plus {} files changed by the last apply", plan.note, missing.len());
+        plan.files.extend(missing);
+        payload["include_files"] = json!(plan.files);
+    } else if plan.mode != InjectionMode::Full {
+        payload["include_files"] = json!(plan.files);
+    }
 }
 
 fn default_context_export_save_path() -> PathBuf {
@@ -152,62 +472,559 @@ fn default_context_export_save_path() -> PathBuf {
 }
 
 fn resolve_context_export_save_path(req: &ContextExportPayload) -> PathBuf {
-    if req.save_path.trim().is_empty() {
+    let raw = req.save_path.trim();
+    let path = if raw.is_empty() {
         default_context_export_save_path()
+    } else if raw.ends_with(['/', '\\']) || Path::new(raw).is_dir() {
+        Path::new(raw).join(expand_save_path_template(DEFAULT_FILENAME_TEMPLATE, req))
     } else {
-        PathBuf::from(&req.save_path)
+        PathBuf::from(expand_save_path_template(raw, req))
+    };
+    let path = if req.format == ContextExportFormat::Zip && path.extension().is_none_or(|ext| ext != "zip") {
+        path.with_extension("zip")
+    } else {
+        path
+    };
+    match req.if_exists {
+        ContextExportIfExists::Overwrite => path,
+        ContextExportIfExists::NewVersion => next_free_version(path),
     }
 }
 
-fn build_context_export_text(repo: &Path, req: &ContextExportPayload) -> Result<String> {
+/// Fills in the save path placeholders. Values are reduced to characters that are safe in a file
+/// name, so a ref like `feature/x` becomes `feature-x` rather than a new directory.
+fn expand_save_path_template(template: &str, req: &ContextExportPayload) -> String {
+    let repo = Path::new(req.repo_ref.trim().trim_end_matches(['/', '\\']))
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    let now = chrono::Local::now();
+    template
+        .replace("{repo}", &file_name_safe(&repo))
+        .replace("{ref}", &file_name_safe(req.git_ref.trim()))
+        .replace("{timestamp}", &now.format("%Y%m%d-%H%M%S").to_string())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+}
+
+fn file_name_safe(value: &str) -> String {
+    let safe: String = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '-' })
+        .collect();
+    let safe = safe.trim_matches(['-', '.']);
+    if safe.is_empty() { "x".to_string() } else { safe.to_string() }
+}
+
+fn next_free_version(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|version| path.with_file_name(format!("{stem}-{version}{ext}")))
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded version range")
+}
+
+struct CollectedExport {
+    git_ref: String,
+    candidate_count: usize,
+    included: Vec<(String, Vec<u8>)>,
+    manifest: ContextManifest,
+    staged_diff: Option<Vec<u8>>,
+    unstaged_diff: Option<Vec<u8>>,
+}
+
+/// Worktree files and diffs copied into memory in one go under the repo lock, so an export never mixes
+/// versions from before and after a changeset apply that was running at the same time.
+struct WorktreeSnapshot {
+    files: HashMap<String, Vec<u8>>,
+    staged_diff: Option<Vec<u8>>,
+    unstaged_diff: Option<Vec<u8>>,
+}
+
+fn snapshot_worktree(repo: &Path, paths: &[String], req: &ContextExportPayload) -> Result<WorktreeSnapshot> {
+    let lock = repo_lock(&repo.to_string_lossy());
+    let _reading = lock.read().unwrap_or_else(|err| err.into_inner());
+    let mut files = HashMap::with_capacity(paths.len());
+    for rel in paths {
+        // Links that lead out of the repository are left out, like files deleted since listing.
+        let Ok(full) = resolve_workspace_path(&repo.to_string_lossy(), rel) else { continue };
+        match fs::read(full) {
+            Ok(bytes) => {
+                files.insert(rel.clone(), bytes);
+            }
+            // Deleted after the file list was taken; the snapshot simply doesn't have it.
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err).with_context(|| format!("failed to read {}", rel)),
+        }
+    }
+    let staged_diff = req.include_staged_diff.then(|| run_git_capture(repo, &["diff", "--cached"])).transpose()?;
+    let unstaged_diff = req.include_unstaged_diff.then(|| run_git_capture(repo, &["diff"])).transpose()?;
+    Ok(WorktreeSnapshot { files, staged_diff, unstaged_diff })
+}
+
+fn collect_export_files(repo: &Path, req: &ContextExportPayload, job: Option<&ContextExportJob>) -> Result<CollectedExport> {
     let compiled = compile_excludes(&req.exclude_regex)?;
-    let mut files = collect_candidate_files(repo, &req.git_ref, req.include_files.as_ref())?;
+    let git_ref = effective_read_ref(repo, &req.git_ref);
+    let mut files = collect_candidate_files(repo, &git_ref, req.include_files.as_ref())?;
     files.sort();
     files.dedup();
 
-    let mut out = String::new();
-    out.push_str(&format!("## Repo Context Export\nrepo: {}\nref: {}\ninclude_staged_diff: {}\ninclude_unstaged_diff: {}\nfiles: {}\n\n", repo.display(), if req.git_ref.is_empty() { "WORKTREE" } else { &req.git_ref }, req.include_staged_diff, req.include_unstaged_diff, files.len()));
-
-    for rel in files {
-        if path_is_excluded(&rel, &compiled) {
-            continue;
+    let mut selected = Vec::with_capacity(files.len());
+    for rel in &files {
+        if job.is_some_and(ContextExportJob::is_cancelled) {
+            bail!("context export cancelled");
         }
-        if req.skip_gitignore && is_gitignored(repo, &rel)? {
+        // A line break in a path would split its `==== path ====` section marker.
+        if rel.contains(['\n', '\r']) || path_is_excluded(rel, &compiled) || (req.skip_gitignore && is_gitignored(repo, rel)?) {
             continue;
         }
-        let bytes = read_file_bytes(repo, effective_ref(&req.git_ref), &rel)?;
+        selected.push(rel.clone());
+    }
+    let mut snapshot = if git_ref == "WORKTREE" { Some(snapshot_worktree(repo, &selected, req)?) } else { None };
+
+    let mut included: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut worktree_blobs = HashMap::new();
+    let mut truncated = std::collections::HashSet::new();
+    let (mut collected_bytes, mut counted) = (0, 0);
+    for (index, rel) in selected.iter().enumerate() {
+        if let Some(job) = job {
+            if job.is_cancelled() {
+                bail!("context export cancelled");
+            }
+            collected_bytes += included[counted..].iter().map(|(_, bytes)| bytes.len()).sum::<usize>();
+            counted = included.len();
+            let bytes = collected_bytes;
+            job.update(|progress| {
+                progress.files_done = index;
+                progress.files_total = selected.len();
+                progress.bytes_written = bytes;
+            });
+        }
+        let bytes = match snapshot.as_mut() {
+            Some(snapshot) => match snapshot.files.remove(rel) {
+                Some(bytes) => {
+                    worktree_blobs.insert(rel.clone(), git_blob_id(&bytes));
+                    bytes
+                }
+                None => continue,
+            },
+            None => read_file_bytes(repo, &git_ref, rel)?,
+        };
         if req.skip_binary && is_probably_binary(&bytes) {
             continue;
         }
-        let text = String::from_utf8_lossy(&bytes);
-        out.push_str(&format!("==== {} ====\n{}\n\n", rel, text));
+        let reduced = req
+            .symbols
+            .get(rel)
+            .filter(|names| !names.is_empty())
+            .and_then(|names| extract_symbols(rel, &String::from_utf8_lossy(&bytes), names))
+            .map(String::into_bytes);
+        let selected = reduced.is_some();
+        let bytes = reduced.unwrap_or(bytes);
+        let limited = match byte_budget(req, rel) {
+            Some(max) if bytes.len() > max && !is_probably_binary(&bytes) => {
+                Some(fit_to_budget(rel, &String::from_utf8_lossy(&bytes), max, req.keep_signatures).into_bytes())
+            }
+            _ => None,
+        };
+        if selected || limited.is_some() {
+            truncated.insert(rel.clone());
+        }
+        included.push((rel.clone(), limited.unwrap_or(bytes)));
     }
 
-    if effective_ref(&req.git_ref) == "WORKTREE" {
-        if req.include_staged_diff {
-            let diff = run_git_capture_string(repo, &["diff", "--cached"])?;
-            if !diff.trim().is_empty() {
-                out.push_str("==== STAGED DIFF ====\n");
-                out.push_str(&diff);
-                out.push_str("\n\n");
+    // Worktree ids come from the snapshotted bytes themselves, so they always describe what was exported.
+    let blobs = if snapshot.is_some() {
+        worktree_blobs
+    } else {
+        blob_hashes(repo, &git_ref, &included.iter().map(|(rel, _)| rel.clone()).collect::<Vec<_>>())?
+    };
+    let manifest = ContextManifest {
+        total_files: included.len(),
+        total_bytes: included.iter().map(|(_, bytes)| bytes.len()).sum(),
+        files: included
+            .iter()
+            .map(|(rel, bytes)| ContextManifestEntry {
+                path: rel.clone(),
+                bytes: bytes.len(),
+                blob: blobs.get(rel).cloned().unwrap_or_default(),
+                truncated: truncated.contains(rel),
+            })
+            .collect(),
+    };
+
+    let (staged_diff, unstaged_diff) = snapshot.map(|snapshot| (snapshot.staged_diff, snapshot.unstaged_diff)).unwrap_or_default();
+    Ok(CollectedExport {
+        git_ref,
+        candidate_count: files.len(),
+        included,
+        manifest,
+        staged_diff,
+        unstaged_diff,
+    })
+}
+
+/// The cap for `rel`: an override on the file itself wins, then the one on its deepest directory,
+/// then the export-wide `max_bytes_per_file`.
+fn byte_budget(req: &ContextExportPayload, rel: &str) -> Option<usize> {
+    let overrides = req.file_budgets_by_ref.get(&req.git_ref);
+    let budget = overrides.and_then(|budgets| {
+        budgets.get(rel).or_else(|| {
+            budgets
+                .iter()
+                .filter(|(key, _)| {
+                    let dir = key.trim_end_matches('/');
+                    !dir.is_empty() && rel.strip_prefix(dir).is_some_and(|rest| rest.starts_with('/'))
+                })
+                .max_by_key(|(key, _)| key.trim_end_matches('/').len())
+                .map(|(_, budget)| budget)
+        })
+    });
+    match budget {
+        Some(budget) if budget.full => None,
+        Some(FileBudget { max_bytes: Some(max), .. }) => Some(*max).filter(|max| *max > 0),
+        _ => req.max_bytes_per_file.filter(|max| *max > 0),
+    }
+}
+
+/// Shrinks `text` to at most `max_bytes`: the signature skeleton when asked for and it fits, otherwise
+/// whole lines from the head and tail around an elision marker.
+fn fit_to_budget(path: &str, text: &str, max_bytes: usize, keep_signatures: bool) -> String {
+    if keep_signatures {
+        if let Some(skeleton) = signature_skeleton(path, text) {
+            if skeleton.len() <= max_bytes {
+                return skeleton;
+            }
+            return truncate_head_tail(&skeleton, max_bytes, comment_prefix(path));
+        }
+    }
+    truncate_head_tail(text, max_bytes, comment_prefix(path))
+}
+
+/// Keeps about two thirds of the budget from the start and one third from the end, cutting only at
+/// line boundaries. A single line longer than the budget (minified files) is cut at a char boundary.
+pub fn truncate_head_tail(text: &str, max_bytes: usize, comment: Option<&str>) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let marker = |count: usize, bytes: usize| match comment {
+        Some(prefix) => format!("{prefix} ... {count} lines ({bytes} bytes) elided ...\n"),
+        None => format!("[... {count} lines ({bytes} bytes) elided ...]\n"),
+    };
+    let budget = max_bytes.saturating_sub(marker(lines.len(), text.len()).len());
+
+    let mut head = 0;
+    let mut head_bytes = 0;
+    while head < lines.len() && head_bytes + lines[head].len() <= budget * 2 / 3 {
+        head_bytes += lines[head].len();
+        head += 1;
+    }
+    let mut tail = lines.len();
+    let mut tail_bytes = 0;
+    while tail > head && head_bytes + tail_bytes + lines[tail - 1].len() <= budget {
+        tail_bytes += lines[tail - 1].len();
+        tail -= 1;
+    }
+
+    let mut out = String::with_capacity(max_bytes);
+    if head == 0 && tail == lines.len() {
+        let mut cut = budget.min(text.len());
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        out.push_str(&text[..cut]);
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&marker(1, text.len() - cut));
+        return out;
+    }
+    lines[..head].iter().for_each(|line| out.push_str(line));
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(&marker(tail - head, text.len() - head_bytes - tail_bytes));
+    lines[tail..].iter().for_each(|line| out.push_str(line));
+    out
+}
+
+fn build_context_export_zip(repo: &Path, req: &ContextExportPayload, job: Option<&ContextExportJob>) -> Result<Vec<u8>> {
+    let CollectedExport { git_ref, included, manifest, staged_diff, unstaged_diff, .. } = collect_export_files(repo, req, job)?;
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+
+    for (rel, bytes) in &included {
+        writer.start_file(zip_entry_name(rel)?, options)?;
+        writer.write_all(bytes)?;
+    }
+
+    writer.start_file(format!("{ZIP_META_DIR}/manifest.json"), options)?;
+    writer.write_all(&serde_json::to_vec_pretty(&json!({
+        "repo": repo.display().to_string(),
+        "ref": git_ref,
+        "manifest": manifest,
+    }))?)?;
+
+    for (name, diff) in [("STAGED.diff", staged_diff), ("UNSTAGED.diff", unstaged_diff)] {
+        if let Some(diff) = diff.filter(|diff| !diff.is_empty()) {
+            writer.start_file(format!("{ZIP_META_DIR}/{name}"), options)?;
+            writer.write_all(&diff)?;
+        }
+    }
+
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Repo files keep their relative path in the archive, so a name must not climb out of the
+/// extraction folder or land in the folder holding the export's own metadata.
+fn zip_entry_name(rel: &str) -> Result<String> {
+    let name = normalize_rel_path(rel)?;
+    if name.is_empty() {
+        bail!("empty archive entry name");
+    }
+    if name == ZIP_META_DIR || name.starts_with(&format!("{ZIP_META_DIR}/")) {
+        bail!("{name} collides with the reserved {ZIP_META_DIR}/ folder of the export");
+    }
+    Ok(name)
+}
+
+fn build_context_export_text(repo: &Path, req: &ContextExportPayload, job: Option<&ContextExportJob>) -> Result<String> {
+    let CollectedExport { git_ref, candidate_count, included, manifest, staged_diff, unstaged_diff } = collect_export_files(repo, req, job)?;
+
+    let mut out = String::new();
+    out.push_str(&format!("## Repo Context Export\nrepo: {}\nref: {}\ninclude_staged_diff: {}\ninclude_unstaged_diff: {}\nfiles: {}\n", repo.display(), git_ref, req.include_staged_diff, req.include_unstaged_diff, candidate_count));
+    out.push_str(&format!("manifest: {}\n\n", serde_json::to_string(&manifest)?));
+
+    for (rel, bytes) in &included {
+        out.push_str(&format!("==== {} ====\n{}\n\n", rel, String::from_utf8_lossy(bytes)));
+    }
+
+    for (title, diff) in [("STAGED DIFF", staged_diff), ("UNSTAGED DIFF", unstaged_diff)] {
+        let diff = diff.map(|diff| String::from_utf8_lossy(&diff).into_owned()).unwrap_or_default();
+        if !diff.trim().is_empty() {
+            out.push_str(&format!("==== {} ====\n", title));
+            out.push_str(&diff);
+            out.push_str("\n\n");
+        }
+    }
+
+    Ok(out)
+}
+
+/// Validates each exclude pattern and counts the files at `git_ref` it would drop from an export.
+pub fn preview_excludes(repo_ref: &str, git_ref: &str, patterns: &[String]) -> Result<ExcludePreview> {
+    let repo = Path::new(repo_ref.trim());
+    if !repo.is_dir() {
+        bail!("repo_ref is not a directory: {}", repo.display());
+    }
+    let git_ref = effective_read_ref(repo, git_ref);
+    let files = collect_candidate_files(repo, &git_ref, None)?;
+
+    let mut reports = Vec::new();
+    let mut active = Vec::new();
+    for raw in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let enabled = !disabled_exclude(raw);
+        let pattern = raw.trim_start_matches('#').trim();
+        let (error, error_offset, excluded) = match Regex::new(pattern) {
+            Ok(re) => {
+                let excluded = files.iter().filter(|path| re.is_match(path)).count();
+                if enabled {
+                    active.push(re);
+                }
+                (None, None, excluded)
+            }
+            Err(err) => {
+                let (message, offset) = describe_regex_error(pattern, &err);
+                (Some(message), offset, 0)
+            }
+        };
+        reports.push(ExcludePatternReport { pattern: pattern.to_string(), enabled, error, error_offset, excluded });
+    }
+
+    Ok(ExcludePreview {
+        candidate_files: files.len(),
+        excluded_files: files.iter().filter(|path| path_is_excluded(path, &active)).count(),
+        patterns: reports,
+    })
+}
+
+/// The parser's own message and position when it has one; otherwise the compiled-regex error (e.g. size limits).
+fn describe_regex_error(pattern: &str, err: &regex::Error) -> (String, Option<usize>) {
+    match regex_syntax::ast::parse::Parser::new().parse(pattern) {
+        Err(ast_err) => {
+            let offset = ast_err.span().start.offset;
+            (ast_err.kind().to_string(), Some(pattern[..offset].chars().count()))
+        }
+        Ok(_) => (err.to_string().lines().last().unwrap_or_default().trim_start_matches("error: ").to_string(), None),
+    }
+}
+
+/// Compares the file sections embedded in a previous export against the same paths at `git_ref`
+/// (defaults to the ref recorded in the export header).
+pub fn check_context_drift(export_text: &str, repo_ref: Option<&str>, git_ref: Option<&str>) -> Result<ContextDriftReport> {
+    let header_value = |key: &str| {
+        export_text
+            .lines()
+            .take_while(|line| !line.starts_with("==== "))
+            .find_map(|line| line.strip_prefix(&format!("{key}: ")))
+            .map(|value| value.trim().to_string())
+    };
+
+    let repo_ref = repo_ref
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .or_else(|| header_value("repo"))
+        .context("export has no repo header; pass the repository explicitly")?;
+    let export_ref = header_value("ref").unwrap_or_else(default_git_ref);
+    let repo = PathBuf::from(&repo_ref);
+    let current_ref = effective_read_ref(repo.as_path(), git_ref.map(str::trim).filter(|value| !value.is_empty()).unwrap_or(&export_ref));
+
+    let manifest = header_value("manifest").and_then(|raw| serde_json::from_str::<ContextManifest>(&raw).ok());
+    let mut changed = Vec::new();
+    let files_checked = if let Some(manifest) = manifest {
+        let paths = manifest.files.iter().map(|entry| entry.path.clone()).collect::<Vec<_>>();
+        let current = blob_hashes(repo.as_path(), &current_ref, &paths)?;
+        for entry in &manifest.files {
+            let status = match current.get(&entry.path) {
+                Some(blob) if *blob == entry.blob => continue,
+                Some(_) => "modified",
+                None => "deleted",
+            };
+            changed.push(ContextDriftEntry { path: entry.path.clone(), status: status.to_string() });
+        }
+        manifest.files.len()
+    } else {
+        let sections = parse_export_sections(export_text);
+        if sections.is_empty() {
+            bail!("no file sections found in export");
+        }
+        for (path, embedded) in &sections {
+            let status = match read_file_bytes(repo.as_path(), &current_ref, path) {
+                Ok(bytes) if String::from_utf8_lossy(&bytes) == *embedded => continue,
+                Ok(_) => "modified",
+                Err(_) => "deleted",
+            };
+            changed.push(ContextDriftEntry { path: path.clone(), status: status.to_string() });
+        }
+        sections.len()
+    };
+
+    Ok(ContextDriftReport {
+        repo_ref,
+        export_ref,
+        current_ref,
+        files_checked,
+        unchanged: files_checked - changed.len(),
+        stale: !changed.is_empty(),
+        changed,
+    })
+}
+
+/// Git blob ids for `paths` at `git_ref`; paths missing at that ref are left out. Worktree files are
+/// read and hashed in-process under the repo read lock, so each id matches the bytes read at that moment.
+pub(crate) fn blob_hashes(repo: &Path, git_ref: &str, paths: &[String]) -> Result<HashMap<String, String>> {
+    if paths.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    if git_ref != "WORKTREE" {
+        let stdout = run_git_capture(repo, &["ls-tree", "-r", "-z", "--full-tree", "--end-of-options", git_ref])?;
+        let stdout = String::from_utf8_lossy(&stdout);
+        let tree = stdout
+            .split('\0')
+            .filter_map(|entry| {
+                let (meta, path) = entry.split_once('\t')?;
+                Some((path.to_string(), meta.split_whitespace().nth(2)?.to_string()))
+            })
+            .collect::<HashMap<_, _>>();
+        return Ok(paths.iter().filter_map(|path| Some((path.clone(), tree.get(path)?.clone()))).collect());
+    }
+
+    let repo_ref = repo.to_string_lossy();
+    let lock = repo_lock(&repo_ref);
+    let _reading = lock.read().unwrap_or_else(|err| err.into_inner());
+    let mut blobs = HashMap::with_capacity(paths.len());
+    for path in paths {
+        let Ok(full) = resolve_workspace_path(&repo_ref, path) else { continue };
+        match fs::read(full) {
+            Ok(bytes) => {
+                blobs.insert(path.clone(), git_blob_id(&bytes));
             }
+            Err(err) if matches!(err.kind(), std::io::ErrorKind::NotFound | std::io::ErrorKind::IsADirectory) => {}
+            Err(err) => return Err(err).with_context(|| format!("failed to read {path}")),
         }
-        if req.include_unstaged_diff {
-            let diff = run_git_capture_string(repo, &["diff"])?;
-            if !diff.trim().is_empty() {
-                out.push_str("==== UNSTAGED DIFF ====\n");
-                out.push_str(&diff);
-                out.push_str("\n\n");
+    }
+    Ok(blobs)
+}
+
+/// The id `git hash-object` gives these bytes (without clean filters): SHA-1 over a `blob <len>` header.
+fn git_blob_id(bytes: &[u8]) -> String {
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY);
+    ctx.update(format!("blob {}\0", bytes.len()).as_bytes());
+    ctx.update(bytes);
+    ctx.finish().as_ref().iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn parse_export_sections(export_text: &str) -> Vec<(String, String)> {
+    let mut sections = Vec::new();
+    let mut current: Option<(String, usize)> = None;
+    let mut offset = 0;
+
+    for line in export_text.split_inclusive('\n') {
+        let marker = line
+            .trim_end_matches(['\r', '\n'])
+            .strip_prefix("==== ")
+            .and_then(|rest| rest.strip_suffix(" ===="));
+        if let Some(name) = marker {
+            if let Some((path, start)) = current.take() {
+                sections.push((path, section_body(&export_text[start..offset])));
+            }
+            if name != "STAGED DIFF" && name != "UNSTAGED DIFF" {
+                current = Some((name.to_string(), offset + line.len()));
             }
         }
+        offset += line.len();
     }
+    if let Some((path, start)) = current {
+        sections.push((path, section_body(&export_text[start..])));
+    }
+    sections
+}
 
+fn section_body(raw: &str) -> String {
+    raw.strip_suffix("\n\n").unwrap_or(raw).to_string()
+}
+
+/// Every tracked or untracked, not ignored path at `git_ref`, without walking ignored directories.
+pub(crate) fn list_repo_paths(repo: &Path, git_ref: &str) -> Result<Vec<String>> {
+    let stdout = if effective_ref(git_ref) == "WORKTREE" {
+        run_git_capture_string(repo, &["ls-files", "--cached", "--others", "--exclude-standard"])?
+    } else {
+        run_git_capture_string(repo, &["ls-tree", "-r", "--name-only", "--end-of-options", effective_ref(git_ref)])?
+    };
+    repo_paths(stdout.lines())
+}
+
+/// Repository-relative paths with separators normalized; `..` or a drive prefix is an error.
+fn repo_paths<'a>(paths: impl IntoIterator<Item = &'a str>) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    for path in paths {
+        let path = normalize_rel_path(path.trim().trim_matches(['/', '\\']))?;
+        if !path.is_empty() {
+            out.push(path);
+        }
+    }
     Ok(out)
 }
 
 fn collect_candidate_files(repo: &Path, git_ref: &str, include_files: Option<&Vec<String>>) -> Result<Vec<String>> {
     if let Some(include_files) = include_files {
-        return Ok(include_files.iter().map(|p| normalize_rel_path(p)).filter(|p| !p.is_empty()).collect());
+        return repo_paths(include_files.iter().map(String::as_str));
     }
 
     if effective_ref(git_ref) == "WORKTREE" {
@@ -216,12 +1033,8 @@ fn collect_candidate_files(repo: &Path, git_ref: &str, include_files: Option<&Ve
         return Ok(out);
     }
 
-    let stdout = run_git_capture_string(repo, &["ls-tree", "-r", "--name-only", effective_ref(git_ref)])?;
-    Ok(stdout
-        .lines()
-        .map(normalize_rel_path)
-        .filter(|p| !p.is_empty())
-        .collect())
+    let stdout = run_git_capture_string(repo, &["ls-tree", "-r", "--name-only", "--end-of-options", effective_ref(git_ref)])?;
+    repo_paths(stdout.lines())
 }
 
 fn collect_worktree_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
@@ -246,9 +1059,14 @@ fn path_is_excluded(path: &str, patterns: &[Regex]) -> bool {
     patterns.iter().any(|re| re.is_match(path))
 }
 
+/// Exclude entries starting with `#` are kept in the list but switched off.
+fn disabled_exclude(pattern: &str) -> bool {
+    pattern.trim_start().starts_with('#')
+}
+
 fn compile_excludes(patterns: &[String]) -> Result<Vec<Regex>> {
     let mut out = Vec::new();
-    for p in patterns {
+    for p in patterns.iter().filter(|p| !disabled_exclude(p)) {
         out.push(Regex::new(p).with_context(|| format!("Bad exclude regex '{p}'"))?);
     }
     Ok(out)
@@ -256,13 +1074,10 @@ fn compile_excludes(patterns: &[String]) -> Result<Vec<Regex>> {
 
 fn read_file_bytes(repo: &Path, git_ref: &str, rel: &str) -> Result<Vec<u8>> {
     if git_ref == "WORKTREE" {
-        return fs::read(repo.join(rel)).with_context(|| format!("failed to read {}", rel));
+        let full = resolve_workspace_path(&repo.to_string_lossy(), rel)?;
+        return fs::read(full).with_context(|| format!("failed to read {}", rel));
     }
-    run_git_capture(repo, &["show", &format!("{}:{}", git_ref, rel)])
-}
-
-fn normalize_rel_path(path: &str) -> String {
-    path.trim().trim_matches('/').replace('\\', "/")
+    run_git_capture(repo, &["show", "--end-of-options", &format!("{}:{}", git_ref, rel)])
 }
 
 fn effective_ref(git_ref: &str) -> &str {
@@ -274,6 +1089,7 @@ fn is_probably_binary(bytes: &[u8]) -> bool {
 }
 
 fn is_gitignored(repo: &Path, rel: &str) -> Result<bool> {
+    crate::metrics::record_git_spawn();
     let output = Command::new("git")
         .arg("check-ignore")
         .arg("--quiet")
@@ -286,9 +1102,8 @@ fn is_gitignored(repo: &Path, rel: &str) -> Result<bool> {
 }
 
 fn run_git_capture(repo: &Path, args: &[&str]) -> Result<Vec<u8>> {
-    let output = Command::new("git")
+    let output = git_command(repo)
         .args(args)
-        .current_dir(repo)
         .output()
         .with_context(|| format!("failed to run git {:?}", args))?;
     if !output.status.success() {
@@ -300,3 +1115,125 @@ fn run_git_capture(repo: &Path, args: &[&str]) -> Result<Vec<u8>> {
 fn run_git_capture_string(repo: &Path, args: &[&str]) -> Result<String> {
     Ok(String::from_utf8(run_git_capture(repo, args)?)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::capabilities::git::git::{run_git, run_git_with_input};
+
+    #[test]
+    fn blob_ids_match_git_hash_object() {
+        let repo = std::env::temp_dir().join(format!("context-export-blobs-{}", Uuid::new_v4()));
+        fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        let bytes = b"line one\r\nline two\n\0binary";
+        let expected = run_git_with_input(&repo, &["hash-object", "--stdin"], bytes).unwrap();
+        assert_eq!(git_blob_id(bytes), String::from_utf8_lossy(&expected).trim());
+
+        fs::write(repo.join("a.txt"), bytes).unwrap();
+        fs::write(repo.join("odd\nname.txt"), "odd").unwrap();
+        let paths = ["a.txt".to_string(), "odd\nname.txt".to_string(), "missing.txt".to_string()];
+        let worktree = blob_hashes(&repo, "WORKTREE", &paths).unwrap();
+        assert_eq!(worktree.len(), 2);
+        assert_eq!(worktree["a.txt"], git_blob_id(bytes));
+
+        run_git(&repo, &["add", "-A"]).unwrap();
+        run_git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]).unwrap();
+        assert_eq!(blob_hashes(&repo, "HEAD", &paths).unwrap(), worktree);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    fn plan(mode: InjectionMode, files: &[&str]) -> InjectionPlan {
+        InjectionPlan {
+            mode,
+            files: files.iter().map(ToString::to_string).collect(),
+            note: String::new(),
+            refresh: false,
+            blobs: Vec::new(),
+            git_ref: "WORKTREE".to_string(),
+        }
+    }
+
+    #[test]
+    fn pending_refresh_never_narrows_a_whole_repo_export() {
+        let pending = || vec!["changed.rs".to_string()];
+
+        let mut whole = plan(InjectionMode::Full, &["a.rs", "b.rs"]);
+        let mut payload = json!({ "repo_ref": "/repo" });
+        include_pending_refresh(&mut whole, &mut payload, pending());
+        assert!(payload.get("include_files").is_none());
+        assert_eq!(whole.files, ["a.rs", "b.rs"]);
+
+        let mut listed = plan(InjectionMode::Full, &["a.rs"]);
+        let mut payload = json!({ "include_files": ["a.rs"] });
+        include_pending_refresh(&mut listed, &mut payload, pending());
+        assert_eq!(payload["include_files"], json!(["a.rs", "changed.rs"]));
+
+        let mut pinned = plan(InjectionMode::Pinned, &["a.rs", "changed.rs"]);
+        let mut payload = json!({});
+        include_pending_refresh(&mut pinned, &mut payload, pending());
+        assert_eq!(payload["include_files"], json!(["a.rs", "changed.rs"]));
+        assert!(pinned.note.is_empty());
+    }
+
+    #[test]
+    fn zip_entries_stay_relative_and_out_of_the_metadata_folder() {
+        assert_eq!(zip_entry_name("src/./main.rs").unwrap(), "src/main.rs");
+        assert_eq!(zip_entry_name("docs\\guide.md").unwrap(), "docs/guide.md");
+        assert_eq!(zip_entry_name("_mdevelopment/notes.md").unwrap(), "_mdevelopment/notes.md");
+        for name in ["", ".", "../escape.txt", "src/../../escape.txt", "/etc/passwd", "_mdev", "_mdev/manifest.json"] {
+            assert!(zip_entry_name(name).is_err(), "{name}");
+        }
+    }
+
+    fn job(finished_ago: Option<Duration>) -> Arc<ContextExportJob> {
+        let id = Uuid::new_v4();
+        Arc::new(ContextExportJob {
+            cancelled: AtomicBool::new(false),
+            fetched: AtomicBool::new(false),
+            finished_at: Mutex::new(finished_ago.map(|ago| Instant::now().checked_sub(ago).unwrap())),
+            progress: Mutex::new(ContextExportProgress {
+                id,
+                status: if finished_ago.is_some() { "done" } else { "running" }.to_string(),
+                files_done: 0,
+                files_total: 0,
+                bytes_written: 0,
+                output_path: None,
+                error: None,
+            }),
+        })
+    }
+
+    #[test]
+    fn finished_jobs_stay_until_fetched_expired_or_over_the_limit() {
+        let exports = ContextExportJobs::default();
+        let running = job(None);
+        let unfetched = job(Some(Duration::from_secs(60)));
+        let fetched = job(Some(Duration::from_secs(60)));
+        let expired = job(Some(FINISHED_JOB_TTL + Duration::from_secs(1)));
+        let ids = [&running, &unfetched, &fetched, &expired].map(|job| job.snapshot().id);
+        {
+            let mut jobs = exports.jobs.lock().unwrap();
+            for (id, job) in ids.iter().zip([&running, &unfetched, &fetched, &expired]) {
+                jobs.insert(*id, job.clone());
+            }
+        }
+        assert_eq!(exports.get(ids[0]).unwrap().status, "running");
+        assert_eq!(exports.get(ids[2]).unwrap().status, "done");
+
+        let mut jobs = exports.jobs.lock().unwrap();
+        prune_finished_jobs(&mut jobs);
+        assert!(jobs.contains_key(&ids[0]) && jobs.contains_key(&ids[1]));
+        assert!(!jobs.contains_key(&ids[2]) && !jobs.contains_key(&ids[3]));
+
+        let oldest = job(Some(Duration::from_secs(120)));
+        jobs.insert(oldest.snapshot().id, oldest.clone());
+        for _ in 0..MAX_FINISHED_JOBS {
+            let recent = job(Some(Duration::from_secs(1)));
+            jobs.insert(recent.snapshot().id, recent);
+        }
+        prune_finished_jobs(&mut jobs);
+        assert!(!jobs.contains_key(&oldest.snapshot().id) && !jobs.contains_key(&ids[1]));
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 1);
+    }
+}
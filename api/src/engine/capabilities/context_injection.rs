@@ -0,0 +1,349 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    engine::{load_run, merge_json_values, persist_context},
+};
+
+use super::{
+    context_export::{blob_hashes, list_repo_paths, ContextExportPayload},
+    git::git::effective_read_ref,
+    inference::prompt_section,
+};
+
+/// Which files an injection sends, chosen per loop in `capabilities.context_export.injection`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionMode {
+    /// Every selected file, every time.
+    #[default]
+    Full,
+    /// Selected files whose contents changed since the previous injection; everything on the first one.
+    ChangedSinceLast,
+    /// Repository files whose path appears in the latest inference prompts or replies.
+    RecentMessages,
+    /// Only the loop's pinned files.
+    Pinned,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InjectionStrategy {
+    #[serde(default)]
+    pub mode: InjectionMode,
+    /// Inference turns `recent_messages` looks back over.
+    #[serde(default = "default_recent_messages")]
+    pub recent_messages: usize,
+    #[serde(default)]
+    pub pinned: Vec<String>,
+}
+
+impl Default for InjectionStrategy {
+    fn default() -> Self {
+        Self { mode: InjectionMode::Full, recent_messages: default_recent_messages(), pinned: Vec::new() }
+    }
+}
+
+fn default_recent_messages() -> usize {
+    3
+}
+
+/// Stored in the run next to the strategy after each injection, so the next one knows what changed and
+/// what the model has already seen.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LastInjection {
+    pub at: String,
+    pub mode: InjectionMode,
+    pub git_ref: String,
+    /// Blob ids of the selected files at the time of the injection; only `changed_since_last` keeps them.
+    #[serde(default)]
+    pub blobs: Vec<InjectedBlob>,
+    /// Paths that were actually sent.
+    pub sent: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct InjectedBlob {
+    pub path: String,
+    pub blob: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InjectionPlan {
+    pub mode: InjectionMode,
+    pub files: Vec<String>,
+    pub note: String,
+    /// Re-sends files an automatic apply changed; recorded on top of the previous injection rather than replacing it.
+    pub refresh: bool,
+    #[serde(skip)]
+    pub blobs: Vec<InjectedBlob>,
+    #[serde(skip)]
+    pub git_ref: String,
+}
+
+/// The strategy from the export payload, falling back to the run's saved one.
+pub fn resolve_strategy(payload: &Value, context_export_state: &Value) -> InjectionStrategy {
+    payload
+        .get("injection")
+        .or_else(|| context_export_state.get("injection"))
+        .cloned()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+pub async fn load_context_export_state(state: &AppState, run_id: Uuid) -> Result<Value> {
+    let run = load_run(state, run_id).await?;
+    Ok(run
+        .context
+        .pointer("/workflow_engine/global_state/capabilities/context_export")
+        .cloned()
+        .unwrap_or_else(|| json!({})))
+}
+
+/// User messages and replies of the last `limit` inference turns of the run, newest first. Injected
+/// repository context is left out, so files aren't picked just because an earlier export included them.
+pub async fn recent_inference_text(state: &AppState, run_id: Uuid, limit: usize) -> Result<String> {
+    let rows = sqlx::query(
+        "SELECT payload_json FROM workflow_events WHERE run_id = ? AND kind = 'inference_completed' ORDER BY sequence_no DESC LIMIT ?",
+    )
+    .bind(run_id.to_string())
+    .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+    .fetch_all(&state.db)
+    .await?;
+    let mut text = String::new();
+    for row in rows {
+        let payload: Value = serde_json::from_str(row.get::<String, _>("payload_json").as_str()).unwrap_or_default();
+        let message = payload.get("result").and_then(|result| prompt_section(result, "user_input"));
+        let reply = payload.pointer("/result/result/text").and_then(Value::as_str);
+        for part in [message, reply].into_iter().flatten() {
+            text.push_str(part);
+            text.push('\n');
+        }
+    }
+    Ok(text)
+}
+
+/// Narrows the export's selected files according to `strategy`.
+pub fn plan_injection(
+    req: &ContextExportPayload,
+    strategy: &InjectionStrategy,
+    last: Option<&LastInjection>,
+    recent_text: &str,
+) -> Result<InjectionPlan> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let git_ref = effective_read_ref(&repo, &req.git_ref);
+    let selected = || -> Result<Vec<String>> {
+        Ok(match &req.include_files {
+            Some(files) => normalized_set(files).into_iter().collect(),
+            None => list_repo_paths(&repo, &git_ref)?,
+        })
+    };
+
+    let mut blobs = Vec::new();
+    let (files, note) = match strategy.mode {
+        InjectionMode::Full => {
+            let selected = selected()?;
+            let note = format!("all {} selected files", selected.len());
+            (selected, note)
+        }
+        InjectionMode::ChangedSinceLast => {
+            let selected = selected()?;
+            let current = blob_hashes(&repo, &git_ref, &selected)?;
+            let last = last.filter(|last| last.git_ref == git_ref && last.mode == InjectionMode::ChangedSinceLast);
+            let (files, note) = match last {
+                None => (selected.clone(), "first injection at this ref, so every selected file".to_string()),
+                Some(last) => {
+                    let previous = last.blobs.iter().map(|entry| (entry.path.as_str(), entry.blob.as_str())).collect::<HashMap<_, _>>();
+                    let changed = selected
+                        .iter()
+                        .filter(|path| current.get(*path).is_some_and(|blob| previous.get(path.as_str()) != Some(&blob.as_str())))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    let note = format!("{} of {} selected files changed since {}", changed.len(), selected.len(), last.at);
+                    (changed, note)
+                }
+            };
+            blobs = selected
+                .iter()
+                .filter_map(|path| Some(InjectedBlob { path: path.clone(), blob: current.get(path)?.clone() }))
+                .collect();
+            (files, note)
+        }
+        InjectionMode::RecentMessages => {
+            let referenced = referenced_paths(&repo, &git_ref, recent_text)?;
+            let note = format!("{} files mentioned in the last {} inference turns", referenced.len(), strategy.recent_messages);
+            (referenced, note)
+        }
+        InjectionMode::Pinned => {
+            let pinned = pinned_paths(&repo, &git_ref, &strategy.pinned)?;
+            let note = format!("{} files from {} pins", pinned.len(), strategy.pinned.len());
+            (pinned, note)
+        }
+    };
+
+    Ok(InjectionPlan { mode: strategy.mode, files, note, refresh: false, blobs, git_ref })
+}
+
+/// Records the injection in the run, where the next one (and the loop's saved state) picks it up. Anything queued
+/// for refresh has now been sent.
+pub async fn record_injection(
+    state: &AppState,
+    run_id: Uuid,
+    plan: &InjectionPlan,
+    previous: Option<&LastInjection>,
+) -> Result<LastInjection> {
+    let last = match previous.filter(|_| plan.refresh) {
+        Some(previous) => {
+            let mut blobs = previous.blobs.iter().map(|entry| (entry.path.clone(), entry.blob.clone())).collect::<BTreeMap<_, _>>();
+            if previous.mode == InjectionMode::ChangedSinceLast {
+                blobs.extend(plan.blobs.iter().map(|entry| (entry.path.clone(), entry.blob.clone())));
+            }
+            let sent = normalized_set(previous.sent.iter().chain(plan.files.iter()));
+            LastInjection {
+                at: Utc::now().to_rfc3339(),
+                mode: previous.mode,
+                git_ref: previous.git_ref.clone(),
+                blobs: blobs.into_iter().map(|(path, blob)| InjectedBlob { path, blob }).collect(),
+                sent: sent.into_iter().collect(),
+            }
+        }
+        None => LastInjection {
+            at: Utc::now().to_rfc3339(),
+            mode: plan.mode,
+            git_ref: plan.git_ref.clone(),
+            blobs: plan.blobs.clone(),
+            sent: plan.files.clone(),
+        },
+    };
+    let mut run = load_run(state, run_id).await?;
+    merge_json_values(
+        &mut run.context,
+        &json!({ "workflow_engine": { "global_state": { "capabilities": { "context_export": { "last_injection": last, "pending_refresh": null } } } } }),
+    );
+    persist_context(state, run_id, &run.context).await?;
+    Ok(last)
+}
+
+fn normalized_set<'a>(paths: impl IntoIterator<Item = &'a String>) -> BTreeSet<String> {
+    paths
+        .into_iter()
+        .map(|path| path.trim().trim_matches('/').replace('\\', "/"))
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Pinned files, with a pinned directory standing for every file under it.
+fn pinned_paths(repo: &Path, git_ref: &str, pinned: &[String]) -> Result<Vec<String>> {
+    let pinned = normalized_set(pinned);
+    if pinned.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(list_repo_paths(repo, git_ref)?
+        .into_iter()
+        .filter(|path| pinned.iter().any(|pin| path == pin || path.strip_prefix(pin.as_str()).is_some_and(|rest| rest.starts_with('/'))))
+        .collect())
+}
+
+/// Repository paths mentioned in `text`.
+fn referenced_paths(repo: &Path, git_ref: &str, text: &str) -> Result<Vec<String>> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let text = text.replace('\\', "/");
+    Ok(list_repo_paths(repo, git_ref)?.into_iter().filter(|path| mentions(&text, path)).collect())
+}
+
+/// `path` as a whole token, so `lib.rs` doesn't match inside `mylib.rs`; a leading `/` or `./` is fine.
+fn mentions(text: &str, path: &str) -> bool {
+    let joins_before = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.');
+    let joins_after = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '/');
+    text.match_indices(path).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + path.len()..].chars().next();
+        !before.is_some_and(joins_before) && !after.is_some_and(joins_after)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::engine::capabilities::{context_export::parse_context_export_payload, git::git::run_git};
+
+    fn strategy(mode: InjectionMode) -> InjectionStrategy {
+        InjectionStrategy { mode, ..InjectionStrategy::default() }
+    }
+
+    #[test]
+    fn only_changed_since_last_hashes_and_compares_blobs() {
+        let repo = std::env::temp_dir().join(format!("context-injection-{}", Uuid::new_v4()));
+        fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        fs::write(repo.join("a.txt"), "a\n").unwrap();
+        fs::write(repo.join("b.txt"), "b\n").unwrap();
+        let req = parse_context_export_payload(json!({ "repo_ref": repo.to_string_lossy(), "include_files": ["a.txt", "b.txt"] })).unwrap();
+
+        let full = plan_injection(&req, &strategy(InjectionMode::Full), None, "").unwrap();
+        assert_eq!(full.files, ["a.txt", "b.txt"]);
+        assert!(full.blobs.is_empty());
+
+        let first = plan_injection(&req, &strategy(InjectionMode::ChangedSinceLast), None, "").unwrap();
+        assert_eq!(first.files, ["a.txt", "b.txt"]);
+        assert_eq!(first.blobs.len(), 2);
+
+        fs::write(repo.join("a.txt"), "a changed\n").unwrap();
+        let mut last = LastInjection {
+            at: Utc::now().to_rfc3339(),
+            mode: InjectionMode::ChangedSinceLast,
+            git_ref: first.git_ref.clone(),
+            blobs: first.blobs.clone(),
+            sent: first.files.clone(),
+        };
+        let next = plan_injection(&req, &strategy(InjectionMode::ChangedSinceLast), Some(&last), "").unwrap();
+        assert_eq!(next.files, ["a.txt"]);
+
+        last.mode = InjectionMode::Full;
+        let after_full = plan_injection(&req, &strategy(InjectionMode::ChangedSinceLast), Some(&last), "").unwrap();
+        assert_eq!(after_full.files, ["a.txt", "b.txt"]);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[tokio::test]
+    async fn recent_text_skips_injected_context() {
+        let state = AppState::new(crate::db::test_db_with_settings(json!({})).await);
+        let run_id = Uuid::new_v4();
+        let prompt = "Repository context: src/exported.rs\nPlease fix src/asked.rs";
+        let user_input = prompt.find("Please").unwrap();
+        let payload = json!({
+            "result": {
+                "prompt": prompt,
+                "request": { "sections": [{ "name": "user_input", "start": user_input, "end": prompt.len() }] },
+                "result": { "text": "Done; also touched src/replied.rs" },
+            },
+        });
+        sqlx::query(
+            "INSERT INTO workflow_events (id, run_id, sequence_no, is_header_event, level, kind, message, payload_json, created_at)
+             VALUES (?, ?, 1, 0, 'info', 'inference_completed', '', ?, '')",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(run_id.to_string())
+        .bind(payload.to_string())
+        .execute(&state.db)
+        .await
+        .unwrap();
+
+        let text = recent_inference_text(&state, run_id, 5).await.unwrap();
+        assert!(text.contains("src/asked.rs") && text.contains("src/replied.rs"));
+        assert!(!text.contains("src/exported.rs"));
+    }
+}
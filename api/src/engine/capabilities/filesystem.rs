@@ -1,8 +1,19 @@
-use std::{fs, io::ErrorKind, path::{Component, Path, PathBuf}};
+use std::{
+    collections::HashMap,
+    fs,
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, RwLock},
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    engine::capabilities::git::git::{effective_read_ref, run_git, show_file_at},
+    long_path::extended,
+};
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct FilesystemTarget {
     pub repo_ref: String,
@@ -37,12 +48,110 @@ pub struct FileStat {
     pub bytes: u64,
 }
 
+static REPO_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<RwLock<()>>>>> = OnceLock::new();
+
+/// Per-repository lock between writers that change many files at once (changeset apply) and readers that
+/// need a consistent view of them (context snapshots). Ordinary single-file saves don't take it.
+pub fn repo_lock(repo_ref: &str) -> Arc<RwLock<()>> {
+    let key = fs::canonicalize(repo_ref.trim()).unwrap_or_else(|_| PathBuf::from(repo_ref.trim()));
+    let mut locks = REPO_LOCKS.get_or_init(Default::default).lock().unwrap_or_else(|err| err.into_inner());
+    locks.entry(key).or_default().clone()
+}
+
 pub fn read_text_file(repo_ref: &str, path: &str) -> Result<String> {
     let full = resolve_workspace_path(repo_ref, path)?;
     let bytes = fs::read(&full).with_context(|| format!("failed to read {}", full.display()))?;
     String::from_utf8(bytes).with_context(|| format!("{} is not valid UTF-8 text", path))
 }
 
+pub fn read_text_file_at_ref(repo_ref: &str, path: &str, git_ref: &str) -> Result<String> {
+    let repo = Path::new(repo_ref.trim());
+    let git_ref = effective_read_ref(repo, git_ref);
+    if git_ref == "WORKTREE" {
+        return read_text_file(repo_ref, path);
+    }
+    let bytes = show_file_at(repo, &format!("{}:{}", git_ref, path))?;
+    String::from_utf8(bytes).with_context(|| format!("{} at {} is not valid UTF-8 text", path, git_ref))
+}
+
+/// `lf` or `crlf` when `.gitattributes` pins the file's line endings with `eol`.
+pub fn eol_attribute(repo_ref: &str, path: &str) -> Option<&'static str> {
+    let out = run_git(Path::new(repo_ref.trim()), &["check-attr", "eol", "--", path]).ok()?;
+    match String::from_utf8_lossy(&out).trim().rsplit(": ").next()? {
+        "lf" => Some("lf"),
+        "crlf" => Some("crlf"),
+        _ => None,
+    }
+}
+
+/// Editor language and comment tokens for a file, so comment toggling works for languages the editor
+/// has no configuration for.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LanguageHint {
+    pub language: &'static str,
+    pub line_comment: Option<&'static str>,
+    pub block_comment: Option<(&'static str, &'static str)>,
+}
+
+pub fn language_hint_for_path(path: &str) -> Option<LanguageHint> {
+    let name = Path::new(path).file_name()?.to_string_lossy().to_ascii_lowercase();
+    let extension = match name.as_str() {
+        "dockerfile" => "dockerfile",
+        "makefile" => "makefile",
+        _ => name.rsplit_once('.')?.1,
+    };
+    let (language, line_comment, block_comment) = match extension {
+        "rs" => ("rust", Some("//"), Some(("/*", "*/"))),
+        "ts" | "tsx" => ("typescript", Some("//"), Some(("/*", "*/"))),
+        "js" | "jsx" | "mjs" | "cjs" => ("javascript", Some("//"), Some(("/*", "*/"))),
+        "go" => ("go", Some("//"), Some(("/*", "*/"))),
+        "java" => ("java", Some("//"), Some(("/*", "*/"))),
+        "kt" | "kts" => ("kotlin", Some("//"), Some(("/*", "*/"))),
+        "c" | "h" => ("c", Some("//"), Some(("/*", "*/"))),
+        "cc" | "cpp" | "hpp" => ("cpp", Some("//"), Some(("/*", "*/"))),
+        "cs" => ("csharp", Some("//"), Some(("/*", "*/"))),
+        "swift" => ("swift", Some("//"), Some(("/*", "*/"))),
+        "css" => ("css", None, Some(("/*", "*/"))),
+        "scss" => ("scss", Some("//"), Some(("/*", "*/"))),
+        "py" | "pyi" => ("python", Some("#"), None),
+        "rb" => ("ruby", Some("#"), None),
+        "sh" | "bash" | "zsh" => ("shell", Some("#"), None),
+        "ps1" => ("powershell", Some("#"), Some(("<#", "#>"))),
+        "toml" => ("toml", Some("#"), None),
+        "yaml" | "yml" => ("yaml", Some("#"), None),
+        "dockerfile" => ("dockerfile", Some("#"), None),
+        "makefile" => ("makefile", Some("#"), None),
+        "sql" => ("sql", Some("--"), Some(("/*", "*/"))),
+        "lua" => ("lua", Some("--"), Some(("--[[", "]]"))),
+        "abap" => ("abap", Some("\""), None),
+        "ini" => ("ini", Some(";"), None),
+        "html" | "xml" | "svg" => (if extension == "html" { "html" } else { "xml" }, None, Some(("<!--", "-->"))),
+        "md" => ("markdown", None, Some(("<!--", "-->"))),
+        _ => return None,
+    };
+    Some(LanguageHint { language, line_comment, block_comment })
+}
+
+/// Rewrites every line ending as `eol` (`lf` or `crlf`).
+pub fn convert_line_endings(contents: &str, eol: &str) -> String {
+    let lf = contents.replace("\r\n", "\n");
+    if eol == "crlf" {
+        lf.replace('\n', "\r\n")
+    } else {
+        lf
+    }
+}
+
+pub fn resolve_ref_commit(repo_ref: &str, git_ref: &str) -> Result<Option<String>> {
+    let repo = Path::new(repo_ref.trim());
+    let git_ref = effective_read_ref(repo, git_ref);
+    if git_ref == "WORKTREE" {
+        return Ok(None);
+    }
+    let out = run_git(repo, &["rev-parse", "--verify", &format!("{}^{{commit}}", git_ref)])?;
+    Ok(Some(String::from_utf8_lossy(&out).trim().to_string()))
+}
+
 pub fn write_text_file(repo_ref: &str, path: &str, contents: &str) -> Result<FileStat> {
     let full = resolve_workspace_path(repo_ref, path)?;
     let parent = full.parent().ok_or_else(|| anyhow!("path has no parent: {}", full.display()))?;
@@ -51,6 +160,89 @@ pub fn write_text_file(repo_ref: &str, path: &str, contents: &str) -> Result<Fil
     Ok(stat_for_path(repo_ref, path, &full)?)
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BatchWrite {
+    pub path: String,
+    pub contents: String,
+}
+
+/// Outcome of one item in a batch; `contents` is only set for reads.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemResult {
+    pub path: String,
+    pub ok: bool,
+    pub bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contents: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BatchProgress<'a> {
+    pub done: usize,
+    pub total: usize,
+    pub path: &'a str,
+}
+
+/// Normalizes and resolves every path before anything is touched, failing with all of the bad ones at once.
+pub fn validate_batch_paths<'a>(repo_ref: &str, paths: impl IntoIterator<Item = &'a str>) -> Result<Vec<(String, PathBuf)>> {
+    let mut resolved = Vec::new();
+    let mut invalid = Vec::new();
+    for path in paths {
+        match normalize_rel_path(path).and_then(|normalized| Ok((resolve_workspace_path(repo_ref, &normalized)?, normalized))) {
+            Ok((full, normalized)) => resolved.push((normalized, full)),
+            Err(err) => invalid.push(format!("{path}: {err:#}")),
+        }
+    }
+    if !invalid.is_empty() {
+        bail!("{} of the batch's paths are invalid, nothing was done: {}", invalid.len(), invalid.join("; "));
+    }
+    Ok(resolved)
+}
+
+/// Writes every file after validating all paths. A failed write is reported in its result and the rest still go ahead.
+pub fn write_many(repo_ref: &str, items: &[BatchWrite], mut on_progress: impl FnMut(BatchProgress<'_>)) -> Result<Vec<BatchItemResult>> {
+    let resolved = validate_batch_paths(repo_ref, items.iter().map(|item| item.path.as_str()))?;
+    let total = items.len();
+    let mut results = Vec::with_capacity(total);
+    for (index, ((path, full), item)) in resolved.into_iter().zip(items).enumerate() {
+        let written = full
+            .parent()
+            .ok_or_else(|| anyhow!("path has no parent: {}", full.display()))
+            .and_then(|parent| fs::create_dir_all(parent).with_context(|| format!("failed to create parent dir {}", parent.display())))
+            .and_then(|_| fs::write(&full, item.contents.as_bytes()).with_context(|| format!("failed to write {}", full.display())));
+        on_progress(BatchProgress { done: index + 1, total, path: &path });
+        results.push(match written {
+            Ok(()) => BatchItemResult { path, ok: true, bytes: item.contents.len() as u64, contents: None, error: None },
+            Err(err) => BatchItemResult { path, ok: false, bytes: 0, contents: None, error: Some(format!("{err:#}")) },
+        });
+    }
+    Ok(results)
+}
+
+/// Reads every file at `git_ref` after validating all paths; unreadable files are reported per item.
+pub fn read_many(
+    repo_ref: &str,
+    paths: &[String],
+    git_ref: &str,
+    mut on_progress: impl FnMut(BatchProgress<'_>),
+) -> Result<Vec<BatchItemResult>> {
+    let resolved = validate_batch_paths(repo_ref, paths.iter().map(String::as_str))?;
+    let git_ref = effective_read_ref(Path::new(repo_ref.trim()), git_ref);
+    let total = resolved.len();
+    let mut results = Vec::with_capacity(total);
+    for (index, (path, _)) in resolved.into_iter().enumerate() {
+        let read = read_text_file_at_ref(repo_ref, &path, &git_ref);
+        on_progress(BatchProgress { done: index + 1, total, path: &path });
+        results.push(match read {
+            Ok(contents) => BatchItemResult { bytes: contents.len() as u64, path, ok: true, contents: Some(contents), error: None },
+            Err(err) => BatchItemResult { path, ok: false, bytes: 0, contents: None, error: Some(format!("{err:#}")) },
+        });
+    }
+    Ok(results)
+}
+
 pub fn create_file(repo_ref: &str, path: &str, contents: &str) -> Result<FileStat> {
     let full = resolve_workspace_path(repo_ref, path)?;
     if full.exists() {
@@ -76,9 +268,13 @@ pub fn delete_path(repo_ref: &str, path: &str) -> Result<()> {
     if normalized.is_empty() {
         bail!("refusing to delete workspace root");
     }
-    let full = resolve_workspace_path(repo_ref, &normalized)?;
-    let metadata = fs::metadata(&full).with_context(|| format!("failed to stat {}", full.display()))?;
-    if metadata.is_dir() {
+    let full = resolve_workspace_entry(repo_ref, &normalized)?;
+    let metadata = fs::symlink_metadata(&full).with_context(|| format!("failed to stat {}", full.display()))?;
+    if metadata.file_type().is_symlink() {
+        fs::remove_file(&full)
+            .or_else(|_| fs::remove_dir(&full))
+            .with_context(|| format!("failed to delete link {}", full.display()))?;
+    } else if metadata.is_dir() {
         fs::remove_dir_all(&full).with_context(|| format!("failed to delete directory {}", full.display()))?;
     } else {
         fs::remove_file(&full).with_context(|| format!("failed to delete file {}", full.display()))?;
@@ -100,7 +296,7 @@ pub fn normalize_rel_path(path: &str) -> Result<String> {
     Ok(out.to_string_lossy().replace('\\', "/"))
 }
 
-fn resolve_workspace_path(repo_ref: &str, path: &str) -> Result<PathBuf> {
+pub(crate) fn resolve_workspace_path(repo_ref: &str, path: &str) -> Result<PathBuf> {
     let repo_root = PathBuf::from(repo_ref);
     if repo_root.as_os_str().is_empty() {
         bail!("repo_ref is required");
@@ -111,7 +307,23 @@ fn resolve_workspace_path(repo_ref: &str, path: &str) -> Result<PathBuf> {
     }
     let full = repo_root.join(&normalized);
     ensure_within_root(&repo_root, &full)?;
-    Ok(full)
+    Ok(extended(&full))
+}
+
+/// Like `resolve_workspace_path`, but only the parents have to stay inside the workspace: the entry itself
+/// may be a symlink pointing anywhere, and callers act on the link rather than its target.
+pub(crate) fn resolve_workspace_entry(repo_ref: &str, path: &str) -> Result<PathBuf> {
+    let repo_root = PathBuf::from(repo_ref);
+    if repo_root.as_os_str().is_empty() {
+        bail!("repo_ref is required");
+    }
+    let normalized = normalize_rel_path(path)?;
+    if normalized.is_empty() {
+        bail!("path is required");
+    }
+    let full = repo_root.join(&normalized);
+    ensure_within_root(&repo_root, full.parent().unwrap_or(&repo_root))?;
+    Ok(extended(&full))
 }
 
 fn ensure_within_root(root: &Path, full: &Path) -> Result<()> {
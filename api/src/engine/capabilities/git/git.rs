@@ -4,6 +4,11 @@ use std::path::{Path, PathBuf};
 use std::collections::{HashMap, HashSet};
 use std::process::Command;
 
+use crate::engine::capabilities::analysis::outline::comment_prefix;
+use crate::engine::capabilities::context_export::truncate_head_tail;
+use crate::engine::capabilities::git::types::{GitCredentials, GitDiscardPlan, GitRemoteResult};
+use crate::long_path::{extended, strip_extended};
+
 const WORKTREE_REF: &str = "WORKTREE";
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GitPatchScope {
@@ -22,15 +27,63 @@ pub fn ensure_git_installed() -> Result<()> {
 }
 
 pub fn ensure_git_repo(repo: &Path) -> Result<()> {
-    let _ = run_git(repo, &["rev-parse", "--is-inside-work-tree"])
+    let _ = run_git(repo, &["rev-parse", "--git-dir"])
         .with_context(|| format!("{:?} does not appear to be a git repo", repo))?;
     Ok(())
 }
 
+pub fn ensure_git_worktree(repo: &Path) -> Result<()> {
+    if is_bare_git_dir(repo) {
+        bail!("{:?} is a bare repository or .git directory; worktree operations are not available", repo);
+    }
+    ensure_git_repo(repo)
+}
+
+pub fn is_bare_git_dir(repo: &Path) -> bool {
+    repo.join("HEAD").is_file() && repo.join("objects").is_dir() && repo.join("refs").is_dir()
+}
+
+pub fn effective_read_ref(repo: &Path, git_ref: &str) -> String {
+    let git_ref = git_ref.trim();
+    if git_ref.is_empty() || git_ref == WORKTREE_REF {
+        if is_bare_git_dir(repo) {
+            return "HEAD".to_string();
+        }
+        return WORKTREE_REF.to_string();
+    }
+    git_ref.to_string()
+}
+
+/// A client-supplied ref resolved to a commit id (or WORKTREE), so it can never reach git as an option.
+pub fn resolve_read_ref(repo: &Path, git_ref: &str) -> Result<String> {
+    let git_ref = effective_read_ref(repo, git_ref);
+    if git_ref == WORKTREE_REF {
+        return Ok(git_ref);
+    }
+    if git_ref.starts_with('-') {
+        bail!("invalid git ref {git_ref:?}");
+    }
+    let spec = format!("{git_ref}^{{commit}}");
+    let (code, stdout, _) = run_git_allow_fail(repo, &["rev-parse", "--verify", "--quiet", "--end-of-options", &spec])?;
+    if code != 0 {
+        bail!("unknown git ref {git_ref:?}");
+    }
+    Ok(String::from_utf8_lossy(&stdout).trim().to_string())
+}
+
+pub fn git_command(repo: &Path) -> Command {
+    crate::metrics::record_git_spawn();
+    let mut cmd = Command::new("git");
+    if is_bare_git_dir(repo) {
+        cmd.arg("--git-dir").arg(repo);
+    } else {
+        cmd.arg("-C").arg(strip_extended(repo));
+    }
+    cmd
+}
+
 pub fn run_git(repo: &Path, args: &[&str]) -> Result<Vec<u8>> {
-    let out = Command::new("git")
-        .arg("-C")
-        .arg(repo)
+    let out = git_command(repo)
         .args(args)
         .output()
         .with_context(|| format!("failed to run git -C {:?} {}", repo, args.join(" ")))?;
@@ -48,7 +101,7 @@ pub fn generate_git_apply_patch(
     paths: Option<&[String]>,
     context_lines: Option<u32>,
 ) -> Result<String> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
 
     let mut args: Vec<String> = vec!["diff".to_string(), "--binary".to_string()];
 
@@ -75,9 +128,7 @@ pub fn generate_git_apply_patch(
 }
 
 pub fn run_git_allow_fail(repo: &Path, args: &[&str]) -> Result<(i32, Vec<u8>, Vec<u8>)> {
-    let out = Command::new("git")
-        .arg("-C")
-        .arg(repo)
+    let out = git_command(repo)
         .args(args)
         .output()
         .with_context(|| format!("failed to run git -C {:?} {}", repo, args.join(" ")))?;
@@ -217,37 +268,184 @@ pub fn git_current_branch(repo: &Path) -> Result<String> {
     Ok(String::from_utf8_lossy(&out).trim().to_string())
 }
 
-pub fn git_fetch(repo: &Path, remote: Option<&str>) -> Result<String> {
-    ensure_git_repo(repo)?;
-    let r = remote.unwrap_or("origin");
-    let (code, stdout, stderr) = run_git_text_allow_fail(repo, &["fetch", r])?;
-    Ok(format!("git fetch {}\n[exit: {}]\n{}{}", r, code, stdout, stderr))
+/// The remote must be one `git remote` lists, so a client value can never reach git as an option or URL.
+fn checked_remote(repo: &Path, remote: Option<&str>) -> Result<String> {
+    let remote = remote.map(str::trim).filter(|r| !r.is_empty()).unwrap_or("origin");
+    if remote.starts_with('-') {
+        bail!("invalid remote {remote:?}");
+    }
+    if !git_list_remotes(repo)?.iter().any(|known| known == remote) {
+        bail!("unknown remote {remote:?}");
+    }
+    Ok(remote.to_string())
 }
 
-pub fn git_pull(repo: &Path, remote: Option<&str>, branch: Option<&str>) -> Result<String> {
-    ensure_git_repo(repo)?;
-    let r = remote.unwrap_or("origin");
-    let args: Vec<&str> = match branch {
-        Some(b) if !b.trim().is_empty() => vec!["pull", r, b],
-        _ => vec!["pull", r],
-    };
-    let (code, stdout, stderr) = run_git_text_allow_fail(repo, &args)?;
-    Ok(format!("git {}\n[exit: {}]\n{}{}", args.join(" "), code, stdout, stderr))
+fn checked_branch(branch: Option<&str>) -> Result<Option<&str>> {
+    match branch.map(str::trim).filter(|b| !b.is_empty()) {
+        Some(b) if b.starts_with('-') => bail!("invalid branch {b:?}"),
+        other => Ok(other),
+    }
 }
 
-pub fn git_push(repo: &Path, remote: Option<&str>, branch: Option<&str>) -> Result<String> {
+pub fn git_fetch(repo: &Path, remote: Option<&str>, credentials: Option<&GitCredentials>) -> Result<GitRemoteResult> {
     ensure_git_repo(repo)?;
-    let r = remote.unwrap_or("origin");
-    let args: Vec<&str> = match branch {
-        Some(b) if !b.trim().is_empty() => vec!["push", r, b],
-        _ => vec!["push", r],
+    let r = checked_remote(repo, remote)?;
+    run_git_remote(repo, &r, &["fetch", "--end-of-options", &r], credentials)
+}
+
+pub fn git_pull(
+    repo: &Path,
+    remote: Option<&str>,
+    branch: Option<&str>,
+    credentials: Option<&GitCredentials>,
+) -> Result<GitRemoteResult> {
+    ensure_git_worktree(repo)?;
+    let r = checked_remote(repo, remote)?;
+    let mut args = vec!["pull", "--end-of-options", &r];
+    args.extend(checked_branch(branch)?);
+    run_git_remote(repo, &r, &args, credentials)
+}
+
+pub fn git_push(
+    repo: &Path,
+    remote: Option<&str>,
+    branch: Option<&str>,
+    credentials: Option<&GitCredentials>,
+) -> Result<GitRemoteResult> {
+    ensure_git_worktree(repo)?;
+    let r = checked_remote(repo, remote)?;
+    let mut args = vec!["push", "--end-of-options", &r];
+    args.extend(checked_branch(branch)?);
+    run_git_remote(repo, &r, &args, credentials)
+}
+
+const GIT_AUTH_FAILURE_MARKERS: &[&str] = &[
+    "terminal prompts disabled",
+    "could not read username",
+    "could not read password",
+    "authentication failed",
+    "invalid username or password",
+    "permission denied (publickey",
+    "host key verification failed",
+    "http basic: access denied",
+];
+
+fn run_git_remote(
+    repo: &Path,
+    remote: &str,
+    args: &[&str],
+    credentials: Option<&GitCredentials>,
+) -> Result<GitRemoteResult> {
+    let remote_url = run_git_text_allow_fail(repo, &["remote", "get-url", "--end-of-options", remote])
+        .map(|(_, stdout, _)| stdout.trim().to_string())
+        .unwrap_or_default();
+    let auth_kind = remote_auth_kind(&remote_url);
+
+    let mut cmd = git_command(repo);
+    cmd.args(args).env("GIT_TERMINAL_PROMPT", "0");
+    if std::env::var_os("GIT_SSH_COMMAND").is_none() && std::env::var_os("GIT_SSH").is_none() {
+        cmd.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+    if let Some(creds) = credentials.filter(|c| c.username.is_some() || c.password.is_some()) {
+        cmd.env("GIT_ASKPASS", ensure_askpass_helper()?)
+            .env("MDEV_GIT_USERNAME", creds.username.as_deref().unwrap_or(""))
+            .env("MDEV_GIT_PASSWORD", creds.password.as_deref().unwrap_or(""));
+    }
+
+    let out = cmd
+        .output()
+        .with_context(|| format!("failed to run git -C {:?} {}", repo, args.join(" ")))?;
+    let code = out.status.code().unwrap_or(1);
+    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+
+    let stderr_lower = stderr.to_lowercase();
+    let needs_credentials = code != 0 && GIT_AUTH_FAILURE_MARKERS.iter().any(|m| stderr_lower.contains(m));
+    let message = if !needs_credentials {
+        None
+    } else if auth_kind == "ssh" && std::env::var_os("SSH_AUTH_SOCK").is_none() {
+        Some("ssh authentication failed and no ssh-agent is running (SSH_AUTH_SOCK is not set); start ssh-agent and add your key".to_string())
+    } else if auth_kind == "ssh" {
+        Some("ssh authentication failed; make sure your key is loaded in ssh-agent and the host is in known_hosts".to_string())
+    } else {
+        Some("remote requires credentials; provide a username and password or access token".to_string())
     };
-    let (code, stdout, stderr) = run_git_text_allow_fail(repo, &args)?;
-    Ok(format!("git {}\n[exit: {}]\n{}{}", args.join(" "), code, stdout, stderr))
+
+    Ok(GitRemoteResult {
+        ok: code == 0,
+        exit_code: code,
+        log: format!("git {}\n[exit: {}]\n{}{}", args.join(" "), code, stdout, stderr),
+        auth_kind: auth_kind.to_string(),
+        needs_credentials,
+        message,
+    })
+}
+
+fn remote_auth_kind(url: &str) -> &'static str {
+    let url = url.trim();
+    if url.starts_with("http://") || url.starts_with("https://") {
+        "https"
+    } else if url.starts_with("ssh://") || url.starts_with("git@") || (url.contains('@') && url.contains(':') && !url.contains("://")) {
+        "ssh"
+    } else {
+        "none"
+    }
+}
+
+#[cfg(unix)]
+const ASKPASS_HELPER: (&str, &str) = (
+    "git-askpass.sh",
+    "#!/bin/sh\ncase \"$1\" in\n  Username*) printf '%s\\n' \"$MDEV_GIT_USERNAME\" ;;\n  *) printf '%s\\n' \"$MDEV_GIT_PASSWORD\" ;;\nesac\n",
+);
+
+/// cmd only looks at git's prompt; the credential itself is read from the environment by PowerShell, so
+/// cmd never parses its value.
+#[cfg(not(unix))]
+const ASKPASS_HELPER: (&str, &str) = (
+    "git-askpass.cmd",
+    "@echo off\r\nset \"MDEV_GIT_FIELD=MDEV_GIT_PASSWORD\"\r\nset \"MDEV_GIT_PROMPT=%~1\"\r\nif /i \"%MDEV_GIT_PROMPT:~0,8%\"==\"Username\" set \"MDEV_GIT_FIELD=MDEV_GIT_USERNAME\"\r\npowershell -NoProfile -NonInteractive -Command \"[Console]::Out.WriteLine([Environment]::GetEnvironmentVariable($env:MDEV_GIT_FIELD))\"\r\n",
+);
+
+/// The helper lives in a private directory under the app data dir. A fresh copy is written under a unique
+/// name and renamed into place, so the file git runs is never one another user could have created.
+fn ensure_askpass_helper() -> Result<PathBuf> {
+    let (name, script) = ASKPASS_HELPER;
+    let dir = crate::runtime_env::data_dir().join("git-askpass");
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        builder.mode(0o700);
+        builder.create(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))
+            .with_context(|| format!("failed to chmod {}", dir.display()))?;
+    }
+    #[cfg(not(unix))]
+    builder.create(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let path = dir.join(name);
+    if std::fs::read_to_string(&path).ok().as_deref() == Some(script) {
+        return Ok(path);
+    }
+    let staging = dir.join(format!(".{name}.{}", uuid::Uuid::new_v4().simple()));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o700);
+    }
+    let written = options.open(&staging).and_then(|mut file| std::io::Write::write_all(&mut file, script.as_bytes()));
+    if let Err(err) = written.and_then(|_| std::fs::rename(&staging, &path)) {
+        let _ = std::fs::remove_file(&staging);
+        return Err(err).with_context(|| format!("failed to write {}", path.display()));
+    }
+    Ok(path)
 }
 
 pub fn git_checkout_branch(repo: &Path, branch: &str, create_if_missing: bool) -> Result<String> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
     let b = branch.trim();
     if b.is_empty() {
         bail!("branch is empty");
@@ -264,7 +462,7 @@ pub fn git_checkout_branch(repo: &Path, branch: &str, create_if_missing: bool) -
 }
 
 pub fn git_stage_paths(repo: &Path, paths: &[String]) -> Result<()> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
     if paths.is_empty() {
         return Ok(());
     }
@@ -284,8 +482,105 @@ pub fn git_stage_paths(repo: &Path, paths: &[String]) -> Result<()> {
     Ok(())
 }
 
+/// Stages only the worktree changes touching `start_line..=end_line` (1-based, worktree numbering)
+/// by rewriting a zero-context diff and applying it to the index. Returns the number of lines staged.
+pub fn git_stage_line_range(repo: &Path, path: &str, start_line: usize, end_line: usize) -> Result<usize> {
+    ensure_git_worktree(repo)?;
+    if path.contains("..") {
+        bail!("refusing to stage path with '..': {}", path);
+    }
+    let (start_line, end_line) = (start_line.min(end_line), start_line.max(end_line));
+
+    let tracked = run_git_allow_fail(repo, &["ls-files", "--error-unmatch", "--", path])?.0 == 0;
+    if !tracked {
+        run_git(repo, &["add", "--intent-to-add", "--", path])?;
+    }
+
+    let diff = String::from_utf8(run_git(repo, &["diff", "--unified=0", "--no-color", "--", path])?)
+        .context("worktree diff is not valid UTF-8")?;
+    let Some(first_hunk) = diff.find("\n@@ ") else {
+        bail!("{} has no unstaged changes", path);
+    };
+    let header = &diff[..first_hunk + 1];
+    let hunk_re = Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@").unwrap();
+
+    let mut hunks: Vec<(usize, usize, usize, Vec<&str>)> = Vec::new();
+    for line in diff[first_hunk + 1..].lines() {
+        if let Some(caps) = hunk_re.captures(line) {
+            let number = |index: usize, default: usize| caps.get(index).map_or(default, |m| m.as_str().parse().unwrap_or(default));
+            hunks.push((number(1, 0), number(3, 0), number(4, 1), Vec::new()));
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.3.push(line);
+        }
+    }
+
+    let mut patch = header.to_string();
+    let mut delta: i64 = 0;
+    let mut staged = 0usize;
+    for (old_start, new_start, new_count, lines) in hunks {
+        let (anchor_start, anchor_end) = if new_count == 0 { (new_start, new_start + 1) } else { (new_start, new_start + new_count - 1) };
+        let removals_selected = anchor_start <= end_line && anchor_end >= start_line;
+
+        let mut body = Vec::new();
+        let (mut old_lines, mut new_lines, mut changes) = (0usize, 0usize, 0usize);
+        let mut added_line = new_start;
+        let mut keep_previous = false;
+        for line in lines {
+            if line.starts_with('\\') {
+                if keep_previous {
+                    body.push(line.to_string());
+                }
+                continue;
+            }
+            match line.as_bytes().first() {
+                Some(b'-') if removals_selected => {
+                    body.push(line.to_string());
+                    old_lines += 1;
+                    changes += 1;
+                    keep_previous = true;
+                }
+                Some(b'-') => {
+                    body.push(format!(" {}", &line[1..]));
+                    old_lines += 1;
+                    new_lines += 1;
+                    keep_previous = true;
+                }
+                Some(b'+') => {
+                    keep_previous = (start_line..=end_line).contains(&added_line);
+                    if keep_previous {
+                        body.push(line.to_string());
+                        new_lines += 1;
+                        changes += 1;
+                    }
+                    added_line += 1;
+                }
+                _ => keep_previous = false,
+            }
+        }
+        if changes == 0 {
+            continue;
+        }
+
+        let shifted = old_start as i64 + delta;
+        let new_start = if old_lines == 0 { shifted + 1 } else if new_lines == 0 { shifted - 1 } else { shifted };
+        patch.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_lines, new_start.max(0), new_lines));
+        for line in body {
+            patch.push_str(&line);
+            patch.push('\n');
+        }
+        delta += new_lines as i64 - old_lines as i64;
+        staged += changes;
+    }
+
+    if staged == 0 {
+        bail!("no changes of {} fall within lines {}-{}", path, start_line, end_line);
+    }
+    run_git_with_input(repo, &["apply", "--cached", "--unidiff-zero", "--whitespace=nowarn", "-"], patch.as_bytes())?;
+    Ok(staged)
+}
+
 pub fn git_unstage_paths(repo: &Path, paths: &[String]) -> Result<()> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
     if paths.is_empty() {
         return Ok(());
     }
@@ -306,7 +601,7 @@ pub fn git_unstage_paths(repo: &Path, paths: &[String]) -> Result<()> {
 }
 
 pub fn git_restore_paths(repo: &Path, paths: &[String]) -> Result<()> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
     if paths.is_empty() {
         return Ok(());
     }
@@ -326,20 +621,81 @@ pub fn git_restore_paths(repo: &Path, paths: &[String]) -> Result<()> {
     Ok(())
 }
 
+/// Classifies every changed path (or those under `paths`, when given) for a discard back to HEAD.
+pub fn git_discard_plan(repo: &Path, paths: Option<&[String]>) -> Result<GitDiscardPlan> {
+    ensure_git_worktree(repo)?;
+    let out = run_git(repo, &["status", "--porcelain=v1", "-z", "--untracked-files=all"]).context("git status failed")?;
+    let text = String::from_utf8_lossy(&out);
+    let wanted = |path: &str| {
+        paths.is_none_or(|paths| {
+            paths.iter().any(|want| {
+                let want = want.trim().trim_end_matches('/');
+                path == want || path.starts_with(&format!("{want}/"))
+            })
+        })
+    };
+
+    let mut plan = GitDiscardPlan::default();
+    let mut parts = text.split('\0').filter(|part| part.len() > 3);
+    while let Some(entry) = parts.next() {
+        let (xy, path) = entry.split_at(2);
+        let path = path[1..].to_string();
+        let mut chars = xy.chars();
+        let (x, y) = (chars.next().unwrap_or(' '), chars.next().unwrap_or(' '));
+        let orig = if matches!(x, 'R' | 'C') { parts.next().map(ToOwned::to_owned) } else { None };
+        let selected = wanted(&path) || orig.as_deref().is_some_and(wanted);
+        if !selected {
+            continue;
+        }
+        if xy == "??" {
+            plan.untracked.push(path);
+            continue;
+        }
+        if matches!(x, 'A' | 'R' | 'C') || y == 'A' {
+            plan.added.push(path);
+        } else {
+            plan.restore.push(path);
+        }
+        if let Some(orig) = orig.filter(|_| x == 'R') {
+            plan.restore.push(orig);
+        }
+    }
+    Ok(plan)
+}
+
+/// Carries out a discard plan. Callers are expected to snapshot the paths first.
+pub fn git_discard(repo: &Path, plan: &GitDiscardPlan) -> Result<()> {
+    ensure_git_worktree(repo)?;
+    if !plan.restore.is_empty() {
+        let mut args = vec!["restore", "--source=HEAD", "--staged", "--worktree", "--"];
+        args.extend(plan.restore.iter().map(String::as_str));
+        run_git(repo, &args)?;
+    }
+    if !plan.added.is_empty() {
+        let mut args = vec!["rm", "-q", "-f", "--cached", "--ignore-unmatch", "--"];
+        args.extend(plan.added.iter().map(String::as_str));
+        run_git(repo, &args)?;
+    }
+    for path in plan.added.iter().chain(&plan.untracked) {
+        delete_worktree_path(repo, path)?;
+    }
+    Ok(())
+}
+
 pub fn git_stage_all(repo: &Path) -> Result<()> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
     let _ = run_git(repo, &["add", "-A"])?;
     Ok(())
 }
 
 pub fn git_unstage_all(repo: &Path) -> Result<()> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
     let _ = run_git(repo, &["restore", "--staged", "--", "."])?;
     Ok(())
 }
 
 pub fn git_commit(repo: &Path, message: &str, branch: Option<&str>) -> Result<String> {
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
     let msg = message.trim();
     if msg.is_empty() {
         bail!("commit message is empty");
@@ -366,7 +722,7 @@ pub fn git_commit(repo: &Path, message: &str, branch: Option<&str>) -> Result<St
 pub fn git_status(repo: &Path) -> Result<crate::engine::capabilities::git::types::GitStatusResult> {
     use crate::engine::capabilities::git::types::{GitStatusEntry, GitStatusResult};
 
-    ensure_git_repo(repo)?;
+    ensure_git_worktree(repo)?;
 
     let out = run_git(repo, &["status", "--porcelain=v2", "-b", "-z", "--untracked-files=all"]).context("git status failed")?;
     let s = String::from_utf8_lossy(&out);
@@ -420,6 +776,7 @@ pub fn git_status(repo: &Path) -> Result<crate::engine::capabilities::git::types
                 worktree_status: "?".to_string(),
                 staged: false,
                 untracked: true,
+                conflicted: false,
                 additions: None,
                 deletions: None,
             });
@@ -447,12 +804,34 @@ pub fn git_status(repo: &Path) -> Result<crate::engine::capabilities::git::types
                     worktree_status,
                     staged,
                     untracked,
+                    conflicted: false,
                     additions: None,
                     deletions: None,
                 });
             }
             continue;
         }
+
+        // `u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>`
+        if let Some(rest) = p.strip_prefix("u ") {
+            let fields: Vec<&str> = rest.splitn(10, ' ').collect();
+            if let [xy, .., path] = fields.as_slice() {
+                if fields.len() == 10 && !path.is_empty() {
+                    let mut xy = xy.chars();
+                    files.push(GitStatusEntry {
+                        path: path.to_string(),
+                        index_status: xy.next().unwrap_or('U').to_string(),
+                        worktree_status: xy.next().unwrap_or('U').to_string(),
+                        staged: false,
+                        untracked: false,
+                        conflicted: true,
+                        additions: None,
+                        deletions: None,
+                    });
+                }
+            }
+            continue;
+        }
     }
 
     let mut seen: HashSet<String> = HashSet::new();
@@ -475,7 +854,7 @@ pub fn git_status(repo: &Path) -> Result<crate::engine::capabilities::git::types
 
 
 pub fn show_file_at(repo: &Path, spec: &str) -> Result<Vec<u8>> {
-    run_git(repo, &["show", spec])
+    run_git(repo, &["show", "--end-of-options", spec])
 }
 
 pub fn file_history(repo: &Path, path: &str, max: usize) -> Result<Vec<u8>> {
@@ -523,6 +902,9 @@ fn list_files_recursive_fs(root: &Path) -> Result<Vec<String>> {
         )
     }
 
+    // Children are joined onto the extended root, so deep trees stay readable past MAX_PATH on Windows.
+    let root = &extended(root);
+    let root_canon = std::fs::canonicalize(root).ok();
     let mut out = Vec::new();
     let mut stack: Vec<PathBuf> = vec![root.to_path_buf()];
 
@@ -543,12 +925,24 @@ fn list_files_recursive_fs(root: &Path) -> Result<Vec<String>> {
                 Err(_) => continue,
             };
 
+            let path = ent.path();
+
+            // Symlinked files count when they resolve inside the repo; symlinked dirs are never
+            // followed, which also keeps link cycles out of the walk.
             if ft.is_symlink() {
+                let inside = std::fs::canonicalize(&path)
+                    .ok()
+                    .filter(|target| target.is_file())
+                    .zip(root_canon.as_ref())
+                    .is_some_and(|(target, root)| target.starts_with(root));
+                if inside {
+                    if let Some(s) = path.strip_prefix(root).ok().and_then(normalize_rel_path) {
+                        out.push(s);
+                    }
+                }
                 continue;
             }
 
-            let path = ent.path();
-
             if ft.is_dir() {
                 let name = ent.file_name().to_string_lossy().to_string();
                 if skip_dir(&name) {
@@ -669,21 +1063,40 @@ pub fn diff_file_between(
 }
 
 
+/// Joins `rel_path` onto the repo, refusing `..` and parent directories that are symlinks out of the repo.
+/// The final component is not resolved, so a link itself can still be deleted or moved.
 fn safe_join_repo_path(repo: &Path, rel_path: &str) -> Result<PathBuf> {
     let rel = rel_path.trim_start_matches("./").replace('\\', "/");
     if rel.contains("..") {
         bail!("refusing to access path with '..': {}", rel_path);
     }
-    Ok(repo.join(Path::new(&rel)))
+    let p = extended(&repo.join(Path::new(&rel)));
+    if let Some(parent) = p.parent() {
+        ensure_resolves_within_repo(repo, parent, rel_path)?;
+    }
+    Ok(p)
+}
+
+fn ensure_resolves_within_repo(repo: &Path, path: &Path, rel_path: &str) -> Result<()> {
+    let Ok(root) = std::fs::canonicalize(repo) else { return Ok(()) };
+    let Some(existing) = path.ancestors().find(|candidate| candidate.exists()) else { return Ok(()) };
+    let resolved = std::fs::canonicalize(existing).with_context(|| format!("failed to resolve {}", existing.display()))?;
+    if !resolved.starts_with(&root) {
+        bail!("refusing to follow a symlink out of the repository: {}", rel_path);
+    }
+    Ok(())
 }
 
 pub fn read_worktree_file(repo: &Path, rel_path: &str) -> Result<Vec<u8>> {
     let p = safe_join_repo_path(repo, rel_path)?;
+    // Reads and writes go through a final symlink, so its target has to stay in the repo too.
+    ensure_resolves_within_repo(repo, &p, rel_path)?;
     std::fs::read(&p).with_context(|| format!("failed to read {}", p.display()))
 }
 
 pub fn write_worktree_file(repo: &Path, rel_path: &str, bytes: &[u8]) -> Result<()> {
     let p = safe_join_repo_path(repo, rel_path)?;
+    ensure_resolves_within_repo(repo, &p, rel_path)?;
     if let Some(parent) = p.parent() {
         std::fs::create_dir_all(parent)
             .with_context(|| format!("failed to create dirs for {}", parent.display()))?;
@@ -699,11 +1112,15 @@ pub fn create_worktree_dir(repo: &Path, rel_path: &str) -> Result<()> {
 
 pub fn delete_worktree_path(repo: &Path, rel_path: &str) -> Result<()> {
     let p = safe_join_repo_path(repo, rel_path)?;
-    if !p.exists() {
+    // symlink_metadata so a link is removed itself, dangling or not, and its target is left alone.
+    let Ok(md) = std::fs::symlink_metadata(&p) else {
         return Ok(());
-    }
-    let md = std::fs::metadata(&p).with_context(|| format!("failed to stat {}", p.display()))?;
-    if md.is_dir() {
+    };
+    if md.file_type().is_symlink() {
+        std::fs::remove_file(&p)
+            .or_else(|_| std::fs::remove_dir(&p))
+            .with_context(|| format!("failed to remove link {}", p.display()))?;
+    } else if md.is_dir() {
         std::fs::remove_dir_all(&p)
             .with_context(|| format!("failed to remove dir {}", p.display()))?;
     } else {
@@ -824,7 +1241,7 @@ fn is_env_path(path: &str) -> bool {
     p == ".env" || p.ends_with("/.env") || p.contains("/.env.")
 }
 
-fn glob_to_regex_pattern(glob: &str) -> Option<String> {
+pub(crate) fn glob_to_regex_pattern(glob: &str) -> Option<String> {
     let mut g = glob.trim();
     if g.is_empty() {
         return None;
@@ -993,8 +1410,7 @@ fn write_file_tree(out: &mut String, paths: &[String]) {
             bytes.len(),
             CONTEXT_EXPORT_MAX_BYTES_PER_FILE
         ));
-        out.push_str(&String::from_utf8_lossy(&bytes[..CONTEXT_EXPORT_MAX_BYTES_PER_FILE]));
-        out.push_str("\n");
+        out.push_str(&truncate_head_tail(&String::from_utf8_lossy(bytes), CONTEXT_EXPORT_MAX_BYTES_PER_FILE, comment_prefix(header)));
     } else {
         out.push_str(&String::from_utf8_lossy(bytes));
         if !out.ends_with('\n') {
@@ -1217,9 +1633,7 @@ pub fn export_repo_context(
 pub fn run_git_with_input(repo: &Path, args: &[&str], stdin_bytes: &[u8]) -> Result<Vec<u8>> {
     use std::io::Write;
 
-    let mut child = Command::new("git")
-        .arg("-C")
-        .arg(repo)
+    let mut child = git_command(repo)
         .args(args)
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
@@ -1243,6 +1657,7 @@ pub fn run_git_with_input(repo: &Path, args: &[&str], stdin_bytes: &[u8]) -> Res
 }
 
 pub fn apply_git_patch(repo: &Path, patch_text: &str) -> Result<()> {
+    ensure_git_worktree(repo)?;
     let mut patch = patch_text.replace("\r\n", "\n");
     if !patch.ends_with('\n') {
         patch.push('\n');
@@ -1303,6 +1718,7 @@ pub fn apply_git_patch(repo: &Path, patch_text: &str) -> Result<()> {
 
 
 pub fn apply_git_patch_reverse(repo: &Path, patch_text: &str) -> Result<()> {
+    ensure_git_worktree(repo)?;
     let mut patch = patch_text.replace("\r\n", "\n");
     if !patch.ends_with('\n') {
         patch.push('\n');
@@ -1357,3 +1773,85 @@ pub fn apply_git_patch_reverse(repo: &Path, patch_text: &str) -> Result<()> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_repo(name: &str) -> PathBuf {
+        let repo = std::env::temp_dir().join(format!("{name}-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        repo
+    }
+
+    #[test]
+    fn remote_ops_reject_option_like_and_unknown_remotes() {
+        let repo = init_repo("git-remote");
+        let upstream = init_repo("git-remote-upstream");
+        run_git(&repo, &["remote", "add", "origin", &upstream.to_string_lossy()]).unwrap();
+        let marker = repo.join("pwned");
+        let upload_pack = format!("--upload-pack=touch {}", marker.display());
+
+        assert!(git_fetch(&repo, Some(&upload_pack), None).is_err());
+        assert!(git_pull(&repo, Some(&upload_pack), None, None).is_err());
+        assert!(git_push(&repo, Some(&upload_pack), None, None).is_err());
+        assert!(git_fetch(&repo, Some("upstream"), None).is_err());
+        assert!(git_pull(&repo, Some("origin"), Some("--rebase=interactive"), None).is_err());
+        assert!(git_push(&repo, None, Some(&upload_pack), None).is_err());
+        assert!(!marker.exists());
+
+        assert!(git_fetch(&repo, Some("origin"), None).unwrap().ok);
+        std::fs::remove_dir_all(&repo).unwrap();
+        std::fs::remove_dir_all(&upstream).unwrap();
+    }
+
+    #[test]
+    fn read_refs_resolve_to_commit_ids() {
+        let repo = init_repo("git-read-ref");
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        run_git(&repo, &["add", "a.txt"]).unwrap();
+        run_git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]).unwrap();
+        let head = String::from_utf8(run_git(&repo, &["rev-parse", "HEAD"]).unwrap()).unwrap().trim().to_string();
+
+        assert_eq!(resolve_read_ref(&repo, "HEAD").unwrap(), head);
+        assert_eq!(resolve_read_ref(&repo, " WORKTREE ").unwrap(), "WORKTREE");
+        assert_eq!(resolve_read_ref(&repo, "").unwrap(), "WORKTREE");
+        assert_eq!(resolve_read_ref(&repo.join(".git"), "").unwrap(), head);
+        assert!(resolve_read_ref(&repo, "--output=/tmp/x").is_err());
+        assert!(resolve_read_ref(&repo, "no-such-branch").is_err());
+        let tree = String::from_utf8(run_git(&repo, &["rev-parse", "HEAD^{tree}"]).unwrap()).unwrap();
+        assert!(resolve_read_ref(&repo, tree.trim()).is_err());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn push_needs_a_worktree() {
+        let repo = init_repo("git-push-bare");
+        let git_dir = repo.join(".git");
+        run_git(&repo, &["remote", "add", "origin", &repo.to_string_lossy()]).unwrap();
+        let err = git_push(&git_dir, None, None, None).unwrap_err();
+        assert!(format!("{err:#}").contains("bare repository"));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn askpass_helper_is_private_and_rewritten_when_changed() {
+        let data = crate::runtime_env::use_test_data_dir();
+        let (name, script) = ASKPASS_HELPER;
+        let path = ensure_askpass_helper().unwrap();
+        assert_eq!(path, data.join("git-askpass").join(name));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), script);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(path.parent().unwrap()), 0o700);
+            assert_eq!(mode(&path) & 0o077, 0);
+        }
+
+        std::fs::write(&path, "#!/bin/sh\necho stolen\n").unwrap();
+        assert_eq!(ensure_askpass_helper().unwrap(), path);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), script);
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+
+use super::git::{run_git, run_git_allow_fail};
+
+/// Back-off between attempts while another tool holds `index.lock`; roughly four seconds total.
+const LOCK_RETRY_DELAYS_MS: [u64; 5] = [100, 250, 500, 1000, 2000];
+/// A lock older than this with no git process attached is treated as left behind by a crash.
+pub const STALE_LOCK_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub name: String,
+    pub cmdline: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexLockInfo {
+    pub repo_ref: String,
+    pub lock_path: String,
+    pub age_secs: Option<u64>,
+    /// Running git processes that are likely to own the lock. Only detectable on Linux.
+    pub holders: Vec<LockHolder>,
+    pub stale: bool,
+}
+
+/// Returned when an index operation still hits `index.lock` after every retry.
+#[derive(Debug)]
+pub struct IndexLockContention {
+    pub info: IndexLockInfo,
+    pub stderr: String,
+}
+
+impl fmt::Display for IndexLockContention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "git index is locked by another process ({})", self.info.lock_path)
+    }
+}
+
+impl std::error::Error for IndexLockContention {}
+
+pub fn is_index_lock_error(stderr: &str) -> bool {
+    stderr.contains("index.lock") && (stderr.contains("File exists") || stderr.contains("Unable to create"))
+}
+
+/// Like `run_git_allow_fail`, but retries with back-off while the index is locked.
+pub fn run_git_index_op_allow_fail(repo: &Path, args: &[&str]) -> Result<(i32, Vec<u8>, Vec<u8>)> {
+    let mut out = run_git_allow_fail(repo, args)?;
+    for delay in LOCK_RETRY_DELAYS_MS {
+        if out.0 == 0 || !is_index_lock_error(&String::from_utf8_lossy(&out.2)) {
+            break;
+        }
+        thread::sleep(Duration::from_millis(delay));
+        out = run_git_allow_fail(repo, args)?;
+    }
+    Ok(out)
+}
+
+/// Like `run_git`, but retries while the index is locked and fails with `IndexLockContention`
+/// when the lock never clears.
+pub fn run_git_index_op(repo: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let (code, stdout, stderr) = run_git_index_op_allow_fail(repo, args)?;
+    if code == 0 {
+        return Ok(stdout);
+    }
+    let stderr = String::from_utf8_lossy(&stderr).trim().to_string();
+    if is_index_lock_error(&stderr) {
+        if let Some(info) = inspect_index_lock(repo)? {
+            return Err(IndexLockContention { info, stderr }.into());
+        }
+    }
+    bail!("git {} failed: {}", args.join(" "), stderr)
+}
+
+pub fn inspect_index_lock(repo: &Path) -> Result<Option<IndexLockInfo>> {
+    let lock_path = index_lock_path(repo)?;
+    let Ok(meta) = fs::metadata(&lock_path) else {
+        return Ok(None);
+    };
+    let age_secs = meta
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age.as_secs());
+    let holders = git_processes_in(repo);
+    let stale = holders.is_empty() && age_secs.is_some_and(|age| age >= STALE_LOCK_SECS);
+    Ok(Some(IndexLockInfo {
+        repo_ref: repo.to_string_lossy().to_string(),
+        lock_path: lock_path.to_string_lossy().to_string(),
+        age_secs,
+        holders,
+        stale,
+    }))
+}
+
+/// Deletes `index.lock` only when it passes the staleness check; returns whether one was removed.
+pub fn remove_stale_index_lock(repo: &Path) -> Result<bool> {
+    let Some(info) = inspect_index_lock(repo)? else {
+        return Ok(false);
+    };
+    if let Some(holder) = info.holders.first() {
+        bail!("{} (pid {}) is still running in this repository; let it finish first", holder.name, holder.pid);
+    }
+    if !info.stale {
+        bail!("the lock is only {}s old; wait at least {STALE_LOCK_SECS}s before removing it", info.age_secs.unwrap_or(0));
+    }
+    fs::remove_file(&info.lock_path).map_err(|err| anyhow!("failed to remove {}: {err}", info.lock_path))?;
+    Ok(true)
+}
+
+fn index_lock_path(repo: &Path) -> Result<PathBuf> {
+    let git_dir = String::from_utf8(run_git(repo, &["rev-parse", "--absolute-git-dir"])?)?;
+    Ok(PathBuf::from(git_dir.trim()).join("index.lock"))
+}
+
+#[cfg(target_os = "linux")]
+fn git_processes_in(repo: &Path) -> Vec<LockHolder> {
+    let Ok(repo) = fs::canonicalize(repo) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let dir = entry.path();
+            let name = fs::read_to_string(dir.join("comm")).ok()?.trim().to_string();
+            if !name.starts_with("git") {
+                return None;
+            }
+            let cwd = fs::read_link(dir.join("cwd")).ok()?;
+            let cmdline = fs::read(dir.join("cmdline")).ok()?;
+            let cmdline = String::from_utf8_lossy(&cmdline).replace('\0', " ").trim().to_string();
+            let repo_str = repo.to_string_lossy();
+            (cwd.starts_with(&repo) || cmdline.contains(repo_str.as_ref())).then_some(LockHolder { pid, name, cmdline })
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn git_processes_in(_repo: &Path) -> Vec<LockHolder> {
+    Vec::new()
+}
@@ -1,2 +1,3 @@
 pub mod git;
+pub mod index_lock;
 pub mod types;
@@ -5,6 +5,8 @@ pub struct GitStatusEntry {
     pub worktree_status: String,
     pub staged: bool,
     pub untracked: bool,
+    /// Unmerged path left by a merge, rebase or cherry-pick.
+    pub conflicted: bool,
     pub additions: Option<u64>,
     pub deletions: Option<u64>,
 }
@@ -17,3 +19,40 @@ pub struct GitStatusResult {
     pub behind: u32,
     pub files: Vec<GitStatusEntry>,
 }
+
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+pub struct GitCredentials {
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct GitRemoteResult {
+    pub ok: bool,
+    pub exit_code: i32,
+    pub log: String,
+    pub auth_kind: String,
+    pub needs_credentials: bool,
+    pub message: Option<String>,
+}
+
+/// What discarding a set of changes would do: tracked paths are restored from HEAD, added
+/// paths are dropped from the index and deleted, untracked paths are deleted.
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct GitDiscardPlan {
+    pub restore: Vec<String>,
+    pub added: Vec<String>,
+    pub untracked: Vec<String>,
+}
+
+impl GitDiscardPlan {
+    pub fn total(&self) -> usize {
+        self.restore.len() + self.added.len() + self.untracked.len()
+    }
+
+    pub fn all_paths(&self) -> Vec<String> {
+        self.restore.iter().chain(&self.added).chain(&self.untracked).cloned().collect()
+    }
+}
@@ -3,7 +3,12 @@ pub mod oai;
 use anyhow::Result;
 use serde_json::json;
 
-use super::{persist_inference_config, InferenceConfig, InferenceResult, InferenceTransport};
+use crate::{
+    engine::append_engine_event,
+    http_client::{build_http_client, load_network_settings},
+};
+
+use super::{persist_inference_config, scheduler::load_inference_limits, InferenceConfig, InferenceResult, InferenceTransport};
 use super::super::registry::CapabilityContext;
 
 pub async fn execute(ctx: &CapabilityContext<'_>) -> Result<serde_json::Value> {
@@ -22,13 +27,37 @@ pub async fn execute(ctx: &CapabilityContext<'_>) -> Result<serde_json::Value> {
         .and_then(|v| serde_json::from_value(v).ok())
         .unwrap_or_default();
 
-    let client = oai::OpenAIInferenceClient::from_env();
-    let (text, conversation_id) = client
+    let limits = load_inference_limits(&ctx.state.db).await;
+    let scheduler = ctx.state.inference_scheduler();
+    let queued = scheduler.enqueue(ctx.run_id);
+    if queued.position() > 1 || scheduler.snapshot().active >= limits.max_concurrent_requests {
+        append_engine_event(
+            ctx.state,
+            ctx.run_id,
+            Some(ctx.step.id.as_str()),
+            "info",
+            "inference_queued",
+            &format!("Waiting for an inference slot (queue position {}).", queued.position()),
+            json!({
+                "queue_position": queued.position(),
+                "max_concurrent_requests": limits.max_concurrent_requests,
+                "requests_per_minute": limits.requests_per_minute,
+            }),
+        )
+        .await?;
+    }
+    let _permit = queued.acquire(limits).await;
+
+    let network = load_network_settings(&ctx.state.db).await?;
+    let client = oai::OpenAIInferenceClient::from_env().with_http(build_http_client(&network)?);
+    let generation = inference_cfg.generation.for_model(&inference_cfg.model);
+    let (text, conversation_id, total_tokens) = client
         .chat_in_conversation(
             &inference_cfg.model,
             inference_cfg.conversation_id.clone(),
             Vec::new(),
             vec![("user".to_string(), prompt)],
+            &generation,
         )
         .await?;
 
@@ -40,6 +69,7 @@ pub async fn execute(ctx: &CapabilityContext<'_>) -> Result<serde_json::Value> {
         text,
         conversation_id: Some(conversation_id),
         browser_session_id: None,
+        total_tokens,
     };
 
     Ok(json!(result))
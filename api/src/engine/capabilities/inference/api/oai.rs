@@ -2,6 +2,8 @@ use anyhow::{anyhow, Context, Result};
 use reqwest::Client;
 use serde_json::{json, Value};
 
+use super::super::GenerationSettings;
+
 #[derive(Clone)]
 pub struct OpenAIInferenceClient {
     http: Client,
@@ -20,6 +22,11 @@ impl OpenAIInferenceClient {
         }
     }
 
+    pub fn with_http(mut self, http: Client) -> Self {
+        self.http = http;
+        self
+    }
+
     fn auth(&self, rb: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
         let key = self
             .api_key
@@ -28,6 +35,18 @@ impl OpenAIInferenceClient {
         Ok(rb.bearer_auth(key))
     }
 
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Lists models with the configured key, which is the cheapest call that proves the key is accepted.
+    /// Returns the HTTP status rather than failing on 4xx so callers can tell a bad key from an outage.
+    pub async fn verify_api_key(&self) -> Result<reqwest::StatusCode> {
+        let url = format!("{}/v1/models", self.base_url.trim_end_matches('/'));
+        let resp = self.auth(self.http.get(url))?.send().await.context("OpenAI /v1/models request failed")?;
+        Ok(resp.status())
+    }
+
     async fn create_conversation(&self, items: Vec<(String, String)>) -> Result<String> {
         let url = format!("{}/v1/conversations", self.base_url.trim_end_matches('/'));
         let payload_items: Vec<Value> = items
@@ -55,7 +74,8 @@ impl OpenAIInferenceClient {
         conversation_id: Option<String>,
         prior_items: Vec<(String, String)>,
         turn_items: Vec<(String, String)>,
-    ) -> Result<(String, String)> {
+        generation: &GenerationSettings,
+    ) -> Result<(String, String, Option<u64>)> {
         let conv_id = match conversation_id {
             Some(id) if !id.trim().is_empty() => id,
             _ => self.create_conversation(prior_items).await?,
@@ -67,11 +87,26 @@ impl OpenAIInferenceClient {
             .map(|(role, content)| json!({ "role": role, "content": content }))
             .collect();
 
-        let rb = self.http.post(url).json(&json!({
+        let mut payload = json!({
             "model": model,
             "conversation": conv_id,
             "input": input
-        }));
+        });
+        let body = payload.as_object_mut().expect("responses payload must be object");
+        if let Some(temperature) = generation.temperature {
+            body.insert("temperature".to_string(), json!(temperature));
+        }
+        if let Some(top_p) = generation.top_p {
+            body.insert("top_p".to_string(), json!(top_p));
+        }
+        if let Some(max_output_tokens) = generation.max_output_tokens {
+            body.insert("max_output_tokens".to_string(), json!(max_output_tokens));
+        }
+        if let Some(effort) = &generation.reasoning_effort {
+            body.insert("reasoning".to_string(), json!({ "effort": effort }));
+        }
+
+        let rb = self.http.post(url).json(&payload);
 
         let resp = self.auth(rb)?.send().await.context("OpenAI /v1/responses request failed")?;
 
@@ -102,6 +137,11 @@ impl OpenAIInferenceClient {
             }
         }
 
-        Ok((out, conv_id))
+        let total_tokens = v
+            .get("usage")
+            .and_then(|u| u.get("total_tokens"))
+            .and_then(Value::as_u64);
+
+        Ok((out, conv_id, total_tokens))
     }
 }
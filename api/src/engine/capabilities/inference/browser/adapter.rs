@@ -712,5 +712,6 @@ pub fn send_chat_and_wait(cfg: &mut BrowserConfig, text: &str) -> Result<Inferen
         }).to_string(),
         conversation_id: None,
         browser_session_id: cfg.session_id.clone(),
+        total_tokens: None,
     })
 }
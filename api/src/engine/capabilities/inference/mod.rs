@@ -1,5 +1,6 @@
 pub mod api;
 pub mod browser;
+pub mod scheduler;
 pub mod stage_support;
 
 use anyhow::Result;
@@ -86,6 +87,8 @@ pub struct InferenceConfig {
     pub conversation_id: Option<String>,
     #[serde(default)]
     pub browser: BrowserConfig,
+    #[serde(default)]
+    pub generation: GenerationSettings,
 }
 
 impl Default for InferenceConfig {
@@ -95,10 +98,50 @@ impl Default for InferenceConfig {
             model: default_model(),
             conversation_id: None,
             browser: BrowserConfig::default(),
+            generation: GenerationSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GenerationSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+}
+
+impl GenerationSettings {
+    /// Drops knobs the model rejects: reasoning models ignore sampling
+    /// parameters, and only they accept a reasoning effort.
+    pub fn for_model(&self, model: &str) -> Self {
+        let reasoning = is_reasoning_model(model);
+        Self {
+            temperature: self.temperature.filter(|_| !reasoning).map(|v| v.clamp(0.0, 2.0)),
+            top_p: self.top_p.filter(|_| !reasoning).map(|v| v.clamp(0.0, 1.0)),
+            max_output_tokens: self.max_output_tokens.filter(|v| *v > 0),
+            reasoning_effort: self
+                .reasoning_effort
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| reasoning && matches!(*v, "minimal" | "low" | "medium" | "high"))
+                .map(ToOwned::to_owned),
         }
     }
 }
 
+pub fn is_reasoning_model(model: &str) -> bool {
+    let model = model.trim().to_ascii_lowercase();
+    let model = model.rsplit('/').next().unwrap_or(&model);
+    ["o1", "o3", "o4", "gpt-5"]
+        .iter()
+        .any(|prefix| model == *prefix || model.starts_with(&format!("{}-", prefix)))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InferenceResult {
     pub transport: InferenceTransport,
@@ -108,6 +151,8 @@ pub struct InferenceResult {
     pub conversation_id: Option<String>,
     #[serde(default)]
     pub browser_session_id: Option<String>,
+    #[serde(default)]
+    pub total_tokens: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -241,6 +286,8 @@ pub async fn execute(
         .unwrap_or("")
         .to_string();
 
+    let request = build_request_record(ctx.local_state, &selected_transport, &sent_prompt, &response);
+
     let capability_ok = response_ok && (!sent_prompt.trim().is_empty() || response_text.trim().is_empty() || selected_transport != InferenceTransport::Browser);
 
     let message = if capability_ok {
@@ -260,6 +307,7 @@ pub async fn execute(
         payload: json!({
             "message": message,
             "prompt": sent_prompt,
+            "request": request,
             "result": response,
         }),
         follow_ups: if capability_ok {
@@ -274,6 +322,89 @@ pub async fn execute(
     })
 }
 
+fn approx_tokens(bytes: usize) -> usize {
+    bytes.div_ceil(4)
+}
+
+/// Text of the named section of an inference result, cut from its recorded `prompt`.
+pub fn prompt_section<'a>(payload: &'a Value, name: &str) -> Option<&'a str> {
+    let prompt = payload.get("prompt").and_then(Value::as_str)?;
+    let section = payload
+        .pointer("/request/sections")
+        .and_then(Value::as_array)?
+        .iter()
+        .find(|section| section.get("name").and_then(Value::as_str) == Some(name))?;
+    let start = section.get("start").and_then(Value::as_u64)? as usize;
+    let end = section.get("end").and_then(Value::as_u64)? as usize;
+    prompt.get(start..end)
+}
+
+/// Sizes and prompt ranges of what was sent; the prompt itself is stored once, next to this record.
+fn build_request_record(
+    local_state: &Value,
+    transport: &InferenceTransport,
+    prompt: &str,
+    response: &Value,
+) -> Value {
+    let cfg = local_state
+        .get("capabilities")
+        .and_then(|v| v.get("inference"))
+        .cloned()
+        .and_then(|v| serde_json::from_value::<InferenceConfig>(v).ok())
+        .unwrap_or_default();
+
+    let sections: Vec<Value> = local_state
+        .get("prompt_sections")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|section| {
+            let name = section.get("name").and_then(Value::as_str)?.to_string();
+            let start = section.get("start").and_then(Value::as_u64)? as usize;
+            let end = section.get("end").and_then(Value::as_u64)? as usize;
+            let bytes = end.checked_sub(start)?;
+            Some(json!({
+                "name": name,
+                "start": start,
+                "end": end,
+                "bytes": bytes,
+                "approx_tokens": approx_tokens(bytes),
+            }))
+        })
+        .collect();
+
+    let conversation_id = response
+        .get("conversation_id")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .or(cfg.conversation_id.clone());
+
+    let parameters = match transport {
+        InferenceTransport::Api => json!(cfg.generation.for_model(&cfg.model)),
+        InferenceTransport::Browser => json!({
+            "profile": cfg.browser.profile,
+            "target_url": cfg.browser.target_url,
+        }),
+    };
+
+    json!({
+        "transport": transport,
+        "model": cfg.model,
+        "conversation_id": conversation_id,
+        "parameters": parameters,
+        "messages": [{
+            "role": "user",
+            "bytes": prompt.len(),
+            "approx_tokens": approx_tokens(prompt.len()),
+        }],
+        "sections": sections,
+        "total_bytes": prompt.len(),
+        "approx_total_tokens": approx_tokens(prompt.len()),
+        "recorded_at": chrono::Utc::now().to_rfc3339(),
+    })
+}
+
 fn default_profile() -> String {
     "default".to_string()
 }
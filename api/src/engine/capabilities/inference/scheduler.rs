@@ -0,0 +1,297 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::db::load_app_settings_section;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const MAX_WAIT_SLICE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct InferenceLimits {
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    #[serde(default = "default_requests_per_minute")]
+    pub requests_per_minute: usize,
+}
+
+fn default_max_concurrent_requests() -> usize {
+    2
+}
+
+fn default_requests_per_minute() -> usize {
+    30
+}
+
+impl Default for InferenceLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: default_max_concurrent_requests(),
+            requests_per_minute: default_requests_per_minute(),
+        }
+    }
+}
+
+pub async fn load_inference_limits(db: &SqlitePool) -> InferenceLimits {
+    load_app_settings_section(db, "inference")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InferenceQueueEntry {
+    pub ticket: u64,
+    pub run_id: Uuid,
+    pub position: usize,
+    pub waiting_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InferenceQueueSnapshot {
+    pub active: usize,
+    pub active_by_run: HashMap<Uuid, usize>,
+    pub requests_last_minute: usize,
+    pub waiting: Vec<InferenceQueueEntry>,
+}
+
+struct Waiter {
+    ticket: u64,
+    run_id: Uuid,
+    queued_at: Instant,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    next_ticket: u64,
+    active: usize,
+    active_by_run: HashMap<Uuid, usize>,
+    waiting: VecDeque<Waiter>,
+    recent: VecDeque<Instant>,
+}
+
+impl SchedulerState {
+    fn prune(&mut self, now: Instant) {
+        while self.recent.front().is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW) {
+            self.recent.pop_front();
+        }
+    }
+
+    // Fair pick: the waiter whose run currently holds the fewest permits, oldest ticket first.
+    fn pick_next(&self) -> Option<u64> {
+        self.waiting
+            .iter()
+            .min_by_key(|w| (self.active_by_run.get(&w.run_id).copied().unwrap_or(0), w.ticket))
+            .map(|w| w.ticket)
+    }
+
+    fn position_of(&self, ticket: u64) -> usize {
+        let mut order: Vec<&Waiter> = self.waiting.iter().collect();
+        order.sort_by_key(|w| (self.active_by_run.get(&w.run_id).copied().unwrap_or(0), w.ticket));
+        order.iter().position(|w| w.ticket == ticket).map(|idx| idx + 1).unwrap_or(0)
+    }
+}
+
+#[derive(Default)]
+pub struct InferenceScheduler {
+    state: Mutex<SchedulerState>,
+    notify: Notify,
+}
+
+pub struct QueuedInference {
+    scheduler: Arc<InferenceScheduler>,
+    ticket: u64,
+    run_id: Uuid,
+    position: usize,
+}
+
+impl QueuedInference {
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub async fn acquire(self, limits: InferenceLimits) -> InferencePermit {
+        self.scheduler.acquire(self.ticket, self.run_id, limits).await
+    }
+}
+
+impl Drop for QueuedInference {
+    fn drop(&mut self) {
+        self.scheduler.cancel(self.ticket);
+    }
+}
+
+pub struct InferencePermit {
+    scheduler: Arc<InferenceScheduler>,
+    run_id: Uuid,
+}
+
+impl Drop for InferencePermit {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.scheduler.state.lock() {
+            state.active = state.active.saturating_sub(1);
+            if let Some(count) = state.active_by_run.get_mut(&self.run_id) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    state.active_by_run.remove(&self.run_id);
+                }
+            }
+        }
+        self.scheduler.notify.notify_waiters();
+    }
+}
+
+impl InferenceScheduler {
+    pub fn enqueue(self: &Arc<Self>, run_id: Uuid) -> QueuedInference {
+        let mut state = self.state.lock().expect("inference scheduler poisoned");
+        state.next_ticket += 1;
+        let ticket = state.next_ticket;
+        state.waiting.push_back(Waiter { ticket, run_id, queued_at: Instant::now() });
+        let position = state.position_of(ticket);
+        QueuedInference { scheduler: Arc::clone(self), ticket, run_id, position }
+    }
+
+    async fn acquire(self: &Arc<Self>, ticket: u64, run_id: Uuid, limits: InferenceLimits) -> InferencePermit {
+        let max_concurrent = limits.max_concurrent_requests.max(1);
+        let per_minute = limits.requests_per_minute.max(1);
+
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let wait = {
+                let mut state = self.state.lock().expect("inference scheduler poisoned");
+                let now = Instant::now();
+                state.prune(now);
+
+                let rate_ok = state.recent.len() < per_minute;
+                if state.active < max_concurrent && rate_ok && state.pick_next() == Some(ticket) {
+                    state.waiting.retain(|w| w.ticket != ticket);
+                    state.active += 1;
+                    *state.active_by_run.entry(run_id).or_insert(0) += 1;
+                    state.recent.push_back(now);
+                    drop(state);
+                    self.notify.notify_waiters();
+                    return InferencePermit { scheduler: Arc::clone(self), run_id };
+                }
+
+                if rate_ok {
+                    MAX_WAIT_SLICE
+                } else {
+                    state
+                        .recent
+                        .front()
+                        .map(|at| RATE_WINDOW.saturating_sub(now.duration_since(*at)))
+                        .unwrap_or(MAX_WAIT_SLICE)
+                        .min(MAX_WAIT_SLICE)
+                }
+            };
+
+            let _ = tokio::time::timeout(wait, notified).await;
+        }
+    }
+
+    fn cancel(&self, ticket: u64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.waiting.retain(|w| w.ticket != ticket);
+        }
+        self.notify.notify_waiters();
+    }
+
+    pub fn snapshot(&self) -> InferenceQueueSnapshot {
+        let mut state = self.state.lock().expect("inference scheduler poisoned");
+        let now = Instant::now();
+        state.prune(now);
+        let mut waiting: Vec<InferenceQueueEntry> = state
+            .waiting
+            .iter()
+            .map(|w| InferenceQueueEntry {
+                ticket: w.ticket,
+                run_id: w.run_id,
+                position: state.position_of(w.ticket),
+                waiting_ms: now.duration_since(w.queued_at).as_millis() as u64,
+            })
+            .collect();
+        waiting.sort_by_key(|entry| entry.position);
+        InferenceQueueSnapshot {
+            active: state.active,
+            active_by_run: state.active_by_run.clone(),
+            requests_last_minute: state.recent.len(),
+            waiting,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_concurrent_requests: usize, requests_per_minute: usize) -> InferenceLimits {
+        InferenceLimits { max_concurrent_requests, requests_per_minute }
+    }
+
+    async fn acquired_within(queued: QueuedInference, limits: InferenceLimits, wait: Duration) -> Option<InferencePermit> {
+        tokio::time::timeout(wait, queued.acquire(limits)).await.ok()
+    }
+
+    #[tokio::test]
+    async fn permits_wait_for_a_free_slot() {
+        let scheduler = Arc::new(InferenceScheduler::default());
+        let run = Uuid::new_v4();
+        let first = scheduler.enqueue(run).acquire(limits(1, 100)).await;
+        assert!(acquired_within(scheduler.enqueue(run), limits(1, 100), Duration::from_millis(100)).await.is_none());
+        assert_eq!(scheduler.snapshot().waiting.len(), 0, "a dropped ticket leaves the queue");
+
+        let queued = scheduler.enqueue(run);
+        let waiter = tokio::spawn(queued.acquire(limits(1, 100)));
+        tokio::task::yield_now().await;
+        assert_eq!(scheduler.snapshot().active, 1);
+        drop(first);
+        let second = tokio::time::timeout(Duration::from_millis(100), waiter).await.unwrap().unwrap();
+        assert_eq!(scheduler.snapshot().active_by_run.get(&run), Some(&1));
+        drop(second);
+        assert_eq!(scheduler.snapshot().active, 0);
+    }
+
+    #[tokio::test]
+    async fn the_run_holding_fewer_permits_goes_first() {
+        let scheduler = Arc::new(InferenceScheduler::default());
+        let (busy, idle) = (Uuid::new_v4(), Uuid::new_v4());
+        let freed = scheduler.enqueue(busy).acquire(limits(2, 100)).await;
+        let _kept = scheduler.enqueue(busy).acquire(limits(2, 100)).await;
+
+        let busy_again = scheduler.enqueue(busy);
+        let idle_first = scheduler.enqueue(idle);
+        assert_eq!((busy_again.position(), idle_first.position()), (1, 1));
+        let order = scheduler.snapshot().waiting.iter().map(|entry| entry.run_id).collect::<Vec<_>>();
+        assert_eq!(order, [idle, busy]);
+
+        let busy_task = tokio::spawn(busy_again.acquire(limits(2, 100)));
+        let idle_task = tokio::spawn(idle_first.acquire(limits(2, 100)));
+        tokio::task::yield_now().await;
+        drop(freed);
+        let idle_permit = tokio::time::timeout(Duration::from_millis(100), idle_task).await.unwrap().unwrap();
+        assert!(!busy_task.is_finished());
+        drop(idle_permit);
+        tokio::time::timeout(Duration::from_millis(100), busy_task).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn requests_per_minute_caps_starts_even_with_free_slots() {
+        let scheduler = Arc::new(InferenceScheduler::default());
+        let run = Uuid::new_v4();
+        drop(scheduler.enqueue(run).acquire(limits(4, 1)).await);
+        assert!(acquired_within(scheduler.enqueue(run), limits(4, 1), Duration::from_millis(100)).await.is_none());
+        assert_eq!(scheduler.snapshot().requests_last_minute, 1);
+    }
+}
@@ -4,7 +4,7 @@ use serde_json::{json, Value};
 use crate::{
     engine::{
         capabilities::{binding_specs, changeset::schema as changeset_schema, context_export},
-        stages::compose_prompt_from_state,
+        stages::compose_prompt_sections,
     },
     models::{StageExecutionNode, StageExecutionNodeKind, WorkflowStepDefinition},
 };
@@ -35,12 +35,8 @@ pub fn prepare_inference_stage_state(
             .unwrap_or_else(|| json!({})),
     );
 
-    let include_repo_context = shared_inference_primitive_enabled(
-        global_state,
-        step,
-        "repo_context",
-        step.prompt.include_repo_context,
-    );
+    let repo_context = resolve_repo_context_payload(repo_ref, global_state, step);
+    let include_repo_context = repo_context.is_some();
 
     let include_changeset_schema = shared_inference_primitive_enabled(
         global_state,
@@ -69,27 +65,17 @@ pub fn prepare_inference_stage_state(
             .remove("user_input");
     }
 
-    let repo_context = if include_repo_context {
-        let repo_context = context_export::normalize_context_export_payload(
-            resolve_context_export_state(global_state),
-            global_state.get("resources").and_then(|v| v.get("repo")).cloned(),
-            repo_ref,
-        );
-        let repo_context_fragment = build_repo_context_prompt_fragment(&repo_context);
-
+    if let Some(repo_context) = &repo_context {
         fragments
             .as_object_mut()
             .expect("prompt fragments must be object")
-            .insert("repo_context".to_string(), Value::String(repo_context_fragment));
-
-        Some(repo_context)
+            .insert("repo_context".to_string(), Value::String(build_repo_context_prompt_fragment(repo_context)));
     } else {
         fragments
             .as_object_mut()
             .expect("prompt fragments must be object")
             .remove("repo_context");
-        None
-    };
+    }
 
     let changeset_schema_fragment = if include_changeset_schema {
         global_state
@@ -133,10 +119,11 @@ pub fn prepare_inference_stage_state(
     );
     enabled_obj.insert("changeset_schema".to_string(), Value::Bool(include_changeset_schema));
 
-    let prompt = compose_prompt_from_state(&effective_enabled, &fragments, &transient_prompt_fragments);
+    let (prompt, section_ranges) = join_prompt_sections(compose_prompt_sections(&effective_enabled, &fragments, &transient_prompt_fragments));
 
     let obj = state.as_object_mut().expect("stage state must be object");
     obj.insert("composed_prompt".to_string(), Value::String(prompt));
+    obj.insert("prompt_sections".to_string(), Value::Array(section_ranges));
     obj.insert("prompt_fragment_enabled".to_string(), effective_enabled);
     obj.insert(
         "transient_prompt_fragments".to_string(),
@@ -185,6 +172,22 @@ pub fn auto_apply_enabled(step: &WorkflowStepDefinition, state: &Value) -> bool
         .unwrap_or(false)
 }
 
+/// Joins the sections into one prompt. Each section is recorded as its byte range of that prompt, so
+/// its text is stored only once.
+fn join_prompt_sections(sections: Vec<(String, String)>) -> (String, Vec<Value>) {
+    let mut prompt = String::new();
+    let mut ranges = Vec::new();
+    for (name, text) in sections {
+        if !prompt.is_empty() {
+            prompt.push_str("\n\n");
+        }
+        let start = prompt.len();
+        prompt.push_str(&text);
+        ranges.push(json!({ "name": name, "start": start, "end": prompt.len() }));
+    }
+    (prompt, ranges)
+}
+
 fn collect_active_transient_prompt_fragments(global_state: &Value) -> Vec<String> {
     global_state
         .get("capabilities")
@@ -203,6 +206,43 @@ fn collect_active_transient_prompt_fragments(global_state: &Value) -> Vec<String
         .unwrap_or_default()
 }
 
+/// The export to run before inference: the armed repo context, or else just the files an automatic apply
+/// changed since the model last saw them.
+fn resolve_repo_context_payload(repo_ref: &str, global_state: &Value, step: &WorkflowStepDefinition) -> Option<Value> {
+    let payload = if shared_inference_primitive_enabled(global_state, step, "repo_context", step.prompt.include_repo_context) {
+        resolve_context_export_state(global_state)
+    } else if binding_specs::stage_supports_shared_capability(step, "repo_context") {
+        pending_refresh_state(global_state)?
+    } else {
+        return None;
+    };
+
+    Some(context_export::normalize_context_export_payload(
+        payload,
+        global_state.get("resources").and_then(|v| v.get("repo")).cloned(),
+        repo_ref,
+    ))
+}
+
+fn pending_refresh_state(global_state: &Value) -> Option<Value> {
+    let baseline = global_state.get("capabilities").and_then(|v| v.get("context_export"))?;
+    let pending = baseline
+        .get("pending_refresh")
+        .and_then(Value::as_array)
+        .filter(|paths| !paths.is_empty())?;
+
+    let mut payload = baseline.clone();
+    let obj = payload.as_object_mut()?;
+    obj.remove("single_use_override");
+    obj.remove("last_injection");
+    obj.insert("include_files".to_string(), Value::Array(pending.clone()));
+    obj.insert("include_staged_diff".to_string(), Value::Bool(false));
+    obj.insert("include_unstaged_diff".to_string(), Value::Bool(false));
+    obj.insert("injection".to_string(), json!({ "mode": "full" }));
+    obj.insert("refresh".to_string(), Value::Bool(true));
+    Some(payload)
+}
+
 fn resolve_context_export_state(global_state: &Value) -> Value {
     let baseline = global_state
         .get("capabilities")
@@ -225,6 +265,12 @@ pub fn build_repo_context_prompt_fragment(repo_context: &Value) -> String {
         .filter(|value| !value.is_empty())
         .unwrap_or("/tmp/repo_context.txt");
 
+    if repo_context.get("refresh").and_then(Value::as_bool).unwrap_or(false) {
+        return format!(
+            "Your last changes were applied. Current copies of the files they touched are attached ({save_path}); they replace any earlier versions of those files."
+        );
+    }
+
     format!(
         "Repo context is attached as a file generated from backend export ({save_path}). Use the uploaded attachment as repository context."
     )
@@ -250,12 +296,8 @@ pub fn build_inference_execution_plan(
     local_state: &Value,
     settings: InferenceStageSettings,
 ) -> Result<Vec<StageExecutionNode>> {
-    let include_repo_context = shared_inference_primitive_enabled(
-        global_state,
-        step,
-        "repo_context",
-        step.prompt.include_repo_context,
-    );
+    let repo_context = resolve_repo_context_payload(repo_ref, global_state, step);
+    let include_repo_context = repo_context.is_some();
 
     let include_changeset_schema = shared_inference_primitive_enabled(
         global_state,
@@ -266,16 +308,6 @@ pub fn build_inference_execution_plan(
 
     let auto_apply_changeset = auto_apply_enabled(step, local_state);
 
-    let repo_context = if include_repo_context {
-        Some(context_export::normalize_context_export_payload(
-            resolve_context_export_state(global_state),
-            global_state.get("resources").and_then(|v| v.get("repo")).cloned(),
-            repo_ref,
-        ))
-    } else {
-        None
-    };
-
     build_execution_plan(
         include_repo_context,
         include_changeset_schema,
@@ -347,3 +379,22 @@ fn ensure_object(value: Value) -> Value {
         _ => json!({}),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::capabilities::inference::prompt_section;
+
+    #[test]
+    fn prompt_sections_are_byte_ranges_of_the_prompt() {
+        let sections = [("user_input", "Fix the café menu"), ("repo_context", "src/menu.rs ✓"), ("transient_1", "Be brief")];
+        let (prompt, ranges) = join_prompt_sections(sections.iter().map(|(name, text)| (name.to_string(), text.to_string())).collect());
+        assert_eq!(prompt, "Fix the café menu\n\nsrc/menu.rs ✓\n\nBe brief");
+        assert!(ranges.iter().all(|range| range.get("text").is_none()));
+
+        let result = json!({ "prompt": prompt, "request": { "sections": ranges } });
+        for (name, text) in sections {
+            assert_eq!(prompt_section(&result, name), Some(text));
+        }
+    }
+}
@@ -1,6 +1,9 @@
 pub mod registry;
+pub mod analysis;
 pub mod binding_specs;
+pub mod broker;
 pub mod context_export;
+pub mod context_injection;
 pub mod changeset;
 pub mod compile_commands;
 pub mod filesystem;
@@ -8,6 +11,7 @@ pub mod git;
 pub mod git_patch_payload;
 pub mod inference;
 pub mod sap;
+pub mod trace;
 
 pub use registry::{
     CapabilityContext,
@@ -6,7 +6,7 @@ use uuid::Uuid;
 
 use crate::{app_state::AppState, engine::{append_engine_event, event_meta, governance, load_run, persist_context}, models::{StageExecutionNodeKind, WorkflowStepDefinition}};
 
-use super::{changeset, compile_commands, context_export, git_patch_payload, inference, sap};
+use super::{changeset, compile_commands, context_export, git_patch_payload, inference, sap, trace};
 
 #[derive(Debug, Clone)]
 pub struct StageCapabilityPolicy {
@@ -187,7 +187,24 @@ pub(crate) async fn execute_capability_chain(
         )
         .await?;
 
-        let result = match dispatch(&ctx, policy, &results, invocation.clone()).await {
+        let dispatched = dispatch(&ctx, policy, &results, invocation.clone()).await;
+        trace::record(
+            &ctx.state.db,
+            trace::TraceRecord {
+                run_id: ctx.run_id,
+                step_id: ctx.step.id.as_str(),
+                capability: invocation.capability.as_str(),
+                invocation_id: capability_invocation_id.as_str(),
+                ok: dispatched.as_ref().is_ok_and(|result| result.ok),
+                duration_ms: u64::try_from(capability_started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+                request: &invocation.config,
+                response: dispatched.as_ref().map(|result| &result.payload).unwrap_or(&Value::Null),
+                error: dispatched.as_ref().err().map(ToString::to_string),
+            },
+        )
+        .await;
+
+        let result = match dispatched {
             Ok(result) => {
                 tracing::info!(
                     run_id = %ctx.run_id,
@@ -0,0 +1,140 @@
+use std::{
+    collections::VecDeque,
+    io::Write,
+    sync::{Mutex, OnceLock},
+};
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::load_app_settings_section;
+
+/// Traces kept in memory; the optional JSONL file keeps everything.
+const MAX_TRACES: usize = 200;
+/// Responses above this are kept as a preview in memory (inference output can be large). The file gets them whole.
+const MAX_STORED_RESPONSE_BYTES: usize = 64 * 1024;
+
+static TRACES: OnceLock<Mutex<Traces>> = OnceLock::new();
+
+#[derive(Default)]
+struct Traces {
+    next_id: u64,
+    entries: VecDeque<CapabilityTrace>,
+}
+
+/// One capability dispatch: the config it was called with and what came back.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityTrace {
+    pub id: u64,
+    pub at: String,
+    pub run_id: Uuid,
+    pub step_id: String,
+    pub capability: String,
+    pub invocation_id: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    pub request_bytes: usize,
+    pub response_bytes: usize,
+    pub request: Value,
+    pub response: Value,
+    pub response_truncated: bool,
+    pub error: Option<String>,
+}
+
+pub struct TraceRecord<'a> {
+    pub run_id: Uuid,
+    pub step_id: &'a str,
+    pub capability: &'a str,
+    pub invocation_id: &'a str,
+    pub ok: bool,
+    pub duration_ms: u64,
+    pub request: &'a Value,
+    pub response: &'a Value,
+    pub error: Option<String>,
+}
+
+fn traces() -> &'static Mutex<Traces> {
+    TRACES.get_or_init(|| Mutex::new(Traces::default()))
+}
+
+/// Path from the `debug.capability_trace_file` setting; empty means memory only.
+async fn trace_file(db: &SqlitePool) -> Option<String> {
+    let section = load_app_settings_section(db, "debug").await.ok().flatten()?;
+    let path = section.get("capability_trace_file").and_then(Value::as_str)?.trim();
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+pub async fn record(db: &SqlitePool, record: TraceRecord<'_>) {
+    let request_bytes = serde_json::to_vec(record.request).map(|bytes| bytes.len()).unwrap_or(0);
+    let response_text = serde_json::to_string(record.response).unwrap_or_default();
+    let mut trace = CapabilityTrace {
+        id: 0,
+        at: Utc::now().to_rfc3339(),
+        run_id: record.run_id,
+        step_id: record.step_id.to_string(),
+        capability: record.capability.to_string(),
+        invocation_id: record.invocation_id.to_string(),
+        ok: record.ok,
+        duration_ms: record.duration_ms,
+        request_bytes,
+        response_bytes: response_text.len(),
+        request: record.request.clone(),
+        response: record.response.clone(),
+        response_truncated: false,
+        error: record.error,
+    };
+
+    if let Some(path) = trace_file(db).await {
+        if let Err(err) = append_line(&path, &trace) {
+            tracing::warn!(path = %path, error = %err, "could not append capability trace");
+        }
+    }
+
+    if response_text.len() > MAX_STORED_RESPONSE_BYTES {
+        let mut cut = MAX_STORED_RESPONSE_BYTES;
+        while !response_text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        trace.response = json!({ "preview": &response_text[..cut] });
+        trace.response_truncated = true;
+    }
+
+    let mut traces = traces().lock().unwrap_or_else(|err| err.into_inner());
+    traces.next_id += 1;
+    trace.id = traces.next_id;
+    traces.entries.push_back(trace);
+    while traces.entries.len() > MAX_TRACES {
+        traces.entries.pop_front();
+    }
+}
+
+fn append_line(path: &str, trace: &CapabilityTrace) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    let mut line = serde_json::to_vec(trace)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+/// Newest first, optionally only one run's.
+pub fn list(run_id: Option<Uuid>) -> Vec<CapabilityTrace> {
+    let traces = traces().lock().unwrap_or_else(|err| err.into_inner());
+    traces
+        .entries
+        .iter()
+        .rev()
+        .filter(|trace| run_id.is_none_or(|run_id| trace.run_id == run_id))
+        .cloned()
+        .collect()
+}
+
+pub fn get(id: u64) -> Option<CapabilityTrace> {
+    let traces = traces().lock().unwrap_or_else(|err| err.into_inner());
+    traces.entries.iter().find(|trace| trace.id == id).cloned()
+}
+
+pub fn clear() {
+    traces().lock().unwrap_or_else(|err| err.into_inner()).entries.clear();
+}
@@ -0,0 +1,304 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::engine::capabilities::git::git::{resolve_read_ref, run_git};
+
+pub const CODE_REVIEWS_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS code_reviews (
+    id TEXT PRIMARY KEY,
+    repo_ref TEXT NOT NULL,
+    base TEXT NOT NULL,
+    head TEXT NOT NULL,
+    viewed_json TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+)
+"#;
+
+pub const CODE_REVIEW_COMMENTS_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS code_review_comments (
+    id TEXT PRIMARY KEY,
+    review_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    side TEXT NOT NULL,
+    line_text TEXT NOT NULL DEFAULT '',
+    body TEXT NOT NULL,
+    created_at TEXT NOT NULL
+)
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub id: String,
+    pub path: String,
+    pub line: i64,
+    /// `new` for added/context lines (head numbering), `old` for removed lines (base numbering).
+    pub side: String,
+    /// The anchored line as it appeared in the diff, kept so exports still make sense later.
+    pub line_text: String,
+    pub body: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeReview {
+    pub id: String,
+    pub repo_ref: String,
+    pub base: String,
+    pub head: String,
+    pub viewed: Vec<String>,
+    pub comments: Vec<ReviewComment>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewReviewComment {
+    pub path: String,
+    pub line: i64,
+    #[serde(default = "default_side")]
+    pub side: String,
+    #[serde(default)]
+    pub line_text: String,
+    pub body: String,
+}
+
+fn default_side() -> String {
+    "new".to_string()
+}
+
+/// Reviews are keyed by repository and the commits both refs resolve to, so reopening the same range
+/// resumes it. Only the commit ids are stored; client refs never reach git again.
+pub async fn open(db: &SqlitePool, repo_ref: &str, base: &str, head: &str) -> Result<CodeReview> {
+    if base.trim().is_empty() || head.trim().is_empty() {
+        bail!("base and head refs are required");
+    }
+    let (base, head) = {
+        let (repo_ref, base, head) = (repo_ref.to_string(), base.to_string(), head.to_string());
+        tokio::task::spawn_blocking(move || -> Result<_> {
+            let repo = Path::new(&repo_ref);
+            Ok((resolve_review_commit(repo, &base)?, resolve_review_commit(repo, &head)?))
+        })
+        .await??
+    };
+    let existing = sqlx::query("SELECT id FROM code_reviews WHERE repo_ref = ? AND base = ? AND head = ?")
+        .bind(repo_ref)
+        .bind(&base)
+        .bind(&head)
+        .fetch_optional(db)
+        .await?;
+    if let Some(row) = existing {
+        return load(db, row.get::<String, _>("id").as_str()).await;
+    }
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    sqlx::query("INSERT INTO code_reviews (id, repo_ref, base, head, viewed_json, created_at, updated_at) VALUES (?, ?, ?, ?, '[]', ?, ?)")
+        .bind(&id)
+        .bind(repo_ref)
+        .bind(&base)
+        .bind(&head)
+        .bind(&now)
+        .bind(&now)
+        .execute(db)
+        .await?;
+    load(db, &id).await
+}
+
+/// A review side as a commit id; the worktree has no commit to review against.
+pub fn resolve_review_commit(repo: &Path, git_ref: &str) -> Result<String> {
+    let commit = resolve_read_ref(repo, git_ref)?;
+    if commit == "WORKTREE" {
+        bail!("a review compares commits; {} is not one", git_ref.trim());
+    }
+    Ok(commit)
+}
+
+/// Files that differ between the merge base of the review's two commits and its head.
+pub fn changed_files(review: &CodeReview) -> Result<Vec<String>> {
+    let repo = Path::new(&review.repo_ref);
+    let range = format!("{}...{}", resolve_review_commit(repo, &review.base)?, resolve_review_commit(repo, &review.head)?);
+    let out = run_git(repo, &["diff", "--name-only", "--end-of-options", &range])?;
+    Ok(String::from_utf8_lossy(&out).lines().map(str::to_string).collect())
+}
+
+pub async fn list(db: &SqlitePool, repo_ref: &str) -> Result<Vec<CodeReview>> {
+    let rows = sqlx::query("SELECT * FROM code_reviews WHERE repo_ref = ? ORDER BY updated_at DESC")
+        .bind(repo_ref)
+        .fetch_all(db)
+        .await?;
+    let mut reviews = Vec::with_capacity(rows.len());
+    for row in rows {
+        reviews.push(with_comments(db, &row).await?);
+    }
+    Ok(reviews)
+}
+
+pub async fn load(db: &SqlitePool, id: &str) -> Result<CodeReview> {
+    let row = sqlx::query("SELECT * FROM code_reviews WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| anyhow!("review {id} not found"))?;
+    with_comments(db, &row).await
+}
+
+pub async fn delete(db: &SqlitePool, id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM code_review_comments WHERE review_id = ?").bind(id).execute(db).await?;
+    sqlx::query("DELETE FROM code_reviews WHERE id = ?").bind(id).execute(db).await?;
+    Ok(())
+}
+
+pub async fn set_viewed(db: &SqlitePool, id: &str, path: &str, viewed: bool) -> Result<CodeReview> {
+    let mut review = load(db, id).await?;
+    review.viewed.retain(|entry| entry != path);
+    if viewed {
+        review.viewed.push(path.to_string());
+        review.viewed.sort();
+    }
+    sqlx::query("UPDATE code_reviews SET viewed_json = ?, updated_at = ? WHERE id = ?")
+        .bind(serde_json::to_string(&review.viewed)?)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(db)
+        .await?;
+    load(db, id).await
+}
+
+pub async fn add_comment(db: &SqlitePool, id: &str, comment: NewReviewComment) -> Result<CodeReview> {
+    if comment.body.trim().is_empty() {
+        bail!("comment body is required");
+    }
+    if comment.side != "new" && comment.side != "old" {
+        bail!("side must be new or old");
+    }
+    load(db, id).await?;
+    let now = Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO code_review_comments (id, review_id, path, line, side, line_text, body, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(id)
+    .bind(&comment.path)
+    .bind(comment.line)
+    .bind(&comment.side)
+    .bind(&comment.line_text)
+    .bind(comment.body.trim())
+    .bind(&now)
+    .execute(db)
+    .await?;
+    touch(db, id, &now).await?;
+    load(db, id).await
+}
+
+pub async fn delete_comment(db: &SqlitePool, id: &str, comment_id: &str) -> Result<CodeReview> {
+    sqlx::query("DELETE FROM code_review_comments WHERE id = ? AND review_id = ?")
+        .bind(comment_id)
+        .bind(id)
+        .execute(db)
+        .await?;
+    touch(db, id, &Utc::now().to_rfc3339()).await?;
+    load(db, id).await
+}
+
+/// Markdown suitable for pasting into a pull request review, grouped by file in line order.
+/// `files` is the current list of changed paths, used for the progress line.
+pub fn export_markdown(review: &CodeReview, files: &[String]) -> String {
+    let viewed = files.iter().filter(|path| review.viewed.contains(path)).count();
+    let mut out = format!("## Review of `{}...{}`\n\n", review.base, review.head);
+    out.push_str(&format!("Viewed {viewed} of {} files, {} comments.\n", files.len(), review.comments.len()));
+
+    let mut by_path: BTreeMap<&str, Vec<&ReviewComment>> = BTreeMap::new();
+    for comment in &review.comments {
+        by_path.entry(comment.path.as_str()).or_default().push(comment);
+    }
+    for (path, mut comments) in by_path {
+        comments.sort_by_key(|comment| comment.line);
+        out.push_str(&format!("\n### `{path}`\n"));
+        for comment in comments {
+            let anchor = if comment.side == "old" { format!("L{} (removed)", comment.line) } else { format!("L{}", comment.line) };
+            out.push_str(&format!("\n**{anchor}**\n"));
+            if !comment.line_text.trim().is_empty() {
+                out.push_str(&format!("\n```\n{}\n```\n", comment.line_text.trim_end()));
+            }
+            out.push_str(&format!("\n{}\n", comment.body.trim()));
+        }
+    }
+    out
+}
+
+async fn touch(db: &SqlitePool, id: &str, now: &str) -> Result<()> {
+    sqlx::query("UPDATE code_reviews SET updated_at = ? WHERE id = ?").bind(now).bind(id).execute(db).await?;
+    Ok(())
+}
+
+async fn with_comments(db: &SqlitePool, row: &SqliteRow) -> Result<CodeReview> {
+    let id: String = row.get("id");
+    let comments = sqlx::query("SELECT * FROM code_review_comments WHERE review_id = ? ORDER BY path, line, created_at")
+        .bind(&id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|comment| ReviewComment {
+            id: comment.get("id"),
+            path: comment.get("path"),
+            line: comment.get("line"),
+            side: comment.get("side"),
+            line_text: comment.get("line_text"),
+            body: comment.get("body"),
+            created_at: comment.get("created_at"),
+        })
+        .collect();
+    Ok(CodeReview {
+        viewed: serde_json::from_str(row.get::<String, _>("viewed_json").as_str()).context("failed to decode viewed files")?,
+        id,
+        repo_ref: row.get("repo_ref"),
+        base: row.get("base"),
+        head: row.get("head"),
+        comments,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(repo: &Path, name: &str) -> String {
+        std::fs::write(repo.join(name), name).unwrap();
+        run_git(repo, &["add", name]).unwrap();
+        run_git(repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", name]).unwrap();
+        String::from_utf8(run_git(repo, &["rev-parse", "HEAD"]).unwrap()).unwrap().trim().to_string()
+    }
+
+    #[tokio::test]
+    async fn reviews_store_commit_ids_and_surface_diff_errors() {
+        let db = crate::db::test_db_with_settings(serde_json::json!({})).await;
+        let repo = std::env::temp_dir().join(format!("code-review-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        let base = commit(&repo, "a.txt");
+        run_git(&repo, &["branch", "base"]).unwrap();
+        let head = commit(&repo, "b.txt");
+        let repo_ref = repo.to_string_lossy().to_string();
+
+        let review = open(&db, &repo_ref, "base", "HEAD").await.unwrap();
+        assert_eq!((review.base.as_str(), review.head.as_str()), (base.as_str(), head.as_str()));
+        assert_eq!(open(&db, &repo_ref, &base, &head).await.unwrap().id, review.id);
+        assert_eq!(changed_files(&review).unwrap(), ["b.txt"]);
+
+        let written = repo.join("written");
+        let option = format!("--output={}", written.display());
+        assert!(open(&db, &repo_ref, &option, "HEAD").await.is_err());
+        assert!(open(&db, &repo_ref, "base", "WORKTREE").await.is_err());
+        assert!(changed_files(&CodeReview { base: option, ..review }).is_err());
+        assert!(!written.exists());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::app_state::AppState;
+
+use super::{append_engine_event, ensure_engine_root, ensure_value_object, load_run, persist_context};
+
+/// Longest transcript carried into the next prompt; older turns are dropped first.
+const MAX_SEED_CHARS: usize = 60_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedMessage {
+    /// `user` or `assistant`; system and tool messages are skipped.
+    pub role: String,
+    pub text: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedConversation {
+    pub source: String,
+    pub title: String,
+    pub messages: Vec<ImportedMessage>,
+}
+
+/// Accepts a ChatGPT or Claude `conversations.json` export (or a single conversation from one).
+pub fn parse_export(export: &Value) -> Result<Vec<ImportedConversation>> {
+    let items = match export {
+        Value::Array(items) => items.iter().collect::<Vec<_>>(),
+        Value::Object(_) => vec![export],
+        _ => bail!("expected a conversations.json export"),
+    };
+    let conversations = items
+        .into_iter()
+        .filter_map(|item| {
+            if item.get("mapping").is_some() {
+                Some(parse_chatgpt(item))
+            } else if item.get("chat_messages").is_some() {
+                Some(parse_claude(item))
+            } else {
+                None
+            }
+        })
+        .filter(|conversation| !conversation.messages.is_empty())
+        .collect::<Vec<_>>();
+    if conversations.is_empty() {
+        bail!("no ChatGPT or Claude conversations found in the export");
+    }
+    Ok(conversations)
+}
+
+/// ChatGPT stores a message tree; the visible thread is the path from `current_node` to the root. A
+/// `parent` link back to a node already walked ends the thread, so a cyclic export can't loop forever.
+fn parse_chatgpt(conversation: &Value) -> ImportedConversation {
+    let mapping = conversation.get("mapping").and_then(Value::as_object);
+    let mut node_id = conversation
+        .get("current_node")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| {
+            mapping.and_then(|nodes| {
+                nodes
+                    .iter()
+                    .find(|(_, node)| node.get("children").and_then(Value::as_array).is_none_or(Vec::is_empty))
+                    .map(|(id, _)| id.clone())
+            })
+        });
+    let mut messages = Vec::new();
+    let mut visited = HashSet::new();
+    while let Some(node) = node_id
+        .filter(|id| visited.insert(id.clone()))
+        .and_then(|id| mapping.and_then(|nodes| nodes.get(&id)))
+    {
+        if let Some(message) = node.get("message").filter(|message| !message.is_null()) {
+            let role = message.pointer("/author/role").and_then(Value::as_str).unwrap_or_default();
+            let content = message.get("content").cloned().unwrap_or_default();
+            let text = match content.get("content_type").and_then(Value::as_str) {
+                Some("code") => {
+                    let language = content.get("language").and_then(Value::as_str).filter(|lang| *lang != "unknown").unwrap_or("");
+                    format!("```{language}\n{}\n```", content.get("text").and_then(Value::as_str).unwrap_or_default())
+                }
+                _ => content
+                    .get("parts")
+                    .and_then(Value::as_array)
+                    .map(|parts| parts.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("\n"))
+                    .unwrap_or_default(),
+            };
+            if let Some(role) = map_role(role) {
+                if !text.trim().is_empty() {
+                    let created_at = message
+                        .get("create_time")
+                        .and_then(Value::as_f64)
+                        .and_then(|secs| chrono::DateTime::from_timestamp(secs as i64, 0))
+                        .map(|at| at.to_rfc3339());
+                    messages.push(ImportedMessage { role: role.to_string(), text, created_at });
+                }
+            }
+        }
+        node_id = node.get("parent").and_then(Value::as_str).map(str::to_string);
+    }
+    messages.reverse();
+    ImportedConversation {
+        source: "chatgpt".to_string(),
+        title: title_of(conversation, "title"),
+        messages,
+    }
+}
+
+fn parse_claude(conversation: &Value) -> ImportedConversation {
+    let messages = conversation
+        .get("chat_messages")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|message| {
+                    let role = map_role(message.get("sender").and_then(Value::as_str).unwrap_or_default())?;
+                    let blocks = message
+                        .get("content")
+                        .and_then(Value::as_array)
+                        .map(|blocks| {
+                            blocks
+                                .iter()
+                                .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+                                .filter_map(|block| block.get("text").and_then(Value::as_str))
+                                .collect::<Vec<_>>()
+                                .join("\n\n")
+                        })
+                        .unwrap_or_default();
+                    let text = if blocks.trim().is_empty() {
+                        message.get("text").and_then(Value::as_str).unwrap_or_default().to_string()
+                    } else {
+                        blocks
+                    };
+                    (!text.trim().is_empty()).then(|| ImportedMessage {
+                        role: role.to_string(),
+                        text,
+                        created_at: message.get("created_at").and_then(Value::as_str).map(str::to_string),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    ImportedConversation {
+        source: "claude".to_string(),
+        title: title_of(conversation, "name"),
+        messages,
+    }
+}
+
+fn map_role(role: &str) -> Option<&'static str> {
+    match role {
+        "user" | "human" => Some("user"),
+        "assistant" => Some("assistant"),
+        _ => None,
+    }
+}
+
+fn title_of(conversation: &Value, key: &str) -> String {
+    conversation
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .unwrap_or("Untitled conversation")
+        .to_string()
+}
+
+/// Records the conversation in the run transcript and, unless `seed_prompt` is false, queues it
+/// as context for the next inference turn so the loop picks up where the chat left off.
+pub async fn import_conversation(state: &AppState, run_id: Uuid, payload: Value) -> Result<Value> {
+    let conversation: ImportedConversation = serde_json::from_value(
+        payload.get("conversation").cloned().ok_or_else(|| anyhow!("conversation is required"))?,
+    )?;
+    if conversation.messages.is_empty() {
+        bail!("the conversation has no messages");
+    }
+    let seed_prompt = payload.get("seed_prompt").and_then(Value::as_bool).unwrap_or(true);
+
+    let mut run = load_run(state, run_id).await?;
+    if seed_prompt {
+        let root = ensure_engine_root(&mut run.context);
+        let global_state = ensure_value_object(root.entry("global_state".to_string()).or_insert_with(|| json!({})));
+        let capabilities = ensure_value_object(global_state.entry("capabilities".to_string()).or_insert_with(|| json!({})));
+        let inference = ensure_value_object(capabilities.entry("inference".to_string()).or_insert_with(|| json!({})));
+        let next = inference
+            .entry("next_prompt_fragments".to_string())
+            .or_insert_with(|| json!([]));
+        if !next.is_array() {
+            *next = json!([]);
+        }
+        next.as_array_mut()
+            .expect("next_prompt_fragments must be array")
+            .push(json!({
+                "text": seed_text(&conversation),
+                "source": "conversation_import",
+            }));
+        persist_context(state, run_id, &run.context).await?;
+    }
+
+    append_engine_event(
+        state,
+        run_id,
+        run.current_step_id.as_deref(),
+        "info",
+        "conversation_imported",
+        &format!("Imported {} messages from {} conversation \"{}\".", conversation.messages.len(), conversation.source, conversation.title),
+        json!({
+            "source": conversation.source,
+            "title": conversation.title,
+            "messages": conversation.messages,
+            "seeded_prompt": seed_prompt,
+        }),
+    )
+    .await?;
+
+    Ok(json!({ "ok": true, "run_id": run_id, "messages": conversation.messages.len(), "seeded_prompt": seed_prompt }))
+}
+
+fn seed_text(conversation: &ImportedConversation) -> String {
+    let mut turns = Vec::new();
+    let mut used = 0;
+    for message in conversation.messages.iter().rev() {
+        let turn = format!("#### {}\n{}", if message.role == "user" { "USER" } else { "ASSISTANT" }, message.text.trim());
+        if used + turn.len() > MAX_SEED_CHARS && !turns.is_empty() {
+            break;
+        }
+        used += turn.len();
+        turns.push(turn);
+    }
+    turns.reverse();
+    let omitted = conversation.messages.len() - turns.len();
+    format!(
+        "### IMPORTED CONVERSATION ({} \"{}\")\n{}{}\n\nContinue this work using the repository context provided.",
+        conversation.source,
+        conversation.title,
+        if omitted > 0 { format!("({omitted} earlier messages omitted)\n\n") } else { String::new() },
+        turns.join("\n\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(parent: &str, role: &str, text: &str) -> Value {
+        json!({ "parent": parent, "message": { "author": { "role": role }, "content": { "parts": [text] } } })
+    }
+
+    #[test]
+    fn chatgpt_threads_stop_at_a_parent_cycle() {
+        let conversation = json!({
+            "title": "cycle",
+            "current_node": "c",
+            "mapping": {
+                "a": node("c", "user", "first"),
+                "b": node("a", "assistant", "second"),
+                "c": node("b", "user", "third"),
+            },
+        });
+        let parsed = parse_chatgpt(&conversation);
+        let texts = parsed.messages.iter().map(|message| message.text.as_str()).collect::<Vec<_>>();
+        assert_eq!(texts, ["first", "second", "third"]);
+    }
+}
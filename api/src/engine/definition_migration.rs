@@ -0,0 +1,99 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Map, Value};
+
+use crate::models::WorkflowTemplateDefinition;
+
+pub const CURRENT_DEFINITION_VERSION: u32 = 1;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`. Steps only touch the
+/// keys they know about, so fields added by other tools pass through unchanged.
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+/// Documents written before the `version` field existed. `steps` was the only required key.
+fn migrate_v0_to_v1(doc: &mut Map<String, Value>) -> Result<()> {
+    if !doc.get("steps").is_some_and(Value::is_array) {
+        bail!("definition has no 'steps' array");
+    }
+    doc.entry("globals").or_insert_with(|| json!({}));
+    Ok(())
+}
+
+/// Upgrades a raw workflow definition to the current version, step by step.
+pub fn migrate_definition_value(value: Value) -> Result<Value> {
+    let Value::Object(mut doc) = value else {
+        bail!("workflow definition must be a JSON object");
+    };
+    let mut version = match doc.get("version") {
+        None | Some(Value::Null) => 0,
+        Some(raw) => raw
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("invalid definition version {raw}"))?,
+    };
+    if version > CURRENT_DEFINITION_VERSION {
+        bail!("definition version {version} was written by a newer release (this build supports up to {CURRENT_DEFINITION_VERSION})");
+    }
+    while version < CURRENT_DEFINITION_VERSION {
+        MIGRATIONS[version as usize](&mut doc).with_context(|| format!("migrating definition from v{version} to v{}", version + 1))?;
+        version += 1;
+        doc.insert("version".to_string(), json!(version));
+    }
+    Ok(Value::Object(doc))
+}
+
+/// Parses stored definition JSON, migrating older versions first.
+pub fn parse_definition(raw: &str) -> Result<WorkflowTemplateDefinition> {
+    let value: Value = serde_json::from_str(raw).context("definition is not valid JSON")?;
+    parse_definition_value(value)
+}
+
+pub fn parse_definition_value(value: Value) -> Result<WorkflowTemplateDefinition> {
+    let migrated = migrate_definition_value(value)?;
+    serde_json::from_value(migrated).context("definition does not match the current schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_unversioned_documents() {
+        let migrated = migrate_definition_value(json!({ "steps": [] })).unwrap();
+        assert_eq!(migrated, json!({ "version": CURRENT_DEFINITION_VERSION, "steps": [], "globals": {} }));
+    }
+
+    #[test]
+    fn keeps_unknown_fields() {
+        let migrated = migrate_definition_value(json!({
+            "steps": [{ "id": "compile", "x_editor": { "color": "red" } }],
+            "globals": { "retries": 2 },
+            "x_tool": "kept",
+        }))
+        .unwrap();
+        assert_eq!(migrated["x_tool"], "kept");
+        assert_eq!(migrated["steps"][0]["x_editor"], json!({ "color": "red" }));
+        assert_eq!(migrated["globals"], json!({ "retries": 2 }));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let err = migrate_definition_value(json!({ "version": CURRENT_DEFINITION_VERSION + 1, "steps": [] })).unwrap_err();
+        assert!(err.to_string().contains("newer release"), "{err:#}");
+    }
+
+    #[test]
+    fn rejects_non_objects() {
+        let err = migrate_definition_value(json!([{ "steps": [] }])).unwrap_err();
+        assert!(err.to_string().contains("must be a JSON object"), "{err:#}");
+    }
+
+    #[test]
+    fn requires_a_steps_array() {
+        for doc in [json!({}), json!({ "steps": {} })] {
+            let err = migrate_definition_value(doc).unwrap_err();
+            assert!(format!("{err:#}").contains("no 'steps' array"), "{err:#}");
+        }
+    }
+}
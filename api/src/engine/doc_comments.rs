@@ -0,0 +1,315 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    engine::{
+        ai_actions::{complete, truncate_chars},
+        capabilities::{changeset::apply::preview_changeset_edits, git::git::run_git_allow_fail, inference::InferenceConfig},
+    },
+};
+
+const MAX_FILE_CHARS: usize = 60_000;
+
+const DOC_COMMENT_INSTRUCTIONS: &str = "You write API documentation. For each numbered function, write a concise doc \
+comment: one summary sentence, then only the details a caller needs (arguments, return value, errors, panics). \
+Reply with JSON only, shaped {\"docs\":[{\"id\":1,\"doc\":\"...\"}]}. The doc is plain text without comment markers \
+or quotes; use \\n for line breaks. Omit functions you cannot describe accurately.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocLanguage {
+    Rust,
+    Python,
+}
+
+impl DocLanguage {
+    fn for_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("rs") => Some(Self::Rust),
+            Some("py" | "pyi") => Some(Self::Python),
+            _ => None,
+        }
+    }
+}
+
+/// A public function without documentation and where its comment goes.
+#[derive(Debug, Clone)]
+struct UndocumentedFn {
+    name: String,
+    signature: String,
+    /// 1-based line the doc is anchored to: inserted before it for Rust, after it for Python.
+    anchor_line: usize,
+    anchor_text: String,
+    indent: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocCommentProposal {
+    pub path: String,
+    /// Edit-only changeset ready for the regular changeset applier.
+    pub payload_text: String,
+    /// Unified diff of what applying `payload_text` would change.
+    pub patch: String,
+    pub documented: Vec<String>,
+    pub model: String,
+    pub total_tokens: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDocs {
+    #[serde(default)]
+    docs: Vec<ModelDoc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelDoc {
+    id: usize,
+    doc: String,
+}
+
+/// Asks the model for docs on the undocumented public functions in `path` (or just `symbol`)
+/// and returns them as anchored insert operations plus a preview diff. Nothing is written.
+pub async fn propose(
+    state: &AppState,
+    config: &InferenceConfig,
+    repo: &Path,
+    path: &str,
+    symbol: Option<&str>,
+) -> Result<DocCommentProposal> {
+    let language = DocLanguage::for_path(path).context("doc comments are supported for Rust and Python files")?;
+    let text = fs::read_to_string(repo.join(path))
+        .with_context(|| format!("failed to read {path}"))?
+        .replace("\r\n", "\n");
+    let symbol = symbol.map(str::trim).filter(|symbol| !symbol.is_empty());
+    let targets: Vec<UndocumentedFn> = undocumented_functions(&text, language)
+        .into_iter()
+        .filter(|target| symbol.is_none_or(|symbol| target.name == symbol))
+        .collect();
+    if targets.is_empty() {
+        match symbol {
+            Some(symbol) => bail!("{symbol} is not an undocumented public function in {path}"),
+            None => bail!("no undocumented public functions in {path}"),
+        }
+    }
+
+    let (source, _) = truncate_chars(&text, MAX_FILE_CHARS);
+    let listing = targets
+        .iter()
+        .enumerate()
+        .map(|(index, target)| format!("{}. {}", index + 1, target.signature))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let input = format!("File: {path}\n\n```\n{source}\n```\n\nFunctions to document:\n{listing}");
+    let (reply, total_tokens) = complete(state, config, DOC_COMMENT_INSTRUCTIONS, input).await?;
+    let json_text = reply
+        .find('{')
+        .zip(reply.rfind('}'))
+        .map(|(start, end)| &reply[start..=end])
+        .context("the model did not return JSON")?;
+    let docs: ModelDocs = serde_json::from_str(json_text).context("failed to parse the model's doc comments")?;
+
+    let mut edits: Vec<(&UndocumentedFn, String)> = docs
+        .docs
+        .into_iter()
+        .filter_map(|doc| {
+            let target = targets.get(doc.id.checked_sub(1)?)?;
+            let lines = clean_doc_lines(&doc.doc);
+            (!lines.is_empty()).then(|| (target, render_doc(language, target, &lines)))
+        })
+        .collect();
+    if edits.is_empty() {
+        bail!("the model returned no usable doc comments");
+    }
+    // Bottom-up, so earlier inserts never shift the line anchors of later ones.
+    edits.sort_by_key(|(target, _)| std::cmp::Reverse(target.anchor_line));
+
+    let action = if language == DocLanguage::Rust { "insert_before" } else { "insert_after" };
+    let changes: Vec<_> = edits
+        .iter()
+        .map(|(target, doc)| {
+            json!({
+                "action": action,
+                "range": { "start_line": target.anchor_line, "end_line": target.anchor_line, "expected_text": target.anchor_text },
+                "text": doc,
+            })
+        })
+        .collect();
+    let mut documented: Vec<String> = edits.iter().map(|(target, _)| target.name.clone()).collect();
+    documented.reverse();
+    let payload_text = serde_json::to_string_pretty(&json!({
+        "version": 1,
+        "description": format!("Add doc comments to {}", documented.join(", ")),
+        "operations": [{ "op": "edit", "path": path, "changes": changes }],
+    }))?;
+
+    let edited = preview_changeset_edits(repo, &payload_text)?
+        .into_iter()
+        .next()
+        .map(|(_, edited)| edited)
+        .unwrap_or_default();
+    let patch = preview_patch(repo, path, &edited)?;
+
+    Ok(DocCommentProposal {
+        path: path.to_string(),
+        payload_text,
+        patch,
+        documented,
+        model: config.model.clone(),
+        total_tokens,
+    })
+}
+
+fn undocumented_functions(text: &str, language: DocLanguage) -> Vec<UndocumentedFn> {
+    let lines: Vec<&str> = text.split('\n').collect();
+    match language {
+        DocLanguage::Rust => undocumented_rust(&lines),
+        DocLanguage::Python => undocumented_python(&lines),
+    }
+}
+
+fn undocumented_rust(lines: &[&str]) -> Vec<UndocumentedFn> {
+    let pattern = Regex::new(
+        r#"^(\s*)pub(?:\([^)]*\))?\s+(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+"[^"]*"\s+)?fn\s+([A-Za-z_][A-Za-z0-9_]*)"#,
+    )
+    .expect("valid rust fn pattern");
+    let mut out = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(caps) = pattern.captures(line) else { continue };
+        // Docs go above any attributes, so skip over those first.
+        let mut anchor = index;
+        while anchor > 0 && lines[anchor - 1].trim_start().starts_with("#[") {
+            anchor -= 1;
+        }
+        let above = if anchor > 0 { lines[anchor - 1].trim() } else { "" };
+        if above.starts_with("///") || above.starts_with("#[doc") || above.ends_with("*/") {
+            continue;
+        }
+        let signature = lines[index..lines.len().min(index + 8)]
+            .iter()
+            .scan(false, |done, line| {
+                if *done {
+                    return None;
+                }
+                *done = line.contains('{') || line.trim_end().ends_with(';');
+                Some(line.trim())
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push(UndocumentedFn {
+            name: caps[2].to_string(),
+            signature: signature.trim_end_matches('{').trim().to_string(),
+            anchor_line: anchor + 1,
+            anchor_text: lines[anchor].to_string(),
+            indent: caps[1].to_string(),
+        });
+    }
+    out
+}
+
+fn undocumented_python(lines: &[&str]) -> Vec<UndocumentedFn> {
+    let pattern = Regex::new(r"^(\s*)(?:async\s+)?def\s+([A-Za-z_][A-Za-z0-9_]*)\s*\(").expect("valid python def pattern");
+    let mut out = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(caps) = pattern.captures(line) else { continue };
+        let name = caps[2].to_string();
+        if name.starts_with('_') {
+            continue;
+        }
+        // The docstring goes after the line that closes the signature with `:`.
+        let mut depth = 0i32;
+        let mut end = None;
+        for (offset, candidate) in lines[index..lines.len().min(index + 30)].iter().enumerate() {
+            let code = candidate.split('#').next().unwrap_or("");
+            depth += code.matches(['(', '[', '{']).count() as i32 - code.matches([')', ']', '}']).count() as i32;
+            if depth <= 0 {
+                end = code.trim_end().ends_with(':').then_some(index + offset);
+                break;
+            }
+        }
+        let Some(end) = end else { continue };
+        let def_indent = caps[1].to_string();
+        let body = lines[end + 1..].iter().find(|line| !line.trim().is_empty());
+        let first = body.map(|line| line.trim_start()).unwrap_or("");
+        let opens_docstring = ["\"\"\"", "'''", "r\"\"\"", "r'''", "\"", "'"].iter().any(|quote| first.starts_with(quote));
+        if opens_docstring {
+            continue;
+        }
+        let body_indent = body
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .filter(|indent| indent.len() > def_indent.len())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{def_indent}    "));
+        out.push(UndocumentedFn {
+            name,
+            signature: lines[index..=end].iter().map(|line| line.trim()).collect::<Vec<_>>().join(" "),
+            anchor_line: end + 1,
+            anchor_text: lines[end].to_string(),
+            indent: body_indent,
+        });
+    }
+    out
+}
+
+/// Drops comment markers and quotes the model added despite instructions, and surrounding blank lines.
+fn clean_doc_lines(doc: &str) -> Vec<String> {
+    let lines: Vec<String> = doc
+        .replace("\"\"\"", "")
+        .lines()
+        .map(|line| {
+            let line = line.trim_end();
+            let stripped = line.trim_start();
+            stripped
+                .strip_prefix("///")
+                .or_else(|| stripped.strip_prefix("//"))
+                .map(|rest| rest.strip_prefix(' ').unwrap_or(rest).to_string())
+                .unwrap_or_else(|| line.to_string())
+        })
+        .collect();
+    let start = lines.iter().position(|line| !line.trim().is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(start, |end| end + 1);
+    lines[start..end].to_vec()
+}
+
+fn render_doc(language: DocLanguage, target: &UndocumentedFn, lines: &[String]) -> String {
+    let indent = &target.indent;
+    match language {
+        DocLanguage::Rust => lines
+            .iter()
+            .map(|line| if line.is_empty() { format!("{indent}///\n") } else { format!("{indent}/// {line}\n") })
+            .collect(),
+        DocLanguage::Python if lines.len() == 1 => format!("\n{indent}\"\"\"{}\"\"\"", lines[0]),
+        DocLanguage::Python => {
+            let mut out = format!("\n{indent}\"\"\"{}", lines[0]);
+            for line in &lines[1..] {
+                out.push('\n');
+                if !line.is_empty() {
+                    out.push_str(indent);
+                    out.push_str(line);
+                }
+            }
+            out.push_str(&format!("\n{indent}\"\"\""));
+            out
+        }
+    }
+}
+
+/// `git diff --no-index` between the file on disk and its edited text, relabelled to the repo path.
+pub(crate) fn preview_patch(repo: &Path, path: &str, edited: &str) -> Result<String> {
+    let temp = std::env::temp_dir().join(format!("mdev-doc-preview-{}", Uuid::new_v4()));
+    fs::write(&temp, edited)?;
+    let original = repo.join(path);
+    let result = run_git_allow_fail(
+        repo,
+        &["diff", "--no-color", "--no-index", "--", &original.to_string_lossy(), &temp.to_string_lossy()],
+    );
+    let _ = fs::remove_file(&temp);
+    let (_, stdout, _) = result?;
+    let diff = String::from_utf8_lossy(&stdout);
+    let hunks = diff.find("\n@@").map(|index| &diff[index + 1..]).unwrap_or("");
+    Ok(format!("diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n{hunks}"))
+}
@@ -0,0 +1,227 @@
+use std::{path::Path, process::Command};
+
+use serde::Serialize;
+
+use crate::{
+    app_state::AppState,
+    engine::capabilities::inference::api::oai::OpenAIInferenceClient,
+    http_client::{build_http_client, load_network_settings},
+    long_path::os_long_paths_enabled,
+    runtime_env::data_dir,
+};
+
+/// Below this much free space in app data, snapshots and exports start failing halfway through.
+const LOW_DISK_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+    /// Not applicable on this platform.
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or failure.
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn ok(id: &'static str, label: &'static str, detail: impl Into<String>) -> Self {
+        Self { id, label, status: CheckStatus::Ok, detail: detail.into(), hint: None }
+    }
+
+    fn problem(id: &'static str, label: &'static str, status: CheckStatus, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { id, label, status, detail: detail.into(), hint: Some(hint.into()) }
+    }
+}
+
+/// Preflight checks for what operations otherwise fail on deep inside a run: git, identity, API key,
+/// shell, disk space and (on Windows) long paths.
+pub async fn run_checks(state: &AppState) -> Vec<DoctorCheck> {
+    let mut checks = vec![check_git()];
+    checks.extend(check_git_identity());
+    checks.push(check_api_key(state).await);
+    checks.push(check_shell());
+    checks.push(check_disk_space(&data_dir()));
+    checks.push(check_long_paths());
+    checks
+}
+
+fn check_git() -> DoctorCheck {
+    match output("git", &["--version"]) {
+        Some(version) => DoctorCheck::ok("git", "Git", version),
+        None => DoctorCheck::problem(
+            "git",
+            "Git",
+            CheckStatus::Fail,
+            "git was not found on PATH",
+            "Install git from https://git-scm.com/downloads and restart the app so PATH is picked up.",
+        ),
+    }
+}
+
+fn check_git_identity() -> Vec<DoctorCheck> {
+    [("git_user_name", "Git user.name", "user.name", "Your Name"), ("git_user_email", "Git user.email", "user.email", "you@example.com")]
+        .into_iter()
+        .map(|(id, label, key, example)| match output("git", &["config", "--get", key]) {
+            Some(value) => DoctorCheck::ok(id, label, value),
+            None => DoctorCheck::problem(
+                id,
+                label,
+                CheckStatus::Fail,
+                format!("{key} is not configured, so commits will be rejected"),
+                format!("Run: git config --global {key} \"{example}\""),
+            ),
+        })
+        .collect()
+}
+
+async fn check_api_key(state: &AppState) -> DoctorCheck {
+    const ID: &str = "api_key";
+    const LABEL: &str = "OpenAI API key";
+    if std::env::var("OPENAI_API_KEY").map(|key| key.trim().is_empty()).unwrap_or(true) {
+        return DoctorCheck::problem(
+            ID,
+            LABEL,
+            CheckStatus::Fail,
+            "OPENAI_API_KEY is not set",
+            "Set OPENAI_API_KEY in the .env file next to the app and restart. Browser-session inference still works without it.",
+        );
+    }
+    let client = match load_network_settings(&state.db).await.and_then(|network| build_http_client(&network)) {
+        Ok(http) => OpenAIInferenceClient::from_env().with_http(http),
+        Err(err) => {
+            return DoctorCheck::problem(ID, LABEL, CheckStatus::Fail, format!("{err:#}"), "Fix the proxy and CA bundle in Network settings.");
+        }
+    };
+    match client.verify_api_key().await {
+        Ok(status) if status.is_success() => DoctorCheck::ok(ID, LABEL, format!("Accepted by {}", client.base_url())),
+        Ok(status) if status.as_u16() == 401 || status.as_u16() == 403 => DoctorCheck::problem(
+            ID,
+            LABEL,
+            CheckStatus::Fail,
+            format!("{} rejected the key ({status})", client.base_url()),
+            "Create a new key in your OpenAI dashboard and update OPENAI_API_KEY.",
+        ),
+        Ok(status) => DoctorCheck::problem(
+            ID,
+            LABEL,
+            CheckStatus::Warn,
+            format!("{} answered {status}", client.base_url()),
+            "The key could not be confirmed; check OPENAI_BASE_URL and the provider's status page.",
+        ),
+        Err(err) => DoctorCheck::problem(
+            ID,
+            LABEL,
+            CheckStatus::Warn,
+            format!("{err:#}"),
+            "Check your connection, OPENAI_BASE_URL and the proxy in Network settings.",
+        ),
+    }
+}
+
+/// Compile and terminal commands run through `cmd` on Windows and `sh` elsewhere.
+fn check_shell() -> DoctorCheck {
+    let candidates: &[(&str, &[&str])] = if cfg!(windows) {
+        &[("cmd", &["/C", "ver"]), ("powershell", &["-NoProfile", "-Command", "$PSVersionTable.PSVersion.ToString()"])]
+    } else {
+        &[("sh", &["-c", "echo ok"]), ("bash", &["--version"])]
+    };
+    let found: Vec<String> = candidates
+        .iter()
+        .filter_map(|(shell, args)| output(shell, args).map(|out| format!("{shell}: {}", out.lines().next().unwrap_or_default())))
+        .collect();
+    let required = candidates[0].0;
+    if found.iter().any(|line| line.starts_with(&format!("{required}:"))) {
+        DoctorCheck::ok("shell", "Shell", found.join("; "))
+    } else {
+        DoctorCheck::problem(
+            "shell",
+            "Shell",
+            CheckStatus::Fail,
+            format!("{required} could not be started"),
+            if cfg!(windows) {
+                "Make sure %SystemRoot%\\System32 is on PATH and ComSpec points at cmd.exe."
+            } else {
+                "Install a POSIX shell and make sure /bin is on PATH."
+            },
+        )
+    }
+}
+
+fn check_disk_space(dir: &Path) -> DoctorCheck {
+    const ID: &str = "disk_space";
+    const LABEL: &str = "Disk space (app data)";
+    let existing = dir.ancestors().find(|path| path.exists()).unwrap_or(dir);
+    let Some(free) = free_bytes(existing) else {
+        return DoctorCheck::problem(
+            ID,
+            LABEL,
+            CheckStatus::Warn,
+            format!("could not read free space for {}", existing.display()),
+            "Check the drive manually; snapshots and exports need room in the app data folder.",
+        );
+    };
+    let detail = format!("{:.1} GB free at {}", free as f64 / 1e9, dir.display());
+    if free < LOW_DISK_BYTES {
+        DoctorCheck::problem(ID, LABEL, CheckStatus::Warn, detail, "Free some space or empty the trash; history snapshots and exports are written here.")
+    } else {
+        DoctorCheck::ok(ID, LABEL, detail)
+    }
+}
+
+fn free_bytes(dir: &Path) -> Option<u64> {
+    let dir = dir.to_string_lossy();
+    if cfg!(windows) {
+        let script = format!("(Get-Item -LiteralPath '{}').PSDrive.Free", dir.replace('\'', "''"));
+        output("powershell", &["-NoProfile", "-Command", &script])?.trim().parse().ok()
+    } else {
+        // POSIX format: the second line's fourth column is available 1K blocks.
+        let df = output("df", &["-Pk", &dir])?;
+        let available: u64 = df.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+        Some(available * 1024)
+    }
+}
+
+/// Windows caps paths at 260 characters unless both the OS and git opt in to long paths.
+fn check_long_paths() -> DoctorCheck {
+    const ID: &str = "long_paths";
+    const LABEL: &str = "Long path support";
+    if !cfg!(windows) {
+        return DoctorCheck { id: ID, label: LABEL, status: CheckStatus::Skip, detail: "Only relevant on Windows".to_string(), hint: None };
+    }
+    let os_enabled = os_long_paths_enabled() == Some(true);
+    let git_enabled = output("git", &["config", "--get", "core.longpaths"]).is_some_and(|value| value == "true");
+    match (os_enabled, git_enabled) {
+        (true, true) => DoctorCheck::ok(ID, LABEL, "Enabled for Windows and git"),
+        (false, _) => DoctorCheck::problem(
+            ID,
+            LABEL,
+            CheckStatus::Warn,
+            "LongPathsEnabled is off; the app reads and writes deep paths itself, but git, hooks and build tools fail on them",
+            "In an elevated PowerShell run: New-ItemProperty -Path 'HKLM:\\SYSTEM\\CurrentControlSet\\Control\\FileSystem' -Name LongPathsEnabled -Value 1 -PropertyType DWORD -Force",
+        ),
+        (true, false) => DoctorCheck::problem(
+            ID,
+            LABEL,
+            CheckStatus::Warn,
+            "Windows allows long paths but git core.longpaths is off",
+            "Run: git config --global core.longpaths true",
+        ),
+    }
+}
+
+/// Trimmed stdout of a successful command with some output; `None` when it is missing, fails or prints nothing.
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok()?;
+    let text = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !text.is_empty()).then_some(text)
+}
@@ -0,0 +1,56 @@
+use anyhow::{bail, Result};
+use chrono::Utc;
+use serde_json::Value;
+use sqlx::{Row, SqlitePool};
+
+/// Cursor, selections, scroll offset and folds for one file, as the editor's own view-state JSON.
+pub const EDITOR_FILE_STATE_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS editor_file_state (
+    repo_ref TEXT NOT NULL,
+    path TEXT NOT NULL,
+    state_json TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (repo_ref, path)
+)
+"#;
+
+/// View state is a few hundred bytes; anything much larger is not what the editor sends.
+const MAX_STATE_BYTES: usize = 64 * 1024;
+/// Files remembered per repo; the least recently touched are forgotten first.
+const MAX_FILES_PER_REPO: i64 = 500;
+
+pub async fn load(db: &SqlitePool, repo_ref: &str, path: &str) -> Result<Option<Value>> {
+    let row = sqlx::query("SELECT state_json FROM editor_file_state WHERE repo_ref = ? AND path = ?")
+        .bind(repo_ref)
+        .bind(path)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.and_then(|row| serde_json::from_str(row.get::<String, _>("state_json").as_str()).ok()))
+}
+
+pub async fn save(db: &SqlitePool, repo_ref: &str, path: &str, state: &Value) -> Result<()> {
+    let state_json = serde_json::to_string(state)?;
+    if state_json.len() > MAX_STATE_BYTES {
+        bail!("editor state for {path} is larger than {MAX_STATE_BYTES} bytes");
+    }
+    sqlx::query(
+        "INSERT INTO editor_file_state (repo_ref, path, state_json, updated_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT(repo_ref, path) DO UPDATE SET state_json = excluded.state_json, updated_at = excluded.updated_at",
+    )
+    .bind(repo_ref)
+    .bind(path)
+    .bind(&state_json)
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+    sqlx::query(
+        "DELETE FROM editor_file_state WHERE repo_ref = ? AND path NOT IN
+         (SELECT path FROM editor_file_state WHERE repo_ref = ? ORDER BY updated_at DESC LIMIT ?)",
+    )
+    .bind(repo_ref)
+    .bind(repo_ref)
+    .bind(MAX_FILES_PER_REPO)
+    .execute(db)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+/// The last save path (often a template) each exporter wrote to, so the next export can start there.
+pub const EXPORT_DESTINATIONS_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS export_destinations (
+    exporter TEXT PRIMARY KEY,
+    save_path TEXT NOT NULL,
+    if_exists TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+)
+"#;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportDestination {
+    /// For example `context_export.text` or `context_export.zip`.
+    pub exporter: String,
+    pub save_path: String,
+    /// `overwrite` or `new_version`.
+    pub if_exists: String,
+    pub updated_at: String,
+}
+
+pub async fn list(db: &SqlitePool) -> Result<Vec<ExportDestination>> {
+    let rows = sqlx::query("SELECT exporter, save_path, if_exists, updated_at FROM export_destinations ORDER BY exporter")
+        .fetch_all(db)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| ExportDestination {
+            exporter: row.get("exporter"),
+            save_path: row.get("save_path"),
+            if_exists: row.get("if_exists"),
+            updated_at: row.get("updated_at"),
+        })
+        .collect())
+}
+
+pub async fn remember(db: &SqlitePool, exporter: &str, save_path: &str, if_exists: &str) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO export_destinations (exporter, save_path, if_exists, updated_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT(exporter) DO UPDATE SET save_path = excluded.save_path, if_exists = excluded.if_exists, updated_at = excluded.updated_at",
+    )
+    .bind(exporter)
+    .bind(save_path)
+    .bind(if_exists)
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+    Ok(())
+}
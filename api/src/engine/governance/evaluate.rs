@@ -5,6 +5,7 @@ use uuid::Uuid;
 use crate::{
     engine::capabilities::binding_specs,
     app_state::AppState,
+    engine::capabilities::inference::stage_support::auto_apply_enabled,
     engine::capabilities::registry::{CapabilityInvocation, CapabilityResult},
     models::{WorkflowRun, WorkflowStepDefinition},
 };
@@ -122,7 +123,11 @@ pub async fn after_capability(
     );
 
     let mut decisions = match result.capability.as_str() {
-        "gateway_model/changeset" => evaluate_changeset_guardrails(run, step, result),
+        "gateway_model/changeset" => {
+            let mut decisions = evaluate_changeset_guardrails(run, step, result);
+            decisions.extend(evaluate_context_refresh(run, step, result));
+            decisions
+        }
         "compile_commands" => evaluate_compile_guardrails(run, step, result),
         _ => Vec::new(),
     };
@@ -404,6 +409,67 @@ fn evaluate_changeset_guardrails(
     decisions
 }
 
+/// After an automatic apply, queues the files the model was last shown that the apply just changed, so the next
+/// turn gets current copies instead of reasoning over stale ones. `context_export` clears the queue once sent.
+fn evaluate_context_refresh(
+    run: &WorkflowRun,
+    step: &WorkflowStepDefinition,
+    result: &CapabilityResult,
+) -> Vec<GovernanceDecision> {
+    if !result.ok || !stage_supports_repo_context(step) {
+        return Vec::new();
+    }
+    let workflow_engine = run.context.get("workflow_engine").cloned().unwrap_or_else(|| json!({}));
+    let stage_state = workflow_engine
+        .get("stage_state")
+        .and_then(|v| v.get(step.id.as_str()))
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    if !auto_apply_enabled(step, &stage_state) {
+        return Vec::new();
+    }
+    let context_export = workflow_engine
+        .get("global_state")
+        .and_then(|v| v.get("capabilities"))
+        .and_then(|v| v.get("context_export"))
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    if context_export.get("refresh_on_apply").and_then(Value::as_bool) == Some(false) {
+        return Vec::new();
+    }
+
+    let sent = context_export
+        .pointer("/last_injection/sent")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).collect::<std::collections::HashSet<_>>())
+        .unwrap_or_default();
+    let mut stale = signals::changeset_touched_files(result)
+        .into_iter()
+        .filter(|path| sent.contains(path.as_str()))
+        .collect::<Vec<_>>();
+    if stale.is_empty() {
+        return Vec::new();
+    }
+    if let Some(pending) = context_export.get("pending_refresh").and_then(Value::as_array) {
+        stale.extend(pending.iter().filter_map(Value::as_str).map(ToString::to_string));
+    }
+    stale.sort();
+    stale.dedup();
+
+    vec![GovernanceDecision::MutateContext {
+        mutation: ContextMutation {
+            scope: GovernanceScope::Global,
+            patch: json!({
+                "capabilities": {
+                    "context_export": {
+                        "pending_refresh": stale
+                    }
+                }
+            }),
+        },
+    }]
+}
+
 fn evaluate_compile_guardrails(
     run: &WorkflowRun,
     _step: &WorkflowStepDefinition,
@@ -0,0 +1,354 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use uuid::Uuid;
+
+use super::{
+    blob_store,
+    capabilities::filesystem::{normalize_rel_path, resolve_workspace_entry, resolve_workspace_path},
+    trash,
+};
+
+const HISTORY_LIMIT: i64 = 50;
+
+pub const APP_HISTORY_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS app_history (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    label TEXT NOT NULL,
+    repo_ref TEXT NOT NULL DEFAULT '',
+    snapshot_json TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    undone_at TEXT
+)
+"#;
+
+/// Prior state of one path. `blob` names its contents in the blob store and is empty when the
+/// path did not exist, so undo removes it again. A symlink keeps its target in `link` instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    pub path: String,
+    pub existed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+impl FileSnapshot {
+    pub fn read(&self) -> Result<Vec<u8>> {
+        let id = self.blob.as_deref().with_context(|| format!("snapshot of {} has no stored contents", self.path))?;
+        blob_store::get(id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HistorySnapshot {
+    Files { files: Vec<FileSnapshot> },
+    State { state: Value },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub kind: String,
+    pub label: String,
+    pub repo_ref: String,
+    pub paths: Vec<String>,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryUndoResult {
+    pub entry: HistoryEntry,
+    pub restored: Vec<String>,
+    pub removed: Vec<String>,
+    pub state: Option<Value>,
+}
+
+/// Captures the current contents of `paths` under `repo`, expanding directories to the files
+/// they contain. Missing paths are recorded as absent. Symlinks are recorded as links and never
+/// followed, and special files are left out.
+pub fn snapshot_paths(repo: &Path, paths: &[String]) -> Result<Vec<FileSnapshot>> {
+    let repo_ref = repo.to_string_lossy();
+    let mut out = Vec::new();
+    for path in paths {
+        let rel = normalize_rel_path(path)?;
+        let full = resolve_workspace_entry(&repo_ref, &rel)?;
+        snapshot_entry(&rel, &full, &mut out)?;
+    }
+    Ok(out)
+}
+
+fn snapshot_entry(rel: &str, full: &Path, out: &mut Vec<FileSnapshot>) -> Result<()> {
+    let metadata = match fs::symlink_metadata(full) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            out.push(FileSnapshot { path: rel.to_string(), existed: false, blob: None, link: None });
+            return Ok(());
+        }
+        Err(err) => return Err(err).with_context(|| format!("failed to stat {}", full.display())),
+    };
+    if metadata.file_type().is_symlink() {
+        let target = fs::read_link(full).with_context(|| format!("failed to read link {}", full.display()))?;
+        let link = Some(target.to_string_lossy().to_string());
+        out.push(FileSnapshot { path: rel.to_string(), existed: true, blob: None, link });
+    } else if metadata.is_dir() {
+        for entry in fs::read_dir(full).with_context(|| format!("failed to read {}", full.display()))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name != ".git" {
+                snapshot_entry(&format!("{rel}/{name}"), &entry.path(), out)?;
+            }
+        }
+    } else if metadata.is_file() {
+        let raw = fs::read(full).with_context(|| format!("failed to read {}", full.display()))?;
+        let blob = Some(blob_store::put(&raw)?);
+        out.push(FileSnapshot { path: rel.to_string(), existed: true, blob, link: None });
+    }
+    Ok(())
+}
+
+/// Where a snapshotted path is written back. Files go through `resolve_workspace_path`, so neither a
+/// linked parent nor a link at the path itself can redirect the write; a link only needs its parent
+/// inside the repository because it replaces whatever sits at the path.
+pub(crate) fn restore_target(repo_ref: &str, file: &FileSnapshot) -> Result<PathBuf> {
+    if file.link.is_some() || !file.existed {
+        resolve_workspace_entry(repo_ref, &file.path)
+    } else {
+        resolve_workspace_path(repo_ref, &file.path)
+    }
+}
+
+/// Puts one snapshotted path back the way it was: contents for files, the link itself for symlinks.
+pub(crate) fn write_back(repo_ref: &str, file: &FileSnapshot) -> Result<()> {
+    let full = restore_target(repo_ref, file)?;
+    if let Some(parent) = full.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match &file.link {
+        Some(target) => {
+            if fs::symlink_metadata(&full).is_ok_and(|metadata| !metadata.is_dir()) {
+                fs::remove_file(&full).with_context(|| format!("failed to replace {}", full.display()))?;
+            }
+            create_symlink(Path::new(target), &full).with_context(|| format!("failed to restore link {}", full.display()))
+        }
+        None => fs::write(&full, file.read()?).with_context(|| format!("failed to restore {}", full.display())),
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    let resolved = link.parent().map(|parent| parent.join(target)).unwrap_or_else(|| PathBuf::from(target));
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+pub async fn record(db: &SqlitePool, kind: &str, label: &str, repo_ref: &str, snapshot: &HistorySnapshot) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO app_history (id, kind, label, repo_ref, snapshot_json, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(kind)
+    .bind(label)
+    .bind(repo_ref)
+    .bind(serde_json::to_string(snapshot)?)
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+
+    sqlx::query("DELETE FROM app_history WHERE id NOT IN (SELECT id FROM app_history ORDER BY created_at DESC LIMIT ?)")
+        .bind(HISTORY_LIMIT)
+        .execute(db)
+        .await?;
+    if let Err(err) = sweep_blobs(db).await {
+        tracing::warn!(error = %err, "failed to sweep unreferenced history blobs");
+    }
+    Ok(id)
+}
+
+/// Removes stored file contents that neither a history entry nor the trash refers to any more.
+pub async fn sweep_blobs(db: &SqlitePool) -> Result<usize> {
+    let mut keep = trash::referenced_blobs()?;
+    for row in sqlx::query("SELECT snapshot_json FROM app_history").fetch_all(db).await? {
+        if let Ok(HistorySnapshot::Files { files }) = serde_json::from_str(row.get::<String, _>("snapshot_json").as_str()) {
+            keep.extend(files.into_iter().filter_map(|file| file.blob));
+        }
+    }
+    blob_store::sweep(&keep)
+}
+
+/// Records an undoable file snapshot and keeps a copy of the removed or overwritten files in
+/// the repository's trash, which outlives the bounded undo history.
+pub async fn record_files(db: &SqlitePool, kind: &str, label: &str, repo_ref: &str, files: Vec<FileSnapshot>) -> Result<String> {
+    trash::store(repo_ref, kind, label, &files, trash::retention_days(db).await)?;
+    record(db, kind, label, repo_ref, &HistorySnapshot::Files { files }).await
+}
+
+pub async fn list(db: &SqlitePool) -> Result<Vec<HistoryEntry>> {
+    let rows = sqlx::query("SELECT * FROM app_history ORDER BY created_at DESC")
+        .fetch_all(db)
+        .await?;
+    rows.into_iter().map(|row| row_to_entry(&row).map(|(entry, _)| entry)).collect()
+}
+
+/// Reverts the given entry, or the most recent one not yet undone. File snapshots are written
+/// back to disk; state snapshots are returned for the client to reapply.
+pub async fn undo(db: &SqlitePool, id: Option<&str>) -> Result<HistoryUndoResult> {
+    let row = match id {
+        Some(id) => sqlx::query("SELECT * FROM app_history WHERE id = ?").bind(id).fetch_optional(db).await?,
+        None => {
+            sqlx::query("SELECT * FROM app_history WHERE undone_at IS NULL ORDER BY created_at DESC LIMIT 1")
+                .fetch_optional(db)
+                .await?
+        }
+    };
+    let row = row.ok_or_else(|| anyhow!("nothing to undo"))?;
+    let (mut entry, snapshot) = row_to_entry(&row)?;
+    if entry.undone_at.is_some() {
+        return Err(anyhow!("'{}' was already undone", entry.label));
+    }
+
+    let mut restored = Vec::new();
+    let mut removed = Vec::new();
+    let mut state = None;
+    match snapshot {
+        HistorySnapshot::Files { files } => {
+            for file in files {
+                if !file.existed {
+                    let full = restore_target(&entry.repo_ref, &file)?;
+                    if fs::symlink_metadata(&full).is_ok_and(|metadata| !metadata.is_dir()) {
+                        fs::remove_file(&full).with_context(|| format!("failed to remove {}", full.display()))?;
+                        removed.push(file.path);
+                    }
+                    continue;
+                }
+                write_back(&entry.repo_ref, &file)?;
+                restored.push(file.path);
+            }
+        }
+        HistorySnapshot::State { state: value } => state = Some(value),
+    }
+
+    let now = Utc::now().to_rfc3339();
+    sqlx::query("UPDATE app_history SET undone_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(&entry.id)
+        .execute(db)
+        .await?;
+    entry.undone_at = Some(now);
+
+    Ok(HistoryUndoResult { entry, restored, removed, state })
+}
+
+fn row_to_entry(row: &SqliteRow) -> Result<(HistoryEntry, HistorySnapshot)> {
+    let snapshot: HistorySnapshot = serde_json::from_str(row.get::<String, _>("snapshot_json").as_str())
+        .context("failed to decode history snapshot")?;
+    let paths = match &snapshot {
+        HistorySnapshot::Files { files } => files.iter().map(|file| file.path.clone()).collect(),
+        HistorySnapshot::State { .. } => Vec::new(),
+    };
+    Ok((
+        HistoryEntry {
+            id: row.get("id"),
+            kind: row.get("kind"),
+            label: row.get("label"),
+            repo_ref: row.get("repo_ref"),
+            paths,
+            created_at: row.get("created_at"),
+            undone_at: row.get("undone_at"),
+        },
+        snapshot,
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn snapshots_record_symlinks_without_following_them() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_dir("history-repo");
+        let outside = temp_dir("history-outside");
+        fs::write(outside.join("secret.txt"), "secret").unwrap();
+        fs::create_dir(repo.join("src")).unwrap();
+        fs::write(repo.join("src/lib.rs"), "fn main() {}").unwrap();
+        std::os::unix::fs::symlink(&outside, repo.join("src/home")).unwrap();
+
+        let files = snapshot_paths(&repo, &["src".to_string()]).unwrap();
+        let link = files.iter().find(|file| file.path == "src/home").unwrap();
+        assert_eq!(link.link.as_deref(), Some(outside.to_string_lossy().as_ref()));
+        assert!(link.blob.is_none());
+        assert!(files.iter().all(|file| !file.path.contains("secret")));
+
+        fs::remove_file(repo.join("src/home")).unwrap();
+        write_back(&repo.to_string_lossy(), link).unwrap();
+        assert!(fs::symlink_metadata(repo.join("src/home")).unwrap().file_type().is_symlink());
+
+        fs::remove_dir_all(&repo).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn snapshots_refuse_paths_under_a_linked_parent() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_dir("history-repo");
+        let outside = temp_dir("history-outside");
+        fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(&outside, repo.join("escape")).unwrap();
+
+        assert!(snapshot_paths(&repo, &["escape/secret.txt".to_string()]).is_err());
+        assert!(snapshot_paths(&repo, &["../secret.txt".to_string()]).is_err());
+        assert!(!snapshot_paths(&repo, &["missing.txt".to_string()]).unwrap()[0].existed);
+
+        fs::remove_dir_all(&repo).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn undo_writes_stay_inside_the_repo() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_dir("history-repo");
+        let outside = temp_dir("history-outside");
+        fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(&outside, repo.join("escape")).unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.txt"), repo.join("notes.txt")).unwrap();
+        let blob = Some(blob_store::put(b"overwritten").unwrap());
+        let repo_ref = repo.to_string_lossy().to_string();
+        let snapshot = |path: &str| FileSnapshot { path: path.to_string(), existed: true, blob: blob.clone(), link: None };
+
+        for path in ["../secret.txt", "escape/secret.txt", "notes.txt"] {
+            assert!(write_back(&repo_ref, &snapshot(path)).is_err(), "{path}");
+        }
+        assert_eq!(fs::read_to_string(outside.join("secret.txt")).unwrap(), "secret");
+
+        fs::remove_dir_all(&repo).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::{
+    db::load_app_settings_section,
+    engine::{
+        capabilities::broker::CapabilityBroker,
+        updates::CURRENT_VERSION,
+    },
+};
+
+const SUPPORTED_PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+const MAX_TREE_ENTRIES: usize = 5_000;
+const DEFAULT_SEARCH_RESULTS: usize = 200;
+
+/// MCP exposure settings: which read-only tools are offered and which repositories they may touch.
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "all_tools")]
+    pub tools: Vec<String>,
+    /// Repository roots clients may read; empty denies every repository.
+    #[serde(default)]
+    pub allowed_repos: Vec<String>,
+}
+
+fn all_tools() -> Vec<String> {
+    TOOLS.iter().map(|(name, _, _)| name.to_string()).collect()
+}
+
+/// Tool name, description and input schema.
+type ToolSpec = (&'static str, &'static str, fn() -> Value);
+
+const TOOLS: [ToolSpec; 5] = [
+    ("repo_tree", "List the files of a repository at a git ref (WORKTREE for the working tree).", repo_tree_schema),
+    ("read_file", "Read a text file from a repository at a git ref.", read_file_schema),
+    ("search", "Search repository files with a regular expression (git grep). Returns path:line:text matches.", search_schema),
+    ("diff", "Unified diff of unstaged, staged or all uncommitted changes, optionally limited to one path.", diff_schema),
+    ("context_export", "Build a repo context export (manifest plus file contents) for the given files.", context_export_schema),
+];
+
+pub async fn load_mcp_settings(db: &SqlitePool) -> Result<McpSettings> {
+    let section = load_app_settings_section(db, "mcp").await?.unwrap_or_default();
+    Ok(serde_json::from_value(section).unwrap_or(McpSettings { enabled: false, tools: all_tools(), allowed_repos: Vec::new() }))
+}
+
+/// Handles one JSON-RPC message. Notifications produce no response.
+pub async fn handle_message(db: &SqlitePool, message: Value) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let method = message.get("method").and_then(Value::as_str).unwrap_or_default().to_string();
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+    let id = id?;
+    let result = match method.as_str() {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => list_tools(db).await,
+        "tools/call" => Ok(call_tool(db, &params).await),
+        other => {
+            return Some(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("method not found: {other}") }
+            }))
+        }
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32603, "message": format!("{err:#}") } }),
+    })
+}
+
+/// `workflow-api mcp`: serves MCP over stdin/stdout for clients that launch the server themselves.
+pub async fn serve_stdio(db: SqlitePool) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Array(batch)) => {
+                let mut responses = Vec::new();
+                for message in batch {
+                    responses.extend(handle_message(&db, message).await);
+                }
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(message) => handle_message(&db, message).await,
+            Err(err) => Some(json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": err.to_string() } })),
+        };
+        if let Some(response) = response {
+            stdout.write_all(format!("{response}\n").as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str).unwrap_or_default();
+    let version = SUPPORTED_PROTOCOL_VERSIONS.iter().find(|version| **version == requested).unwrap_or(&SUPPORTED_PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "mdev", "version": CURRENT_VERSION },
+        "instructions": "Read-only repository context: list files, read files, search, diff and build context exports. Pass repo_ref as an absolute path."
+    })
+}
+
+async fn list_tools(db: &SqlitePool) -> Result<Value> {
+    let settings = load_mcp_settings(db).await?;
+    let tools = TOOLS
+        .iter()
+        .filter(|(name, _, _)| settings.tools.iter().any(|tool| tool == name))
+        .map(|(name, description, schema)| json!({ "name": name, "description": description, "inputSchema": schema() }))
+        .collect::<Vec<_>>();
+    Ok(json!({ "tools": tools }))
+}
+
+/// Tool failures are reported in-band (`isError`) so the client model can read and recover from them.
+async fn call_tool(db: &SqlitePool, params: &Value) -> Value {
+    let name = params.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+    let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+    let outcome = match load_mcp_settings(db).await {
+        Ok(settings) => {
+            tokio::task::spawn_blocking(move || run_tool(&settings, &name, &args))
+                .await
+                .unwrap_or_else(|err| Err(anyhow!("tool panicked: {err}")))
+        }
+        Err(err) => Err(err),
+    };
+    match outcome {
+        Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+        Err(err) => json!({ "content": [{ "type": "text", "text": format!("{err:#}") }], "isError": true }),
+    }
+}
+
+fn run_tool(settings: &McpSettings, name: &str, args: &Value) -> Result<String> {
+    if !settings.enabled {
+        bail!("MCP access is disabled in settings");
+    }
+    let broker = CapabilityBroker::new(settings.tools.clone(), &settings.allowed_repos);
+    let repo_ref = str_arg(args, "repo_ref").ok_or_else(|| anyhow!("repo_ref is required"))?;
+    let git_ref = str_arg(args, "git_ref").unwrap_or("WORKTREE");
+    match name {
+        "repo_tree" => {
+            let files = broker.repo_tree(repo_ref, git_ref)?;
+            let mut text = files.iter().take(MAX_TREE_ENTRIES).map(String::as_str).collect::<Vec<_>>().join("\n");
+            if files.len() > MAX_TREE_ENTRIES {
+                text.push_str(&format!("\n… {} more files", files.len() - MAX_TREE_ENTRIES));
+            }
+            Ok(text)
+        }
+        "read_file" => {
+            let path = str_arg(args, "path").ok_or_else(|| anyhow!("path is required"))?;
+            broker.read_file(repo_ref, path, git_ref)
+        }
+        "search" => {
+            let pattern = str_arg(args, "pattern").ok_or_else(|| anyhow!("pattern is required"))?;
+            let max = args.get("max_results").and_then(Value::as_u64).map(|max| max as usize).unwrap_or(DEFAULT_SEARCH_RESULTS);
+            let matches = broker.search(repo_ref, pattern, git_ref)?;
+            if matches.is_empty() {
+                return Ok("no matches".to_string());
+            }
+            Ok(matches.into_iter().take(max).collect::<Vec<_>>().join("\n"))
+        }
+        "diff" => {
+            let patch = broker.diff(repo_ref, str_arg(args, "scope").unwrap_or("all"), str_arg(args, "path"))?;
+            Ok(if patch.trim().is_empty() { "no changes".to_string() } else { patch })
+        }
+        "context_export" => {
+            let files = args.get("files").cloned().unwrap_or_else(|| json!([]));
+            let include_unstaged_diff = args.get("include_unstaged_diff").and_then(Value::as_bool).unwrap_or(false);
+            broker.context_export(repo_ref, git_ref, files, include_unstaged_diff)
+        }
+        other => bail!("unknown tool {other}"),
+    }
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(Value::as_str).map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn repo_tree_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "repo_ref": { "type": "string", "description": "Absolute path of the repository." },
+            "git_ref": { "type": "string", "description": "Branch, tag or commit; defaults to WORKTREE." }
+        },
+        "required": ["repo_ref"]
+    })
+}
+
+fn read_file_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "repo_ref": { "type": "string", "description": "Absolute path of the repository." },
+            "path": { "type": "string", "description": "Repo-relative file path." },
+            "git_ref": { "type": "string", "description": "Branch, tag or commit; defaults to WORKTREE." }
+        },
+        "required": ["repo_ref", "path"]
+    })
+}
+
+fn search_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "repo_ref": { "type": "string", "description": "Absolute path of the repository." },
+            "pattern": { "type": "string", "description": "Extended regular expression." },
+            "git_ref": { "type": "string", "description": "Search this ref instead of the working tree." },
+            "max_results": { "type": "integer", "minimum": 1 }
+        },
+        "required": ["repo_ref", "pattern"]
+    })
+}
+
+fn diff_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "repo_ref": { "type": "string", "description": "Absolute path of the repository." },
+            "scope": { "type": "string", "enum": ["unstaged", "staged", "all"] },
+            "path": { "type": "string", "description": "Limit the diff to this repo-relative path." }
+        },
+        "required": ["repo_ref"]
+    })
+}
+
+fn context_export_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "repo_ref": { "type": "string", "description": "Absolute path of the repository." },
+            "files": { "type": "array", "items": { "type": "string" }, "description": "Repo-relative paths; empty exports the whole repository." },
+            "git_ref": { "type": "string", "description": "Branch, tag or commit; defaults to WORKTREE." },
+            "include_unstaged_diff": { "type": "boolean" }
+        },
+        "required": ["repo_ref"]
+    })
+}
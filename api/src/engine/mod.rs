@@ -1,9 +1,31 @@
+pub(crate) mod ai_actions;
+pub(crate) mod analysis_snapshots;
+pub(crate) mod blob_store;
+mod budget;
 pub(crate) mod capabilities;
+pub(crate) mod code_review;
+pub(crate) mod conversation_import;
+pub(crate) mod definition_migration;
+pub(crate) mod doc_comments;
+pub(crate) mod doctor;
+pub(crate) mod editor_state;
+pub(crate) mod export_destinations;
 pub(crate) mod governance;
+pub(crate) mod history;
+pub(crate) mod mcp;
+pub(crate) mod plugins;
+pub(crate) mod processes;
+pub(crate) mod project_search;
+pub(crate) mod repo_overview;
+pub(crate) mod review_queue;
 mod runtime;
+pub(crate) mod save_hooks;
 pub(crate) mod shared_capability_lifecycle;
 mod stages;
+pub(crate) mod test_generation;
 mod transitions;
+pub(crate) mod trash;
+pub(crate) mod updates;
 
 use anyhow::{anyhow, Result};
 use chrono::Utc;
@@ -30,7 +52,7 @@ pub async fn load_run(state: &AppState, run_id: Uuid) -> Result<WorkflowRun> {
     let mut run = WorkflowRun {
         id: Uuid::parse_str(row.get::<String, _>("id").as_str())?,
         template_id: row.get::<Option<String>, _>("template_id").map(|v| Uuid::parse_str(v.as_str())).transpose()?,
-        definition: serde_json::from_str(row.get::<String, _>("definition_json").as_str())?,
+        definition: definition_migration::parse_definition(row.get::<String, _>("definition_json").as_str())?,
         status: match row.get::<String, _>("status").as_str() {
             "draft" => RunStatus::Draft,
             "queued" => RunStatus::Queued,
@@ -313,6 +335,35 @@ pub async fn patch_global_state(state: &AppState, run_id: Uuid, payload: Value)
     Ok(json!({ "ok": true, "global_state": global_state_snapshot }))
 }
 
+/// Saves run notes unless they changed since `base_updated_at` (another tab or instance wrote
+/// them). On conflict nothing is written and the stored notes are returned for the caller to
+/// merge or overwrite with `force`.
+pub async fn save_run_notes(state: &AppState, run_id: Uuid, payload: Value) -> Result<Value> {
+    let markdown = payload
+        .get("markdown")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("markdown is required"))?;
+    let base_updated_at = payload.get("base_updated_at").and_then(Value::as_str).unwrap_or("");
+    let force = payload.get("force").and_then(Value::as_bool).unwrap_or(false);
+
+    let mut run = load_run(state, run_id).await?;
+    let root = ensure_engine_root(&mut run.context);
+    let global_state = root.entry("global_state".to_string()).or_insert_with(|| json!({}));
+    if !global_state.is_object() {
+        *global_state = json!({});
+    }
+    let current = global_state.get("notes").cloned().unwrap_or_else(|| json!({}));
+    let current_updated_at = current.get("updated_at").and_then(Value::as_str).unwrap_or("");
+    if !force && current_updated_at != base_updated_at {
+        return Ok(json!({ "ok": false, "conflict": true, "notes": current }));
+    }
+
+    let notes = json!({ "markdown": markdown, "updated_at": Utc::now().to_rfc3339() });
+    global_state["notes"] = notes.clone();
+    update_run_context(&state.db, run_id, &run.context).await?;
+    Ok(json!({ "ok": true, "conflict": false, "notes": notes }))
+}
+
 fn strip_governance_owned_inference_enabled_flags(payload: &mut Map<String, Value>) {
     let Some(execution_logic) = payload.get_mut("execution_logic") else {
         return;
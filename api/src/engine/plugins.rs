@@ -0,0 +1,353 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use rhai::{module_resolvers::DummyModuleResolver, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::{
+    db::load_app_settings_section,
+    engine::{capabilities::filesystem::read_text_file, processes},
+    runtime_env::data_dir,
+};
+
+const PLUGIN_EXTENSION: &str = "rhai";
+const MAX_OPERATIONS: u64 = 5_000_000;
+const MAX_OUTPUT_CHARS: usize = 64 * 1024;
+/// Read-only git subcommands plugins may run; global options, aliases and anything that writes are refused.
+const GIT_SUBCOMMANDS: [&str; 13] = [
+    "status", "log", "diff", "show", "ls-files", "ls-tree", "rev-parse", "rev-list", "blame", "grep", "describe", "shortlog", "merge-base",
+];
+/// Short flags that make git read config from the command line, change directory or run a pager program.
+/// A flag cluster such as `-nO<cmd>` is refused if any letter in it is one of these.
+const GIT_FORBIDDEN_SHORT_FLAGS: [char; 3] = ['c', 'C', 'O'];
+/// Long options plugins may pass, matched exactly (git's abbreviations are refused). Anything that writes a
+/// file, runs another program or points git at a different repository is left out.
+const GIT_ALLOWED_LONG_OPTIONS: [&str; 75] = [
+    "--abbrev", "--abbrev-commit", "--abbrev-ref", "--after", "--all", "--always", "--ancestry-path", "--author",
+    "--before", "--boundary", "--branch", "--branches", "--cached", "--cherry-pick", "--color", "--committer",
+    "--contains", "--count", "--date", "--date-order", "--decorate", "--deleted", "--diff-filter",
+    "--exclude-standard", "--extended-regexp", "--files-with-matches", "--find-renames", "--first-parent",
+    "--fixed-strings", "--follow", "--format", "--full-name", "--graph", "--grep", "--histogram",
+    "--ignore-all-space", "--ignore-case", "--ignore-space-change", "--ignored", "--is-ancestor",
+    "--is-inside-work-tree", "--left-right", "--line-number", "--long", "--max-count", "--merges", "--minimal",
+    "--modified", "--name-only", "--name-status", "--no-color", "--no-decorate", "--no-ext-diff", "--no-merges",
+    "--no-patch", "--no-renames", "--no-textconv", "--numbered", "--numstat", "--oneline", "--others", "--patch",
+    "--porcelain", "--pretty", "--quiet", "--remotes", "--reverse", "--short", "--shortstat", "--show-toplevel",
+    "--since", "--skip", "--stat", "--summary", "--tags",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginSettings {
+    /// Off until the user opts in, so scripts dropped into the data dir never run on their own.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Programs scripts may launch through `run()`; matched against the first word of the command. `git` is
+    /// further limited to read-only subcommands.
+    #[serde(default = "default_allowed_commands")]
+    pub allowed_commands: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    false
+}
+
+fn default_allowed_commands() -> Vec<String> {
+    vec!["git".to_string()]
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PluginEntry {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PluginInfo {
+    pub id: String,
+    pub path: String,
+    pub description: Option<String>,
+    pub commands: Vec<PluginEntry>,
+    pub panels: Vec<PluginEntry>,
+    /// Compile or registration failure; the plugin is listed but cannot be invoked.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginNotification {
+    pub level: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginOutput {
+    pub output: String,
+    pub notifications: Vec<PluginNotification>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginCatalog {
+    pub dir: String,
+    pub enabled: bool,
+    pub allowed_commands: Vec<String>,
+    pub plugins: Vec<PluginInfo>,
+}
+
+/// Everything a script may touch: the active repository, allow-listed programs and notifications.
+#[derive(Clone)]
+struct Sandbox {
+    repo_ref: Option<String>,
+    allowed_commands: Arc<Vec<String>>,
+    notifications: Arc<Mutex<Vec<PluginNotification>>>,
+    manifest: Arc<Mutex<PluginInfo>>,
+}
+
+pub fn plugins_dir() -> PathBuf {
+    data_dir().join("plugins")
+}
+
+pub async fn load_plugin_settings(db: &SqlitePool) -> Result<PluginSettings> {
+    let section = load_app_settings_section(db, "plugins").await?.unwrap_or_default();
+    Ok(serde_json::from_value(section).unwrap_or(PluginSettings { enabled: default_enabled(), allowed_commands: default_allowed_commands() }))
+}
+
+/// Compiles every `*.rhai` file in the plugin directory and collects what it registers.
+pub fn catalog(settings: &PluginSettings) -> Result<PluginCatalog> {
+    let dir = plugins_dir();
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let mut plugins = Vec::new();
+    if settings.enabled {
+        let mut paths = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == PLUGIN_EXTENSION))
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            let sandbox = Sandbox::new(settings, None);
+            let info = match load(&path, &sandbox) {
+                Ok(_) => sandbox.manifest.lock().unwrap().clone(),
+                Err(err) => PluginInfo { error: Some(format!("{err:#}")), ..Default::default() },
+            };
+            plugins.push(PluginInfo { id: plugin_id(&path), path: path.to_string_lossy().to_string(), ..info });
+        }
+    }
+    Ok(PluginCatalog {
+        dir: dir.to_string_lossy().to_string(),
+        enabled: settings.enabled,
+        allowed_commands: settings.allowed_commands.clone(),
+        plugins,
+    })
+}
+
+/// Runs a registered palette command. Its return value, if any, becomes `output`.
+pub fn run_command(settings: &PluginSettings, plugin: &str, command: &str, repo_ref: Option<&str>) -> Result<PluginOutput> {
+    invoke(settings, plugin, repo_ref, |info| info.commands.iter().any(|entry| entry.id == command), command)
+}
+
+/// Renders a registered panel; the function returns text (or an array of lines) to display.
+pub fn render_panel(settings: &PluginSettings, plugin: &str, panel: &str, repo_ref: Option<&str>) -> Result<PluginOutput> {
+    invoke(settings, plugin, repo_ref, |info| info.panels.iter().any(|entry| entry.id == panel), panel)
+}
+
+fn invoke(
+    settings: &PluginSettings,
+    plugin: &str,
+    repo_ref: Option<&str>,
+    registered: impl Fn(&PluginInfo) -> bool,
+    function: &str,
+) -> Result<PluginOutput> {
+    if !settings.enabled {
+        bail!("plugins are disabled in settings");
+    }
+    let path = plugins_dir().join(format!("{plugin}.{PLUGIN_EXTENSION}"));
+    if plugin.contains(['/', '\\']) || !path.is_file() {
+        bail!("plugin {plugin} not found");
+    }
+    let sandbox = Sandbox::new(settings, repo_ref.map(str::to_string));
+    let (engine, ast) = load(&path, &sandbox)?;
+    if !registered(&sandbox.manifest.lock().unwrap()) {
+        bail!("plugin {plugin} does not register {function}");
+    }
+    let result = engine
+        .call_fn_with_options::<Dynamic>(CallFnOptions::new().eval_ast(false), &mut Scope::new(), &ast, function, ())
+        .map_err(|err| anyhow!("{plugin}::{function} failed: {err}"))?;
+    let output = if result.is_unit() {
+        String::new()
+    } else if result.is_array() {
+        result.cast::<rhai::Array>().iter().map(|line| line.to_string()).collect::<Vec<_>>().join("\n")
+    } else {
+        result.to_string()
+    };
+    let notifications = std::mem::take(&mut *sandbox.notifications.lock().unwrap());
+    Ok(PluginOutput { output: truncate(output), notifications })
+}
+
+/// Compiles the script and evaluates its top level, which is where `command()` / `panel()` run.
+fn load(path: &Path, sandbox: &Sandbox) -> Result<(Engine, AST)> {
+    let source = fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let engine = sandbox.engine();
+    let ast = engine.compile(&source).map_err(|err| anyhow!("{} does not compile: {err}", path.display()))?;
+    engine.run_ast(&ast).map_err(|err| anyhow!("{} failed while loading: {err}", path.display()))?;
+    Ok((engine, ast))
+}
+
+impl Sandbox {
+    fn new(settings: &PluginSettings, repo_ref: Option<String>) -> Self {
+        Self {
+            repo_ref,
+            allowed_commands: Arc::new(settings.allowed_commands.clone()),
+            notifications: Arc::default(),
+            manifest: Arc::default(),
+        }
+    }
+
+    fn engine(&self) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(32);
+        engine.set_max_string_size(4 * 1024 * 1024);
+        engine.set_max_array_size(100_000);
+        engine.set_max_map_size(100_000);
+
+        let manifest = self.manifest.clone();
+        engine.register_fn("describe", move |text: &str| {
+            manifest.lock().unwrap().description = Some(text.to_string());
+        });
+        let manifest = self.manifest.clone();
+        engine.register_fn("command", move |id: &str, title: &str| {
+            manifest.lock().unwrap().commands.push(PluginEntry { id: id.to_string(), title: title.to_string() });
+        });
+        let manifest = self.manifest.clone();
+        engine.register_fn("panel", move |id: &str, title: &str| {
+            manifest.lock().unwrap().panels.push(PluginEntry { id: id.to_string(), title: title.to_string() });
+        });
+
+        let notifications = self.notifications.clone();
+        engine.register_fn("notify", move |message: &str| {
+            notifications.lock().unwrap().push(PluginNotification { level: "info".to_string(), message: message.to_string() });
+        });
+        let notifications = self.notifications.clone();
+        engine.register_fn("notify", move |level: &str, message: &str| {
+            notifications.lock().unwrap().push(PluginNotification { level: level.to_string(), message: message.to_string() });
+        });
+
+        let repo_ref = self.repo_ref.clone();
+        engine.register_fn("repo", move || repo_ref.clone().unwrap_or_default());
+        let repo_ref = self.repo_ref.clone();
+        engine.register_fn("read_file", move |path: &str| -> Result<String, Box<EvalAltResult>> {
+            let repo_ref = repo_ref.as_deref().ok_or("no repository is open")?;
+            read_text_file(repo_ref, path).map_err(|err| format!("{err:#}").into())
+        });
+        let repo_ref = self.repo_ref.clone();
+        let allowed = self.allowed_commands.clone();
+        engine.register_fn("run", move |command: &str| -> Result<Map, Box<EvalAltResult>> {
+            let repo_ref = repo_ref.as_deref().ok_or("no repository is open")?;
+            run_allowed(Path::new(repo_ref), &allowed, command).map_err(|err| format!("{err:#}").into())
+        });
+        engine
+    }
+}
+
+/// Launches the program directly (no shell), so only the allow-listed binary can ever run.
+fn run_allowed(repo: &Path, allowed: &[String], command: &str) -> Result<Map> {
+    let mut words = command.split_whitespace();
+    let program = words.next().ok_or_else(|| anyhow!("empty command"))?;
+    if !allowed.iter().any(|entry| entry.trim() == program) {
+        bail!("{program} is not in the plugin command allow-list");
+    }
+    let args = words.collect::<Vec<_>>();
+    if program == "git" {
+        check_git_args(&args)?;
+    }
+    let mut cmd = Command::new(program);
+    if program == "git" {
+        cmd.arg("--no-pager").env("GIT_PAGER", "cat").env("PAGER", "cat");
+    }
+    cmd.args(&args).current_dir(repo);
+    let output = processes::run_tracked("plugin", command, cmd).with_context(|| format!("failed to run {program}"))?;
+    let mut result = Map::new();
+    result.insert("code".into(), Dynamic::from_int(output.status.code().unwrap_or(-1) as rhai::INT));
+    result.insert("stdout".into(), truncate(String::from_utf8_lossy(&output.stdout).to_string()).into());
+    result.insert("stderr".into(), truncate(String::from_utf8_lossy(&output.stderr).to_string()).into());
+    Ok(result)
+}
+
+fn check_git_args(args: &[&str]) -> Result<()> {
+    let subcommand = args.first().copied().unwrap_or_default();
+    if !GIT_SUBCOMMANDS.contains(&subcommand) {
+        bail!("plugins may only run git {}", GIT_SUBCOMMANDS.join(", "));
+    }
+    for arg in args[1..].iter().take_while(|arg| **arg != "--") {
+        if !arg.starts_with('-') {
+            continue;
+        }
+        if arg.starts_with("--") {
+            let name = arg.split('=').next().unwrap_or_default();
+            if !GIT_ALLOWED_LONG_OPTIONS.contains(&name) {
+                bail!("git {name} is not allowed in plugin commands");
+            }
+        } else if let Some(flag) = arg.chars().skip(1).find(|flag| GIT_FORBIDDEN_SHORT_FLAGS.contains(flag)) {
+            bail!("git -{flag} is not allowed in plugin commands");
+        }
+    }
+    Ok(())
+}
+
+fn plugin_id(path: &Path) -> String {
+    path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn truncate(text: String) -> String {
+    if text.chars().count() <= MAX_OUTPUT_CHARS {
+        return text;
+    }
+    format!("{}…", text.chars().take(MAX_OUTPUT_CHARS).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_args_refuse_pager_and_config_flags_in_clusters() {
+        for args in [
+            &["grep", "-nOecho hi"][..],
+            &["grep", "-O"],
+            &["grep", "-iO", "pattern"],
+            &["log", "-c", "core.pager=sh"],
+            &["status", "-C", "/"],
+        ] {
+            assert!(check_git_args(args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn git_args_only_accept_listed_long_options() {
+        for args in [
+            &["grep", "--open-files-in-pager=echo", "x"][..],
+            &["grep", "--open", "x"],
+            &["diff", "--output=/tmp/out"],
+            &["diff", "--out=/tmp/out"],
+            &["log", "--exec-path=/tmp"],
+            &["show", "--textconv"],
+            &["ls-files", "--git-dir=/"],
+        ] {
+            assert!(check_git_args(args).is_err(), "{args:?}");
+        }
+        assert!(check_git_args(&["push", "origin"]).is_err());
+        assert!(check_git_args(&["log", "--oneline", "-n5", "--format=%H", "--", "-src"]).is_ok());
+        assert!(check_git_args(&["grep", "-ni", "needle"]).is_ok());
+    }
+
+    #[test]
+    fn plugins_are_off_unless_enabled() {
+        let settings: PluginSettings = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(!settings.enabled);
+    }
+}
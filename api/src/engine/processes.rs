@@ -0,0 +1,338 @@
+use std::{
+    collections::VecDeque,
+    ffi::OsString,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+
+/// Finished processes kept for the panel after they exit; running ones are never dropped.
+const MAX_FINISHED: usize = 50;
+/// Output a restarted process keeps, since nothing else is reading it.
+const MAX_TAIL_BYTES: usize = 16 * 1024;
+/// Time between the polite and the forced kill when a timeout runs out.
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    entries: VecDeque<Entry>,
+}
+
+struct Entry {
+    id: u64,
+    pid: u32,
+    kind: String,
+    label: String,
+    spec: Spec,
+    started_at: String,
+    started: Instant,
+    ended: Option<Instant>,
+    exit_code: Option<i32>,
+    kill_requested: bool,
+    output_tail: Option<String>,
+}
+
+/// Enough of the original command to start it again.
+#[derive(Clone)]
+struct Spec {
+    program: OsString,
+    args: Vec<OsString>,
+    cwd: Option<PathBuf>,
+    envs: Vec<(OsString, OsString)>,
+}
+
+impl Spec {
+    fn of(cmd: &Command) -> Self {
+        Self {
+            program: cmd.get_program().to_os_string(),
+            args: cmd.get_args().map(|arg| arg.to_os_string()).collect(),
+            cwd: cmd.get_current_dir().map(PathBuf::from),
+            envs: cmd
+                .get_envs()
+                .filter_map(|(key, value)| value.map(|value| (key.to_os_string(), value.to_os_string())))
+                .collect(),
+        }
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args).envs(self.envs.iter().map(|(key, value)| (key, value)));
+        if let Some(cwd) = &self.cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd
+    }
+
+    fn display(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|part| part.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub id: u64,
+    pub pid: u32,
+    /// What started it: `terminal`, `compile`, `save_hook`, `plugin`.
+    pub kind: String,
+    pub label: String,
+    pub command: String,
+    pub cwd: Option<String>,
+    pub started_at: String,
+    pub runtime_ms: u64,
+    /// `running`, `exited` or `killed`.
+    pub status: String,
+    pub exit_code: Option<i32>,
+    /// Share of one core used since start, from `/proc` on Linux; `None` elsewhere or once exited.
+    pub cpu_percent: Option<f64>,
+    /// Last output of a process restarted from the panel.
+    pub output_tail: Option<String>,
+}
+
+fn registry() -> &'static Mutex<Registry> {
+    REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+/// Runs `cmd` to completion like `Command::output`, listed in the process panel while it runs.
+/// On Unix the child leads its own process group so a kill also reaches whatever a shell started.
+pub fn run_tracked(kind: &str, label: &str, cmd: Command) -> Result<Output> {
+    run_tracked_with_timeout(kind, label, cmd, None).map(|(output, _)| output)
+}
+
+/// [`run_tracked`] that stops the process group once `timeout` passes, first with SIGTERM and then,
+/// if it is still running after a short grace period, SIGKILL. Also reports whether that happened.
+pub fn run_tracked_with_timeout(kind: &str, label: &str, mut cmd: Command, timeout: Option<Duration>) -> Result<(Output, bool)> {
+    let spec = Spec::of(&cmd);
+    let child = spawn_in_group(&mut cmd).with_context(|| format!("failed to start {}", spec.display()))?;
+    let pid = child.id();
+    let id = register(kind, label, pid, spec);
+
+    let (done, finished) = mpsc::channel::<()>();
+    let watchdog = timeout.map(|timeout| {
+        std::thread::spawn(move || {
+            if finished.recv_timeout(timeout) != Err(RecvTimeoutError::Timeout) {
+                return false;
+            }
+            mark_kill_requested(id);
+            if signal(pid, false).is_err() {
+                return false;
+            }
+            if finished.recv_timeout(KILL_GRACE) == Err(RecvTimeoutError::Timeout) {
+                let _ = signal(pid, true);
+            }
+            true
+        })
+    });
+    let output = child.wait_with_output();
+    drop(done);
+    let timed_out = watchdog.is_some_and(|watchdog| watchdog.join().unwrap_or(false));
+    finish(id, output.as_ref().ok().and_then(|output| output.status.code()), None);
+    Ok((output?, timed_out))
+}
+
+fn mark_kill_requested(id: u64) {
+    if let Some(entry) = registry().lock().unwrap().entries.iter_mut().find(|entry| entry.id == id) {
+        entry.kill_requested = true;
+    }
+}
+
+fn spawn_in_group(cmd: &mut Command) -> std::io::Result<std::process::Child> {
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    cmd.spawn()
+}
+
+fn register(kind: &str, label: &str, pid: u32, spec: Spec) -> u64 {
+    let mut registry = registry().lock().unwrap();
+    registry.next_id += 1;
+    let id = registry.next_id;
+    registry.entries.push_back(Entry {
+        id,
+        pid,
+        kind: kind.to_string(),
+        label: label.to_string(),
+        spec,
+        started_at: Utc::now().to_rfc3339(),
+        started: Instant::now(),
+        ended: None,
+        exit_code: None,
+        kill_requested: false,
+        output_tail: None,
+    });
+    let finished = registry.entries.iter().filter(|entry| entry.ended.is_some()).count();
+    if finished > MAX_FINISHED {
+        if let Some(index) = registry.entries.iter().position(|entry| entry.ended.is_some()) {
+            registry.entries.remove(index);
+        }
+    }
+    id
+}
+
+fn finish(id: u64, exit_code: Option<i32>, output_tail: Option<String>) {
+    let mut registry = registry().lock().unwrap();
+    if let Some(entry) = registry.entries.iter_mut().find(|entry| entry.id == id) {
+        entry.ended = Some(Instant::now());
+        entry.exit_code = exit_code;
+        if output_tail.is_some() {
+            entry.output_tail = output_tail;
+        }
+    }
+}
+
+/// Newest first.
+pub fn list() -> Vec<ProcessInfo> {
+    let registry = registry().lock().unwrap();
+    registry.entries.iter().rev().map(info).collect()
+}
+
+fn info(entry: &Entry) -> ProcessInfo {
+    let runtime = entry.ended.unwrap_or_else(Instant::now).duration_since(entry.started);
+    let status = match (entry.ended, entry.kill_requested) {
+        (None, _) => "running",
+        (Some(_), true) => "killed",
+        (Some(_), false) => "exited",
+    };
+    ProcessInfo {
+        id: entry.id,
+        pid: entry.pid,
+        kind: entry.kind.clone(),
+        label: entry.label.clone(),
+        command: entry.spec.display(),
+        cwd: entry.spec.cwd.as_ref().map(|cwd| cwd.to_string_lossy().to_string()),
+        started_at: entry.started_at.clone(),
+        runtime_ms: runtime.as_millis() as u64,
+        status: status.to_string(),
+        exit_code: entry.exit_code,
+        cpu_percent: if entry.ended.is_none() { cpu_percent(entry.pid, runtime.as_secs_f64()) } else { None },
+        output_tail: entry.output_tail.clone(),
+    }
+}
+
+/// Stops a running process and everything in its group. A second kill escalates to SIGKILL.
+pub fn kill(id: u64) -> Result<ProcessInfo> {
+    let (pid, force) = {
+        let mut registry = registry().lock().unwrap();
+        let entry = registry.entries.iter_mut().find(|entry| entry.id == id).ok_or_else(|| anyhow!("no process {id}"))?;
+        if entry.ended.is_some() {
+            bail!("process {id} has already exited");
+        }
+        let force = entry.kill_requested;
+        entry.kill_requested = true;
+        (entry.pid, force)
+    };
+    signal(pid, force)?;
+    get(id).ok_or_else(|| anyhow!("no process {id}"))
+}
+
+/// Kills the process if it still runs, then starts the same command again in the background.
+pub fn restart(id: u64) -> Result<ProcessInfo> {
+    let (kind, label, spec, running) = {
+        let registry = registry().lock().unwrap();
+        let entry = registry.entries.iter().find(|entry| entry.id == id).ok_or_else(|| anyhow!("no process {id}"))?;
+        (entry.kind.clone(), entry.label.clone(), entry.spec.clone(), entry.ended.is_none())
+    };
+    if running {
+        kill(id)?;
+    }
+
+    let mut cmd = spec.command();
+    let child = spawn_in_group(&mut cmd).with_context(|| format!("failed to start {}", spec.display()))?;
+    let new_id = register(&kind, &label, child.id(), spec);
+    std::thread::spawn(move || {
+        let output = child.wait_with_output();
+        let (code, tail) = match output {
+            Ok(output) => {
+                let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                (output.status.code(), tail_of(&text))
+            }
+            Err(err) => (None, err.to_string()),
+        };
+        finish(new_id, code, Some(tail));
+    });
+    get(new_id).ok_or_else(|| anyhow!("no process {new_id}"))
+}
+
+/// Called on shutdown so commands started from the app do not outlive it.
+pub fn kill_all() {
+    let running: Vec<u32> = {
+        let mut registry = registry().lock().unwrap();
+        registry
+            .entries
+            .iter_mut()
+            .filter(|entry| entry.ended.is_none())
+            .map(|entry| {
+                entry.kill_requested = true;
+                entry.pid
+            })
+            .collect()
+    };
+    for pid in running {
+        let _ = signal(pid, false);
+    }
+}
+
+pub fn get(id: u64) -> Option<ProcessInfo> {
+    registry().lock().unwrap().entries.iter().find(|entry| entry.id == id).map(info)
+}
+
+fn tail_of(text: &str) -> String {
+    if text.len() <= MAX_TAIL_BYTES {
+        return text.to_string();
+    }
+    let mut start = text.len() - MAX_TAIL_BYTES;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("…{}", &text[start..])
+}
+
+fn signal(pid: u32, force: bool) -> Result<()> {
+    #[cfg(unix)]
+    let status = Command::new("kill")
+        .args([if force { "-KILL" } else { "-TERM" }, "--", &format!("-{pid}")])
+        .status();
+    #[cfg(not(unix))]
+    let status = {
+        let _ = force;
+        Command::new("taskkill").args(["/PID", &pid.to_string(), "/T", "/F"]).status()
+    };
+    let status = status.context("failed to send the kill signal")?;
+    if !status.success() {
+        bail!("could not signal process {pid}; it may have just exited");
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_percent(pid: u32, runtime_secs: f64) -> Option<f64> {
+    const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name may contain spaces, so fields are counted from after its closing parenthesis.
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let ticks: f64 = fields.get(11..15)?.iter().filter_map(|field| field.parse::<f64>().ok()).sum();
+    (runtime_secs > 0.0).then(|| (ticks / CLOCK_TICKS_PER_SEC / runtime_secs * 100.0).min(100.0 * 64.0))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cpu_percent(_pid: u32, _runtime_secs: f64) -> Option<f64> {
+    None
+}
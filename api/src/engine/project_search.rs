@@ -0,0 +1,312 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    fs,
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::engine::{
+    capabilities::{
+        changeset::{apply_changeset, ChangesetRequest},
+        filesystem,
+        git::git::run_git,
+    },
+    doc_comments::preview_patch,
+};
+
+const DEFAULT_MAX_RESULTS: usize = 2000;
+/// Larger files are skipped; they are almost always generated or vendored.
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchQuery {
+    pub repo_ref: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Repo-relative files or directories to search, e.g. the current tree filter; empty searches everything.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub path: String,
+    pub line: usize,
+    /// 1-based character column of the match start.
+    pub column: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+    pub files: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplacePreview {
+    pub path: String,
+    pub replacements: usize,
+    pub patch: String,
+    /// Identifies the contents the preview was built from; apply refuses files that changed since.
+    pub fingerprint: String,
+}
+
+/// Tracked and untracked, non-ignored text files under `paths`, matched line by line.
+pub fn search(query: &SearchQuery) -> Result<SearchResults> {
+    let regex = build_regex(query)?;
+    let repo = Path::new(query.repo_ref.trim());
+    let max = query.max_results.unwrap_or(DEFAULT_MAX_RESULTS).max(1);
+    let mut matches = Vec::new();
+    let mut files = 0;
+    for path in candidate_files(repo, &query.paths)? {
+        let Some(text) = read_text(repo, &path) else { continue };
+        let before = matches.len();
+        for (index, line) in text.lines().enumerate() {
+            for found in regex.find_iter(line) {
+                if matches.len() == max {
+                    return Ok(SearchResults { matches, files: files + 1, truncated: true });
+                }
+                matches.push(SearchMatch {
+                    path: path.clone(),
+                    line: index + 1,
+                    column: line[..found.start()].chars().count() + 1,
+                    text: line.to_string(),
+                });
+            }
+        }
+        if matches.len() > before {
+            files += 1;
+        }
+    }
+    Ok(SearchResults { matches, files, truncated: false })
+}
+
+/// One preview diff per file the replacement would change. Nothing is written.
+pub fn preview_replace(query: &SearchQuery, replacement: &str) -> Result<Vec<ReplacePreview>> {
+    let regex = build_regex(query)?;
+    let repo = Path::new(query.repo_ref.trim());
+    let mut out = Vec::new();
+    for path in candidate_files(repo, &query.paths)? {
+        let Some(text) = read_text(repo, &path) else { continue };
+        let (edited, replacements) = replace_lines(&regex, query.regex, &text, replacement);
+        if replacements == 0 || edited == text {
+            continue;
+        }
+        out.push(ReplacePreview {
+            patch: preview_patch(repo, &path, &edited)?,
+            fingerprint: fingerprint(&text),
+            path,
+            replacements,
+        });
+    }
+    Ok(out)
+}
+
+/// Rewrites the previewed files as one changeset, so the writes are linted and recorded like any other apply.
+/// `expected` maps each path to the fingerprint from its preview; every file is checked before any is written.
+pub async fn apply_replace(db: &SqlitePool, query: &SearchQuery, replacement: &str, expected: &BTreeMap<String, String>) -> Result<Value> {
+    let operations = {
+        let (query, replacement, expected) = (query.clone(), replacement.to_string(), expected.clone());
+        tokio::task::spawn_blocking(move || replace_operations(&query, &replacement, &expected)).await??
+    };
+
+    let payload_text = serde_json::to_string_pretty(&json!({
+        "version": 1,
+        "description": format!("Replace \"{}\" with \"{replacement}\"", query.pattern),
+        "operations": operations,
+    }))?;
+    apply_changeset(
+        db,
+        ChangesetRequest {
+            repo_ref: query.repo_ref.trim().to_string(),
+            git_ref: "WORKTREE".to_string(),
+            payload_text,
+            source: "search_replace".to_string(),
+            workflow_key: None,
+            run_id: None,
+            step_id: None,
+            stage_after_apply: false,
+            commit_after_apply: false,
+            commit_message: None,
+        },
+    )
+    .await
+}
+
+/// One `write` operation per expected file, refusing the lot when any file no longer matches its preview.
+fn replace_operations(query: &SearchQuery, replacement: &str, expected: &BTreeMap<String, String>) -> Result<Vec<Value>> {
+    if expected.is_empty() {
+        bail!("no files selected for replacement");
+    }
+    let regex = build_regex(query)?;
+    let repo = Path::new(query.repo_ref.trim());
+    let mut operations = Vec::new();
+    for (path, want) in expected {
+        let path = filesystem::normalize_rel_path(path)?;
+        let text = read_text(repo, &path).with_context(|| format!("{path} is no longer a readable text file"))?;
+        if fingerprint(&text) != *want {
+            bail!("{path} changed since the preview; search again before replacing");
+        }
+        let (edited, _) = replace_lines(&regex, query.regex, &text, replacement);
+        operations.push(json!({ "op": "write", "path": path, "contents": edited }));
+    }
+    Ok(operations)
+}
+
+pub fn render_markdown(query: &SearchQuery, results: &SearchResults) -> String {
+    let mut out = format!(
+        "# Search results\n\nPattern: `{}`{}\n\n{} match(es) in {} file(s){}\n",
+        query.pattern,
+        if query.regex { " (regex)" } else { "" },
+        results.matches.len(),
+        results.files,
+        if results.truncated { ", truncated" } else { "" },
+    );
+    let mut current = None;
+    for found in &results.matches {
+        if current != Some(found.path.as_str()) {
+            out.push_str(&format!("\n## {}\n\n| Line | Col | Text |\n| ---: | ---: | --- |\n", found.path));
+            current = Some(found.path.as_str());
+        }
+        let text = found.text.trim().replace('|', "\\|");
+        out.push_str(&format!("| {} | {} | `{}` |\n", found.line, found.column, text.replace('`', "'")));
+    }
+    out
+}
+
+pub fn render_csv(results: &SearchResults) -> String {
+    let mut out = String::from("path,line,column,text\n");
+    for found in &results.matches {
+        let cells = [csv_cell(&found.path), found.line.to_string(), found.column.to_string(), csv_cell(&found.text)];
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn build_regex(query: &SearchQuery) -> Result<Regex> {
+    if query.pattern.is_empty() {
+        bail!("search pattern is empty");
+    }
+    let pattern = if query.regex { query.pattern.clone() } else { regex::escape(&query.pattern) };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .build()
+        .with_context(|| format!("invalid regular expression: {}", query.pattern))
+}
+
+fn candidate_files(repo: &Path, paths: &[String]) -> Result<Vec<String>> {
+    let mut args = vec!["ls-files", "--cached", "--others", "--exclude-standard", "--"];
+    args.extend(paths.iter().map(String::as_str).filter(|path| !path.trim().is_empty()));
+    let listing = run_git(repo, &args)?;
+    let mut files: Vec<String> = String::from_utf8_lossy(&listing).lines().map(str::to_string).collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn read_text(repo: &Path, path: &str) -> Option<String> {
+    let full = filesystem::resolve_workspace_path(&repo.to_string_lossy(), path).ok()?;
+    if fs::metadata(&full).ok()?.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let text = String::from_utf8(fs::read(&full).ok()?).ok()?;
+    (!text.contains('\0')).then_some(text)
+}
+
+/// Replaces within each line, leaving line endings alone, so matches never span lines.
+fn replace_lines(regex: &Regex, expand: bool, text: &str, replacement: &str) -> (String, usize) {
+    let mut out = String::with_capacity(text.len());
+    let mut count = 0;
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\n', '\r']);
+        let ending = &line[body.len()..];
+        let hits = regex.find_iter(body).count();
+        if hits == 0 {
+            out.push_str(line);
+            continue;
+        }
+        count += hits;
+        let replaced = if expand { regex.replace_all(body, replacement) } else { regex.replace_all(body, NoExpand(replacement)) };
+        out.push_str(&replaced);
+        out.push_str(ending);
+    }
+    (out, count)
+}
+
+fn fingerprint(text: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(repo_ref: &str, pattern: &str, regex: bool) -> SearchQuery {
+        SearchQuery {
+            repo_ref: repo_ref.to_string(),
+            pattern: pattern.to_string(),
+            regex,
+            case_sensitive: true,
+            paths: Vec::new(),
+            max_results: None,
+        }
+    }
+
+    #[test]
+    fn replacements_stay_within_lines_and_keep_endings() {
+        let literal = build_regex(&query("", "a$1", false)).unwrap();
+        assert_eq!(replace_lines(&literal, false, "a$1 a$1\r\nb\n", "$0"), ("$0 $0\r\nb\n".to_string(), 2));
+
+        let words = build_regex(&query("", r"(\w+)=(\w+)", true)).unwrap();
+        assert_eq!(replace_lines(&words, true, "x=1\ny=2", "$2=$1"), ("1=x\n2=y".to_string(), 2));
+
+        let across = build_regex(&query("", r"end\s+start", true)).unwrap();
+        assert_eq!(replace_lines(&across, true, "end\nstart\n", "joined"), ("end\nstart\n".to_string(), 0));
+    }
+
+    #[test]
+    fn files_changed_since_the_preview_are_refused() {
+        let repo = std::env::temp_dir().join(format!("search-replace-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        fs::write(repo.join("a.txt"), "old value\n").unwrap();
+        fs::write(repo.join("b.txt"), "old too\n").unwrap();
+        let query = query(&repo.to_string_lossy(), "old", false);
+
+        let previews = preview_replace(&query, "new").unwrap();
+        assert_eq!(previews.iter().map(|preview| preview.path.as_str()).collect::<Vec<_>>(), ["a.txt", "b.txt"]);
+        let expected = previews.into_iter().map(|preview| (preview.path, preview.fingerprint)).collect::<BTreeMap<_, _>>();
+        let operations = replace_operations(&query, "new", &expected).unwrap();
+        assert_eq!(operations[0]["contents"], "new value\n");
+
+        fs::write(repo.join("b.txt"), "old, edited since\n").unwrap();
+        let err = replace_operations(&query, "new", &expected).unwrap_err();
+        assert!(err.to_string().contains("b.txt changed since the preview"), "{err:#}");
+        assert!(replace_operations(&query, "new", &BTreeMap::new()).is_err());
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{
+    app_state::AppState,
+    engine::{
+        ai_actions::{complete, truncate_chars},
+        capabilities::{
+            analysis::{
+                commands::{detect_project_commands, ProjectCommand},
+                dependencies::collect_dependency_inventory,
+                sizes::{collect_file_sizes, FileSizeEntry},
+            },
+            filesystem::write_text_file,
+            inference::InferenceConfig,
+        },
+    },
+    runtime_env::data_dir,
+};
+
+const MAX_README_CHARS: usize = 6_000;
+const MAX_ENTRY_POINT_LINES: usize = 60;
+const MAX_ENTRY_POINTS: usize = 12;
+const MAX_OUTLINE_CHILDREN: usize = 12;
+
+/// Default location when the report is saved into the repository.
+pub const DEFAULT_REPO_REPORT_PATH: &str = "docs/OVERVIEW.md";
+
+const ARCHITECTURE_INSTRUCTIONS: &str = "You are onboarding a new contributor to this repository. From the README, \
+layout, entry points and commands provided, describe its architecture: the main components and what each is \
+responsible for, how they talk to each other, how a request or command flows through the system, and where a \
+newcomer should start reading. Answer in Markdown without a top-level heading, using ### subheadings, in 300 to \
+600 words. Only describe what the material supports.";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoOverview {
+    pub markdown: String,
+    pub model: String,
+    pub total_tokens: Option<u64>,
+}
+
+/// Builds the onboarding report: stats and layout come from analysis, the architecture section from the model.
+pub async fn generate(state: &AppState, config: &InferenceConfig, repo: &Path) -> Result<RepoOverview> {
+    let sizes = collect_file_sizes(repo)?;
+    if sizes.is_empty() {
+        bail!("the repository has no files to describe");
+    }
+    let commands = detect_project_commands(repo)?;
+    let dependencies = collect_dependency_inventory(repo).unwrap_or_default().dependencies;
+    let entry_points = entry_points(repo, &sizes);
+    let layout = directory_outline(&sizes);
+    let readme = sizes
+        .iter()
+        .find(|entry| !entry.path.contains('/') && entry.path.to_ascii_lowercase().starts_with("readme"))
+        .and_then(|entry| fs::read_to_string(repo.join(&entry.path)).ok())
+        .unwrap_or_default();
+
+    let mut input = String::new();
+    if !readme.trim().is_empty() {
+        input.push_str(&format!("README:\n{}\n\n", truncate_chars(&readme, MAX_README_CHARS).0));
+    }
+    input.push_str(&format!("Layout (files, lines):\n{layout}\n"));
+    input.push_str(&format!("Commands:\n{}\n\n", commands.iter().map(|c| format!("- {} ({})", c.command, c.source)).collect::<Vec<_>>().join("\n")));
+    for path in &entry_points {
+        let head = fs::read_to_string(repo.join(path))
+            .map(|text| text.lines().take(MAX_ENTRY_POINT_LINES).collect::<Vec<_>>().join("\n"))
+            .unwrap_or_default();
+        input.push_str(&format!("Entry point {path}:\n```\n{head}\n```\n\n"));
+    }
+    let (architecture, total_tokens) = complete(state, config, ARCHITECTURE_INSTRUCTIONS, input).await?;
+
+    let name = repo.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| "Repository".to_string());
+    let mut markdown = format!("# {name} overview\n\n_Generated {} with {}._\n\n", Utc::now().format("%Y-%m-%d"), config.model);
+    markdown.push_str("## At a glance\n\n");
+    markdown.push_str(&format!(
+        "- {} files, {} lines\n",
+        sizes.len(),
+        sizes.iter().map(|entry| entry.lines).sum::<u64>()
+    ));
+    for (language, (files, lines)) in language_totals(&sizes).into_iter().take(6) {
+        markdown.push_str(&format!("- {language}: {files} files, {lines} lines\n"));
+    }
+    if !dependencies.is_empty() {
+        let mut ecosystems: BTreeMap<&str, usize> = BTreeMap::new();
+        for entry in &dependencies {
+            *ecosystems.entry(entry.ecosystem.as_str()).or_default() += 1;
+        }
+        let summary = ecosystems.iter().map(|(ecosystem, count)| format!("{count} {ecosystem}")).collect::<Vec<_>>().join(", ");
+        markdown.push_str(&format!("- Dependencies: {summary}\n"));
+    }
+    markdown.push_str(&format!("\n## Architecture\n\n{}\n\n", architecture.trim()));
+    markdown.push_str(&format!("## Layout\n\n```\n{layout}```\n\n"));
+    if !entry_points.is_empty() {
+        markdown.push_str("## Entry points\n\n");
+        for path in &entry_points {
+            markdown.push_str(&format!("- `{path}`\n"));
+        }
+        markdown.push('\n');
+    }
+    markdown.push_str(&commands_section(&commands));
+
+    Ok(RepoOverview { markdown, model: config.model.clone(), total_tokens })
+}
+
+/// Writes the report into the repository at `path`, or into app data when `path` is `None`.
+/// Returns where it was written.
+pub fn save(repo: &Path, markdown: &str, path: Option<&str>) -> Result<PathBuf> {
+    match path {
+        Some(path) => {
+            write_text_file(&repo.to_string_lossy(), path, markdown)?;
+            Ok(repo.join(path))
+        }
+        None => {
+            let name: String = repo
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+                .chars()
+                .map(|ch| if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' { ch } else { '_' })
+                .collect();
+            let target = data_dir().join("overviews").join(format!("{}.md", if name.is_empty() { "repo" } else { &name }));
+            fs::create_dir_all(target.parent().expect("overview path has a parent"))?;
+            fs::write(&target, markdown)?;
+            Ok(target)
+        }
+    }
+}
+
+/// File and line counts.
+type Totals = (usize, u64);
+
+fn language_totals(sizes: &[FileSizeEntry]) -> Vec<(&str, Totals)> {
+    let mut totals: BTreeMap<&str, Totals> = BTreeMap::new();
+    for entry in sizes.iter().filter(|entry| !matches!(entry.language.as_str(), "Other" | "Docs" | "Config")) {
+        let total = totals.entry(entry.language.as_str()).or_default();
+        total.0 += 1;
+        total.1 += entry.lines;
+    }
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_by_key(|(_, (_, lines))| std::cmp::Reverse(*lines));
+    totals
+}
+
+/// Two levels of directories, largest first, with file and line counts.
+fn directory_outline(sizes: &[FileSizeEntry]) -> String {
+    let mut top: BTreeMap<&str, (Totals, BTreeMap<&str, Totals>)> = BTreeMap::new();
+    for entry in sizes {
+        let mut parts = entry.path.splitn(3, '/');
+        let first = parts.next().unwrap_or("");
+        let Some(second) = parts.next() else { continue };
+        let (totals, children) = top.entry(first).or_default();
+        totals.0 += 1;
+        totals.1 += entry.lines;
+        if parts.next().is_some() {
+            let child = children.entry(second).or_default();
+            child.0 += 1;
+            child.1 += entry.lines;
+        }
+    }
+    let root_files = sizes.iter().filter(|entry| !entry.path.contains('/')).count();
+
+    let mut dirs: Vec<_> = top.into_iter().collect();
+    dirs.sort_by_key(|(_, ((_, lines), _))| std::cmp::Reverse(*lines));
+    let mut out = String::new();
+    for (dir, ((files, lines), children)) in dirs.into_iter().take(MAX_OUTLINE_CHILDREN) {
+        out.push_str(&format!("{dir}/  ({files} files, {lines} lines)\n"));
+        let mut children: Vec<_> = children.into_iter().collect();
+        children.sort_by_key(|(_, (_, lines))| std::cmp::Reverse(*lines));
+        for (child, (files, lines)) in children.into_iter().take(MAX_OUTLINE_CHILDREN) {
+            out.push_str(&format!("  {child}/  ({files} files, {lines} lines)\n"));
+        }
+    }
+    if root_files > 0 {
+        out.push_str(&format!("({root_files} files at the root)\n"));
+    }
+    out
+}
+
+/// Conventional program and library roots: `main.rs`, `lib.rs`, `main.go`, `__main__.py`, `index.ts` and friends.
+fn entry_points(repo: &Path, sizes: &[FileSizeEntry]) -> Vec<String> {
+    const NAMES: &[&str] = &[
+        "main.rs", "lib.rs", "main.go", "__main__.py", "main.py", "app.py", "manage.py", "cli.py", "main.ts", "main.tsx",
+        "index.ts", "index.tsx", "index.js", "server.ts", "server.js", "App.tsx",
+    ];
+    let mut found: Vec<String> = sizes
+        .iter()
+        .filter(|entry| entry.path.matches('/').count() <= 3)
+        .filter(|entry| {
+            let name = entry.path.rsplit('/').next().unwrap_or("");
+            NAMES.contains(&name) || entry.path.contains("/src/bin/") || entry.path.starts_with("src/bin/")
+        })
+        .filter(|entry| !entry.path.split('/').any(|part| matches!(part, "tests" | "test" | "examples" | "fixtures")))
+        .map(|entry| entry.path.clone())
+        .collect();
+    // package.json "main" points at the real entry when the name is unconventional.
+    for entry in sizes.iter().filter(|entry| entry.path.ends_with("package.json") && !entry.path.contains("node_modules")) {
+        let dir = entry.path.strip_suffix("package.json").unwrap_or("");
+        let main = fs::read_to_string(repo.join(&entry.path))
+            .ok()
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+            .and_then(|manifest| manifest.get("main").and_then(|main| main.as_str()).map(str::to_string));
+        if let Some(main) = main {
+            let path = format!("{dir}{}", main.trim_start_matches("./"));
+            if repo.join(&path).is_file() && !found.contains(&path) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort_by_key(|path| (path.matches('/').count(), path.clone()));
+    found.truncate(MAX_ENTRY_POINTS);
+    found
+}
+
+fn commands_section(commands: &[ProjectCommand]) -> String {
+    if commands.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("## Build and run\n\n| Kind | Command | From |\n| --- | --- | --- |\n");
+    for kind in ["build", "test", "run", "lint", "other"] {
+        for command in commands.iter().filter(|command| command.kind == kind) {
+            out.push_str(&format!("| {kind} | `{}` | `{}` |\n", command.command, command.source));
+        }
+    }
+    out
+}
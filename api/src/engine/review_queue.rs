@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{RunStatus, WorkflowRun},
+};
+
+use super::{append_engine_event, ensure_engine_root, ensure_value_object, load_run, persist_context, set_run_status};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewQueueItem {
+    pub run_id: Uuid,
+    pub title: String,
+    pub repo_ref: String,
+    pub status: String,
+    pub reason: &'static str,
+    pub current_step_id: Option<String>,
+    pub current_step_name: Option<String>,
+    pub last_message: Option<String>,
+    pub updated_at: String,
+}
+
+fn status_label(status: &RunStatus) -> String {
+    format!("{:?}", status).to_lowercase()
+}
+
+fn review_marker(run: &WorkflowRun) -> Option<&Value> {
+    run.context
+        .get("workflow_engine")
+        .and_then(|v| v.get("run_state"))
+        .and_then(|v| v.get("review"))
+}
+
+fn review_reason(run: &WorkflowRun) -> Option<&'static str> {
+    let step = run
+        .current_step_id
+        .as_deref()
+        .and_then(|id| run.definition.steps.iter().find(|step| step.id == id));
+
+    let reason = match run.status {
+        RunStatus::Error => "failed",
+        RunStatus::Waiting | RunStatus::Paused if step.map(|s| s.step_type == "review").unwrap_or(false) => "awaiting_review",
+        _ => return None,
+    };
+
+    let reviewed = review_marker(run)
+        .filter(|marker| marker.get("step_id").and_then(Value::as_str) == run.current_step_id.as_deref())
+        .filter(|marker| marker.get("run_status").and_then(Value::as_str) == Some(status_label(&run.status).as_str()))
+        .is_some();
+
+    if reviewed {
+        None
+    } else {
+        Some(reason)
+    }
+}
+
+pub async fn list_review_queue(state: &AppState) -> Result<Vec<ReviewQueueItem>> {
+    let rows = sqlx::query(
+        "SELECT id FROM workflow_runs WHERE status IN ('error', 'waiting', 'paused') ORDER BY updated_at DESC"
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        let run_id = Uuid::parse_str(row.get::<String, _>("id").as_str())?;
+        let run = load_run(state, run_id).await?;
+        let Some(reason) = review_reason(&run) else {
+            continue;
+        };
+
+        let last_message = sqlx::query(
+            "SELECT message FROM workflow_events WHERE run_id = ? ORDER BY sequence_no DESC, created_at DESC LIMIT 1"
+        )
+        .bind(run_id.to_string())
+        .fetch_optional(&state.db)
+        .await?
+        .map(|row| row.get::<String, _>("message"));
+
+        let current_step_name = run
+            .current_step_id
+            .as_deref()
+            .and_then(|id| run.definition.steps.iter().find(|step| step.id == id))
+            .map(|step| step.name.clone());
+
+        items.push(ReviewQueueItem {
+            run_id,
+            title: run.title.clone(),
+            repo_ref: run.repo_ref.clone(),
+            status: status_label(&run.status),
+            reason,
+            current_step_id: run.current_step_id.clone(),
+            current_step_name,
+            last_message,
+            updated_at: run.updated_at.to_rfc3339(),
+        });
+    }
+
+    Ok(items)
+}
+
+fn set_review_marker(run: &mut WorkflowRun, decision: &str, comment: Option<&str>) {
+    let step_id = run.current_step_id.clone();
+    let run_status = status_label(&run.status);
+    let root = ensure_engine_root(&mut run.context);
+    let run_state = ensure_value_object(root.entry("run_state".to_string()).or_insert_with(|| json!({})));
+    run_state.insert(
+        "review".to_string(),
+        json!({
+            "decision": decision,
+            "comment": comment,
+            "step_id": step_id,
+            "run_status": run_status,
+            "reviewed_at": Utc::now().to_rfc3339(),
+        }),
+    );
+}
+
+pub(crate) fn clear_review_marker(run: &mut WorkflowRun) {
+    let root = ensure_engine_root(&mut run.context);
+    if let Some(run_state) = root.get_mut("run_state").and_then(Value::as_object_mut) {
+        run_state.remove("review");
+    }
+}
+
+pub async fn approve_review(state: &AppState, run_id: Uuid) -> Result<Value> {
+    let mut run = load_run(state, run_id).await?;
+    set_review_marker(&mut run, "approved", None);
+    persist_context(state, run_id, &run.context).await?;
+
+    append_engine_event(
+        state,
+        run_id,
+        run.current_step_id.as_deref(),
+        "info",
+        "run_review_approved",
+        "Run marked as reviewed.",
+        json!({ "status": status_label(&run.status) }),
+    )
+    .await?;
+
+    Ok(json!({ "ok": true, "run_id": run_id, "decision": "approved" }))
+}
+
+pub async fn reject_review(state: &AppState, run_id: Uuid, comment: &str) -> Result<Value> {
+    let comment = comment.trim();
+    if comment.is_empty() {
+        return Err(anyhow!("a comment is required to reject a run"));
+    }
+
+    let mut run = load_run(state, run_id).await?;
+    let reopen = matches!(run.status, RunStatus::Error | RunStatus::Paused);
+    if reopen {
+        run.status = RunStatus::Waiting;
+    }
+    set_review_marker(&mut run, "rejected", Some(comment));
+
+    let root = ensure_engine_root(&mut run.context);
+    let global_state = ensure_value_object(root.entry("global_state".to_string()).or_insert_with(|| json!({})));
+    let capabilities = ensure_value_object(global_state.entry("capabilities".to_string()).or_insert_with(|| json!({})));
+    let inference = ensure_value_object(capabilities.entry("inference".to_string()).or_insert_with(|| json!({})));
+    let next = inference
+        .entry("next_prompt_fragments".to_string())
+        .or_insert_with(|| json!([]));
+    if !next.is_array() {
+        *next = json!([]);
+    }
+    next.as_array_mut()
+        .expect("next_prompt_fragments must be array")
+        .push(json!({
+            "text": format!("### REVIEW FEEDBACK\n{}\n\nAddress this feedback in your next response.", comment),
+            "source": "review_reject",
+        }));
+
+    persist_context(state, run_id, &run.context).await?;
+    if reopen {
+        set_run_status(state, run_id, RunStatus::Waiting, run.current_step_id.as_deref()).await?;
+    }
+
+    append_engine_event(
+        state,
+        run_id,
+        run.current_step_id.as_deref(),
+        "info",
+        "run_review_rejected",
+        "Run rejected; feedback will be sent with the next inference turn.",
+        json!({ "comment": comment }),
+    )
+    .await?;
+
+    Ok(json!({ "ok": true, "run_id": run_id, "decision": "rejected" }))
+}
@@ -18,6 +18,8 @@ use super::{
     persist_context,
     set_run_status,
 };
+use super::budget::{record_stage, reset_auto_budget};
+use super::review_queue::clear_review_marker;
 use super::stages::{execute_stage, StageDisposition};
 use super::transitions::{resolve_next_target, should_auto_advance};
 
@@ -106,7 +108,9 @@ pub async fn run_step(state: &AppState, run_id: Uuid, requested_step_id: Option<
 }
 
 async fn start_or_resume_automatic_run(state: &AppState, run_id: Uuid, requested_step_id: Option<&str>) -> Result<serde_json::Value> {
-    let run = load_run(state, run_id).await?;
+    let mut run = load_run(state, run_id).await?;
+    reset_auto_budget(&mut run);
+    persist_context(state, run_id, &run.context).await?;
     let definition = load_template_definition(state, &run).await?
         .ok_or_else(|| anyhow!("run has no template definition"))?;
 
@@ -222,6 +226,7 @@ async fn run_stages(state: &AppState, run_id: Uuid, requested_step_id: Option<&s
         activate_next_prompt_fragments_for_stage(&mut run);
         let outcome = execute_stage(state, run_id, &mut run, &step, automatic).await?;
         clear_active_prompt_fragments_for_stage(&mut run);
+        clear_review_marker(&mut run);
 
         if matches!(
             outcome.disposition,
@@ -250,6 +255,7 @@ async fn run_stages(state: &AppState, run_id: Uuid, requested_step_id: Option<&s
 
         let next_target = resolve_next_target(&definition, &step, &outcome);
         let auto_advance = automatic && should_auto_advance(&step, &outcome);
+        let guard_trip = if automatic { record_stage(&mut run, &step, &outcome, next_target.as_deref()) } else { None };
         let latest_run = load_run(state, run_id).await?;
         if run_pause_requested(&latest_run) {
             clear_run_pause_requested(&mut run);
@@ -274,6 +280,27 @@ async fn run_stages(state: &AppState, run_id: Uuid, requested_step_id: Option<&s
         }
         persist_context(state, run_id, &run.context).await?;
 
+        if let (Some(trip), Some(target)) = (guard_trip, next_target.clone()) {
+            set_run_status(state, run_id, RunStatus::Paused, Some(target.as_str())).await?;
+            append_engine_event(
+                state,
+                run_id,
+                Some(step.id.as_str()),
+                "warn",
+                "automatic_run_guard_fired",
+                &trip.message,
+                json!(trip),
+            ).await?;
+            return Ok(json!({
+                "ok": true,
+                "status": "paused",
+                "step_id": step.id,
+                "next_step_id": target,
+                "message": trip.message,
+                "guard": trip,
+            }));
+        }
+
         if matches!(mode, RunMode::Manual) {
             let status = match outcome.disposition {
                 StageDisposition::Paused => RunStatus::Paused,
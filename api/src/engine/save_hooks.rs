@@ -0,0 +1,205 @@
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    db::load_app_settings_section,
+    engine::capabilities::{compile_commands::run_pane_command, git::git::glob_to_regex_pattern},
+};
+
+/// Finished and running hook commands kept per repo for the terminal panes to pick up.
+const MAX_RUNS_PER_REPO: usize = 50;
+const MAX_DEBOUNCE_MS: u64 = 60_000;
+
+/// `terminal.save_hooks_by_repo.<repo>` in app settings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveHookSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    #[serde(default)]
+    pub rules: Vec<SaveHookRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveHookRule {
+    /// Gitignore-style pattern: `*.rs` matches at any depth, a leading `/` anchors at the repo root.
+    pub glob: String,
+    pub command: String,
+    /// Terminal pane whose shell and working directory the command uses, and where its output shows.
+    #[serde(default)]
+    pub pane: String,
+}
+
+fn default_debounce_ms() -> u64 {
+    800
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SaveHookRun {
+    pub id: Uuid,
+    pub glob: String,
+    pub command: String,
+    pub pane: String,
+    /// Files saved since the previous run of this rule.
+    pub paths: Vec<String>,
+    pub started_at: String,
+    /// `None` while the command is still running.
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// (repo, rule) -> latest generation and the paths saved while waiting.
+type PendingSaves = HashMap<(String, usize), (u64, BTreeSet<String>)>;
+
+/// Debounces saves per rule and runs the matching commands.
+#[derive(Default)]
+pub struct SaveHooks {
+    pending: Mutex<PendingSaves>,
+    runs: Mutex<HashMap<String, VecDeque<SaveHookRun>>>,
+}
+
+impl SaveHooks {
+    /// Newest first.
+    pub fn runs(&self, repo_ref: &str) -> Vec<SaveHookRun> {
+        self.runs.lock().unwrap().get(repo_ref).map(|runs| runs.iter().rev().cloned().collect()).unwrap_or_default()
+    }
+
+    fn record(&self, repo_ref: &str, run: SaveHookRun) {
+        let mut runs = self.runs.lock().unwrap();
+        let runs = runs.entry(repo_ref.to_string()).or_default();
+        match runs.iter_mut().find(|existing| existing.id == run.id) {
+            Some(existing) => *existing = run,
+            None => {
+                runs.push_back(run);
+                if runs.len() > MAX_RUNS_PER_REPO {
+                    runs.pop_front();
+                }
+            }
+        }
+    }
+}
+
+/// Called after the app writes files into `repo_ref` (editor save, ChangeSet apply). Each rule
+/// whose glob matches one of `paths` runs once the saves have been quiet for the debounce period.
+/// Hook problems are logged rather than failing the write that triggered them.
+pub async fn files_saved(state: &AppState, repo_ref: &str, paths: &[String]) {
+    if let Err(err) = schedule(&state.db, state.save_hooks(), repo_ref.trim(), paths).await {
+        tracing::warn!(repo = %repo_ref, "save hooks skipped: {err:#}");
+    }
+}
+
+async fn schedule(db: &SqlitePool, hooks: &Arc<SaveHooks>, repo_ref: &str, paths: &[String]) -> Result<()> {
+    let Some(settings) = load_settings(db, repo_ref).await? else {
+        return Ok(());
+    };
+    if !settings.enabled || paths.is_empty() {
+        return Ok(());
+    }
+    let debounce = Duration::from_millis(settings.debounce_ms.min(MAX_DEBOUNCE_MS));
+    let panes = load_panes(db, repo_ref).await;
+
+    for (index, rule) in settings.rules.into_iter().enumerate() {
+        if rule.command.trim().is_empty() {
+            continue;
+        }
+        let Some(matcher) = glob_to_regex_pattern(&rule.glob).and_then(|pattern| Regex::new(&pattern).ok()) else { continue };
+        let matched: Vec<String> = paths.iter().filter(|path| matcher.is_match(&path.replace('\\', "/"))).cloned().collect();
+        if matched.is_empty() {
+            continue;
+        }
+
+        let key = (repo_ref.to_string(), index);
+        let generation = {
+            let mut pending = hooks.pending.lock().unwrap();
+            let entry = pending.entry(key.clone()).or_default();
+            entry.0 += 1;
+            entry.1.extend(matched);
+            entry.0
+        };
+
+        let db = db.clone();
+        let hooks = hooks.clone();
+        let repo_ref = repo_ref.to_string();
+        let (working_dir, shell) = panes.get(&rule.pane).cloned().unwrap_or_default();
+        tokio::spawn(async move {
+            tokio::time::sleep(debounce).await;
+            let paths = {
+                let mut pending = hooks.pending.lock().unwrap();
+                // A later save restarted the wait; that task runs instead.
+                if pending.get(&key).is_none_or(|(latest, _)| *latest != generation) {
+                    return;
+                }
+                pending.remove(&key).map(|(_, paths)| paths.into_iter().collect()).unwrap_or_default()
+            };
+
+            let mut run = SaveHookRun {
+                id: Uuid::new_v4(),
+                glob: rule.glob,
+                command: rule.command,
+                pane: rule.pane,
+                paths,
+                started_at: Utc::now().to_rfc3339(),
+                status: None,
+                stdout: String::new(),
+                stderr: String::new(),
+            };
+            hooks.record(&repo_ref, run.clone());
+            match run_pane_command(&db, &repo_ref, &run.command, Some(working_dir.as_str()), Some(shell.as_str())).await {
+                Ok(result) => {
+                    let row = result.get("results").and_then(|rows| rows.get(0)).cloned().unwrap_or(Value::Null);
+                    run.status = Some(row.get("status").and_then(Value::as_i64).unwrap_or(-1) as i32);
+                    run.stdout = row.get("stdout").and_then(Value::as_str).unwrap_or_default().to_string();
+                    run.stderr = row.get("stderr").and_then(Value::as_str).unwrap_or_default().to_string();
+                }
+                Err(err) => {
+                    run.status = Some(-1);
+                    run.stderr = format!("{err:#}");
+                }
+            }
+            hooks.record(&repo_ref, run);
+        });
+    }
+    Ok(())
+}
+
+async fn load_settings(db: &SqlitePool, repo_ref: &str) -> Result<Option<SaveHookSettings>> {
+    let terminal = load_app_settings_section(db, "terminal").await?.unwrap_or_default();
+    let Some(hooks) = terminal.get("save_hooks_by_repo").and_then(|by_repo| by_repo.get(repo_ref)) else {
+        return Ok(None);
+    };
+    Ok(serde_json::from_value(hooks.clone()).ok())
+}
+
+/// Pane id -> (working directory, shell) from the saved terminal pane layout.
+async fn load_panes(db: &SqlitePool, repo_ref: &str) -> HashMap<String, (String, String)> {
+    let workspace = load_app_settings_section(db, "workspace").await.ok().flatten().unwrap_or_default();
+    let panes = workspace
+        .get("terminal_panes")
+        .and_then(|by_repo| by_repo.get(repo_ref))
+        .and_then(|layout| layout.get("panes"))
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    panes
+        .iter()
+        .filter_map(|pane| {
+            let field = |key: &str| pane.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+            let id = field("id");
+            (!id.is_empty()).then(|| (id, (field("working_dir"), field("shell"))))
+        })
+        .collect()
+}
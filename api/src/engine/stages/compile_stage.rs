@@ -189,6 +189,7 @@ pub fn build_compile_error_patch(capability_results: &[Value]) -> Value {
         .filter_map(|result| result.get("results"))
         .filter_map(Value::as_array)
         .flat_map(|items| items.iter())
+        .filter(|row| !row.get("skipped").and_then(Value::as_bool).unwrap_or(false))
         .map(|row| {
             let label = row.get("label").and_then(Value::as_str).unwrap_or("command");
             let status = row.get("status").and_then(Value::as_i64).unwrap_or(-1);
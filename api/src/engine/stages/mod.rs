@@ -662,11 +662,11 @@ fn synthesize_execution_plan(bindings: &[WorkflowCapabilityBinding]) -> Vec<Stag
         .collect()
 }
 
-pub(crate) fn compose_prompt_from_state(
+pub(crate) fn compose_prompt_sections(
     enabled: &Value,
     fragments: &Value,
     transient_fragments: &[String],
-) -> String {
+) -> Vec<(String, String)> {
     let enabled_obj = enabled.as_object().cloned().unwrap_or_default();
     let fragments_obj = fragments.as_object().cloned().unwrap_or_default();
     let order = ["user_input", "repo_context", "changeset_schema"];
@@ -679,18 +679,18 @@ pub(crate) fn compose_prompt_from_state(
         }
         let value = fragments_obj.get(key).and_then(Value::as_str).unwrap_or("").trim();
         if !value.is_empty() {
-            parts.push(value.to_string());
+            parts.push((key.to_string(), value.to_string()));
         }
     }
 
-    for value in transient_fragments {
+    for (index, value) in transient_fragments.iter().enumerate() {
         let value = value.trim();
         if !value.is_empty() {
-            parts.push(value.to_string());
+            parts.push((format!("transient_{}", index + 1), value.to_string()));
         }
     }
 
-    parts.join("\n\n")
+    parts
 }
 
 fn format_disposition(disposition: &StageDisposition) -> String {
@@ -0,0 +1,222 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::engine::capabilities::git::git::run_git;
+
+/// Stop scanning for inline Rust test modules after this many files; large crates have made their choice by then.
+const MAX_SCANNED_FILES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestLayout {
+    /// `#[cfg(test)] mod tests` at the bottom of the source file.
+    InlineModule,
+    /// A separate file under the crate's or project's `tests/` directory.
+    TestsDir,
+    /// A test file next to the source file (`foo.test.ts`, `foo_test.go`, `test_foo.py`).
+    SiblingFile,
+}
+
+/// Where tests for one source file go and how to run them, following what the repo already does.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestGenerationPlan {
+    pub path: String,
+    pub symbol: Option<String>,
+    pub language: &'static str,
+    pub layout: TestLayout,
+    pub test_path: String,
+    pub test_command: String,
+    /// An existing test file the model should imitate, when the repo has one.
+    pub reference_test: Option<String>,
+    /// Task description for the code stage.
+    pub instructions: String,
+}
+
+pub fn plan(repo: &Path, path: &str, symbol: Option<&str>) -> Result<TestGenerationPlan> {
+    let path = path.trim().trim_start_matches("./").to_string();
+    if path.is_empty() {
+        bail!("path is required");
+    }
+    if !repo.join(&path).is_file() {
+        bail!("{path} is not a file in the repository");
+    }
+    let symbol = symbol.map(str::trim).filter(|symbol| !symbol.is_empty()).map(str::to_string);
+    let listing = run_git(repo, &["ls-files", "--cached", "--others", "--exclude-standard"])?;
+    let files: Vec<String> = String::from_utf8_lossy(&listing).lines().map(str::to_string).collect();
+
+    let (dir, file_name) = path.rsplit_once('/').unwrap_or(("", path.as_str()));
+    let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+    let (language, layout, test_path, test_command, reference_test) = match extension {
+        "rs" => plan_rust(repo, &files, &path, dir, stem),
+        "py" => plan_python(&files, dir, stem),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => plan_javascript(repo, &files, dir, stem, extension)?,
+        "go" => (
+            "go",
+            TestLayout::SiblingFile,
+            join_path(dir, &format!("{stem}_test.go")),
+            format!("go test ./{dir}"),
+            files.iter().find(|file| file.ends_with("_test.go")).cloned(),
+        ),
+        _ => bail!("test generation supports Rust, Python, JavaScript/TypeScript and Go files"),
+    };
+
+    let mut plan = TestGenerationPlan {
+        path,
+        symbol,
+        language,
+        layout,
+        test_path,
+        test_command,
+        reference_test,
+        instructions: String::new(),
+    };
+    plan.instructions = instructions(&plan);
+    Ok(plan)
+}
+
+type LayoutChoice = (&'static str, TestLayout, String, String, Option<String>);
+
+fn plan_rust(repo: &Path, files: &[String], path: &str, dir: &str, stem: &str) -> LayoutChoice {
+    // The crate root is the nearest directory above the file with a Cargo.toml.
+    let mut crate_root = dir.to_string();
+    while !repo.join(&crate_root).join("Cargo.toml").is_file() && !crate_root.is_empty() {
+        crate_root = crate_root.rsplit_once('/').map(|(parent, _)| parent.to_string()).unwrap_or_default();
+    }
+    let in_crate = |prefix: &str, file: &&String| file.starts_with(&join_path(&crate_root, prefix)) && file.ends_with(".rs");
+
+    let integration: Vec<&String> = files.iter().filter(|file| in_crate("tests/", file)).collect();
+    let inline: Vec<&String> = files
+        .iter()
+        .filter(|file| in_crate("src/", file))
+        .take(MAX_SCANNED_FILES)
+        .filter(|file| fs::read_to_string(repo.join(file)).is_ok_and(|text| text.contains("#[cfg(test)]")))
+        .collect();
+
+    let test_command = if crate_root.is_empty() {
+        "cargo test".to_string()
+    } else {
+        format!("cargo test --manifest-path {crate_root}/Cargo.toml")
+    };
+    // Inline modules are the Rust default; only switch when the crate clearly prefers tests/.
+    if integration.len() > inline.len() {
+        let name = match stem {
+            "lib" | "main" | "mod" => dir.rsplit('/').next().filter(|name| !name.is_empty() && *name != "src").unwrap_or("unit"),
+            other => other,
+        };
+        let test_path = join_path(&crate_root, &format!("tests/{name}.rs"));
+        return ("rust", TestLayout::TestsDir, test_path, test_command, integration.first().map(|file| file.to_string()));
+    }
+    let reference = inline
+        .iter()
+        .find(|file| file.as_str() == path)
+        .or_else(|| inline.first())
+        .map(|file| file.to_string());
+    ("rust", TestLayout::InlineModule, path.to_string(), test_command, reference)
+}
+
+fn plan_python(files: &[String], dir: &str, stem: &str) -> LayoutChoice {
+    let is_test = |file: &&String| {
+        let name = file.rsplit('/').next().unwrap_or(file);
+        name.ends_with(".py") && (name.starts_with("test_") || name.ends_with("_test.py"))
+    };
+    let existing: Vec<&String> = files.iter().filter(is_test).collect();
+    // Use the tests directory most existing tests live in, if there is one.
+    let mut dirs: BTreeMap<&str, usize> = BTreeMap::new();
+    for file in &existing {
+        let parent = file.rsplit_once('/').map(|(parent, _)| parent).unwrap_or("");
+        if parent.rsplit('/').next().is_some_and(|name| name == "tests" || name == "test") {
+            *dirs.entry(parent).or_default() += 1;
+        }
+    }
+    let tests_dir = dirs.into_iter().max_by_key(|(_, count)| *count).map(|(dir, _)| dir.to_string());
+    let (layout, test_path) = match tests_dir {
+        Some(tests_dir) => (TestLayout::TestsDir, join_path(&tests_dir, &format!("test_{stem}.py"))),
+        None => (TestLayout::SiblingFile, join_path(dir, &format!("test_{stem}.py"))),
+    };
+    let test_command = format!("python -m pytest -q {test_path}");
+    ("python", layout, test_path, test_command, existing.first().map(|file| file.to_string()))
+}
+
+fn plan_javascript(repo: &Path, files: &[String], dir: &str, stem: &str, extension: &str) -> Result<LayoutChoice> {
+    let existing: Vec<&String> = files
+        .iter()
+        .filter(|file| file.contains(".test.") || file.contains(".spec.") || file.contains("/__tests__/"))
+        .filter(|file| !file.contains("node_modules/"))
+        .collect();
+    let in_tests_dir = existing.iter().filter(|file| file.contains("__tests__/")).count();
+    let suffix = if existing.iter().filter(|file| file.contains(".spec.")).count() * 2 > existing.len() { "spec" } else { "test" };
+    let file_name = format!("{stem}.{suffix}.{extension}");
+    let (layout, test_path) = if in_tests_dir * 2 > existing.len() {
+        (TestLayout::TestsDir, join_path(&join_path(dir, "__tests__"), &file_name))
+    } else {
+        (TestLayout::SiblingFile, join_path(dir, &file_name))
+    };
+
+    // Run from the nearest package.json so workspaces pick the right runner.
+    let mut package_dir = dir.to_string();
+    while !repo.join(&package_dir).join("package.json").is_file() {
+        if package_dir.is_empty() {
+            bail!("no package.json found above {}", join_path(dir, stem));
+        }
+        package_dir = package_dir.rsplit_once('/').map(|(parent, _)| parent.to_string()).unwrap_or_default();
+    }
+    let manifest: Value = serde_json::from_str(&fs::read_to_string(repo.join(&package_dir).join("package.json"))?)
+        .context("failed to parse package.json")?;
+    let has_dependency = |name: &str| {
+        ["dependencies", "devDependencies"]
+            .iter()
+            .any(|section| manifest.get(section).and_then(|deps| deps.get(name)).is_some())
+    };
+    let relative_test_path = test_path.strip_prefix(&format!("{package_dir}/")).unwrap_or(&test_path);
+    let run = if manifest.pointer("/scripts/test").and_then(Value::as_str).is_some() {
+        "npm test --silent".to_string()
+    } else if has_dependency("vitest") {
+        format!("npx --no-install vitest run {relative_test_path}")
+    } else if has_dependency("jest") {
+        format!("npx --no-install jest {relative_test_path}")
+    } else {
+        bail!("package.json has no test script and no vitest or jest dependency");
+    };
+    let test_command = if package_dir.is_empty() { run } else { format!("cd {package_dir} && {run}") };
+    Ok(("javascript", layout, test_path, test_command, existing.first().map(|file| file.to_string())))
+}
+
+fn instructions(plan: &TestGenerationPlan) -> String {
+    let target = match &plan.symbol {
+        Some(symbol) => format!("`{symbol}` in `{}`", plan.path),
+        None => format!("the public API of `{}`", plan.path),
+    };
+    let placement = match plan.layout {
+        TestLayout::InlineModule => format!(
+            "Put the tests in a `#[cfg(test)] mod tests` module at the end of `{}`; extend the module if it already exists.",
+            plan.test_path
+        ),
+        TestLayout::TestsDir if plan.language == "rust" => format!(
+            "Put the tests in `{}` as integration tests; they can only reach items exported from the crate.",
+            plan.test_path
+        ),
+        TestLayout::TestsDir | TestLayout::SiblingFile => format!("Put the tests in `{}`; extend it if it already exists.", plan.test_path),
+    };
+    let mut lines = vec![
+        format!("Write unit tests for {target}."),
+        placement,
+        "Cover the normal path, edge cases and error handling. Keep each test small and focused, and name it after the behaviour it checks.".to_string(),
+        "Only add or change test code. If a test reveals a real bug, leave the code under test alone and describe the bug in the changeset description.".to_string(),
+        format!("The tests must compile and pass with `{}`.", plan.test_command),
+    ];
+    if let Some(reference) = plan.reference_test.as_deref().filter(|reference| *reference != plan.test_path) {
+        lines.insert(2, format!("Follow the style, helpers and imports of the existing tests in `{reference}`."));
+    }
+    lines.join("\n")
+}
+
+fn join_path(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
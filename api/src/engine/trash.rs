@@ -0,0 +1,292 @@
+use std::{collections::HashSet, fs, path::{Path, PathBuf}};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use sqlx::SqlitePool;
+
+use crate::{
+    db::{load_app_settings_section, repo_basename_for_workflow_key},
+    engine::{blob_store, history::{restore_target, write_back, FileSnapshot}},
+    runtime_env::data_dir,
+};
+
+/// Used until the `trash.retention_days` setting says otherwise.
+pub const DEFAULT_RETENTION_DAYS: i64 = 14;
+pub const MAX_RETENTION_DAYS: i64 = 365;
+const ENTRY_FILE: &str = "entry.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashFile {
+    pub path: String,
+    pub bytes: u64,
+    /// Contents in the blob store; empty for a symlink.
+    #[serde(default)]
+    pub blob: String,
+    /// Target of a trashed symlink, restored as a link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub repo_ref: String,
+    pub kind: String,
+    pub label: String,
+    pub created_at: String,
+    pub files: Vec<TrashFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrashRestoreResult {
+    pub restored: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Days a trash entry is kept, from the `trash` settings section.
+pub async fn retention_days(db: &SqlitePool) -> i64 {
+    load_app_settings_section(db, "trash")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|section| section.get("retention_days").and_then(serde_json::Value::as_i64))
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+        .clamp(1, MAX_RETENTION_DAYS)
+}
+
+fn trash_root() -> PathBuf {
+    data_dir().join("trash")
+}
+
+/// Stable per-repository directory name: readable basename plus an FNV-1a hash of the path.
+fn repo_dir(repo_ref: &str) -> PathBuf {
+    let normalized = repo_ref.trim().replace('\\', "/");
+    let hash = normalized.trim_end_matches('/').bytes().fold(0xcbf29ce484222325u64, |acc, byte| {
+        (acc ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    trash_root().join(format!("{}-{:08x}", repo_basename_for_workflow_key(repo_ref), hash as u32))
+}
+
+/// Records the prior contents of every snapshotted file that existed in a new trash entry.
+/// Returns `None` when nothing existed, so there is nothing worth keeping.
+pub fn store(repo_ref: &str, kind: &str, label: &str, snapshots: &[FileSnapshot], retention_days: i64) -> Result<Option<String>> {
+    let existing = snapshots.iter().filter(|file| file.existed).collect::<Vec<_>>();
+    if existing.is_empty() {
+        return Ok(None);
+    }
+
+    let id = format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%S"), &Uuid::new_v4().simple().to_string()[..8]);
+    let dir = repo_dir(repo_ref).join(&id);
+    let mut files = Vec::new();
+    for file in existing {
+        if let Some(link) = &file.link {
+            files.push(TrashFile { path: file.path.clone(), bytes: 0, blob: String::new(), link: Some(link.clone()) });
+            continue;
+        }
+        let blob = file.blob.clone().with_context(|| format!("snapshot of {} has no stored contents", file.path))?;
+        files.push(TrashFile { path: file.path.clone(), bytes: blob_store::size(&blob)?, blob, link: None });
+    }
+
+    let entry = TrashEntry {
+        id: id.clone(),
+        repo_ref: repo_ref.to_string(),
+        kind: kind.to_string(),
+        label: label.to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        files,
+    };
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    fs::write(dir.join(ENTRY_FILE), serde_json::to_vec_pretty(&entry)?)?;
+    prune(retention_days)?;
+    Ok(Some(id))
+}
+
+/// Entries for one repository, newest first. Entries older than `retention_days` are pruned first.
+pub fn list(repo_ref: &str, retention_days: i64) -> Result<Vec<TrashEntry>> {
+    prune(retention_days)?;
+    entries(repo_ref)
+}
+
+fn entries(repo_ref: &str) -> Result<Vec<TrashEntry>> {
+    let dir = repo_dir(repo_ref);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut entries = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| read_entry(&entry.path()).ok())
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(entries)
+}
+
+/// Writes trashed files back into the repository. Paths that currently exist are skipped
+/// unless `overwrite` is set. Every target is resolved inside the repository first, so a
+/// symlinked parent can't send a restore elsewhere.
+pub fn restore(repo_ref: &str, id: &str, paths: Option<&[String]>, overwrite: bool) -> Result<TrashRestoreResult> {
+    let dir = entry_dir(repo_ref, id)?;
+    let entry = read_entry(&dir)?;
+    let mut result = TrashRestoreResult { restored: Vec::new(), skipped: Vec::new() };
+    for file in &entry.files {
+        if paths.is_some_and(|paths| !paths.contains(&file.path)) {
+            continue;
+        }
+        let blob = Some(file.blob.clone()).filter(|blob| !blob.is_empty());
+        let snapshot = FileSnapshot { path: file.path.clone(), existed: true, blob, link: file.link.clone() };
+        if restore_target(&entry.repo_ref, &snapshot).is_ok_and(|target| fs::symlink_metadata(target).is_ok()) && !overwrite {
+            result.skipped.push(file.path.clone());
+            continue;
+        }
+        write_back(&entry.repo_ref, &snapshot)?;
+        result.restored.push(file.path.clone());
+    }
+    Ok(result)
+}
+
+/// Removes one entry, or every entry for the repository when `id` is `None`.
+pub fn purge(repo_ref: &str, id: Option<&str>) -> Result<usize> {
+    match id {
+        Some(id) => {
+            fs::remove_dir_all(entry_dir(repo_ref, id)?)?;
+            Ok(1)
+        }
+        None => {
+            let count = entries(repo_ref)?.len();
+            let dir = repo_dir(repo_ref);
+            if dir.is_dir() {
+                fs::remove_dir_all(&dir)?;
+            }
+            Ok(count)
+        }
+    }
+}
+
+/// Blob ids every trash entry still points at.
+pub fn referenced_blobs() -> Result<HashSet<String>> {
+    let root = trash_root();
+    let mut blobs = HashSet::new();
+    if !root.is_dir() {
+        return Ok(blobs);
+    }
+    for repo in fs::read_dir(&root)?.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        let Ok(entries) = fs::read_dir(&repo) else { continue };
+        for dir in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            if let Ok(entry) = read_entry(&dir) {
+                blobs.extend(entry.files.into_iter().map(|file| file.blob).filter(|blob| !blob.is_empty()));
+            }
+        }
+    }
+    Ok(blobs)
+}
+
+fn prune(retention_days: i64) -> Result<()> {
+    let root = trash_root();
+    if !root.is_dir() {
+        return Ok(());
+    }
+    let cutoff = Utc::now() - Duration::days(retention_days);
+    for repo in fs::read_dir(&root)?.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        if !repo.is_dir() {
+            continue;
+        }
+        for dir in fs::read_dir(&repo)?.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            let Ok(entry) = read_entry(&dir) else { continue };
+            let expired = DateTime::parse_from_rfc3339(&entry.created_at).ok().is_none_or(|created| created < cutoff);
+            if expired {
+                let _ = fs::remove_dir_all(&dir);
+            }
+        }
+        if fs::read_dir(&repo).map(|mut items| items.next().is_none()).unwrap_or(false) {
+            let _ = fs::remove_dir(&repo);
+        }
+    }
+    Ok(())
+}
+
+fn entry_dir(repo_ref: &str, id: &str) -> Result<PathBuf> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        bail!("invalid trash entry id {id}");
+    }
+    let dir = repo_dir(repo_ref).join(id);
+    if !dir.join(ENTRY_FILE).is_file() {
+        return Err(anyhow!("trash entry {id} not found"));
+    }
+    Ok(dir)
+}
+
+fn read_entry(dir: &Path) -> Result<TrashEntry> {
+    let raw = fs::read(dir.join(ENTRY_FILE)).with_context(|| format!("failed to read {}", dir.display()))?;
+    Ok(serde_json::from_slice(&raw)?)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn snapshot(path: &str, contents: &str) -> FileSnapshot {
+        FileSnapshot { path: path.to_string(), existed: true, blob: Some(blob_store::put(contents.as_bytes()).unwrap()), link: None }
+    }
+
+    #[test]
+    fn restore_stays_inside_the_repo() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_dir("trash-repo");
+        let outside = temp_dir("trash-outside");
+        let repo_ref = repo.to_string_lossy().to_string();
+        let files = [snapshot("escape/planted.txt", "planted"), snapshot("kept.txt", "kept")];
+        let id = store(&repo_ref, "delete_path", "Delete", &files, DEFAULT_RETENTION_DAYS).unwrap().unwrap();
+
+        std::os::unix::fs::symlink(&outside, repo.join("escape")).unwrap();
+        assert!(restore(&repo_ref, &id, None, true).is_err());
+        assert!(!outside.join("planted.txt").exists());
+
+        let result = restore(&repo_ref, &id, Some(&["kept.txt".to_string()]), false).unwrap();
+        assert_eq!(result.restored, ["kept.txt"]);
+        assert_eq!(fs::read_to_string(repo.join("kept.txt")).unwrap(), "kept");
+
+        purge(&repo_ref, None).unwrap();
+        fs::remove_dir_all(&repo).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn restore_puts_links_back_as_links() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_dir("trash-repo");
+        let repo_ref = repo.to_string_lossy().to_string();
+        let link = FileSnapshot { path: "home".to_string(), existed: true, blob: None, link: Some("/".to_string()) };
+        let id = store(&repo_ref, "discard", "Discard home", &[link], DEFAULT_RETENTION_DAYS).unwrap().unwrap();
+
+        assert_eq!(restore(&repo_ref, &id, None, false).unwrap().restored, ["home"]);
+        assert_eq!(fs::read_link(repo.join("home")).unwrap(), Path::new("/"));
+        assert_eq!(restore(&repo_ref, &id, None, false).unwrap().skipped, ["home"]);
+
+        purge(&repo_ref, None).unwrap();
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn entries_older_than_the_retention_are_pruned() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = temp_dir("trash-repo");
+        let repo_ref = repo.to_string_lossy().to_string();
+        let id = store(&repo_ref, "delete_path", "Delete", &[snapshot("a.txt", "a")], DEFAULT_RETENTION_DAYS).unwrap().unwrap();
+        let dir = entry_dir(&repo_ref, &id).unwrap();
+        let mut entry = read_entry(&dir).unwrap();
+        entry.created_at = (Utc::now() - Duration::days(3)).to_rfc3339();
+        fs::write(dir.join(ENTRY_FILE), serde_json::to_vec(&entry).unwrap()).unwrap();
+
+        assert_eq!(list(&repo_ref, 7).unwrap().len(), 1);
+        assert!(list(&repo_ref, 2).unwrap().is_empty());
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
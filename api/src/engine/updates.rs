@@ -0,0 +1,449 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use reqwest::header::{ACCEPT, USER_AGENT};
+use ring::{
+    digest::{digest, SHA256},
+    signature::{UnparsedPublicKey, ED25519},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::{
+    db::load_app_settings_section,
+    http_client::{build_http_client, load_network_settings},
+    runtime_env::data_dir,
+};
+
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const DEFAULT_FEED_URL: &str = "https://api.github.com/repos/hunterviolette/mdev/releases/latest";
+const STATUS_FILE: &str = "status.json";
+const STAGED_FILE: &str = "staged.json";
+const SCHEDULER_TICK_SECS: u64 = 15 * 60;
+/// Base64 Ed25519 public key that releases are signed with, pinned at build time. Each binary ships
+/// with `<asset>.sig` (raw or base64), the signature over its `release_manifest`; builds without a
+/// key cannot install updates.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("MDEV_RELEASE_PUBLIC_KEY");
+/// Only stable releases are offered, so this is the one channel a signature may name.
+const RELEASE_CHANNEL: &str = "stable";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_interval")]
+    pub check_interval_hours: u64,
+    #[serde(default)]
+    pub feed_url: String,
+}
+
+/// Checking reaches out to the release feed, so it stays off until the user turns it on.
+fn default_enabled() -> bool {
+    false
+}
+
+fn default_interval() -> u64 {
+    24
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub url: String,
+    pub size: u64,
+    #[serde(default)]
+    pub signature_url: Option<String>,
+    #[serde(default)]
+    pub checksum_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateStatus {
+    pub current_version: String,
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    pub release_name: Option<String>,
+    pub release_url: Option<String>,
+    pub changelog: Option<String>,
+    pub published_at: Option<String>,
+    /// Binary for this platform, when the release ships one.
+    pub asset: Option<ReleaseAsset>,
+    pub checked_at: Option<String>,
+    pub error: Option<String>,
+    pub staged_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StagedUpdate {
+    version: String,
+    asset: String,
+    sha256: String,
+    /// Base64 Ed25519 signature, checked again right before the binary is installed.
+    signature: String,
+    staged_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    name: Option<String>,
+    html_url: Option<String>,
+    body: Option<String>,
+    published_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    size: u64,
+}
+
+fn updates_dir() -> PathBuf {
+    data_dir().join("updates")
+}
+
+pub async fn load_update_settings(db: &SqlitePool) -> Result<UpdateSettings> {
+    let section = load_app_settings_section(db, "updates").await?.unwrap_or_default();
+    let mut settings: UpdateSettings = serde_json::from_value(section)
+        .unwrap_or(UpdateSettings { enabled: default_enabled(), check_interval_hours: default_interval(), feed_url: String::new() });
+    if settings.feed_url.trim().is_empty() {
+        settings.feed_url = DEFAULT_FEED_URL.to_string();
+    }
+    Ok(settings)
+}
+
+/// Last check result as stored on disk, refreshed with the currently staged version.
+pub fn status() -> UpdateStatus {
+    let mut status = fs::read(updates_dir().join(STATUS_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_slice::<UpdateStatus>(&raw).ok())
+        .unwrap_or_default();
+    status.current_version = CURRENT_VERSION.to_string();
+    status.update_available = status.latest_version.as_deref().is_some_and(|latest| is_newer(latest, CURRENT_VERSION));
+    status.staged_version = read_staged().map(|staged| staged.version);
+    status
+}
+
+/// Fetches the latest release from the feed and records the outcome, including failures.
+pub async fn check(db: &SqlitePool) -> Result<UpdateStatus> {
+    let settings = load_update_settings(db).await?;
+    let mut status = status();
+    status.checked_at = Some(Utc::now().to_rfc3339());
+    match fetch_latest(db, &settings.feed_url).await {
+        Ok(release) => {
+            status.latest_version = Some(release.tag_name.trim_start_matches('v').to_string());
+            status.release_name = release.name.filter(|name| !name.trim().is_empty());
+            status.release_url = release.html_url;
+            status.changelog = release.body;
+            status.published_at = release.published_at;
+            status.asset = pick_asset(&release.assets);
+            status.error = None;
+        }
+        Err(err) => status.error = Some(format!("{err:#}")),
+    }
+    status.update_available = status.latest_version.as_deref().is_some_and(|latest| is_newer(latest, CURRENT_VERSION));
+    fs::create_dir_all(updates_dir())?;
+    fs::write(updates_dir().join(STATUS_FILE), serde_json::to_vec_pretty(&status)?)?;
+    Ok(status)
+}
+
+async fn fetch_latest(db: &SqlitePool, feed_url: &str) -> Result<GithubRelease> {
+    let client = build_http_client(&load_network_settings(db).await?)?;
+    let release = client
+        .get(feed_url)
+        .header(USER_AGENT, format!("mdev/{CURRENT_VERSION}"))
+        .header(ACCEPT, "application/vnd.github+json")
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .with_context(|| format!("failed to reach {feed_url}"))?
+        .error_for_status()?
+        .json::<GithubRelease>()
+        .await
+        .context("release feed returned an unexpected payload")?;
+    if release.draft || release.prerelease {
+        bail!("latest release {} is not a stable release", release.tag_name);
+    }
+    Ok(release)
+}
+
+/// Picks the raw release binary whose name mentions this OS (and preferably this architecture);
+/// installers and archives cannot be swapped in place.
+fn pick_asset(assets: &[GithubAsset]) -> Option<ReleaseAsset> {
+    let os_names: &[&str] = match std::env::consts::OS {
+        "macos" => &["macos", "darwin", "apple"],
+        "windows" => &["windows", "win64", ".exe"],
+        other => &[other],
+    };
+    let arch = std::env::consts::ARCH;
+    let candidates = assets
+        .iter()
+        .filter(|asset| {
+            let name = asset.name.to_lowercase();
+            let packaged = [".sha256", ".sig", ".zip", ".tar.gz", ".tgz", ".dmg", ".msi", ".deb", ".rpm"]
+                .iter()
+                .any(|suffix| name.ends_with(suffix));
+            os_names.iter().any(|os| name.contains(os)) && !packaged
+        })
+        .collect::<Vec<_>>();
+    let sibling = |name: &str, suffix: &str| {
+        let wanted = format!("{name}{suffix}");
+        assets.iter().find(|asset| asset.name == wanted).map(|asset| asset.browser_download_url.clone())
+    };
+    candidates
+        .iter()
+        .find(|asset| asset.name.to_lowercase().contains(arch))
+        .or_else(|| candidates.first())
+        .map(|asset| ReleaseAsset {
+            name: asset.name.clone(),
+            url: asset.browser_download_url.clone(),
+            size: asset.size,
+            signature_url: sibling(&asset.name, ".sig"),
+            checksum_url: sibling(&asset.name, ".sha256"),
+        })
+}
+
+/// Release names end up in paths under the updates directory, so they must be one plain component.
+fn plain_component<'a>(value: &'a str, what: &str) -> Result<&'a str> {
+    let mut components = Path::new(value).components();
+    let plain = matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !value.contains(['/', '\\', ':']);
+    if !plain {
+        bail!("release {what} {value:?} is not a plain file name");
+    }
+    Ok(value)
+}
+
+fn release_public_key() -> Result<Vec<u8>> {
+    let key = RELEASE_PUBLIC_KEY.context("this build has no release signing key, so updates cannot be verified; install them manually")?;
+    BASE64.decode(key.trim()).context("the pinned release signing key is not valid base64")
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    digest(&SHA256, bytes).as_ref().iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// What a release signature covers. Binding the version and channel to the binary's digest keeps
+/// an older, validly signed build from being served again as a newer release.
+fn release_manifest(version: &str, asset: &str, sha256: &str) -> String {
+    format!("mdev-release\nchannel: {RELEASE_CHANNEL}\nversion: {version}\nasset: {asset}\nsha256: {sha256}\n")
+}
+
+fn verify_manifest(key: &[u8], manifest: &str, signature: &[u8]) -> Result<()> {
+    UnparsedPublicKey::new(&ED25519, key)
+        .verify(manifest.as_bytes(), signature)
+        .map_err(|_| anyhow!("release signature does not match the pinned signing key for this version"))
+}
+
+/// A `.sig` asset holds the 64-byte signature either raw or base64-encoded.
+fn decode_signature(raw: &[u8]) -> Result<Vec<u8>> {
+    if raw.len() == 64 {
+        return Ok(raw.to_vec());
+    }
+    BASE64
+        .decode(String::from_utf8_lossy(raw).trim())
+        .ok()
+        .filter(|signature| signature.len() == 64)
+        .context("release signature is malformed")
+}
+
+async fn download(client: &reqwest::Client, url: &str, name: &str) -> Result<Vec<u8>> {
+    Ok(client
+        .get(url)
+        .header(USER_AGENT, format!("mdev/{CURRENT_VERSION}"))
+        .send()
+        .await
+        .with_context(|| format!("failed to download {name}"))?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec())
+}
+
+/// Downloads the platform asset of the last checked release so it replaces the binary on next launch.
+pub async fn stage(db: &SqlitePool) -> Result<UpdateStatus> {
+    let status = status();
+    let (Some(version), Some(asset)) = (status.latest_version.clone(), status.asset.clone()) else {
+        bail!("no downloadable update for this platform; check for updates first");
+    };
+    if !status.update_available {
+        bail!("{CURRENT_VERSION} is already up to date");
+    }
+    plain_component(&version, "version")?;
+    plain_component(&asset.name, "asset")?;
+    let signature_url = asset.signature_url.as_deref().with_context(|| format!("release has no {}.sig; refusing an unsigned update", asset.name))?;
+    release_public_key()?;
+
+    let client = build_http_client(&load_network_settings(db).await?)?;
+    let bytes = download(&client, &asset.url, &asset.name).await?;
+    if asset.size > 0 && bytes.len() as u64 != asset.size {
+        bail!("download of {} is incomplete ({} of {} bytes)", asset.name, bytes.len(), asset.size);
+    }
+    let signature = decode_signature(&download(&client, signature_url, &format!("{}.sig", asset.name)).await?)?;
+    let sha256 = sha256_hex(&bytes);
+    verify_manifest(&release_public_key()?, &release_manifest(&version, &asset.name, &sha256), &signature)?;
+    if let Some(checksum_url) = &asset.checksum_url {
+        let listed = download(&client, checksum_url, &format!("{}.sha256", asset.name)).await?;
+        let listed = String::from_utf8_lossy(&listed);
+        if listed.split_whitespace().next().map(str::to_ascii_lowercase) != Some(sha256.clone()) {
+            bail!("{} does not match its published sha256", asset.name);
+        }
+    }
+
+    let dir = updates_dir().join(&version);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(&asset.name);
+    fs::write(&path, &bytes).with_context(|| format!("failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+    let staged = StagedUpdate {
+        version,
+        asset: asset.name,
+        sha256,
+        signature: BASE64.encode(signature),
+        staged_at: Utc::now().to_rfc3339(),
+    };
+    fs::write(updates_dir().join(STAGED_FILE), serde_json::to_vec_pretty(&staged)?)?;
+    Ok(self::status())
+}
+
+/// Called at startup: swaps a staged binary in for the current executable and returns its
+/// version, after which the caller relaunches into it. The staged file is re-verified first, since
+/// anything that can write the data directory could have replaced it since it was downloaded, and
+/// the signed manifest must name the version being installed so an old build cannot pose as new.
+pub fn apply_staged_update() -> Result<Option<String>> {
+    let Some(staged) = read_staged() else {
+        return Ok(None);
+    };
+    let _ = fs::remove_file(updates_dir().join(STAGED_FILE));
+    if !is_newer(&staged.version, CURRENT_VERSION) {
+        return Ok(None);
+    }
+    let path = updates_dir().join(plain_component(&staged.version, "version")?).join(plain_component(&staged.asset, "asset")?);
+    let bytes = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    if sha256_hex(&bytes) != staged.sha256 {
+        bail!("staged update {} changed after it was downloaded", path.display());
+    }
+    let signature = BASE64.decode(&staged.signature).context("staged signature is malformed")?;
+    verify_manifest(&release_public_key()?, &release_manifest(&staged.version, &staged.asset, &staged.sha256), &signature)?;
+    let exe = std::env::current_exe()?;
+    let backup = exe.with_extension("old");
+    let _ = fs::remove_file(&backup);
+    fs::rename(&exe, &backup).with_context(|| format!("failed to move {} aside", exe.display()))?;
+    if let Err(err) = fs::write(&exe, &bytes) {
+        let _ = fs::rename(&backup, &exe);
+        return Err(err).with_context(|| format!("failed to install {}", path.display()));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&exe, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(Some(staged.version))
+}
+
+fn read_staged() -> Option<StagedUpdate> {
+    serde_json::from_slice(&fs::read(updates_dir().join(STAGED_FILE)).ok()?).ok()
+}
+
+/// Runs `check` whenever the configured interval has elapsed since the last one.
+pub fn spawn_scheduler(db: SqlitePool) {
+    tokio::spawn(async move {
+        loop {
+            if let Ok(settings) = load_update_settings(&db).await {
+                let due = status()
+                    .checked_at
+                    .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                    .is_none_or(|at| Utc::now() - at.with_timezone(&Utc) >= chrono::Duration::hours(settings.check_interval_hours as i64));
+                if settings.enabled && due {
+                    if let Err(err) = check(&db).await {
+                        tracing::warn!(error = %format!("{err:#}"), "update check failed");
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(SCHEDULER_TICK_SECS)).await;
+        }
+    });
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn is_newer(candidate: &str, current: &str) -> bool {
+    let (mut a, mut b) = (version_parts(candidate), version_parts(current));
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    a > b
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    use super::*;
+
+    #[test]
+    fn signatures_are_bound_to_the_release_version() {
+        let pair = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+        let key = pair.public_key().as_ref();
+        let sha256 = sha256_hex(b"old build");
+        let signature = pair.sign(release_manifest("1.0.0", "mdev-linux", &sha256).as_bytes());
+
+        verify_manifest(key, &release_manifest("1.0.0", "mdev-linux", &sha256), signature.as_ref()).unwrap();
+        assert!(verify_manifest(key, &release_manifest("9.0.0", "mdev-linux", &sha256), signature.as_ref()).is_err());
+        assert!(verify_manifest(key, &release_manifest("1.0.0", "mdev-macos", &sha256), signature.as_ref()).is_err());
+        assert!(verify_manifest(key, &release_manifest("1.0.0", "mdev-linux", &sha256_hex(b"new build")), signature.as_ref()).is_err());
+    }
+
+    #[test]
+    fn update_checks_are_off_unless_enabled() {
+        let settings: UpdateSettings = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(!settings.enabled);
+    }
+
+    #[test]
+    fn release_names_must_be_plain_file_names() {
+        assert_eq!(plain_component("1.2.0", "version").unwrap(), "1.2.0");
+        assert_eq!(plain_component("mdev-linux-x86_64.tar.gz", "asset").unwrap(), "mdev-linux-x86_64.tar.gz");
+        for bad in ["", ".", "..", "../mdev", "/usr/bin/mdev", "a/b", "a\\b", "C:mdev"] {
+            assert!(plain_component(bad, "asset").is_err(), "{bad:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn signatures_are_read_raw_or_base64() {
+        let raw = [3u8; 64];
+        assert_eq!(decode_signature(&raw).unwrap(), raw);
+        assert_eq!(decode_signature(format!("{}\n", BASE64.encode(raw)).as_bytes()).unwrap(), raw);
+        assert!(decode_signature(BASE64.encode([3u8; 32]).as_bytes()).is_err());
+        assert!(decode_signature(b"not a signature").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use crate::db::load_app_settings_section;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NetworkSettings {
+    #[serde(default)]
+    pub proxy_url: String,
+    #[serde(default)]
+    pub no_proxy: String,
+    #[serde(default)]
+    pub ca_cert_path: String,
+}
+
+pub async fn load_network_settings(db: &SqlitePool) -> Result<NetworkSettings> {
+    let network = load_app_settings_section(db, "network")
+        .await
+        .context("failed to load network settings")?;
+    Ok(network
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+pub fn build_http_client(settings: &NetworkSettings) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().connect_timeout(Duration::from_secs(30));
+
+    let proxy_url = settings.proxy_url.trim();
+    if !proxy_url.is_empty() {
+        let mut proxy = reqwest::Proxy::all(proxy_url).with_context(|| format!("invalid proxy url {}", proxy_url))?;
+        if !settings.no_proxy.trim().is_empty() {
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string(settings.no_proxy.trim()));
+        }
+        builder = builder.proxy(proxy);
+    }
+
+    let ca_cert_path = settings.ca_cert_path.trim();
+    if !ca_cert_path.is_empty() {
+        let pem = std::fs::read(ca_cert_path).with_context(|| format!("failed to read CA bundle {}", ca_cert_path))?;
+        for cert in reqwest::Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("{} is not a valid PEM certificate bundle", ca_cert_path))?
+        {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder.build().context("failed to build HTTP client")
+}
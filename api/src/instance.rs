@@ -0,0 +1,84 @@
+use std::{fs, net::SocketAddr, path::PathBuf, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::runtime_env::data_dir;
+
+const INSTANCE_FILE: &str = "instance.json";
+
+/// Written by the instance that owns the data directory so later launches know where to forward.
+#[derive(Debug, Serialize, Deserialize)]
+struct InstanceMarker {
+    pid: u32,
+    addr: SocketAddr,
+    started_at: String,
+}
+
+/// `workflow-api open <path>` or `workflow-api <path>`: the repository to open on launch.
+pub fn open_request_from_args(args: &[String]) -> Result<Option<String>> {
+    let raw = match args {
+        [] => return Ok(None),
+        [cmd, path, ..] if cmd == "open" => path,
+        [cmd] if cmd == "open" => bail!("usage: workflow-api open <path>"),
+        [path, ..] => path,
+    };
+    let path = fs::canonicalize(raw).with_context(|| format!("cannot open {raw}"))?;
+    if !path.is_dir() {
+        bail!("{} is not a directory", path.display());
+    }
+    Ok(Some(path.to_string_lossy().replace('\\', "/").trim_start_matches("//?/").to_string()))
+}
+
+/// Looks for a live instance (marker address first, then the configured one) and hands it the
+/// open request. Returns the address that accepted it, or `None` when this process should serve.
+pub async fn forward_to_running(configured: Option<SocketAddr>, open: Option<&str>) -> Result<Option<SocketAddr>> {
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .timeout(Duration::from_secs(2))
+        .build()?;
+    let marker = read_marker().map(|marker| marker.addr);
+    for addr in marker.into_iter().chain(configured) {
+        let alive = client
+            .get(format!("http://{addr}/health"))
+            .send()
+            .await
+            .is_ok_and(|response| response.status().is_success());
+        if !alive {
+            continue;
+        }
+        if let Some(path) = open {
+            client
+                .post(format!("http://{addr}/api/instance/open"))
+                .json(&json!({ "path": path }))
+                .send()
+                .await?
+                .error_for_status()
+                .context("running instance rejected the open request")?;
+        }
+        return Ok(Some(addr));
+    }
+    Ok(None)
+}
+
+pub fn write_marker(addr: SocketAddr) -> Result<()> {
+    let marker = InstanceMarker { pid: std::process::id(), addr, started_at: Utc::now().to_rfc3339() };
+    fs::write(marker_path(), serde_json::to_vec_pretty(&marker)?).context("failed to write instance marker")
+}
+
+/// Removes the marker only if it still belongs to this process.
+pub fn remove_marker() {
+    if read_marker().is_some_and(|marker| marker.pid == std::process::id()) {
+        let _ = fs::remove_file(marker_path());
+    }
+}
+
+fn read_marker() -> Option<InstanceMarker> {
+    serde_json::from_slice(&fs::read(marker_path()).ok()?).ok()
+}
+
+fn marker_path() -> PathBuf {
+    data_dir().join(INSTANCE_FILE)
+}
@@ -0,0 +1,121 @@
+//! Windows caps ordinary paths at MAX_PATH (260 characters). The `\\?\` extended-length prefix lifts the
+//! limit for filesystem calls but turns off path normalization, so paths must be absolute, backslashed and
+//! free of `.`/`..` before the prefix is added. Everywhere else these functions leave paths alone.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const VERBATIM: &str = r"\\?\";
+const VERBATIM_UNC: &str = r"\\?\UNC\";
+
+/// The extended-length form of `path` for filesystem calls on Windows; `path` unchanged elsewhere.
+pub fn extended(path: &Path) -> PathBuf {
+    if !cfg!(windows) {
+        return path.to_path_buf();
+    }
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match absolute.to_str().and_then(to_extended) {
+        Some(extended) => PathBuf::from(extended),
+        None => absolute,
+    }
+}
+
+/// The ordinary form of an extended-length path, for messages and for tools like git that expect it.
+pub fn strip_extended(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if let Some(unc) = raw.strip_prefix(VERBATIM_UNC) {
+        PathBuf::from(format!(r"\\{unc}"))
+    } else if let Some(rest) = raw.strip_prefix(VERBATIM) {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// `C:\a\..\b` becomes `\\?\C:\b` and `\\server\share\x` becomes `\\?\UNC\server\share\x`. Already
+/// prefixed, device (`\\.\`) and relative paths return `None`.
+fn to_extended(raw: &str) -> Option<String> {
+    if raw.starts_with(VERBATIM) || raw.starts_with(r"\\.\") {
+        return None;
+    }
+    let raw = raw.replace('/', "\\");
+    let (prefix, rest) = if let Some(unc) = raw.strip_prefix(r"\\") {
+        // Server and share are part of the root and can't be popped by `..`.
+        let mut parts = unc.splitn(3, '\\');
+        let (server, share) = (parts.next().filter(|s| !s.is_empty())?, parts.next().filter(|s| !s.is_empty())?);
+        (format!(r"{VERBATIM_UNC}{server}\{share}"), parts.next().unwrap_or("").to_string())
+    } else {
+        let bytes = raw.as_bytes();
+        if bytes.len() < 3 || !bytes[0].is_ascii_alphabetic() || bytes[1] != b':' || bytes[2] != b'\\' {
+            return None;
+        }
+        (format!("{VERBATIM}{}", &raw[..2]), raw[3..].to_string())
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    if parts.is_empty() && prefix.starts_with(VERBATIM_UNC) {
+        // A share root has no trailing separator; a drive root keeps its one.
+        return Some(prefix);
+    }
+    Some(format!(r"{prefix}\{}", parts.join("\\")))
+}
+
+/// Whether Windows has the `LongPathsEnabled` policy on. `None` off Windows or when it can't be read.
+/// Extended-length paths work regardless, but other programs (git hooks, build tools) still hit MAX_PATH.
+pub fn os_long_paths_enabled() -> Option<bool> {
+    if !cfg!(windows) {
+        return None;
+    }
+    let out = Command::new("reg")
+        .args(["query", r"HKLM\SYSTEM\CurrentControlSet\Control\FileSystem", "/v", "LongPathsEnabled"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return Some(false);
+    }
+    Some(String::from_utf8_lossy(&out.stdout).split_whitespace().last() == Some("0x1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_drive_paths() {
+        assert_eq!(to_extended(r"C:\repo\src\..\lib.rs").as_deref(), Some(r"\\?\C:\repo\lib.rs"));
+        assert_eq!(to_extended("C:/repo/./a").as_deref(), Some(r"\\?\C:\repo\a"));
+        assert_eq!(to_extended(r"C:\").as_deref(), Some(r"\\?\C:\"));
+    }
+
+    #[test]
+    fn prefixes_unc_paths() {
+        assert_eq!(to_extended(r"\\server\share").as_deref(), Some(r"\\?\UNC\server\share"));
+        assert_eq!(to_extended(r"\\server\share\a\..\..\b").as_deref(), Some(r"\\?\UNC\server\share\b"));
+        assert_eq!(to_extended(r"\\server"), None);
+    }
+
+    #[test]
+    fn leaves_prefixed_and_device_paths() {
+        assert_eq!(to_extended(r"\\?\C:\repo"), None);
+        assert_eq!(to_extended(r"\\?\UNC\server\share"), None);
+        assert_eq!(to_extended(r"\\.\pipe\name"), None);
+    }
+
+    #[test]
+    fn leaves_relative_paths() {
+        assert_eq!(to_extended(r"repo\src"), None);
+        assert_eq!(to_extended(r"C:repo"), None);
+        assert_eq!(to_extended(r"\repo"), None);
+    }
+}
@@ -1,6 +1,11 @@
+mod app_error;
 mod app_state;
 mod db;
 mod engine;
+mod http_client;
+mod instance;
+mod long_path;
+mod metrics;
 mod models;
 mod runtime_env;
 mod routes;
@@ -11,11 +16,11 @@ use anyhow::Context;
 use dotenvy::dotenv;
 use axum::Router;
 use tower_http::{
-    cors::CorsLayer,
+    cors::{AllowOrigin, Any, CorsLayer},
     services::{ServeDir, ServeFile},
     trace::TraceLayer,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::app_state::AppState;
 
@@ -23,11 +28,16 @@ use crate::app_state::AppState;
 async fn main() -> anyhow::Result<()> {
     let _ = dotenv();
 
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    // `mcp` speaks JSON-RPC on stdout, so logs must go to stderr.
+    let stdio_mcp = args.first().is_some_and(|arg| arg == "mcp");
+    let log_writer = if stdio_mcp { BoxMakeWriter::new(std::io::stderr) } else { BoxMakeWriter::new(std::io::stdout) };
+
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
             "workflow_api=info,workflow_api::engine::capabilities::inference::api::oai=info,workflow_api::engine::capabilities::inference::browser::adapter=info,workflow_api::routes::runs=info,tower_http=info".into()
         }))
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(log_writer))
         .init();
 
     let cwd = env::current_dir().context("failed to determine current directory")?;
@@ -36,28 +46,67 @@ async fn main() -> anyhow::Result<()> {
 
     let data_dir = repo_root.join(".data");
     fs::create_dir_all(&data_dir).context("failed to create .data directory")?;
+    crate::runtime_env::set_data_dir(data_dir.clone());
 
     let db_path = data_dir.join("workflow.db");
     let db_url = format!("sqlite://{}", db_path.to_string_lossy().replace('\\', "/"));
+    if stdio_mcp {
+        let db = db::connect(&db_url).await?;
+        db::migrate(&db).await?;
+        return crate::engine::mcp::serve_stdio(db).await;
+    }
+
+    let open_request = instance::open_request_from_args(&args)?;
+    let addr = crate::runtime_env::workflow_api_bind_addr();
+    if let Some(running) = instance::forward_to_running(addr.as_ref().ok().copied(), open_request.as_deref()).await? {
+        match &open_request {
+            Some(path) => tracing::info!(%running, path, "forwarded open request to running instance"),
+            None => tracing::info!(%running, "workflow api already running"),
+        }
+        return Ok(());
+    }
+    let addr = addr?;
+    // Resolved before the swap: on Linux the running image follows the binary to its `.old` name.
+    let exe = env::current_exe()?;
+    match crate::engine::updates::apply_staged_update() {
+        Ok(Some(version)) => {
+            tracing::info!(version, "installed staged update; restarting");
+            let status = std::process::Command::new(&exe).args(&args).status()?;
+            std::process::exit(status.code().unwrap_or(0));
+        }
+        Ok(None) => {}
+        Err(err) => tracing::warn!(error = %format!("{err:#}"), "failed to install staged update"),
+    }
+
+    if crate::long_path::os_long_paths_enabled() == Some(false) {
+        tracing::warn!(
+            "Windows long paths are disabled (LongPathsEnabled=0); the app handles deep paths itself, but git and build tools may fail on them. Run the environment doctor for the fix."
+        );
+    }
 
     let db = db::connect(&db_url).await?;
     db::migrate(&db).await?;
 
+    crate::engine::updates::spawn_scheduler(db.clone());
     let state = AppState::new(db);
+    if let Some(path) = open_request {
+        state.request_open(path);
+    }
 
     let web_dist = repo_root.join("web").join("dist");
     let app = build_router(state, &web_dist);
 
-    let addr = crate::runtime_env::workflow_api_bind_addr()?;
-
     let listener = tokio::net::TcpListener::bind(addr).await?;
+    instance::write_marker(addr)?;
     tracing::info!(%addr, web_dist = %web_dist.display(), "workflow api listening");
     axum::serve(listener, app)
         .with_graceful_shutdown(async {
             let _ = tokio::signal::ctrl_c().await;
             crate::engine::capabilities::inference::browser::adapter::shutdown_browser_bridge();
+            crate::engine::processes::kill_all();
         })
         .await?;
+    instance::remove_marker();
     Ok(())
 }
 
@@ -65,7 +114,8 @@ fn build_router(state: AppState, web_dist: &Path) -> Router {
     let api = Router::new()
         .merge(routes::router())
         .with_state(state)
-        .layer(CorsLayer::permissive())
+        .layer(axum::middleware::from_fn(app_error::structure_error_responses))
+        .layer(cors_layer(crate::runtime_env::env_http_url("WORKFLOW_WEB_HOST", "WORKFLOW_WEB_PORT").ok().as_deref()))
         .layer(TraceLayer::new_for_http());
 
     if web_dist.exists() {
@@ -77,6 +127,17 @@ fn build_router(state: AppState, web_dist: &Path) -> Router {
     }
 }
 
+/// The UI is served same-origin (from web/dist or through the Vite proxy), so cross-origin requests are
+/// only allowed from the configured dev server. Token-guarded endpoints (automation, MCP) must not be
+/// readable by arbitrary pages.
+fn cors_layer(web_origin: Option<&str>) -> CorsLayer {
+    let origins = web_origin
+        .and_then(|origin| origin.parse().ok())
+        .into_iter()
+        .collect::<Vec<axum::http::HeaderValue>>();
+    CorsLayer::new().allow_origin(AllowOrigin::list(origins)).allow_methods(Any).allow_headers(Any)
+}
+
 fn detect_repo_root(start: &Path) -> Option<PathBuf> {
     for dir in start.ancestors() {
         let has_web = dir.join("web").exists();
@@ -87,3 +148,30 @@ fn detect_repo_root(start: &Path) -> Option<PathBuf> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header, Request},
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn allowed_origin(web_origin: Option<&str>, origin: &str) -> Option<String> {
+        let app = Router::new().route("/api/ping", get(|| async { "pong" })).layer(cors_layer(web_origin));
+        let request = Request::get("/api/ping").header(header::ORIGIN, origin).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn cors_only_admits_the_web_dev_origin() {
+        let dev = "http://127.0.0.1:5173";
+        assert_eq!(allowed_origin(Some(dev), dev).await.as_deref(), Some(dev));
+        assert_eq!(allowed_origin(Some(dev), "https://evil.example").await, None);
+        assert_eq!(allowed_origin(None, dev).await, None);
+    }
+}
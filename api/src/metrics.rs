@@ -0,0 +1,72 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+static GIT_SPAWNS_TOTAL: AtomicU64 = AtomicU64::new(0);
+static GIT_SPAWN_WINDOW: OnceLock<Mutex<VecDeque<Instant>>> = OnceLock::new();
+
+const GIT_SPAWN_WINDOW_SECS: u64 = 10;
+
+#[derive(Debug, Serialize)]
+pub struct GitSpawnStats {
+    pub total: u64,
+    pub last_second: usize,
+    pub per_second_avg: f64,
+    pub window_secs: u64,
+}
+
+fn git_spawn_window() -> &'static Mutex<VecDeque<Instant>> {
+    GIT_SPAWN_WINDOW.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+fn prune(window: &mut VecDeque<Instant>, now: Instant) {
+    let horizon = Duration::from_secs(GIT_SPAWN_WINDOW_SECS);
+    while window.front().map(|t| now.duration_since(*t) > horizon).unwrap_or(false) {
+        window.pop_front();
+    }
+}
+
+pub fn record_git_spawn() {
+    GIT_SPAWNS_TOTAL.fetch_add(1, Ordering::Relaxed);
+    let now = Instant::now();
+    if let Ok(mut window) = git_spawn_window().lock() {
+        prune(&mut window, now);
+        window.push_back(now);
+    }
+}
+
+pub fn git_spawn_stats() -> GitSpawnStats {
+    let now = Instant::now();
+    let (last_second, in_window) = match git_spawn_window().lock() {
+        Ok(mut window) => {
+            prune(&mut window, now);
+            let last_second = window
+                .iter()
+                .filter(|t| now.duration_since(**t) <= Duration::from_secs(1))
+                .count();
+            (last_second, window.len())
+        }
+        Err(_) => (0, 0),
+    };
+
+    GitSpawnStats {
+        total: GIT_SPAWNS_TOTAL.load(Ordering::Relaxed),
+        last_second,
+        per_second_avg: in_window as f64 / GIT_SPAWN_WINDOW_SECS as f64,
+        window_secs: GIT_SPAWN_WINDOW_SECS,
+    }
+}
+
+/// Resident set size in bytes, read from /proc on Linux.
+pub fn resident_memory_bytes() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
@@ -153,6 +153,9 @@ pub struct WorkflowTemplateDefinition {
     #[serde(default)]
     pub governance: Value,
     pub steps: Vec<WorkflowStepDefinition>,
+    /// Fields this build does not know about, kept so saving does not drop them.
+    #[serde(flatten, default)]
+    pub extra: serde_json::Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -404,6 +407,32 @@ pub struct AppSettings {
     pub bridges: Value,
     #[serde(default)]
     pub git: Value,
+    #[serde(default)]
+    pub network: Value,
+    #[serde(default)]
+    pub inference: Value,
+    #[serde(default)]
+    pub workspace: Value,
+    #[serde(default)]
+    pub tree_filters: Value,
+    #[serde(default)]
+    pub terminal: Value,
+    #[serde(default)]
+    pub canvas: Value,
+    #[serde(default)]
+    pub updates: Value,
+    #[serde(default)]
+    pub accessibility: Value,
+    #[serde(default)]
+    pub plugins: Value,
+    #[serde(default)]
+    pub automation: Value,
+    #[serde(default)]
+    pub mcp: Value,
+    #[serde(default)]
+    pub editor: Value,
+    #[serde(default)]
+    pub debug: Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
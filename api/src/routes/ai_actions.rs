@@ -0,0 +1,256 @@
+use std::path::Path;
+
+use axum::{extract::State, routing::post, Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::{
+        ai_actions::{self, AiActionResult, SelectionEdit},
+        capabilities::{
+            changeset::{apply_changeset, ChangesetRequest},
+            inference::{GenerationSettings, InferenceConfig},
+        },
+        doc_comments::{self, DocCommentProposal},
+        repo_overview::{self, RepoOverview, DEFAULT_REPO_REPORT_PATH},
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/ai/explain-commit", post(explain_commit))
+        .route("/api/ai/summarize-diff", post(summarize_diff))
+        .route("/api/ai/edit-selection", post(edit_selection))
+        .route("/api/ai/doc-comments", post(propose_doc_comments))
+        .route("/api/ai/doc-comments/apply", post(apply_doc_comments))
+        .route("/api/ai/repo-overview", post(generate_repo_overview))
+        .route("/api/ai/repo-overview/save", post(save_repo_overview))
+}
+
+/// Model settings for one-off requests; unset fields fall back to the inference defaults.
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct AiModelRequest {
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub generation: Option<GenerationSettings>,
+}
+
+impl AiModelRequest {
+    pub(super) fn config(&self) -> InferenceConfig {
+        let mut config = InferenceConfig::default();
+        if let Some(model) = self.model.as_deref().map(str::trim).filter(|model| !model.is_empty()) {
+            config.model = model.to_string();
+        }
+        if let Some(generation) = &self.generation {
+            config.generation = generation.clone();
+        }
+        config
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ExplainCommitRequest {
+    repo_ref: String,
+    commit: String,
+    #[serde(default, flatten)]
+    model: AiModelRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct SummarizeDiffRequest {
+    repo_ref: String,
+    scope: String,
+    #[serde(default, flatten)]
+    model: AiModelRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct EditSelectionRequest {
+    #[serde(flatten)]
+    edit: SelectionEdit,
+    #[serde(default, flatten)]
+    model: AiModelRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct DocCommentsRequest {
+    repo_ref: String,
+    path: String,
+    /// Limits the action to one function; the whole file when unset.
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(default, flatten)]
+    model: AiModelRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApplyDocCommentsRequest {
+    repo_ref: String,
+    payload_text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoOverviewRequest {
+    repo_ref: String,
+    #[serde(default, flatten)]
+    model: AiModelRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct SaveRepoOverviewRequest {
+    repo_ref: String,
+    markdown: String,
+    /// `repo` writes to `path` inside the repository; `app_data` keeps it out of the tree.
+    destination: String,
+    #[serde(default)]
+    path: Option<String>,
+}
+
+async fn explain_commit(
+    State(state): State<AppState>,
+    Json(req): Json<ExplainCommitRequest>,
+) -> Result<Json<AiActionResult>, AppError> {
+    let show = tokio::task::spawn_blocking(move || ai_actions::commit_to_explain(Path::new(&req.repo_ref), &req.commit))
+        .await
+        .map_err(internal)?
+        .map_err(bad_request)?;
+    ai_actions::explain_commit(&state, &req.model.config(), &show).await.map(Json).map_err(bad_gateway)
+}
+
+async fn summarize_diff(
+    State(state): State<AppState>,
+    Json(req): Json<SummarizeDiffRequest>,
+) -> Result<Json<AiActionResult>, AppError> {
+    let diff = tokio::task::spawn_blocking(move || ai_actions::diff_to_summarize(Path::new(&req.repo_ref), &req.scope))
+        .await
+        .map_err(internal)?
+        .map_err(bad_request)?;
+    ai_actions::summarize_diff(&state, &req.model.config(), &diff).await.map(Json).map_err(bad_gateway)
+}
+
+async fn edit_selection(
+    State(state): State<AppState>,
+    Json(req): Json<EditSelectionRequest>,
+) -> Result<Json<AiActionResult>, AppError> {
+    ai_actions::check_selection_edit(&req.edit).map_err(bad_request)?;
+    ai_actions::edit_selection(&state, &req.model.config(), &req.edit)
+        .await
+        .map(Json)
+        .map_err(bad_gateway)
+}
+
+async fn propose_doc_comments(
+    State(state): State<AppState>,
+    Json(req): Json<DocCommentsRequest>,
+) -> Result<Json<DocCommentProposal>, AppError> {
+    doc_comments::propose(&state, &req.model.config(), Path::new(&req.repo_ref), &req.path, req.symbol.as_deref())
+        .await
+        .map(Json)
+        .map_err(bad_gateway)
+}
+
+/// Writes a previewed proposal through the regular changeset applier, so it is linted and shows up in changeset history.
+async fn apply_doc_comments(
+    State(state): State<AppState>,
+    Json(req): Json<ApplyDocCommentsRequest>,
+) -> Result<Json<Value>, AppError> {
+    apply_changeset(
+        &state.db,
+        ChangesetRequest {
+            repo_ref: req.repo_ref,
+            git_ref: "WORKTREE".to_string(),
+            payload_text: req.payload_text,
+            source: "ai_doc_comments".to_string(),
+            workflow_key: None,
+            run_id: None,
+            step_id: None,
+            stage_after_apply: false,
+            commit_after_apply: false,
+            commit_message: None,
+        },
+    )
+    .await
+    .map(Json)
+    .map_err(internal)
+}
+
+async fn generate_repo_overview(
+    State(state): State<AppState>,
+    Json(req): Json<RepoOverviewRequest>,
+) -> Result<Json<RepoOverview>, AppError> {
+    repo_overview::generate(&state, &req.model.config(), Path::new(&req.repo_ref))
+        .await
+        .map(Json)
+        .map_err(bad_gateway)
+}
+
+async fn save_repo_overview(Json(req): Json<SaveRepoOverviewRequest>) -> Result<Json<Value>, AppError> {
+    let path = match req.destination.as_str() {
+        "repo" => Some(req.path.as_deref().map(str::trim).filter(|path| !path.is_empty()).unwrap_or(DEFAULT_REPO_REPORT_PATH)),
+        "app_data" => None,
+        other => return Err((axum::http::StatusCode::BAD_REQUEST, format!("unknown destination {other}")).into()),
+    };
+    let saved = repo_overview::save(Path::new(&req.repo_ref), &req.markdown, path)
+        .map_err(bad_request)?;
+    Ok(Json(json!({ "ok": true, "path": saved.to_string_lossy() })))
+}
+
+fn bad_request<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")).into()
+}
+
+fn bad_gateway<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_GATEWAY, format!("{err:#}")).into()
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::capabilities::git::git::run_git;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn input_errors_are_bad_requests_before_any_provider_call() {
+        let state = AppState::new(crate::db::test_db_with_settings(json!({})).await);
+        let repo = std::env::temp_dir().join(format!("ai-explain-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        run_git(&repo, &["add", "a.txt"]).unwrap();
+        run_git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]).unwrap();
+        let written = repo.join("written");
+
+        for commit in ["", "WORKTREE", "no-such-commit", &format!("--output={}", written.display())] {
+            let req = ExplainCommitRequest {
+                repo_ref: repo.to_string_lossy().to_string(),
+                commit: commit.to_string(),
+                model: AiModelRequest::default(),
+            };
+            let err = explain_commit(State(state.clone()), Json(req)).await.unwrap_err();
+            assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST, "{commit:?}");
+        }
+        assert!(!written.exists());
+        assert!(ai_actions::commit_to_explain(&repo, "HEAD").unwrap().contains("a.txt"));
+
+        let req = SummarizeDiffRequest {
+            repo_ref: repo.to_string_lossy().to_string(),
+            scope: "staged".to_string(),
+            model: AiModelRequest::default(),
+        };
+        let err = summarize_diff(State(state.clone()), Json(req)).await.unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
+
+        let edit = serde_json::from_value(json!({ "path": "a.txt", "selection": "a", "instruction": " " })).unwrap();
+        let req = EditSelectionRequest { edit, model: AiModelRequest::default() };
+        let err = edit_selection(State(state), Json(req)).await.unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
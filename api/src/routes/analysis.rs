@@ -0,0 +1,494 @@
+use std::path::PathBuf;
+
+use axum::{extract::{Path, Query, State}, routing::{delete, get, post}, Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::capabilities::analysis::{
+        artifacts::{append_gitignore_pattern, detect_build_artifacts, ArtifactFinding, DEFAULT_LARGE_BLOB_BYTES},
+        commands::DetectedProjectCommands,
+        dependencies::{collect_dependency_inventory, render_dependency_csv, DependencyEntry},
+        doc_links::{scan_doc_links, DocLinkReport},
+        duplicates::{diff_worktree_files, find_duplicates, DuplicateReport, DEFAULT_NEAR_DUPLICATE_THRESHOLD},
+        outline::{outline_source, OutlineSymbol},
+        ownership::{ownership_map, suggest_reviewers, OwnershipMap, ReviewerSuggestions, DEFAULT_OWNERSHIP_WINDOW_DAYS},
+        range::{analyze_range, RangeAnalysis},
+        report::{build_report, render_html, render_stats_csv},
+        sizes::{collect_file_sizes, FileSizeEntry},
+    },
+    engine::capabilities::{filesystem::read_text_file_at_ref, git::git::resolve_read_ref},
+    engine::analysis_snapshots::{self, AnalysisSnapshot, SnapshotComparison},
+};
+
+use super::workflow_scope::resolve_workflow_scope;
+
+#[derive(Debug, Deserialize)]
+struct AnalysisRepoRequest {
+    repo_ref: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DependencyExportRequest {
+    repo_ref: String,
+    #[serde(default = "default_export_format")]
+    format: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtifactScanRequest {
+    repo_ref: String,
+    #[serde(default)]
+    large_blob_bytes: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitignoreAppendRequest {
+    repo_ref: String,
+    pattern: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectCommandsRequest {
+    repo_ref: String,
+    /// Rescans the manifests instead of answering from the per-repo cache.
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutlineRequest {
+    repo_ref: String,
+    path: String,
+    #[serde(default)]
+    git_ref: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutlineResponse {
+    ok: bool,
+    path: String,
+    symbols: Vec<OutlineSymbol>,
+}
+
+#[derive(Debug, Serialize)]
+struct ArtifactScanResponse {
+    ok: bool,
+    repo_ref: String,
+    large_blob_bytes: u64,
+    findings: Vec<ArtifactFinding>,
+}
+
+#[derive(Debug, Serialize)]
+struct DependencyInventoryResponse {
+    ok: bool,
+    repo_ref: String,
+    count: usize,
+    dependencies: Vec<DependencyEntry>,
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FileSizesResponse {
+    ok: bool,
+    repo_ref: String,
+    total_bytes: u64,
+    total_lines: u64,
+    files: Vec<FileSizeEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct AnalysisExportResponse {
+    ok: bool,
+    format: String,
+    file_name: String,
+    contents: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SizesExportRequest {
+    repo_ref: String,
+    #[serde(default = "default_export_format")]
+    format: String,
+    /// For CSV: `extensions` (default) or `directories`.
+    #[serde(default)]
+    table: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RangeAnalysisRequest {
+    repo_ref: String,
+    from_ref: String,
+    to_ref: String,
+    #[serde(default)]
+    depth: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwnershipRequest {
+    repo_ref: String,
+    #[serde(default)]
+    window_days: Option<u32>,
+    /// Owners kept per directory (map) or reviewers returned (suggestions).
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DuplicatesRequest {
+    repo_ref: String,
+    /// Minimum shingle similarity (0.1 to 1.0) for near duplicates.
+    #[serde(default)]
+    threshold: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DuplicateDiffRequest {
+    repo_ref: String,
+    left: String,
+    right: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DuplicateDiffResponse {
+    ok: bool,
+    patch: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotListQuery {
+    repo_ref: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateSnapshotRequest {
+    repo_ref: String,
+    name: String,
+    #[serde(default)]
+    git_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareSnapshotsRequest {
+    base_id: String,
+    head_id: String,
+    /// Path segments used to group directories; 1 groups by top-level directory.
+    #[serde(default)]
+    depth: Option<usize>,
+}
+
+fn default_export_format() -> String {
+    "json".to_string()
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/analysis/dependencies", post(analysis_dependencies))
+        .route("/api/analysis/dependencies/export", post(analysis_dependencies_export))
+        .route("/api/analysis/artifacts", post(analysis_artifacts))
+        .route("/api/analysis/gitignore", post(analysis_gitignore_append))
+        .route("/api/analysis/sizes", post(analysis_sizes))
+        .route("/api/analysis/sizes/export", post(analysis_sizes_export))
+        .route("/api/analysis/outline", post(analysis_outline))
+        .route("/api/analysis/range", post(analysis_range))
+        .route("/api/analysis/ownership", post(analysis_ownership))
+        .route("/api/analysis/ownership/reviewers", post(analysis_suggest_reviewers))
+        .route("/api/analysis/doc-links", post(analysis_doc_links))
+        .route("/api/analysis/duplicates", post(analysis_duplicates))
+        .route("/api/analysis/duplicates/diff", post(analysis_duplicate_diff))
+        .route("/api/analysis/commands", post(analysis_project_commands))
+        .route("/api/analysis/snapshots", get(list_analysis_snapshots).post(create_analysis_snapshot))
+        .route("/api/analysis/snapshots/compare", post(compare_analysis_snapshots))
+        .route("/api/analysis/snapshots/:id", delete(delete_analysis_snapshot))
+        .route("/api/workflow-runs/:run_id/analysis/dependencies", get(workflow_analysis_dependencies))
+}
+
+async fn analysis_dependencies(
+    Json(req): Json<AnalysisRepoRequest>,
+) -> Result<Json<DependencyInventoryResponse>, AppError> {
+    dependency_inventory_response(req.repo_ref)
+}
+
+async fn workflow_analysis_dependencies(
+    State(state): State<AppState>,
+    Path(run_id): Path<uuid::Uuid>,
+) -> Result<Json<DependencyInventoryResponse>, AppError> {
+    let scope = resolve_workflow_scope(&state, run_id).await?;
+    dependency_inventory_response(scope.repo_ref)
+}
+
+fn dependency_inventory_response(
+    repo_ref: String,
+) -> Result<Json<DependencyInventoryResponse>, AppError> {
+    let inventory = collect_dependency_inventory(&PathBuf::from(&repo_ref)).map_err(internal)?;
+    Ok(Json(DependencyInventoryResponse {
+        ok: true,
+        repo_ref,
+        count: inventory.dependencies.len(),
+        dependencies: inventory.dependencies,
+        warnings: inventory.warnings,
+    }))
+}
+
+async fn analysis_dependencies_export(
+    Json(req): Json<DependencyExportRequest>,
+) -> Result<Json<AnalysisExportResponse>, AppError> {
+    let dependencies = collect_dependency_inventory(&PathBuf::from(&req.repo_ref)).map_err(internal)?.dependencies;
+    let format = req.format.trim().to_ascii_lowercase();
+    let contents = match format.as_str() {
+        "csv" => render_dependency_csv(&dependencies),
+        "json" => serde_json::to_string_pretty(&dependencies).map_err(internal)?,
+        other => {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("unsupported export format: {}", other),
+            ).into())
+        }
+    };
+    Ok(Json(AnalysisExportResponse {
+        ok: true,
+        file_name: format!("dependency_inventory.{}", format),
+        format,
+        contents,
+    }))
+}
+
+async fn analysis_artifacts(
+    Json(req): Json<ArtifactScanRequest>,
+) -> Result<Json<ArtifactScanResponse>, AppError> {
+    let large_blob_bytes = req.large_blob_bytes.unwrap_or(DEFAULT_LARGE_BLOB_BYTES);
+    let findings = detect_build_artifacts(&PathBuf::from(&req.repo_ref), large_blob_bytes).map_err(internal)?;
+    Ok(Json(ArtifactScanResponse {
+        ok: true,
+        repo_ref: req.repo_ref,
+        large_blob_bytes,
+        findings,
+    }))
+}
+
+async fn analysis_sizes(
+    Json(req): Json<AnalysisRepoRequest>,
+) -> Result<Json<FileSizesResponse>, AppError> {
+    let files = collect_file_sizes(&PathBuf::from(&req.repo_ref)).map_err(internal)?;
+    Ok(Json(FileSizesResponse {
+        ok: true,
+        repo_ref: req.repo_ref,
+        total_bytes: files.iter().map(|file| file.bytes).sum(),
+        total_lines: files.iter().map(|file| file.lines).sum(),
+        files,
+    }))
+}
+
+async fn analysis_sizes_export(
+    Json(req): Json<SizesExportRequest>,
+) -> Result<Json<AnalysisExportResponse>, AppError> {
+    let files = collect_file_sizes(&PathBuf::from(&req.repo_ref)).map_err(internal)?;
+    let report = build_report(&req.repo_ref, files);
+    let format = req.format.trim().to_ascii_lowercase();
+    let (file_name, contents) = match format.as_str() {
+        "json" => ("repo_analysis.json".to_string(), serde_json::to_string_pretty(&report).map_err(internal)?),
+        "html" => ("repo_analysis.html".to_string(), render_html(&report)),
+        "csv" => match req.table.as_deref().unwrap_or("extensions") {
+            "extensions" => ("repo_analysis_extensions.csv".to_string(), render_stats_csv("extension", &report.extensions)),
+            "directories" => ("repo_analysis_directories.csv".to_string(), render_stats_csv("directory", &report.directories)),
+            other => return Err(bad_request(format!("unsupported CSV table: {other}"))),
+        },
+        other => return Err(bad_request(format!("unsupported export format: {other}"))),
+    };
+    Ok(Json(AnalysisExportResponse { ok: true, format, file_name, contents }))
+}
+
+async fn analysis_range(
+    Json(req): Json<RangeAnalysisRequest>,
+) -> Result<Json<RangeAnalysis>, AppError> {
+    let repo = PathBuf::from(req.repo_ref.trim());
+    tokio::task::spawn_blocking(move || analyze_range(&repo, &req.from_ref, &req.to_ref, req.depth.unwrap_or(1)))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn analysis_ownership(
+    Json(req): Json<OwnershipRequest>,
+) -> Result<Json<OwnershipMap>, AppError> {
+    let repo = PathBuf::from(req.repo_ref.trim());
+    let window_days = req.window_days.unwrap_or(DEFAULT_OWNERSHIP_WINDOW_DAYS);
+    tokio::task::spawn_blocking(move || ownership_map(&repo, window_days, req.limit))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn analysis_suggest_reviewers(
+    Json(req): Json<OwnershipRequest>,
+) -> Result<Json<ReviewerSuggestions>, AppError> {
+    let repo = PathBuf::from(req.repo_ref.trim());
+    let window_days = req.window_days.unwrap_or(DEFAULT_OWNERSHIP_WINDOW_DAYS);
+    tokio::task::spawn_blocking(move || suggest_reviewers(&repo, window_days, req.limit))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn analysis_doc_links(
+    Json(req): Json<AnalysisRepoRequest>,
+) -> Result<Json<DocLinkReport>, AppError> {
+    let repo = PathBuf::from(req.repo_ref.trim());
+    tokio::task::spawn_blocking(move || scan_doc_links(&repo))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn analysis_duplicates(
+    Json(req): Json<DuplicatesRequest>,
+) -> Result<Json<DuplicateReport>, AppError> {
+    let repo = PathBuf::from(req.repo_ref.trim());
+    let threshold = req.threshold.unwrap_or(DEFAULT_NEAR_DUPLICATE_THRESHOLD);
+    tokio::task::spawn_blocking(move || find_duplicates(&repo, threshold))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn analysis_duplicate_diff(
+    Json(req): Json<DuplicateDiffRequest>,
+) -> Result<Json<DuplicateDiffResponse>, AppError> {
+    let patch = diff_worktree_files(&PathBuf::from(req.repo_ref.trim()), &req.left, &req.right).map_err(bad_request)?;
+    Ok(Json(DuplicateDiffResponse { ok: true, patch }))
+}
+
+async fn analysis_outline(
+    Json(req): Json<OutlineRequest>,
+) -> Result<Json<OutlineResponse>, AppError> {
+    let repo_ref = req.repo_ref.trim().to_string();
+    let path = req.path.trim().trim_start_matches('/').to_string();
+    let git_ref = req.git_ref.unwrap_or_default();
+    let text = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || {
+            let git_ref = resolve_read_ref(std::path::Path::new(&repo_ref), &git_ref)?;
+            read_text_file_at_ref(&repo_ref, &path, &git_ref)
+        })
+        .await
+        .map_err(internal)?
+        .map_err(bad_request)?
+    };
+    let symbols = outline_source(&path, &text);
+    Ok(Json(OutlineResponse { ok: true, path, symbols }))
+}
+
+async fn analysis_project_commands(
+    State(state): State<AppState>,
+    Json(req): Json<ProjectCommandsRequest>,
+) -> Result<Json<DetectedProjectCommands>, AppError> {
+    let repo_ref = req.repo_ref.trim();
+    if repo_ref.is_empty() {
+        return Err(bad_request("repo_ref is required"));
+    }
+    state.project_commands().get_or_detect(repo_ref, req.refresh).map(Json).map_err(bad_request)
+}
+
+async fn list_analysis_snapshots(
+    State(state): State<AppState>,
+    Query(query): Query<SnapshotListQuery>,
+) -> Result<Json<Vec<AnalysisSnapshot>>, AppError> {
+    analysis_snapshots::list(&state.db, &query.repo_ref).await.map(Json).map_err(internal)
+}
+
+async fn create_analysis_snapshot(
+    State(state): State<AppState>,
+    Json(req): Json<CreateSnapshotRequest>,
+) -> Result<Json<AnalysisSnapshot>, AppError> {
+    analysis_snapshots::create(&state.db, &req.repo_ref, &req.name, req.git_ref.as_deref().unwrap_or_default())
+        .await
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn compare_analysis_snapshots(
+    State(state): State<AppState>,
+    Json(req): Json<CompareSnapshotsRequest>,
+) -> Result<Json<SnapshotComparison>, AppError> {
+    analysis_snapshots::compare(&state.db, &req.base_id, &req.head_id, req.depth.unwrap_or(1))
+        .await
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn delete_analysis_snapshot(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let deleted = analysis_snapshots::delete(&state.db, &id).await.map_err(internal)?;
+    Ok(Json(serde_json::json!({ "ok": true, "deleted": deleted })))
+}
+
+async fn analysis_gitignore_append(
+    Json(req): Json<GitignoreAppendRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let added = append_gitignore_pattern(&PathBuf::from(&req.repo_ref), &req.pattern).map_err(internal)?;
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "repo_ref": req.repo_ref,
+        "pattern": req.pattern.trim(),
+        "added": added,
+    })))
+}
+
+fn bad_request<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")).into()
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::capabilities::git::git::run_git;
+
+    use super::*;
+
+    async fn outline(repo: &std::path::Path, path: &str, git_ref: &str) -> Result<OutlineResponse, AppError> {
+        let req = OutlineRequest {
+            repo_ref: repo.to_string_lossy().to_string(),
+            path: path.to_string(),
+            git_ref: Some(git_ref.to_string()),
+        };
+        analysis_outline(Json(req)).await.map(|Json(response)| response)
+    }
+
+    #[tokio::test]
+    async fn outlines_read_only_inside_the_repo_at_a_resolved_ref() {
+        let repo = std::env::temp_dir().join(format!("analysis-outline-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        std::fs::write(repo.join("lib.rs"), "pub fn exported() {}\n").unwrap();
+        run_git(&repo, &["add", "lib.rs"]).unwrap();
+        run_git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]).unwrap();
+        let outside = repo.with_extension("rs");
+        std::fs::write(&outside, "pub fn secret() {}\n").unwrap();
+        let escape = format!("../{}", outside.file_name().unwrap().to_string_lossy());
+
+        assert!(!outline(&repo, "lib.rs", "WORKTREE").await.unwrap().symbols.is_empty());
+        assert!(!outline(&repo, "lib.rs", "HEAD").await.unwrap().symbols.is_empty());
+        let err = outline(&repo, &escape, "WORKTREE").await.unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
+
+        let written = repo.join("written");
+        let option = format!("--output={}", written.display());
+        let err = outline(&repo, "lib.rs", &option).await.unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
+        assert!(!written.exists());
+
+        std::fs::remove_dir_all(&repo).unwrap();
+        std::fs::remove_file(&outside).unwrap();
+    }
+}
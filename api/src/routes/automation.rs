@@ -0,0 +1,224 @@
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::token_auth::require_token;
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::capabilities::{
+        analysis::{artifacts::{detect_build_artifacts, DEFAULT_LARGE_BLOB_BYTES}, dependencies::collect_dependency_inventory, sizes::collect_file_sizes},
+        context_export::{normalize_context_export_payload, render_context_export_text},
+        filesystem::resolve_workspace_path,
+        git::git::resolve_read_ref,
+    },
+    models::{CreateRunRequest, WorkflowRun},
+};
+
+/// Endpoints for editors and scripts. Off by default; when enabled every call must carry
+/// `Authorization: Bearer <automation.token>`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/automation/open", post(open_repo))
+        .route("/api/automation/analysis/:kind", post(run_analysis))
+        .route("/api/automation/context-export", post(context_export))
+        .route("/api/automation/tasks/:name/run", post(run_task))
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoRequest {
+    repo_ref: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AutomationContextExportRequest {
+    repo_ref: String,
+    /// Repo-relative or absolute paths, e.g. the file currently open in the editor.
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(default)]
+    git_ref: Option<String>,
+    #[serde(default)]
+    include_unstaged_diff: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunTaskRequest {
+    repo_ref: String,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+async fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), AppError> {
+    require_token(state, headers, "automation", "the automation API is disabled; enable it under Automation", "automation").await
+}
+
+async fn open_repo(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<RepoRequest>,
+) -> Result<Json<Value>, AppError> {
+    authorize(&state, &headers).await?;
+    let path = existing_repo(&req.repo_ref)?;
+    state.request_open(path.clone());
+    Ok(Json(json!({ "ok": true, "repo_ref": path })))
+}
+
+async fn run_analysis(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(kind): Path<String>,
+    Json(req): Json<RepoRequest>,
+) -> Result<Json<Value>, AppError> {
+    authorize(&state, &headers).await?;
+    let repo = PathBuf::from(existing_repo(&req.repo_ref)?);
+    let result = tokio::task::spawn_blocking(move || -> anyhow::Result<Value> {
+        Ok(match kind.as_str() {
+            "sizes" => json!({ "files": collect_file_sizes(&repo)? }),
+            "dependencies" => serde_json::to_value(collect_dependency_inventory(&repo)?)?,
+            "artifacts" => json!({ "findings": detect_build_artifacts(&repo, DEFAULT_LARGE_BLOB_BYTES)? }),
+            other => anyhow::bail!("unknown analysis {other}; expected sizes, dependencies or artifacts"),
+        })
+    })
+    .await
+    .map_err(internal)?
+    .map_err(|err| (StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+    Ok(Json(json!({ "ok": true, "repo_ref": req.repo_ref, "result": result })))
+}
+
+async fn context_export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<AutomationContextExportRequest>,
+) -> Result<Json<Value>, AppError> {
+    authorize(&state, &headers).await?;
+    let repo_ref = existing_repo(&req.repo_ref)?;
+    let files = export_files(&repo_ref, &req.files)?;
+    let git_ref = req.git_ref.unwrap_or_else(|| "WORKTREE".to_string());
+    resolve_read_ref(FsPath::new(&repo_ref), &git_ref).map_err(|err| (StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+    let payload = normalize_context_export_payload(
+        json!({
+            "repo_ref": repo_ref,
+            "git_ref": git_ref,
+            "include_files": files,
+            "include_unstaged_diff": req.include_unstaged_diff,
+        }),
+        None,
+        &repo_ref,
+    );
+    let text = tokio::task::spawn_blocking(move || render_context_export_text(payload))
+        .await
+        .map_err(internal)?
+        .map_err(internal)?;
+    Ok(Json(json!({ "ok": true, "repo_ref": repo_ref, "bytes": text.len(), "text": text })))
+}
+
+/// Starts a run from the workflow template with this name.
+async fn run_task(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<RunTaskRequest>,
+) -> Result<Json<WorkflowRun>, AppError> {
+    authorize(&state, &headers).await?;
+    let repo_ref = existing_repo(&req.repo_ref)?;
+    let row = sqlx::query("SELECT id FROM workflow_templates WHERE name = ? ORDER BY updated_at DESC LIMIT 1")
+        .bind(name.trim())
+        .fetch_optional(&state.db)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no workflow template named {name}")))?;
+    let template_id = Uuid::parse_str(row.get::<String, _>("id").as_str()).map_err(internal)?;
+    let request = CreateRunRequest {
+        template_id: Some(template_id),
+        title: req.title.unwrap_or_else(|| name.clone()),
+        repo_ref,
+        workflow_key: None,
+        definition: None,
+        context: json!({}),
+    };
+    super::runs::create_run(State(state), Json(request)).await
+}
+
+/// Repo-relative forms of `files`; anything that resolves outside the repository is refused.
+fn export_files(repo_ref: &str, files: &[String]) -> Result<Vec<String>, AppError> {
+    let mut out = Vec::with_capacity(files.len());
+    for file in files {
+        let file = file.replace('\\', "/");
+        let file = file.strip_prefix(&format!("{repo_ref}/")).unwrap_or(&file).trim_start_matches('/');
+        if file.is_empty() {
+            continue;
+        }
+        resolve_workspace_path(repo_ref, file).map_err(|err| (StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+        out.push(file.to_string());
+    }
+    Ok(out)
+}
+
+fn existing_repo(repo_ref: &str) -> Result<String, AppError> {
+    let path = repo_ref.trim().trim_end_matches(['/', '\\']).replace('\\', "/");
+    if path.is_empty() || !FsPath::new(&path).is_dir() {
+        return Err((StatusCode::BAD_REQUEST, format!("not a directory: {repo_ref}")).into());
+    }
+    Ok(path)
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_files_stay_inside_the_repo() {
+        let repo = std::env::temp_dir().join(format!("automation-export-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let repo_ref = repo.to_string_lossy().replace('\\', "/");
+
+        let files = export_files(&repo_ref, &[format!("{repo_ref}/src/lib.rs"), "/README.md".to_string()]).unwrap();
+        assert_eq!(files, ["src/lib.rs", "README.md"]);
+
+        for escape in ["../secret.txt", "src/../../secret.txt"] {
+            let err = export_files(&repo_ref, &[escape.to_string()]).unwrap_err();
+            assert_eq!(err.status, StatusCode::BAD_REQUEST, "{escape}");
+        }
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[tokio::test]
+    async fn export_refs_are_resolved_before_use() {
+        let repo = std::env::temp_dir().join(format!("automation-export-ref-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        crate::engine::capabilities::git::git::run_git(&repo, &["init", "-q"]).unwrap();
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        let settings = json!({ "automation": { "enabled": true, "token": "s3cret" } });
+        let state = AppState::new(crate::db::test_db_with_settings(settings).await);
+        let mut headers = HeaderMap::new();
+        headers.insert(axum::http::header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        let request = |git_ref: &str| AutomationContextExportRequest {
+            repo_ref: repo.to_string_lossy().to_string(),
+            files: vec!["a.txt".to_string()],
+            git_ref: Some(git_ref.to_string()),
+            include_unstaged_diff: false,
+        };
+
+        for bad in ["--output=/tmp/x", "no-such-branch"] {
+            let err = context_export(State(state.clone()), headers.clone(), Json(request(bad))).await.unwrap_err();
+            assert_eq!(err.status, StatusCode::BAD_REQUEST, "{bad}");
+        }
+        let Json(export) = context_export(State(state), headers, Json(request("WORKTREE"))).await.unwrap();
+        assert!(export["text"].as_str().unwrap().contains("a.txt"));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -1,13 +1,24 @@
-use axum::{extract::{Path, State}, routing::{get, post}, Json, Router};
+use axum::{extract::{Path, Query, State}, routing::{get, post}, Json, Router};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::{
+    app_error::AppError,
     app_state::AppState,
-    engine::capabilities::{
-        changeset::schema::CHANGESET_SCHEMA_EXAMPLE,
-        registry::{stage_capability_policy, CapabilityContext, CapabilityInvocation, execute_capability_invocations},
+    engine::{
+        capabilities::{
+            changeset::schema::CHANGESET_SCHEMA_EXAMPLE,
+            context_export::{
+                check_context_drift, normalize_context_export_payload, parse_context_export_payload, preview_excludes, ContextDriftReport,
+                ContextExportFormat, ContextExportProgress, ExcludePreview,
+            },
+            inference::scheduler::load_inference_limits,
+            registry::{stage_capability_policy, CapabilityContext, CapabilityInvocation, execute_capability_invocations},
+            trace::{self, CapabilityTrace},
+        },
+        export_destinations::{self, ExportDestination},
+        save_hooks::SaveHookRun,
     },
 };
 
@@ -36,9 +47,50 @@ struct ExecuteWorkflowCapabilityRequest {
     config: Option<Value>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ContextDriftRequest {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    repo_ref: Option<String>,
+    #[serde(default)]
+    git_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SaveHookRunsQuery {
+    repo_ref: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapabilityTracesQuery {
+    #[serde(default)]
+    run_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExcludePreviewRequest {
+    repo_ref: String,
+    #[serde(default)]
+    git_ref: Option<String>,
+    #[serde(default)]
+    patterns: Vec<String>,
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/capabilities/changeset-schema", get(get_changeset_schema))
+        .route("/api/capabilities/inference/queue", get(get_inference_queue))
+        .route("/api/capabilities/context-export/drift", post(check_context_export_drift))
+        .route("/api/capabilities/context-export/exclude-preview", post(preview_context_export_excludes))
+        .route("/api/capabilities/context-export/jobs", post(start_context_export_job))
+        .route("/api/capabilities/context-export/destinations", get(list_export_destinations))
+        .route("/api/capabilities/save-hooks/runs", get(list_save_hook_runs))
+        .route("/api/capabilities/context-export/jobs/:id", get(get_context_export_job).delete(cancel_context_export_job))
+        .route("/api/capabilities/traces", get(list_capability_traces).delete(clear_capability_traces))
+        .route("/api/capabilities/traces/:id/replay", post(replay_capability_trace))
         .route("/api/workflow-runs/:run_id/capabilities", get(list_workflow_capabilities))
         .route("/api/workflow-runs/:run_id/capabilities/:capability_id/execute", post(execute_workflow_capability))
 }
@@ -50,10 +102,89 @@ async fn get_changeset_schema() -> Json<serde_json::Value> {
     }))
 }
 
+async fn get_inference_queue(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let limits = load_inference_limits(&state.db).await;
+    Json(json!({
+        "ok": true,
+        "limits": limits,
+        "queue": state.inference_scheduler().snapshot(),
+    }))
+}
+
+async fn check_context_export_drift(
+    Json(req): Json<ContextDriftRequest>,
+) -> Result<Json<ContextDriftReport>, AppError> {
+    let text = match (req.text, req.path) {
+        (Some(text), _) if !text.trim().is_empty() => text,
+        (_, Some(path)) if !path.trim().is_empty() => std::fs::read_to_string(path.trim()).map_err(bad_request)?,
+        _ => return Err(bad_request("path or text is required")),
+    };
+    let report = tokio::task::spawn_blocking(move || check_context_drift(&text, req.repo_ref.as_deref(), req.git_ref.as_deref()))
+        .await
+        .map_err(internal)?
+        .map_err(bad_request)?;
+    Ok(Json(report))
+}
+
+/// Takes the same payload as the context_export capability; missing fields get its defaults.
+async fn start_context_export_job(
+    State(state): State<AppState>,
+    Json(payload): Json<Value>,
+) -> Result<Json<ContextExportProgress>, AppError> {
+    let repo_ref = payload.get("repo_ref").and_then(Value::as_str).unwrap_or_default().trim().to_string();
+    if repo_ref.is_empty() {
+        return Err(bad_request("repo_ref is required"));
+    }
+    let req = parse_context_export_payload(normalize_context_export_payload(payload, None, &repo_ref)).map_err(bad_request)?;
+    let exporter = format!("context_export.{}", if req.format == ContextExportFormat::Zip { "zip" } else { "text" });
+    export_destinations::remember(&state.db, &exporter, req.save_path.trim(), req.if_exists.as_str())
+        .await
+        .map_err(internal)?;
+    let id = state.context_exports().start(req);
+    state.context_exports().get(id).map(Json).ok_or_else(|| internal("export job vanished"))
+}
+
+/// The save path and overwrite choice each exporter last ran with.
+async fn list_export_destinations(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ExportDestination>>, AppError> {
+    export_destinations::list(&state.db).await.map(Json).map_err(internal)
+}
+
+/// Recent on-save commands for a repo, newest first, including ones still running.
+async fn list_save_hook_runs(State(state): State<AppState>, Query(query): Query<SaveHookRunsQuery>) -> Json<Vec<SaveHookRun>> {
+    Json(state.save_hooks().runs(query.repo_ref.trim()))
+}
+
+async fn get_context_export_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ContextExportProgress>, AppError> {
+    state.context_exports().get(id).map(Json).ok_or_else(|| not_found(format!("no export job {id}")))
+}
+
+async fn cancel_context_export_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ContextExportProgress>, AppError> {
+    state.context_exports().cancel(id).map(Json).ok_or_else(|| not_found(format!("no export job {id}")))
+}
+
+async fn preview_context_export_excludes(
+    Json(req): Json<ExcludePreviewRequest>,
+) -> Result<Json<ExcludePreview>, AppError> {
+    let git_ref = req.git_ref.unwrap_or_else(|| "WORKTREE".to_string());
+    let preview = tokio::task::spawn_blocking(move || preview_excludes(&req.repo_ref, &git_ref, &req.patterns))
+        .await
+        .map_err(internal)?
+        .map_err(bad_request)?;
+    Ok(Json(preview))
+}
+
 async fn list_workflow_capabilities(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
-) -> Result<Json<WorkflowCapabilityListResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<WorkflowCapabilityListResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     let policy = stage_capability_policy(&scope.step).map_err(internal)?;
     let capabilities = policy
@@ -78,11 +209,39 @@ async fn execute_workflow_capability(
     State(state): State<AppState>,
     Path((run_id, capability_id)): Path<(Uuid, String)>,
     Json(req): Json<ExecuteWorkflowCapabilityRequest>,
-) -> Result<Json<Value>, (axum::http::StatusCode, String)> {
-    let scope = resolve_workflow_scope(&state, run_id).await?;
+) -> Result<Json<Value>, AppError> {
     let config = req.config.or(req.input).unwrap_or_else(|| json!({}));
+    run_workflow_capability(&state, run_id, capability_id, config).await.map(Json)
+}
+
+/// Recent capability dispatches, newest first.
+async fn list_capability_traces(Query(query): Query<CapabilityTracesQuery>) -> Json<Vec<CapabilityTrace>> {
+    Json(trace::list(query.run_id))
+}
+
+async fn clear_capability_traces() -> Json<Value> {
+    trace::clear();
+    Json(json!({ "ok": true }))
+}
+
+/// Runs a traced capability again with the config it was recorded with, against the run's current stage.
+async fn replay_capability_trace(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<Value>, AppError> {
+    let recorded = trace::get(id).ok_or_else(|| not_found(format!("no capability trace {id}")))?;
+    run_workflow_capability(&state, recorded.run_id, recorded.capability, recorded.request).await.map(Json)
+}
+
+async fn run_workflow_capability(
+    state: &AppState,
+    run_id: Uuid,
+    capability_id: String,
+    config: Value,
+) -> Result<Value, AppError> {
+    let scope = resolve_workflow_scope(state, run_id).await?;
     let ctx = CapabilityContext {
-        state: &state,
+        state,
         run_id: scope.run_id,
         repo_ref: scope.repo_ref.as_str(),
         step: &scope.step,
@@ -92,7 +251,7 @@ async fn execute_workflow_capability(
         .await
         .map_err(internal)?;
 
-    Ok(Json(json!({
+    Ok(json!({
         "ok": results.iter().all(|item| item.ok),
         "run_id": scope.run_id,
         "step_id": scope.step.id,
@@ -102,9 +261,17 @@ async fn execute_workflow_capability(
             "capability": item.capability,
             "payload": item.payload
         })).collect::<Vec<_>>()
-    })))
+    }))
+}
+
+fn bad_request<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, err.to_string()).into()
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn not_found<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::NOT_FOUND, err.to_string()).into()
 }
@@ -5,8 +5,17 @@ use sqlx::{Row, SqlitePool};
 use uuid::Uuid;
 
 use crate::{
+    app_error::AppError,
     app_state::AppState,
-    engine::capabilities::changeset::{self, ChangesetRequest},
+    engine::{
+        capabilities::changeset::{
+            self,
+            ingest::{ingest_pasted_changeset, ChangesetIngestReport},
+            lint::{lint_changeset_text, ChangesetLintReport},
+            ChangesetRequest,
+        },
+        save_hooks,
+    },
 };
 
 use super::workflow_scope::{resolve_workflow_scope, WorkflowScope};
@@ -17,11 +26,22 @@ struct ListChangesetsQuery {
     limit: i64,
 }
 
+#[derive(Debug, Deserialize)]
+struct LintChangesetRequest {
+    payload_text: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct ApplyChangesetRequest {
     #[serde(default = "default_git_ref")]
     git_ref: String,
     payload_text: String,
+    #[serde(default)]
+    stage_after_apply: bool,
+    #[serde(default)]
+    commit_after_apply: bool,
+    #[serde(default)]
+    commit_message: Option<String>,
 }
 
 fn default_git_ref() -> String {
@@ -36,16 +56,23 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/workflow-runs/:run_id/changesets", get(list_changesets))
         .route("/api/workflow-runs/:run_id/changesets/apply", post(apply_changeset))
+        .route("/api/workflow-runs/:run_id/changesets/lint", post(lint_changeset))
+        .route("/api/changesets/ingest", post(ingest_changeset))
         .route("/api/workflows/:workflow_key/changesets", get(list_workflow_changesets))
         .route("/api/workflows/:workflow_key/changesets/:attempt_id", get(get_workflow_changeset))
         .route("/api/workflows/:workflow_key/changesets/apply", post(apply_workflow_changeset))
+        .route("/api/workflows/:workflow_key/changesets/lint", post(lint_workflow_changeset))
+}
+
+async fn ingest_changeset(Json(req): Json<LintChangesetRequest>) -> Json<ChangesetIngestReport> {
+    Json(ingest_pasted_changeset(&req.payload_text))
 }
 
 async fn list_changesets(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
     Query(query): Query<ListChangesetsQuery>,
-) -> Result<Json<Vec<Value>>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Vec<Value>>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     let workflow_key = workflow_key_for_scope(&state, &scope).await?;
     let items = list_changesets_with_attempted_files(&state.db, &workflow_key, query.limit)
@@ -58,7 +85,7 @@ async fn list_workflow_changesets(
     State(state): State<AppState>,
     Path(workflow_key): Path<String>,
     Query(query): Query<ListChangesetsQuery>,
-) -> Result<Json<Vec<Value>>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Vec<Value>>, AppError> {
     let items = list_changesets_with_attempted_files(&state.db, &workflow_key, query.limit)
         .await
         .map_err(internal)?;
@@ -209,7 +236,7 @@ fn push_unique_file(files: &mut Vec<String>, path: &str) {
 async fn get_workflow_changeset(
     State(state): State<AppState>,
     Path((workflow_key, attempt_id)): Path<(String, String)>,
-) -> Result<Json<Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Value>, AppError> {
     let row = sqlx::query(
         r#"
         SELECT
@@ -232,7 +259,7 @@ async fn get_workflow_changeset(
     .map_err(internal)?;
 
     let Some(row) = row else {
-        return Err((axum::http::StatusCode::NOT_FOUND, format!("changeset attempt not found: {attempt_id}")));
+        return Err((axum::http::StatusCode::NOT_FOUND, format!("changeset attempt not found: {attempt_id}")).into());
     };
 
     let record = changeset::ChangesetAttemptRecord::from_row(row);
@@ -253,7 +280,7 @@ async fn get_workflow_changeset(
 async fn resolve_workflow_scope_by_key(
     state: &AppState,
     workflow_key: &str,
-) -> Result<WorkflowScope, (axum::http::StatusCode, String)> {
+) -> Result<WorkflowScope, AppError> {
     let row = sqlx::query(
         r#"
         SELECT id
@@ -269,7 +296,7 @@ async fn resolve_workflow_scope_by_key(
     .map_err(internal)?;
 
     let Some(row) = row else {
-        return Err((axum::http::StatusCode::NOT_FOUND, format!("workflow not found: {workflow_key}")));
+        return Err((axum::http::StatusCode::NOT_FOUND, format!("workflow not found: {workflow_key}")).into());
     };
 
     let run_id_text: String = row.get("id");
@@ -282,7 +309,7 @@ async fn resolve_workflow_scope_by_key(
 async fn workflow_key_for_scope(
     state: &AppState,
     scope: &WorkflowScope,
-) -> Result<String, (axum::http::StatusCode, String)> {
+) -> Result<String, AppError> {
     let row = sqlx::query("SELECT workflow_key FROM workflow_runs WHERE id = ?")
         .bind(scope.run_id.to_string())
         .fetch_optional(&state.db)
@@ -294,7 +321,7 @@ async fn workflow_key_for_scope(
         .unwrap_or_default();
 
     if key.trim().is_empty() {
-        Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, "workflow_key is missing for run".to_string()))
+        Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, "workflow_key is missing for run".to_string()).into())
     } else {
         Ok(key)
     }
@@ -304,7 +331,7 @@ async fn apply_changeset(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
     Json(req): Json<ApplyChangesetRequest>,
-) -> Result<Json<Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Value>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     let workflow_key = workflow_key_for_scope(&state, &scope).await?;
     let git_ref = if req.git_ref.trim().is_empty() {
@@ -323,10 +350,14 @@ async fn apply_changeset(
             workflow_key: Some(workflow_key),
             run_id: Some(scope.run_id.to_string()),
             step_id: Some(scope.step.id.clone()),
+            stage_after_apply: req.stage_after_apply,
+            commit_after_apply: req.commit_after_apply,
+            commit_message: req.commit_message,
         },
     )
     .await
     .map_err(internal)?;
+    save_hooks::files_saved(&state, &scope.repo_ref, &touched_files(&result)).await;
 
     Ok(Json(result))
 }
@@ -335,7 +366,7 @@ async fn apply_workflow_changeset(
     State(state): State<AppState>,
     Path(workflow_key): Path<String>,
     Json(req): Json<ApplyChangesetRequest>,
-) -> Result<Json<Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Value>, AppError> {
     let scope = resolve_workflow_scope_by_key(&state, &workflow_key).await?;
     let git_ref = if req.git_ref.trim().is_empty() {
         scope.git_ref.clone()
@@ -353,14 +384,57 @@ async fn apply_workflow_changeset(
             workflow_key: Some(workflow_key),
             run_id: Some(scope.run_id.to_string()),
             step_id: Some(scope.step.id.clone()),
+            stage_after_apply: req.stage_after_apply,
+            commit_after_apply: req.commit_after_apply,
+            commit_message: req.commit_message,
         },
     )
     .await
     .map_err(internal)?;
+    save_hooks::files_saved(&state, &scope.repo_ref, &touched_files(&result)).await;
 
     Ok(Json(result))
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+async fn lint_changeset(
+    State(state): State<AppState>,
+    Path(run_id): Path<Uuid>,
+    Json(req): Json<LintChangesetRequest>,
+) -> Result<Json<ChangesetLintReport>, AppError> {
+    let scope = resolve_workflow_scope(&state, run_id).await?;
+    lint_for_scope(&scope, &req.payload_text)
+}
+
+async fn lint_workflow_changeset(
+    State(state): State<AppState>,
+    Path(workflow_key): Path<String>,
+    Json(req): Json<LintChangesetRequest>,
+) -> Result<Json<ChangesetLintReport>, AppError> {
+    let scope = resolve_workflow_scope_by_key(&state, &workflow_key).await?;
+    lint_for_scope(&scope, &req.payload_text)
+}
+
+/// Paths a clean apply wrote; a failed apply runs no save hooks.
+fn touched_files(result: &Value) -> Vec<String> {
+    if !result.get("ok").and_then(Value::as_bool).unwrap_or(false) {
+        return Vec::new();
+    }
+    result
+        .get("touched_files")
+        .and_then(Value::as_array)
+        .map(|files| files.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn lint_for_scope(
+    scope: &WorkflowScope,
+    payload_text: &str,
+) -> Result<Json<ChangesetLintReport>, AppError> {
+    let report = lint_changeset_text(std::path::Path::new(&scope.repo_ref), payload_text)
+        .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{:#}", err)))?;
+    Ok(Json(report))
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
@@ -0,0 +1,129 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::code_review::{self, CodeReview, NewReviewComment},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/code-reviews", get(list_reviews).post(open_review))
+        .route("/api/code-reviews/:id", get(get_review).delete(delete_review))
+        .route("/api/code-reviews/:id/viewed", post(set_viewed))
+        .route("/api/code-reviews/:id/comments", post(add_comment))
+        .route("/api/code-reviews/:id/comments/:comment_id", delete(delete_comment))
+        .route("/api/code-reviews/:id/export", get(export_review))
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    repo_ref: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenReviewRequest {
+    repo_ref: String,
+    base: String,
+    head: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ViewedRequest {
+    path: String,
+    viewed: bool,
+}
+
+async fn list_reviews(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<CodeReview>>, AppError> {
+    Ok(Json(code_review::list(&state.db, query.repo_ref.trim()).await.map_err(internal)?))
+}
+
+async fn open_review(
+    State(state): State<AppState>,
+    Json(req): Json<OpenReviewRequest>,
+) -> Result<Json<CodeReview>, AppError> {
+    code_review::open(&state.db, req.repo_ref.trim(), &req.base, &req.head)
+        .await
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn get_review(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<CodeReview>, AppError> {
+    code_review::load(&state.db, &id).await.map(Json).map_err(not_found)
+}
+
+async fn delete_review(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, AppError> {
+    code_review::delete(&state.db, &id).await.map_err(internal)?;
+    Ok(Json(json!({ "ok": true })))
+}
+
+async fn set_viewed(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<ViewedRequest>,
+) -> Result<Json<CodeReview>, AppError> {
+    code_review::set_viewed(&state.db, &id, &req.path, req.viewed)
+        .await
+        .map(Json)
+        .map_err(not_found)
+}
+
+async fn add_comment(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<NewReviewComment>,
+) -> Result<Json<CodeReview>, AppError> {
+    code_review::add_comment(&state.db, &id, req).await.map(Json).map_err(bad_request)
+}
+
+async fn delete_comment(
+    State(state): State<AppState>,
+    Path((id, comment_id)): Path<(String, String)>,
+) -> Result<Json<CodeReview>, AppError> {
+    code_review::delete_comment(&state.db, &id, &comment_id)
+        .await
+        .map(Json)
+        .map_err(not_found)
+}
+
+async fn export_review(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, AppError> {
+    let review = code_review::load(&state.db, &id).await.map_err(not_found)?;
+    let files = {
+        let review = review.clone();
+        tokio::task::spawn_blocking(move || code_review::changed_files(&review))
+            .await
+            .map_err(internal)?
+            .map_err(bad_request)?
+    };
+    Ok(Json(json!({ "ok": true, "markdown": code_review::export_markdown(&review, &files) })))
+}
+
+fn bad_request<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")).into()
+}
+
+fn not_found<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::NOT_FOUND, format!("{err:#}")).into()
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
@@ -14,6 +14,7 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use uuid::Uuid;
 
 use crate::{
+    app_error::AppError,
     app_state::AppState,
     engine::{load_run, load_template_definition},
     models::WorkflowEventStreamItem,
@@ -83,14 +84,14 @@ pub fn router() -> Router<AppState> {
 async fn get_event_chain_summary(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
-) -> Result<Json<EventChainSummaryResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<EventChainSummaryResponse>, AppError> {
     Ok(Json(build_event_chain_summary(&state, run_id).await?))
 }
 
 async fn build_event_chain_summary(
     state: &AppState,
     run_id: Uuid,
-) -> Result<EventChainSummaryResponse, (axum::http::StatusCode, String)> {
+) -> Result<EventChainSummaryResponse, AppError> {
     let run = load_run(state, run_id).await.map_err(internal)?;
     let definition = load_template_definition(state, &run).await.map_err(internal)?;
     let order = definition
@@ -251,7 +252,7 @@ async fn build_event_chain_summary(
 async fn get_stage_execution_chain(
     State(state): State<AppState>,
     Path((run_id, step_id, stage_execution_id)): Path<(Uuid, String, String)>,
-) -> Result<Json<StageExecutionChain>, (axum::http::StatusCode, String)> {
+) -> Result<Json<StageExecutionChain>, AppError> {
     let rows = sqlx::query(
         "SELECT id, run_id, step_id, stage_execution_id, capability_invocation_id, parent_invocation_id, sequence_no, level, kind, message, payload_json, created_at FROM workflow_events WHERE run_id = ? AND step_id = ? AND stage_execution_id = ? ORDER BY sequence_no ASC"
     )
@@ -389,7 +390,7 @@ fn monitor_snapshot_sse(summary: &EventChainSummaryResponse) -> Option<Event> {
         .map(|text| Event::default().event("monitor_snapshot").data(text))
 }
 
-fn row_to_stage_chain_event(row: sqlx::sqlite::SqliteRow) -> Result<StageChainEvent, (axum::http::StatusCode, String)> {
+fn row_to_stage_chain_event(row: sqlx::sqlite::SqliteRow) -> Result<StageChainEvent, AppError> {
     Ok(StageChainEvent {
         id: row.get("id"),
         run_id: row.get("run_id"),
@@ -406,6 +407,6 @@ fn row_to_stage_chain_event(row: sqlx::sqlite::SqliteRow) -> Result<StageChainEv
     })
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
@@ -2,8 +2,12 @@ use axum::{extract::{Path, Query, State}, routing::{delete, get, post, put}, Jso
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    app_error::AppError,
     app_state::AppState,
-    engine::capabilities::filesystem,
+    engine::{
+        capabilities::{filesystem, git::git::effective_read_ref},
+        editor_state, history, save_hooks,
+    },
 };
 
 use super::workflow_scope::resolve_workflow_scope;
@@ -14,6 +18,26 @@ struct FileQuery {
     path: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ReadFileQuery {
+    repo_ref: String,
+    path: String,
+    #[serde(default)]
+    git_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EditorStateBody {
+    repo_ref: String,
+    path: String,
+    state: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct EditorStateResponse {
+    state: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Deserialize)]
 struct WriteFileBody {
     repo_ref: String,
@@ -24,6 +48,8 @@ struct WriteFileBody {
 #[derive(Debug, Deserialize)]
 struct WorkflowFileQuery {
     path: String,
+    #[serde(default)]
+    git_ref: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,6 +58,32 @@ struct WorkflowWriteFileBody {
     contents: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct WriteManyBody {
+    #[serde(default)]
+    repo_ref: String,
+    files: Vec<filesystem::BatchWrite>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadManyBody {
+    #[serde(default)]
+    repo_ref: String,
+    paths: Vec<String>,
+    #[serde(default)]
+    git_ref: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchResponse {
+    ok: bool,
+    repo_ref: String,
+    succeeded: usize,
+    failed: usize,
+    duration_ms: u64,
+    results: Vec<filesystem::BatchItemResult>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CreateFileBody {
     repo_ref: String,
@@ -51,7 +103,13 @@ struct FileContentsResponse {
     ok: bool,
     repo_ref: String,
     path: String,
+    git_ref: String,
+    commit: Option<String>,
+    read_only: bool,
     contents: String,
+    /// Line endings `.gitattributes` enforces on save, if any.
+    eol_attribute: Option<&'static str>,
+    language_hint: Option<filesystem::LanguageHint>,
 }
 
 #[derive(Debug, Serialize)]
@@ -66,46 +124,86 @@ struct MutatePathResponse {
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/file", get(read_file).put(write_file).post(create_file).delete(delete_file))
+        .route("/api/files/write-many", post(write_many_files))
+        .route("/api/files/read-many", post(read_many_files))
         .route("/api/folder", post(create_folder))
+        .route("/api/editor-state", get(get_editor_state).put(put_editor_state))
         .route("/api/workflow-runs/:run_id/filesystem/read", get(read_workflow_file))
         .route("/api/workflow-runs/:run_id/filesystem/write", post(write_workflow_file))
+        .route("/api/workflow-runs/:run_id/filesystem/write-many", post(write_many_workflow_files))
+        .route("/api/workflow-runs/:run_id/filesystem/read-many", post(read_many_workflow_files))
 }
 
 async fn read_file(
+    Query(query): Query<ReadFileQuery>,
+) -> Result<Json<FileContentsResponse>, AppError> {
+    read_file_response(query.repo_ref, &query.path, query.git_ref.as_deref())
+}
+
+async fn get_editor_state(
+    State(state): State<AppState>,
     Query(query): Query<FileQuery>,
-) -> Result<Json<FileContentsResponse>, (axum::http::StatusCode, String)> {
-    let normalized = filesystem::normalize_rel_path(&query.path).map_err(internal)?;
-    let contents = filesystem::read_text_file(&query.repo_ref, &normalized).map_err(internal)?;
-    Ok(Json(FileContentsResponse {
-        ok: true,
-        repo_ref: query.repo_ref,
-        path: normalized,
-        contents,
-    }))
+) -> Result<Json<EditorStateResponse>, AppError> {
+    let path = filesystem::normalize_rel_path(&query.path).map_err(internal)?;
+    let saved = editor_state::load(&state.db, query.repo_ref.trim(), &path).await.map_err(internal)?;
+    Ok(Json(EditorStateResponse { state: saved }))
+}
+
+async fn put_editor_state(
+    State(state): State<AppState>,
+    Json(body): Json<EditorStateBody>,
+) -> Result<Json<EditorStateResponse>, AppError> {
+    let path = filesystem::normalize_rel_path(&body.path).map_err(internal)?;
+    editor_state::save(&state.db, body.repo_ref.trim(), &path, &body.state)
+        .await
+        .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, err.to_string()))?;
+    Ok(Json(EditorStateResponse { state: Some(body.state) }))
 }
 
 async fn read_workflow_file(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Query(query): Query<WorkflowFileQuery>,
-) -> Result<Json<FileContentsResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<FileContentsResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
-    let normalized = filesystem::normalize_rel_path(&query.path).map_err(internal)?;
-    let contents = filesystem::read_text_file(&scope.repo_ref, &normalized).map_err(internal)?;
+    read_file_response(scope.repo_ref, &query.path, query.git_ref.as_deref())
+}
+
+fn read_file_response(
+    repo_ref: String,
+    path: &str,
+    git_ref: Option<&str>,
+) -> Result<Json<FileContentsResponse>, AppError> {
+    let normalized = filesystem::normalize_rel_path(path).map_err(internal)?;
+    let git_ref = effective_read_ref(std::path::Path::new(repo_ref.trim()), git_ref.unwrap_or_default());
+    let contents = filesystem::read_text_file_at_ref(&repo_ref, &normalized, &git_ref).map_err(internal)?;
+    let commit = filesystem::resolve_ref_commit(&repo_ref, &git_ref).map_err(internal)?;
+    let eol_attribute = filesystem::eol_attribute(&repo_ref, &normalized);
+    let language_hint = filesystem::language_hint_for_path(&normalized);
     Ok(Json(FileContentsResponse {
         ok: true,
-        repo_ref: scope.repo_ref,
+        repo_ref,
         path: normalized,
+        read_only: git_ref != "WORKTREE",
+        git_ref,
+        commit,
         contents,
+        eol_attribute,
+        language_hint,
     }))
 }
 
 async fn write_file(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(body): Json<WriteFileBody>,
-) -> Result<Json<MutatePathResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<MutatePathResponse>, AppError> {
     let normalized = filesystem::normalize_rel_path(&body.path).map_err(internal)?;
-    let stat = filesystem::write_text_file(&body.repo_ref, &normalized, &body.contents).map_err(internal)?;
+    let contents = match filesystem::eol_attribute(&body.repo_ref, &normalized) {
+        Some(eol) => filesystem::convert_line_endings(&body.contents, eol),
+        None => body.contents,
+    };
+    let stat = filesystem::write_text_file(&body.repo_ref, &normalized, &contents).map_err(internal)?;
+    save_hooks::files_saved(&state, &body.repo_ref, std::slice::from_ref(&stat.path)).await;
     Ok(Json(MutatePathResponse {
         ok: true,
         repo_ref: body.repo_ref,
@@ -119,10 +217,11 @@ async fn write_workflow_file(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Json(body): Json<WorkflowWriteFileBody>,
-) -> Result<Json<MutatePathResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<MutatePathResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     let normalized = filesystem::normalize_rel_path(&body.path).map_err(internal)?;
     let stat = filesystem::write_text_file(&scope.repo_ref, &normalized, &body.contents).map_err(internal)?;
+    save_hooks::files_saved(&state, &scope.repo_ref, std::slice::from_ref(&stat.path)).await;
     Ok(Json(MutatePathResponse {
         ok: true,
         repo_ref: scope.repo_ref,
@@ -132,10 +231,98 @@ async fn write_workflow_file(
     }))
 }
 
+async fn write_many_files(
+    State(state): State<AppState>,
+    Json(body): Json<WriteManyBody>,
+) -> Result<Json<BatchResponse>, AppError> {
+    write_many_response(&state, body.repo_ref, body.files).await
+}
+
+async fn write_many_workflow_files(
+    State(state): State<AppState>,
+    Path(run_id): Path<uuid::Uuid>,
+    Json(body): Json<WriteManyBody>,
+) -> Result<Json<BatchResponse>, AppError> {
+    let scope = resolve_workflow_scope(&state, run_id).await?;
+    write_many_response(&state, scope.repo_ref, body.files).await
+}
+
+async fn read_many_files(Json(body): Json<ReadManyBody>) -> Result<Json<BatchResponse>, AppError> {
+    read_many_response(body.repo_ref, body.paths, body.git_ref).await
+}
+
+async fn read_many_workflow_files(
+    State(state): State<AppState>,
+    Path(run_id): Path<uuid::Uuid>,
+    Json(body): Json<ReadManyBody>,
+) -> Result<Json<BatchResponse>, AppError> {
+    let scope = resolve_workflow_scope(&state, run_id).await?;
+    read_many_response(scope.repo_ref, body.paths, body.git_ref).await
+}
+
+/// One request for many files: paths are all validated first, line endings follow `.gitattributes` like a
+/// single save, and save hooks fire once for everything written.
+async fn write_many_response(
+    state: &AppState,
+    repo_ref: String,
+    mut files: Vec<filesystem::BatchWrite>,
+) -> Result<Json<BatchResponse>, AppError> {
+    let started = std::time::Instant::now();
+    let blocking_repo = repo_ref.clone();
+    let results = tokio::task::spawn_blocking(move || {
+        for file in &mut files {
+            if let Some(eol) = filesystem::eol_attribute(&blocking_repo, &file.path) {
+                file.contents = filesystem::convert_line_endings(&file.contents, eol);
+            }
+        }
+        filesystem::write_many(&blocking_repo, &files, |progress| {
+            tracing::debug!(done = progress.done, total = progress.total, path = progress.path, "write-many");
+        })
+    })
+    .await
+    .map_err(internal)?
+    .map_err(bad_request)?;
+    let written = results.iter().filter(|item| item.ok).map(|item| item.path.clone()).collect::<Vec<_>>();
+    if !written.is_empty() {
+        save_hooks::files_saved(state, &repo_ref, &written).await;
+    }
+    Ok(Json(batch_response(repo_ref, results, started)))
+}
+
+async fn read_many_response(
+    repo_ref: String,
+    paths: Vec<String>,
+    git_ref: Option<String>,
+) -> Result<Json<BatchResponse>, AppError> {
+    let started = std::time::Instant::now();
+    let blocking_repo = repo_ref.clone();
+    let results = tokio::task::spawn_blocking(move || {
+        filesystem::read_many(&blocking_repo, &paths, git_ref.as_deref().unwrap_or_default(), |progress| {
+            tracing::debug!(done = progress.done, total = progress.total, path = progress.path, "read-many");
+        })
+    })
+    .await
+    .map_err(internal)?
+    .map_err(bad_request)?;
+    Ok(Json(batch_response(repo_ref, results, started)))
+}
+
+fn batch_response(repo_ref: String, results: Vec<filesystem::BatchItemResult>, started: std::time::Instant) -> BatchResponse {
+    let succeeded = results.iter().filter(|item| item.ok).count();
+    BatchResponse {
+        ok: succeeded == results.len(),
+        repo_ref,
+        succeeded,
+        failed: results.len() - succeeded,
+        duration_ms: started.elapsed().as_millis() as u64,
+        results,
+    }
+}
+
 async fn create_file(
     State(_state): State<AppState>,
     Json(body): Json<CreateFileBody>,
-) -> Result<Json<MutatePathResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<MutatePathResponse>, AppError> {
     let normalized = filesystem::normalize_rel_path(&body.path).map_err(internal)?;
     let stat = filesystem::create_file(&body.repo_ref, &normalized, &body.contents).map_err(internal)?;
     Ok(Json(MutatePathResponse {
@@ -150,7 +337,7 @@ async fn create_file(
 async fn create_folder(
     State(_state): State<AppState>,
     Json(body): Json<CreateFolderBody>,
-) -> Result<Json<MutatePathResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<MutatePathResponse>, AppError> {
     let normalized = filesystem::normalize_rel_path(&body.path).map_err(internal)?;
     let stat = filesystem::create_dir(&body.repo_ref, &normalized).map_err(internal)?;
     Ok(Json(MutatePathResponse {
@@ -163,10 +350,19 @@ async fn create_folder(
 }
 
 async fn delete_file(
+    State(state): State<AppState>,
     Query(query): Query<FileQuery>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let normalized = filesystem::normalize_rel_path(&query.path).map_err(internal)?;
+    if normalized.is_empty() {
+        return Err(bad_request("refusing to delete workspace root"));
+    }
+    let snapshot = history::snapshot_paths(std::path::Path::new(&query.repo_ref), std::slice::from_ref(&normalized)).map_err(internal)?;
     filesystem::delete_path(&query.repo_ref, &normalized).map_err(internal)?;
+    let label = format!("Delete {}", normalized);
+    history::record_files(&state.db, "delete_path", &label, &query.repo_ref, snapshot)
+        .await
+        .map_err(internal)?;
     Ok(Json(serde_json::json!({
         "ok": true,
         "repo_ref": query.repo_ref,
@@ -174,6 +370,10 @@ async fn delete_file(
     })))
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn bad_request<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, err.to_string()).into()
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
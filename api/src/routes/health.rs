@@ -1,10 +1,61 @@
-use axum::{routing::get, Json, Router};
+use axum::{extract::State, routing::get, Json, Router};
+use chrono::Utc;
 use serde_json::json;
+use sqlx::Row;
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::doctor::{run_checks, DoctorCheck},
+    metrics,
+};
 
 pub fn router() -> Router<crate::app_state::AppState> {
-    Router::new().route("/health", get(health))
+    Router::new()
+        .route("/health", get(health))
+        .route("/api/debug/metrics", get(debug_metrics))
+        .route("/api/doctor", get(doctor))
 }
 
 async fn health() -> Json<serde_json::Value> {
     Json(json!({ "ok": true }))
 }
+
+async fn doctor(State(state): State<AppState>) -> Json<Vec<DoctorCheck>> {
+    Json(run_checks(&state).await)
+}
+
+async fn debug_metrics(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let runs = sqlx::query("SELECT COUNT(*) AS n, COALESCE(SUM(LENGTH(context_json)), 0) AS bytes FROM workflow_runs")
+        .fetch_one(&state.db)
+        .await
+        .map_err(internal)?;
+    let events = sqlx::query("SELECT COUNT(*) AS n, COALESCE(SUM(LENGTH(payload_json)), 0) AS bytes FROM workflow_events")
+        .fetch_one(&state.db)
+        .await
+        .map_err(internal)?;
+    let changesets = sqlx::query("SELECT COUNT(*) AS n, COALESCE(SUM(LENGTH(payload_text)), 0) AS bytes FROM changeset_attempts")
+        .fetch_one(&state.db)
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(json!({
+        "collected_at": Utc::now().to_rfc3339(),
+        "process": {
+            "resident_bytes": metrics::resident_memory_bytes(),
+        },
+        "git": metrics::git_spawn_stats(),
+        "inference_queue": state.inference_scheduler().snapshot(),
+        "buffers": {
+            "run_contexts": { "count": runs.get::<i64, _>("n"), "bytes": runs.get::<i64, _>("bytes") },
+            "event_payloads": { "count": events.get::<i64, _>("n"), "bytes": events.get::<i64, _>("bytes") },
+            "changeset_payloads": { "count": changesets.get::<i64, _>("n"), "bytes": changesets.get::<i64, _>("bytes") },
+        },
+    })))
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
+}
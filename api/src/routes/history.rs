@@ -0,0 +1,64 @@
+use axum::{extract::State, routing::{get, post}, Json, Router};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::history::{self, HistoryEntry, HistorySnapshot, HistoryUndoResult},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/history", get(list_history).post(record_history_state))
+        .route("/api/history/undo", post(undo_history))
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordStateRequest {
+    kind: String,
+    label: String,
+    #[serde(default)]
+    repo_ref: String,
+    state: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct UndoRequest {
+    #[serde(default)]
+    id: Option<String>,
+}
+
+async fn list_history(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<HistoryEntry>>, AppError> {
+    Ok(Json(history::list(&state.db).await.map_err(internal)?))
+}
+
+async fn record_history_state(
+    State(state): State<AppState>,
+    Json(req): Json<RecordStateRequest>,
+) -> Result<Json<Value>, AppError> {
+    if req.kind.trim().is_empty() || req.label.trim().is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "kind and label are required".to_string()).into());
+    }
+    let snapshot = HistorySnapshot::State { state: req.state };
+    let id = history::record(&state.db, req.kind.trim(), req.label.trim(), req.repo_ref.trim(), &snapshot)
+        .await
+        .map_err(internal)?;
+    Ok(Json(serde_json::json!({ "ok": true, "id": id })))
+}
+
+async fn undo_history(
+    State(state): State<AppState>,
+    Json(req): Json<UndoRequest>,
+) -> Result<Json<HistoryUndoResult>, AppError> {
+    history::undo(&state.db, req.id.as_deref())
+        .await
+        .map(Json)
+        .map_err(|err| AppError::from((axum::http::StatusCode::BAD_REQUEST, format!("{:#}", err))))
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
@@ -0,0 +1,72 @@
+use std::{convert::Infallible, path::Path};
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::app_error::AppError;
+use crate::app_state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/instance/open", post(open_repo))
+        .route("/api/instance/open-requests/stream", get(stream_open_requests))
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenRepoRequest {
+    path: String,
+}
+
+/// Called by a second launch (`workflow-api open <path>`) to hand its repository to this instance.
+async fn open_repo(
+    State(state): State<AppState>,
+    Json(req): Json<OpenRepoRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let path = req.path.trim().to_string();
+    if path.is_empty() || !Path::new(&path).is_dir() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, format!("not a directory: {path}")).into());
+    }
+    tracing::info!(path, "open request from another launch");
+    state.request_open(path.clone());
+    Ok(Json(json!({ "ok": true, "path": path })))
+}
+
+async fn stream_open_requests(
+    State(state): State<AppState>,
+) -> Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<Event, Infallible>>();
+    let (pending, mut live_rx) = state.subscribe_open_requests();
+
+    tokio::spawn(async move {
+        if let Some(path) = pending {
+            if tx.send(Ok(open_repo_sse(&path))).is_err() {
+                return;
+            }
+        }
+        loop {
+            match live_rx.recv().await {
+                Ok(path) => {
+                    if tx.send(Ok(open_repo_sse(&path))).is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            }
+        }
+    });
+
+    Sse::new(UnboundedReceiverStream::new(rx)).keep_alive(KeepAlive::default())
+}
+
+fn open_repo_sse(path: &str) -> Event {
+    Event::default().event("open_repo").data(json!({ "path": path }).to_string())
+}
@@ -0,0 +1,40 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde_json::Value;
+
+use super::token_auth::require_token;
+use crate::{app_error::AppError, app_state::AppState, engine::mcp};
+
+/// Streamable-HTTP MCP endpoint (JSON responses only, no server-initiated stream).
+pub fn router() -> Router<AppState> {
+    Router::new().route("/mcp", post(handle_mcp))
+}
+
+async fn handle_mcp(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(message): Json<Value>,
+) -> Result<Response, AppError> {
+    require_token(&state, &headers, "mcp", "MCP access is disabled; enable it under MCP server", "MCP").await?;
+
+    let response = match message {
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for message in batch {
+                responses.extend(mcp::handle_message(&state.db, message).await);
+            }
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        message => mcp::handle_message(&state.db, message).await,
+    };
+    Ok(match response {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    })
+}
+
@@ -1,14 +1,27 @@
+mod ai_actions;
+mod analysis;
+mod automation;
 mod capabilities;
 mod changesets;
+mod code_review;
 mod event_chains;
 mod filesystem;
 mod health;
+mod history;
+mod instance;
+mod mcp;
+mod plugins;
+mod processes;
 mod repo_tree;
 mod runs;
+mod search;
 mod sap;
 mod review;
 mod settings;
 mod templates;
+mod token_auth;
+mod trash;
+mod updates;
 mod workflow_builder;
 mod workflow_scope;
 
@@ -21,6 +34,9 @@ pub fn router() -> Router<crate::app_state::AppState> {
         .merge(repo_tree::router())
         .merge(templates::router())
         .merge(review::router())
+        .merge(code_review::router())
+        .merge(search::router())
+        .merge(ai_actions::router())
         .merge(workflow_builder::router())
         .merge(runs::router())
         .merge(sap::router())
@@ -28,4 +44,13 @@ pub fn router() -> Router<crate::app_state::AppState> {
         .merge(event_chains::router())
         .merge(capabilities::router())
         .merge(changesets::router())
+        .merge(analysis::router())
+        .merge(history::router())
+        .merge(trash::router())
+        .merge(instance::router())
+        .merge(updates::router())
+        .merge(plugins::router())
+        .merge(processes::router())
+        .merge(automation::router())
+        .merge(mcp::router())
 }
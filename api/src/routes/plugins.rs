@@ -0,0 +1,66 @@
+use axum::{extract::State, routing::{get, post}, Json, Router};
+use serde::Deserialize;
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::plugins::{self, PluginCatalog, PluginOutput},
+};
+
+#[derive(Debug, Deserialize)]
+struct InvokePluginRequest {
+    plugin: String,
+    id: String,
+    #[serde(default)]
+    repo_ref: Option<String>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/plugins", get(list_plugins))
+        .route("/api/plugins/commands/run", post(run_command))
+        .route("/api/plugins/panels/render", post(render_panel))
+}
+
+async fn list_plugins(
+    State(state): State<AppState>,
+) -> Result<Json<PluginCatalog>, AppError> {
+    let settings = plugins::load_plugin_settings(&state.db).await.map_err(internal)?;
+    let catalog = tokio::task::spawn_blocking(move || plugins::catalog(&settings))
+        .await
+        .map_err(internal)?
+        .map_err(internal)?;
+    Ok(Json(catalog))
+}
+
+async fn run_command(
+    State(state): State<AppState>,
+    Json(req): Json<InvokePluginRequest>,
+) -> Result<Json<PluginOutput>, AppError> {
+    let settings = plugins::load_plugin_settings(&state.db).await.map_err(internal)?;
+    let output = tokio::task::spawn_blocking(move || plugins::run_command(&settings, &req.plugin, &req.id, req.repo_ref.as_deref()))
+        .await
+        .map_err(internal)?
+        .map_err(bad_request)?;
+    Ok(Json(output))
+}
+
+async fn render_panel(
+    State(state): State<AppState>,
+    Json(req): Json<InvokePluginRequest>,
+) -> Result<Json<PluginOutput>, AppError> {
+    let settings = plugins::load_plugin_settings(&state.db).await.map_err(internal)?;
+    let output = tokio::task::spawn_blocking(move || plugins::render_panel(&settings, &req.plugin, &req.id, req.repo_ref.as_deref()))
+        .await
+        .map_err(internal)?
+        .map_err(bad_request)?;
+    Ok(Json(output))
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
+
+fn bad_request<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")).into()
+}
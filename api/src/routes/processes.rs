@@ -0,0 +1,31 @@
+use axum::{extract::Path, routing::{get, post}, Json, Router};
+use serde_json::{json, Value};
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::processes::{self, ProcessInfo},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/processes", get(list_processes))
+        .route("/api/processes/:id/kill", post(kill_process))
+        .route("/api/processes/:id/restart", post(restart_process))
+}
+
+async fn list_processes() -> Json<Value> {
+    Json(json!({ "processes": processes::list() }))
+}
+
+async fn kill_process(Path(id): Path<u64>) -> Result<Json<ProcessInfo>, AppError> {
+    processes::kill(id).map(Json).map_err(bad_request)
+}
+
+async fn restart_process(Path(id): Path<u64>) -> Result<Json<ProcessInfo>, AppError> {
+    processes::restart(id).map(Json).map_err(bad_request)
+}
+
+fn bad_request(err: anyhow::Error) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, format!("{:#}", err)).into()
+}
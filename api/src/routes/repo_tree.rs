@@ -1,10 +1,15 @@
-use std::{collections::BTreeMap, fs, path::{Path, PathBuf}, process::Command};
+use std::{collections::{BTreeMap, BTreeSet}, fs, path::{Path, PathBuf}, process::Command};
 
 use anyhow::Context;
-use axum::{extract::{Path as AxumPath, Query, State}, routing::get, Json, Router};
+use axum::{extract::{Path as AxumPath, Query, State}, routing::{get, post}, Json, Router};
 use serde::{Deserialize, Serialize};
+use sqlx::Row;
 
-use crate::app_state::AppState;
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::capabilities::git::git::{effective_read_ref, ensure_git_repo, git_command, is_bare_git_dir},
+};
 
 use super::workflow_scope::resolve_workflow_scope;
 
@@ -20,14 +25,28 @@ pub struct RepoTreeQuery {
     pub skip_binary: bool,
     #[serde(default)]
     pub skip_gitignore: bool,
+    /// Expand symlinked directories that stay inside the repo.
+    #[serde(default)]
+    pub follow_symlinks: bool,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, Default)]
 pub struct RepoTreeEntry {
     pub name: String,
     pub path: String,
     pub kind: String,
     pub has_children: bool,
+    /// `symlink` or `submodule` (a gitlink); absent for ordinary entries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub special: Option<&'static str>,
+    /// Symlink target as stored in the link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<String>,
+    /// The symlink dangles or resolves outside the repo, so it is never followed.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub link_outside: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub executable: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -59,9 +78,93 @@ pub struct RepoValidateResponse {
     pub exists: bool,
     pub is_dir: bool,
     pub git_repo: bool,
+    pub bare: bool,
     pub message: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct RecentRepo {
+    pub repo_ref: String,
+    pub last_used_at: String,
+    pub workflow_count: i64,
+    pub exists: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloneRepoRequest {
+    pub url: String,
+    pub destination: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloneRepoResponse {
+    pub ok: bool,
+    pub repo_ref: String,
+    pub output: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RepoTreeFilterRequest {
+    pub repo_ref: String,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    #[serde(default)]
+    pub changed_in_worktree: bool,
+    #[serde(default)]
+    pub changed_in_last_commits: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoTreeFilterMatch {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoExtensionStat {
+    pub extension: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoTreeFilterResponse {
+    pub repo_ref: String,
+    pub matches: Vec<RepoTreeFilterMatch>,
+    pub extensions: Vec<RepoExtensionStat>,
+    pub scanned: usize,
+    pub refreshed_at: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TouchedPathsSource {
+    Changeset,
+    Patch,
+    Range,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RepoTouchedPathsRequest {
+    pub repo_ref: String,
+    #[serde(default = "default_git_ref")]
+    pub git_ref: String,
+    pub source: TouchedPathsSource,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoTouchedPathsResponse {
+    pub repo_ref: String,
+    pub git_ref: String,
+    pub paths: Vec<String>,
+    pub missing: Vec<String>,
+}
+
 fn default_git_ref() -> String {
     "WORKTREE".to_string()
 }
@@ -71,19 +174,24 @@ pub fn router() -> Router<AppState> {
         .route("/api/repo-tree", get(get_repo_tree))
         .route("/api/repo-files", get(get_repo_files))
         .route("/api/repo/validate", get(validate_repo_ref))
+        .route("/api/repo/recent", get(list_recent_repos))
+        .route("/api/repo/clone", post(clone_repo))
+        .route("/api/repo/tree/filter", post(filter_repo_tree))
+        .route("/api/repo/tree/touched", post(touched_repo_paths))
         .route("/api/workflow-runs/:run_id/repository/tree", get(get_workflow_repo_tree))
 }
 
 async fn get_repo_tree(
     Query(query): Query<RepoTreeQuery>,
-) -> Result<Json<RepoTreeResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<RepoTreeResponse>, AppError> {
     let repo = PathBuf::from(&query.repo_ref);
     let base_path = normalize_rel_path(&query.base_path);
+    let git_ref = effective_read_ref(&repo, &query.git_ref);
 
-    let mut entries = if effective_ref(&query.git_ref) == "WORKTREE" {
-        collect_worktree_entries(&repo, &base_path, query.skip_binary, query.skip_gitignore).map_err(internal)?
+    let mut entries = if git_ref == "WORKTREE" {
+        collect_worktree_entries(&repo, &base_path, query.skip_binary, query.skip_gitignore, query.follow_symlinks).map_err(internal)?
     } else {
-        collect_git_entries(&repo, effective_ref(&query.git_ref), &base_path, query.skip_binary).map_err(internal)?
+        collect_git_entries(&repo, &git_ref, &base_path, query.skip_binary).map_err(internal)?
     };
 
     entries.sort_by(|a, b| {
@@ -104,13 +212,14 @@ async fn get_repo_tree(
 
 async fn get_repo_files(
     Query(query): Query<RepoTreeQuery>,
-) -> Result<Json<RepoFilesResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<RepoFilesResponse>, AppError> {
     let repo = PathBuf::from(&query.repo_ref);
+    let git_ref = effective_read_ref(&repo, &query.git_ref);
 
-    let mut files = if effective_ref(&query.git_ref) == "WORKTREE" {
+    let mut files = if git_ref == "WORKTREE" {
         collect_worktree_tracked_files_flat(&repo, query.skip_binary).map_err(internal)?
     } else {
-        collect_git_files_flat(&repo, effective_ref(&query.git_ref), query.skip_binary).map_err(internal)?
+        collect_git_files_flat(&repo, &git_ref, query.skip_binary).map_err(internal)?
     };
 
     files.sort();
@@ -128,7 +237,7 @@ async fn get_workflow_repo_tree(
     State(state): State<AppState>,
     AxumPath(run_id): AxumPath<uuid::Uuid>,
     Query(query): Query<RepoTreeQuery>,
-) -> Result<Json<RepoTreeResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<RepoTreeResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     get_repo_tree(Query(RepoTreeQuery {
         repo_ref: scope.repo_ref,
@@ -136,12 +245,13 @@ async fn get_workflow_repo_tree(
         base_path: query.base_path,
         skip_binary: query.skip_binary,
         skip_gitignore: query.skip_gitignore,
+        follow_symlinks: query.follow_symlinks,
     })).await
 }
 
 async fn validate_repo_ref(
     Query(query): Query<RepoValidateQuery>,
-) -> Result<Json<RepoValidateResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<RepoValidateResponse>, AppError> {
     let repo_ref = query.repo_ref.trim().to_string();
     if repo_ref.is_empty() {
         return Ok(Json(RepoValidateResponse {
@@ -150,6 +260,7 @@ async fn validate_repo_ref(
             exists: false,
             is_dir: false,
             git_repo: false,
+            bare: false,
             message: "Repository path is required.".to_string(),
         }));
     }
@@ -157,17 +268,8 @@ async fn validate_repo_ref(
     let repo = PathBuf::from(&repo_ref);
     let exists = repo.exists();
     let is_dir = exists && repo.is_dir();
-    let git_repo = if is_dir {
-        Command::new("git")
-            .arg("-C")
-            .arg(&repo)
-            .args(["rev-parse", "--is-inside-work-tree"])
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
-    } else {
-        false
-    };
+    let git_repo = is_dir && ensure_git_repo(&repo).is_ok();
+    let bare = git_repo && is_bare_git_dir(&repo);
 
     let message = if !exists {
         format!("Repository path does not exist: {}", repo_ref)
@@ -175,6 +277,8 @@ async fn validate_repo_ref(
         format!("Repository path is not a directory: {}", repo_ref)
     } else if !git_repo {
         format!("Repository path is not a git repository: {}", repo_ref)
+    } else if bare {
+        format!("Bare repository (read-only, browsing refs only): {}", repo_ref)
     } else {
         format!("Repository path is valid: {}", repo_ref)
     };
@@ -185,17 +289,244 @@ async fn validate_repo_ref(
         exists,
         is_dir,
         git_repo,
+        bare,
         message,
     }))
 }
 
+async fn list_recent_repos(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<RecentRepo>>, AppError> {
+    let rows = sqlx::query(
+        "SELECT repo_ref, MAX(updated_at) AS last_used_at, COUNT(*) AS workflow_count FROM workflow_runs WHERE TRIM(repo_ref) != '' GROUP BY repo_ref ORDER BY last_used_at DESC LIMIT 12"
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal)?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|row| {
+                let repo_ref: String = row.get("repo_ref");
+                RecentRepo {
+                    exists: Path::new(&repo_ref).is_dir(),
+                    repo_ref,
+                    last_used_at: row.get("last_used_at"),
+                    workflow_count: row.get("workflow_count"),
+                }
+            })
+            .collect(),
+    ))
+}
+
+async fn clone_repo(
+    Json(req): Json<CloneRepoRequest>,
+) -> Result<Json<CloneRepoResponse>, AppError> {
+    let url = req.url.trim().to_string();
+    let destination = req.destination.trim().to_string();
+    if url.is_empty() || destination.is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "url and destination are required".to_string()).into());
+    }
+
+    let dest = PathBuf::from(&destination);
+    if !dest.is_absolute() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "destination must be an absolute path".to_string()).into());
+    }
+    let occupied = fs::read_dir(&dest).map(|mut entries| entries.next().is_some()).unwrap_or(false);
+    if occupied {
+        return Err((axum::http::StatusCode::CONFLICT, format!("destination is not empty: {}", destination)).into());
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(internal)?;
+    }
+
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new("git")
+            .args(["clone", "--", url.as_str(), destination.as_str()])
+            .output()
+    })
+    .await
+    .map_err(internal)?
+    .map_err(internal)?;
+
+    Ok(Json(CloneRepoResponse {
+        ok: output.status.success(),
+        repo_ref: dest.to_string_lossy().to_string(),
+        output: format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)),
+    }))
+}
+
+/// Structured tree filter over the worktree. Every supplied criterion must hold.
+async fn filter_repo_tree(
+    Json(req): Json<RepoTreeFilterRequest>,
+) -> Result<Json<RepoTreeFilterResponse>, AppError> {
+    let repo = PathBuf::from(req.repo_ref.trim());
+    ensure_git_repo(&repo).map_err(|err| (axum::http::StatusCode::BAD_REQUEST, err.to_string()))?;
+
+    let mut files: BTreeSet<String> = BTreeSet::new();
+    for args in [&["ls-files"][..], &["ls-files", "--others", "--exclude-standard"][..]] {
+        let stdout = run_git_capture_string(&repo, args).map_err(internal)?;
+        files.extend(stdout.lines().map(str::trim).filter(|s| !s.is_empty()).map(ToOwned::to_owned));
+    }
+
+    let worktree_changes = if req.changed_in_worktree {
+        Some(worktree_changed_paths(&repo).map_err(internal)?)
+    } else {
+        None
+    };
+    let recent_changes = match req.changed_in_last_commits.filter(|n| *n > 0) {
+        Some(count) => Some(recently_changed_paths(&repo, count).map_err(internal)?),
+        None => None,
+    };
+    let wanted_extensions: BTreeSet<String> = req
+        .extensions
+        .iter()
+        .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect();
+
+    let mut stats: BTreeMap<String, RepoExtensionStat> = BTreeMap::new();
+    let mut matches = Vec::new();
+    for path in &files {
+        let Ok(meta) = fs::metadata(repo.join(path)) else { continue };
+        if !meta.is_file() {
+            continue;
+        }
+        let size = meta.len();
+        let extension = Path::new(path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        let stat = stats.entry(extension.clone()).or_insert_with(|| RepoExtensionStat {
+            extension: extension.clone(),
+            files: 0,
+            bytes: 0,
+        });
+        stat.files += 1;
+        stat.bytes += size;
+
+        let keep = (wanted_extensions.is_empty() || wanted_extensions.contains(&extension))
+            && req.min_size.is_none_or(|min| size >= min)
+            && req.max_size.is_none_or(|max| size <= max)
+            && worktree_changes.as_ref().is_none_or(|set| set.contains(path))
+            && recent_changes.as_ref().is_none_or(|set| set.contains(path));
+        if keep {
+            matches.push(RepoTreeFilterMatch { path: path.clone(), size });
+        }
+    }
+
+    let mut extensions: Vec<RepoExtensionStat> = stats.into_values().collect();
+    extensions.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.extension.cmp(&b.extension)));
+
+    Ok(Json(RepoTreeFilterResponse {
+        repo_ref: req.repo_ref,
+        matches,
+        extensions,
+        scanned: files.len(),
+        refreshed_at: chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+async fn touched_repo_paths(
+    Json(req): Json<RepoTouchedPathsRequest>,
+) -> Result<Json<RepoTouchedPathsResponse>, AppError> {
+    let bad_request = |err: anyhow::Error| (axum::http::StatusCode::BAD_REQUEST, format!("{:#}", err));
+    let repo = PathBuf::from(req.repo_ref.trim());
+    ensure_git_repo(&repo).map_err(bad_request)?;
+    let git_ref = effective_read_ref(&repo, &req.git_ref);
+
+    let touched = match req.source {
+        TouchedPathsSource::Changeset => {
+            crate::engine::capabilities::changeset::changeset_touched_paths(&req.text).map_err(bad_request)?
+        }
+        TouchedPathsSource::Patch => patch_touched_paths(&req.text),
+        TouchedPathsSource::Range => {
+            let range = req.text.trim();
+            if range.is_empty() || range.starts_with('-') {
+                return Err((axum::http::StatusCode::BAD_REQUEST, "a ref range such as HEAD~3..HEAD is required".to_string()).into());
+            }
+            run_git_capture_string(&repo, &["diff", "--name-only", "--no-renames", range, "--"])
+                .map_err(bad_request)?
+                .lines()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(ToOwned::to_owned)
+                .collect()
+        }
+    };
+
+    let existing: Option<BTreeSet<String>> = if git_ref == "WORKTREE" {
+        None
+    } else {
+        Some(collect_git_files_flat(&repo, &git_ref, false).map_err(internal)?.into_iter().collect())
+    };
+    let mut seen = BTreeSet::new();
+    let (paths, missing): (Vec<String>, Vec<String>) = touched
+        .into_iter()
+        .map(|path| normalize_rel_path(&path))
+        .filter(|path| !path.is_empty() && seen.insert(path.clone()))
+        .partition(|path| match &existing {
+            Some(files) => files.contains(path),
+            None => repo.join(path).is_file(),
+        });
+
+    Ok(Json(RepoTouchedPathsResponse {
+        repo_ref: req.repo_ref,
+        git_ref,
+        paths,
+        missing,
+    }))
+}
+
+fn patch_touched_paths(text: &str) -> Vec<String> {
+    let unquote = |path: &str| path.trim().trim_matches('"').to_string();
+    let mut paths = Vec::new();
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            if let Some((from, to)) = rest.split_once(" b/") {
+                paths.push(unquote(from.trim_start_matches("a/")));
+                paths.push(unquote(to));
+            }
+        } else if let Some(rest) = line.strip_prefix("+++ ").or_else(|| line.strip_prefix("--- ")) {
+            let rest = rest.split('\t').next().unwrap_or(rest).trim();
+            if rest != "/dev/null" {
+                paths.push(unquote(rest.strip_prefix("a/").or_else(|| rest.strip_prefix("b/")).unwrap_or(rest)));
+            }
+        } else if let Some(rest) = line.strip_prefix("rename from ").or_else(|| line.strip_prefix("rename to ")) {
+            paths.push(unquote(rest));
+        }
+    }
+    paths
+}
+
+fn worktree_changed_paths(repo: &Path) -> anyhow::Result<BTreeSet<String>> {
+    let stdout = run_git_capture_string(repo, &["status", "--porcelain", "--untracked-files=all"])?;
+    Ok(stdout
+        .lines()
+        .filter(|line| line.len() > 3)
+        .map(|line| {
+            let rest = &line[3..];
+            rest.rsplit_once(" -> ").map(|(_, to)| to).unwrap_or(rest).trim_matches('"').to_string()
+        })
+        .collect())
+}
+
+fn recently_changed_paths(repo: &Path, count: u32) -> anyhow::Result<BTreeSet<String>> {
+    let limit = format!("-n{}", count);
+    let stdout = run_git_capture_string(repo, &["log", &limit, "--name-only", "--pretty=format:"])?;
+    Ok(stdout.lines().map(str::trim).filter(|s| !s.is_empty()).map(ToOwned::to_owned).collect())
+}
+
 fn collect_worktree_entries(
     repo: &Path,
     base_path: &str,
     skip_binary: bool,
     skip_gitignore: bool,
+    follow_symlinks: bool,
 ) -> anyhow::Result<Vec<RepoTreeEntry>> {
     let dir = if base_path.is_empty() { repo.to_path_buf() } else { repo.join(base_path) };
+    let repo_canon = fs::canonicalize(repo).with_context(|| format!("failed to resolve {}", repo.display()))?;
     let mut out = Vec::new();
 
     for entry in fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))? {
@@ -213,16 +544,45 @@ fn collect_worktree_entries(
             .to_string_lossy()
             .replace('\\', "/");
 
-        if file_type.is_dir() {
+        if file_type.is_symlink() {
+            if skip_gitignore && is_gitignored(repo, &rel)? {
+                continue;
+            }
+            let link_target = fs::read_link(&path).ok().map(|target| target.to_string_lossy().replace('\\', "/"));
+            let resolved = fs::canonicalize(&path).ok().filter(|target| target.starts_with(&repo_canon));
+            let target_is_dir = resolved.as_ref().is_some_and(|target| target.is_dir());
+            out.push(RepoTreeEntry {
+                name,
+                kind: if target_is_dir { "dir" } else { "file" }.to_string(),
+                has_children: target_is_dir && follow_symlinks && dir_has_visible_children(repo, &rel, skip_binary, skip_gitignore)?,
+                path: rel,
+                special: Some("symlink"),
+                link_target,
+                link_outside: resolved.is_none(),
+                ..RepoTreeEntry::default()
+            });
+        } else if file_type.is_dir() {
             if skip_gitignore && (is_fast_ignored_dir_name(&name) || is_gitignored(repo, &rel)?) {
                 continue;
             }
+            // A nested checkout with its own `.git` is a submodule; its contents belong to another repo.
+            if path.join(".git").exists() {
+                out.push(RepoTreeEntry {
+                    name,
+                    path: rel,
+                    kind: "dir".to_string(),
+                    special: Some("submodule"),
+                    ..RepoTreeEntry::default()
+                });
+                continue;
+            }
             let has_children = dir_has_visible_children(repo, &rel, skip_binary, skip_gitignore)?;
             out.push(RepoTreeEntry {
                 name,
                 path: rel,
                 kind: "dir".to_string(),
                 has_children,
+                ..RepoTreeEntry::default()
             });
         } else if file_type.is_file() {
             if skip_gitignore && is_gitignored(repo, &rel)? {
@@ -234,11 +594,13 @@ fn collect_worktree_entries(
                     continue;
                 }
             }
+            let executable = is_executable(&entry.metadata()?);
             out.push(RepoTreeEntry {
                 name,
                 path: rel,
                 kind: "file".to_string(),
-                has_children: false,
+                executable,
+                ..RepoTreeEntry::default()
             });
         }
     }
@@ -246,6 +608,17 @@ fn collect_worktree_entries(
     Ok(out)
 }
 
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
 fn dir_has_visible_children(
     repo: &Path,
     rel_dir: &str,
@@ -275,6 +648,13 @@ fn dir_has_visible_children(
             return Ok(true);
         }
 
+        if file_type.is_symlink() {
+            if skip_gitignore && is_gitignored(repo, &rel)? {
+                continue;
+            }
+            return Ok(true);
+        }
+
         if file_type.is_file() {
             if skip_gitignore && is_gitignored(repo, &rel)? {
                 continue;
@@ -319,7 +699,7 @@ fn collect_git_entries(
     base_path: &str,
     skip_binary: bool,
 ) -> anyhow::Result<Vec<RepoTreeEntry>> {
-    let stdout = run_git_capture_string(repo, &["ls-tree", "-r", "--name-only", git_ref])?;
+    let stdout = run_git_capture_string(repo, &["ls-tree", "-r", git_ref])?;
     let prefix = if base_path.is_empty() {
         String::new()
     } else {
@@ -328,7 +708,10 @@ fn collect_git_entries(
 
     let mut grouped = BTreeMap::<String, RepoTreeEntry>::new();
 
-    for line in stdout.lines().map(str::trim).filter(|s| !s.is_empty()) {
+    for record in stdout.lines().filter(|s| !s.trim().is_empty()) {
+        // `<mode> <type> <object>\t<path>`
+        let Some((meta, line)) = record.split_once('\t') else { continue };
+        let mode = meta.split_whitespace().next().unwrap_or_default();
         if !prefix.is_empty() && !line.starts_with(&prefix) {
             continue;
         }
@@ -345,9 +728,32 @@ fn collect_git_entries(
                 path,
                 kind: "dir".to_string(),
                 has_children: true,
+                ..RepoTreeEntry::default()
             });
         } else {
             let rel = if base_path.is_empty() { remainder.to_string() } else { format!("{}/{}", base_path, remainder) };
+            if mode == "160000" {
+                grouped.entry(rel.clone()).or_insert(RepoTreeEntry {
+                    name: remainder.to_string(),
+                    path: rel,
+                    kind: "dir".to_string(),
+                    special: Some("submodule"),
+                    ..RepoTreeEntry::default()
+                });
+                continue;
+            }
+            if mode == "120000" {
+                let link_target = read_git_file_bytes(repo, git_ref, &rel).ok().map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+                grouped.entry(rel.clone()).or_insert(RepoTreeEntry {
+                    name: remainder.to_string(),
+                    path: rel,
+                    kind: "file".to_string(),
+                    special: Some("symlink"),
+                    link_target,
+                    ..RepoTreeEntry::default()
+                });
+                continue;
+            }
             if skip_binary {
                 let bytes = read_git_file_bytes(repo, git_ref, &rel)?;
                 if is_probably_binary(&bytes) {
@@ -358,7 +764,8 @@ fn collect_git_entries(
                 name: remainder.to_string(),
                 path: rel,
                 kind: "file".to_string(),
-                has_children: false,
+                executable: mode == "100755",
+                ..RepoTreeEntry::default()
             });
         }
     }
@@ -399,19 +806,12 @@ fn read_git_file_bytes(repo: &Path, git_ref: &str, rel: &str) -> anyhow::Result<
     run_git_capture(repo, &["show", &format!("{}:{}", git_ref, rel)])
 }
 
-fn effective_ref(git_ref: &str) -> &str {
-    if git_ref.trim().is_empty() {
-        "WORKTREE"
-    } else {
-        git_ref
-    }
-}
-
 fn is_probably_binary(bytes: &[u8]) -> bool {
     bytes.iter().any(|b| *b == 0)
 }
 
 fn is_gitignored(repo: &Path, rel: &str) -> anyhow::Result<bool> {
+    crate::metrics::record_git_spawn();
     let output = Command::new("git")
         .arg("check-ignore")
         .arg("--quiet")
@@ -424,9 +824,8 @@ fn is_gitignored(repo: &Path, rel: &str) -> anyhow::Result<bool> {
 }
 
 fn run_git_capture(repo: &Path, args: &[&str]) -> anyhow::Result<Vec<u8>> {
-    let output = Command::new("git")
+    let output = git_command(repo)
         .args(args)
-        .current_dir(repo)
         .output()
         .with_context(|| format!("failed to run git {:?}", args))?;
     if !output.status.success() {
@@ -439,6 +838,33 @@ fn run_git_capture_string(repo: &Path, args: &[&str]) -> anyhow::Result<String>
     Ok(String::from_utf8(run_git_capture(repo, args)?)?)
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::capabilities::git::git::run_git;
+
+    #[tokio::test]
+    async fn bare_repos_browse_head_but_echo_the_requested_ref() {
+        let repo = std::env::temp_dir().join(format!("repo-tree-bare-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        std::fs::write(repo.join("src/main.rs"), "fn main() {}\n").unwrap();
+        run_git(&repo, &["add", "-A"]).unwrap();
+        run_git(&repo, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]).unwrap();
+        let query = || {
+            Query(serde_json::from_value::<RepoTreeQuery>(serde_json::json!({ "repo_ref": repo.join(".git").to_string_lossy() })).unwrap())
+        };
+
+        let Json(tree) = get_repo_tree(query()).await.unwrap();
+        assert_eq!(tree.git_ref, "WORKTREE");
+        assert_eq!(tree.entries.iter().map(|entry| entry.path.as_str()).collect::<Vec<_>>(), ["src"]);
+        let Json(files) = get_repo_files(query()).await.unwrap();
+        assert_eq!(files.git_ref, "WORKTREE");
+        assert_eq!(files.files, ["src/main.rs"]);
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
 }
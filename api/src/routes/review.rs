@@ -2,20 +2,39 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 
 use axum::{extract::{Path, Query, State}, routing::{get, post}, Json, Router};
+use chrono::Datelike;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    app_error::AppError,
     app_state::AppState,
     engine::capabilities::git::git::{
         generate_git_apply_patch,
         git_diff_stats,
+        git_discard,
+        git_discard_plan,
+        git_fetch,
+        git_pull,
+        git_push,
+        ensure_git_worktree,
+        git_stage_line_range,
         git_status,
         git_untracked_line_stats,
+        resolve_read_ref,
         run_git,
         run_git_allow_fail,
         GitPatchScope,
     },
+    engine::capabilities::git::index_lock::{
+        inspect_index_lock,
+        remove_stale_index_lock,
+        run_git_index_op,
+        IndexLockContention,
+        IndexLockInfo,
+    },
+    engine::capabilities::git::types::{GitCredentials, GitDiscardPlan, GitRemoteResult},
+    engine::history,
 };
 
 use super::workflow_scope::resolve_workflow_scope;
@@ -26,6 +45,17 @@ pub struct ReviewRepoRequest {
     pub repo_ref: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReviewRemoteRequest {
+    pub repo_ref: String,
+    #[serde(default)]
+    pub remote: Option<String>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub credentials: Option<GitCredentials>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ReviewDiffRequest {
     pub repo_ref: String,
@@ -36,6 +66,9 @@ pub struct ReviewDiffRequest {
     pub context_lines: Option<u32>,
     #[serde(default)]
     pub whole_file: bool,
+    /// `eol`, `change` or `all`: ignore CR at EOL (`--ignore-cr-at-eol`), `-b` or `-w`.
+    #[serde(default)]
+    pub whitespace: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,6 +86,8 @@ pub struct ReviewFilePatchRequest {
     pub context_lines: Option<u32>,
     #[serde(default)]
     pub whole_file: bool,
+    #[serde(default)]
+    pub whitespace: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,6 +130,42 @@ pub struct ReviewCommitRequest {
     pub commit: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReviewCompareRequest {
+    pub repo_ref: String,
+    pub base: String,
+    pub head: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewCompareDiffRequest {
+    pub repo_ref: String,
+    pub base: String,
+    pub head: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub context_lines: Option<u32>,
+    #[serde(default)]
+    pub whole_file: bool,
+    #[serde(default)]
+    pub whitespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewReflogRequest {
+    pub repo_ref: String,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewCreateBranchRequest {
+    pub repo_ref: String,
+    pub name: String,
+    pub start_point: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ReviewCommitDiffRequest {
     pub repo_ref: String,
@@ -105,6 +176,8 @@ pub struct ReviewCommitDiffRequest {
     pub context_lines: Option<u32>,
     #[serde(default)]
     pub whole_file: bool,
+    #[serde(default)]
+    pub whitespace: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -178,12 +251,24 @@ pub struct ReviewCommitReportBucket {
     pub groups: Vec<ReviewCommitReportGroupBucket>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct ReviewCommitReportContributor {
+    pub key: String,
+    pub label: String,
+    pub commits: u64,
+    pub additions: u64,
+    pub deletions: u64,
+    pub files_changed: u64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ReviewCommitReportResponse {
     pub ok: bool,
     pub commits: Vec<ReviewCommitSummary>,
     pub months: Vec<ReviewCommitReportMonthBucket>,
     pub buckets: Vec<ReviewCommitReportBucket>,
+    pub authors: Vec<ReviewCommitReportContributor>,
+    pub directories: Vec<ReviewCommitReportContributor>,
     pub aggregation_window: String,
     pub color_by: String,
     pub exclude_regex: Vec<String>,
@@ -214,6 +299,31 @@ pub struct ReviewStageActionRequest {
     pub path: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReviewDiscardRequest {
+    pub repo_ref: String,
+    #[serde(default)]
+    pub paths: Option<Vec<String>>,
+    #[serde(default)]
+    pub expected_total: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewDiscardResponse {
+    pub plan: GitDiscardPlan,
+    pub total: usize,
+    pub untracked_bytes: u64,
+    pub history_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewStageLinesRequest {
+    pub repo_ref: String,
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WorkflowReviewDiffQuery {
     pub scope: String,
@@ -223,6 +333,8 @@ pub struct WorkflowReviewDiffQuery {
     pub context_lines: Option<u32>,
     #[serde(default)]
     pub whole_file: bool,
+    #[serde(default)]
+    pub whitespace: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -271,6 +383,7 @@ pub struct ReviewStatusFileEntry {
     pub index_status: String,
     pub worktree_status: String,
     pub untracked: bool,
+    pub conflicted: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -302,6 +415,8 @@ pub struct ReviewDiffManifestFileEntry {
     pub index_status: String,
     pub worktree_status: String,
     pub untracked: bool,
+    /// `eol` when only line endings changed, `whitespace` when only whitespace did.
+    pub whitespace_only: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -376,6 +491,52 @@ pub struct ReviewCommitListResponse {
     pub has_more: bool,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ReviewCompareResponse {
+    pub ok: bool,
+    pub base: String,
+    pub head: String,
+    pub merge_base: Option<String>,
+    pub ahead: u64,
+    pub behind: u64,
+    pub ahead_commits: Vec<ReviewCommitSummary>,
+    pub behind_commits: Vec<ReviewCommitSummary>,
+    pub files: Vec<ReviewDiffManifestFileEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewReflogEntry {
+    pub selector: String,
+    pub sha: String,
+    pub short_sha: String,
+    pub action: String,
+    pub message: String,
+    pub subject: String,
+    pub recorded_at: String,
+    pub reachable: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewReflogResponse {
+    pub ok: bool,
+    pub entries: Vec<ReviewReflogEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewCreateBranchResponse {
+    pub ok: bool,
+    pub name: String,
+    pub sha: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewCherryPickResponse {
+    pub ok: bool,
+    pub commit: String,
+    pub head: Option<String>,
+    pub message: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ReviewCommitDiffManifestResponse {
     pub ok: bool,
@@ -430,8 +591,19 @@ pub fn router() -> Router<AppState> {
         .route("/api/review/commit-options", post(review_commit_options))
         .route("/api/review/commit/diff", post(review_commit_diff))
         .route("/api/review/commit/diff/manifest", post(review_commit_diff_manifest))
+        .route("/api/review/compare", post(review_compare))
+        .route("/api/review/compare/diff", post(review_compare_diff))
+        .route("/api/review/cherry-pick", post(review_cherry_pick))
+        .route("/api/review/reflog", post(review_reflog))
+        .route("/api/review/branch", post(review_create_branch))
         .route("/api/review/stage", post(review_stage))
+        .route("/api/review/stage-lines", post(review_stage_lines))
         .route("/api/review/unstage", post(review_unstage))
+        .route("/api/review/index-lock", post(review_index_lock))
+        .route("/api/review/index-lock/remove", post(review_remove_index_lock))
+        .route("/api/review/discard/preview", post(review_discard_preview))
+        .route("/api/review/discard", post(review_discard))
+        .route("/api/review/remote/:action", post(review_remote))
         .route("/api/workflow-runs/:run_id/review/status", get(workflow_review_status))
         .route("/api/workflow-runs/:run_id/review/diff", get(workflow_review_diff))
         .route("/api/workflow-runs/:run_id/review/diff/manifest", get(workflow_review_diff_manifest))
@@ -444,7 +616,7 @@ pub fn router() -> Router<AppState> {
 async fn workflow_review_status(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
-) -> Result<Json<ReviewStatusResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewStatusResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     review_status(Json(ReviewRepoRequest { repo_ref: scope.repo_ref })).await
 }
@@ -453,7 +625,7 @@ async fn workflow_review_diff(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Query(query): Query<WorkflowReviewDiffQuery>,
-) -> Result<Json<ReviewDiffResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewDiffResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     review_diff(Json(ReviewDiffRequest {
         repo_ref: scope.repo_ref,
@@ -461,6 +633,7 @@ async fn workflow_review_diff(
         path: query.path,
         context_lines: query.context_lines,
         whole_file: query.whole_file,
+        whitespace: query.whitespace,
     })).await
 }
 
@@ -468,7 +641,7 @@ async fn workflow_review_diff_manifest(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Query(query): Query<WorkflowReviewManifestQuery>,
-) -> Result<Json<ReviewDiffManifestResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewDiffManifestResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     review_diff_manifest(Json(ReviewDiffManifestRequest {
         repo_ref: scope.repo_ref,
@@ -480,7 +653,7 @@ async fn workflow_review_stage(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Json(req): Json<WorkflowStageActionRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     review_stage(Json(ReviewStageActionRequest {
         repo_ref: scope.repo_ref,
@@ -493,7 +666,7 @@ async fn workflow_review_unstage(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Json(req): Json<WorkflowStageActionRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     review_unstage(Json(ReviewStageActionRequest {
         repo_ref: scope.repo_ref,
@@ -506,7 +679,7 @@ async fn workflow_review_commits_get(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Query(query): Query<WorkflowCommitListQuery>,
-) -> Result<Json<ReviewCommitListResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewCommitListResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     review_commits(Json(ReviewCommitListRequest {
         repo_ref: scope.repo_ref,
@@ -522,7 +695,7 @@ async fn workflow_review_commits_post(
     State(state): State<AppState>,
     Path(run_id): Path<uuid::Uuid>,
     Json(req): Json<WorkflowCommitListRequest>,
-) -> Result<Json<ReviewCommitListResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewCommitListResponse>, AppError> {
     let scope = resolve_workflow_scope(&state, run_id).await?;
     review_commits(Json(ReviewCommitListRequest {
         repo_ref: scope.repo_ref,
@@ -534,6 +707,63 @@ async fn workflow_review_commits_post(
     })).await
 }
 
+fn whitespace_args(mode: Option<&str>) -> Result<Vec<String>, AppError> {
+    let flag = match mode.map(str::trim).unwrap_or_default() {
+        "" | "none" => return Ok(Vec::new()),
+        "eol" => "--ignore-cr-at-eol",
+        "change" => "--ignore-space-change",
+        "all" => "--ignore-all-space",
+        other => {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("unsupported whitespace mode {other}"),
+            ).into());
+        }
+    };
+    Ok(vec![flag.to_string()])
+}
+
+/// Paths that still differ once CR-at-EOL, and then all whitespace, is ignored. A changed path
+/// missing from these sets is a line-ending-only or whitespace-only change.
+struct SubstantiveChanges {
+    ignoring_eol: BTreeSet<String>,
+    ignoring_whitespace: BTreeSet<String>,
+}
+
+impl SubstantiveChanges {
+    fn load(repo: &std::path::Path, diff_args: &[&str]) -> Option<Self> {
+        let changed_paths = |flag: &str| -> Option<BTreeSet<String>> {
+            let mut args = vec!["diff", "--numstat", flag];
+            args.extend_from_slice(diff_args);
+            let out = run_git(repo, &args).ok()?;
+            Some(
+                String::from_utf8_lossy(&out)
+                    .lines()
+                    .filter_map(parse_numstat_line)
+                    .filter(|(_, additions, deletions)| additions + deletions > 0)
+                    .map(|(path, _, _)| path)
+                    .collect(),
+            )
+        };
+        Some(Self {
+            ignoring_eol: changed_paths("--ignore-cr-at-eol")?,
+            ignoring_whitespace: changed_paths("--ignore-all-space")?,
+        })
+    }
+
+    fn classify(&self, path: &str, additions: u64, deletions: u64) -> Option<String> {
+        if additions + deletions == 0 {
+            None
+        } else if !self.ignoring_eol.contains(path) {
+            Some("eol".to_string())
+        } else if !self.ignoring_whitespace.contains(path) {
+            Some("whitespace".to_string())
+        } else {
+            None
+        }
+    }
+}
+
 fn parse_numstat_line(line: &str) -> Option<(String, u64, u64)> {
     let mut parts = line.splitn(3, '\t');
     let additions = parts.next()?.trim();
@@ -578,10 +808,10 @@ fn normalize_review_extension(value: &str) -> String {
     if trimmed.is_empty() { "[none]".to_string() } else { format!(".{trimmed}") }
 }
 
-fn compile_review_exclude_regex(patterns: &[String]) -> Result<Vec<Regex>, (axum::http::StatusCode, String)> {
+fn compile_review_exclude_regex(patterns: &[String]) -> Result<Vec<Regex>, AppError> {
     patterns
         .iter()
-        .map(|pattern| Regex::new(pattern).map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("invalid exclude regex '{pattern}': {err}"))))
+        .map(|pattern| Regex::new(pattern).map_err(|err| AppError::from((axum::http::StatusCode::BAD_REQUEST, format!("invalid exclude regex '{pattern}': {err}")))))
         .collect()
 }
 
@@ -636,6 +866,9 @@ fn review_month_for_authored_at(value: &str) -> String {
 fn review_period_for_authored_at(value: &str, aggregation_window: &str) -> String {
     match aggregation_window {
         "daily" => value.get(0..10).unwrap_or(value).to_string(),
+        "weekly" => chrono::NaiveDate::parse_from_str(value.get(0..10).unwrap_or(value), "%Y-%m-%d")
+            .map(|date| (date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)).to_string())
+            .unwrap_or_else(|_| value.get(0..10).unwrap_or(value).to_string()),
         "yearly" => value.get(0..4).unwrap_or(value).to_string(),
         _ => value.get(0..7).unwrap_or(value).to_string(),
     }
@@ -644,6 +877,7 @@ fn review_period_for_authored_at(value: &str, aggregation_window: &str) -> Strin
 fn normalize_review_aggregation_window(value: Option<String>) -> String {
     match value.as_deref().map(str::trim) {
         Some("daily") => "daily".to_string(),
+        Some("weekly") => "weekly".to_string(),
         Some("yearly") => "yearly".to_string(),
         _ => "monthly".to_string(),
     }
@@ -652,17 +886,53 @@ fn normalize_review_aggregation_window(value: Option<String>) -> String {
 fn normalize_review_color_by(value: Option<String>) -> String {
     match value.as_deref().map(str::trim) {
         Some("author") => "author".to_string(),
+        Some("directory") => "directory".to_string(),
         _ => "extension".to_string(),
     }
 }
 
+fn review_directory_for_path(path: &str) -> String {
+    match path.replace('\\', "/").split_once('/') {
+        Some((head, _)) if !head.is_empty() => format!("{}/", head),
+        _ => "[root]".to_string(),
+    }
+}
+
+fn review_author_key_label(summary: &ReviewCommitSummary) -> (String, String) {
+    if summary.author_name.trim().is_empty() {
+        (summary.author_email.trim().to_ascii_lowercase(), summary.author_email.clone())
+    } else {
+        (summary.author_name.trim().to_ascii_lowercase(), summary.author_name.clone())
+    }
+}
+
+fn bump_contributor(rows: &mut BTreeMap<String, ReviewCommitReportContributor>, key: &str, label: &str, file: &ReviewCommitFileStat) {
+    let entry = rows.entry(key.to_string()).or_insert_with(|| ReviewCommitReportContributor {
+        key: key.to_string(),
+        label: label.to_string(),
+        commits: 0,
+        additions: 0,
+        deletions: 0,
+        files_changed: 0,
+    });
+    entry.additions = entry.additions.saturating_add(file.additions);
+    entry.deletions = entry.deletions.saturating_add(file.deletions);
+    entry.files_changed = entry.files_changed.saturating_add(1);
+}
+
+fn ranked_contributors(rows: BTreeMap<String, ReviewCommitReportContributor>) -> Vec<ReviewCommitReportContributor> {
+    let mut out = rows.into_values().collect::<Vec<_>>();
+    out.sort_by(|a, b| (b.additions + b.deletions).cmp(&(a.additions + a.deletions)).then_with(|| a.label.cmp(&b.label)));
+    out
+}
+
 fn is_review_stat_ignored_path(path: &str) -> bool {
     let normalized = path.replace('\\', "/");
     let name = normalized.rsplit('/').next().unwrap_or(normalized.as_str());
     matches!(name, "Cargo.lock" | "package-lock.json" | "pnpm-lock.yaml" | "yarn.lock")
 }
 
-fn commit_parent_ref(repo: &std::path::Path, commit: &str) -> Result<String, (axum::http::StatusCode, String)> {
+fn commit_parent_ref(repo: &std::path::Path, commit: &str) -> Result<String, AppError> {
     let parent_spec = format!("{commit}^1");
     let (code, stdout, _stderr) = run_git_allow_fail(repo, &["rev-parse", "--verify", &parent_spec]).map_err(internal)?;
     if code == 0 {
@@ -677,11 +947,20 @@ fn commit_parent_ref(repo: &std::path::Path, commit: &str) -> Result<String, (ax
 fn commit_diff_entries(
     repo: &std::path::Path,
     commit: &str,
-) -> Result<(String, String, Vec<ReviewDiffManifestFileEntry>), (axum::http::StatusCode, String)> {
+) -> Result<(String, String, Vec<ReviewDiffManifestFileEntry>), AppError> {
     let from_ref = commit_parent_ref(repo, commit)?;
     let to_ref = commit.to_string();
-    let numstat = String::from_utf8(run_git(repo, &["diff", "--numstat", &from_ref, &to_ref]).map_err(internal)?).map_err(internal)?;
-    let name_status = String::from_utf8(run_git(repo, &["diff", "--name-status", &from_ref, &to_ref]).map_err(internal)?).map_err(internal)?;
+    let files = ref_diff_entries(repo, &from_ref, &to_ref)?;
+    Ok((from_ref, to_ref, files))
+}
+
+fn ref_diff_entries(
+    repo: &std::path::Path,
+    from_ref: &str,
+    to_ref: &str,
+) -> Result<Vec<ReviewDiffManifestFileEntry>, AppError> {
+    let numstat = String::from_utf8(run_git(repo, &["diff", "--numstat", from_ref, to_ref]).map_err(internal)?).map_err(internal)?;
+    let name_status = String::from_utf8(run_git(repo, &["diff", "--name-status", from_ref, to_ref]).map_err(internal)?).map_err(internal)?;
 
     let mut status_by_path: HashMap<String, String> = HashMap::new();
     for line in name_status.lines() {
@@ -693,6 +972,7 @@ fn commit_diff_entries(
         }
     }
 
+    let substantive = SubstantiveChanges::load(repo, &[from_ref, to_ref]);
     let mut seen = BTreeSet::new();
     let mut files = Vec::new();
     for line in numstat.lines() {
@@ -703,6 +983,7 @@ fn commit_diff_entries(
             seen.insert(path.clone());
             files.push(ReviewDiffManifestFileEntry {
                 index_status: status_by_path.get(&path).cloned().unwrap_or_else(|| "M".to_string()),
+                whitespace_only: substantive.as_ref().and_then(|changes| changes.classify(&path, additions, deletions)),
                 path,
                 additions,
                 deletions,
@@ -723,11 +1004,69 @@ fn commit_diff_entries(
             index_status: status,
             worktree_status: ".".to_string(),
             untracked: false,
+            whitespace_only: None,
         });
     }
 
     files.sort_by(|a, b| a.path.cmp(&b.path));
-    Ok((from_ref, to_ref, files))
+    Ok(files)
+}
+
+/// A commit the client named, resolved to its id so it can't reach git as an option.
+fn commit_ref(repo: &std::path::Path, value: &str) -> Result<String, AppError> {
+    match resolve_read_ref(repo, value) {
+        Ok(commit) if commit != "WORKTREE" => Ok(commit),
+        Ok(_) => Err((axum::http::StatusCode::BAD_REQUEST, "a commit or ref is required".to_string()).into()),
+        Err(err) => Err((axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")).into()),
+    }
+}
+
+fn compare_merge_base(repo: &std::path::Path, base: &str, head: &str) -> Result<Option<String>, AppError> {
+    let (code, stdout, _stderr) = run_git_allow_fail(repo, &["merge-base", base, head]).map_err(internal)?;
+    if code != 0 {
+        return Ok(None);
+    }
+    let merge_base = String::from_utf8(stdout).map_err(internal)?.trim().to_string();
+    Ok(Some(merge_base).filter(|value| !value.is_empty()))
+}
+
+fn compare_side_commits(repo: &std::path::Path, range: &str) -> Result<Vec<ReviewCommitSummary>, AppError> {
+    let raw = String::from_utf8(
+        run_git(repo, &["log", "--max-count=500", "--shortstat", "--pretty=format:%x1e%H%x1f%h%x1f%an%x1f%ae%x1f%aI%x1f%s", range]).map_err(internal)?,
+    )
+    .map_err(internal)?;
+
+    let number_before = |line: &str, label: &str| {
+        line.split(',')
+            .find(|part| part.contains(label))
+            .and_then(|part| part.split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+
+    let mut commits = Vec::new();
+    for record in raw.split('\x1e') {
+        let mut lines = record.trim_matches('\n').lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let fields: Vec<&str> = header.split('\x1f').collect();
+        if fields.len() < 6 {
+            continue;
+        }
+        let stat = lines.map(str::trim).find(|line| line.contains("changed")).unwrap_or_default();
+        commits.push(ReviewCommitSummary {
+            sha: fields[0].to_string(),
+            short_sha: fields[1].to_string(),
+            author_name: fields[2].to_string(),
+            author_email: fields[3].to_string(),
+            authored_at: fields[4].to_string(),
+            subject: fields[5].to_string(),
+            files_changed: Some(number_before(stat, "changed").unwrap_or(0)),
+            additions: Some(number_before(stat, "insertion").unwrap_or(0)),
+            deletions: Some(number_before(stat, "deletion").unwrap_or(0)),
+        });
+    }
+    Ok(commits)
 }
 
 #[derive(Debug)]
@@ -748,7 +1087,7 @@ struct ReviewCommitHistoryFilters {
 fn collect_review_commit_history(
     repo: &std::path::Path,
     filters: ReviewCommitHistoryFilters,
-) -> Result<ReviewCommitHistoryQueryResult, (axum::http::StatusCode, String)> {
+) -> Result<ReviewCommitHistoryQueryResult, AppError> {
     let compiled_includes = compile_review_exclude_regex(&filters.include_regex)?;
     let compiled_excludes = compile_review_exclude_regex(&filters.exclude_regex)?;
 
@@ -878,7 +1217,7 @@ fn collect_review_commit_history(
 fn collect_full_review_commit_history(
     repo: &std::path::Path,
     req: &ReviewCommitReportRequest,
-) -> Result<ReviewCommitHistoryQueryResult, (axum::http::StatusCode, String)> {
+) -> Result<ReviewCommitHistoryQueryResult, AppError> {
     collect_review_commit_history(
         repo,
         ReviewCommitHistoryFilters {
@@ -899,7 +1238,7 @@ fn collect_full_review_commit_history(
 
 async fn review_commits(
     Json(req): Json<ReviewCommitListRequest>,
-) -> Result<Json<ReviewCommitListResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewCommitListResponse>, AppError> {
     let report = review_commit_report(Json(ReviewCommitReportRequest {
         repo_ref: req.repo_ref,
         limit: Some(req.limit.unwrap_or(50).clamp(1, 200)),
@@ -927,7 +1266,7 @@ async fn review_commits(
 
 async fn review_commit_options(
     Json(req): Json<ReviewRepoRequest>,
-) -> Result<Json<ReviewCommitOptionsResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewCommitOptionsResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let mut refs = Vec::new();
 
@@ -986,7 +1325,16 @@ async fn review_commit_options(
 
 async fn review_commit_report(
     Json(req): Json<ReviewCommitReportRequest>,
-) -> Result<Json<ReviewCommitReportResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewCommitReportResponse>, AppError> {
+    tokio::task::spawn_blocking(move || build_review_commit_report(req))
+        .await
+        .map_err(internal)?
+        .map(Json)
+}
+
+fn build_review_commit_report(
+    req: ReviewCommitReportRequest,
+) -> Result<ReviewCommitReportResponse, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let aggregation_window = normalize_review_aggregation_window(req.aggregation_window.clone());
     let color_by = normalize_review_color_by(req.color_by.clone());
@@ -994,13 +1342,22 @@ async fn review_commit_report(
     let mut group_rows: BTreeMap<String, BTreeMap<String, ReviewCommitReportGroupBucket>> = BTreeMap::new();
     let mut extension_rows: BTreeMap<String, BTreeMap<String, ReviewCommitReportExtensionBucket>> = BTreeMap::new();
     let mut totals: BTreeMap<String, (u64, u64, u64, u64)> = BTreeMap::new();
+    let mut author_rows: BTreeMap<String, ReviewCommitReportContributor> = BTreeMap::new();
+    let mut directory_rows: BTreeMap<String, ReviewCommitReportContributor> = BTreeMap::new();
 
     for row in &result.rows {
         let period = review_period_for_authored_at(&row.summary.authored_at, &aggregation_window);
         let total = totals.entry(period.clone()).or_insert((0, 0, 0, 0));
         total.3 = total.3.saturating_add(1);
+        let (author_key, author_label) = review_author_key_label(&row.summary);
+        let mut commit_directories = BTreeSet::new();
 
         for file in &row.files {
+            let directory = review_directory_for_path(&file.path);
+            bump_contributor(&mut author_rows, &author_key, &author_label, file);
+            bump_contributor(&mut directory_rows, &directory, &directory, file);
+            commit_directories.insert(directory.clone());
+
             total.0 = total.0.saturating_add(file.additions);
             total.1 = total.1.saturating_add(file.deletions);
             total.2 = total.2.saturating_add(1);
@@ -1015,20 +1372,10 @@ async fn review_commit_report(
             extension_bucket.deletions = extension_bucket.deletions.saturating_add(file.deletions);
             extension_bucket.net = extension_bucket.additions as i64 - extension_bucket.deletions as i64;
 
-            let (key, label) = if color_by == "author" {
-                let label = if row.summary.author_name.trim().is_empty() {
-                    row.summary.author_email.clone()
-                } else {
-                    row.summary.author_name.clone()
-                };
-                let key = if row.summary.author_name.trim().is_empty() {
-                    row.summary.author_email.trim().to_ascii_lowercase()
-                } else {
-                    row.summary.author_name.trim().to_ascii_lowercase()
-                };
-                (key, label)
-            } else {
-                (extension.clone(), extension)
+            let (key, label) = match color_by.as_str() {
+                "author" => (author_key.clone(), author_label.clone()),
+                "directory" => (directory.clone(), directory),
+                _ => (extension.clone(), extension),
             };
 
             let group_bucket = group_rows
@@ -1040,6 +1387,15 @@ async fn review_commit_report(
             group_bucket.deletions = group_bucket.deletions.saturating_add(file.deletions);
             group_bucket.net = group_bucket.additions as i64 - group_bucket.deletions as i64;
         }
+
+        if let Some(author) = author_rows.get_mut(&author_key) {
+            author.commits = author.commits.saturating_add(1);
+        }
+        for directory in commit_directories {
+            if let Some(entry) = directory_rows.get_mut(&directory) {
+                entry.commits = entry.commits.saturating_add(1);
+            }
+        }
     }
 
     let mut buckets = Vec::new();
@@ -1097,22 +1453,24 @@ async fn review_commit_report(
         None
     };
 
-    Ok(Json(ReviewCommitReportResponse {
+    Ok(ReviewCommitReportResponse {
         ok: true,
         commits,
         months,
         buckets,
+        authors: ranked_contributors(author_rows),
+        directories: ranked_contributors(directory_rows),
         aggregation_window,
         color_by,
         exclude_regex: result.exclude_regex,
         next_offset,
         has_more: next_offset.is_some(),
-    }))
+    })
 }
 
 async fn review_commit_diff_manifest(
     Json(req): Json<ReviewCommitRequest>,
-) -> Result<Json<ReviewCommitDiffManifestResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewCommitDiffManifestResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let (from_ref, to_ref, files) = commit_diff_entries(&repo, &req.commit)?;
     Ok(Json(ReviewCommitDiffManifestResponse {
@@ -1124,16 +1482,178 @@ async fn review_commit_diff_manifest(
     }))
 }
 
+async fn review_compare(
+    Json(req): Json<ReviewCompareRequest>,
+) -> Result<Json<ReviewCompareResponse>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let base = req.base.trim().to_string();
+    let head = req.head.trim().to_string();
+    if base.is_empty() || head.is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "base and head refs are required".to_string()).into());
+    }
+    let base_commit = commit_ref(&repo, &base)?;
+    let head_commit = commit_ref(&repo, &head)?;
+
+    let counts = String::from_utf8(
+        run_git(&repo, &["rev-list", "--left-right", "--count", &format!("{base_commit}...{head_commit}")]).map_err(internal)?,
+    )
+    .map_err(internal)?;
+    let mut parts = counts.split_whitespace().map(|value| value.parse::<u64>().unwrap_or(0));
+    let behind = parts.next().unwrap_or(0);
+    let ahead = parts.next().unwrap_or(0);
+
+    let merge_base = compare_merge_base(&repo, &base_commit, &head_commit)?;
+    let files = ref_diff_entries(&repo, merge_base.as_deref().unwrap_or(&base_commit), &head_commit)?;
+
+    Ok(Json(ReviewCompareResponse {
+        ok: true,
+        ahead_commits: compare_side_commits(&repo, &format!("{base_commit}..{head_commit}"))?,
+        behind_commits: compare_side_commits(&repo, &format!("{head_commit}..{base_commit}"))?,
+        base,
+        head,
+        merge_base,
+        ahead,
+        behind,
+        files,
+    }))
+}
+
+async fn review_compare_diff(
+    Json(req): Json<ReviewCompareDiffRequest>,
+) -> Result<Json<ReviewCommitDiffResponse>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let base = commit_ref(&repo, &req.base)?;
+    let to_ref = commit_ref(&repo, &req.head)?;
+    let from_ref = compare_merge_base(&repo, &base, &to_ref)?.unwrap_or(base);
+    let effective_context = if req.whole_file { 2147483647 } else { req.context_lines.unwrap_or(10).min(1000) };
+    let unified_arg = format!("--unified={}", effective_context);
+
+    let mut args = vec!["diff".to_string(), unified_arg];
+    args.extend(whitespace_args(req.whitespace.as_deref())?);
+    args.extend([from_ref.clone(), to_ref.clone()]);
+    if let Some(path) = req.path.as_deref().filter(|value| !value.trim().is_empty()) {
+        args.push("--".to_string());
+        args.push(path.to_string());
+    }
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let patch = String::from_utf8(run_git(&repo, &arg_refs).map_err(internal)?).map_err(internal)?;
+
+    Ok(Json(ReviewCommitDiffResponse {
+        ok: true,
+        commit: to_ref.clone(),
+        path: req.path,
+        from_ref,
+        to_ref,
+        patch,
+    }))
+}
+
+async fn review_cherry_pick(
+    Json(req): Json<ReviewCommitRequest>,
+) -> Result<Json<ReviewCherryPickResponse>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    ensure_git_worktree(&repo).map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+    let commit = commit_ref(&repo, &req.commit)?;
+    let (code, stdout, stderr) = run_git_allow_fail(&repo, &["cherry-pick", &commit]).map_err(internal)?;
+    if code != 0 {
+        let _ = run_git_allow_fail(&repo, &["cherry-pick", "--abort"]);
+        let message = String::from_utf8_lossy(&stderr).trim().to_string();
+        return Ok(Json(ReviewCherryPickResponse {
+            ok: false,
+            commit: req.commit,
+            head: None,
+            message: if message.is_empty() { String::from_utf8_lossy(&stdout).trim().to_string() } else { message },
+        }));
+    }
+
+    let head = String::from_utf8(run_git(&repo, &["rev-parse", "HEAD"]).map_err(internal)?).map_err(internal)?.trim().to_string();
+    Ok(Json(ReviewCherryPickResponse {
+        ok: true,
+        commit: req.commit,
+        head: Some(head),
+        message: String::from_utf8_lossy(&stdout).trim().to_string(),
+    }))
+}
+
+async fn review_reflog(
+    Json(req): Json<ReviewReflogRequest>,
+) -> Result<Json<ReviewReflogResponse>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let max_count = format!("--max-count={}", req.limit.unwrap_or(200).clamp(1, 2000));
+    let (code, stdout, _stderr) = run_git_allow_fail(
+        &repo,
+        &["reflog", "show", &max_count, "--date=iso-strict", "--format=%H%x1f%h%x1f%gd%x1f%gs%x1f%s", "HEAD"],
+    )
+    .map_err(internal)?;
+    if code != 0 {
+        return Ok(Json(ReviewReflogResponse { ok: true, entries: Vec::new() }));
+    }
+
+    let reachable = String::from_utf8(run_git(&repo, &["rev-list", "--all"]).map_err(internal)?)
+        .map_err(internal)?
+        .lines()
+        .map(str::to_string)
+        .collect::<BTreeSet<_>>();
+
+    let mut entries = Vec::new();
+    for line in String::from_utf8(stdout).map_err(internal)?.lines() {
+        let fields: Vec<&str> = line.split('\x1f').collect();
+        if fields.len() < 5 {
+            continue;
+        }
+        let (action, message) = fields[3].split_once(": ").unwrap_or(("", fields[3]));
+        entries.push(ReviewReflogEntry {
+            selector: format!("HEAD@{{{}}}", entries.len()),
+            sha: fields[0].to_string(),
+            short_sha: fields[1].to_string(),
+            action: action.to_string(),
+            message: message.to_string(),
+            subject: fields[4].to_string(),
+            recorded_at: fields[2].trim_start_matches("HEAD@{").trim_end_matches('}').to_string(),
+            reachable: reachable.contains(fields[0]),
+        });
+    }
+
+    Ok(Json(ReviewReflogResponse { ok: true, entries }))
+}
+
+async fn review_create_branch(
+    Json(req): Json<ReviewCreateBranchRequest>,
+) -> Result<Json<ReviewCreateBranchResponse>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let name = req.name.trim();
+    if name.is_empty() || name.starts_with('-') {
+        return Err((axum::http::StatusCode::BAD_REQUEST, format!("invalid branch name {name:?}")).into());
+    }
+    let (code, _stdout, _stderr) = run_git_allow_fail(&repo, &["check-ref-format", "--branch", name]).map_err(internal)?;
+    if code != 0 {
+        return Err((axum::http::StatusCode::BAD_REQUEST, format!("invalid branch name {name:?}")).into());
+    }
+    let start_point = match req.start_point.trim() {
+        "" => commit_ref(&repo, "HEAD")?,
+        start_point => commit_ref(&repo, start_point)?,
+    };
+
+    let (code, _stdout, stderr) = run_git_allow_fail(&repo, &["branch", name, &start_point]).map_err(internal)?;
+    if code != 0 {
+        return Err((axum::http::StatusCode::BAD_REQUEST, String::from_utf8_lossy(&stderr).trim().to_string()).into());
+    }
+
+    Ok(Json(ReviewCreateBranchResponse { ok: true, name: name.to_string(), sha: start_point }))
+}
+
 async fn review_commit_diff(
     Json(req): Json<ReviewCommitDiffRequest>,
-) -> Result<Json<ReviewCommitDiffResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewCommitDiffResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let from_ref = commit_parent_ref(&repo, &req.commit)?;
     let to_ref = req.commit.clone();
     let effective_context = if req.whole_file { 2147483647 } else { req.context_lines.unwrap_or(10).min(1000) };
     let unified_arg = format!("--unified={}", effective_context);
 
-    let mut args = vec!["diff".to_string(), unified_arg, from_ref.clone(), to_ref.clone()];
+    let mut args = vec!["diff".to_string(), unified_arg];
+    args.extend(whitespace_args(req.whitespace.as_deref())?);
+    args.extend([from_ref.clone(), to_ref.clone()]);
     if let Some(path) = req.path.as_deref().filter(|value| !value.trim().is_empty()) {
         args.push("--".to_string());
         args.push(path.to_string());
@@ -1153,7 +1673,7 @@ async fn review_commit_diff(
 
 async fn review_status(
     Json(req): Json<ReviewRepoRequest>,
-) -> Result<Json<ReviewStatusResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewStatusResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let status = git_status(&repo).map_err(internal)?;
 
@@ -1186,6 +1706,7 @@ async fn review_status(
                 index_status: file.index_status.clone(),
                 worktree_status: file.worktree_status.clone(),
                 untracked: file.untracked,
+                conflicted: file.conflicted,
             });
         }
 
@@ -1197,6 +1718,7 @@ async fn review_status(
                 index_status: file.index_status.clone(),
                 worktree_status: file.worktree_status.clone(),
                 untracked: file.untracked,
+                conflicted: file.conflicted,
             });
         }
     }
@@ -1215,18 +1737,18 @@ async fn review_status(
     }))
 }
 
-fn refs_for_scope(scope: &str) -> Result<(String, String, bool), (axum::http::StatusCode, String)> {
+fn refs_for_scope(scope: &str) -> Result<(String, String, bool), AppError> {
     match scope {
         "staged" => Ok(("HEAD".to_string(), "INDEX".to_string(), true)),
         "unstaged" => Ok(("INDEX".to_string(), "WORKTREE".to_string(), false)),
         other => Err((
             axum::http::StatusCode::BAD_REQUEST,
             format!("unsupported review diff scope {other}"),
-        )),
+        ).into()),
     }
 }
 
-fn git_patch_scope(scope: &str) -> Result<(GitPatchScope, String, String), (axum::http::StatusCode, String)> {
+fn git_patch_scope(scope: &str) -> Result<(GitPatchScope, String, String), AppError> {
     match scope {
         "staged" => Ok((GitPatchScope::Staged, "HEAD".to_string(), "INDEX".to_string())),
         "unstaged" => Ok((GitPatchScope::Unstaged, "INDEX".to_string(), "WORKTREE".to_string())),
@@ -1234,11 +1756,11 @@ fn git_patch_scope(scope: &str) -> Result<(GitPatchScope, String, String), (axum
         other => Err((
             axum::http::StatusCode::BAD_REQUEST,
             format!("unsupported git patch scope {other}"),
-        )),
+        ).into()),
     }
 }
 
-fn read_text_for_review_ref(repo: &std::path::Path, git_ref: &str, path: &str) -> Result<String, (axum::http::StatusCode, String)> {
+fn read_text_for_review_ref(repo: &std::path::Path, git_ref: &str, path: &str) -> Result<String, AppError> {
     let bytes = match git_ref {
         "WORKTREE" => match std::fs::read(repo.join(path)) {
             Ok(bytes) => bytes,
@@ -1272,7 +1794,7 @@ fn read_text_for_review_ref(repo: &std::path::Path, git_ref: &str, path: &str) -
 fn review_scope_entries(
     repo: &std::path::Path,
     scope: &str,
-) -> Result<(String, String, Vec<ReviewDiffManifestFileEntry>), (axum::http::StatusCode, String)> {
+) -> Result<(String, String, Vec<ReviewDiffManifestFileEntry>), AppError> {
     let (from_ref, to_ref, _use_cached) = refs_for_scope(scope)?;
     let status = git_status(repo).map_err(internal)?;
 
@@ -1285,6 +1807,7 @@ fn review_scope_entries(
         .map(|item| item.path.clone())
         .collect();
     let untracked_stats = git_untracked_line_stats(repo, &untracked_paths);
+    let substantive = SubstantiveChanges::load(repo, if scope == "staged" { &["--cached"] } else { &[] });
 
     let mut files = Vec::new();
     for file in status.files {
@@ -1310,6 +1833,11 @@ fn review_scope_entries(
             _ => (0, 0),
         };
 
+        let whitespace_only = if file.untracked {
+            None
+        } else {
+            substantive.as_ref().and_then(|changes| changes.classify(&file.path, additions, deletions))
+        };
         files.push(ReviewDiffManifestFileEntry {
             path: file.path.clone(),
             additions,
@@ -1317,6 +1845,7 @@ fn review_scope_entries(
             index_status: file.index_status.clone(),
             worktree_status: file.worktree_status.clone(),
             untracked: file.untracked,
+            whitespace_only,
         });
     }
 
@@ -1326,7 +1855,7 @@ fn review_scope_entries(
 
 async fn review_diff_manifest(
     Json(req): Json<ReviewDiffManifestRequest>,
-) -> Result<Json<ReviewDiffManifestResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewDiffManifestResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let status = git_status(&repo).map_err(internal)?;
     let staged_stats = git_diff_stats(&repo, true).unwrap_or_else(|_| HashMap::new());
@@ -1340,6 +1869,7 @@ async fn review_diff_manifest(
     let untracked_stats = git_untracked_line_stats(&repo, &untracked_paths);
 
     let (from_ref, to_ref, use_cached) = refs_for_scope(&req.scope)?;
+    let substantive = SubstantiveChanges::load(&repo, if use_cached { &["--cached"] } else { &[] });
     let mut files = Vec::new();
 
     for file in status.files {
@@ -1360,6 +1890,11 @@ async fn review_diff_manifest(
             unstaged_stats.get(&file.path).copied().unwrap_or((0, 0))
         };
 
+        let whitespace_only = if file.untracked {
+            None
+        } else {
+            substantive.as_ref().and_then(|changes| changes.classify(&file.path, counts.0, counts.1))
+        };
         files.push(ReviewDiffManifestFileEntry {
             path: file.path.clone(),
             additions: counts.0,
@@ -1367,6 +1902,7 @@ async fn review_diff_manifest(
             index_status: file.index_status.clone(),
             worktree_status: file.worktree_status.clone(),
             untracked: file.untracked,
+            whitespace_only,
         });
     }
 
@@ -1383,7 +1919,7 @@ async fn review_diff_manifest(
 
 async fn review_file_patch(
     Json(req): Json<ReviewFilePatchRequest>,
-) -> Result<Json<ReviewFilePatchResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewFilePatchResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let effective_context = if req.whole_file {
         2147483647
@@ -1398,6 +1934,7 @@ async fn review_file_patch(
         args.push("--cached".to_string());
     }
     args.push(unified_arg);
+    args.extend(whitespace_args(req.whitespace.as_deref())?);
     args.push("--".to_string());
     args.push(req.path.clone());
 
@@ -1417,7 +1954,7 @@ async fn review_file_patch(
 
 async fn review_diff(
     Json(req): Json<ReviewDiffRequest>,
-) -> Result<Json<ReviewDiffResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewDiffResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let effective_context = if req.whole_file {
         2147483647
@@ -1448,10 +1985,11 @@ async fn review_diff(
             return Err((
                 axum::http::StatusCode::BAD_REQUEST,
                 format!("unsupported review diff scope {other}"),
-            ));
+            ).into());
         }
     };
 
+    args.extend(whitespace_args(req.whitespace.as_deref())?);
     args.extend(path_args);
     let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
     let patch = String::from_utf8(run_git(&repo, &arg_refs).map_err(internal)?)
@@ -1469,7 +2007,7 @@ async fn review_diff(
 
 async fn review_git_patch(
     Json(req): Json<ReviewGitPatchRequest>,
-) -> Result<Json<ReviewGitPatchResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewGitPatchResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let (scope, from_ref, to_ref) = git_patch_scope(&req.scope)?;
     let patch = generate_git_apply_patch(
@@ -1496,7 +2034,7 @@ async fn review_git_patch(
 
 async fn review_multifile_contents(
     Json(req): Json<ReviewMultiFileContentsRequest>,
-) -> Result<Json<ReviewMultiFileContentsResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ReviewMultiFileContentsResponse>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     let (from_ref, to_ref, mut files) = review_scope_entries(&repo, &req.scope)?;
 
@@ -1532,11 +2070,11 @@ async fn review_multifile_contents(
 
 async fn review_stage(
     Json(req): Json<ReviewStageActionRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     match req.path.as_deref().filter(|value| !value.trim().is_empty()) {
         Some(path) => {
-            run_git(&repo, &["add", "--", path]).map_err(internal)?;
+            run_index_op(repo, &["add", "--", path]).await?;
         }
         None => {
             let target = match req.scope.as_str() {
@@ -1546,32 +2084,331 @@ async fn review_stage(
                     return Err((
                         axum::http::StatusCode::BAD_REQUEST,
                         format!("unsupported review stage scope {other}"),
-                    ));
+                    ).into());
                 }
             };
-            run_git(&repo, &["add", "-A", "--", target]).map_err(internal)?;
+            run_index_op(repo, &["add", "-A", "--", target]).await?;
         }
     }
 
     Ok(Json(serde_json::json!({ "ok": true })))
 }
 
+async fn review_stage_lines(
+    Json(req): Json<ReviewStageLinesRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let staged = git_stage_line_range(&repo, req.path.trim(), req.start_line, req.end_line)
+        .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+    Ok(Json(serde_json::json!({ "ok": true, "staged_lines": staged })))
+}
+
+async fn review_remote(
+    Path(action): Path<String>,
+    Json(req): Json<ReviewRemoteRequest>,
+) -> Result<Json<GitRemoteResult>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let remote = req.remote.as_deref().map(str::trim).filter(|value| !value.is_empty());
+    let branch = req.branch.as_deref().map(str::trim).filter(|value| !value.is_empty());
+    if let Some(value) = remote.into_iter().chain(branch).find(|value| value.starts_with('-')) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, format!("invalid remote or branch {value:?}")).into());
+    }
+    let credentials = req.credentials.as_ref();
+    let result = match action.as_str() {
+        "fetch" => git_fetch(&repo, remote, credentials),
+        "pull" => git_pull(&repo, remote, branch, credentials),
+        "push" => git_push(&repo, remote, branch, credentials),
+        other => {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("unsupported remote action {other}"),
+            ).into());
+        }
+    };
+    Ok(Json(result.map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")))?))
+}
+
 async fn review_unstage(
     Json(req): Json<ReviewStageActionRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let repo = PathBuf::from(&req.repo_ref);
     match req.path.as_deref().filter(|value| !value.trim().is_empty()) {
         Some(path) => {
-            run_git(&repo, &["restore", "--staged", "--", path]).map_err(internal)?;
+            run_index_op(repo, &["restore", "--staged", "--", path]).await?;
         }
         None => {
-            run_git(&repo, &["restore", "--staged", "."]).map_err(internal)?;
+            run_index_op(repo, &["restore", "--staged", "."]).await?;
         }
     }
 
     Ok(Json(serde_json::json!({ "ok": true })))
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+async fn review_index_lock(
+    Json(req): Json<ReviewRepoRequest>,
+) -> Result<Json<Option<IndexLockInfo>>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    Ok(Json(inspect_index_lock(&repo).map_err(internal)?))
+}
+
+/// Removes a leftover `index.lock`; refuses while a git process is still active in the repo
+/// or the lock is too fresh to be considered abandoned.
+async fn review_remove_index_lock(
+    Json(req): Json<ReviewRepoRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let removed = remove_stale_index_lock(&repo).map_err(|err| (axum::http::StatusCode::CONFLICT, format!("{err:#}")))?;
+    Ok(Json(serde_json::json!({ "ok": true, "removed": removed })))
+}
+
+/// Index operations back off while `index.lock` is held, so they run off the async workers.
+async fn run_index_op(repo: PathBuf, args: &[&str]) -> Result<(), AppError> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    tokio::task::spawn_blocking(move || run_git_index_op(&repo, &args.iter().map(String::as_str).collect::<Vec<_>>()))
+        .await
+        .map_err(internal)?
+        .map(|_| ())
+        .map_err(index_op_error)
+}
+
+/// Lock contention is reported as 423 with `error: "index_locked"`, which the client turns into the lock overlay.
+fn index_op_error(err: anyhow::Error) -> AppError {
+    match err.downcast::<IndexLockContention>() {
+        Ok(contention) => AppError::from_detail(axum::http::StatusCode::LOCKED, "", &contention.to_string())
+            .with_extra("error", "index_locked")
+            .with_extra("git_stderr", &contention.stderr)
+            .with_extra("lock", &contention.info),
+        Err(err) => internal(err),
+    }
+}
+
+fn discard_plan_for(req: &ReviewDiscardRequest) -> Result<(PathBuf, GitDiscardPlan, u64), AppError> {
+    let repo = PathBuf::from(&req.repo_ref);
+    let paths = req.paths.as_deref().filter(|paths| !paths.is_empty());
+    let plan = git_discard_plan(&repo, paths).map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+    let untracked_bytes = plan
+        .untracked
+        .iter()
+        .filter_map(|path| std::fs::symlink_metadata(repo.join(path)).ok())
+        .filter(|meta| meta.is_file())
+        .map(|meta| meta.len())
+        .sum();
+    Ok((repo, plan, untracked_bytes))
+}
+
+async fn review_discard_preview(
+    Json(req): Json<ReviewDiscardRequest>,
+) -> Result<Json<ReviewDiscardResponse>, AppError> {
+    let (_, plan, untracked_bytes) = discard_plan_for(&req)?;
+    Ok(Json(ReviewDiscardResponse { total: plan.total(), plan, untracked_bytes, history_id: None }))
+}
+
+/// Discards changes back to HEAD after snapshotting every affected file into the app history,
+/// so the discard can be undone. `record_files` also files a per-file trash entry for the repo,
+/// which outlives the bounded history; both point at the same blob store contents rather than
+/// keeping a second copy. Symlinks are backed up as links, never by copying their target.
+/// `expected_total` guards against the set changing after preview.
+async fn review_discard(
+    State(state): State<AppState>,
+    Json(req): Json<ReviewDiscardRequest>,
+) -> Result<Json<ReviewDiscardResponse>, AppError> {
+    let (repo, plan, untracked_bytes) = discard_plan_for(&req)?;
+    let total = plan.total();
+    if req.expected_total.is_some_and(|expected| expected != total) {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("the working tree changed since the preview ({total} paths now); review again before discarding"),
+        ).into());
+    }
+    if total == 0 {
+        return Ok(Json(ReviewDiscardResponse { plan, total, untracked_bytes, history_id: None }));
+    }
+
+    let files = history::snapshot_paths(&repo, &plan.all_paths()).map_err(internal)?;
+    let label = match plan.all_paths().as_slice() {
+        [only] => format!("Discard {only}"),
+        _ => format!("Discard {total} changes"),
+    };
+    let history_id = history::record_files(&state.db, "discard", &label, &req.repo_ref, files)
+        .await
+        .map_err(internal)?;
+    git_discard(&repo, &plan).map_err(internal)?;
+
+    Ok(Json(ReviewDiscardResponse { plan, total, untracked_bytes, history_id: Some(history_id) }))
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_repo(name: &str) -> PathBuf {
+        let repo = std::env::temp_dir().join(format!("{name}-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        run_git(&repo, &["config", "user.name", "t"]).unwrap();
+        run_git(&repo, &["config", "user.email", "t@t"]).unwrap();
+        repo
+    }
+
+    fn commit_file(repo: &std::path::Path, name: &str, text: &str) -> String {
+        std::fs::write(repo.join(name), text).unwrap();
+        run_git(repo, &["add", name]).unwrap();
+        run_git(repo, &["commit", "-q", "-m", name]).unwrap();
+        String::from_utf8(run_git(repo, &["rev-parse", "HEAD"]).unwrap()).unwrap().trim().to_string()
+    }
+
+    /// A repo whose `feature` branch is one commit (adding b.txt) ahead of the checked-out branch.
+    fn repo_with_feature(name: &str) -> (PathBuf, String, String) {
+        let repo = init_repo(name);
+        let base = commit_file(&repo, "a.txt", "a\n");
+        run_git(&repo, &["checkout", "-q", "-b", "feature"]).unwrap();
+        let feature = commit_file(&repo, "b.txt", "b\n");
+        run_git(&repo, &["checkout", "-q", "-"]).unwrap();
+        (repo, base, feature)
+    }
+
+    #[tokio::test]
+    async fn compare_resolves_both_sides_to_commits() {
+        let (repo, base, feature) = repo_with_feature("review-compare");
+        let repo_ref = repo.to_string_lossy().to_string();
+        let compare = |base: &str, head: &str| {
+            review_compare(Json(ReviewCompareRequest { repo_ref: repo_ref.clone(), base: base.to_string(), head: head.to_string() }))
+        };
+
+        let Json(response) = compare(&base, "feature").await.unwrap();
+        assert_eq!((response.ahead, response.behind), (1, 0));
+        assert_eq!(response.merge_base.as_deref(), Some(base.as_str()));
+        assert_eq!(response.ahead_commits[0].sha, feature);
+        assert_eq!(response.files.iter().map(|file| file.path.as_str()).collect::<Vec<_>>(), ["b.txt"]);
+
+        let marker = repo.join("pwned");
+        let output = format!("--output={}", marker.display());
+        assert_eq!(compare("HEAD", &output).await.unwrap_err().status, axum::http::StatusCode::BAD_REQUEST);
+        assert_eq!(compare(&output, "HEAD").await.unwrap_err().status, axum::http::StatusCode::BAD_REQUEST);
+        assert_eq!(compare("HEAD", "WORKTREE").await.unwrap_err().status, axum::http::StatusCode::BAD_REQUEST);
+        let diff = review_compare_diff(Json(ReviewCompareDiffRequest {
+            repo_ref: repo_ref.clone(),
+            base: "HEAD".to_string(),
+            head: output,
+            path: None,
+            context_lines: None,
+            whole_file: false,
+            whitespace: None,
+        }))
+        .await;
+        assert!(diff.is_err());
+        assert!(!marker.exists());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[tokio::test]
+    async fn cherry_pick_takes_a_commit_in_a_worktree() {
+        let (repo, _, feature) = repo_with_feature("review-cherry-pick");
+        let pick = |repo: &std::path::Path, commit: &str| {
+            review_cherry_pick(Json(ReviewCommitRequest { repo_ref: repo.to_string_lossy().to_string(), commit: commit.to_string() }))
+        };
+
+        assert_eq!(pick(&repo, "--quit").await.unwrap_err().status, axum::http::StatusCode::BAD_REQUEST);
+        assert_eq!(pick(&repo.join(".git"), &feature).await.unwrap_err().status, axum::http::StatusCode::BAD_REQUEST);
+        assert!(!repo.join("b.txt").exists());
+
+        let Json(response) = pick(&repo, "feature").await.unwrap();
+        assert!(response.ok);
+        assert_eq!(std::fs::read_to_string(repo.join("b.txt")).unwrap(), "b\n");
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[tokio::test]
+    async fn create_branch_refuses_option_like_names_and_start_points() {
+        let (repo, base, _) = repo_with_feature("review-branch");
+        let create = |name: &str, start_point: &str| {
+            review_create_branch(Json(ReviewCreateBranchRequest {
+                repo_ref: repo.to_string_lossy().to_string(),
+                name: name.to_string(),
+                start_point: start_point.to_string(),
+            }))
+        };
+
+        for (name, start_point) in [("-D", ""), ("--force", "feature"), ("topic", "--orphan"), ("bad..name", ""), ("topic", "no-such-ref")] {
+            assert_eq!(create(name, start_point).await.unwrap_err().status, axum::http::StatusCode::BAD_REQUEST, "{name} {start_point}");
+        }
+        let branches = String::from_utf8(run_git(&repo, &["branch", "--format=%(refname:short)"]).unwrap()).unwrap();
+        assert_eq!(branches.lines().count(), 2);
+
+        let Json(response) = create("topic", "").await.unwrap();
+        assert_eq!(response.sha, base);
+        let Json(response) = create("from-feature", "feature").await.unwrap();
+        assert_eq!(response.sha, String::from_utf8(run_git(&repo, &["rev-parse", "feature"]).unwrap()).unwrap().trim());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    fn stage_all(repo: &std::path::Path) -> impl std::future::Future<Output = Result<Json<serde_json::Value>, AppError>> {
+        review_stage(Json(ReviewStageActionRequest { repo_ref: repo.to_string_lossy().to_string(), scope: "unstaged".to_string(), path: None }))
+    }
+
+    #[tokio::test]
+    async fn index_ops_wait_for_the_lock_off_the_async_workers() {
+        let repo = init_repo("review-index-wait");
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        let lock = repo.join(".git/index.lock");
+        std::fs::write(&lock, "").unwrap();
+
+        // The test runtime has one worker thread, so the lock is only released if staging yields it.
+        let release = async {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            std::fs::remove_file(&lock).unwrap();
+        };
+        let (staged, ()) = tokio::join!(stage_all(&repo), release);
+        assert_eq!(staged.unwrap().0["ok"], serde_json::json!(true));
+        let index = String::from_utf8(run_git(&repo, &["diff", "--cached", "--name-only"]).unwrap()).unwrap();
+        assert_eq!(index.trim(), "a.txt");
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_held_index_lock_reaches_the_client_as_423() {
+        let repo = init_repo("review-index-locked");
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        std::fs::write(repo.join(".git/index.lock"), "").unwrap();
+
+        let err = stage_all(&repo).await.unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::LOCKED);
+        let body = serde_json::to_value(&err).unwrap();
+        assert_eq!(body["error"], "index_locked");
+        assert!(body["lock"]["lock_path"].as_str().unwrap().ends_with("index.lock"));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn discard_backs_up_untracked_links_as_links() {
+        crate::runtime_env::use_test_data_dir();
+        let repo = std::env::temp_dir().join(format!("review-discard-{}", uuid::Uuid::new_v4()));
+        let home = std::env::temp_dir().join(format!("review-discard-home-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::create_dir_all(&home).unwrap();
+        std::fs::write(home.join("big.bin"), vec![0u8; 4096]).unwrap();
+        run_git(&repo, &["init", "-q"]).unwrap();
+        std::os::unix::fs::symlink(&home, repo.join("home")).unwrap();
+        std::fs::write(repo.join("notes.txt"), "abc").unwrap();
+
+        let req = ReviewDiscardRequest { repo_ref: repo.to_string_lossy().to_string(), paths: None, expected_total: None };
+        let (_, plan, untracked_bytes) = discard_plan_for(&req).unwrap();
+        assert_eq!(plan.untracked, ["home", "notes.txt"]);
+        assert_eq!(untracked_bytes, 3);
+
+        let files = history::snapshot_paths(&repo, &plan.all_paths()).unwrap();
+        let link = files.iter().find(|file| file.path == "home").unwrap();
+        assert!(link.blob.is_none() && link.link.is_some());
+        assert!(files.iter().all(|file| !file.path.contains("big.bin")));
+
+        git_discard(&repo, &plan).unwrap();
+        assert!(home.join("big.bin").is_file());
+        std::fs::remove_dir_all(&repo).unwrap();
+        std::fs::remove_dir_all(&home).unwrap();
+    }
 }
@@ -1,16 +1,21 @@
 use axum::{extract::{Path, State}, routing::{get, post}, Json, Router};
 use chrono::Utc;
+use serde::Deserialize;
 use serde_json::{json, Map, Value};
 use sqlx::Row;
 use uuid::Uuid;
 
 use crate::{
+    app_error::AppError,
     db::new_workflow_key,
     app_state::AppState,
-    engine,
+    engine::{self, definition_migration::parse_definition},
     models::{CreateRunRequest, RunActionRequest, RunStatus, WorkflowEvent, WorkflowRun, WorkflowTemplateDefinition},
 };
 
+/// Full chat exports hold every conversation in one file.
+const CONVERSATION_EXPORT_LIMIT: usize = 256 * 1024 * 1024;
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/workflow-runs", get(list_runs).post(create_run))
@@ -18,9 +23,49 @@ pub fn router() -> Router<AppState> {
         .route("/api/workflow-runs/:run_id/open", post(open_run))
         .route("/api/workflow-runs/:run_id/events", get(list_run_events))
         .route("/api/workflow-runs/:run_id/actions", post(run_action))
+        .route("/api/workflow-runs/:run_id/review", post(review_run))
+        .route("/api/review-queue", get(list_review_queue))
+        .route(
+            "/api/conversation-import/preview",
+            post(preview_conversation_import).layer(axum::extract::DefaultBodyLimit::max(CONVERSATION_EXPORT_LIMIT)),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewDecisionRequest {
+    pub decision: String,
+    #[serde(default)]
+    pub comment: String,
+}
+
+async fn list_review_queue(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<engine::review_queue::ReviewQueueItem>>, AppError> {
+    let items = engine::review_queue::list_review_queue(&state).await.map_err(internal)?;
+    Ok(Json(items))
 }
 
-async fn list_runs(State(state): State<AppState>) -> Result<Json<Vec<WorkflowRun>>, (axum::http::StatusCode, String)> {
+async fn review_run(
+    State(state): State<AppState>,
+    Path(run_id): Path<Uuid>,
+    Json(req): Json<ReviewDecisionRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let response = match req.decision.as_str() {
+        "approve" => engine::review_queue::approve_review(&state, run_id).await.map_err(internal)?,
+        "reject" => {
+            if req.comment.trim().is_empty() {
+                return Err((axum::http::StatusCode::BAD_REQUEST, "comment required to reject".to_string()).into());
+            }
+            engine::review_queue::reject_review(&state, run_id, &req.comment).await.map_err(internal)?
+        }
+        other => {
+            return Err((axum::http::StatusCode::BAD_REQUEST, format!("unknown review decision {}", other)).into());
+        }
+    };
+    Ok(Json(response))
+}
+
+async fn list_runs(State(state): State<AppState>) -> Result<Json<Vec<WorkflowRun>>, AppError> {
     let rows = sqlx::query(
         "SELECT id, template_id, definition_json, status, current_step_id, title, repo_ref, workflow_key, context_json, created_at, updated_at FROM workflow_runs ORDER BY updated_at DESC"
     )
@@ -35,7 +80,7 @@ async fn list_runs(State(state): State<AppState>) -> Result<Json<Vec<WorkflowRun
 async fn get_run(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
-) -> Result<Json<WorkflowRun>, (axum::http::StatusCode, String)> {
+) -> Result<Json<WorkflowRun>, AppError> {
     let row = sqlx::query(
         "SELECT id, template_id, definition_json, status, current_step_id, title, repo_ref, workflow_key, context_json, created_at, updated_at FROM workflow_runs WHERE id = ?"
     )
@@ -50,7 +95,7 @@ async fn get_run(
 async fn open_run(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
-) -> Result<Json<WorkflowRun>, (axum::http::StatusCode, String)> {
+) -> Result<Json<WorkflowRun>, AppError> {
     let row = sqlx::query(
         "SELECT id, template_id, definition_json, status, current_step_id, title, repo_ref, workflow_key, context_json, created_at, updated_at FROM workflow_runs WHERE id = ?"
     )
@@ -65,7 +110,7 @@ async fn open_run(
 async fn list_run_events(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
-) -> Result<Json<Vec<WorkflowEvent>>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Vec<WorkflowEvent>>, AppError> {
     let rows = sqlx::query(
         "SELECT id, run_id, step_id, level, kind, message, payload_json, created_at FROM workflow_events WHERE run_id = ? ORDER BY sequence_no ASC, created_at ASC"
     )
@@ -81,7 +126,7 @@ async fn list_run_events(
 async fn delete_run(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, AppError> {
     sqlx::query("DELETE FROM workflow_events WHERE run_id = ?")
         .bind(run_id.to_string())
         .execute(&state.db)
@@ -101,7 +146,7 @@ async fn run_action(
     State(state): State<AppState>,
     Path(run_id): Path<Uuid>,
     Json(req): Json<RunActionRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let action = req.action.as_str();
     let requested_step_id = req.step_id.clone();
 
@@ -150,6 +195,14 @@ async fn run_action(
         "patch_global_state" => {
             engine::patch_global_state(&state, run_id, req.payload).await.map_err(internal)?
         }
+        "save_notes" => {
+            engine::save_run_notes(&state, run_id, req.payload).await.map_err(internal)?
+        }
+        "import_conversation" => {
+            engine::conversation_import::import_conversation(&state, run_id, req.payload)
+                .await
+                .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")))?
+        }
         "patch_stage_state" => {
             let step_id = req.step_id.as_deref().ok_or_else(|| (axum::http::StatusCode::BAD_REQUEST, "step_id required".to_string()))?;
             engine::patch_stage_state(&state, run_id, step_id, req.payload).await.map_err(internal)?
@@ -194,7 +247,7 @@ async fn run_action(
             engine::select_step(&state, run_id, &previous_id).await.map_err(internal)?
         }
         other => {
-            return Err((axum::http::StatusCode::BAD_REQUEST, format!("unsupported action {other}")));
+            return Err((axum::http::StatusCode::BAD_REQUEST, format!("unsupported action {other}")).into());
         }
     };
 
@@ -291,10 +344,19 @@ fn seed_governance_context_from_definition(context: &mut Value, definition: &Wor
     engine::merge_json_values(governance, &definition.governance);
 }
 
-async fn create_run(
+/// Parses a ChatGPT/Claude export so the user can pick which conversation to import.
+async fn preview_conversation_import(
+    Json(export): Json<Value>,
+) -> Result<Json<Vec<engine::conversation_import::ImportedConversation>>, AppError> {
+    engine::conversation_import::parse_export(&export)
+        .map(Json)
+        .map_err(|err| AppError::from((axum::http::StatusCode::BAD_REQUEST, format!("{err:#}"))))
+}
+
+pub(super) async fn create_run(
     State(state): State<AppState>,
     Json(req): Json<CreateRunRequest>,
-) -> Result<Json<WorkflowRun>, (axum::http::StatusCode, String)> {
+) -> Result<Json<WorkflowRun>, AppError> {
     let now = Utc::now();
     let id = Uuid::new_v4();
     let workflow_key = req
@@ -316,9 +378,10 @@ async fn create_run(
             .map_err(internal)?
             .ok_or_else(|| (axum::http::StatusCode::BAD_REQUEST, "template not found".to_string()))?;
         let definition_json: String = template_row.get("definition_json");
-        serde_json::from_str(&definition_json).map_err(internal)?
+        parse_definition(&definition_json)
+            .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("template cannot be loaded: {err:#}")))?
     } else {
-        return Err((axum::http::StatusCode::BAD_REQUEST, "definition or template_id is required".to_string()));
+        return Err((axum::http::StatusCode::BAD_REQUEST, "definition or template_id is required".to_string()).into());
     };
 
     let mut run_context = req.context.clone();
@@ -494,7 +557,7 @@ fn compile_commands_from_checks(checks: &Value) -> Value {
     Value::Array(commands)
 }
 
-fn row_to_event(row: sqlx::sqlite::SqliteRow) -> Result<WorkflowEvent, (axum::http::StatusCode, String)> {
+fn row_to_event(row: sqlx::sqlite::SqliteRow) -> Result<WorkflowEvent, AppError> {
     Ok(WorkflowEvent {
         id: Uuid::parse_str(row.get::<String, _>("id").as_str()).map_err(internal)?,
         run_id: Uuid::parse_str(row.get::<String, _>("run_id").as_str()).map_err(internal)?,
@@ -507,11 +570,11 @@ fn row_to_event(row: sqlx::sqlite::SqliteRow) -> Result<WorkflowEvent, (axum::ht
     })
 }
 
-fn row_to_run(row: sqlx::sqlite::SqliteRow) -> Result<WorkflowRun, (axum::http::StatusCode, String)> {
+fn row_to_run(row: sqlx::sqlite::SqliteRow) -> Result<WorkflowRun, AppError> {
     Ok(WorkflowRun {
         id: Uuid::parse_str(row.get::<String, _>("id").as_str()).map_err(internal)?,
         template_id: row.get::<Option<String>, _>("template_id").map(|v| Uuid::parse_str(v.as_str())).transpose().map_err(internal)?,
-        definition: serde_json::from_str(row.get::<String, _>("definition_json").as_str()).map_err(internal)?,
+        definition: parse_definition(row.get::<String, _>("definition_json").as_str()).map_err(internal)?,
         status: match row.get::<String, _>("status").as_str() {
             "draft" => RunStatus::Waiting,
             "queued" => RunStatus::Queued,
@@ -532,6 +595,6 @@ fn row_to_run(row: sqlx::sqlite::SqliteRow) -> Result<WorkflowRun, (axum::http::
     })
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
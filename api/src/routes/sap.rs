@@ -1,6 +1,7 @@
 use axum::{routing::post, Json, Router};
 
 use crate::{
+    app_error::AppError,
     app_state::AppState,
     engine::capabilities::sap::runtime::{
         fetch_object_manifest,
@@ -20,16 +21,16 @@ pub fn router() -> Router<AppState> {
 
 async fn search(
     Json(req): Json<SearchRequest>,
-) -> Result<Json<SearchResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<SearchResponse>, AppError> {
     search_package_objects(req).map(Json).map_err(internal)
 }
 
 async fn object(
     Json(req): Json<ObjectRequest>,
-) -> Result<Json<ObjectResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<ObjectResponse>, AppError> {
     fetch_object_manifest(req).map(Json).map_err(internal)
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
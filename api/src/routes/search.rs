@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use axum::{extract::State, routing::post, Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::project_search::{self, ReplacePreview, SearchQuery, SearchResults},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/search", post(search))
+        .route("/api/search/export", post(export_results))
+        .route("/api/search/replace/preview", post(preview_replace))
+        .route("/api/search/replace", post(apply_replace))
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportRequest {
+    #[serde(flatten)]
+    query: SearchQuery,
+    #[serde(default = "default_export_format")]
+    format: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportResponse {
+    file_name: String,
+    contents: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplaceRequest {
+    #[serde(flatten)]
+    query: SearchQuery,
+    replacement: String,
+    /// Path to preview fingerprint for each file to rewrite; only used when applying.
+    #[serde(default)]
+    expected: BTreeMap<String, String>,
+}
+
+fn default_export_format() -> String {
+    "md".to_string()
+}
+
+async fn search(Json(query): Json<SearchQuery>) -> Result<Json<SearchResults>, AppError> {
+    tokio::task::spawn_blocking(move || project_search::search(&query))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn export_results(Json(req): Json<ExportRequest>) -> Result<Json<ExportResponse>, AppError> {
+    if !matches!(req.format.as_str(), "md" | "csv") {
+        return Err(bad_request(format!("unsupported export format: {}", req.format)));
+    }
+    tokio::task::spawn_blocking(move || {
+        let results = project_search::search(&req.query)?;
+        let contents = match req.format.as_str() {
+            "csv" => project_search::render_csv(&results),
+            _ => project_search::render_markdown(&req.query, &results),
+        };
+        Ok::<_, anyhow::Error>(ExportResponse { file_name: format!("search-results.{}", req.format), contents })
+    })
+    .await
+    .map_err(internal)?
+    .map(Json)
+    .map_err(bad_request)
+}
+
+async fn preview_replace(Json(req): Json<ReplaceRequest>) -> Result<Json<Vec<ReplacePreview>>, AppError> {
+    tokio::task::spawn_blocking(move || project_search::preview_replace(&req.query, &req.replacement))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(bad_request)
+}
+
+async fn apply_replace(
+    State(state): State<AppState>,
+    Json(req): Json<ReplaceRequest>,
+) -> Result<Json<Value>, AppError> {
+    project_search::apply_replace(&state.db, &req.query, &req.replacement, &req.expected)
+        .await
+        .map(Json)
+        .map_err(bad_request)
+}
+
+fn bad_request<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")).into()
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
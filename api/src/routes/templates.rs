@@ -1,19 +1,35 @@
 use axum::{extract::{Path, State}, routing::get, Json, Router};
 use chrono::Utc;
+use serde::Serialize;
 use serde_json::{json, Value};
 use sqlx::Row;
 use uuid::Uuid;
 
-use crate::{app_state::AppState, models::{CreateTemplateRequest, WorkflowTemplate, WorkflowTemplateDefinition}};
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::definition_migration::parse_definition,
+    models::{CreateTemplateRequest, WorkflowTemplate},
+};
+
+/// A stored template whose definition cannot be migrated to the current schema.
+#[derive(Debug, Serialize)]
+struct TemplateLoadIssue {
+    id: String,
+    name: String,
+    error: String,
+    raw: String,
+}
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/workflow-templates", get(list_templates).post(create_template))
+        .route("/api/workflow-templates/issues", get(list_template_issues))
         .route("/api/workflow-templates/:template_id", axum::routing::delete(delete_template))
         .route("/api/workflow-builder-contract", get(get_workflow_builder_contract))
 }
 
-async fn list_templates(State(state): State<AppState>) -> Result<Json<Vec<WorkflowTemplate>>, (axum::http::StatusCode, String)> {
+async fn list_templates(State(state): State<AppState>) -> Result<Json<Vec<WorkflowTemplate>>, AppError> {
     let rows = sqlx::query(
         "SELECT id, name, description, repo_ref, definition_json, created_at, updated_at FROM workflow_templates ORDER BY updated_at DESC"
     )
@@ -21,9 +37,12 @@ async fn list_templates(State(state): State<AppState>) -> Result<Json<Vec<Workfl
     .await
     .map_err(internal)?;
 
+    // Templates that fail to migrate are left out here and reported by `list_template_issues`.
     let mut out = Vec::with_capacity(rows.len());
     for row in rows {
-        let definition: WorkflowTemplateDefinition = serde_json::from_str(row.get::<String, _>("definition_json").as_str()).map_err(internal)?;
+        let Ok(definition) = parse_definition(row.get::<String, _>("definition_json").as_str()) else {
+            continue;
+        };
         out.push(WorkflowTemplate {
             id: parse_uuid(row.get("id"))?,
             name: row.get("name"),
@@ -38,58 +57,36 @@ async fn list_templates(State(state): State<AppState>) -> Result<Json<Vec<Workfl
     Ok(Json(out))
 }
 
+async fn list_template_issues(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<TemplateLoadIssue>>, AppError> {
+    let rows = sqlx::query("SELECT id, name, definition_json FROM workflow_templates ORDER BY updated_at DESC")
+        .fetch_all(&state.db)
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(
+        rows.into_iter()
+            .filter_map(|row| {
+                let raw: String = row.get("definition_json");
+                let error = parse_definition(&raw).err()?;
+                Some(TemplateLoadIssue { id: row.get("id"), name: row.get("name"), error: format!("{error:#}"), raw })
+            })
+            .collect(),
+    ))
+}
+
 async fn create_template(
     State(state): State<AppState>,
     Json(req): Json<CreateTemplateRequest>,
-) -> Result<Json<WorkflowTemplate>, (axum::http::StatusCode, String)> {
+) -> Result<Json<WorkflowTemplate>, AppError> {
     let now = Utc::now();
     let definition_json = serde_json::to_string_pretty(&req.definition).map_err(internal)?;
 
-    let existing = sqlx::query(
-        "SELECT id, created_at FROM workflow_templates WHERE name = ?"
-    )
-    .bind(&req.name)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(internal)?;
-
-    let (id, created_at) = if let Some(row) = existing {
-        let id = parse_uuid(row.get("id"))?;
-        let created_at = parse_ts(row.get("created_at"))?;
-
-        sqlx::query(
-            "UPDATE workflow_templates SET description = ?, repo_ref = ?, definition_json = ?, updated_at = ? WHERE id = ?"
-        )
-        .bind(&req.description)
-        .bind(&req.repo_ref)
-        .bind(&definition_json)
-        .bind(now.to_rfc3339())
-        .bind(id.to_string())
-        .execute(&state.db)
-        .await
-        .map_err(internal)?;
-
-        (id, created_at)
-    } else {
-        let id = Uuid::new_v4();
-
-        sqlx::query(
-            "INSERT INTO workflow_templates (id, name, description, repo_ref, definition_json, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
-        )
-        .bind(id.to_string())
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(&req.repo_ref)
-        .bind(&definition_json)
-        .bind(now.to_rfc3339())
-        .bind(now.to_rfc3339())
-        .execute(&state.db)
+    let (id, created_at) = upsert_template(&state.db, &req.name, &req.description, &req.repo_ref, &definition_json)
         .await
         .map_err(internal)?;
 
-        (id, now)
-    };
-
     Ok(Json(WorkflowTemplate {
         id,
         name: req.name,
@@ -101,10 +98,56 @@ async fn create_template(
     }))
 }
 
+/// Inserts a template, or replaces the definition of the existing template with the same name.
+pub(super) async fn upsert_template(
+    db: &sqlx::SqlitePool,
+    name: &str,
+    description: &str,
+    repo_ref: &str,
+    definition_json: &str,
+) -> anyhow::Result<(Uuid, chrono::DateTime<Utc>)> {
+    let now = Utc::now();
+    let existing = sqlx::query("SELECT id, created_at FROM workflow_templates WHERE name = ?")
+        .bind(name)
+        .fetch_optional(db)
+        .await?;
+
+    if let Some(row) = existing {
+        let id = Uuid::parse_str(row.get::<String, _>("id").as_str())?;
+        let created_at = chrono::DateTime::parse_from_rfc3339(row.get::<String, _>("created_at").as_str())?.with_timezone(&Utc);
+        sqlx::query(
+            "UPDATE workflow_templates SET description = ?, repo_ref = ?, definition_json = ?, updated_at = ? WHERE id = ?"
+        )
+        .bind(description)
+        .bind(repo_ref)
+        .bind(definition_json)
+        .bind(now.to_rfc3339())
+        .bind(id.to_string())
+        .execute(db)
+        .await?;
+        return Ok((id, created_at));
+    }
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO workflow_templates (id, name, description, repo_ref, definition_json, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(id.to_string())
+    .bind(name)
+    .bind(description)
+    .bind(repo_ref)
+    .bind(definition_json)
+    .bind(now.to_rfc3339())
+    .bind(now.to_rfc3339())
+    .execute(db)
+    .await?;
+    Ok((id, now))
+}
+
 async fn delete_template(
     State(state): State<AppState>,
     Path(template_id): Path<Uuid>,
-) -> Result<Json<Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Value>, AppError> {
     let result = sqlx::query("DELETE FROM workflow_templates WHERE id = ?")
         .bind(template_id.to_string())
         .execute(&state.db)
@@ -112,7 +155,7 @@ async fn delete_template(
         .map_err(internal)?;
 
     if result.rows_affected() == 0 {
-        return Err((axum::http::StatusCode::NOT_FOUND, "Template not found".to_string()));
+        return Err((axum::http::StatusCode::NOT_FOUND, "Template not found".to_string()).into());
     }
 
     Ok(Json(json!({ "ok": true })))
@@ -120,7 +163,7 @@ async fn delete_template(
 
 async fn get_workflow_builder_contract(
     State(_state): State<AppState>,
-) -> Result<Json<Value>, (axum::http::StatusCode, String)> {
+) -> Result<Json<Value>, AppError> {
     Ok(Json(json!({
         "version": 1,
         "stages": [
@@ -302,16 +345,16 @@ async fn get_workflow_builder_contract(
     })))
 }
 
-fn parse_uuid(value: String) -> Result<Uuid, (axum::http::StatusCode, String)> {
+fn parse_uuid(value: String) -> Result<Uuid, AppError> {
     Uuid::parse_str(&value).map_err(internal)
 }
 
-fn parse_ts(value: String) -> Result<chrono::DateTime<Utc>, (axum::http::StatusCode, String)> {
+fn parse_ts(value: String) -> Result<chrono::DateTime<Utc>, AppError> {
     chrono::DateTime::parse_from_rfc3339(&value)
         .map(|dt| dt.with_timezone(&Utc))
         .map_err(internal)
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
@@ -0,0 +1,71 @@
+use axum::http::{header, HeaderMap, StatusCode};
+use serde_json::Value;
+
+use crate::{app_error::AppError, app_state::AppState, db::load_app_settings_section};
+
+/// Guards the token-protected endpoints (automation, MCP): the settings `section` must be enabled and the
+/// request must carry `Authorization: Bearer <section.token>`.
+pub async fn require_token(
+    state: &AppState,
+    headers: &HeaderMap,
+    section: &str,
+    disabled_message: &str,
+    label: &str,
+) -> Result<(), AppError> {
+    let settings = load_app_settings_section(&state.db, section)
+        .await
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")))?
+        .unwrap_or_default();
+    if !settings.get("enabled").and_then(Value::as_bool).unwrap_or(false) {
+        return Err((StatusCode::FORBIDDEN, disabled_message.to_string()).into());
+    }
+    let expected = settings.get("token").and_then(Value::as_str).unwrap_or_default();
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .trim();
+    if expected.is_empty() || !constant_time_eq(presented.as_bytes(), expected.as_bytes()) {
+        return Err((StatusCode::UNAUTHORIZED, format!("missing or invalid {label} token")).into());
+    }
+    Ok(())
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    use super::*;
+
+    async fn check(mcp: Value, authorization: Option<&str>) -> Result<(), AppError> {
+        let state = AppState::new(crate::db::test_db_with_settings(json!({ "mcp": mcp })).await);
+        let mut headers = HeaderMap::new();
+        if let Some(value) = authorization {
+            headers.insert(header::AUTHORIZATION, HeaderValue::from_str(value).unwrap());
+        }
+        require_token(&state, &headers, "mcp", "MCP is disabled", "MCP").await
+    }
+
+    #[tokio::test]
+    async fn only_the_configured_bearer_token_passes() {
+        let enabled = json!({ "enabled": true, "token": "s3cret" });
+        check(enabled.clone(), Some("Bearer s3cret")).await.unwrap();
+        for presented in [None, Some("Bearer wrong"), Some("Bearer s3cre"), Some("s3cret"), Some("Basic s3cret")] {
+            assert_eq!(check(enabled.clone(), presented).await.unwrap_err().status, StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled_or_tokenless_sections_refuse_everyone() {
+        let disabled = check(json!({ "enabled": false, "token": "s3cret" }), Some("Bearer s3cret")).await.unwrap_err();
+        assert_eq!(disabled.status, StatusCode::FORBIDDEN);
+        let tokenless = check(json!({ "enabled": true, "token": "" }), Some("Bearer ")).await.unwrap_err();
+        assert_eq!(tokenless.status, StatusCode::UNAUTHORIZED);
+    }
+}
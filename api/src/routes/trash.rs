@@ -0,0 +1,76 @@
+use axum::{extract::{Query, State}, routing::{get, post}, Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::{
+        history,
+        trash::{self, TrashEntry, TrashRestoreResult},
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/trash", get(list_trash))
+        .route("/api/trash/restore", post(restore_trash))
+        .route("/api/trash/purge", post(purge_trash))
+}
+
+#[derive(Debug, Deserialize)]
+struct TrashQuery {
+    repo_ref: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestoreRequest {
+    repo_ref: String,
+    id: String,
+    #[serde(default)]
+    paths: Option<Vec<String>>,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PurgeRequest {
+    repo_ref: String,
+    #[serde(default)]
+    id: Option<String>,
+}
+
+async fn list_trash(
+    State(state): State<AppState>,
+    Query(query): Query<TrashQuery>,
+) -> Result<Json<Value>, AppError> {
+    let retention_days = trash::retention_days(&state.db).await;
+    let entries: Vec<TrashEntry> = trash::list(query.repo_ref.trim(), retention_days).map_err(internal)?;
+    Ok(Json(json!({
+        "repo_ref": query.repo_ref,
+        "retention_days": retention_days,
+        "entries": entries,
+    })))
+}
+
+async fn restore_trash(
+    Json(req): Json<RestoreRequest>,
+) -> Result<Json<TrashRestoreResult>, AppError> {
+    trash::restore(req.repo_ref.trim(), req.id.trim(), req.paths.as_deref(), req.overwrite)
+        .map(Json)
+        .map_err(|err| AppError::from((axum::http::StatusCode::BAD_REQUEST, format!("{:#}", err))))
+}
+
+async fn purge_trash(
+    State(state): State<AppState>,
+    Json(req): Json<PurgeRequest>,
+) -> Result<Json<Value>, AppError> {
+    let purged = trash::purge(req.repo_ref.trim(), req.id.as_deref().map(str::trim))
+        .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{:#}", err)))?;
+    history::sweep_blobs(&state.db).await.map_err(internal)?;
+    Ok(Json(json!({ "ok": true, "purged": purged })))
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
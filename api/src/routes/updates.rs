@@ -0,0 +1,34 @@
+use axum::{extract::State, routing::{get, post}, Json, Router};
+
+use crate::{
+    app_error::AppError,
+    app_state::AppState,
+    engine::updates::{self, UpdateStatus},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/updates", get(update_status))
+        .route("/api/updates/check", post(check_updates))
+        .route("/api/updates/stage", post(stage_update))
+}
+
+async fn update_status() -> Json<UpdateStatus> {
+    Json(updates::status())
+}
+
+async fn check_updates(
+    State(state): State<AppState>,
+) -> Result<Json<UpdateStatus>, AppError> {
+    Ok(Json(updates::check(&state.db).await.map_err(internal)?))
+}
+
+async fn stage_update(
+    State(state): State<AppState>,
+) -> Result<Json<UpdateStatus>, AppError> {
+    Ok(Json(updates::stage(&state.db).await.map_err(internal)?))
+}
+
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into()
+}
@@ -1,11 +1,19 @@
+use std::path::Path;
+
 use axum::{extract::State, routing::{get, post}, Json, Router};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use super::ai_actions::AiModelRequest;
 use crate::{
+    app_error::AppError,
     app_state::AppState,
-    engine::capabilities::inference::stage_support::{
-        build_inference_execution_plan,
-        InferenceStageSettings,
+    engine::{
+        capabilities::inference::stage_support::{
+            build_inference_execution_plan,
+            InferenceStageSettings,
+        },
+        test_generation::{self, TestGenerationPlan},
     },
     models::{
         AutomationMode,
@@ -38,27 +46,128 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/workflow-builder-catalog", get(get_workflow_builder_catalog))
         .route("/api/workflow-builder/compile", post(compile_workflow_builder))
+        .route("/api/workflow-builder/task-templates/generate-tests", post(generate_tests_task))
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateTestsRequest {
+    repo_ref: String,
+    path: String,
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(default, flatten)]
+    model: AiModelRequest,
 }
 
+/// A ready-to-run "Generate tests" workflow; the client creates the run from `definition` and `context`.
+#[derive(Debug, Serialize)]
+struct GenerateTestsTask {
+    title: String,
+    plan: TestGenerationPlan,
+    definition: WorkflowTemplateDefinition,
+    context: Value,
+}
+
+/// Test-generation loops stop after this many write-and-run passes unless the tests pass first.
+const GENERATE_TESTS_MAX_ITERATIONS: u64 = 5;
+
 async fn get_workflow_builder_catalog(
     State(_state): State<AppState>,
-) -> Result<Json<WorkflowBuilderCatalog>, (axum::http::StatusCode, String)> {
+) -> Result<Json<WorkflowBuilderCatalog>, AppError> {
     Ok(Json(default_builder_catalog()))
 }
 
 async fn compile_workflow_builder(
     State(_state): State<AppState>,
     Json(req): Json<CompileWorkflowBuilderRequest>,
-) -> Result<Json<CompileWorkflowBuilderResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Json<CompileWorkflowBuilderResponse>, AppError> {
     let catalog = default_builder_catalog();
     let compiled = compile_document(&catalog, req.document)?;
     Ok(Json(compiled))
 }
 
+/// Builds a write tests → run tests → review loop for one source file. Failing test runs move back to the code
+/// stage with the output in its prompt, until the tests pass or a run guard stops the loop.
+async fn generate_tests_task(
+    State(_state): State<AppState>,
+    Json(req): Json<GenerateTestsRequest>,
+) -> Result<Json<GenerateTestsTask>, AppError> {
+    let repo = Path::new(&req.repo_ref);
+    let plan = test_generation::plan(repo, &req.path, req.symbol.as_deref())
+        .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+
+    let mut globals = default_globals();
+    let mut include_files = vec![plan.path.clone()];
+    include_files.extend(
+        [Some(&plan.test_path), plan.reference_test.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter(|file| repo.join(file).is_file())
+            .cloned(),
+    );
+    include_files.dedup();
+    globals.capabilities["context_export"]["enabled"] = json!(true);
+    globals.capabilities["context_export"]["include_files"] = json!(include_files);
+    let config = req.model.config();
+    if req.model.model.is_some() || req.model.generation.is_some() {
+        globals.capabilities["inference"] = json!({ "model": config.model, "generation": config.generation });
+    }
+    globals.automation = json!({
+        "stop_conditions": {
+            "max_iterations": GENERATE_TESTS_MAX_ITERATIONS,
+            "stop_after_consecutive_passes": 1
+        }
+    });
+
+    let document = WorkflowBuilderDocument {
+        version: 1,
+        globals,
+        governance: json!({}),
+        stages: vec![
+            WorkflowBuilderStageDocument {
+                id: "write_tests".to_string(),
+                name: "Write tests".to_string(),
+                step_type: "code".to_string(),
+                field_values: json!({ "automation.auto_apply_changeset": true }),
+            },
+            WorkflowBuilderStageDocument {
+                id: "run_tests".to_string(),
+                name: "Run tests".to_string(),
+                step_type: "compile".to_string(),
+                field_values: json!({ "execution.compile_checks.commands_text": plan.test_command }),
+            },
+            WorkflowBuilderStageDocument {
+                id: "review".to_string(),
+                name: "Review".to_string(),
+                step_type: "review".to_string(),
+                field_values: json!({}),
+            },
+        ],
+    };
+    let compiled = compile_document(&default_builder_catalog(), document)?;
+    if !compiled.ok {
+        return Err(internal(compiled.errors.join("; ")));
+    }
+
+    let title = match &plan.symbol {
+        Some(symbol) => format!("Generate tests for {symbol}"),
+        None => format!("Generate tests for {}", plan.path),
+    };
+    // Stage user input lives in the run's stage overrides, which is where the code stage reads it from.
+    let context = json!({
+        "workflow_engine": {
+            "stage_overrides": {
+                "write_tests": { "prompt": { "user_input": plan.instructions } }
+            }
+        }
+    });
+    Ok(Json(GenerateTestsTask { title, plan, definition: compiled.definition, context }))
+}
+
 fn compile_document(
     catalog: &WorkflowBuilderCatalog,
     document: WorkflowBuilderDocument,
-) -> Result<CompileWorkflowBuilderResponse, (axum::http::StatusCode, String)> {
+) -> Result<CompileWorkflowBuilderResponse, AppError> {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
     let mut steps = Vec::with_capacity(document.stages.len());
@@ -97,6 +206,7 @@ fn compile_document(
             governance: compile_governance(&catalog, &document.governance)
                 .map_err(|err| (axum::http::StatusCode::BAD_REQUEST, err))?,
             steps,
+            extra: Default::default(),
         },
         capability_summary,
         warnings,
@@ -941,6 +1051,6 @@ fn is_empty_object(value: &Value) -> bool {
     value.as_object().map(|obj| obj.is_empty()).unwrap_or(true)
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (axum::http::StatusCode, String) {
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
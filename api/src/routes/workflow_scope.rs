@@ -3,6 +3,7 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::{
+    app_error::AppError,
     app_state::AppState,
     engine,
     models::{WorkflowRun, WorkflowStepDefinition},
@@ -21,7 +22,7 @@ pub struct WorkflowScope {
 pub async fn resolve_workflow_scope(
     state: &AppState,
     run_id: Uuid,
-) -> Result<WorkflowScope, (StatusCode, String)> {
+) -> Result<WorkflowScope, AppError> {
     let run = engine::load_run(state, run_id).await.map_err(internal)?;
     let root = run.context.get("workflow_engine").cloned().unwrap_or_else(|| json!({}));
     let global_state = root.get("global_state").cloned().unwrap_or_else(|| json!({}));
@@ -36,7 +37,7 @@ pub async fn resolve_workflow_scope(
         .to_string();
 
     if repo_ref.is_empty() {
-        return Err((StatusCode::BAD_REQUEST, "workflow has no repo resource".to_string()));
+        return Err((StatusCode::BAD_REQUEST, "workflow has no repo resource".to_string()).into());
     }
 
     let git_ref = repo_resource
@@ -78,6 +79,6 @@ pub async fn resolve_workflow_scope(
     })
 }
 
-fn internal<E: std::fmt::Display>(err: E) -> (StatusCode, String) {
-    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+fn internal<E: std::fmt::Display>(err: E) -> AppError {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
 }
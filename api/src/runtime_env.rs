@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf, sync::OnceLock};
 
 use anyhow::{Context, Result};
 
@@ -31,3 +31,24 @@ pub fn default_browser_cdp_url() -> Result<String> {
 pub fn default_browser_bridge_url() -> Result<String> {
     env_http_url("WORKFLOW_BROWSER_BRIDGE_HOST", "WORKFLOW_BROWSER_BRIDGE_PORT")
 }
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_data_dir(path: PathBuf) {
+    let _ = DATA_DIR.set(path);
+}
+
+/// Points the data dir at a per-process temp directory so tests never write into the checkout.
+#[cfg(test)]
+pub fn use_test_data_dir() -> PathBuf {
+    set_data_dir(std::env::temp_dir().join(format!("mdev-test-data-{}", std::process::id())));
+    data_dir()
+}
+
+/// App data directory (`<repo>/.data`), falling back to `./.data` before startup sets it.
+pub fn data_dir() -> PathBuf {
+    DATA_DIR
+        .get()
+        .cloned()
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default().join(".data"))
+}
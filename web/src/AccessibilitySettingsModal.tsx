@@ -0,0 +1,64 @@
+import { useEffect, useState } from 'react';
+import { Button, Group, Modal, NumberInput, Stack, Switch, Text } from '@mantine/core';
+import { applyAccessibilityPreferences, type AccessibilityPreferences } from './accessibility';
+import { getAppSettings, patchAppSettings } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type AccessibilitySettingsModalProps = {
+  opened: boolean;
+  onClose: () => void;
+};
+
+const DEFAULT_PREFERENCES: AccessibilityPreferences = { high_contrast: false, min_font_size: 0 };
+
+export function AccessibilitySettingsModal(props: AccessibilitySettingsModalProps) {
+  const { opened, onClose } = props;
+  const [preferences, setPreferences] = useState<AccessibilityPreferences>(DEFAULT_PREFERENCES);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!opened) return;
+    setError(null);
+    void getAppSettings()
+      .then((settings) => setPreferences(settings.accessibility))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [opened]);
+
+  function update(patch: Partial<AccessibilityPreferences>) {
+    const next = { ...preferences, ...patch };
+    setPreferences(next);
+    applyAccessibilityPreferences(next);
+    void patchAppSettings({ accessibility: patch }).catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Accessibility" centered>
+      <Stack gap="sm">
+        {error ? <ErrorPanel message={error} /> : null}
+        <Switch
+          label="High-contrast theme"
+          description="Black surfaces, white text and borders, and a bright focus ring."
+          checked={preferences.high_contrast}
+          onChange={(event) => update({ high_contrast: event.currentTarget.checked })}
+        />
+        <NumberInput
+          label="Minimum font size (px)"
+          description="Small labels never render below this size. 0 keeps the theme defaults."
+          min={0}
+          max={24}
+          value={preferences.min_font_size}
+          onChange={(value) => {
+            const size = Number(value) || 0;
+            update({ min_font_size: size === 0 ? 0 : Math.min(24, Math.max(10, size)) });
+          }}
+        />
+        <Text size="xs" c="dimmed">
+          Keyboard: F6 cycles panes, Enter or Space activates focused rows, arrow keys move through the file tree, Esc closes overlays.
+        </Text>
+        <Group justify="flex-end">
+          <Button variant="default" onClick={onClose}>Close</Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
@@ -0,0 +1,160 @@
+import { useMemo, useState } from 'react';
+import { Badge, Box, Button, Card, Group, Text, TextInput } from '@mantine/core';
+import { IconSparkles } from '@tabler/icons-react';
+import { editSelectionWithAi, type AiActionModel, type AiActionResult } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type DiffRow = { kind: 'ctx' | 'add' | 'del'; text: string };
+
+const ROW_COLORS: Record<DiffRow['kind'], string | undefined> = {
+  add: 'rgba(46, 160, 67, 0.15)',
+  del: 'rgba(248, 81, 73, 0.15)',
+  ctx: undefined
+};
+
+/** Above this many line pairs the LCS table gets too big; fall back to "all removed, all added". */
+const MAX_LCS_CELLS = 250_000;
+
+/** Line-level LCS diff, small enough for the few dozen lines a selection usually spans. */
+export function lineDiff(before: string, after: string): DiffRow[] {
+  const a = before.replace(/\n$/, '').split('\n');
+  const b = after.replace(/\n$/, '').split('\n');
+  if (a.length * b.length > MAX_LCS_CELLS) {
+    return [...a.map((text) => ({ kind: 'del' as const, text })), ...b.map((text) => ({ kind: 'add' as const, text }))];
+  }
+  const lcs: number[][] = Array.from({ length: a.length + 1 }, () => new Array<number>(b.length + 1).fill(0));
+  for (let i = a.length - 1; i >= 0; i -= 1) {
+    for (let j = b.length - 1; j >= 0; j -= 1) {
+      lcs[i][j] = a[i] === b[j] ? lcs[i + 1][j + 1] + 1 : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+    }
+  }
+  const rows: DiffRow[] = [];
+  let i = 0;
+  let j = 0;
+  while (i < a.length && j < b.length) {
+    if (a[i] === b[j]) {
+      rows.push({ kind: 'ctx', text: a[i] });
+      i += 1;
+      j += 1;
+    } else if (lcs[i + 1][j] >= lcs[i][j + 1]) {
+      rows.push({ kind: 'del', text: a[i++] });
+    } else {
+      rows.push({ kind: 'add', text: b[j++] });
+    }
+  }
+  while (i < a.length) rows.push({ kind: 'del', text: a[i++] });
+  while (j < b.length) rows.push({ kind: 'add', text: b[j++] });
+  return rows;
+}
+
+export type AiSelectionEditTarget = {
+  path: string;
+  startLine: number;
+  endLine: number;
+  text: string;
+  before: string;
+  after: string;
+};
+
+type AiSelectionEditBarProps = {
+  target: AiSelectionEditTarget;
+  aiModel?: AiActionModel;
+  /** Applies the replacement; throw to keep the bar open with an error. */
+  onAccept: (replacement: string) => void;
+  onClose: () => void;
+};
+
+/** Inline "Edit selection with AI": instruction box, then a before/after diff of the selection with Accept/Reject. */
+export function AiSelectionEditBar(props: AiSelectionEditBarProps) {
+  const { target, aiModel, onAccept, onClose } = props;
+  const [instruction, setInstruction] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [result, setResult] = useState<AiActionResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const rows = useMemo(() => (result ? lineDiff(target.text, result.text) : []), [result, target.text]);
+  const unchanged = result !== null && result.text === target.text;
+
+  async function generate() {
+    if (!instruction.trim()) return;
+    setBusy(true);
+    setError(null);
+    setResult(null);
+    try {
+      setResult(
+        await editSelectionWithAi({
+          path: target.path,
+          selection: target.text,
+          instruction,
+          before: target.before,
+          after: target.after,
+          ...aiModel
+        })
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  function accept() {
+    if (!result) return;
+    try {
+      onAccept(result.text);
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  return (
+    <Card withBorder radius={0} p="sm" style={{ borderLeft: 0, borderRight: 0 }}>
+      <Group gap="xs" mb={result || error ? 'xs' : 0} wrap="nowrap">
+        <IconSparkles size={16} />
+        <Text size="sm" fw={600} style={{ whiteSpace: 'nowrap' }}>
+          Edit lines {target.startLine}{target.endLine !== target.startLine ? `-${target.endLine}` : ''}
+        </Text>
+        <TextInput
+          size="xs"
+          style={{ flex: 1 }}
+          data-autofocus
+          autoFocus
+          placeholder="Describe the change, e.g. extract a helper, add error handling"
+          value={instruction}
+          onChange={(event) => setInstruction(event.currentTarget.value)}
+          onKeyDown={(event) => {
+            if (event.key === 'Enter') void generate();
+            if (event.key === 'Escape') onClose();
+          }}
+        />
+        <Button size="xs" loading={busy} disabled={!instruction.trim()} onClick={() => void generate()}>
+          {result ? 'Regenerate' : 'Generate'}
+        </Button>
+        <Button size="xs" variant="default" onClick={onClose}>Cancel</Button>
+      </Group>
+      {error ? <ErrorPanel message={error} /> : null}
+      {result ? (
+        <>
+          <Box ff="monospace" fz="xs" mah={280} style={{ overflow: 'auto', border: '1px solid rgba(255,255,255,0.08)', borderRadius: 4 }}>
+            {unchanged ? (
+              <Text size="xs" c="dimmed" p="xs">The model returned the selection unchanged.</Text>
+            ) : (
+              rows.map((row, index) => (
+                <Text key={index} span display="block" fz="xs" px={6} style={{ whiteSpace: 'pre', background: ROW_COLORS[row.kind] }}>
+                  {row.kind === 'add' ? '+' : row.kind === 'del' ? '-' : ' '} {row.text}
+                </Text>
+              ))
+            )}
+          </Box>
+          <Group justify="space-between" mt="xs">
+            <Badge size="xs" variant="light">{result.model}</Badge>
+            <Group gap="xs">
+              <Button size="xs" variant="default" onClick={() => setResult(null)}>Reject</Button>
+              <Button size="xs" color="teal" disabled={unchanged} onClick={accept}>Accept</Button>
+            </Group>
+          </Group>
+        </>
+      ) : null}
+    </Card>
+  );
+}
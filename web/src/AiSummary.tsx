@@ -0,0 +1,92 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Badge, Box, Button, Card, Collapse, CopyButton, Group, Text, Tooltip } from '@mantine/core';
+import { IconChevronDown, IconChevronRight, IconSparkles, IconX } from '@tabler/icons-react';
+import type { AiActionResult } from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { MarkdownMessage } from './MarkdownMessage';
+
+export type AiSummaryState = ReturnType<typeof useAiSummary>;
+
+/** State for a one-click AI summary; `resetKey` clears it when the commit or scope being summarized changes. */
+export function useAiSummary(resetKey: string) {
+  const [busy, setBusy] = useState(false);
+  const [result, setResult] = useState<AiActionResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [expanded, setExpanded] = useState(true);
+
+  useEffect(() => {
+    setResult(null);
+    setError(null);
+  }, [resetKey]);
+
+  async function run(request: () => Promise<AiActionResult>) {
+    setBusy(true);
+    setError(null);
+    try {
+      setResult(await request());
+      setExpanded(true);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  function dismiss() {
+    setResult(null);
+    setError(null);
+  }
+
+  return { busy, result, error, expanded, setExpanded, run, dismiss };
+}
+
+export function AiSummaryButton(props: { summary: AiSummaryState; label: string; disabled?: boolean; request: () => Promise<AiActionResult> }) {
+  const { summary, label, disabled, request } = props;
+  return (
+    <Button size="xs" variant="light" leftSection={<IconSparkles size={14} />} loading={summary.busy} disabled={disabled} onClick={() => void summary.run(request)}>
+      {label}
+    </Button>
+  );
+}
+
+/** Collapsible answer with a copy button; renders nothing until a summary was requested. */
+export function AiSummaryCard(props: { summary: AiSummaryState; title: string }) {
+  const { summary, title } = props;
+  const { result, error, expanded } = summary;
+  if (!result && !error) return null;
+
+  return (
+    <Card withBorder radius="md" p="sm" mb="sm">
+      <Group justify="space-between" wrap="nowrap">
+        <Group gap="xs" wrap="nowrap">
+          <ActionIcon size="sm" variant="subtle" aria-label={expanded ? 'Collapse summary' : 'Expand summary'} onClick={() => summary.setExpanded((value) => !value)}>
+            {expanded ? <IconChevronDown size={14} /> : <IconChevronRight size={14} />}
+          </ActionIcon>
+          <Text size="sm" fw={600}>{title}</Text>
+          {result ? <Badge size="xs" variant="light">{result.model}</Badge> : null}
+          {result?.truncated ? (
+            <Tooltip label="The diff was too large; only its beginning was sent.">
+              <Badge size="xs" color="orange" variant="light">truncated</Badge>
+            </Tooltip>
+          ) : null}
+        </Group>
+        <Group gap={4} wrap="nowrap">
+          {result ? (
+            <CopyButton value={result.text}>
+              {({ copied, copy }) => <Button size="compact-xs" variant="subtle" onClick={copy}>{copied ? 'Copied' : 'Copy'}</Button>}
+            </CopyButton>
+          ) : null}
+          <ActionIcon size="sm" variant="subtle" aria-label="Dismiss summary" onClick={summary.dismiss}>
+            <IconX size={14} />
+          </ActionIcon>
+        </Group>
+      </Group>
+      <Collapse in={expanded}>
+        <Box mt="xs" mah={360} style={{ overflowY: 'auto' }}>
+          {error ? <ErrorPanel message={error} /> : null}
+          {result ? <MarkdownMessage text={result.text} /> : null}
+        </Box>
+      </Collapse>
+    </Card>
+  );
+}
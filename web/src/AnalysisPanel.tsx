@@ -0,0 +1,303 @@
+import { useEffect, useMemo, useState } from 'react';
+import { Alert, Badge, Button, Card, Group, Loader, ScrollArea, Stack, Table, Text, TextInput, Title } from '@mantine/core';
+import {
+  appendGitignorePattern,
+  exportDependencyInventory,
+  getArtifactFindings,
+  getDependencyInventory,
+  type AiActionModel,
+  type ArtifactFinding,
+  type DependencyEntry,
+} from './api';
+import { RepoOverviewCard } from './RepoOverviewCard';
+import { SizeTreemap } from './SizeTreemap';
+import { AnalysisSnapshotsCard } from './AnalysisSnapshotsCard';
+import { RangeAnalysisCard } from './RangeAnalysisCard';
+import { OwnershipCard } from './OwnershipCard';
+import { DuplicatesCard } from './DuplicatesCard';
+import { DocLinksCard } from './DocLinksCard';
+import { ErrorPanel } from './ErrorPanel';
+
+type AnalysisPanelProps = {
+  repoRef: string;
+  onAddExcludeRegex?: (pattern: string) => void;
+  onOpenFile?: (path: string) => void;
+  /** Model for the AI-written part of the repo overview. */
+  aiModel?: AiActionModel;
+  onExportContext?: (paths: string[], gitRef: string) => void;
+};
+
+type DependencySortKey = 'ecosystem' | 'name' | 'version' | 'license' | 'source_file';
+
+function formatBytes(bytes: number) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} B`;
+}
+
+function downloadTextFile(filename: string, contents: string, contentType: string) {
+  const blob = new Blob([contents], { type: contentType });
+  const url = URL.createObjectURL(blob);
+  const anchor = document.createElement('a');
+  anchor.href = url;
+  anchor.download = filename;
+  document.body.appendChild(anchor);
+  anchor.click();
+  anchor.remove();
+  URL.revokeObjectURL(url);
+}
+
+export function AnalysisPanel(props: AnalysisPanelProps) {
+  const { repoRef, onAddExcludeRegex, onOpenFile, aiModel, onExportContext } = props;
+  const [artifacts, setArtifacts] = useState<ArtifactFinding[]>([]);
+  const [artifactsLoading, setArtifactsLoading] = useState(false);
+  const [notice, setNotice] = useState<string | null>(null);
+  const [dependencies, setDependencies] = useState<DependencyEntry[]>([]);
+  const [dependencyWarnings, setDependencyWarnings] = useState<string[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [filter, setFilter] = useState('');
+  const [sortKey, setSortKey] = useState<DependencySortKey>('name');
+  const [sortAsc, setSortAsc] = useState(true);
+
+  async function loadDependencies() {
+    if (!repoRef.trim()) return;
+    setLoading(true);
+    setError(null);
+    try {
+      const response = await getDependencyInventory(repoRef);
+      setDependencies(response.dependencies);
+      setDependencyWarnings(response.warnings ?? []);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  async function loadArtifacts() {
+    if (!repoRef.trim()) return;
+    setArtifactsLoading(true);
+    try {
+      const response = await getArtifactFindings(repoRef);
+      setArtifacts(response.findings);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setArtifactsLoading(false);
+    }
+  }
+
+  async function addToGitignore(finding: ArtifactFinding) {
+    try {
+      setError(null);
+      const response = await appendGitignorePattern(repoRef, finding.gitignore_pattern);
+      setNotice(response.added ? `Added ${response.pattern} to .gitignore` : `${response.pattern} is already in .gitignore`);
+      await loadArtifacts();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  async function exportDependencies(format: 'csv' | 'json') {
+    try {
+      setError(null);
+      const response = await exportDependencyInventory(repoRef, format);
+      downloadTextFile(response.file_name, response.contents, format === 'csv' ? 'text/csv' : 'application/json');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    setDependencies([]);
+    setDependencyWarnings([]);
+    setArtifacts([]);
+    setNotice(null);
+    void loadArtifacts();
+    void loadDependencies();
+  }, [repoRef]);
+
+  const visibleDependencies = useMemo(() => {
+    const needle = filter.trim().toLowerCase();
+    const rows = needle
+      ? dependencies.filter((item) =>
+          [item.ecosystem, item.name, item.version, item.license ?? '', item.source_file].some((value) =>
+            value.toLowerCase().includes(needle)
+          )
+        )
+      : dependencies.slice();
+    rows.sort((a, b) => {
+      const left = String(a[sortKey] ?? '').toLowerCase();
+      const right = String(b[sortKey] ?? '').toLowerCase();
+      const cmp = left.localeCompare(right, undefined, { numeric: true });
+      return sortAsc ? cmp : -cmp;
+    });
+    return rows;
+  }, [dependencies, filter, sortKey, sortAsc]);
+
+  function toggleSort(key: DependencySortKey) {
+    if (key === sortKey) {
+      setSortAsc((prev) => !prev);
+    } else {
+      setSortKey(key);
+      setSortAsc(true);
+    }
+  }
+
+  function sortHeader(key: DependencySortKey, label: string) {
+    const marker = key === sortKey ? (sortAsc ? ' ▲' : ' ▼') : '';
+    return (
+      <Table.Th style={{ cursor: 'pointer', whiteSpace: 'nowrap' }} onClick={() => toggleSort(key)}>
+        {label}{marker}
+      </Table.Th>
+    );
+  }
+
+  return (
+    <Stack gap="md">
+      <RepoOverviewCard repoRef={repoRef} aiModel={aiModel} onOpenFile={onOpenFile} />
+      <SizeTreemap repoRef={repoRef} onOpenFile={onOpenFile} />
+      <AnalysisSnapshotsCard repoRef={repoRef} onOpenFile={onOpenFile} />
+      <DuplicatesCard repoRef={repoRef} onOpenFile={onOpenFile} />
+      <OwnershipCard repoRef={repoRef} />
+      <RangeAnalysisCard repoRef={repoRef} onOpenFile={onOpenFile} onExportContext={onExportContext} />
+      <DocLinksCard repoRef={repoRef} onOpenFile={onOpenFile} />
+      <Card withBorder>
+        <Stack gap="md">
+          <Group justify="space-between" align="flex-start" wrap="wrap">
+            <Stack gap={2}>
+              <Group gap="xs">
+                <Title order={4}>Build artifacts and large binaries</Title>
+                <Badge variant="light" color={artifacts.length ? 'orange' : 'green'}>{artifacts.length} findings</Badge>
+              </Group>
+              <Text size="sm" c="dimmed">Build output directories and blobs over 5 MB that are tracked or not yet ignored.</Text>
+            </Stack>
+            <Button variant="default" size="xs" onClick={() => void loadArtifacts()} loading={artifactsLoading}>Rescan</Button>
+          </Group>
+
+          {notice ? <Alert color="blue" withCloseButton onClose={() => setNotice(null)}>{notice}</Alert> : null}
+
+          {artifacts.length ? (
+            <Table striped highlightOnHover>
+              <Table.Thead>
+                <Table.Tr>
+                  <Table.Th>Path</Table.Th>
+                  <Table.Th>Kind</Table.Th>
+                  <Table.Th>Size</Table.Th>
+                  <Table.Th>Git</Table.Th>
+                  <Table.Th></Table.Th>
+                </Table.Tr>
+              </Table.Thead>
+              <Table.Tbody>
+                {artifacts.map((finding) => (
+                  <Table.Tr key={`${finding.kind}:${finding.path}`}>
+                    <Table.Td><Text size="sm">{finding.path}</Text></Table.Td>
+                    <Table.Td>
+                      <Badge variant="light" size="sm" color={finding.kind === 'large_blob' ? 'grape' : 'orange'}>
+                        {finding.kind === 'large_blob' ? 'large blob' : `artifact dir (${finding.file_count} files)`}
+                      </Badge>
+                    </Table.Td>
+                    <Table.Td><Text size="sm">{formatBytes(finding.bytes)}</Text></Table.Td>
+                    <Table.Td>
+                      <Group gap={4}>
+                        <Badge variant="outline" size="xs" color={finding.tracked ? 'red' : 'gray'}>{finding.tracked ? 'tracked' : 'untracked'}</Badge>
+                        {finding.gitignored ? <Badge variant="outline" size="xs" color="gray">ignored</Badge> : null}
+                      </Group>
+                    </Table.Td>
+                    <Table.Td>
+                      <Group gap={4} justify="flex-end" wrap="nowrap">
+                        {onAddExcludeRegex ? (
+                          <Button variant="subtle" size="compact-xs" onClick={() => {
+                            onAddExcludeRegex(finding.exclude_regex);
+                            setNotice(`Added ${finding.exclude_regex} to the repo context exclude regex list`);
+                          }}>
+                            Add to exclude regex
+                          </Button>
+                        ) : null}
+                        <Button variant="subtle" size="compact-xs" disabled={finding.gitignored} onClick={() => void addToGitignore(finding)}>
+                          Add to .gitignore
+                        </Button>
+                      </Group>
+                    </Table.Td>
+                  </Table.Tr>
+                ))}
+              </Table.Tbody>
+            </Table>
+          ) : (
+            <Text size="sm" c="dimmed">{artifactsLoading ? 'Scanning…' : 'No committed build artifacts or large binaries found.'}</Text>
+          )}
+        </Stack>
+      </Card>
+      <Card withBorder>
+        <Stack gap="md">
+          <Group justify="space-between" align="flex-start" wrap="wrap">
+            <Stack gap={2}>
+              <Group gap="xs">
+                <Title order={4}>Dependency inventory</Title>
+                <Badge variant="light">{dependencies.length} packages</Badge>
+              </Group>
+              <Text size="sm" c="dimmed">Parsed from Cargo.lock, package-lock.json and requirements.txt files in the repository.</Text>
+            </Stack>
+            <Group gap="xs">
+              <Button variant="default" size="xs" onClick={() => void loadDependencies()} loading={loading}>Refresh</Button>
+              <Button variant="default" size="xs" disabled={!dependencies.length} onClick={() => void exportDependencies('csv')}>Export CSV</Button>
+              <Button variant="default" size="xs" disabled={!dependencies.length} onClick={() => void exportDependencies('json')}>Export JSON</Button>
+            </Group>
+          </Group>
+
+          {!repoRef.trim() ? <Alert color="yellow">Select a workflow or provide a repo path first.</Alert> : null}
+          {error ? <ErrorPanel message={error} /> : null}
+          {dependencyWarnings.length ? (
+            <Alert color="yellow" title="Some lockfiles were skipped">
+              <Stack gap={2}>
+                {dependencyWarnings.map((warning) => <Text key={warning} size="xs">{warning}</Text>)}
+              </Stack>
+            </Alert>
+          ) : null}
+
+          <TextInput
+            size="xs"
+            placeholder="Filter by name, version, license or file"
+            value={filter}
+            onChange={(event) => setFilter(event.currentTarget.value)}
+          />
+
+          {loading ? (
+            <Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Scanning lockfiles…</Text></Group>
+          ) : (
+            <ScrollArea.Autosize mah={640}>
+              <Table striped highlightOnHover>
+                <Table.Thead>
+                  <Table.Tr>
+                    {sortHeader('ecosystem', 'Ecosystem')}
+                    {sortHeader('name', 'Name')}
+                    {sortHeader('version', 'Version')}
+                    {sortHeader('license', 'License')}
+                    {sortHeader('source_file', 'Source')}
+                  </Table.Tr>
+                </Table.Thead>
+                <Table.Tbody>
+                  {visibleDependencies.map((item) => (
+                    <Table.Tr key={`${item.source_file}:${item.ecosystem}:${item.name}:${item.version}`}>
+                      <Table.Td><Badge variant="light" size="sm">{item.ecosystem}</Badge></Table.Td>
+                      <Table.Td>
+                        <Group gap={6}>
+                          <Text size="sm">{item.name}</Text>
+                          {item.dev ? <Badge variant="outline" size="xs" color="gray">dev</Badge> : null}
+                        </Group>
+                      </Table.Td>
+                      <Table.Td><Text size="sm">{item.version}</Text></Table.Td>
+                      <Table.Td><Text size="sm" c={item.license ? undefined : 'dimmed'}>{item.license ?? 'undeclared'}</Text></Table.Td>
+                      <Table.Td><Text size="xs" c="dimmed">{item.source_file}</Text></Table.Td>
+                    </Table.Tr>
+                  ))}
+                </Table.Tbody>
+              </Table>
+            </ScrollArea.Autosize>
+          )}
+        </Stack>
+      </Card>
+    </Stack>
+  );
+}
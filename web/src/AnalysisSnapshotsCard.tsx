@@ -0,0 +1,260 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Badge, Button, Card, Group, NumberInput, ScrollArea, Select, SimpleGrid, Stack, Table, Text, TextInput, Title } from '@mantine/core';
+import { IconTrash } from '@tabler/icons-react';
+import {
+  compareAnalysisSnapshots,
+  createAnalysisSnapshot,
+  deleteAnalysisSnapshot,
+  listAnalysisSnapshots,
+  type AnalysisSnapshot,
+  type SnapshotComparison,
+} from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type AnalysisSnapshotsCardProps = {
+  repoRef: string;
+  onOpenFile?: (path: string) => void;
+};
+
+function signed(value: number) {
+  return value > 0 ? `+${value.toLocaleString()}` : value.toLocaleString();
+}
+
+function deltaColor(value: number) {
+  return value > 0 ? 'teal' : value < 0 ? 'red' : 'dimmed';
+}
+
+function percent(share: number) {
+  return `${(share * 100).toFixed(1)}%`;
+}
+
+function snapshotLabel(snapshot: AnalysisSnapshot) {
+  const ref = snapshot.git_ref === 'WORKTREE' ? 'worktree' : snapshot.git_ref;
+  return `${snapshot.name} · ${ref} · ${new Date(snapshot.created_at).toLocaleDateString()}`;
+}
+
+/** Named size/inventory snapshots per ref, and a diff between any two of them. */
+export function AnalysisSnapshotsCard(props: AnalysisSnapshotsCardProps) {
+  const { repoRef, onOpenFile } = props;
+  const [snapshots, setSnapshots] = useState<AnalysisSnapshot[]>([]);
+  const [name, setName] = useState('');
+  const [gitRef, setGitRef] = useState('WORKTREE');
+  const [baseId, setBaseId] = useState<string | null>(null);
+  const [headId, setHeadId] = useState<string | null>(null);
+  const [depth, setDepth] = useState(1);
+  const [comparison, setComparison] = useState<SnapshotComparison | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function load() {
+    if (!repoRef.trim()) return;
+    try {
+      setSnapshots(await listAnalysisSnapshots(repoRef));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    setSnapshots([]);
+    setComparison(null);
+    setBaseId(null);
+    setHeadId(null);
+    void load();
+  }, [repoRef]);
+
+  async function run(action: () => Promise<void>) {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  const save = () =>
+    run(async () => {
+      const snapshot = await createAnalysisSnapshot(repoRef, name.trim(), gitRef.trim() || 'WORKTREE');
+      setName('');
+      await load();
+      if (baseId) setHeadId(snapshot.id);
+      else setBaseId(snapshot.id);
+    });
+
+  const remove = (id: string) =>
+    run(async () => {
+      await deleteAnalysisSnapshot(id);
+      if (baseId === id) setBaseId(null);
+      if (headId === id) setHeadId(null);
+      setComparison(null);
+      await load();
+    });
+
+  const compare = () =>
+    run(async () => {
+      if (!baseId || !headId) return;
+      setComparison(await compareAnalysisSnapshots(baseId, headId, depth));
+    });
+
+  const options = snapshots.map((snapshot) => ({ value: snapshot.id, label: snapshotLabel(snapshot) }));
+  const changedDirectories = (comparison?.directories ?? []).filter(
+    (row) => row.base_lines !== row.head_lines || row.base_files !== row.head_files
+  );
+
+  return (
+    <Card withBorder>
+      <Stack gap="md">
+        <Stack gap={2}>
+          <Group gap="xs">
+            <Title order={4}>Snapshots</Title>
+            <Badge variant="light">{snapshots.length} saved</Badge>
+          </Group>
+          <Text size="sm" c="dimmed">Save file counts, sizes and languages at a ref, then compare two snapshots to see how the repo grew.</Text>
+        </Stack>
+        {error ? <ErrorPanel message={error} /> : null}
+
+        <Group gap="xs" align="flex-end">
+          <TextInput size="xs" label="Name" placeholder="v1.4 release" value={name} onChange={(event) => setName(event.currentTarget.value)} />
+          <TextInput size="xs" label="Ref" value={gitRef} onChange={(event) => setGitRef(event.currentTarget.value)} />
+          <Button size="xs" onClick={() => void save()} loading={busy} disabled={!repoRef.trim() || !name.trim()}>
+            Save snapshot
+          </Button>
+        </Group>
+
+        {snapshots.length ? (
+          <ScrollArea.Autosize mah={200}>
+            <Table striped>
+              <Table.Tbody>
+                {snapshots.map((snapshot) => (
+                  <Table.Tr key={snapshot.id}>
+                    <Table.Td><Text size="sm" fw={500}>{snapshot.name}</Text></Table.Td>
+                    <Table.Td>
+                      <Text size="xs" c="dimmed">
+                        {snapshot.git_ref}
+                        {snapshot.commit ? ` @ ${snapshot.commit.slice(0, 8)}` : ''}
+                      </Text>
+                    </Table.Td>
+                    <Table.Td><Text size="xs">{snapshot.total_files.toLocaleString()} files · {snapshot.total_lines.toLocaleString()} lines</Text></Table.Td>
+                    <Table.Td><Text size="xs" c="dimmed">{new Date(snapshot.created_at).toLocaleString()}</Text></Table.Td>
+                    <Table.Td>
+                      <ActionIcon size="sm" variant="subtle" color="gray" aria-label={`Delete ${snapshot.name}`} onClick={() => void remove(snapshot.id)}>
+                        <IconTrash size={14} />
+                      </ActionIcon>
+                    </Table.Td>
+                  </Table.Tr>
+                ))}
+              </Table.Tbody>
+            </Table>
+          </ScrollArea.Autosize>
+        ) : null}
+
+        {snapshots.length >= 2 ? (
+          <Group gap="xs" align="flex-end">
+            <Select size="xs" label="Base" data={options} value={baseId} onChange={setBaseId} w={280} />
+            <Select size="xs" label="Compare with" data={options} value={headId} onChange={setHeadId} w={280} />
+            <NumberInput size="xs" label="Directory depth" min={1} max={6} value={depth} onChange={(value) => setDepth(Number(value) || 1)} w={120} />
+            <Button size="xs" variant="default" onClick={() => void compare()} disabled={!baseId || !headId || baseId === headId} loading={busy}>
+              Compare
+            </Button>
+          </Group>
+        ) : null}
+
+        {comparison ? (
+          <Stack gap="sm">
+            <Group gap="lg">
+              <Text size="sm">Files <Text span c={deltaColor(comparison.head.total_files - comparison.base.total_files)}>{signed(comparison.head.total_files - comparison.base.total_files)}</Text></Text>
+              <Text size="sm">Lines <Text span c={deltaColor(comparison.head.total_lines - comparison.base.total_lines)}>{signed(comparison.head.total_lines - comparison.base.total_lines)}</Text></Text>
+              <Text size="sm" c="dimmed">{comparison.added.length} added · {comparison.removed.length} removed · {comparison.modified} resized</Text>
+            </Group>
+
+            <SimpleGrid cols={{ base: 1, md: 2 }}>
+              <Stack gap={4}>
+                <Text size="sm" fw={500}>Directories</Text>
+                <ScrollArea.Autosize mah={280}>
+                  <Table>
+                    <Table.Thead>
+                      <Table.Tr>
+                        <Table.Th>Directory</Table.Th>
+                        <Table.Th>Files</Table.Th>
+                        <Table.Th>Lines</Table.Th>
+                      </Table.Tr>
+                    </Table.Thead>
+                    <Table.Tbody>
+                      {changedDirectories.map((row) => (
+                        <Table.Tr key={row.directory}>
+                          <Table.Td><Text size="xs" ff="monospace">{row.directory}</Text></Table.Td>
+                          <Table.Td><Text size="xs" c={deltaColor(row.head_files - row.base_files)}>{signed(row.head_files - row.base_files)}</Text></Table.Td>
+                          <Table.Td>
+                            <Text size="xs" c={deltaColor(row.head_lines - row.base_lines)}>
+                              {signed(row.head_lines - row.base_lines)} <Text span c="dimmed" size="xs">({row.head_lines.toLocaleString()})</Text>
+                            </Text>
+                          </Table.Td>
+                        </Table.Tr>
+                      ))}
+                    </Table.Tbody>
+                  </Table>
+                </ScrollArea.Autosize>
+                {!changedDirectories.length ? <Text size="xs" c="dimmed">No directory changed.</Text> : null}
+              </Stack>
+              <Stack gap={4}>
+                <Text size="sm" fw={500}>Language share (lines)</Text>
+                <Table>
+                  <Table.Thead>
+                    <Table.Tr>
+                      <Table.Th>Language</Table.Th>
+                      <Table.Th>Base</Table.Th>
+                      <Table.Th>Now</Table.Th>
+                      <Table.Th>Change</Table.Th>
+                    </Table.Tr>
+                  </Table.Thead>
+                  <Table.Tbody>
+                    {comparison.languages.map((row) => {
+                      const points = (row.head_share - row.base_share) * 100;
+                      return (
+                        <Table.Tr key={row.language}>
+                          <Table.Td><Text size="xs">{row.language}</Text></Table.Td>
+                          <Table.Td><Text size="xs">{percent(row.base_share)}</Text></Table.Td>
+                          <Table.Td><Text size="xs">{percent(row.head_share)}</Text></Table.Td>
+                          <Table.Td><Text size="xs" c={deltaColor(Math.round(points * 10))}>{points >= 0.05 ? '+' : ''}{points.toFixed(1)} pts</Text></Table.Td>
+                        </Table.Tr>
+                      );
+                    })}
+                  </Table.Tbody>
+                </Table>
+              </Stack>
+            </SimpleGrid>
+
+            <SimpleGrid cols={{ base: 1, md: 2 }}>
+              {([['Added', comparison.added, 'teal'], ['Removed', comparison.removed, 'red']] as const).map(([label, files, color]) => (
+                <Stack key={label} gap={4}>
+                  <Group gap={6}>
+                    <Text size="sm" fw={500}>{label}</Text>
+                    <Badge size="xs" variant="light" color={color}>{files.length}</Badge>
+                  </Group>
+                  <ScrollArea.Autosize mah={200}>
+                    <Stack gap={0}>
+                      {files.map((file) => (
+                        <Text
+                          key={file.path}
+                          size="xs"
+                          ff="monospace"
+                          style={{ cursor: onOpenFile && label === 'Added' ? 'pointer' : undefined }}
+                          onClick={onOpenFile && label === 'Added' ? () => onOpenFile(file.path) : undefined}
+                        >
+                          {file.path} <Text span c="dimmed" size="xs">{file.lines.toLocaleString()} lines</Text>
+                        </Text>
+                      ))}
+                    </Stack>
+                  </ScrollArea.Autosize>
+                </Stack>
+              ))}
+            </SimpleGrid>
+          </Stack>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
@@ -0,0 +1,160 @@
+import { useMemo, useState, type CSSProperties, type ReactNode } from 'react';
+import { ActionIcon, Anchor, Box, CopyButton, Group, Switch, Text, Tooltip } from '@mantine/core';
+import { IconCheck, IconCopy } from '@tabler/icons-react';
+
+type AnsiStyle = {
+  fg?: string;
+  bg?: string;
+  bold?: boolean;
+  dim?: boolean;
+  italic?: boolean;
+  underline?: boolean;
+};
+
+type AnsiSpan = { text: string; style: AnsiStyle };
+
+const BASIC_COLORS = ['#3b3b3b', '#ff6b6b', '#69db7c', '#ffd43b', '#4dabf7', '#da77f2', '#3bc9db', '#dee2e6'];
+const BRIGHT_COLORS = ['#868e96', '#ff8787', '#8ce99a', '#ffe066', '#74c0fc', '#e599f7', '#66d9e8', '#f8f9fa'];
+
+const ANSI_PATTERN = /\x1b\[([0-9;?]*)([A-Za-z])|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)/g;
+const FILE_LINE_PATTERN = /((?:[A-Za-z]:)?[\w./\\-]*[\w-]+\.[A-Za-z0-9]+):(\d+)(?::(\d+))?/g;
+
+export function stripAnsi(text: string) {
+  return text.replace(ANSI_PATTERN, '');
+}
+
+function color256(index: number) {
+  if (index < 8) return BASIC_COLORS[index];
+  if (index < 16) return BRIGHT_COLORS[index - 8];
+  if (index >= 232) {
+    const level = 8 + (index - 232) * 10;
+    return `rgb(${level},${level},${level})`;
+  }
+  const cube = index - 16;
+  const channel = (value: number) => (value === 0 ? 0 : 55 + value * 40);
+  return `rgb(${channel(Math.floor(cube / 36))},${channel(Math.floor(cube / 6) % 6)},${channel(cube % 6)})`;
+}
+
+function applySgr(style: AnsiStyle, params: number[]): AnsiStyle {
+  const next = { ...style };
+  for (let index = 0; index < params.length; index += 1) {
+    const code = params[index];
+    if (code === 0) {
+      Object.keys(next).forEach((key) => delete next[key as keyof AnsiStyle]);
+    } else if (code === 1) next.bold = true;
+    else if (code === 2) next.dim = true;
+    else if (code === 3) next.italic = true;
+    else if (code === 4) next.underline = true;
+    else if (code === 22) {
+      next.bold = false;
+      next.dim = false;
+    } else if (code === 23) next.italic = false;
+    else if (code === 24) next.underline = false;
+    else if (code >= 30 && code <= 37) next.fg = BASIC_COLORS[code - 30];
+    else if (code >= 90 && code <= 97) next.fg = BRIGHT_COLORS[code - 90];
+    else if (code === 39) delete next.fg;
+    else if (code >= 40 && code <= 47) next.bg = BASIC_COLORS[code - 40];
+    else if (code >= 100 && code <= 107) next.bg = BRIGHT_COLORS[code - 100];
+    else if (code === 49) delete next.bg;
+    else if ((code === 38 || code === 48) && params[index + 1] === 5) {
+      const value = color256(params[index + 2] ?? 0);
+      if (code === 38) next.fg = value;
+      else next.bg = value;
+      index += 2;
+    } else if ((code === 38 || code === 48) && params[index + 1] === 2) {
+      const value = `rgb(${params[index + 2] ?? 0},${params[index + 3] ?? 0},${params[index + 4] ?? 0})`;
+      if (code === 38) next.fg = value;
+      else next.bg = value;
+      index += 4;
+    }
+  }
+  return next;
+}
+
+export function parseAnsi(text: string): AnsiSpan[] {
+  const spans: AnsiSpan[] = [];
+  let style: AnsiStyle = {};
+  let last = 0;
+  let match: RegExpExecArray | null;
+  ANSI_PATTERN.lastIndex = 0;
+  while ((match = ANSI_PATTERN.exec(text)) !== null) {
+    if (match.index > last) spans.push({ text: text.slice(last, match.index), style });
+    if (match[2] === 'm') {
+      const params = (match[1] || '0').split(';').map((value) => Number(value) || 0);
+      style = applySgr(style, params);
+    }
+    last = match.index + match[0].length;
+  }
+  if (last < text.length) spans.push({ text: text.slice(last), style });
+  return spans;
+}
+
+function spanStyle(style: AnsiStyle): CSSProperties {
+  return {
+    color: style.fg,
+    background: style.bg,
+    fontWeight: style.bold ? 700 : undefined,
+    opacity: style.dim ? 0.7 : undefined,
+    fontStyle: style.italic ? 'italic' : undefined,
+    textDecoration: style.underline ? 'underline' : undefined,
+  };
+}
+
+function linkify(text: string, onOpenFile?: (path: string, line: number) => void): ReactNode[] {
+  if (!onOpenFile) return [text];
+  const nodes: ReactNode[] = [];
+  let last = 0;
+  let match: RegExpExecArray | null;
+  FILE_LINE_PATTERN.lastIndex = 0;
+  while ((match = FILE_LINE_PATTERN.exec(text)) !== null) {
+    if (match.index > last) nodes.push(text.slice(last, match.index));
+    const path = match[1];
+    const line = Number(match[2]);
+    nodes.push(
+      <Anchor key={match.index} component="button" type="button" inherit onClick={() => onOpenFile(path, line)} style={{ font: 'inherit' }}>
+        {match[0]}
+      </Anchor>
+    );
+    last = match.index + match[0].length;
+  }
+  if (last < text.length) nodes.push(text.slice(last));
+  return nodes;
+}
+
+type AnsiOutputProps = {
+  text: string;
+  label?: string;
+  maxHeight?: number;
+  onOpenFile?: (path: string, line: number) => void;
+};
+
+export function AnsiOutput(props: AnsiOutputProps) {
+  const { text, label, maxHeight = 420, onOpenFile } = props;
+  const [stripOnCopy, setStripOnCopy] = useState(true);
+  const spans = useMemo(() => parseAnsi(text), [text]);
+
+  return (
+    <Box style={{ border: '1px solid var(--mantine-color-dark-4)', borderRadius: 8, background: 'rgba(0,0,0,0.35)' }}>
+      <Group justify="space-between" px="xs" py={2} style={{ borderBottom: '1px solid var(--mantine-color-dark-4)' }}>
+        <Text size="xs" c="dimmed">{label ?? 'output'}</Text>
+        <Group gap="xs">
+          <Switch size="xs" label="Strip colors on copy" checked={stripOnCopy} onChange={(event) => setStripOnCopy(event.currentTarget.checked)} />
+          <CopyButton value={stripOnCopy ? stripAnsi(text) : text} timeout={1500}>
+            {({ copied, copy }) => (
+              <Tooltip label={copied ? 'Copied' : 'Copy'} withArrow>
+                <ActionIcon size="xs" variant="subtle" color={copied ? 'teal' : 'gray'} onClick={copy}>
+                  {copied ? <IconCheck size={12} /> : <IconCopy size={12} />}
+                </ActionIcon>
+              </Tooltip>
+            )}
+          </CopyButton>
+        </Group>
+      </Group>
+      <Box component="pre" p="xs" m={0} style={{ maxHeight, overflow: 'auto', fontSize: 12, lineHeight: 1.45, whiteSpace: 'pre-wrap', overflowWrap: 'anywhere' }}>
+        {text ? spans.map((span, index) => (
+          <span key={index} style={spanStyle(span.style)}>{linkify(span.text, onOpenFile)}</span>
+        )) : <Text span size="xs" c="dimmed">(empty)</Text>}
+      </Box>
+    </Box>
+  );
+}
@@ -1,5 +1,15 @@
+import { useEffect } from 'react';
+import { applyAccessibilityPreferences } from './accessibility';
+import { getAppSettings } from './api';
+import { PopoutPane, popoutRequestFromLocation } from './PopoutPane';
 import { WorkflowShell } from './WorkflowShell';
 
 export default function App() {
+  useEffect(() => {
+    void getAppSettings().then((settings) => applyAccessibilityPreferences(settings.accessibility)).catch(() => undefined);
+  }, []);
+
+  const popout = popoutRequestFromLocation();
+  if (popout) return <PopoutPane pane={popout.pane} repoRef={popout.repoRef} />;
   return <WorkflowShell />;
 }
@@ -0,0 +1,85 @@
+import { useState, type ReactNode } from 'react';
+import { Box, Menu, Text } from '@mantine/core';
+import { IconMessageQuestion } from '@tabler/icons-react';
+
+/** A diff hunk or editor selection to ask about in the run's next prompt. */
+export type AskAiSnippet = {
+  repoRef: string;
+  path: string;
+  snippet: string;
+  kind: 'diff' | 'code';
+  startLine?: number;
+  endLine?: number;
+  /** Lines around the selection, shown separately so the question stays focused on the snippet. */
+  context?: string;
+};
+
+export type AskAiHandler = (snippet: AskAiSnippet) => void;
+
+export function formatAskAiDraft(snippet: AskAiSnippet) {
+  const lines = snippet.startLine
+    ? `:${snippet.startLine}${snippet.endLine && snippet.endLine !== snippet.startLine ? `-${snippet.endLine}` : ''}`
+    : '';
+  const fence = snippet.kind === 'diff' ? 'diff' : (snippet.path.split('.').pop() ?? '');
+  const parts = [`### QUESTION ABOUT ${snippet.path}${lines}`, `\`\`\`${fence}\n${snippet.snippet.trimEnd()}\n\`\`\``];
+  if (snippet.context?.trim()) parts.push(`Surrounding context:\n\`\`\`${fence}\n${snippet.context.trimEnd()}\n\`\`\``);
+  parts.push('Question: ');
+  return parts.join('\n\n');
+}
+
+type PatchHunk = { header: string; text: string; startLine: number; endLine: number };
+
+/** Splits one file's unified patch into hunks, numbered on the new side. */
+export function patchHunks(patch: string): PatchHunk[] {
+  const hunks: PatchHunk[] = [];
+  let current: PatchHunk | null = null;
+  for (const line of patch.split('\n')) {
+    if (line.startsWith('@@')) {
+      const match = /\+(\d+)(?:,(\d+))?/.exec(line);
+      const start = match ? Number(match[1]) : 0;
+      const count = match?.[2] !== undefined ? Number(match[2]) : 1;
+      current = { header: line, text: line, startLine: start, endLine: start + Math.max(count - 1, 0) };
+      hunks.push(current);
+    } else if (current && /^[ +\-\\]/.test(line)) {
+      current.text += `\n${line}`;
+    }
+  }
+  return hunks;
+}
+
+/** Right-click menu over a rendered diff offering "Ask AI about this" for each of its hunks. */
+export function AskAiHunkMenu(props: { repoRef: string; path: string; patch: string; onAskAi?: AskAiHandler; children: ReactNode }) {
+  const { repoRef, path, patch, onAskAi, children } = props;
+  const [position, setPosition] = useState<{ x: number; y: number } | null>(null);
+  if (!onAskAi) return <>{children}</>;
+  const hunks = position ? patchHunks(patch) : [];
+
+  return (
+    <Box
+      onContextMenu={(event) => {
+        if (!patch.includes('@@')) return;
+        event.preventDefault();
+        setPosition({ x: event.clientX, y: event.clientY });
+      }}
+    >
+      {children}
+      <Menu opened={!!position} onChange={(opened) => { if (!opened) setPosition(null); }} position="bottom-start" withinPortal>
+        <Menu.Target>
+          <Box style={{ position: 'fixed', left: position?.x ?? 0, top: position?.y ?? 0, width: 0, height: 0 }} />
+        </Menu.Target>
+        <Menu.Dropdown mah={320} style={{ overflowY: 'auto' }}>
+          <Menu.Label>Ask AI about this</Menu.Label>
+          {hunks.map((hunk, index) => (
+            <Menu.Item
+              key={index}
+              leftSection={<IconMessageQuestion size={14} />}
+              onClick={() => onAskAi({ repoRef, path, snippet: hunk.text, kind: 'diff', startLine: hunk.startLine, endLine: hunk.endLine })}
+            >
+              <Text size="xs" ff="monospace" truncate maw={420}>{hunk.header}</Text>
+            </Menu.Item>
+          ))}
+        </Menu.Dropdown>
+      </Menu>
+    </Box>
+  );
+}
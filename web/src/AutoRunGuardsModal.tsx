@@ -0,0 +1,109 @@
+import { useEffect, useState } from 'react';
+import { Alert, Button, Group, Modal, NumberInput, SimpleGrid, Stack, Text } from '@mantine/core';
+import {
+  patchWorkflowGlobalState,
+  type AutoBudget,
+  type AutoStopConditions,
+  type WorkflowRun,
+} from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type AutoRunGuardsModalProps = {
+  opened: boolean;
+  onClose: () => void;
+  run: WorkflowRun | null;
+  onSaved: () => Promise<void> | void;
+};
+
+type GuardField = {
+  key: keyof AutoStopConditions;
+  label: string;
+  description: string;
+  decimalScale?: number;
+};
+
+const GUARD_FIELDS: GuardField[] = [
+  { key: 'max_iterations', label: 'Max iterations', description: 'Times an automatic run may loop back to a step it already ran' },
+  { key: 'max_total_tokens', label: 'Max total tokens', description: 'Reported usage, or an estimate for browser transport' },
+  { key: 'max_cost_usd', label: 'Max cost (USD)', description: 'Requires a price per million tokens', decimalScale: 2 },
+  { key: 'usd_per_million_tokens', label: 'USD per million tokens', description: 'Used to estimate cost', decimalScale: 4 },
+  { key: 'max_wall_clock_secs', label: 'Max wall-clock (seconds)', description: 'Measured from run start or resume' },
+  { key: 'stop_after_consecutive_passes', label: 'Stop after N passes', description: 'Consecutive successful compile stages' },
+];
+
+function engineSection(run: WorkflowRun | null, key: string): Record<string, unknown> {
+  const engine = (run?.context?.workflow_engine ?? {}) as Record<string, unknown>;
+  return (engine[key] ?? {}) as Record<string, unknown>;
+}
+
+export function AutoRunGuardsModal(props: AutoRunGuardsModalProps) {
+  const { opened, onClose, run, onSaved } = props;
+  const [conditions, setConditions] = useState<AutoStopConditions>({});
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const automation = (engineSection(run, 'global_state').automation ?? {}) as Record<string, unknown>;
+  const budget = (engineSection(run, 'run_state').auto_budget ?? null) as AutoBudget | null;
+
+  useEffect(() => {
+    if (!opened) return;
+    setError(null);
+    setConditions({ ...((automation.stop_conditions ?? {}) as AutoStopConditions) });
+  }, [opened, run?.id]);
+
+  async function handleSave() {
+    if (!run) return;
+    try {
+      setBusy(true);
+      setError(null);
+      const stopConditions = Object.fromEntries(
+        GUARD_FIELDS.map((field) => [field.key, conditions[field.key] ?? null])
+      );
+      await patchWorkflowGlobalState(run.id, {
+        automation: { ...automation, stop_conditions: stopConditions },
+      });
+      await onSaved();
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Automatic run guards" size="lg" centered>
+      <Stack gap="md">
+        <Text size="sm" c="dimmed">
+          The automatic run pauses after the stage that crosses any limit below. Leave a field empty to disable it.
+        </Text>
+        <SimpleGrid cols={{ base: 1, sm: 2 }}>
+          {GUARD_FIELDS.map((field) => (
+            <NumberInput
+              key={field.key}
+              label={field.label}
+              description={field.description}
+              value={conditions[field.key] ?? ''}
+              onChange={(value) => setConditions((current) => ({
+                ...current,
+                [field.key]: typeof value === 'number' && Number.isFinite(value) ? value : null,
+              }))}
+              min={0}
+              decimalScale={field.decimalScale ?? 0}
+            />
+          ))}
+        </SimpleGrid>
+        {budget ? (
+          <Alert color="gray" title="Current budget">
+            {budget.iterations} iterations, {budget.total_tokens.toLocaleString()} tokens, {budget.consecutive_passes} consecutive passes since {new Date(budget.started_at).toLocaleString()}
+          </Alert>
+        ) : null}
+        {error ? <ErrorPanel message={error} /> : null}
+        <Group justify="flex-end">
+          <Button variant="default" onClick={onClose}>Cancel</Button>
+          <Button onClick={() => void handleSave()} loading={busy} disabled={!run}>Save</Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
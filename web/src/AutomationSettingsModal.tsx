@@ -0,0 +1,89 @@
+import { useEffect, useState } from 'react';
+import { Button, Code, CopyButton, Group, Modal, PasswordInput, Stack, Switch, Text } from '@mantine/core';
+import { getAppSettings, patchAppSettings, rotateAccessToken, type AppSettings } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type AutomationSettingsModalProps = {
+  opened: boolean;
+  onClose: () => void;
+};
+
+export function AutomationSettingsModal(props: AutomationSettingsModalProps) {
+  const { opened, onClose } = props;
+  const [settings, setSettings] = useState<AppSettings['automation'] | null>(null);
+  const [token, setToken] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!opened) return;
+    setError(null);
+    setToken(null);
+    void getAppSettings()
+      .then((next) => setSettings(next.automation))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [opened]);
+
+  function regenerate() {
+    void rotateAccessToken('automation')
+      .then((next) => {
+        setToken(next.token);
+        setSettings((current) => (current ? { ...current, token_set: true } : current));
+      })
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  function update(patch: Partial<AppSettings['automation']>) {
+    void patchAppSettings({ automation: patch })
+      .then((next) => setSettings(next.automation))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  const example = `curl -X POST ${window.location.origin}/api/automation/context-export \\\n  -H "Authorization: Bearer ${token ?? '<token>'}" \\\n  -H "Content-Type: application/json" \\\n  -d '{"repo_ref": "/path/to/repo", "files": ["/path/to/repo/src/main.rs"]}'`;
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Automation API" size="lg" centered>
+      <Stack gap="sm">
+        {error ? <ErrorPanel message={error} /> : null}
+        <Switch
+          label="Enable automation API"
+          description="Lets editors and scripts open repositories, run analysis, export context and start named tasks over local HTTP."
+          checked={settings?.enabled ?? false}
+          disabled={!settings}
+          onChange={(event) => update({ enabled: event.currentTarget.checked })}
+        />
+        {settings?.enabled ? (
+          <>
+            <Group align="flex-end" gap="xs">
+              <PasswordInput
+                label="Token"
+                description={token ? 'Shown once; copy it now.' : settings.token_set ? 'A token is set. Regenerating replaces it and shows the new one.' : 'No token yet.'}
+                value={token ?? ''}
+                placeholder={settings.token_set ? '••••••••' : ''}
+                readOnly
+                style={{ flex: 1 }}
+              />
+              <CopyButton value={token ?? ''}>
+                {({ copied, copy }) => <Button variant="default" onClick={copy} disabled={!token}>{copied ? 'Copied' : 'Copy'}</Button>}
+              </CopyButton>
+              <Button variant="default" onClick={regenerate}>Regenerate</Button>
+            </Group>
+            <Text size="sm">Endpoints (POST, JSON body with <Code>repo_ref</Code>):</Text>
+            <Text size="xs" component="div">
+              <Code>/api/automation/open</Code> opens the repository in this window.
+              <br />
+              <Code>/api/automation/analysis/sizes|dependencies|artifacts</Code> runs an analysis.
+              <br />
+              <Code>/api/automation/context-export</Code> returns a context export for <Code>files</Code>.
+              <br />
+              <Code>/api/automation/tasks/&lt;template name&gt;/run</Code> starts a run from a workflow template.
+            </Text>
+            <Code block>{example}</Code>
+          </>
+        ) : null}
+        <Group justify="flex-end">
+          <Button variant="default" onClick={onClose}>Close</Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
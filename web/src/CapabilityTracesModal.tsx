@@ -0,0 +1,202 @@
+import { Fragment, useEffect, useState } from 'react';
+import { ActionIcon, Badge, Button, Code, Group, Modal, Stack, Switch, Table, Text, TextInput, Tooltip } from '@mantine/core';
+import { IconChevronDown, IconChevronRight, IconPlayerPlay, IconRefresh, IconTrash } from '@tabler/icons-react';
+import {
+  clearCapabilityTraces,
+  getAppSettings,
+  listCapabilityTraces,
+  patchAppSettings,
+  replayCapabilityTrace,
+  type CapabilityTrace
+} from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type CapabilityTracesModalProps = {
+  opened: boolean;
+  onClose: () => void;
+  runId: string | null;
+};
+
+function formatBytes(bytes: number) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} B`;
+}
+
+function formatJson(value: unknown) {
+  return JSON.stringify(value, null, 2);
+}
+
+/** Request/response pairs from recent capability dispatches, with replay for reproducing a failure. */
+export function CapabilityTracesModal(props: CapabilityTracesModalProps) {
+  const { opened, onClose, runId } = props;
+  const [traces, setTraces] = useState<CapabilityTrace[]>([]);
+  const [onlyThisRun, setOnlyThisRun] = useState(true);
+  const [expandedId, setExpandedId] = useState<number | null>(null);
+  const [replayingId, setReplayingId] = useState<number | null>(null);
+  const [traceFile, setTraceFile] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const filterRunId = onlyThisRun ? runId : null;
+
+  async function refresh() {
+    try {
+      setTraces(await listCapabilityTraces(filterRunId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    if (!opened) return;
+    setError(null);
+    void refresh();
+  }, [opened, filterRunId]);
+
+  useEffect(() => {
+    if (!opened) return;
+    void getAppSettings()
+      .then((settings) => setTraceFile(settings.debug?.capability_trace_file ?? ''))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [opened]);
+
+  function saveTraceFile() {
+    void patchAppSettings({ debug: { capability_trace_file: traceFile.trim() } })
+      .then((settings) => setTraceFile(settings.debug.capability_trace_file))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  async function replay(id: number) {
+    setReplayingId(id);
+    setError(null);
+    try {
+      await replayCapabilityTrace(id);
+      await refresh();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setReplayingId(null);
+    }
+  }
+
+  async function clear() {
+    try {
+      await clearCapabilityTraces();
+      setTraces([]);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Capability traces" size="xl">
+      <Stack gap="sm">
+        <Group justify="space-between">
+          <Switch
+            size="xs"
+            label="Only the selected run"
+            checked={onlyThisRun && !!runId}
+            disabled={!runId}
+            onChange={(event) => setOnlyThisRun(event.currentTarget.checked)}
+          />
+          <Group gap="xs">
+            <Button size="xs" variant="default" leftSection={<IconRefresh size={14} />} onClick={() => void refresh()}>
+              Refresh
+            </Button>
+            <Button size="xs" variant="subtle" color="red" leftSection={<IconTrash size={14} />} onClick={() => void clear()}>
+              Clear
+            </Button>
+          </Group>
+        </Group>
+        <TextInput
+          size="xs"
+          label="Trace file"
+          description="Every trace is also appended here as JSON lines, untruncated. Leave empty to keep only the last 200 in memory."
+          placeholder="/path/to/capability-traces.jsonl"
+          value={traceFile}
+          onChange={(event) => setTraceFile(event.currentTarget.value)}
+          onBlur={saveTraceFile}
+        />
+        {error ? <ErrorPanel message={error} /> : null}
+        {traces.length === 0 ? (
+          <Text size="sm" c="dimmed">No capability calls recorded yet.</Text>
+        ) : (
+          <Table striped withTableBorder verticalSpacing={4} fz="xs">
+            <Table.Thead>
+              <Table.Tr>
+                <Table.Th />
+                <Table.Th>Capability</Table.Th>
+                <Table.Th>Stage</Table.Th>
+                <Table.Th>Result</Table.Th>
+                <Table.Th>Duration</Table.Th>
+                <Table.Th>Request</Table.Th>
+                <Table.Th>Response</Table.Th>
+                <Table.Th>At</Table.Th>
+                <Table.Th />
+              </Table.Tr>
+            </Table.Thead>
+            <Table.Tbody>
+              {traces.map((trace) => {
+                const expanded = expandedId === trace.id;
+                return (
+                  <Fragment key={trace.id}>
+                    <Table.Tr>
+                      <Table.Td>
+                        <ActionIcon
+                          size="sm"
+                          variant="subtle"
+                          aria-label={expanded ? 'Hide details' : 'Show details'}
+                          onClick={() => setExpandedId(expanded ? null : trace.id)}
+                        >
+                          {expanded ? <IconChevronDown size={14} /> : <IconChevronRight size={14} />}
+                        </ActionIcon>
+                      </Table.Td>
+                      <Table.Td ff="monospace">{trace.capability}</Table.Td>
+                      <Table.Td>{trace.step_id}</Table.Td>
+                      <Table.Td>
+                        <Badge size="xs" variant="light" color={trace.ok ? 'green' : 'red'}>{trace.ok ? 'ok' : 'failed'}</Badge>
+                      </Table.Td>
+                      <Table.Td>{trace.duration_ms} ms</Table.Td>
+                      <Table.Td>{formatBytes(trace.request_bytes)}</Table.Td>
+                      <Table.Td>{formatBytes(trace.response_bytes)}</Table.Td>
+                      <Table.Td title={trace.at}>{new Date(trace.at).toLocaleTimeString()}</Table.Td>
+                      <Table.Td>
+                        <Tooltip label="Replay with the same config">
+                          <ActionIcon
+                            size="sm"
+                            variant="subtle"
+                            aria-label="Replay request"
+                            loading={replayingId === trace.id}
+                            disabled={replayingId !== null}
+                            onClick={() => void replay(trace.id)}
+                          >
+                            <IconPlayerPlay size={14} />
+                          </ActionIcon>
+                        </Tooltip>
+                      </Table.Td>
+                    </Table.Tr>
+                    {expanded ? (
+                      <Table.Tr>
+                        <Table.Td colSpan={9}>
+                          <Stack gap={4}>
+                            {trace.error ? <Text size="xs" c="red">{trace.error}</Text> : null}
+                            <Text size="xs" fw={600}>Request</Text>
+                            <Code block mah={200} style={{ overflow: 'auto' }}>{formatJson(trace.request)}</Code>
+                            <Text size="xs" fw={600}>
+                              Response{trace.response_truncated ? ' (truncated; the trace file has it in full)' : ''}
+                            </Text>
+                            <Code block mah={300} style={{ overflow: 'auto' }}>{formatJson(trace.response)}</Code>
+                          </Stack>
+                        </Table.Td>
+                      </Table.Tr>
+                    ) : null}
+                  </Fragment>
+                );
+              })}
+            </Table.Tbody>
+          </Table>
+        )}
+      </Stack>
+    </Modal>
+  );
+}
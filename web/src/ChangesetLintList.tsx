@@ -0,0 +1,31 @@
+import { Alert, Badge, Group, Stack, Text } from '@mantine/core';
+import type { ChangesetLintReport } from './api';
+
+type ChangesetLintListProps = {
+  report: ChangesetLintReport | null;
+};
+
+export function ChangesetLintList(props: ChangesetLintListProps) {
+  const { report } = props;
+  if (!report) return null;
+
+  if (report.findings.length === 0) {
+    return <Alert color="green" variant="light">Lint passed with no findings.</Alert>;
+  }
+
+  return (
+    <Alert color={report.error_count > 0 ? 'red' : 'yellow'} variant="light" title={`Lint: ${report.error_count} errors, ${report.warning_count} warnings`}>
+      <Stack gap={4}>
+        {report.findings.map((finding, index) => (
+          <Group key={`${finding.operation_index}-${finding.action_index ?? 0}-${finding.code}-${index}`} gap="xs" wrap="nowrap" align="flex-start">
+            <Badge size="xs" color={finding.severity === 'error' ? 'red' : 'yellow'} variant="filled">{finding.severity}</Badge>
+            <Text size="xs" ff="monospace">
+              op {finding.operation_index}{finding.action_index ? ` · edit ${finding.action_index}` : ''}{finding.path ? ` · ${finding.path}` : ''}
+            </Text>
+            <Text size="xs">{finding.message}</Text>
+          </Group>
+        ))}
+      </Stack>
+    </Alert>
+  );
+}
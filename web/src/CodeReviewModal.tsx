@@ -0,0 +1,308 @@
+import { useEffect, useMemo, useState } from 'react';
+import {
+  ActionIcon,
+  Badge,
+  Box,
+  Button,
+  Checkbox,
+  CopyButton,
+  Group,
+  Loader,
+  Modal,
+  Progress,
+  ScrollArea,
+  Stack,
+  Text,
+  Textarea,
+  Tooltip,
+  UnstyledButton
+} from '@mantine/core';
+import { IconMessagePlus, IconTrash } from '@tabler/icons-react';
+import {
+  addCodeReviewComment,
+  deleteCodeReviewComment,
+  exportCodeReview,
+  getReviewCompareDiff,
+  openCodeReview,
+  setCodeReviewFileViewed,
+  type CodeReview,
+  type CodeReviewComment,
+  type ReviewDiffManifestFileEntry
+} from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { WhitespaceOnlyBadge } from './DiffWhitespace';
+
+type CodeReviewModalProps = {
+  opened: boolean;
+  onClose: () => void;
+  repoRef: string;
+  base: string;
+  head: string;
+  files: ReviewDiffManifestFileEntry[];
+};
+
+type PatchRow = {
+  kind: 'hunk' | 'add' | 'del' | 'ctx';
+  text: string;
+  oldLine: number | null;
+  newLine: number | null;
+};
+
+type Anchor = { line: number; side: 'new' | 'old'; text: string };
+
+const ROW_COLORS: Record<PatchRow['kind'], string | undefined> = {
+  add: 'rgba(46, 160, 67, 0.15)',
+  del: 'rgba(248, 81, 73, 0.15)',
+  hunk: 'rgba(34, 139, 230, 0.1)',
+  ctx: undefined
+};
+
+/** Flattens a unified patch into numbered rows that comments can be anchored to. */
+function patchRows(patch: string): PatchRow[] {
+  const rows: PatchRow[] = [];
+  let oldLine = 0;
+  let newLine = 0;
+  let inHunk = false;
+  for (const raw of patch.split('\n')) {
+    if (raw.startsWith('@@')) {
+      const match = /-(\d+)(?:,\d+)? \+(\d+)/.exec(raw);
+      oldLine = match ? Number(match[1]) : 0;
+      newLine = match ? Number(match[2]) : 0;
+      inHunk = true;
+      rows.push({ kind: 'hunk', text: raw, oldLine: null, newLine: null });
+    } else if (!inHunk || raw.startsWith('\\')) {
+      continue;
+    } else if (raw.startsWith('+')) {
+      rows.push({ kind: 'add', text: raw.slice(1), oldLine: null, newLine: newLine++ });
+    } else if (raw.startsWith('-')) {
+      rows.push({ kind: 'del', text: raw.slice(1), oldLine: oldLine++, newLine: null });
+    } else if (raw.startsWith(' ')) {
+      rows.push({ kind: 'ctx', text: raw.slice(1), oldLine: oldLine++, newLine: newLine++ });
+    }
+  }
+  return rows;
+}
+
+function rowAnchor(row: PatchRow): Anchor | null {
+  if (row.kind === 'hunk') return null;
+  return row.kind === 'del'
+    ? { line: row.oldLine ?? 0, side: 'old', text: row.text }
+    : { line: row.newLine ?? 0, side: 'new', text: row.text };
+}
+
+export function CodeReviewModal(props: CodeReviewModalProps) {
+  const { opened, onClose, repoRef, base, head, files } = props;
+  const [review, setReview] = useState<CodeReview | null>(null);
+  const [selectedPath, setSelectedPath] = useState<string | null>(null);
+  const [patch, setPatch] = useState('');
+  const [patchBusy, setPatchBusy] = useState(false);
+  const [draftAnchor, setDraftAnchor] = useState<Anchor | null>(null);
+  const [draft, setDraft] = useState('');
+  const [markdown, setMarkdown] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  function fail(err: unknown) {
+    setError(err instanceof Error ? err.message : String(err));
+  }
+
+  useEffect(() => {
+    if (!opened) return;
+    setReview(null);
+    setMarkdown(null);
+    setError(null);
+    setSelectedPath(files[0]?.path ?? null);
+    void openCodeReview({ repo_ref: repoRef, base, head }).then(setReview).catch(fail);
+  }, [opened, repoRef, base, head]);
+
+  useEffect(() => {
+    if (!opened || !selectedPath) return;
+    setPatch('');
+    setDraftAnchor(null);
+    setPatchBusy(true);
+    void getReviewCompareDiff({ repo_ref: repoRef, base, head, path: selectedPath })
+      .then((response) => setPatch(response.patch))
+      .catch(fail)
+      .finally(() => setPatchBusy(false));
+  }, [opened, repoRef, base, head, selectedPath]);
+
+  const rows = useMemo(() => patchRows(patch), [patch]);
+  const viewed = new Set(review?.viewed ?? []);
+  const viewedCount = files.filter((file) => viewed.has(file.path)).length;
+  const commentsByPath = useMemo(() => {
+    const out: Record<string, CodeReviewComment[]> = {};
+    for (const comment of review?.comments ?? []) (out[comment.path] ??= []).push(comment);
+    return out;
+  }, [review]);
+  const fileComments = selectedPath ? commentsByPath[selectedPath] ?? [] : [];
+
+  async function toggleViewed(path: string, next: boolean) {
+    if (!review) return;
+    try {
+      setReview(await setCodeReviewFileViewed(review.id, path, next));
+      if (next && path === selectedPath) {
+        const nextFile = files.find((file) => file.path !== path && !viewed.has(file.path));
+        if (nextFile) setSelectedPath(nextFile.path);
+      }
+    } catch (err) {
+      fail(err);
+    }
+  }
+
+  async function saveComment() {
+    if (!review || !selectedPath || !draftAnchor || !draft.trim()) return;
+    try {
+      setReview(
+        await addCodeReviewComment(review.id, {
+          path: selectedPath,
+          line: draftAnchor.line,
+          side: draftAnchor.side,
+          line_text: draftAnchor.text,
+          body: draft
+        })
+      );
+      setDraft('');
+      setDraftAnchor(null);
+    } catch (err) {
+      fail(err);
+    }
+  }
+
+  async function removeComment(comment: CodeReviewComment) {
+    if (!review) return;
+    try {
+      setReview(await deleteCodeReviewComment(review.id, comment.id));
+    } catch (err) {
+      fail(err);
+    }
+  }
+
+  async function exportMarkdown() {
+    if (!review) return;
+    try {
+      setMarkdown((await exportCodeReview(review.id)).markdown);
+    } catch (err) {
+      fail(err);
+    }
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} size="95%" title={`Review ${base}…${head}`}>
+      <Stack gap="sm">
+        <Group justify="space-between">
+          <Group gap="xs" style={{ flex: 1 }}>
+            <Text size="sm">{viewedCount} of {files.length} files viewed</Text>
+            <Progress value={files.length ? (viewedCount / files.length) * 100 : 0} style={{ flex: 1, maxWidth: 240 }} aria-label="Review progress" />
+            <Badge variant="light">{review?.comments.length ?? 0} comments</Badge>
+          </Group>
+          <Button size="xs" variant="default" disabled={!review} onClick={() => void exportMarkdown()}>Export Markdown</Button>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        {!review && !error ? <Group justify="center" py="xl"><Loader /></Group> : null}
+        {review ? (
+          <Box style={{ display: 'grid', gridTemplateColumns: '320px 1fr', gap: 12, height: '70vh' }}>
+            <ScrollArea h="100%" type="auto">
+              <Stack gap={2}>
+                {files.map((file) => (
+                  <Group
+                    key={file.path}
+                    gap="xs"
+                    wrap="nowrap"
+                    px={6}
+                    py={4}
+                    style={{ borderRadius: 6, background: selectedPath === file.path ? 'rgba(34, 139, 230, 0.12)' : undefined }}
+                  >
+                    <Checkbox
+                      size="xs"
+                      aria-label={`Viewed ${file.path}`}
+                      checked={viewed.has(file.path)}
+                      onChange={(event) => void toggleViewed(file.path, event.currentTarget.checked)}
+                    />
+                    <UnstyledButton onClick={() => setSelectedPath(file.path)} style={{ minWidth: 0, flex: 1 }}>
+                      <Text size="xs" truncate c={viewed.has(file.path) ? 'dimmed' : undefined}>{file.path}</Text>
+                    </UnstyledButton>
+                    <WhitespaceOnlyBadge kind={file.whitespace_only} />
+                    {commentsByPath[file.path]?.length ? <Badge size="xs" variant="light">{commentsByPath[file.path].length}</Badge> : null}
+                  </Group>
+                ))}
+              </Stack>
+            </ScrollArea>
+            <ScrollArea h="100%" type="auto">
+              {patchBusy ? (
+                <Group justify="center" py="xl"><Loader /></Group>
+              ) : rows.length === 0 ? (
+                <Text size="sm" c="dimmed">No textual changes.</Text>
+              ) : (
+                <Box ff="monospace" fz="xs">
+                  {rows.map((row, index) => {
+                    const anchor = rowAnchor(row);
+                    const anchored = anchor
+                      ? fileComments.filter((comment) => comment.side === anchor.side && comment.line === anchor.line)
+                      : [];
+                    const drafting = anchor && draftAnchor?.side === anchor.side && draftAnchor.line === anchor.line;
+                    return (
+                      <Box key={index}>
+                        <Group gap={0} wrap="nowrap" style={{ background: ROW_COLORS[row.kind] }}>
+                          <Text span fz="xs" c="dimmed" w={48} ta="right" pr={6}>{row.oldLine ?? ''}</Text>
+                          <Text span fz="xs" c="dimmed" w={48} ta="right" pr={6}>{row.newLine ?? ''}</Text>
+                          <Box w={24}>
+                            {anchor ? (
+                              <Tooltip label="Comment on this line" openDelay={400}>
+                                <ActionIcon size="xs" variant="subtle" aria-label="Comment on this line" onClick={() => setDraftAnchor(anchor)}>
+                                  <IconMessagePlus size={12} />
+                                </ActionIcon>
+                              </Tooltip>
+                            ) : null}
+                          </Box>
+                          <Text span fz="xs" style={{ whiteSpace: 'pre', flex: 1 }}>
+                            {row.kind === 'add' ? '+' : row.kind === 'del' ? '-' : row.kind === 'ctx' ? ' ' : ''}{row.text}
+                          </Text>
+                        </Group>
+                        {anchored.map((comment) => (
+                          <Group key={comment.id} justify="space-between" wrap="nowrap" ml={120} my={4} p={6} style={{ borderLeft: '3px solid var(--mantine-color-blue-5)', fontFamily: 'var(--mantine-font-family)' }}>
+                            <Text size="sm" style={{ whiteSpace: 'pre-wrap' }}>{comment.body}</Text>
+                            <ActionIcon size="sm" variant="subtle" color="red" aria-label="Delete comment" onClick={() => void removeComment(comment)}>
+                              <IconTrash size={14} />
+                            </ActionIcon>
+                          </Group>
+                        ))}
+                        {drafting ? (
+                          <Stack gap={4} ml={120} my={4} style={{ fontFamily: 'var(--mantine-font-family)' }}>
+                            <Textarea
+                              autosize
+                              minRows={2}
+                              data-autofocus
+                              placeholder="Leave a comment"
+                              value={draft}
+                              onChange={(event) => setDraft(event.currentTarget.value)}
+                              onKeyDown={(event) => {
+                                if (event.key === 'Enter' && (event.metaKey || event.ctrlKey)) void saveComment();
+                              }}
+                            />
+                            <Group gap="xs">
+                              <Button size="xs" disabled={!draft.trim()} onClick={() => void saveComment()}>Comment</Button>
+                              <Button size="xs" variant="default" onClick={() => setDraftAnchor(null)}>Cancel</Button>
+                            </Group>
+                          </Stack>
+                        ) : null}
+                      </Box>
+                    );
+                  })}
+                </Box>
+              )}
+            </ScrollArea>
+          </Box>
+        ) : null}
+      </Stack>
+      <Modal opened={markdown !== null} onClose={() => setMarkdown(null)} title="Review comments" size="lg">
+        <Stack gap="sm">
+          <Textarea readOnly autosize minRows={8} maxRows={24} value={markdown ?? ''} ff="monospace" />
+          <Group justify="flex-end">
+            <CopyButton value={markdown ?? ''}>
+              {({ copied, copy }) => <Button size="xs" onClick={copy}>{copied ? 'Copied' : 'Copy Markdown'}</Button>}
+            </CopyButton>
+          </Group>
+        </Stack>
+      </Modal>
+    </Modal>
+  );
+}
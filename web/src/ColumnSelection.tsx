@@ -0,0 +1,40 @@
+import { Text } from '@mantine/core';
+import { StatusBarToggle } from './FileFormatStatus';
+
+/** Modifier constants from `monaco.KeyMod`/`KeyCode`, used when the global namespace is not exposed. */
+const KEY_MOD_CTRL_CMD = 2048;
+const KEY_MOD_SHIFT = 1024;
+const KEY_MOD_ALT = 512;
+const KEY_CODE_V = 52;
+
+export const PASTE_ACROSS_CURSORS_KEYBINDING = KEY_MOD_CTRL_CMD | KEY_MOD_SHIFT | KEY_MOD_ALT | KEY_CODE_V;
+
+/**
+ * One clipboard line per cursor, top to bottom. Monaco's own paste only spreads when the counts match, so
+ * here shorter clipboards repeat from the top and extra lines are dropped.
+ */
+export function distributeLines(text: string, cursors: number): string[] {
+  const lines = text.replace(/\r\n/g, '\n').replace(/\n$/, '').split('\n');
+  return Array.from({ length: cursors }, (_, index) => lines[index % lines.length]);
+}
+
+type ColumnSelectionToggleProps = {
+  enabled: boolean;
+  cursorCount: number;
+  onToggle: () => void;
+};
+
+/** Status bar switch for column mode, where a plain drag selects a block, plus the live cursor count. */
+export function ColumnSelectionToggle({ enabled, cursorCount, onToggle }: ColumnSelectionToggleProps) {
+  return (
+    <>
+      {cursorCount > 1 ? <Text size="xs" c="dimmed">{cursorCount} cursors</Text> : null}
+      <StatusBarToggle
+        label="Column mode"
+        enabled={enabled}
+        hint="Alt+drag always selects a block. Ctrl+Shift+Alt+V pastes clipboard lines across the cursors."
+        onToggle={onToggle}
+      />
+    </>
+  );
+}
@@ -19,9 +19,10 @@ import {
   Text,
   TextInput,
 } from '@mantine/core';
-import { parsePatchFiles, type FileDiffMetadata } from '@pierre/diffs';
-import { FileDiff, PatchDiff, Virtualizer } from '@pierre/diffs/react';
+import type { FileDiffMetadata } from '@pierre/diffs';
+import { FileDiff, Virtualizer } from '@pierre/diffs/react';
 import {
+  explainCommitWithAi,
   getReviewCommitDiff,
   getReviewCommitDiffManifest,
   getReviewCommitReport,
@@ -29,20 +30,30 @@ import {
   type ReviewCommitDiffManifestResponse,
   type ReviewCommitDiffResponse,
   type ReviewCommitSummary,
+  type DiffWhitespaceMode,
   type ReviewDiffManifestFileEntry,
+  type ReviewCommitReportContributor,
   type ReviewCommitReportResponse,
   type ReviewCommitRefOption,
+  type AiActionModel,
 } from './api';
+import { CompareBranchesPanel } from './CompareBranchesPanel';
+import { ReflogPanel } from './ReflogPanel';
+import { PatchScreenReaderSummary, SafePatchDiff, type DiffStyle } from './SafePatchDiff';
+import { AskAiHunkMenu, type AskAiHandler } from './AskAi';
+import { AiSummaryButton, AiSummaryCard, useAiSummary } from './AiSummary';
+import { PatchWhitespaceIssues, WhitespaceModeSelect, WhitespaceOnlyBadge } from './DiffWhitespace';
+import { cachedPatchFiles } from './sharedRenderCache';
+import { ErrorPanel } from './ErrorPanel';
 
 const COMMIT_PAGE_SIZE = 75;
 const DEFAULT_COMMIT_ROW_HEIGHT = 44;
 const LARGE_SINGLE_FILE_RENDER_LINE_LIMIT = 8000;
 
-type DiffStyle = 'unified' | 'split';
-type CommitReportType = 'commits' | 'analytics';
+type CommitReportType = 'commits' | 'analytics' | 'compare' | 'reflog';
 type CommitAnalyticsMode = 'activity' | 'net';
-type CommitAggregationWindow = 'daily' | 'monthly' | 'yearly';
-type CommitAnalyticsColorBy = 'extension' | 'author';
+type CommitAggregationWindow = 'daily' | 'weekly' | 'monthly' | 'yearly';
+type CommitAnalyticsColorBy = 'extension' | 'author' | 'directory';
 
 type CommitAnalyticsGroupBucket = {
   key: string;
@@ -97,6 +108,11 @@ function downloadTextFile(filename: string, contents: string, contentType: strin
 
 type CommitSummaryPanelProps = {
   repoRef: string;
+  onAskAi?: AskAiHandler;
+  /** Model used by "Explain this commit"; defaults to the inference defaults. */
+  aiModel?: AiActionModel;
+  /** Loads a set of files at a ref into the repo context export. */
+  onExportContext?: (paths: string[], gitRef: string) => void;
 };
 
 type CommitReviewState = {
@@ -105,6 +121,7 @@ type CommitReviewState = {
   only_changes: boolean;
   context_lines: number;
   whole_file: boolean;
+  whitespace_mode: DiffWhitespaceMode;
 };
 
 const DEFAULT_REVIEW_STATE: CommitReviewState = {
@@ -113,6 +130,7 @@ const DEFAULT_REVIEW_STATE: CommitReviewState = {
   only_changes: true,
   context_lines: 10,
   whole_file: false,
+  whitespace_mode: 'none',
 };
 
 function formatCommitDate(value: string) {
@@ -143,50 +161,6 @@ function sumCounts(files: ReviewDiffManifestFileEntry[]) {
   );
 }
 
-type SafePatchDiffProps = {
-  patch: string;
-  diffStyle: DiffStyle;
-};
-
-type SafePatchDiffState = {
-  error: string | null;
-};
-
-class SafePatchDiff extends Component<SafePatchDiffProps, SafePatchDiffState> {
-  state: SafePatchDiffState = { error: null };
-
-  static getDerivedStateFromError(error: unknown): SafePatchDiffState {
-    return { error: error instanceof Error ? error.message : String(error) };
-  }
-
-  componentDidCatch() {}
-
-  componentDidUpdate(prevProps: SafePatchDiffProps) {
-    if ((prevProps.patch !== this.props.patch || prevProps.diffStyle !== this.props.diffStyle) && this.state.error) {
-      this.setState({ error: null });
-    }
-  }
-
-  render() {
-    if (this.state.error) {
-      return (
-        <Stack gap="sm">
-          <Alert color="yellow">The rich diff renderer failed for this patch. Showing the raw patch instead.</Alert>
-          <Text size="xs" c="dimmed">{this.state.error}</Text>
-          <Code block>{this.props.patch}</Code>
-        </Stack>
-      );
-    }
-
-    return (
-      <PatchDiff
-        patch={this.props.patch}
-        options={{ theme: { dark: 'pierre-dark', light: 'pierre-light' }, diffStyle: this.props.diffStyle }}
-      />
-    );
-  }
-}
-
 type SafeFileDiffProps = {
   fileDiff: FileDiffMetadata;
   patch: string;
@@ -224,10 +198,15 @@ class SafeFileDiff extends Component<SafeFileDiffProps, SafeFileDiffState> {
     }
 
     return (
-      <FileDiff
-        fileDiff={this.props.fileDiff}
-        options={{ theme: { dark: 'pierre-dark', light: 'pierre-light' }, diffStyle: this.props.diffStyle }}
-      />
+      <div role="region" aria-label="Diff">
+        <PatchScreenReaderSummary patch={this.props.patch} />
+        <div aria-hidden>
+          <FileDiff
+            fileDiff={this.props.fileDiff}
+            options={{ theme: { dark: 'pierre-dark', light: 'pierre-light' }, diffStyle: this.props.diffStyle }}
+          />
+        </div>
+      </div>
     );
   }
 }
@@ -537,6 +516,85 @@ function CommitAnalyticsChart(props: { report: ReviewCommitReportResponse | null
   );
 }
 
+function pastYearSince() {
+  const date = new Date();
+  date.setFullYear(date.getFullYear() - 1);
+  return date.toISOString().slice(0, 10);
+}
+
+function ContributionBars(props: { title: string; rows: ReviewCommitReportContributor[]; limit: number }) {
+  const { title, rows, limit } = props;
+  const visible = rows.slice(0, limit);
+  const total = Math.max(1, rows.reduce((sum, row) => sum + row.additions + row.deletions, 0));
+  const max = Math.max(1, ...visible.map((row) => row.additions + row.deletions));
+
+  return (
+    <Card withBorder padding="sm" style={{ flex: 1, minWidth: 320 }}>
+      <Group justify="space-between" mb="xs">
+        <Text fw={600} size="sm">{title}</Text>
+        <Badge size="xs" variant="light">{rows.length}</Badge>
+      </Group>
+      {visible.length === 0 ? <Text size="xs" c="dimmed">No data.</Text> : null}
+      <Stack gap={6}>
+        {visible.map((row) => {
+          const churn = row.additions + row.deletions;
+          return (
+            <Box key={row.key}>
+              <Group justify="space-between" gap="xs" wrap="nowrap">
+                <Text size="xs" truncate style={{ minWidth: 0 }}>{row.label}</Text>
+                <Text size="xs" c="dimmed" style={{ whiteSpace: 'nowrap' }}>
+                  {row.commits} commits · +{row.additions} / -{row.deletions} · {((churn / total) * 100).toFixed(1)}%
+                </Text>
+              </Group>
+              <Box style={{ display: 'flex', height: 6, borderRadius: 3, overflow: 'hidden', background: 'rgba(255,255,255,0.06)' }}>
+                <Box style={{ width: `${(row.additions / max) * 100}%`, background: 'var(--mantine-color-green-6)' }} />
+                <Box style={{ width: `${(row.deletions / max) * 100}%`, background: 'var(--mantine-color-red-6)' }} />
+              </Box>
+            </Box>
+          );
+        })}
+      </Stack>
+    </Card>
+  );
+}
+
+function CommitContributionBreakdown(props: { report: ReviewCommitReportResponse | null }) {
+  const { report } = props;
+  const periods = report?.months ?? [];
+  if (!report || periods.length === 0) return null;
+
+  const maxCommits = Math.max(1, ...periods.map((period) => period.commits));
+  const barWidth = 14;
+  const chartHeight = 90;
+
+  return (
+    <Stack gap="sm">
+      <Card withBorder padding="sm">
+        <Group justify="space-between" mb="xs">
+          <Text fw={600} size="sm">Commits per {report.aggregation_window === 'weekly' ? 'week' : report.aggregation_window === 'daily' ? 'day' : report.aggregation_window === 'yearly' ? 'year' : 'month'}</Text>
+          <Badge size="xs" variant="light">peak {maxCommits}</Badge>
+        </Group>
+        <ScrollArea type="auto">
+          <svg width={Math.max(240, periods.length * (barWidth + 2))} height={chartHeight + 4} role="img" aria-label="Commits per period" style={{ display: 'block' }}>
+            {periods.map((period, index) => {
+              const barHeight = Math.max(1, (period.commits / maxCommits) * chartHeight);
+              return (
+                <rect key={period.month} x={index * (barWidth + 2)} y={chartHeight - barHeight + 2} width={barWidth} height={barHeight} rx={2} fill="var(--mantine-color-blue-5)">
+                  <title>{`${period.month}: ${period.commits} commits, +${period.additions} / -${period.deletions}`}</title>
+                </rect>
+              );
+            })}
+          </svg>
+        </ScrollArea>
+      </Card>
+      <Group align="stretch" gap="sm" wrap="wrap">
+        <ContributionBars title="Authors" rows={report.authors ?? []} limit={12} />
+        <ContributionBars title="Directory churn" rows={report.directories ?? []} limit={12} />
+      </Group>
+    </Stack>
+  );
+}
+
 function CommitFileRow(props: {
   file: ReviewDiffManifestFileEntry;
   active: boolean;
@@ -560,6 +618,7 @@ function CommitFileRow(props: {
           <Text size="sm" fw={active ? 700 : 500} style={{ wordBreak: 'break-word' }}>{file.path}</Text>
         </Group>
         <Group gap={6} wrap="nowrap">
+          <WhitespaceOnlyBadge kind={file.whitespace_only} />
           <Badge color="green" variant="light">+{file.additions}</Badge>
           <Badge color="red" variant="light">-{file.deletions}</Badge>
           <Button
@@ -579,7 +638,7 @@ function CommitFileRow(props: {
 }
 
 export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
-  const { repoRef } = props;
+  const { repoRef, onAskAi, aiModel, onExportContext } = props;
   const [commits, setCommits] = useState<ReviewCommitSummary[]>([]);
   const [commitReportType, setCommitReportType] = useState<CommitReportType>('commits');
   const [commitReport, setCommitReport] = useState<ReviewCommitReportResponse | null>(null);
@@ -606,6 +665,7 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
   const [filePatchBusyByPath, setFilePatchBusyByPath] = useState<Record<string, boolean>>({});
   const [collapsedByPath, setCollapsedByPath] = useState<Record<string, boolean>>({});
   const [reviewState, setReviewState] = useState<CommitReviewState>(DEFAULT_REVIEW_STATE);
+  const commitExplanation = useAiSummary(`${repoRef}:${selectedCommit?.sha ?? ''}`);
   const [reviewOpen, setReviewOpen] = useState(false);
   const [busy, setBusy] = useState(false);
   const [diffBusy, setDiffBusy] = useState(false);
@@ -784,13 +844,18 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
     return diff.patch;
   }, [reviewState.selected_path, diff?.patch]);
 
+  const whitespaceScanPatches = useMemo(
+    () => (reviewState.selected_path ? [selectedFilePatch] : Object.values(filePatchByPath)),
+    [reviewState.selected_path, selectedFilePatch, filePatchByPath]
+  );
+
   const selectedFilePayloadInfo = useMemo(() => {
     if (!reviewState.selected_path || !diff?.patch?.trim()) {
       return { fileCount: 0, containsSelectedFile: false, isExactSelectedFilePayload: false, selectedUnifiedLineCount: 0 };
     }
 
     try {
-      const files = parsePatchFiles(diff.patch).flatMap((patch) => patch.files ?? []);
+      const files = cachedPatchFiles(diff.patch);
       const selected = files.find((file) => file.name === reviewState.selected_path) ?? null;
       return {
         fileCount: files.length,
@@ -812,7 +877,7 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
         continue;
       }
       try {
-        const parsed = parsePatchFiles(patch).flatMap((item) => item.files ?? []);
+        const parsed = cachedPatchFiles(patch);
         next[file.path] = parsed.find((entry) => entry.name === file.path) ?? parsed[0] ?? null;
       } catch {
         next[file.path] = null;
@@ -960,6 +1025,7 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
           path: nextState.selected_path,
           context_lines: nextState.whole_file ? 1000 : clampContextLines(nextState.context_lines),
           whole_file: nextState.whole_file,
+          whitespace: nextState.whitespace_mode,
         });
         if (refreshDiffRequestIdRef.current !== requestId) return;
         setDiff(json);
@@ -983,6 +1049,7 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
               path: file.path,
               context_lines: nextState.whole_file ? 1000 : clampContextLines(nextState.context_lines),
               whole_file: nextState.whole_file,
+              whitespace: nextState.whitespace_mode,
             });
             return [file.path, json.patch] as const;
           } catch {
@@ -1121,6 +1188,11 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
             <Text fw={700} style={{ wordBreak: 'break-word' }}>{reviewState.selected_path ?? selectedCommit.subject}</Text>
           </Stack>
           <Group gap="xs">
+            <AiSummaryButton
+              summary={commitExplanation}
+              label="Explain this commit"
+              request={() => explainCommitWithAi({ repo_ref: repoRef, commit: selectedCommit.sha, ...aiModel })}
+            />
             {!reviewState.selected_path && hasCommitDiffRows ? (
               <Button size="xs" variant="default" onClick={() => setAllCommitRowsCollapsed(!allCommitRowsCollapsed)}>
                 {allCommitRowsCollapsed ? 'Expand all' : 'Collapse all'}
@@ -1130,11 +1202,13 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
           </Group>
         </Group>
         <Divider mb="sm" />
+        <AiSummaryCard summary={commitExplanation} title={`Explanation of ${selectedCommit.short_sha}`} />
+        {!diffBusy && !error ? <PatchWhitespaceIssues patches={whitespaceScanPatches} /> : null}
         <Box style={{ flex: 1, minHeight: 0 }}>
           {diffBusy ? (
             <Group justify="center" py="xl"><Loader /></Group>
           ) : error ? (
-            <Alert color="red">{error}</Alert>
+            <ErrorPanel message={error} />
           ) : reviewState.selected_path ? (
             !selectedFilePayloadInfo.containsSelectedFile ? (
               <Alert color="yellow" title="Selected file diff unavailable">
@@ -1163,7 +1237,9 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
             ) : selectedFilePatch ? (
               <ScrollArea h="100%" type="auto">
                 <Box p={0} style={{ overflow: 'hidden' }}>
-                  <SafePatchDiff patch={selectedFilePatch} diffStyle={reviewState.diff_style} />
+                  <AskAiHunkMenu repoRef={repoRef} path={reviewState.selected_path ?? ''} patch={selectedFilePatch} onAskAi={onAskAi}>
+                    <SafePatchDiff patch={selectedFilePatch} diffStyle={reviewState.diff_style} />
+                  </AskAiHunkMenu>
                 </Box>
               </ScrollArea>
             ) : (
@@ -1185,6 +1261,7 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
                               <Text size="sm" fw={600} style={{ wordBreak: 'break-word' }}>{file.path}</Text>
                             </Group>
                             <Group gap="xs" wrap="nowrap">
+                              <WhitespaceOnlyBadge kind={file.whitespace_only} />
                               <Badge color="green" variant="light">+{file.additions}</Badge>
                               <Badge color="red" variant="light">-{file.deletions}</Badge>
                             </Group>
@@ -1192,7 +1269,11 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
                         </Box>
                         {!collapsed ? (
                           parsed ? (
-                            <Box p={0} style={{ overflow: 'hidden' }}><SafeFileDiff fileDiff={parsed} patch={patch} diffStyle={reviewState.diff_style} /></Box>
+                            <Box p={0} style={{ overflow: 'hidden' }}>
+                              <AskAiHunkMenu repoRef={repoRef} path={file.path} patch={patch} onAskAi={onAskAi}>
+                                <SafeFileDiff fileDiff={parsed} patch={patch} diffStyle={reviewState.diff_style} />
+                              </AskAiHunkMenu>
+                            </Box>
                           ) : filePatchBusyByPath[file.path] ? (
                             <Box p="md"><Group justify="center" py="lg"><Loader size="sm" /></Group></Box>
                           ) : patch ? (
@@ -1238,6 +1319,9 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
           onChange={(value) => void patchReviewState({ context_lines: clampContextLines(value) })}
           mt="sm"
         />
+        <Box mt="sm">
+          <WhitespaceModeSelect value={reviewState.whitespace_mode} onChange={(value) => void patchReviewState({ whitespace_mode: value })} />
+        </Box>
         <Divider my="sm" />
         <Button
           variant={reviewState.selected_path === null ? 'filled' : 'default'}
@@ -1282,7 +1366,7 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
                   size="xs"
                   value={commitReportType}
                   onChange={(value) => setCommitReportType(value as CommitReportType)}
-                  data={[{ label: 'Commits', value: 'commits' }, { label: 'Analytics', value: 'analytics' }]}
+                  data={[{ label: 'Commits', value: 'commits' }, { label: 'Analytics', value: 'analytics' }, { label: 'Compare', value: 'compare' }, { label: 'Reflog', value: 'reflog' }]}
                 />
                 <SegmentedControl
                   size="xs"
@@ -1295,14 +1379,14 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
                   size="xs"
                   value={commitAggregationWindow}
                   onChange={(value) => setCommitAggregationWindow(value as CommitAggregationWindow)}
-                  data={[{ label: 'Daily', value: 'daily' }, { label: 'Monthly', value: 'monthly' }, { label: 'Yearly', value: 'yearly' }]}
+                  data={[{ label: 'Daily', value: 'daily' }, { label: 'Weekly', value: 'weekly' }, { label: 'Monthly', value: 'monthly' }, { label: 'Yearly', value: 'yearly' }]}
                   disabled={commitReportType !== 'analytics'}
                 />
                 <SegmentedControl
                   size="xs"
                   value={commitAnalyticsColorBy}
                   onChange={(value) => setCommitAnalyticsColorBy(value as CommitAnalyticsColorBy)}
-                  data={[{ label: 'Ext', value: 'extension' }, { label: 'Author', value: 'author' }]}
+                  data={[{ label: 'Ext', value: 'extension' }, { label: 'Author', value: 'author' }, { label: 'Dir', value: 'directory' }]}
                   disabled={commitReportType !== 'analytics'}
                 />
                 <Select
@@ -1315,6 +1399,17 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
                   style={{ minWidth: 180 }}
                 />
                 <TextInput size="xs" label="Since" placeholder="2025-01-01" value={commitReportSince} onChange={(event) => setCommitReportSince(event.currentTarget.value)} />
+                <Button
+                  size="xs"
+                  variant="subtle"
+                  onClick={() => {
+                    setCommitReportSince(pastYearSince());
+                    setCommitReportUntil('');
+                    setCommitAggregationWindow('weekly');
+                  }}
+                >
+                  Past year
+                </Button>
                 <TextInput size="xs" label="Until" placeholder="2025-02-28" value={commitReportUntil} onChange={(event) => setCommitReportUntil(event.currentTarget.value)} />
                 <TextInput size="xs" label="Include paths" placeholder="api/src, web/src" value={commitReportIncludePathsText} onChange={(event) => setCommitReportIncludePathsText(event.currentTarget.value)} />
                 <TextInput size="xs" label="Exclude paths" placeholder="target, node_modules" value={commitReportExcludePathsText} onChange={(event) => setCommitReportExcludePathsText(event.currentTarget.value)} />
@@ -1344,12 +1439,23 @@ export function CommitSummaryPanel(props: CommitSummaryPanelProps) {
           </Group>
           {error && !reviewOpen ? <Alert color="red" mb="sm">{error}</Alert> : null}
           <Divider mb="sm" />
-          {commitReportType === 'analytics' ? (
+          {commitReportType === 'compare' ? (
+            <Box style={{ flex: 1, minHeight: 0, overflowY: 'auto' }}>
+              <CompareBranchesPanel repoRef={repoRef} refOptions={commitRefOptions} defaultRef={commitReportRefName} onExportContext={onExportContext} />
+            </Box>
+          ) : commitReportType === 'reflog' ? (
+            <Box style={{ flex: 1, minHeight: 0, overflowY: 'auto' }}>
+              <ReflogPanel repoRef={repoRef} />
+            </Box>
+          ) : commitReportType === 'analytics' ? (
             (commitReportBusy || busy) && !commitReport ? (
               <Group justify="center" py="xl"><Loader /></Group>
             ) : (
-              <Box style={{ flex: 1, minHeight: 0 }}>
-                <CommitAnalyticsChart report={commitReport} mode={commitAnalyticsMode} />
+              <Box style={{ flex: 1, minHeight: 0, overflowY: 'auto' }}>
+                <Stack gap="md">
+                  <CommitAnalyticsChart report={commitReport} mode={commitAnalyticsMode} />
+                  <CommitContributionBreakdown report={commitReport} />
+                </Stack>
               </Box>
             )
           ) : busy && commits.length === 0 ? (
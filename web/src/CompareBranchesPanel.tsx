@@ -0,0 +1,242 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Alert, Badge, Box, Button, Card, Group, Loader, Modal, ScrollArea, SegmentedControl, Select, SimpleGrid, Stack, Text, Tooltip } from '@mantine/core';
+import { IconArrowsLeftRight, IconGitCherryPick } from '@tabler/icons-react';
+import {
+  cherryPickReviewCommit,
+  compareReviewRefs,
+  getReviewCompareDiff,
+  type ReviewCommitRefOption,
+  type ReviewCommitSummary,
+  type ReviewCompareResponse,
+} from './api';
+import { SafePatchDiff, type DiffStyle } from './SafePatchDiff';
+import { ErrorPanel } from './ErrorPanel';
+import { CodeReviewModal } from './CodeReviewModal';
+import { RangeAnalysisCard } from './RangeAnalysisCard';
+
+type CompareBranchesPanelProps = {
+  repoRef: string;
+  refOptions: ReviewCommitRefOption[];
+  defaultRef?: string;
+  onExportContext?: (paths: string[], gitRef: string) => void;
+};
+
+function CompareCommitList(props: {
+  title: string;
+  commits: ReviewCommitSummary[];
+  busySha: string | null;
+  onCherryPick: (commit: ReviewCommitSummary) => void;
+}) {
+  const { title, commits, busySha, onCherryPick } = props;
+  return (
+    <Card withBorder padding="sm">
+      <Group justify="space-between" mb="xs">
+        <Text fw={600} size="sm">{title}</Text>
+        <Badge size="xs" variant="light">{commits.length}</Badge>
+      </Group>
+      {commits.length === 0 ? <Text size="xs" c="dimmed">No unique commits.</Text> : null}
+      <ScrollArea.Autosize mah={320} type="auto">
+        <Stack gap={4}>
+          {commits.map((commit) => (
+            <Group key={commit.sha} gap="xs" wrap="nowrap" justify="space-between">
+              <Box style={{ minWidth: 0 }}>
+                <Group gap={6} wrap="nowrap">
+                  <Text size="xs" ff="monospace" c="dimmed">{commit.short_sha}</Text>
+                  <Text size="xs" truncate>{commit.subject}</Text>
+                </Group>
+                <Text size="xs" c="dimmed">
+                  {commit.author_name} · {commit.authored_at.slice(0, 10)} · +{commit.additions ?? 0} / -{commit.deletions ?? 0}
+                </Text>
+              </Box>
+              <Tooltip label="Cherry-pick onto the current branch" withArrow>
+                <ActionIcon size="sm" variant="subtle" loading={busySha === commit.sha} onClick={() => onCherryPick(commit)}>
+                  <IconGitCherryPick size={14} />
+                </ActionIcon>
+              </Tooltip>
+            </Group>
+          ))}
+        </Stack>
+      </ScrollArea.Autosize>
+    </Card>
+  );
+}
+
+export function CompareBranchesPanel(props: CompareBranchesPanelProps) {
+  const { repoRef, refOptions, defaultRef, onExportContext } = props;
+  const [base, setBase] = useState<string | null>(defaultRef || null);
+  const [head, setHead] = useState<string | null>(null);
+  const [result, setResult] = useState<ReviewCompareResponse | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+  const [cherryPickSha, setCherryPickSha] = useState<string | null>(null);
+  const [diffPath, setDiffPath] = useState<string | null>(null);
+  const [diffPatch, setDiffPatch] = useState('');
+  const [diffBusy, setDiffBusy] = useState(false);
+  const [diffStyle, setDiffStyle] = useState<DiffStyle>('split');
+  const [reviewOpen, setReviewOpen] = useState(false);
+
+  useEffect(() => {
+    setResult(null);
+    setError(null);
+    setNotice(null);
+  }, [repoRef]);
+
+  useEffect(() => {
+    if (!base && defaultRef) setBase(defaultRef);
+  }, [defaultRef]);
+
+  async function runCompare(nextBase = base, nextHead = head) {
+    if (!repoRef.trim() || !nextBase || !nextHead) return;
+    setBusy(true);
+    setError(null);
+    try {
+      setResult(await compareReviewRefs({ repo_ref: repoRef, base: nextBase, head: nextHead }));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function openDiff(path: string) {
+    if (!result) return;
+    setDiffPath(path);
+    setDiffPatch('');
+    setDiffBusy(true);
+    try {
+      const response = await getReviewCompareDiff({ repo_ref: repoRef, base: result.base, head: result.head, path });
+      setDiffPatch(response.patch);
+    } catch (err) {
+      setDiffPatch(err instanceof Error ? err.message : String(err));
+    } finally {
+      setDiffBusy(false);
+    }
+  }
+
+  async function cherryPick(commit: ReviewCommitSummary) {
+    if (!window.confirm(`Cherry-pick ${commit.short_sha} "${commit.subject}" onto the current branch?`)) return;
+    setCherryPickSha(commit.sha);
+    setError(null);
+    setNotice(null);
+    try {
+      const response = await cherryPickReviewCommit({ repo_ref: repoRef, commit: commit.sha });
+      if (response.ok) {
+        setNotice(`Cherry-picked ${commit.short_sha} as ${response.head?.slice(0, 7) ?? 'new commit'}.`);
+        await runCompare();
+      } else {
+        setError(`Cherry-pick of ${commit.short_sha} was aborted: ${response.message}`);
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setCherryPickSha(null);
+    }
+  }
+
+  const totals = (result?.files ?? []).reduce(
+    (acc, file) => ({ additions: acc.additions + file.additions, deletions: acc.deletions + file.deletions }),
+    { additions: 0, deletions: 0 }
+  );
+
+  return (
+    <Stack gap="sm">
+      <Group gap="xs" align="end">
+        <Select size="xs" label="Base" searchable data={refOptions} value={base} onChange={setBase} style={{ minWidth: 200 }} />
+        <ActionIcon
+          size="lg"
+          variant="default"
+          aria-label="Swap refs"
+          onClick={() => {
+            setBase(head);
+            setHead(base);
+            void runCompare(head, base);
+          }}
+        >
+          <IconArrowsLeftRight size={16} />
+        </ActionIcon>
+        <Select size="xs" label="Compare" searchable data={refOptions} value={head} onChange={setHead} style={{ minWidth: 200 }} />
+        <Button size="xs" onClick={() => void runCompare()} loading={busy} disabled={!base || !head}>Compare</Button>
+      </Group>
+
+      {error ? <ErrorPanel message={error} /> : null}
+      {notice ? <Alert color="teal">{notice}</Alert> : null}
+
+      {busy && !result ? <Group justify="center" py="xl"><Loader /></Group> : null}
+
+      {result ? (
+        <>
+          <Group gap="xs">
+            <Badge variant="light" color="green">{result.ahead} ahead</Badge>
+            <Badge variant="light" color="orange">{result.behind} behind</Badge>
+            <Badge variant="light">{result.files.length} files</Badge>
+            <Badge variant="light" color="green">+{totals.additions}</Badge>
+            <Badge variant="light" color="red">-{totals.deletions}</Badge>
+            {result.merge_base ? <Text size="xs" c="dimmed">merge base {result.merge_base.slice(0, 7)}</Text> : <Text size="xs" c="dimmed">no common ancestor</Text>}
+            <Button size="compact-xs" variant="light" ml="auto" disabled={result.files.length === 0} onClick={() => setReviewOpen(true)}>
+              Review changes
+            </Button>
+          </Group>
+
+          <SimpleGrid cols={2} spacing="sm">
+            <CompareCommitList title={`Only in ${result.head}`} commits={result.ahead_commits} busySha={cherryPickSha} onCherryPick={(commit) => void cherryPick(commit)} />
+            <CompareCommitList title={`Only in ${result.base}`} commits={result.behind_commits} busySha={cherryPickSha} onCherryPick={(commit) => void cherryPick(commit)} />
+          </SimpleGrid>
+
+          <Card withBorder padding="sm">
+            <Text fw={600} size="sm" mb="xs">Changed files since the merge base</Text>
+            {result.files.length === 0 ? <Text size="xs" c="dimmed">No file changes.</Text> : null}
+            <Stack gap={2}>
+              {result.files.map((file) => (
+                <Group
+                  key={file.path}
+                  gap="xs"
+                  wrap="nowrap"
+                  justify="space-between"
+                  px={6}
+                  py={3}
+                  style={{ cursor: 'pointer', borderRadius: 6, background: diffPath === file.path ? 'rgba(34, 139, 230, 0.12)' : undefined }}
+                  onClick={() => void openDiff(file.path)}
+                >
+                  <Group gap={6} wrap="nowrap" style={{ minWidth: 0 }}>
+                    <Badge size="xs" variant="outline">{file.index_status}</Badge>
+                    <Text size="xs" truncate>{file.path}</Text>
+                  </Group>
+                  <Text size="xs" c="dimmed" style={{ whiteSpace: 'nowrap' }}>+{file.additions} / -{file.deletions}</Text>
+                </Group>
+              ))}
+            </Stack>
+          </Card>
+
+          <RangeAnalysisCard repoRef={repoRef} fromRef={result.merge_base ?? result.base} toRef={result.head} onExportContext={onExportContext} />
+        </>
+      ) : null}
+
+      {result ? (
+        <CodeReviewModal
+          opened={reviewOpen}
+          onClose={() => setReviewOpen(false)}
+          repoRef={repoRef}
+          base={result.base}
+          head={result.head}
+          files={result.files}
+        />
+      ) : null}
+
+      <Modal opened={!!diffPath} onClose={() => setDiffPath(null)} size="90%" title={diffPath ?? ''}>
+        <Stack gap="sm">
+          <Group justify="space-between">
+            <Text size="xs" c="dimmed">{result?.base} … {result?.head}</Text>
+            <SegmentedControl
+              size="xs"
+              value={diffStyle}
+              onChange={(value) => setDiffStyle(value as DiffStyle)}
+              data={[{ label: 'Split', value: 'split' }, { label: 'Unified', value: 'unified' }]}
+            />
+          </Group>
+          {diffBusy ? <Group justify="center" py="xl"><Loader /></Group> : diffPatch ? <SafePatchDiff patch={diffPatch} diffStyle={diffStyle} /> : <Text size="sm" c="dimmed">No textual changes.</Text>}
+        </Stack>
+      </Modal>
+    </Stack>
+  );
+}
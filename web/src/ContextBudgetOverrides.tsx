@@ -0,0 +1,130 @@
+import { useState } from 'react';
+import { ActionIcon, Badge, Button, Card, Group, NumberInput, Select, Stack, Text } from '@mantine/core';
+import { IconX } from '@tabler/icons-react';
+
+export type FileBudget = {
+  full?: boolean;
+  max_bytes?: number | null;
+};
+
+type ContextBudgetOverridesProps = {
+  gitRef: string;
+  files: string[];
+  /** Overrides keyed by git ref, then by file or directory path. */
+  value: Record<string, Record<string, FileBudget>>;
+  onChange: (value: Record<string, Record<string, FileBudget>>) => void;
+};
+
+/** Rough guide only; the exporter caps bytes. */
+const BYTES_PER_TOKEN = 4;
+
+export function parseFileBudgets(raw: unknown): Record<string, Record<string, FileBudget>> {
+  if (!raw || typeof raw !== 'object') return {};
+  const out: Record<string, Record<string, FileBudget>> = {};
+  for (const [ref, budgets] of Object.entries(raw as Record<string, unknown>)) {
+    if (!budgets || typeof budgets !== 'object') continue;
+    for (const [path, budget] of Object.entries(budgets as Record<string, unknown>)) {
+      if (!budget || typeof budget !== 'object') continue;
+      const { full, max_bytes } = budget as Record<string, unknown>;
+      (out[ref] ??= {})[path] = { full: full === true, max_bytes: typeof max_bytes === 'number' && max_bytes > 0 ? max_bytes : null };
+    }
+  }
+  return out;
+}
+
+function describe(budget: FileBudget) {
+  if (budget.full) return 'full contents';
+  if (!budget.max_bytes) return 'default cap';
+  return `${budget.max_bytes.toLocaleString()} bytes (~${Math.round(budget.max_bytes / BYTES_PER_TOKEN).toLocaleString()} tokens)`;
+}
+
+export function ContextBudgetOverrides(props: ContextBudgetOverridesProps) {
+  const { gitRef, files, value, onChange } = props;
+  const [path, setPath] = useState<string | null>(null);
+  const [mode, setMode] = useState<'full' | 'cap'>('cap');
+  const [maxBytes, setMaxBytes] = useState<number>(4000);
+
+  const current = value[gitRef] ?? {};
+  const directories = Array.from(new Set(files.flatMap((file) => {
+    const segments = file.split('/');
+    return segments.slice(0, -1).map((_, index) => `${segments.slice(0, index + 1).join('/')}/`);
+  }))).sort();
+  const options = [...directories, ...files];
+
+  function update(next: Record<string, FileBudget>) {
+    const all = { ...value };
+    if (Object.keys(next).length) all[gitRef] = next;
+    else delete all[gitRef];
+    onChange(all);
+  }
+
+  function add() {
+    if (!path) return;
+    update({ ...current, [path]: mode === 'full' ? { full: true, max_bytes: null } : { full: false, max_bytes: maxBytes } });
+    setPath(null);
+  }
+
+  function remove(key: string) {
+    const next = { ...current };
+    delete next[key];
+    update(next);
+  }
+
+  const entries = Object.entries(current).sort(([a], [b]) => a.localeCompare(b));
+
+  return (
+    <Card withBorder padding="sm">
+      <Stack gap="xs">
+        <Group justify="space-between">
+          <Text fw={600} size="sm">Per-path size budgets</Text>
+          <Text size="xs" c="dimmed">Override the max bytes per file for {gitRef}; a file override beats its directory's.</Text>
+        </Group>
+        <Group gap="xs" align="end" grow>
+          <Select
+            size="xs"
+            label="File or directory"
+            placeholder={files.length ? 'Pick a selected path' : 'Select files in the tree first'}
+            searchable
+            data={options}
+            value={path}
+            onChange={setPath}
+            limit={200}
+          />
+          <Select
+            size="xs"
+            label="Budget"
+            data={[{ value: 'cap', label: 'Custom cap' }, { value: 'full', label: 'Always full contents' }]}
+            value={mode}
+            onChange={(next) => setMode(next === 'full' ? 'full' : 'cap')}
+            allowDeselect={false}
+          />
+          <NumberInput
+            size="xs"
+            label="Max bytes"
+            description={`~${Math.round(maxBytes / BYTES_PER_TOKEN).toLocaleString()} tokens`}
+            min={200}
+            step={1000}
+            thousandSeparator=","
+            disabled={mode === 'full'}
+            value={maxBytes}
+            onChange={(next) => setMaxBytes(typeof next === 'number' && next > 0 ? next : 4000)}
+          />
+          <Button size="xs" variant="light" disabled={!path} onClick={add}>Set</Button>
+        </Group>
+        {entries.length ? (
+          <Stack gap={4}>
+            {entries.map(([key, budget]) => (
+              <Group key={key} gap="xs" wrap="nowrap">
+                <ActionIcon size="xs" variant="subtle" color="gray" aria-label="Remove override" onClick={() => remove(key)}>
+                  <IconX size={12} />
+                </ActionIcon>
+                <Text size="xs" truncate style={{ cursor: 'pointer' }} onClick={() => setPath(key)}>{key}</Text>
+                <Badge size="xs" variant="light" color={budget.full ? 'green' : 'blue'}>{describe(budget)}</Badge>
+              </Group>
+            ))}
+          </Stack>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
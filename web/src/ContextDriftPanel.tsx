@@ -0,0 +1,72 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, Group, ScrollArea, Stack, Text, TextInput } from '@mantine/core';
+import { checkContextDrift, type ContextDriftReport } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type ContextDriftPanelProps = {
+  repoRef: string;
+  defaultPath: string;
+};
+
+export function ContextDriftPanel(props: ContextDriftPanelProps) {
+  const { repoRef, defaultPath } = props;
+  const [path, setPath] = useState(defaultPath);
+  const [gitRef, setGitRef] = useState('');
+  const [report, setReport] = useState<ContextDriftReport | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!path.trim()) setPath(defaultPath);
+  }, [defaultPath]);
+
+  async function run() {
+    if (!path.trim()) return;
+    setBusy(true);
+    setError(null);
+    setReport(null);
+    try {
+      setReport(await checkContextDrift({ path: path.trim(), repo_ref: repoRef || undefined, git_ref: gitRef.trim() || undefined }));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Card withBorder padding="sm">
+      <Stack gap="xs">
+        <Text fw={600} size="sm">Context drift</Text>
+        <Group gap="xs" align="end" wrap="nowrap">
+          <TextInput size="xs" label="Exported context file" placeholder="/tmp/repo_context.txt" value={path} onChange={(event) => setPath(event.currentTarget.value)} style={{ flex: 1 }} />
+          <TextInput size="xs" label="Compare against" placeholder="ref from export" value={gitRef} onChange={(event) => setGitRef(event.currentTarget.value)} w={180} />
+          <Button size="xs" variant="light" onClick={() => void run()} loading={busy} disabled={!path.trim()}>Check drift</Button>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        {report ? (
+          <Stack gap={6}>
+            <Group gap="xs">
+              <Badge color={report.stale ? 'orange' : 'green'} variant="light">{report.stale ? 'Stale — regenerate' : 'Up to date'}</Badge>
+              <Text size="xs" c="dimmed">
+                {report.unchanged}/{report.files_checked} files unchanged · exported at {report.export_ref}, compared with {report.current_ref}
+              </Text>
+            </Group>
+            {report.changed.length ? (
+              <ScrollArea.Autosize mah={200} type="auto">
+                <Stack gap={2}>
+                  {report.changed.map((entry) => (
+                    <Group key={entry.path} gap="xs" wrap="nowrap">
+                      <Badge size="xs" variant="outline" color={entry.status === 'deleted' ? 'red' : 'yellow'}>{entry.status}</Badge>
+                      <Text size="xs" truncate>{entry.path}</Text>
+                    </Group>
+                  ))}
+                </Stack>
+              </ScrollArea.Autosize>
+            ) : null}
+          </Stack>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
@@ -0,0 +1,97 @@
+import { useEffect, useRef, useState } from 'react';
+import { Button, Group, Progress, Stack, Text } from '@mantine/core';
+import { cancelContextExport, getContextExportProgress, startContextExport, type ContextExportProgress } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type ContextExportRunnerProps = {
+  /** The context_export payload to run, built from the current (possibly unsaved) settings. */
+  buildPayload: () => Record<string, unknown>;
+  disabled?: boolean;
+};
+
+const POLL_MS = 400;
+
+function formatBytes(bytes: number) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} B`;
+}
+
+/** Runs the export in the background with a progress bar and cancel; cancelling writes nothing. */
+export function ContextExportRunner(props: ContextExportRunnerProps) {
+  const { buildPayload, disabled } = props;
+  const [progress, setProgress] = useState<ContextExportProgress | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const timer = useRef<number | null>(null);
+
+  function stopPolling() {
+    if (timer.current !== null) window.clearInterval(timer.current);
+    timer.current = null;
+  }
+
+  useEffect(() => stopPolling, []);
+
+  async function start() {
+    stopPolling();
+    setError(null);
+    try {
+      const started = await startContextExport(buildPayload());
+      setProgress(started);
+      timer.current = window.setInterval(() => {
+        void getContextExportProgress(started.id)
+          .then((next) => {
+            setProgress(next);
+            if (next.status !== 'running') stopPolling();
+          })
+          .catch((err) => {
+            stopPolling();
+            setError(err instanceof Error ? err.message : String(err));
+          });
+      }, POLL_MS);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  async function cancel() {
+    if (!progress) return;
+    try {
+      setProgress(await cancelContextExport(progress.id));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  const running = progress?.status === 'running';
+  const percent = progress && progress.files_total ? (progress.files_done / progress.files_total) * 100 : 0;
+
+  let statusLine = '';
+  if (progress?.status === 'running') {
+    statusLine = progress.files_total
+      ? `Exporting ${progress.files_done.toLocaleString()} / ${progress.files_total.toLocaleString()} files · ${formatBytes(progress.bytes_written)}`
+      : 'Listing files…';
+  } else if (progress?.status === 'done') {
+    statusLine = `Exported ${progress.files_total.toLocaleString()} files (${formatBytes(progress.bytes_written)}) to ${progress.output_path}`;
+  } else if (progress?.status === 'cancelled') {
+    statusLine = 'Export cancelled; nothing was written.';
+  }
+
+  return (
+    <Stack gap={6}>
+      <Group gap="xs" wrap="nowrap">
+        <Button size="xs" variant="light" onClick={() => void start()} loading={running} disabled={disabled || running}>
+          Export now
+        </Button>
+        {running ? (
+          <Button size="xs" variant="subtle" color="red" onClick={() => void cancel()}>
+            Cancel
+          </Button>
+        ) : null}
+        {statusLine ? <Text size="xs" c="dimmed" truncate>{statusLine}</Text> : null}
+      </Group>
+      {running ? <Progress value={progress?.files_total ? percent : 100} size="sm" animated={!progress?.files_total} /> : null}
+      {progress?.status === 'failed' && progress.error ? <ErrorPanel message={progress.error} /> : null}
+      {error ? <ErrorPanel message={error} /> : null}
+    </Stack>
+  );
+}
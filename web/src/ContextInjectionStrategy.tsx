@@ -0,0 +1,113 @@
+import { Badge, Card, Group, NumberInput, SegmentedControl, Stack, TagsInput, Text } from '@mantine/core';
+
+export type InjectionMode = 'full' | 'changed_since_last' | 'recent_messages' | 'pinned';
+
+export type InjectionStrategy = {
+  mode: InjectionMode;
+  recent_messages: number;
+  pinned: string[];
+};
+
+export type LastInjection = {
+  at: string;
+  mode: InjectionMode;
+  git_ref: string;
+  sent: string[];
+};
+
+type ContextInjectionStrategyProps = {
+  files: string[];
+  value: InjectionStrategy;
+  onChange: (value: InjectionStrategy) => void;
+  last: LastInjection | null;
+};
+
+const MODES: Array<{ value: InjectionMode; label: string; description: string }> = [
+  { value: 'full', label: 'Full', description: 'Every selected file on every turn.' },
+  { value: 'changed_since_last', label: 'Changed since last', description: 'Only selected files whose contents changed since the previous injection; all of them the first time.' },
+  { value: 'recent_messages', label: 'Recent messages', description: 'Repository files whose path appears in the latest prompts or replies, selected or not.' },
+  { value: 'pinned', label: 'Pinned', description: 'Only the pinned files; a pinned directory brings everything under it.' }
+];
+
+export const DEFAULT_INJECTION_STRATEGY: InjectionStrategy = { mode: 'full', recent_messages: 3, pinned: [] };
+
+export function parseInjectionStrategy(raw: unknown): InjectionStrategy {
+  if (!raw || typeof raw !== 'object') return DEFAULT_INJECTION_STRATEGY;
+  const { mode, recent_messages, pinned } = raw as Record<string, unknown>;
+  return {
+    mode: MODES.some((item) => item.value === mode) ? (mode as InjectionMode) : 'full',
+    recent_messages: typeof recent_messages === 'number' && recent_messages > 0 ? recent_messages : 3,
+    pinned: Array.isArray(pinned) ? pinned.filter((path): path is string => typeof path === 'string') : []
+  };
+}
+
+export function parseLastInjection(raw: unknown): LastInjection | null {
+  if (!raw || typeof raw !== 'object') return null;
+  const { at, mode, git_ref, sent } = raw as Record<string, unknown>;
+  if (typeof at !== 'string') return null;
+  return {
+    at,
+    mode: MODES.some((item) => item.value === mode) ? (mode as InjectionMode) : 'full',
+    git_ref: typeof git_ref === 'string' ? git_ref : '',
+    sent: Array.isArray(sent) ? sent.filter((path): path is string => typeof path === 'string') : []
+  };
+}
+
+/** How much of the selection goes to the model on each turn of the loop. */
+export function ContextInjectionStrategy(props: ContextInjectionStrategyProps) {
+  const { files, value, onChange, last } = props;
+  const directories = Array.from(new Set(files.flatMap((file) => {
+    const segments = file.split('/');
+    return segments.slice(0, -1).map((_, index) => `${segments.slice(0, index + 1).join('/')}/`);
+  }))).sort();
+  const description = MODES.find((item) => item.value === value.mode)?.description;
+
+  return (
+    <Card withBorder padding="sm">
+      <Stack gap="xs">
+        <Group justify="space-between">
+          <Text fw={600} size="sm">Injection strategy</Text>
+          <Text size="xs" c="dimmed">Saved with this loop; applies each time the fragment is injected before inference.</Text>
+        </Group>
+        <SegmentedControl
+          size="xs"
+          value={value.mode}
+          onChange={(mode) => onChange({ ...value, mode: mode as InjectionMode })}
+          data={MODES.map((item) => ({ value: item.value, label: item.label }))}
+        />
+        <Text size="xs" c="dimmed">{description}</Text>
+        {value.mode === 'recent_messages' ? (
+          <NumberInput
+            size="xs"
+            label="Inference turns to scan"
+            min={1}
+            max={50}
+            value={value.recent_messages}
+            onChange={(next) => onChange({ ...value, recent_messages: typeof next === 'number' && next > 0 ? next : 3 })}
+            w={200}
+          />
+        ) : null}
+        {value.mode === 'pinned' ? (
+          <TagsInput
+            size="xs"
+            label="Pinned files"
+            placeholder="Type a path or pick a selected one"
+            data={[...directories, ...files]}
+            value={value.pinned}
+            onChange={(pinned) => onChange({ ...value, pinned })}
+            limit={200}
+            clearable
+          />
+        ) : null}
+        {last ? (
+          <Group gap="xs" wrap="nowrap">
+            <Badge size="xs" variant="light">{MODES.find((item) => item.value === last.mode)?.label ?? last.mode}</Badge>
+            <Text size="xs" c="dimmed" truncate title={last.sent.join('\n')}>
+              Last injection at {new Date(last.at).toLocaleString()} sent {last.sent.length} file{last.sent.length === 1 ? '' : 's'} from {last.git_ref}
+            </Text>
+          </Group>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
@@ -0,0 +1,85 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Badge, Card, Group, MultiSelect, Select, Stack, Text } from '@mantine/core';
+import { IconX } from '@tabler/icons-react';
+import { getFileOutline, type OutlineSymbol } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type ContextSymbolPickerProps = {
+  repoRef: string;
+  gitRef: string;
+  files: string[];
+  value: Record<string, string[]>;
+  onChange: (value: Record<string, string[]>) => void;
+};
+
+export function ContextSymbolPicker(props: ContextSymbolPickerProps) {
+  const { repoRef, gitRef, files, value, onChange } = props;
+  const [path, setPath] = useState<string | null>(null);
+  const [symbols, setSymbols] = useState<OutlineSymbol[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setSymbols([]);
+    setError(null);
+    if (!path || !repoRef.trim()) return;
+    void getFileOutline(repoRef, path, gitRef)
+      .then((response) => setSymbols(response.symbols))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [path, repoRef, gitRef]);
+
+  function update(file: string, names: string[]) {
+    const next = { ...value };
+    if (names.length) next[file] = names;
+    else delete next[file];
+    onChange(next);
+  }
+
+  const scoped = Object.entries(value).filter(([, names]) => names.length > 0);
+
+  return (
+    <Card withBorder padding="sm">
+      <Stack gap="xs">
+        <Group justify="space-between">
+          <Text fw={600} size="sm">Symbol-level context</Text>
+          <Text size="xs" c="dimmed">Scoped files export their header and imports plus the chosen symbols only.</Text>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        <Group gap="xs" align="end" grow>
+          <Select
+            size="xs"
+            label="File"
+            placeholder={files.length ? 'Pick a selected file' : 'Select files in the tree first'}
+            searchable
+            data={files}
+            value={path}
+            onChange={setPath}
+            limit={200}
+          />
+          <MultiSelect
+            size="xs"
+            label="Symbols"
+            placeholder={path && symbols.length === 0 ? 'No symbols found' : 'Whole file'}
+            searchable
+            disabled={!path}
+            data={symbols.map((symbol) => ({ value: symbol.qualified_name, label: `${symbol.kind} ${symbol.qualified_name} (${symbol.start_line}-${symbol.end_line})` }))}
+            value={path ? value[path] ?? [] : []}
+            onChange={(names) => path && update(path, names)}
+          />
+        </Group>
+        {scoped.length ? (
+          <Stack gap={4}>
+            {scoped.map(([file, names]) => (
+              <Group key={file} gap="xs" wrap="nowrap">
+                <ActionIcon size="xs" variant="subtle" color="gray" aria-label="Export whole file" onClick={() => update(file, [])}>
+                  <IconX size={12} />
+                </ActionIcon>
+                <Text size="xs" truncate style={{ cursor: 'pointer' }} onClick={() => setPath(file)}>{file}</Text>
+                <Badge size="xs" variant="light">{names.length} symbol{names.length === 1 ? '' : 's'}</Badge>
+              </Group>
+            ))}
+          </Stack>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
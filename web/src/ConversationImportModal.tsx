@@ -0,0 +1,122 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Checkbox, FileInput, Group, Modal, ScrollArea, Stack, Text, TextInput, UnstyledButton } from '@mantine/core';
+import { importConversation, previewConversationImport, type ImportedConversation } from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { pressableProps } from './accessibility';
+
+type ConversationImportModalProps = {
+  opened: boolean;
+  onClose: () => void;
+  runId: string | null;
+  onImported: () => void;
+};
+
+export function ConversationImportModal(props: ConversationImportModalProps) {
+  const { opened, onClose, runId, onImported } = props;
+  const [conversations, setConversations] = useState<ImportedConversation[]>([]);
+  const [selected, setSelected] = useState<number | null>(null);
+  const [query, setQuery] = useState('');
+  const [seedPrompt, setSeedPrompt] = useState(true);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!opened) return;
+    setConversations([]);
+    setSelected(null);
+    setQuery('');
+    setError(null);
+  }, [opened]);
+
+  async function loadFile(file: File | null) {
+    setConversations([]);
+    setSelected(null);
+    setError(null);
+    if (!file) return;
+    setBusy(true);
+    try {
+      const parsed = await previewConversationImport(JSON.parse(await file.text()));
+      setConversations(parsed);
+      setSelected(parsed.length === 1 ? 0 : null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleImport() {
+    const conversation = selected === null ? null : conversations[selected];
+    if (!runId || !conversation) return;
+    setBusy(true);
+    setError(null);
+    try {
+      await importConversation(runId, conversation, seedPrompt);
+      onImported();
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  const needle = query.trim().toLowerCase();
+  const visible = conversations
+    .map((conversation, index) => ({ conversation, index }))
+    .filter(({ conversation }) => !needle || conversation.title.toLowerCase().includes(needle));
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Import chat conversation" size="lg" centered>
+      <Stack gap="sm">
+        <FileInput
+          label="Export file"
+          description="conversations.json from a ChatGPT or Claude data export, or a single conversation from one."
+          accept="application/json,.json"
+          placeholder="Choose file"
+          onChange={(file) => void loadFile(file)}
+          disabled={busy}
+        />
+        {error ? <ErrorPanel message={error} /> : null}
+        {conversations.length > 1 ? (
+          <TextInput placeholder="Filter by title" value={query} onChange={(event) => setQuery(event.currentTarget.value)} />
+        ) : null}
+        {conversations.length > 0 ? (
+          <ScrollArea.Autosize mah={300} type="auto">
+            <Stack gap={2}>
+              {visible.map(({ conversation, index }) => (
+                <UnstyledButton
+                  key={index}
+                  {...pressableProps(() => setSelected(index))}
+                  aria-pressed={selected === index}
+                  px="xs"
+                  py={6}
+                  style={{ borderRadius: 4, background: selected === index ? 'var(--mantine-color-default-hover)' : undefined }}
+                >
+                  <Group justify="space-between" wrap="nowrap">
+                    <Text size="sm" truncate>{conversation.title}</Text>
+                    <Group gap={4} wrap="nowrap">
+                      <Badge size="xs" variant="light" color="gray">{conversation.source === 'chatgpt' ? 'ChatGPT' : 'Claude'}</Badge>
+                      <Badge size="xs" variant="light">{conversation.messages.length} messages</Badge>
+                    </Group>
+                  </Group>
+                </UnstyledButton>
+              ))}
+            </Stack>
+          </ScrollArea.Autosize>
+        ) : null}
+        <Checkbox
+          label="Send the conversation as context with the next inference turn"
+          checked={seedPrompt}
+          onChange={(event) => setSeedPrompt(event.currentTarget.checked)}
+        />
+        <Group justify="flex-end">
+          <Button variant="default" onClick={onClose}>Cancel</Button>
+          <Button onClick={() => void handleImport()} loading={busy} disabled={!runId || selected === null}>
+            Import into run
+          </Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
@@ -0,0 +1,161 @@
+import { useEffect, useRef, useState } from 'react';
+import { ActionIcon, Button, Group, Paper, Stack, Text } from '@mantine/core';
+import { IconX } from '@tabler/icons-react';
+import { getDebugMetrics, type DebugMetricsResponse } from './api';
+import { cacheStats } from './perfMetrics';
+
+type DebugMetricsOverlayProps = {
+  opened: boolean;
+  onClose: () => void;
+};
+
+type FrameStats = { avgMs: number; maxMs: number; fps: number };
+
+type ClientSnapshot = {
+  frame: FrameStats;
+  windows: number;
+  domNodes: number;
+  jsHeapBytes: number | null;
+  caches: ReturnType<typeof cacheStats>;
+};
+
+function formatBytes(bytes: number | null | undefined) {
+  if (bytes == null) return 'n/a';
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+function downloadTextFile(filename: string, contents: string, contentType: string) {
+  const blob = new Blob([contents], { type: contentType });
+  const url = URL.createObjectURL(blob);
+  const anchor = document.createElement('a');
+  anchor.href = url;
+  anchor.download = filename;
+  document.body.appendChild(anchor);
+  anchor.click();
+  anchor.remove();
+  URL.revokeObjectURL(url);
+}
+
+function readClientSnapshot(frame: FrameStats): ClientSnapshot {
+  const memory = (performance as Performance & { memory?: { usedJSHeapSize: number } }).memory;
+  return {
+    frame,
+    windows: document.querySelectorAll('[role="dialog"]').length,
+    domNodes: document.getElementsByTagName('*').length,
+    jsHeapBytes: memory?.usedJSHeapSize ?? null,
+    caches: cacheStats(),
+  };
+}
+
+export function DebugMetricsOverlay(props: DebugMetricsOverlayProps) {
+  const { opened, onClose } = props;
+  const [frame, setFrame] = useState<FrameStats>({ avgMs: 0, maxMs: 0, fps: 0 });
+  const [server, setServer] = useState<DebugMetricsResponse | null>(null);
+  const [serverError, setServerError] = useState<string | null>(null);
+  const samplesRef = useRef<number[]>([]);
+
+  useEffect(() => {
+    if (!opened) return;
+    let handle = 0;
+    let last = performance.now();
+    let windowStart = last;
+    const tick = (now: number) => {
+      samplesRef.current.push(now - last);
+      last = now;
+      if (now - windowStart >= 1000) {
+        const samples = samplesRef.current;
+        const total = samples.reduce((sum, value) => sum + value, 0);
+        setFrame({
+          avgMs: samples.length ? total / samples.length : 0,
+          maxMs: samples.length ? Math.max(...samples) : 0,
+          fps: samples.length,
+        });
+        samplesRef.current = [];
+        windowStart = now;
+      }
+      handle = requestAnimationFrame(tick);
+    };
+    handle = requestAnimationFrame(tick);
+    return () => cancelAnimationFrame(handle);
+  }, [opened]);
+
+  useEffect(() => {
+    if (!opened) return;
+    let cancelled = false;
+    const poll = () => {
+      getDebugMetrics()
+        .then((next) => {
+          if (cancelled) return;
+          setServer(next);
+          setServerError(null);
+        })
+        .catch((err) => {
+          if (!cancelled) setServerError(err instanceof Error ? err.message : String(err));
+        });
+    };
+    poll();
+    const timer = window.setInterval(poll, 2000);
+    return () => {
+      cancelled = true;
+      window.clearInterval(timer);
+    };
+  }, [opened]);
+
+  if (!opened) return null;
+
+  const client = readClientSnapshot(frame);
+
+  function dumpReport() {
+    const report = {
+      generated_at: new Date().toISOString(),
+      user_agent: navigator.userAgent,
+      viewport: { width: window.innerWidth, height: window.innerHeight },
+      client: readClientSnapshot(frame),
+      server,
+      server_error: serverError,
+    };
+    downloadTextFile(`performance-report-${Date.now()}.json`, JSON.stringify(report, null, 2), 'application/json');
+  }
+
+  return (
+    <Paper
+      withBorder
+      shadow="md"
+      p="xs"
+      style={{ position: 'fixed', right: 16, bottom: 16, zIndex: 1000, width: 300, background: 'rgba(20,20,24,0.92)' }}
+    >
+      <Stack gap={4}>
+        <Group justify="space-between">
+          <Text size="xs" fw={700}>Session metrics (Alt+0)</Text>
+          <ActionIcon size="sm" variant="subtle" onClick={onClose}><IconX size={14} /></ActionIcon>
+        </Group>
+        <Text size="xs" ff="monospace">frame {client.frame.avgMs.toFixed(1)} ms avg · {client.frame.maxMs.toFixed(1)} ms max · {client.frame.fps} fps</Text>
+        <Text size="xs" ff="monospace">windows {client.windows} · dom nodes {client.domNodes}</Text>
+        <Text size="xs" ff="monospace">js heap {formatBytes(client.jsHeapBytes)}</Text>
+        {client.caches.length === 0 ? (
+          <Text size="xs" ff="monospace" c="dimmed">render cache: no lookups yet</Text>
+        ) : (
+          client.caches.map((cache) => (
+            <Text key={cache.name} size="xs" ff="monospace">
+              {cache.name} {cache.hitRate == null ? 'n/a' : `${(cache.hitRate * 100).toFixed(0)}%`} hit · {cache.entries} entries
+            </Text>
+          ))
+        )}
+        {server ? (
+          <>
+            <Text size="xs" ff="monospace">git {server.git.last_second}/s now · {server.git.per_second_avg.toFixed(1)}/s avg · {server.git.total} total</Text>
+            <Text size="xs" ff="monospace">server rss {formatBytes(server.process.resident_bytes)}</Text>
+            {Object.entries(server.buffers).map(([name, buffer]) => (
+              <Text key={name} size="xs" ff="monospace">{name} {buffer.count} · {formatBytes(buffer.bytes)}</Text>
+            ))}
+            <Text size="xs" ff="monospace">inference active {server.inference_queue.active} · waiting {server.inference_queue.waiting.length}</Text>
+          </>
+        ) : null}
+        {serverError ? <Text size="xs" c="red">{serverError}</Text> : null}
+        <Button size="compact-xs" variant="light" onClick={dumpReport}>Dump performance report</Button>
+      </Stack>
+    </Paper>
+  );
+}
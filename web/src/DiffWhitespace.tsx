@@ -0,0 +1,123 @@
+import { useMemo, useState } from 'react';
+import { Badge, Box, Button, Code, Group, Select, Stack, Text, Tooltip } from '@mantine/core';
+import type { DiffWhitespaceMode } from './api';
+
+export const WHITESPACE_MODE_OPTIONS: { value: DiffWhitespaceMode; label: string }[] = [
+  { value: 'none', label: 'Show all changes' },
+  { value: 'eol', label: 'Ignore CR at end of line' },
+  { value: 'change', label: 'Ignore whitespace amount (-b)' },
+  { value: 'all', label: 'Ignore all whitespace (-w)' }
+];
+
+export function parseWhitespaceMode(value: unknown): DiffWhitespaceMode {
+  return WHITESPACE_MODE_OPTIONS.some((option) => option.value === value) ? (value as DiffWhitespaceMode) : 'none';
+}
+
+export function WhitespaceModeSelect(props: { value: DiffWhitespaceMode; onChange: (value: DiffWhitespaceMode) => void }) {
+  return (
+    <Select
+      label="Whitespace"
+      data={WHITESPACE_MODE_OPTIONS}
+      value={props.value}
+      allowDeselect={false}
+      onChange={(value) => props.onChange(parseWhitespaceMode(value))}
+    />
+  );
+}
+
+/** Manifest flag for files whose only change is line endings or whitespace. */
+export function WhitespaceOnlyBadge(props: { kind?: 'eol' | 'whitespace' | null }) {
+  if (!props.kind) return null;
+  return props.kind === 'eol' ? (
+    <Tooltip label="Only line endings changed (CRLF ↔ LF)">
+      <Badge color="grape" variant="light">EOL only</Badge>
+    </Tooltip>
+  ) : (
+    <Tooltip label="Only whitespace changed">
+      <Badge color="gray" variant="light">Whitespace only</Badge>
+    </Tooltip>
+  );
+}
+
+type WhitespaceIssue = {
+  file: string;
+  line: number;
+  kinds: ('trailing' | 'mixed' | 'crlf')[];
+  text: string;
+};
+
+/** Whitespace problems on added lines: trailing blanks, tab/space-mixed indentation and CRLF endings. */
+export function scanPatchWhitespace(patch: string) {
+  const issues: WhitespaceIssue[] = [];
+  let file = '';
+  let line = 0;
+  for (const raw of patch.split('\n')) {
+    if (raw.startsWith('+++ ')) {
+      file = raw.slice(4).replace(/^b\//, '').trimEnd();
+    } else if (raw.startsWith('@@')) {
+      const match = /\+(\d+)/.exec(raw);
+      line = match ? Number(match[1]) : 0;
+    } else if (raw.startsWith('+')) {
+      const text = raw.slice(1);
+      const crlf = text.endsWith('\r');
+      const body = crlf ? text.slice(0, -1) : text;
+      const indent = /^[ \t]*/.exec(body)?.[0] ?? '';
+      const kinds: WhitespaceIssue['kinds'] = [];
+      if (/[ \t]+$/.test(body) && body.trim()) kinds.push('trailing');
+      if (indent.includes(' ') && indent.includes('\t')) kinds.push('mixed');
+      if (crlf) kinds.push('crlf');
+      if (kinds.length) issues.push({ file, line, kinds, text });
+      line += 1;
+    } else if (!raw.startsWith('-') && !raw.startsWith('\\')) {
+      line += 1;
+    }
+  }
+  const count = (kind: WhitespaceIssue['kinds'][number]) => issues.filter((issue) => issue.kinds.includes(kind)).length;
+  return { issues, trailing: count('trailing'), mixed: count('mixed'), crlf: count('crlf') };
+}
+
+/** Spaces, tabs and carriage returns drawn as ·, → and ␍ so they can be told apart. */
+export function visualizeWhitespace(text: string) {
+  return text.replace(/ /g, '·').replace(/\t/g, '→   ').replace(/\r/g, '␍');
+}
+
+const ISSUE_LABELS = { trailing: 'trailing whitespace', mixed: 'tabs and spaces mixed', crlf: 'CRLF' } as const;
+const MAX_LISTED_ISSUES = 200;
+
+/** Summary badges plus an expandable list of the offending added lines. */
+export function PatchWhitespaceIssues(props: { patches: string[] }) {
+  const [expanded, setExpanded] = useState(false);
+  const scan = useMemo(() => scanPatchWhitespace(props.patches.join('\n')), [props.patches]);
+  if (!scan.issues.length) return null;
+  return (
+    <Box mb="sm">
+      <Group gap="xs">
+        <Text size="xs" c="dimmed">Added lines with</Text>
+        {scan.trailing ? <Badge color="orange" variant="light">{scan.trailing} trailing whitespace</Badge> : null}
+        {scan.mixed ? <Badge color="yellow" variant="light">{scan.mixed} mixed indentation</Badge> : null}
+        {scan.crlf ? <Badge color="grape" variant="light">{scan.crlf} CRLF</Badge> : null}
+        <Button size="compact-xs" variant="subtle" onClick={() => setExpanded((value) => !value)}>
+          {expanded ? 'Hide lines' : 'Show lines'}
+        </Button>
+      </Group>
+      {expanded ? (
+        <Stack gap={4} mt="xs" style={{ maxHeight: 240, overflow: 'auto' }}>
+          {scan.issues.slice(0, MAX_LISTED_ISSUES).map((issue, index) => (
+            <Group key={index} gap="xs" wrap="nowrap" align="flex-start">
+              <Text size="xs" c="dimmed" style={{ whiteSpace: 'nowrap' }}>
+                {issue.file}:{issue.line}
+              </Text>
+              <Code style={{ whiteSpace: 'pre', flex: 1 }}>{visualizeWhitespace(issue.text)}</Code>
+              <Text size="xs" c="dimmed" style={{ whiteSpace: 'nowrap' }}>
+                {issue.kinds.map((kind) => ISSUE_LABELS[kind]).join(', ')}
+              </Text>
+            </Group>
+          ))}
+          {scan.issues.length > MAX_LISTED_ISSUES ? (
+            <Text size="xs" c="dimmed">{scan.issues.length - MAX_LISTED_ISSUES} more not shown.</Text>
+          ) : null}
+        </Stack>
+      ) : null}
+    </Box>
+  );
+}
@@ -0,0 +1,123 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Group, Loader, Modal, ScrollArea, Stack, Text, TextInput } from '@mantine/core';
+import { discardReviewChanges, previewReviewDiscard, type ReviewDiscardResponse } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+const TYPED_CONFIRM_THRESHOLD = 10;
+
+type DiscardChangesModalProps = {
+  repoRef: string;
+  /** `null` discards every change in the working tree. */
+  paths: string[] | null;
+  opened: boolean;
+  onClose: () => void;
+  onDiscarded: (result: ReviewDiscardResponse) => void;
+};
+
+function formatBytes(bytes: number) {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+export function DiscardChangesModal(props: DiscardChangesModalProps) {
+  const { repoRef, paths, opened, onClose, onDiscarded } = props;
+  const [preview, setPreview] = useState<ReviewDiscardResponse | null>(null);
+  const [typed, setTyped] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function loadPreview() {
+    setPreview(null);
+    setError(null);
+    try {
+      setPreview(await previewReviewDiscard({ repo_ref: repoRef, paths }));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    setTyped('');
+    if (opened && repoRef.trim()) void loadPreview();
+  }, [opened, repoRef, paths?.join('\n')]);
+
+  async function discard() {
+    if (!preview) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const result = await discardReviewChanges({ repo_ref: repoRef, paths, expected_total: preview.total });
+      onDiscarded(result);
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      void loadPreview();
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  const total = preview?.total ?? 0;
+  const phrase = `discard ${total}`;
+  const needsTyped = total > TYPED_CONFIRM_THRESHOLD;
+  const groups = preview
+    ? [
+        { label: 'Restored from HEAD', color: 'yellow', items: preview.plan.restore },
+        { label: 'Removed from index and deleted', color: 'orange', items: preview.plan.added },
+        { label: 'Untracked, will be deleted', color: 'red', items: preview.plan.untracked },
+      ].filter((group) => group.items.length > 0)
+    : [];
+
+  return (
+    <Modal opened={opened} onClose={onClose} title={paths ? 'Discard changes' : 'Discard all changes'} size="lg" centered>
+      <Stack gap="sm">
+        {error ? <ErrorPanel message={error} /> : null}
+        {!preview && !error ? <Group gap="xs"><Loader size="sm" /><Text size="sm">Collecting changes…</Text></Group> : null}
+        {preview && total === 0 ? <Text size="sm" c="dimmed">Nothing to discard.</Text> : null}
+        {preview && total > 0 ? (
+          <>
+            <Group gap="xs">
+              <Badge variant="light">{total} path{total === 1 ? '' : 's'}</Badge>
+              {preview.plan.untracked.length ? (
+                <Badge variant="light" color="red">
+                  {preview.plan.untracked.length} untracked · {formatBytes(preview.untracked_bytes)}
+                </Badge>
+              ) : null}
+            </Group>
+            <ScrollArea.Autosize mah={280} type="auto">
+              <Stack gap="xs">
+                {groups.map((group) => (
+                  <Stack key={group.label} gap={2}>
+                    <Text size="xs" fw={600} c={group.color}>{group.label} ({group.items.length})</Text>
+                    {group.items.map((path) => <Text key={path} size="xs" ff="monospace" truncate>{path}</Text>)}
+                  </Stack>
+                ))}
+              </Stack>
+            </ScrollArea.Autosize>
+            <Text size="xs" c="dimmed">A copy of every affected file goes to the repository trash; recover it with History → Undo or History → Trash.</Text>
+            {needsTyped ? (
+              <TextInput
+                size="xs"
+                label={<>Type <b>{phrase}</b> to confirm</>}
+                value={typed}
+                onChange={(event) => setTyped(event.currentTarget.value)}
+              />
+            ) : null}
+          </>
+        ) : null}
+        <Group justify="flex-end">
+          <Button variant="default" onClick={onClose}>Cancel</Button>
+          <Button
+            color="red"
+            loading={busy}
+            disabled={!preview || total === 0 || (needsTyped && typed.trim() !== phrase)}
+            onClick={() => void discard()}
+          >
+            Discard {total || ''}
+          </Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
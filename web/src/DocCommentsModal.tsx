@@ -0,0 +1,89 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Group, Loader, Modal, ScrollArea, Stack, Text } from '@mantine/core';
+import { applyDocComments, proposeDocComments, type AiActionModel, type DocCommentProposal } from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { SafePatchDiff } from './SafePatchDiff';
+
+type DocCommentsModalProps = {
+  /** The file to document; `symbol` narrows it to one function. Null keeps the modal closed. */
+  request: { path: string; symbol: string | null } | null;
+  repoRef: string;
+  aiModel?: AiActionModel;
+  onClose: () => void;
+  onApplied: (path: string) => void;
+};
+
+/** Generates doc comments for undocumented public functions and previews them before the changeset applier writes them. */
+export function DocCommentsModal(props: DocCommentsModalProps) {
+  const { request, repoRef, aiModel, onClose, onApplied } = props;
+  const [proposal, setProposal] = useState<DocCommentProposal | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [applying, setApplying] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!request) return;
+    let cancelled = false;
+    setProposal(null);
+    setError(null);
+    setBusy(true);
+    void proposeDocComments({ repo_ref: repoRef, path: request.path, symbol: request.symbol, ...aiModel })
+      .then((next) => { if (!cancelled) setProposal(next); })
+      .catch((err) => { if (!cancelled) setError(err instanceof Error ? err.message : String(err)); })
+      .finally(() => { if (!cancelled) setBusy(false); });
+    return () => {
+      cancelled = true;
+    };
+  }, [request, repoRef]);
+
+  async function apply() {
+    if (!proposal) return;
+    setApplying(true);
+    setError(null);
+    try {
+      const result = await applyDocComments({ repo_ref: repoRef, payload_text: proposal.payload_text });
+      if (!result.ok) {
+        setError(result.status ?? result.summary ?? 'The changeset did not apply cleanly.');
+        return;
+      }
+      onApplied(proposal.path);
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setApplying(false);
+    }
+  }
+
+  const title = request?.symbol ? `Document ${request.symbol}` : `Document ${request?.path ?? ''}`;
+
+  return (
+    <Modal opened={!!request} onClose={onClose} size="xl" title={title}>
+      <Stack gap="sm">
+        {busy ? (
+          <Group gap="xs" justify="center" py="xl">
+            <Loader size="sm" />
+            <Text size="sm" c="dimmed">Asking the model for doc comments…</Text>
+          </Group>
+        ) : null}
+        {error ? <ErrorPanel message={error} /> : null}
+        {proposal ? (
+          <>
+            <Group gap="xs">
+              <Text size="sm">Adds docs to</Text>
+              {proposal.documented.map((name) => <Badge key={name} variant="light" ff="monospace">{name}</Badge>)}
+              <Badge size="xs" variant="outline">{proposal.model}</Badge>
+            </Group>
+            <ScrollArea.Autosize mah="60vh" type="auto">
+              <SafePatchDiff patch={proposal.patch} diffStyle="unified" />
+            </ScrollArea.Autosize>
+            <Group justify="flex-end">
+              <Button variant="default" onClick={onClose}>Discard</Button>
+              <Button loading={applying} onClick={() => void apply()}>Apply</Button>
+            </Group>
+          </>
+        ) : null}
+      </Stack>
+    </Modal>
+  );
+}
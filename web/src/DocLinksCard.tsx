@@ -0,0 +1,110 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, Group, ScrollArea, Stack, Table, Text, Title } from '@mantine/core';
+import { scanDocLinks, type DocLinkReport } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type DocLinksCardProps = {
+  repoRef: string;
+  onOpenFile?: (path: string) => void;
+};
+
+/** Broken relative links and images in Markdown, plus docs that nothing links to. */
+export function DocLinksCard(props: DocLinksCardProps) {
+  const { repoRef, onOpenFile } = props;
+  const [report, setReport] = useState<DocLinkReport | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function scan() {
+    if (!repoRef.trim()) return;
+    setBusy(true);
+    setError(null);
+    try {
+      setReport(await scanDocLinks(repoRef));
+    } catch (err) {
+      setReport(null);
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  useEffect(() => {
+    setReport(null);
+    void scan();
+  }, [repoRef]);
+
+  const pathLink = (path: string, label = path) => (
+    <Text
+      size="xs"
+      ff="monospace"
+      style={{ cursor: onOpenFile ? 'pointer' : undefined }}
+      onClick={onOpenFile ? () => onOpenFile(path) : undefined}
+    >
+      {label}
+    </Text>
+  );
+
+  const problems = report ? report.broken.length + report.orphans.length : 0;
+
+  return (
+    <Card withBorder>
+      <Stack gap="md">
+        <Group justify="space-between" align="flex-start" wrap="wrap">
+          <Stack gap={2}>
+            <Group gap="xs">
+              <Title order={4}>Docs problems</Title>
+              {report ? <Badge variant="light" color={problems ? 'orange' : 'green'}>{problems} problems</Badge> : null}
+            </Group>
+            <Text size="sm" c="dimmed">
+              Relative links and images in Markdown that point nowhere, and docs no other file links to or mentions.
+            </Text>
+          </Stack>
+          <Button variant="default" size="xs" onClick={() => void scan()} loading={busy} disabled={!repoRef.trim()}>Rescan</Button>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+
+        {report && report.broken.length ? (
+          <ScrollArea.Autosize mah={320}>
+            <Table striped highlightOnHover>
+              <Table.Thead>
+                <Table.Tr>
+                  <Table.Th>Source</Table.Th>
+                  <Table.Th>Link</Table.Th>
+                  <Table.Th>Missing target</Table.Th>
+                </Table.Tr>
+              </Table.Thead>
+              <Table.Tbody>
+                {report.broken.map((link) => (
+                  <Table.Tr key={`${link.source}:${link.line}:${link.target}`}>
+                    <Table.Td>{pathLink(link.source, `${link.source}:${link.line}`)}</Table.Td>
+                    <Table.Td>
+                      <Group gap={4} wrap="nowrap">
+                        {link.image ? <Badge size="xs" variant="outline">image</Badge> : null}
+                        <Text size="xs" ff="monospace">{link.target}</Text>
+                      </Group>
+                    </Table.Td>
+                    <Table.Td>
+                      {link.resolved ? pathLink(link.resolved) : <Text size="xs" c="dimmed">outside the repository</Text>}
+                    </Table.Td>
+                  </Table.Tr>
+                ))}
+              </Table.Tbody>
+            </Table>
+          </ScrollArea.Autosize>
+        ) : null}
+
+        {report && report.orphans.length ? (
+          <Stack gap={4}>
+            <Text size="sm" fw={500}>Orphaned docs</Text>
+            {report.orphans.map((path) => <div key={path}>{pathLink(path)}</div>)}
+          </Stack>
+        ) : null}
+
+        {report && !problems ? (
+          <Text size="sm" c="dimmed">All links in {report.markdown_files} Markdown files resolve and every doc is referenced.</Text>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
@@ -0,0 +1,78 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Code, Group, Loader, Modal, Paper, Stack, Text } from '@mantine/core';
+import { IconRefresh } from '@tabler/icons-react';
+import { getDoctorReport, type DoctorCheck, type DoctorCheckStatus } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type DoctorModalProps = {
+  opened: boolean;
+  onClose: () => void;
+  /** Called with every fresh report so the shell can keep its startup warning in sync. */
+  onReport?: (checks: DoctorCheck[]) => void;
+};
+
+const STATUS_COLORS: Record<DoctorCheckStatus, string> = {
+  ok: 'green',
+  warn: 'yellow',
+  fail: 'red',
+  skip: 'gray'
+};
+
+/** Preflight checks for git, identity, API key, shell, disk space and long paths, with fix hints. */
+export function DoctorModal(props: DoctorModalProps) {
+  const { opened, onClose, onReport } = props;
+  const [checks, setChecks] = useState<DoctorCheck[] | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function run() {
+    setBusy(true);
+    setError(null);
+    try {
+      const next = await getDoctorReport();
+      setChecks(next);
+      onReport?.(next);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  useEffect(() => {
+    if (opened) void run();
+  }, [opened]);
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Environment doctor" size="lg" centered>
+      <Stack gap="sm">
+        <Group justify="space-between">
+          <Text size="sm" c="dimmed">Checks what runs depend on, so problems show up here instead of halfway through a stage.</Text>
+          <Button size="xs" variant="default" leftSection={<IconRefresh size={14} />} loading={busy} onClick={() => void run()}>
+            Re-run
+          </Button>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        {!checks && busy ? <Loader size="sm" /> : null}
+        {checks?.map((check) => (
+          <Paper key={check.id} withBorder p="sm">
+            <Stack gap={4}>
+              <Group justify="space-between" wrap="nowrap">
+                <Text size="sm" fw={600}>{check.label}</Text>
+                <Badge color={STATUS_COLORS[check.status]} variant="light">{check.status}</Badge>
+              </Group>
+              <Text size="xs" c="dimmed" style={{ wordBreak: 'break-word' }}>{check.detail}</Text>
+              {check.hint ? (
+                check.hint.startsWith('Run: ') ? (
+                  <Text size="xs">Fix: <Code>{check.hint.slice('Run: '.length)}</Code></Text>
+                ) : (
+                  <Text size="xs">Fix: {check.hint}</Text>
+                )
+              ) : null}
+            </Stack>
+          </Paper>
+        ))}
+      </Stack>
+    </Modal>
+  );
+}
@@ -0,0 +1,158 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, Group, Loader, Modal, NumberInput, ScrollArea, Stack, Text, Title } from '@mantine/core';
+import { diffDuplicateFiles, findDuplicateFiles, type DuplicateReport } from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { SafePatchDiff } from './SafePatchDiff';
+
+type DuplicatesCardProps = {
+  repoRef: string;
+  onOpenFile?: (path: string) => void;
+};
+
+type DiffTarget = { left: string; right: string };
+
+function formatBytes(bytes: number) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} B`;
+}
+
+/** Exact copies (same blob) and near duplicates (similar size and shingles), with a side-by-side diff per pair. */
+export function DuplicatesCard(props: DuplicatesCardProps) {
+  const { repoRef, onOpenFile } = props;
+  const [thresholdPercent, setThresholdPercent] = useState(80);
+  const [report, setReport] = useState<DuplicateReport | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [diffTarget, setDiffTarget] = useState<DiffTarget | null>(null);
+  const [diffPatch, setDiffPatch] = useState<string | null>(null);
+  const [diffError, setDiffError] = useState<string | null>(null);
+
+  async function scan() {
+    if (!repoRef.trim()) return;
+    setBusy(true);
+    setError(null);
+    try {
+      setReport(await findDuplicateFiles(repoRef, thresholdPercent / 100));
+    } catch (err) {
+      setReport(null);
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function openDiff(target: DiffTarget) {
+    setDiffTarget(target);
+    setDiffPatch(null);
+    setDiffError(null);
+    try {
+      setDiffPatch((await diffDuplicateFiles(repoRef, target.left, target.right)).patch);
+    } catch (err) {
+      setDiffError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    setReport(null);
+    setError(null);
+  }, [repoRef]);
+
+  const pathLink = (path: string) => (
+    <Text
+      key={path}
+      size="xs"
+      ff="monospace"
+      truncate
+      style={{ cursor: onOpenFile ? 'pointer' : undefined }}
+      onClick={onOpenFile ? () => onOpenFile(path) : undefined}
+    >
+      {path}
+    </Text>
+  );
+
+  return (
+    <Card withBorder>
+      <Stack gap="sm">
+        <Group justify="space-between" align="flex-start" wrap="wrap">
+          <Stack gap={2}>
+            <Title order={5}>Duplicate files</Title>
+            <Text size="xs" c="dimmed">Copy-pasted modules are worth consolidating before asking an AI to refactor them.</Text>
+          </Stack>
+          <Group gap="xs" align="flex-end">
+            <NumberInput
+              size="xs"
+              label="Similarity (%)"
+              min={10}
+              max={100}
+              value={thresholdPercent}
+              onChange={(value) => setThresholdPercent(Number(value) || 80)}
+              w={120}
+            />
+            <Button size="xs" onClick={() => void scan()} loading={busy} disabled={!repoRef.trim()}>
+              {report ? 'Rescan' : 'Scan'}
+            </Button>
+          </Group>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+
+        {report ? (
+          <>
+            <Group gap="xs">
+              <Badge variant="light">{report.files_scanned} files scanned</Badge>
+              <Badge variant="light" color={report.exact.length ? 'orange' : 'gray'}>{report.exact.length} exact clusters</Badge>
+              <Badge variant="light" color={report.near.length ? 'yellow' : 'gray'}>{report.near.length} near-duplicate clusters</Badge>
+            </Group>
+            {!report.exact.length && !report.near.length ? <Text size="sm" c="dimmed">No duplicates found.</Text> : null}
+            <ScrollArea.Autosize mah={420}>
+              <Stack gap="xs">
+                {report.exact.map((cluster) => (
+                  <Card key={cluster.blob} withBorder padding="xs">
+                    <Group justify="space-between" wrap="nowrap" mb={4}>
+                      <Text size="xs" fw={500}>Identical · {cluster.paths.length} copies · {formatBytes(cluster.bytes)} each</Text>
+                      <Text size="xs" c="dimmed" ff="monospace">{cluster.blob.slice(0, 10)}</Text>
+                    </Group>
+                    {cluster.paths.map(pathLink)}
+                  </Card>
+                ))}
+                {report.near.map((cluster) => (
+                  <Card key={cluster.paths.join('\n')} withBorder padding="xs">
+                    <Text size="xs" fw={500} mb={4}>Similar · {cluster.paths.length} files</Text>
+                    {cluster.pairs.map((pair) => (
+                      <Group key={`${pair.left}\n${pair.right}`} gap="xs" wrap="nowrap" justify="space-between">
+                        <Group gap={6} wrap="nowrap" style={{ minWidth: 0 }}>
+                          {pathLink(pair.left)}
+                          <Text size="xs" c="dimmed">↔</Text>
+                          {pathLink(pair.right)}
+                        </Group>
+                        <Group gap={6} wrap="nowrap">
+                          <Badge size="xs" variant="light">{Math.round(pair.similarity * 100)}%</Badge>
+                          <Button size="compact-xs" variant="subtle" onClick={() => void openDiff({ left: pair.left, right: pair.right })}>
+                            Diff
+                          </Button>
+                        </Group>
+                      </Group>
+                    ))}
+                  </Card>
+                ))}
+              </Stack>
+            </ScrollArea.Autosize>
+          </>
+        ) : null}
+      </Stack>
+
+      <Modal
+        opened={diffTarget !== null}
+        onClose={() => setDiffTarget(null)}
+        title={diffTarget ? `${diffTarget.left} ↔ ${diffTarget.right}` : ''}
+        size="90%"
+      >
+        {diffError ? <ErrorPanel message={diffError} /> : null}
+        {diffPatch === null && !diffError ? <Loader size="sm" /> : null}
+        {diffPatch !== null ? (
+          diffPatch.trim() ? <SafePatchDiff patch={diffPatch} diffStyle="split" /> : <Text size="sm" c="dimmed">The files are identical.</Text>
+        ) : null}
+      </Modal>
+    </Card>
+  );
+}
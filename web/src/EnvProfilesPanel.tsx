@@ -0,0 +1,145 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Button, Group, Select, Stack, Text, TextInput, Textarea, Tooltip } from '@mantine/core';
+import { IconTrash } from '@tabler/icons-react';
+import { getAppSettings, patchAppSettings, type TerminalRepoSettings } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type EnvProfilesPanelProps = {
+  repoRef: string;
+  disabled?: boolean;
+};
+
+const EMPTY_SETTINGS: TerminalRepoSettings = { active_profile: null, profiles: {} };
+
+function envToText(env: Record<string, string>) {
+  return Object.entries(env).map(([key, value]) => `${key}=${value}`).join('\n');
+}
+
+function textToEnv(text: string) {
+  const env: Record<string, string> = {};
+  for (const line of text.split('\n')) {
+    const trimmed = line.trim();
+    if (!trimmed || trimmed.startsWith('#')) continue;
+    const index = trimmed.indexOf('=');
+    if (index <= 0) continue;
+    env[trimmed.slice(0, index).trim()] = trimmed.slice(index + 1);
+  }
+  return env;
+}
+
+export function EnvProfilesPanel(props: EnvProfilesPanelProps) {
+  const { repoRef, disabled } = props;
+  const [settings, setSettings] = useState<TerminalRepoSettings>(EMPTY_SETTINGS);
+  const [editing, setEditing] = useState<string | null>(null);
+  const [name, setName] = useState('');
+  const [workingDir, setWorkingDir] = useState('');
+  const [envText, setEnvText] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  function edit(profileName: string | null, source = settings) {
+    const profile = profileName ? source.profiles[profileName] : null;
+    setEditing(profileName);
+    setName(profileName ?? '');
+    setWorkingDir(profile?.working_dir ?? '');
+    setEnvText(profile ? envToText(profile.env ?? {}) : '');
+  }
+
+  useEffect(() => {
+    setSettings(EMPTY_SETTINGS);
+    edit(null, EMPTY_SETTINGS);
+    if (!repoRef.trim()) return;
+    void getAppSettings()
+      .then((response) => {
+        const next = response.terminal?.by_repo?.[repoRef] ?? EMPTY_SETTINGS;
+        setSettings(next);
+        edit(next.active_profile, next);
+      })
+      .catch(() => setSettings(EMPTY_SETTINGS));
+  }, [repoRef]);
+
+  async function persist(patch: Record<string, unknown>) {
+    setError(null);
+    try {
+      const response = await patchAppSettings({ terminal: { by_repo: { [repoRef]: patch } } });
+      const next = response.terminal?.by_repo?.[repoRef] ?? EMPTY_SETTINGS;
+      setSettings(next);
+      return next;
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      return null;
+    }
+  }
+
+  async function save() {
+    const nextName = name.trim();
+    if (!nextName) return;
+    const profiles: Record<string, unknown> = { [nextName]: { env: textToEnv(envText), working_dir: workingDir.trim() } };
+    if (editing && editing !== nextName) profiles[editing] = null;
+    const renamedActive = editing && settings.active_profile === editing;
+    const next = await persist(renamedActive ? { profiles, active_profile: nextName } : { profiles });
+    if (next) edit(nextName, next);
+  }
+
+  async function remove() {
+    if (!editing || !window.confirm(`Delete env profile "${editing}"?`)) return;
+    const next = await persist({ profiles: { [editing]: null } });
+    if (next) edit(null, next);
+  }
+
+  const names = Object.keys(settings.profiles ?? {}).sort();
+
+  return (
+    <Stack gap="xs">
+      <Text fw={600} size="sm">Environment profile</Text>
+      <Text size="xs" c="dimmed">Variables and working directory applied to compile and post-apply commands for this repository.</Text>
+      {error ? <ErrorPanel message={error} /> : null}
+      <Select
+        size="xs"
+        label="Active profile"
+        placeholder="None (inherit server environment)"
+        data={names}
+        value={settings.active_profile}
+        clearable
+        disabled={disabled || !repoRef.trim()}
+        onChange={(value) => {
+          void persist({ active_profile: value });
+          edit(value);
+        }}
+      />
+      <Group gap="xs" align="end" wrap="nowrap">
+        <TextInput size="xs" label="Profile name" placeholder="dotnet dev" value={name} onChange={(event) => setName(event.currentTarget.value)} style={{ flex: 1 }} />
+        <Button size="xs" variant="default" onClick={() => edit(null)}>New</Button>
+        {editing ? (
+          <Tooltip label="Delete profile" withArrow>
+            <ActionIcon size="lg" variant="subtle" color="red" onClick={() => void remove()} disabled={disabled}>
+              <IconTrash size={14} />
+            </ActionIcon>
+          </Tooltip>
+        ) : null}
+      </Group>
+      <TextInput
+        size="xs"
+        label="Working directory"
+        description="Absolute, or relative to the repository root."
+        placeholder="."
+        value={workingDir}
+        onChange={(event) => setWorkingDir(event.currentTarget.value)}
+      />
+      <Textarea
+        size="xs"
+        label="Variables"
+        placeholder={'DOTNET_ENVIRONMENT=Development\nVIRTUAL_ENV=.venv'}
+        value={envText}
+        onChange={(event) => setEnvText(event.currentTarget.value)}
+        minRows={3}
+        autosize
+        styles={{ input: { fontFamily: 'monospace' } }}
+      />
+      <Group justify="flex-end">
+        <Button size="xs" onClick={() => void save()} disabled={disabled || !name.trim() || !repoRef.trim()}>
+          {editing ? 'Save profile' : 'Add profile'}
+        </Button>
+      </Group>
+    </Stack>
+  );
+}
@@ -0,0 +1,49 @@
+import { useState } from 'react';
+import { ActionIcon, Alert, Anchor, Code, CopyButton, Group, Stack, Text, Tooltip } from '@mantine/core';
+import { IconCheck, IconCopy } from '@tabler/icons-react';
+import { apiErrorInfoFor } from './api';
+
+type ErrorPanelProps = {
+  message: string;
+};
+
+/** Short inline message with expandable details, for errors surfaced by API calls. */
+export function ErrorPanel(props: ErrorPanelProps) {
+  const { message } = props;
+  const [expanded, setExpanded] = useState(false);
+  const info = apiErrorInfoFor(message);
+  const detail = info && info.detail !== info.message ? info.detail : null;
+  const copyText = info ? [`${info.title} (${info.component || 'app'})`, info.detail, info.hint ?? ''].filter(Boolean).join('\n') : message;
+
+  return (
+    <Alert color="red" title={info?.title}>
+      <Stack gap={4}>
+        <Group justify="space-between" wrap="nowrap" align="flex-start">
+          <Text size="sm" style={{ wordBreak: 'break-word' }}>{message}</Text>
+          <CopyButton value={copyText}>
+            {({ copied, copy }) => (
+              <Tooltip label={copied ? 'Copied' : 'Copy error'}>
+                <ActionIcon size="sm" variant="subtle" color={copied ? 'teal' : 'gray'} onClick={copy}>
+                  {copied ? <IconCheck size={14} /> : <IconCopy size={14} />}
+                </ActionIcon>
+              </Tooltip>
+            )}
+          </CopyButton>
+        </Group>
+        {info?.hint ? <Text size="xs" c="dimmed">{info.hint}</Text> : null}
+        {detail ? (
+          <>
+            <Anchor component="button" type="button" size="xs" onClick={() => setExpanded((value) => !value)}>
+              {expanded ? 'Hide details' : 'Details'}
+            </Anchor>
+            {expanded ? (
+              <Code block style={{ whiteSpace: 'pre-wrap', wordBreak: 'break-word', maxHeight: 240, overflow: 'auto' }}>
+                {info?.component ? `[${info.component}] ` : ''}{detail}
+              </Code>
+            ) : null}
+          </>
+        ) : null}
+      </Stack>
+    </Alert>
+  );
+}
@@ -0,0 +1,209 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Badge, Button, Code, Group, Menu, Stack, Switch, Text, TextInput, Textarea } from '@mantine/core';
+import { IconPlus, IconTrash } from '@tabler/icons-react';
+import { previewContextExcludes, type ExcludePreview } from './api';
+
+/** Common exclude sets; patterns match repo-relative paths, like the export itself. */
+const EXCLUDE_PRESETS: { label: string; patterns: string[] }[] = [
+  {
+    label: 'Lockfiles',
+    patterns: [
+      '(^|/)(package-lock\\.json|yarn\\.lock|pnpm-lock\\.yaml|bun\\.lockb)$',
+      '(^|/)(Cargo|Gemfile|composer|poetry|Pipfile)\\.lock$',
+      '(^|/)go\\.sum$',
+    ],
+  },
+  {
+    label: 'Build outputs',
+    patterns: ['(^|/)(target|dist|build|out|\\.next|coverage)/', '(^|/)__pycache__/', '\\.(o|a|so|dll|exe|class|pyc)$', '\\.min\\.(js|css)$'],
+  },
+  {
+    label: 'Media',
+    patterns: ['\\.(png|jpe?g|gif|webp|ico|bmp|tiff?)$', '\\.(mp3|mp4|mov|wav|ogg|webm)$', '\\.(woff2?|ttf|otf|eot|pdf)$'],
+  },
+  {
+    label: 'Vendored deps',
+    patterns: ['(^|/)node_modules/', '(^|/)vendor/', '(^|/)third_party/', '(^|/)\\.venv/'],
+  },
+];
+
+type ExcludeRow = { pattern: string; enabled: boolean };
+
+/** One entry per line; a leading `#` keeps the pattern in the list but switches it off. */
+function parseRows(text: string): ExcludeRow[] {
+  return text
+    .split('\n')
+    .map((line) => line.trim())
+    .filter(Boolean)
+    .map((line) => (line.startsWith('#') ? { pattern: line.replace(/^#\s*/, ''), enabled: false } : { pattern: line, enabled: true }));
+}
+
+function formatRows(rows: ExcludeRow[]): string {
+  return rows.map((row) => (row.enabled ? row.pattern : `# ${row.pattern}`)).join('\n');
+}
+
+type ExcludeRegexManagerProps = {
+  value: string;
+  onChange: (value: string) => void;
+  repoRef: string;
+  gitRef: string;
+};
+
+/** Exclude regex list with presets, per-pattern switches, inline validation and live match counts. */
+export function ExcludeRegexManager(props: ExcludeRegexManagerProps) {
+  const { value, onChange, repoRef, gitRef } = props;
+  const rows = parseRows(value);
+  const [draft, setDraft] = useState('');
+  const [rawMode, setRawMode] = useState(false);
+  const [preview, setPreview] = useState<ExcludePreview | null>(null);
+  const [previewError, setPreviewError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!repoRef.trim()) {
+      setPreview(null);
+      return;
+    }
+    let cancelled = false;
+    const timer = window.setTimeout(() => {
+      previewContextExcludes({ repo_ref: repoRef, git_ref: gitRef, patterns: value.split('\n') })
+        .then((response) => {
+          if (cancelled) return;
+          setPreview(response);
+          setPreviewError(null);
+        })
+        .catch((err) => {
+          if (!cancelled) setPreviewError(err instanceof Error ? err.message : String(err));
+        });
+    }, 400);
+    return () => {
+      cancelled = true;
+      window.clearTimeout(timer);
+    };
+  }, [value, repoRef, gitRef]);
+
+  const update = (next: ExcludeRow[]) => onChange(formatRows(next));
+
+  function addPatterns(patterns: string[]) {
+    const existing = new Set(rows.map((row) => row.pattern));
+    update([...rows, ...patterns.filter((pattern) => !existing.has(pattern)).map((pattern) => ({ pattern, enabled: true }))]);
+  }
+
+  return (
+    <Stack gap="xs">
+      <Group justify="space-between">
+        <Text size="sm" fw={500}>Exclude regex</Text>
+        <Group gap="xs">
+          {preview ? (
+            <Text size="xs" c="dimmed">
+              Excluding {preview.excluded_files} of {preview.candidate_files} files
+            </Text>
+          ) : null}
+          <Menu withinPortal zIndex={400}>
+            <Menu.Target>
+              <Button size="compact-xs" variant="light">Add preset</Button>
+            </Menu.Target>
+            <Menu.Dropdown>
+              {EXCLUDE_PRESETS.map((preset) => (
+                <Menu.Item key={preset.label} onClick={() => addPatterns(preset.patterns)}>
+                  {preset.label}
+                </Menu.Item>
+              ))}
+            </Menu.Dropdown>
+          </Menu>
+          <Button size="compact-xs" variant="subtle" onClick={() => setRawMode((current) => !current)}>
+            {rawMode ? 'Edit as list' : 'Edit as text'}
+          </Button>
+        </Group>
+      </Group>
+      {previewError ? <Text size="xs" c="red">{previewError}</Text> : null}
+
+      {rawMode ? (
+        <Textarea
+          minRows={6}
+          value={value}
+          onChange={(event) => onChange(event.currentTarget.value)}
+          placeholder={'target/.*\nnode_modules/.*\n# disabled/.*'}
+          description="One pattern per line; start a line with # to disable it."
+        />
+      ) : (
+        <Stack gap={4}>
+          {rows.map((row, index) => {
+            const report = preview?.patterns[index];
+            const current = report?.pattern === row.pattern ? report : undefined;
+            return (
+              <Stack key={index} gap={2}>
+                <Group gap="xs" wrap="nowrap">
+                  <Switch
+                    size="xs"
+                    checked={row.enabled}
+                    onChange={(event) => {
+                      const enabled = event.currentTarget.checked;
+                      update(rows.map((item, i) => (i === index ? { ...item, enabled } : item)));
+                    }}
+                    aria-label={row.enabled ? 'Disable pattern' : 'Enable pattern'}
+                  />
+                  <TextInput
+                    size="xs"
+                    style={{ flex: 1 }}
+                    styles={{ input: { fontFamily: 'monospace', opacity: row.enabled ? 1 : 0.6 } }}
+                    value={row.pattern}
+                    error={Boolean(current?.error)}
+                    onChange={(event) => {
+                      const pattern = event.currentTarget.value;
+                      update(rows.map((item, i) => (i === index ? { ...item, pattern } : item)));
+                    }}
+                  />
+                  <Badge size="sm" variant="light" color={current?.error ? 'red' : row.enabled ? 'blue' : 'gray'} miw={56}>
+                    {current ? (current.error ? 'invalid' : current.excluded) : '…'}
+                  </Badge>
+                  <ActionIcon size="sm" variant="subtle" color="gray" aria-label="Remove pattern" onClick={() => update(rows.filter((_, i) => i !== index))}>
+                    <IconTrash size={14} />
+                  </ActionIcon>
+                </Group>
+                {current?.error ? (
+                  <Stack gap={0} pl={36}>
+                    {current.error_offset != null ? (
+                      <Code block fz="xs">{`${current.pattern}\n${' '.repeat(current.error_offset)}^`}</Code>
+                    ) : null}
+                    <Text size="xs" c="red">
+                      {current.error}
+                      {current.error_offset != null ? ` (at column ${current.error_offset + 1})` : ''}
+                    </Text>
+                  </Stack>
+                ) : null}
+              </Stack>
+            );
+          })}
+          <Group gap="xs" wrap="nowrap">
+            <TextInput
+              size="xs"
+              style={{ flex: 1 }}
+              styles={{ input: { fontFamily: 'monospace' } }}
+              placeholder="Add a pattern, e.g. (^|/)fixtures/"
+              value={draft}
+              onChange={(event) => setDraft(event.currentTarget.value)}
+              onKeyDown={(event) => {
+                if (event.key === 'Enter' && draft.trim()) {
+                  addPatterns([draft.trim()]);
+                  setDraft('');
+                }
+              }}
+            />
+            <ActionIcon
+              size="sm"
+              variant="light"
+              aria-label="Add pattern"
+              disabled={!draft.trim()}
+              onClick={() => {
+                addPatterns([draft.trim()]);
+                setDraft('');
+              }}
+            >
+              <IconPlus size={14} />
+            </ActionIcon>
+          </Group>
+        </Stack>
+      )}
+    </Stack>
+  );
+}
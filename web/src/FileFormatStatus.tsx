@@ -0,0 +1,138 @@
+import type { ReactNode } from 'react';
+import { Badge, Group, Menu, Text, Tooltip, UnstyledButton } from '@mantine/core';
+
+export type LineEnding = 'LF' | 'CRLF' | 'mixed' | 'none';
+export type IndentStyle = { kind: 'tabs' | 'spaces' | 'mixed' | 'none'; width: number };
+export type FileFormat = { lineEnding: LineEnding; indent: IndentStyle };
+
+const DEFAULT_INDENT_WIDTH = 4;
+
+export function detectLineEnding(text: string): LineEnding {
+  const crlf = (text.match(/\r\n/g) ?? []).length;
+  const lf = (text.match(/\n/g) ?? []).length - crlf;
+  if (!crlf && !lf) return 'none';
+  if (crlf && lf) return 'mixed';
+  return crlf ? 'CRLF' : 'LF';
+}
+
+/** Tabs or spaces by majority of indented lines; the width is the most common indent step between lines. */
+export function guessIndentation(text: string): IndentStyle {
+  let tabs = 0;
+  let spaces = 0;
+  let previous = 0;
+  const steps = new Map<number, number>();
+  for (const line of text.split('\n')) {
+    if (!line.trim()) continue;
+    const indent = /^[ \t]*/.exec(line)?.[0] ?? '';
+    if (indent.startsWith('\t')) tabs += 1;
+    else if (indent.startsWith(' ')) {
+      spaces += 1;
+      const step = indent.length - previous;
+      if (step >= 2 && step <= 8) steps.set(step, (steps.get(step) ?? 0) + 1);
+    }
+    previous = indent.startsWith('\t') ? previous : indent.length;
+  }
+  if (!tabs && !spaces) return { kind: 'none', width: DEFAULT_INDENT_WIDTH };
+  const width = [...steps.entries()].sort((a, b) => b[1] - a[1] || a[0] - b[0])[0]?.[0] ?? DEFAULT_INDENT_WIDTH;
+  if (tabs && spaces && Math.min(tabs, spaces) / (tabs + spaces) > 0.1) return { kind: 'mixed', width };
+  return tabs > spaces ? { kind: 'tabs', width: DEFAULT_INDENT_WIDTH } : { kind: 'spaces', width };
+}
+
+export function describeFileFormat(text: string): FileFormat {
+  return { lineEnding: detectLineEnding(text), indent: guessIndentation(text) };
+}
+
+/** Rewrites leading whitespace level by level; `from.width` is how many columns one level (or tab) takes today. */
+export function reindent(text: string, from: IndentStyle, to: { kind: 'tabs' | 'spaces'; width: number }) {
+  const fromWidth = from.width || DEFAULT_INDENT_WIDTH;
+  return text
+    .split('\n')
+    .map((line) => {
+      const indent = /^[ \t]*/.exec(line)?.[0] ?? '';
+      if (!indent) return line;
+      let columns = 0;
+      for (const ch of indent) columns = ch === '\t' ? columns + fromWidth - (columns % fromWidth) : columns + 1;
+      const levels = Math.floor(columns / fromWidth);
+      const rest = ' '.repeat(columns % fromWidth);
+      const unit = to.kind === 'tabs' ? '\t' : ' '.repeat(to.width);
+      return `${unit.repeat(levels)}${rest}${line.slice(indent.length)}`;
+    })
+    .join('\n');
+}
+
+/** Small on/off text button for editor behaviours shown in the status bar. */
+export function StatusBarToggle({ label, enabled, hint, onToggle }: { label: string; enabled: boolean; hint: string; onToggle: () => void }) {
+  return (
+    <Tooltip label={hint}>
+      <UnstyledButton aria-pressed={enabled} onClick={onToggle}>
+        <Text size="xs" c={enabled ? 'blue' : 'dimmed'}>{label}{enabled ? ': on' : ': off'}</Text>
+      </UnstyledButton>
+    </Tooltip>
+  );
+}
+
+function indentLabel(indent: IndentStyle) {
+  if (indent.kind === 'none') return 'No indentation';
+  if (indent.kind === 'tabs') return 'Tabs';
+  if (indent.kind === 'mixed') return `Mixed tabs/spaces (${indent.width})`;
+  return `Spaces: ${indent.width}`;
+}
+
+type FileFormatStatusBarProps = {
+  format: FileFormat | null;
+  /** `lf` or `crlf` when `.gitattributes` rewrites line endings on save. */
+  eolAttribute: 'lf' | 'crlf' | null;
+  readOnly: boolean;
+  onConvertLineEndings: (to: 'LF' | 'CRLF') => void;
+  onReindent: (to: { kind: 'tabs' | 'spaces'; width: number }) => void;
+  /** Other editor status items, shown before the format controls. */
+  children?: ReactNode;
+};
+
+/** Line ending and indentation of the open buffer, with conversions. */
+export function FileFormatStatusBar(props: FileFormatStatusBarProps) {
+  const { format, eolAttribute, readOnly, onConvertLineEndings, onReindent, children } = props;
+  if (!format) return null;
+  const { lineEnding, indent } = format;
+  const enforced = eolAttribute ? (eolAttribute === 'crlf' ? 'CRLF' : 'LF') : null;
+
+  return (
+    <Group gap="md" px="sm" py={4} justify="flex-end" style={{ borderTop: '1px solid rgba(255,255,255,0.08)' }}>
+      {children}
+      {enforced && lineEnding !== 'none' && lineEnding !== enforced ? (
+        <Tooltip label={`.gitattributes sets eol=${eolAttribute}; line endings are converted when you save`}>
+          <Badge size="xs" variant="light" color="yellow">Saves as {enforced}</Badge>
+        </Tooltip>
+      ) : null}
+      <Menu shadow="md" position="top-end" disabled={readOnly}>
+        <Menu.Target>
+          <UnstyledButton aria-label="Line endings">
+            <Text size="xs" c={lineEnding === 'mixed' ? 'yellow' : 'dimmed'}>
+              {lineEnding === 'none' ? 'Single line' : lineEnding === 'mixed' ? 'Mixed line endings' : lineEnding}
+              {enforced ? ' (.gitattributes)' : ''}
+            </Text>
+          </UnstyledButton>
+        </Menu.Target>
+        <Menu.Dropdown>
+          <Menu.Label>Convert line endings</Menu.Label>
+          <Menu.Item disabled={lineEnding === 'LF'} onClick={() => onConvertLineEndings('LF')}>LF (Unix)</Menu.Item>
+          <Menu.Item disabled={lineEnding === 'CRLF'} onClick={() => onConvertLineEndings('CRLF')}>CRLF (Windows)</Menu.Item>
+        </Menu.Dropdown>
+      </Menu>
+      <Menu shadow="md" position="top-end" disabled={readOnly}>
+        <Menu.Target>
+          <UnstyledButton aria-label="Indentation">
+            <Text size="xs" c={indent.kind === 'mixed' ? 'yellow' : 'dimmed'}>{indentLabel(indent)}</Text>
+          </UnstyledButton>
+        </Menu.Target>
+        <Menu.Dropdown>
+          <Menu.Label>Re-indent buffer</Menu.Label>
+          <Menu.Item onClick={() => onReindent({ kind: 'tabs', width: indent.width })}>Tabs</Menu.Item>
+          {[2, 4, 8].map((width) => (
+            <Menu.Item key={width} onClick={() => onReindent({ kind: 'spaces', width })}>{width} spaces</Menu.Item>
+          ))}
+        </Menu.Dropdown>
+      </Menu>
+    </Group>
+  );
+}
@@ -1,5 +1,6 @@
 import type { ReactNode } from 'react';
 import { Badge, Button, Card, Group, SimpleGrid, Stack, Text, Title } from '@mantine/core';
+import { TreeDropTarget } from './TreeDropTarget';
 
 type GlobalCapabilitiesPanelProps = {
   onOpenInference: () => void;
@@ -7,6 +8,17 @@ type GlobalCapabilitiesPanelProps = {
   onOpenChangesetSchema: () => void;
   onOpenApplyChangeset: () => void;
   onOpenGitPatchPayload: () => void;
+  onOpenNetworkSettings: () => void;
+  onOpenSettingsBundle: () => void;
+  onOpenUpdates: () => void;
+  onOpenAccessibility: () => void;
+  onOpenPlugins: () => void;
+  onOpenAutomation: () => void;
+  onOpenMcpServer: () => void;
+  onOpenDoctor: () => void;
+  onOpenProcesses: () => void;
+  onOpenCapabilityTraces: () => void;
+  onDropRepoFragmentPaths: (paths: string[]) => void;
   repoContextArmed: boolean;
   changesetSchemaArmed: boolean;
 };
@@ -18,12 +30,13 @@ type CapabilityCardProps = {
   buttonLabel: string;
   onClick: () => void;
   badge?: ReactNode;
+  onDropPaths?: (paths: string[]) => void;
 };
 
 function CapabilityCard(props: CapabilityCardProps) {
-  const { title, eyebrow, description, buttonLabel, onClick, badge } = props;
+  const { title, eyebrow, description, buttonLabel, onClick, badge, onDropPaths } = props;
 
-  return (
+  const card = (
     <Card withBorder radius="md" p="md" style={{ height: '100%' }}>
       <Stack gap="sm" h="100%">
         <Group justify="space-between" align="flex-start" wrap="nowrap">
@@ -38,6 +51,10 @@ function CapabilityCard(props: CapabilityCardProps) {
       </Stack>
     </Card>
   );
+
+  return onDropPaths ? (
+    <TreeDropTarget label="Drop to add to selection" onDropPaths={onDropPaths}>{card}</TreeDropTarget>
+  ) : card;
 }
 
 function ArmedBadge(props: { armed: boolean }) {
@@ -55,6 +72,17 @@ export function GlobalCapabilitiesPanel(props: GlobalCapabilitiesPanelProps) {
     onOpenChangesetSchema,
     onOpenApplyChangeset,
     onOpenGitPatchPayload,
+    onOpenNetworkSettings,
+    onOpenSettingsBundle,
+    onOpenUpdates,
+    onOpenAccessibility,
+    onOpenPlugins,
+    onOpenAutomation,
+    onOpenMcpServer,
+    onOpenDoctor,
+    onOpenProcesses,
+    onOpenCapabilityTraces,
+    onDropRepoFragmentPaths,
     repoContextArmed,
     changesetSchemaArmed,
   } = props;
@@ -84,9 +112,10 @@ export function GlobalCapabilitiesPanel(props: GlobalCapabilitiesPanelProps) {
         <CapabilityCard
           eyebrow="Context"
           title="Repo fragment"
-          description="Choose repository files and fragments that can be injected into model-backed stages."
+          description="Choose repository files and fragments that can be injected into model-backed stages. Drop files from the tree here to add them."
           buttonLabel="Configure fragment"
           onClick={onOpenRepoFragment}
+          onDropPaths={onDropRepoFragmentPaths}
           badge={<ArmedBadge armed={repoContextArmed} />}
         />
         <CapabilityCard
@@ -113,6 +142,86 @@ export function GlobalCapabilitiesPanel(props: GlobalCapabilitiesPanelProps) {
           onClick={onOpenGitPatchPayload}
           badge={<Badge color="violet" variant="light">Portable</Badge>}
         />
+        <CapabilityCard
+          eyebrow="Network"
+          title="Proxy, certificates and rate limits"
+          description="Route model API traffic through a corporate proxy, trust a custom CA bundle, and cap concurrent or per-minute inference requests."
+          buttonLabel="Configure network"
+          onClick={onOpenNetworkSettings}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Portability"
+          title="Settings bundle"
+          description="Export layouts, filters, limits and workflow templates to a file, or import a bundle from another machine or teammate."
+          buttonLabel="Export or import"
+          onClick={onOpenSettingsBundle}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Maintenance"
+          title="Updates"
+          description="Check the release feed for a newer version, read the changelog and stage the new binary for the next launch."
+          buttonLabel="Check for updates"
+          onClick={onOpenUpdates}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Display"
+          title="Accessibility"
+          description="Switch to the high-contrast theme and set a minimum font size for labels, badges and code."
+          buttonLabel="Adjust display"
+          onClick={onOpenAccessibility}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Extensions"
+          title="Plugins"
+          description="Run palette commands and open panels registered by Rhai scripts in the plugin folder (Ctrl+Shift+P)."
+          buttonLabel="Open plugins"
+          onClick={onOpenPlugins}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Extensions"
+          title="Automation API"
+          description="Let editors and scripts drive the app over token-protected local HTTP: open repos, run analysis, export context and start tasks."
+          buttonLabel="Configure API"
+          onClick={onOpenAutomation}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Extensions"
+          title="MCP server"
+          description="Expose repo tree, file reads, search, diffs and context export to external AI clients over the Model Context Protocol."
+          buttonLabel="Configure MCP"
+          onClick={onOpenMcpServer}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Diagnostics"
+          title="Environment doctor"
+          description="Check git, commit identity, the API key, the shell, free disk space and Windows long-path support, with a fix for each problem."
+          buttonLabel="Run checks"
+          onClick={onOpenDoctor}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Diagnostics"
+          title="Processes"
+          description="See every command the app has started, from terminal panes and compile stages to save hooks and plugins, and kill or restart it."
+          buttonLabel="Show processes"
+          onClick={onOpenProcesses}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
+        <CapabilityCard
+          eyebrow="Diagnostics"
+          title="Capability traces"
+          description="Inspect each capability call a run made, with its config, response, sizes and timing, and replay one to see whether a failure reproduces."
+          buttonLabel="Show traces"
+          onClick={onOpenCapabilityTraces}
+          badge={<Badge color="gray" variant="light">Global</Badge>}
+        />
       </SimpleGrid>
     </Stack>
   );
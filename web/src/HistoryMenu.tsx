@@ -0,0 +1,100 @@
+import { useEffect, useRef, useState } from 'react';
+import { Badge, Button, Group, Menu, Text } from '@mantine/core';
+import { IconArrowBackUp, IconHistory, IconTrash } from '@tabler/icons-react';
+import { listHistory, undoHistory, type HistoryEntry, type HistoryUndoResult } from './api';
+import { TrashPanel } from './TrashPanel';
+
+type HistoryMenuProps = {
+  repoRef: string;
+  onUndone: (result: HistoryUndoResult) => void;
+  onError: (message: string) => void;
+};
+
+function describe(result: HistoryUndoResult) {
+  const parts = [`Undid "${result.entry.label}"`];
+  if (result.restored.length) parts.push(`restored ${result.restored.length} file${result.restored.length === 1 ? '' : 's'}`);
+  if (result.removed.length) parts.push(`removed ${result.removed.length}`);
+  return parts.join(' · ');
+}
+
+export function HistoryMenu(props: HistoryMenuProps) {
+  const { repoRef, onUndone, onError } = props;
+  const [entries, setEntries] = useState<HistoryEntry[]>([]);
+  const [trashOpen, setTrashOpen] = useState(false);
+  const [lastMessage, setLastMessage] = useState<string | null>(null);
+
+  async function refresh() {
+    try {
+      setEntries(await listHistory());
+    } catch (err) {
+      onError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  async function undo(id?: string) {
+    try {
+      const result = await undoHistory(id);
+      setLastMessage(describe(result));
+      onUndone(result);
+    } catch (err) {
+      onError(err instanceof Error ? err.message : String(err));
+    } finally {
+      void refresh();
+    }
+  }
+
+  const undoRef = useRef(undo);
+  undoRef.current = undo;
+
+  useEffect(() => {
+    const handler = (event: KeyboardEvent) => {
+      if (event.ctrlKey && event.altKey && !event.shiftKey && !event.metaKey && event.key.toLowerCase() === 'z') {
+        event.preventDefault();
+        void undoRef.current();
+      }
+    };
+    window.addEventListener('keydown', handler);
+    return () => window.removeEventListener('keydown', handler);
+  }, []);
+
+  const pending = entries.filter((entry) => !entry.undone_at);
+
+  return (
+    <>
+      <TrashPanel repoRef={repoRef} opened={trashOpen} onClose={() => setTrashOpen(false)} />
+      <Menu shadow="md" width={360} position="bottom-end" onOpen={() => void refresh()}>
+        <Menu.Target>
+          <Button size="xs" variant="default" leftSection={<IconHistory size={16} />}>History</Button>
+        </Menu.Target>
+        <Menu.Dropdown>
+          <Menu.Item leftSection={<IconArrowBackUp size={14} />} disabled={!pending.length} onClick={() => void undo()}>
+            <Group justify="space-between" wrap="nowrap">
+              <Text size="sm">Undo last action</Text>
+              <Text size="xs" c="dimmed">Ctrl+Alt+Z</Text>
+            </Group>
+          </Menu.Item>
+          {lastMessage ? <Text size="xs" c="dimmed" px="sm" py={4}>{lastMessage}</Text> : null}
+          <Menu.Divider />
+          <Menu.Label>Recent destructive actions</Menu.Label>
+          {entries.length === 0 ? <Text size="xs" c="dimmed" px="sm" pb="xs">Nothing recorded yet.</Text> : null}
+          {entries.slice(0, 15).map((entry) => (
+            <Menu.Item key={entry.id} disabled={!!entry.undone_at} onClick={() => void undo(entry.id)}>
+              <Group justify="space-between" wrap="nowrap" gap="xs">
+                <Text size="sm" truncate>{entry.label}</Text>
+                {entry.undone_at ? <Badge size="xs" variant="light" color="gray">undone</Badge> : null}
+              </Group>
+              <Text size="xs" c="dimmed">
+                {new Date(entry.created_at).toLocaleString()}
+                {entry.paths.length ? ` · ${entry.paths.length} file${entry.paths.length === 1 ? '' : 's'}` : ''}
+              </Text>
+            </Menu.Item>
+          ))}
+          <Menu.Divider />
+          <Menu.Item leftSection={<IconTrash size={14} />} disabled={!repoRef.trim()} onClick={() => setTrashOpen(true)}>
+            Trash…
+          </Menu.Item>
+        </Menu.Dropdown>
+      </Menu>
+    </>
+  );
+}
@@ -0,0 +1,103 @@
+import { useEffect, useState } from 'react';
+import { Button, Code, Group, Modal, Stack, Text } from '@mantine/core';
+import { INDEX_LOCKED_EVENT, inspectIndexLock, removeStaleIndexLock, type IndexLockedDetail, type IndexLockInfo } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+/** Listens for index lock contention reported by any git index action and offers to resolve it. */
+export function IndexLockOverlay() {
+  const [detail, setDetail] = useState<IndexLockedDetail | null>(null);
+  const [lock, setLock] = useState<IndexLockInfo | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const handler = (event: Event) => {
+      const next = (event as CustomEvent<IndexLockedDetail>).detail;
+      setDetail(next);
+      setLock(next.lock);
+      setError(null);
+    };
+    window.addEventListener(INDEX_LOCKED_EVENT, handler);
+    return () => window.removeEventListener(INDEX_LOCKED_EVENT, handler);
+  }, []);
+
+  function close() {
+    setDetail(null);
+    setLock(null);
+  }
+
+  async function run(action: () => Promise<unknown>) {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function recheck() {
+    if (!lock) return;
+    const next = await inspectIndexLock({ repo_ref: lock.repo_ref });
+    if (next) setLock(next);
+    else close();
+  }
+
+  async function retry() {
+    if (!detail) return;
+    await detail.retry();
+    close();
+  }
+
+  async function forceRemove() {
+    if (!lock) return;
+    await removeStaleIndexLock({ repo_ref: lock.repo_ref });
+    await retry();
+  }
+
+  return (
+    <Modal opened={!!lock} onClose={close} title="Git index is locked" size="lg" centered>
+      {lock ? (
+        <Stack gap="sm">
+          <Text size="sm">
+            Another process holds <Code>{lock.lock_path}</Code>
+            {lock.age_secs != null ? `, created ${lock.age_secs}s ago` : ''}. The action was retried for a few seconds before giving up.
+          </Text>
+          {lock.holders.length ? (
+            <Stack gap={4}>
+              <Text size="sm" fw={600}>Likely holders</Text>
+              {lock.holders.map((holder) => (
+                <Text key={holder.pid} size="xs" ff="monospace" truncate>
+                  {holder.pid} · {holder.cmdline || holder.name}
+                </Text>
+              ))}
+            </Stack>
+          ) : (
+            <Text size="sm" c="dimmed">
+              No running git process was found for this repository. An editor, GUI client or a crashed git command may have left the lock behind.
+            </Text>
+          )}
+          {error ? <ErrorPanel message={error} /> : null}
+          <Group justify="space-between">
+            <Button
+              color="red"
+              variant="light"
+              disabled={!lock.stale}
+              loading={busy}
+              title={lock.stale ? undefined : 'Only available once no git process is running and the lock is at least 30s old'}
+              onClick={() => void run(forceRemove)}
+            >
+              Force remove stale lock
+            </Button>
+            <Group gap="xs">
+              <Button variant="default" disabled={busy} onClick={() => void run(recheck)}>Check again</Button>
+              <Button disabled={busy} onClick={() => void run(retry)}>Retry</Button>
+            </Group>
+          </Group>
+        </Stack>
+      ) : null}
+    </Modal>
+  );
+}
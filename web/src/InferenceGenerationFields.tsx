@@ -0,0 +1,89 @@
+import { Alert, NumberInput, Select, SimpleGrid, Stack, TextInput } from '@mantine/core';
+import type { InferenceGenerationSettings } from './api';
+
+type InferenceGenerationFieldsProps = {
+  model: string;
+  generation: InferenceGenerationSettings;
+  onModelChange: (value: string) => void;
+  onGenerationChange: (value: InferenceGenerationSettings) => void;
+};
+
+export function isReasoningModel(model: string): boolean {
+  const name = (model.trim().toLowerCase().split('/').pop() ?? '');
+  return ['o1', 'o3', 'o4', 'gpt-5'].some((prefix) => name === prefix || name.startsWith(`${prefix}-`));
+}
+
+export function readGenerationSettings(value: unknown): InferenceGenerationSettings {
+  const raw = (value && typeof value === 'object' ? value : {}) as Record<string, unknown>;
+  const numberOrNull = (v: unknown) => (typeof v === 'number' && Number.isFinite(v) ? v : null);
+  const effort = typeof raw.reasoning_effort === 'string' ? raw.reasoning_effort : null;
+  return {
+    temperature: numberOrNull(raw.temperature),
+    top_p: numberOrNull(raw.top_p),
+    max_output_tokens: numberOrNull(raw.max_output_tokens),
+    reasoning_effort: effort === 'minimal' || effort === 'low' || effort === 'medium' || effort === 'high' ? effort : null,
+  };
+}
+
+export function InferenceGenerationFields(props: InferenceGenerationFieldsProps) {
+  const { model, generation, onModelChange, onGenerationChange } = props;
+  const reasoning = isReasoningModel(model);
+  const numberValue = (value: string | number) => (typeof value === 'number' && Number.isFinite(value) ? value : null);
+
+  return (
+    <Stack gap="sm">
+      <SimpleGrid cols={{ base: 1, md: 3 }}>
+        <TextInput
+          label="Model"
+          value={model}
+          onChange={(e) => onModelChange(e.currentTarget.value)}
+          placeholder="gpt-4.1"
+        />
+        <NumberInput
+          label="Max output tokens"
+          value={generation.max_output_tokens ?? ''}
+          onChange={(value) => onGenerationChange({ ...generation, max_output_tokens: numberValue(value) })}
+          min={1}
+          step={256}
+          placeholder="Model default"
+        />
+        <Select
+          label="Reasoning effort"
+          value={generation.reasoning_effort ?? null}
+          onChange={(value) => onGenerationChange({ ...generation, reasoning_effort: (value as InferenceGenerationSettings['reasoning_effort']) ?? null })}
+          data={['minimal', 'low', 'medium', 'high']}
+          placeholder="Model default"
+          disabled={!reasoning}
+          clearable
+        />
+        <NumberInput
+          label="Temperature"
+          value={generation.temperature ?? ''}
+          onChange={(value) => onGenerationChange({ ...generation, temperature: numberValue(value) })}
+          min={0}
+          max={2}
+          step={0.1}
+          decimalScale={2}
+          placeholder="Model default"
+          disabled={reasoning}
+        />
+        <NumberInput
+          label="Top p"
+          value={generation.top_p ?? ''}
+          onChange={(value) => onGenerationChange({ ...generation, top_p: numberValue(value) })}
+          min={0}
+          max={1}
+          step={0.05}
+          decimalScale={2}
+          placeholder="Model default"
+          disabled={reasoning}
+        />
+      </SimpleGrid>
+      <Alert color="blue">
+        {reasoning
+          ? 'Reasoning models ignore temperature and top p; only reasoning effort and max output tokens are sent.'
+          : 'Reasoning effort only applies to o-series and gpt-5 models and is not sent for this model.'}
+      </Alert>
+    </Stack>
+  );
+}
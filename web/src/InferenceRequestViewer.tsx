@@ -0,0 +1,96 @@
+import { useMemo } from 'react';
+import { Accordion, Badge, Code, Group, Modal, ScrollArea, Stack, Table, Text } from '@mantine/core';
+import type { InferenceRequestRecord } from './api';
+
+type InferenceRequestViewerProps = {
+  opened: boolean;
+  onClose: () => void;
+  request: InferenceRequestRecord | null;
+  /** The prompt recorded with the request; sections are byte ranges of it. */
+  prompt: string;
+};
+
+const SECTION_LABELS: Record<string, string> = {
+  user_input: 'User input',
+  repo_context: 'Repo context',
+  changeset_schema: 'Changeset schema',
+};
+
+function sectionLabel(name: string): string {
+  if (SECTION_LABELS[name]) return SECTION_LABELS[name];
+  if (name.startsWith('transient_')) return `Injected context ${name.slice('transient_'.length)}`;
+  return name;
+}
+
+function sliceUtf8(bytes: Uint8Array, start: number, end: number): string {
+  return new TextDecoder().decode(bytes.subarray(start, end));
+}
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(2)} MB`;
+}
+
+export function InferenceRequestViewer(props: InferenceRequestViewerProps) {
+  const { opened, onClose, request, prompt } = props;
+  const parameterEntries = Object.entries(request?.parameters ?? {});
+  const promptBytes = useMemo(() => new TextEncoder().encode(prompt), [prompt]);
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Sent inference request" size="min(1100px, 96vw)" centered>
+      {!request ? (
+        <Text size="sm" c="dimmed">No inference request has been recorded for this stage yet.</Text>
+      ) : (
+        <Stack gap="sm">
+          <Group gap="xs">
+            <Badge variant="light">{request.transport}</Badge>
+            <Badge variant="light" color="violet">{request.model}</Badge>
+            <Badge variant="outline">{formatBytes(request.total_bytes)}</Badge>
+            <Badge variant="outline">~{request.approx_total_tokens.toLocaleString()} tokens</Badge>
+          </Group>
+          <Text size="xs" c="dimmed">
+            Conversation {request.conversation_id ?? '(new)'} · recorded {new Date(request.recorded_at).toLocaleString()}
+          </Text>
+          {parameterEntries.length > 0 ? (
+            <Table withTableBorder striped>
+              <Table.Tbody>
+                {parameterEntries.map(([key, value]) => (
+                  <Table.Tr key={key}>
+                    <Table.Td><Text size="xs" fw={600}>{key}</Text></Table.Td>
+                    <Table.Td><Code>{typeof value === 'string' ? value : JSON.stringify(value)}</Code></Table.Td>
+                  </Table.Tr>
+                ))}
+              </Table.Tbody>
+            </Table>
+          ) : null}
+          <Accordion multiple variant="separated">
+            {request.sections.map((section) => (
+              <Accordion.Item key={section.name} value={section.name}>
+                <Accordion.Control>
+                  <Group justify="space-between" pr="sm">
+                    <Text size="sm" fw={600}>{sectionLabel(section.name)}</Text>
+                    <Group gap={6}>
+                      <Badge size="sm" variant="light">{formatBytes(section.bytes)}</Badge>
+                      <Badge size="sm" variant="light" color="gray">~{section.approx_tokens.toLocaleString()} tokens</Badge>
+                    </Group>
+                  </Group>
+                </Accordion.Control>
+                <Accordion.Panel>
+                  <ScrollArea.Autosize mah={360}>
+                    <Code block>{sliceUtf8(promptBytes, section.start, section.end)}</Code>
+                  </ScrollArea.Autosize>
+                </Accordion.Panel>
+              </Accordion.Item>
+            ))}
+          </Accordion>
+          {request.messages.map((message, index) => (
+            <Text key={`${message.role}-${index}`} size="xs" c="dimmed">
+              Message {index + 1} ({message.role}): {formatBytes(message.bytes)}, ~{message.approx_tokens.toLocaleString()} tokens
+            </Text>
+          ))}
+        </Stack>
+      )}
+    </Modal>
+  );
+}
@@ -0,0 +1,212 @@
+import type { ReactNode } from 'react';
+import { ActionIcon, Anchor, Box, Code, CopyButton, Group, List, Stack, Text, Title, Tooltip } from '@mantine/core';
+import { IconCheck, IconCopy } from '@tabler/icons-react';
+import { getOrCompute } from './sharedRenderCache';
+
+type Block =
+  | { kind: 'heading'; level: number; text: string }
+  | { kind: 'paragraph'; text: string }
+  | { kind: 'quote'; text: string }
+  | { kind: 'list'; ordered: boolean; items: string[] }
+  | { kind: 'code'; language: string; text: string };
+
+type Token = { kind: 'plain' | 'keyword' | 'string' | 'comment' | 'number'; text: string };
+
+const KEYWORDS: Record<string, string[]> = {
+  rust: ['as', 'async', 'await', 'break', 'const', 'continue', 'crate', 'else', 'enum', 'fn', 'for', 'if', 'impl', 'in', 'let', 'loop', 'match', 'mod', 'move', 'mut', 'pub', 'ref', 'return', 'self', 'Self', 'static', 'struct', 'super', 'trait', 'type', 'use', 'where', 'while', 'true', 'false', 'None', 'Some', 'Ok', 'Err'],
+  ts: ['async', 'await', 'break', 'case', 'catch', 'class', 'const', 'continue', 'default', 'else', 'export', 'extends', 'false', 'for', 'from', 'function', 'if', 'import', 'in', 'interface', 'let', 'new', 'null', 'of', 'return', 'switch', 'this', 'throw', 'true', 'try', 'type', 'undefined', 'var', 'while'],
+  py: ['and', 'as', 'async', 'await', 'break', 'class', 'continue', 'def', 'elif', 'else', 'except', 'False', 'finally', 'for', 'from', 'if', 'import', 'in', 'is', 'lambda', 'None', 'not', 'or', 'pass', 'raise', 'return', 'True', 'try', 'while', 'with', 'yield'],
+  go: ['break', 'case', 'chan', 'const', 'continue', 'default', 'defer', 'else', 'false', 'for', 'func', 'go', 'if', 'import', 'interface', 'map', 'nil', 'package', 'range', 'return', 'select', 'struct', 'switch', 'true', 'type', 'var'],
+};
+
+const LANGUAGE_ALIASES: Record<string, string> = {
+  rs: 'rust',
+  rust: 'rust',
+  ts: 'ts',
+  tsx: 'ts',
+  typescript: 'ts',
+  js: 'ts',
+  jsx: 'ts',
+  javascript: 'ts',
+  json: 'ts',
+  py: 'py',
+  python: 'py',
+  go: 'go',
+};
+
+const TOKEN_COLORS: Record<Token['kind'], string | undefined> = {
+  plain: undefined,
+  keyword: 'var(--mantine-color-grape-4)',
+  string: 'var(--mantine-color-green-4)',
+  comment: 'var(--mantine-color-dark-2)',
+  number: 'var(--mantine-color-orange-4)',
+};
+
+function parseBlocks(input: string): Block[] {
+  const lines = input.split(/\r?\n/);
+  const blocks: Block[] = [];
+  let paragraph: string[] = [];
+  let list: { ordered: boolean; items: string[] } | null = null;
+  let code: { language: string; lines: string[] } | null = null;
+
+  const flush = () => {
+    if (paragraph.length) blocks.push({ kind: 'paragraph', text: paragraph.join('\n') });
+    if (list) blocks.push({ kind: 'list', ...list });
+    paragraph = [];
+    list = null;
+  };
+
+  for (const line of lines) {
+    const fence = line.match(/^\s*```\s*([A-Za-z0-9_+-]*)\s*$/);
+    if (code) {
+      if (fence) {
+        blocks.push({ kind: 'code', language: code.language, text: code.lines.join('\n') });
+        code = null;
+      } else {
+        code.lines.push(line);
+      }
+      continue;
+    }
+    if (fence) {
+      flush();
+      code = { language: fence[1] ?? '', lines: [] };
+      continue;
+    }
+
+    const heading = line.match(/^(#{1,6})\s+(.+)$/);
+    const bullet = line.match(/^\s*[-*+]\s+(.*)$/);
+    const numbered = line.match(/^\s*\d+[.)]\s+(.*)$/);
+    const quote = line.match(/^>\s?(.*)$/);
+
+    if (heading) {
+      flush();
+      blocks.push({ kind: 'heading', level: heading[1].length, text: heading[2] });
+    } else if (bullet || numbered) {
+      const ordered = !bullet;
+      if (paragraph.length || (list && list.ordered !== ordered)) flush();
+      list = list ?? { ordered, items: [] };
+      list.items.push((bullet ?? numbered)![1]);
+    } else if (quote) {
+      flush();
+      blocks.push({ kind: 'quote', text: quote[1] });
+    } else if (!line.trim()) {
+      flush();
+    } else if (list && /^\s{2,}\S/.test(line)) {
+      list.items[list.items.length - 1] += ` ${line.trim()}`;
+    } else {
+      if (list) flush();
+      paragraph.push(line);
+    }
+  }
+
+  if (code) blocks.push({ kind: 'code', language: code.language, text: code.lines.join('\n') });
+  flush();
+  return blocks;
+}
+
+function tokenize(language: string, text: string): Token[] {
+  const family = LANGUAGE_ALIASES[language.toLowerCase()];
+  if (!family) return [{ kind: 'plain', text }];
+  const keywords = new Set(KEYWORDS[family]);
+  const lineComment = family === 'py' ? '#' : '//';
+  const pattern = new RegExp(
+    `(${lineComment === '#' ? '#' : '\\/\\/'}[^\\n]*|\\/\\*[\\s\\S]*?\\*\\/)|("(?:\\\\.|[^"\\\\])*"|'(?:\\\\.|[^'\\\\\\n])*'|\`(?:\\\\.|[^\`\\\\])*\`)|\\b(\\d[\\d_]*(?:\\.\\d+)?)\\b|([A-Za-z_][A-Za-z0-9_]*)`,
+    'g'
+  );
+
+  const tokens: Token[] = [];
+  let last = 0;
+  let match: RegExpExecArray | null;
+  while ((match = pattern.exec(text)) !== null) {
+    if (match.index > last) tokens.push({ kind: 'plain', text: text.slice(last, match.index) });
+    if (match[1]) tokens.push({ kind: 'comment', text: match[1] });
+    else if (match[2]) tokens.push({ kind: 'string', text: match[2] });
+    else if (match[3]) tokens.push({ kind: 'number', text: match[3] });
+    else tokens.push({ kind: keywords.has(match[4]) ? 'keyword' : 'plain', text: match[4] });
+    last = match.index + match[0].length;
+  }
+  if (last < text.length) tokens.push({ kind: 'plain', text: text.slice(last) });
+  return tokens;
+}
+
+function renderInline(text: string): ReactNode[] {
+  const nodes: ReactNode[] = [];
+  const pattern = /(`[^`]+`)|(\*\*[^*]+\*\*)|(\*[^*\s][^*]*\*)|(\[[^\]]+\]\([^)\s]+\))/g;
+  let last = 0;
+  let match: RegExpExecArray | null;
+  while ((match = pattern.exec(text)) !== null) {
+    if (match.index > last) nodes.push(text.slice(last, match.index));
+    const value = match[0];
+    const key = `${match.index}`;
+    if (match[1]) nodes.push(<Code key={key}>{value.slice(1, -1)}</Code>);
+    else if (match[2]) nodes.push(<strong key={key}>{value.slice(2, -2)}</strong>);
+    else if (match[3]) nodes.push(<em key={key}>{value.slice(1, -1)}</em>);
+    else {
+      const link = value.match(/^\[([^\]]+)\]\(([^)\s]+)\)$/);
+      nodes.push(
+        <Anchor key={key} href={link?.[2]} target="_blank" rel="noreferrer">{link?.[1] ?? value}</Anchor>
+      );
+    }
+    last = match.index + value.length;
+  }
+  if (last < text.length) nodes.push(text.slice(last));
+  return nodes;
+}
+
+export function CodeBlock(props: { language: string; text: string }) {
+  const { language, text } = props;
+  const tokens = getOrCompute('code-highlight', [language], text, () => tokenize(language, text));
+  return (
+    <Box style={{ border: '1px solid var(--mantine-color-dark-4)', borderRadius: 8, background: 'rgba(0,0,0,0.25)' }}>
+      <Group justify="space-between" px="xs" py={2} style={{ borderBottom: '1px solid var(--mantine-color-dark-4)' }}>
+        <Text size="xs" c="dimmed">{language || 'code'}</Text>
+        <CopyButton value={text} timeout={1500}>
+          {({ copied, copy }) => (
+            <Tooltip label={copied ? 'Copied' : 'Copy'} withArrow>
+              <ActionIcon size="xs" variant="subtle" color={copied ? 'teal' : 'gray'} onClick={copy}>
+                {copied ? <IconCheck size={12} /> : <IconCopy size={12} />}
+              </ActionIcon>
+            </Tooltip>
+          )}
+        </CopyButton>
+      </Group>
+      <Box component="pre" p="xs" m={0} style={{ overflowX: 'auto', fontSize: 12, lineHeight: 1.45 }}>
+        <code>
+          {tokens.map((token, index) => (
+            <span key={index} style={TOKEN_COLORS[token.kind] ? { color: TOKEN_COLORS[token.kind] } : undefined}>{token.text}</span>
+          ))}
+        </code>
+      </Box>
+    </Box>
+  );
+}
+
+export function MarkdownMessage(props: { text: string }) {
+  const blocks = getOrCompute('markdown-blocks', [], props.text, () => parseBlocks(props.text));
+  return (
+    <Stack gap={6}>
+      {blocks.map((block, index) => {
+        switch (block.kind) {
+          case 'heading':
+            return <Title key={index} order={Math.min(block.level + 2, 6) as 3 | 4 | 5 | 6}>{renderInline(block.text)}</Title>;
+          case 'list':
+            return (
+              <List key={index} type={block.ordered ? 'ordered' : 'unordered'} size="sm" spacing={2}>
+                {block.items.map((item, itemIndex) => <List.Item key={itemIndex}>{renderInline(item)}</List.Item>)}
+              </List>
+            );
+          case 'quote':
+            return (
+              <Text key={index} size="sm" c="dimmed" pl="sm" style={{ borderLeft: '3px solid var(--mantine-color-dark-3)' }}>
+                {renderInline(block.text)}
+              </Text>
+            );
+          case 'code':
+            return <CodeBlock key={index} language={block.language} text={block.text} />;
+          default:
+            return <Text key={index} size="sm" style={{ whiteSpace: 'pre-wrap', overflowWrap: 'anywhere' }}>{renderInline(block.text)}</Text>;
+        }
+      })}
+    </Stack>
+  );
+}
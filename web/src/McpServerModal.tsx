@@ -0,0 +1,109 @@
+import { useEffect, useState } from 'react';
+import { Button, Checkbox, Code, CopyButton, Group, Modal, PasswordInput, Stack, Switch, Text, Textarea } from '@mantine/core';
+import { getAppSettings, patchAppSettings, rotateAccessToken, type AppSettings } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type McpServerModalProps = {
+  opened: boolean;
+  onClose: () => void;
+};
+
+const MCP_TOOLS = [
+  { value: 'repo_tree', label: 'Repo tree' },
+  { value: 'read_file', label: 'Read file' },
+  { value: 'search', label: 'Search' },
+  { value: 'diff', label: 'Diff' },
+  { value: 'context_export', label: 'Context export' }
+];
+
+export function McpServerModal(props: McpServerModalProps) {
+  const { opened, onClose } = props;
+  const [settings, setSettings] = useState<AppSettings['mcp'] | null>(null);
+  const [allowedReposText, setAllowedReposText] = useState('');
+  const [token, setToken] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!opened) return;
+    setError(null);
+    setToken(null);
+    void getAppSettings()
+      .then((next) => {
+        setSettings(next.mcp);
+        setAllowedReposText(next.mcp.allowed_repos.join('\n'));
+      })
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [opened]);
+
+  function regenerate() {
+    void rotateAccessToken('mcp')
+      .then((next) => {
+        setToken(next.token);
+        setSettings((current) => (current ? { ...current, token_set: true } : current));
+      })
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  function update(patch: Partial<AppSettings['mcp']>) {
+    void patchAppSettings({ mcp: patch })
+      .then((next) => setSettings(next.mcp))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  const stdioConfig = JSON.stringify({ mcpServers: { mdev: { command: 'workflow-api', args: ['mcp'] } } }, null, 2);
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="MCP server" size="lg" centered>
+      <Stack gap="sm">
+        {error ? <ErrorPanel message={error} /> : null}
+        <Switch
+          label="Share repository context over MCP"
+          description="External AI clients can list, read, search and diff repositories and build context exports. All tools are read-only."
+          checked={settings?.enabled ?? false}
+          disabled={!settings}
+          onChange={(event) => update({ enabled: event.currentTarget.checked })}
+        />
+        {settings?.enabled ? (
+          <>
+            <Checkbox.Group label="Tools offered" value={settings.tools} onChange={(tools) => update({ tools })}>
+              <Group gap="md" mt={4}>
+                {MCP_TOOLS.map((tool) => <Checkbox key={tool.value} value={tool.value} label={tool.label} />)}
+              </Group>
+            </Checkbox.Group>
+            <Textarea
+              label="Allowed repositories"
+              description="One root per line. Clients can read nothing until at least one is listed."
+              autosize
+              minRows={2}
+              value={allowedReposText}
+              onChange={(event) => setAllowedReposText(event.currentTarget.value)}
+              onBlur={() => update({ allowed_repos: allowedReposText.split('\n').map((line) => line.trim()).filter(Boolean) })}
+            />
+            <Text size="sm">Desktop clients that launch the server themselves (stdio):</Text>
+            <Code block>{stdioConfig}</Code>
+            <Text size="sm">
+              HTTP clients: <Code>{`${window.location.origin}/mcp`}</Code> with <Code>Authorization: Bearer &lt;token&gt;</Code>.
+            </Text>
+            <Group align="flex-end" gap="xs">
+              <PasswordInput
+                label="HTTP token"
+                description={token ? 'Shown once; copy it now.' : settings.token_set ? 'A token is set. Regenerating replaces it and shows the new one.' : 'No token yet.'}
+                value={token ?? ''}
+                placeholder={settings.token_set ? '••••••••' : ''}
+                readOnly
+                style={{ flex: 1 }}
+              />
+              <CopyButton value={token ?? ''}>
+                {({ copied, copy }) => <Button variant="default" onClick={copy} disabled={!token}>{copied ? 'Copied' : 'Copy'}</Button>}
+              </CopyButton>
+              <Button variant="default" onClick={regenerate}>Regenerate</Button>
+            </Group>
+          </>
+        ) : null}
+        <Group justify="flex-end">
+          <Button variant="default" onClick={onClose}>Close</Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
@@ -0,0 +1,137 @@
+import { useEffect, useState } from 'react';
+import { Alert, Button, Divider, Group, Modal, NumberInput, Stack, Text, TextInput } from '@mantine/core';
+import {
+  getAppSettings,
+  getInferenceQueue,
+  patchAppSettings,
+  testNetworkSettings,
+  type AppSettings,
+  type InferenceLimits,
+  type InferenceQueueResponse,
+  type NetworkTestResponse,
+} from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type NetworkSettingsModalProps = {
+  opened: boolean;
+  onClose: () => void;
+};
+
+const EMPTY_NETWORK: AppSettings['network'] = { proxy_url: '', no_proxy: '', ca_cert_path: '' };
+const DEFAULT_LIMITS: InferenceLimits = { max_concurrent_requests: 2, requests_per_minute: 30 };
+
+export function NetworkSettingsModal(props: NetworkSettingsModalProps) {
+  const { opened, onClose } = props;
+  const [network, setNetwork] = useState<AppSettings['network']>(EMPTY_NETWORK);
+  const [busy, setBusy] = useState(false);
+  const [testing, setTesting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [testResult, setTestResult] = useState<NetworkTestResponse | null>(null);
+  const [limits, setLimits] = useState<InferenceLimits>(DEFAULT_LIMITS);
+  const [queue, setQueue] = useState<InferenceQueueResponse['queue'] | null>(null);
+
+  useEffect(() => {
+    if (!opened) return;
+    setError(null);
+    setTestResult(null);
+    getAppSettings()
+      .then((settings) => {
+        setNetwork({ ...EMPTY_NETWORK, ...settings.network });
+        setLimits({ ...DEFAULT_LIMITS, ...settings.inference });
+      })
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+    getInferenceQueue()
+      .then((response) => setQueue(response.queue))
+      .catch(() => setQueue(null));
+  }, [opened]);
+
+  async function handleSave() {
+    try {
+      setBusy(true);
+      setError(null);
+      const settings = await patchAppSettings({ network, inference: limits });
+      setNetwork({ ...EMPTY_NETWORK, ...settings.network });
+      setLimits({ ...DEFAULT_LIMITS, ...settings.inference });
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleTest() {
+    try {
+      setTesting(true);
+      setError(null);
+      setTestResult(await testNetworkSettings({ network }));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setTesting(false);
+    }
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Network and rate limits" size="lg" centered>
+      <Stack gap="sm">
+        <Text size="sm" c="dimmed">Proxy and certificate settings used by the OpenAI client and other outbound HTTP integrations.</Text>
+        <TextInput
+          label="Proxy URL"
+          placeholder="http://proxy.example.com:8080"
+          value={network.proxy_url}
+          onChange={(event) => setNetwork((prev) => ({ ...prev, proxy_url: event.currentTarget.value }))}
+        />
+        <TextInput
+          label="No proxy"
+          placeholder="localhost,127.0.0.1,.internal.example.com"
+          value={network.no_proxy}
+          onChange={(event) => setNetwork((prev) => ({ ...prev, no_proxy: event.currentTarget.value }))}
+        />
+        <TextInput
+          label="CA bundle path"
+          placeholder="/etc/ssl/certs/corporate-ca.pem"
+          value={network.ca_cert_path}
+          onChange={(event) => setNetwork((prev) => ({ ...prev, ca_cert_path: event.currentTarget.value }))}
+        />
+
+        <Divider label="Inference rate limits" labelPosition="left" />
+        <Group grow>
+          <NumberInput
+            label="Max concurrent requests"
+            min={1}
+            value={limits.max_concurrent_requests}
+            onChange={(value) => setLimits((prev) => ({ ...prev, max_concurrent_requests: Math.max(1, Number(value) || 1) }))}
+          />
+          <NumberInput
+            label="Requests per minute"
+            min={1}
+            value={limits.requests_per_minute}
+            onChange={(value) => setLimits((prev) => ({ ...prev, requests_per_minute: Math.max(1, Number(value) || 1) }))}
+          />
+        </Group>
+        {queue ? (
+          <Text size="xs" c="dimmed">
+            {queue.active} active · {queue.waiting.length} queued · {queue.requests_last_minute} requests in the last minute
+          </Text>
+        ) : null}
+
+        {error ? <ErrorPanel message={error} /> : null}
+        {testResult ? (
+          <Alert color={testResult.ok ? 'green' : 'red'} title={testResult.ok ? `Connected (HTTP ${testResult.status}) in ${testResult.elapsed_ms} ms` : 'Connection failed'}>
+            <Text size="xs">{testResult.url}</Text>
+            {testResult.error ? <Text size="xs" ff="monospace">{testResult.error}</Text> : null}
+          </Alert>
+        ) : null}
+
+        <Group justify="space-between">
+          <Button size="xs" variant="default" onClick={() => void handleTest()} loading={testing}>Test connection</Button>
+          <Group gap="xs">
+            <Button size="xs" variant="default" onClick={onClose}>Cancel</Button>
+            <Button size="xs" onClick={() => void handleSave()} loading={busy}>Save</Button>
+          </Group>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
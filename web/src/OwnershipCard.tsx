@@ -0,0 +1,130 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, Group, NumberInput, ScrollArea, Stack, Table, Text, Title } from '@mantine/core';
+import { getOwnershipMap, suggestReviewers, type OwnershipMap, type ReviewerSuggestions } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type OwnershipCardProps = {
+  repoRef: string;
+};
+
+/** Top committers per directory over a window, plus reviewer suggestions for the current working-tree changes. */
+export function OwnershipCard(props: OwnershipCardProps) {
+  const { repoRef } = props;
+  const [windowDays, setWindowDays] = useState(180);
+  const [ownership, setOwnership] = useState<OwnershipMap | null>(null);
+  const [suggestions, setSuggestions] = useState<ReviewerSuggestions | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [suggesting, setSuggesting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function load(days = windowDays) {
+    if (!repoRef.trim()) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setOwnership(await getOwnershipMap(repoRef, days));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  async function suggest() {
+    setSuggesting(true);
+    setError(null);
+    try {
+      setSuggestions(await suggestReviewers(repoRef, windowDays));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setSuggesting(false);
+    }
+  }
+
+  useEffect(() => {
+    setOwnership(null);
+    setSuggestions(null);
+    void load();
+  }, [repoRef]);
+
+  // Top-level directories only; deeper ones show up as folder tooltips in the file explorer.
+  const rows = Object.entries(ownership?.directories ?? {}).filter(([path]) => path === '.' || !path.includes('/'));
+
+  return (
+    <Card withBorder>
+      <Stack gap="md">
+        <Group justify="space-between" align="flex-start" wrap="wrap">
+          <Stack gap={2}>
+            <Title order={4}>Ownership</Title>
+            <Text size="sm" c="dimmed">Top committers per directory. Hover a folder in the file explorer for deeper directories.</Text>
+          </Stack>
+          <Group gap="xs" align="flex-end">
+            <NumberInput size="xs" label="Window (days)" min={1} max={3650} value={windowDays} onChange={(value) => setWindowDays(Number(value) || 180)} w={120} />
+            <Button size="xs" variant="default" onClick={() => void load()} loading={loading} disabled={!repoRef.trim()}>
+              Refresh
+            </Button>
+            <Button size="xs" variant="light" onClick={() => void suggest()} loading={suggesting} disabled={!repoRef.trim()}>
+              Suggest reviewers for current changes
+            </Button>
+          </Group>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+
+        {suggestions ? (
+          <Card withBorder padding="sm">
+            <Stack gap={4}>
+              <Text size="sm" fw={500}>
+                Suggested reviewers for {suggestions.changed_paths.length} changed file{suggestions.changed_paths.length === 1 ? '' : 's'}
+              </Text>
+              {suggestions.reviewers.length ? (
+                suggestions.reviewers.map((reviewer) => (
+                  <Group key={reviewer.email} gap="xs" wrap="nowrap">
+                    <Text size="sm">{reviewer.name}</Text>
+                    <Text size="xs" c="dimmed">{reviewer.email}</Text>
+                    <Badge size="xs" variant="light">{reviewer.paths.length} file{reviewer.paths.length === 1 ? '' : 's'}</Badge>
+                    <Text size="xs" c="dimmed" truncate title={reviewer.paths.join('\n')}>{reviewer.paths.slice(0, 3).join(', ')}</Text>
+                  </Group>
+                ))
+              ) : (
+                <Text size="xs" c="dimmed">
+                  {suggestions.changed_paths.length ? 'Nobody else committed near these files in the window.' : 'The working tree has no changes.'}
+                </Text>
+              )}
+            </Stack>
+          </Card>
+        ) : null}
+
+        {ownership && !rows.length ? <Text size="sm" c="dimmed">No commits in the last {ownership.window_days} days.</Text> : null}
+        {rows.length ? (
+          <ScrollArea.Autosize mah={360}>
+            <Table striped>
+              <Table.Thead>
+                <Table.Tr>
+                  <Table.Th>Directory</Table.Th>
+                  <Table.Th>Top committers</Table.Th>
+                </Table.Tr>
+              </Table.Thead>
+              <Table.Tbody>
+                {rows.map(([path, owners]) => (
+                  <Table.Tr key={path}>
+                    <Table.Td><Text size="xs" ff="monospace">{path === '.' ? '(whole repo)' : path}</Text></Table.Td>
+                    <Table.Td>
+                      <Group gap={6}>
+                        {owners.map((owner) => (
+                          <Badge key={owner.email} size="sm" variant="light" title={`${owner.email} · ${owner.lines.toLocaleString()} lines changed`}>
+                            {owner.name} · {owner.commits}
+                          </Badge>
+                        ))}
+                      </Group>
+                    </Table.Td>
+                  </Table.Tr>
+                ))}
+              </Table.Tbody>
+            </Table>
+          </ScrollArea.Autosize>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
@@ -0,0 +1,149 @@
+import { useEffect, useMemo, useState } from 'react';
+import { Alert, Badge, Button, Code, Group, Modal, ScrollArea, SegmentedControl, Stack, Text, TextInput, UnstyledButton } from '@mantine/core';
+import { listPlugins, renderPluginPanel, runPluginCommand, type PluginCatalog, type PluginOutput } from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { pressableProps } from './accessibility';
+
+type PluginPaletteProps = {
+  opened: boolean;
+  onClose: () => void;
+  repoRef: string | null;
+};
+
+type Invocation = {
+  kind: 'command' | 'panel';
+  plugin: string;
+  id: string;
+  title: string;
+};
+
+const NOTIFICATION_COLORS: Record<string, string> = { error: 'red', warn: 'yellow', warning: 'yellow', success: 'green' };
+
+export function PluginPalette(props: PluginPaletteProps) {
+  const { opened, onClose, repoRef } = props;
+  const [catalog, setCatalog] = useState<PluginCatalog | null>(null);
+  const [mode, setMode] = useState<'command' | 'panel'>('command');
+  const [query, setQuery] = useState('');
+  const [active, setActive] = useState<Invocation | null>(null);
+  const [result, setResult] = useState<PluginOutput | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  function reload() {
+    setError(null);
+    void listPlugins()
+      .then(setCatalog)
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  useEffect(() => {
+    if (!opened) return;
+    setQuery('');
+    setResult(null);
+    setActive(null);
+    reload();
+  }, [opened]);
+
+  const entries = useMemo(() => {
+    const needle = query.trim().toLowerCase();
+    return (catalog?.plugins ?? []).flatMap((plugin) =>
+      (mode === 'command' ? plugin.commands : plugin.panels)
+        .map((entry): Invocation => ({ kind: mode, plugin: plugin.id, id: entry.id, title: entry.title }))
+        .filter((entry) => !needle || `${entry.plugin} ${entry.title}`.toLowerCase().includes(needle))
+    );
+  }, [catalog, mode, query]);
+
+  const broken = (catalog?.plugins ?? []).filter((plugin) => plugin.error);
+
+  async function invoke(entry: Invocation) {
+    setActive(entry);
+    setBusy(true);
+    setError(null);
+    setResult(null);
+    try {
+      const invokeFn = entry.kind === 'command' ? runPluginCommand : renderPluginPanel;
+      setResult(await invokeFn(entry.plugin, entry.id, repoRef));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Plugins" size="lg" centered>
+      <Stack gap="sm">
+        <Group justify="space-between">
+          <SegmentedControl
+            size="xs"
+            value={mode}
+            onChange={(value) => setMode(value as 'command' | 'panel')}
+            data={[
+              { label: 'Commands', value: 'command' },
+              { label: 'Panels', value: 'panel' }
+            ]}
+          />
+          <Button size="xs" variant="default" onClick={reload}>Reload</Button>
+        </Group>
+        <TextInput
+          data-autofocus
+          placeholder={mode === 'command' ? 'Run a plugin command…' : 'Open a plugin panel…'}
+          value={query}
+          onChange={(event) => setQuery(event.currentTarget.value)}
+          onKeyDown={(event) => {
+            if (event.key === 'Enter' && entries[0]) void invoke(entries[0]);
+          }}
+        />
+        {catalog && !catalog.enabled ? <Alert color="gray">Plugins are disabled in settings.</Alert> : null}
+        <ScrollArea.Autosize mah={220} type="auto">
+          <Stack gap={2}>
+            {entries.map((entry) => (
+              <UnstyledButton
+                key={`${entry.plugin}:${entry.id}`}
+                {...pressableProps(() => void invoke(entry))}
+                px="xs"
+                py={6}
+                style={{
+                  borderRadius: 4,
+                  background: active?.plugin === entry.plugin && active.id === entry.id ? 'var(--mantine-color-default-hover)' : undefined
+                }}
+              >
+                <Group justify="space-between" wrap="nowrap">
+                  <Text size="sm">{entry.title}</Text>
+                  <Badge size="xs" variant="light" color="gray">{entry.plugin}</Badge>
+                </Group>
+              </UnstyledButton>
+            ))}
+            {catalog && entries.length === 0 ? (
+              <Text size="sm" c="dimmed">
+                No plugin {mode === 'command' ? 'commands' : 'panels'}{query ? ' match' : ' registered'}. Add <Code>.rhai</Code> scripts to <Code>{catalog.dir}</Code>.
+              </Text>
+            ) : null}
+          </Stack>
+        </ScrollArea.Autosize>
+        {broken.map((plugin) => (
+          <Alert key={plugin.id} color="orange" title={`${plugin.id} failed to load`}>
+            <Text size="xs" style={{ whiteSpace: 'pre-wrap' }}>{plugin.error}</Text>
+          </Alert>
+        ))}
+        {error ? <ErrorPanel message={error} /> : null}
+        {busy ? <Text size="sm" c="dimmed">Running {active?.title}…</Text> : null}
+        {result?.notifications.map((notification, index) => (
+          <Alert key={index} color={NOTIFICATION_COLORS[notification.level] ?? 'blue'} py={6}>
+            {notification.message}
+          </Alert>
+        ))}
+        {result && result.output ? (
+          <ScrollArea.Autosize mah={320} type="auto">
+            <Code block>{result.output}</Code>
+          </ScrollArea.Autosize>
+        ) : null}
+        {catalog ? (
+          <Text size="xs" c="dimmed">
+            Scripts can call read_file, run (allowed: {catalog.allowed_commands.join(', ') || 'none'}; git read-only subcommands only), notify and repo.
+          </Text>
+        ) : null}
+      </Stack>
+    </Modal>
+  );
+}
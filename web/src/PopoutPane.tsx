@@ -0,0 +1,84 @@
+import { lazy, Suspense, useEffect, useState } from 'react';
+import { Alert, Box, Group, Loader, Text, Title } from '@mantine/core';
+import type { ReviewSourceControlState } from './ReviewDiffViewerPanel';
+import { PANE_LABELS, POPOUT_PANES, type WorkspacePane } from './WorkspaceTemplates';
+
+const ReviewDiffViewerPanel = lazy(async () => {
+  const mod = await import('./ReviewDiffViewerPanel');
+  return { default: mod.ReviewDiffViewerPanel };
+});
+
+const CommitSummaryPanel = lazy(async () => {
+  const mod = await import('./CommitSummaryPanel');
+  return { default: mod.CommitSummaryPanel };
+});
+
+const RepoMonacoFileEditorPanel = lazy(async () => {
+  const mod = await import('./RepoMonacoFileEditorPanel');
+  return { default: mod.RepoMonacoFileEditorPanel };
+});
+
+const AnalysisPanel = lazy(async () => {
+  const mod = await import('./AnalysisPanel');
+  return { default: mod.AnalysisPanel };
+});
+
+/** Reads `?popout=<pane>&repo=<path>` from the current URL. */
+export function popoutRequestFromLocation() {
+  const params = new URLSearchParams(window.location.search);
+  const pane = params.get('popout') as WorkspacePane | null;
+  if (!pane) return null;
+  return { pane, repoRef: params.get('repo') ?? '' };
+}
+
+type PopoutPaneProps = {
+  pane: WorkspacePane;
+  repoRef: string;
+};
+
+/** A single workspace pane rendered on its own, for use in a separate browser window. */
+export function PopoutPane(props: PopoutPaneProps) {
+  const { pane, repoRef } = props;
+  const [reviewState, setReviewState] = useState<ReviewSourceControlState>({
+    selected_scope: 'unstaged',
+    selected_path: null,
+    diff_style: 'unified',
+    only_changes: true,
+    context_lines: 10,
+    whole_file: false,
+    whitespace_mode: 'none',
+    sort: 'path',
+    layout: 'flat'
+  });
+
+  useEffect(() => {
+    const repoName = repoRef.split(/[\\/]/).filter(Boolean).pop() ?? repoRef;
+    document.title = `${PANE_LABELS[pane] ?? pane} · ${repoName}`;
+  }, [pane, repoRef]);
+
+  if (!POPOUT_PANES.includes(pane) || !repoRef.trim()) {
+    return <Alert color="red" m="md">This pane cannot be opened in a separate window.</Alert>;
+  }
+
+  const fallback = <Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading…</Text></Group>;
+
+  return (
+    <Box p="md">
+      <Group gap="xs" mb="sm">
+        <Title order={5}>{PANE_LABELS[pane]}</Title>
+        <Text size="xs" c="dimmed" ff="monospace">{repoRef}</Text>
+      </Group>
+      <Suspense fallback={fallback}>
+        {pane === 'diff' ? (
+          <ReviewDiffViewerPanel repoRef={repoRef} state={reviewState} onPersistState={async (next) => setReviewState(next)} forceViewerOpen />
+        ) : pane === 'commits' ? (
+          <CommitSummaryPanel repoRef={repoRef} />
+        ) : pane === 'files' ? (
+          <RepoMonacoFileEditorPanel repoRef={repoRef} viewerId="popout" />
+        ) : (
+          <AnalysisPanel repoRef={repoRef} />
+        )}
+      </Suspense>
+    </Box>
+  );
+}
@@ -0,0 +1,226 @@
+import { useEffect, useState } from 'react';
+import { Alert, Badge, Button, Code, Drawer, Group, Loader, NumberInput, Paper, Stack, Switch, Table, Text } from '@mantine/core';
+import { IconPlayerPlay, IconRefresh } from '@tabler/icons-react';
+import { executeWorkflowCapability } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+export type PostprocessOptions = {
+  skip: string[];
+  timeout_secs: number | null;
+};
+
+type SimulationPlan = {
+  commands: Array<{ label: string; command: string; skipped: boolean }>;
+  shell: string;
+  shell_path: string | null;
+  shell_args: string[];
+  working_dir: string;
+  working_dir_exists: boolean;
+  env_profile: string | null;
+  env: Array<{ key: string; value: string }>;
+  timeout_secs: number | null;
+};
+
+type CommandResult = {
+  label: string;
+  command: string;
+  status: number | null;
+  skipped?: boolean;
+  timed_out?: boolean;
+  duration_ms?: number;
+};
+
+type PostprocessSimulationDrawerProps = {
+  opened: boolean;
+  onClose: () => void;
+  runId: string | null;
+  value: PostprocessOptions;
+  onSave: (next: PostprocessOptions) => Promise<void>;
+};
+
+function capabilityPayload(response: Record<string, unknown>): Record<string, unknown> {
+  const results = Array.isArray(response.results) ? (response.results as Array<Record<string, unknown>>) : [];
+  return (results[0]?.payload ?? {}) as Record<string, unknown>;
+}
+
+function quoteForShell(command: string) {
+  return `'${command.replace(/'/g, `'\\''`)}'`;
+}
+
+/**
+ * Shows what the compile stage's postprocess commands will run with before they run: shell, working
+ * directory and env profile. Commands can be skipped and given a timeout, and the set can be run once from here.
+ */
+export function PostprocessSimulationDrawer(props: PostprocessSimulationDrawerProps) {
+  const { opened, onClose, runId, value, onSave } = props;
+  const [draft, setDraft] = useState<PostprocessOptions>(value);
+  const [plan, setPlan] = useState<SimulationPlan | null>(null);
+  const [results, setResults] = useState<CommandResult[] | null>(null);
+  const [busy, setBusy] = useState<'simulate' | 'run' | 'save' | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  async function simulate(options: PostprocessOptions) {
+    if (!runId) return;
+    setBusy('simulate');
+    setError(null);
+    try {
+      const response = await executeWorkflowCapability(runId, 'compile_commands', { simulate: true, ...options });
+      setPlan(capabilityPayload(response) as unknown as SimulationPlan);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(null);
+    }
+  }
+
+  useEffect(() => {
+    if (!opened) return;
+    setDraft(value);
+    setResults(null);
+    void simulate(value);
+  }, [opened, runId]);
+
+  function toggleSkip(command: string, run: boolean) {
+    setDraft((current) => ({
+      ...current,
+      skip: run ? current.skip.filter((entry) => entry !== command) : [...current.skip.filter((entry) => entry !== command), command]
+    }));
+  }
+
+  async function save() {
+    setBusy('save');
+    setError(null);
+    try {
+      await onSave(draft);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(null);
+    }
+  }
+
+  async function runNow() {
+    if (!runId) return;
+    setBusy('run');
+    setError(null);
+    setResults(null);
+    try {
+      const response = await executeWorkflowCapability(runId, 'compile_commands', draft);
+      const payload = capabilityPayload(response);
+      setResults(Array.isArray(payload.results) ? (payload.results as CommandResult[]) : []);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(null);
+    }
+  }
+
+  const dirty = JSON.stringify(draft) !== JSON.stringify(value);
+  const shellPrefix = plan ? [plan.shell, ...plan.shell_args].join(' ') : '';
+
+  return (
+    <Drawer opened={opened} onClose={onClose} position="right" size="lg" title="Postprocess simulation">
+      <Stack gap="sm">
+        <Text size="xs" c="dimmed">
+          Nothing runs until you press Run now. The compile stage uses the same commands, skips and timeout after each apply.
+        </Text>
+        {!runId ? <Alert color="yellow">Select a run to resolve its commands.</Alert> : null}
+        {error ? <ErrorPanel message={error} /> : null}
+        {busy === 'simulate' && !plan ? <Loader size="sm" /> : null}
+        {plan ? (
+          <>
+            <Paper withBorder p="sm">
+              <Stack gap={4}>
+                <Group gap="xs">
+                  <Text size="xs" fw={600} w={90}>Shell</Text>
+                  <Code>{shellPrefix}</Code>
+                  {plan.shell_path ? <Text size="xs" c="dimmed" ff="monospace">{plan.shell_path}</Text> : <Badge size="xs" color="red" variant="light">not found on PATH</Badge>}
+                </Group>
+                <Group gap="xs">
+                  <Text size="xs" fw={600} w={90}>Working dir</Text>
+                  <Code>{plan.working_dir}</Code>
+                  {!plan.working_dir_exists ? <Badge size="xs" color="red" variant="light">missing</Badge> : null}
+                </Group>
+                <Group gap="xs" align="flex-start">
+                  <Text size="xs" fw={600} w={90}>Environment</Text>
+                  <Stack gap={2} style={{ flex: 1 }}>
+                    <Text size="xs" c="dimmed">
+                      {plan.env_profile ? `Profile "${plan.env_profile}" on top of the app's environment.` : "The app's own environment; no env profile is active for this repo."}
+                    </Text>
+                    {plan.env.length ? (
+                      <Table withTableBorder fz="xs" verticalSpacing={2}>
+                        <Table.Tbody>
+                          {plan.env.map((entry) => (
+                            <Table.Tr key={entry.key}>
+                              <Table.Td ff="monospace">{entry.key}</Table.Td>
+                              <Table.Td ff="monospace" style={{ wordBreak: 'break-all' }}>{entry.value}</Table.Td>
+                            </Table.Tr>
+                          ))}
+                        </Table.Tbody>
+                      </Table>
+                    ) : null}
+                  </Stack>
+                </Group>
+              </Stack>
+            </Paper>
+
+            <NumberInput
+              size="xs"
+              label="Timeout per command (seconds)"
+              description="The command and everything it started are killed once this passes. Leave empty for no limit."
+              min={1}
+              value={draft.timeout_secs ?? ''}
+              onChange={(next) => setDraft((current) => ({ ...current, timeout_secs: typeof next === 'number' && next > 0 ? next : null }))}
+              w={260}
+            />
+
+            {plan.commands.length === 0 ? <Text size="sm" c="dimmed">No postprocess commands are configured for this stage.</Text> : null}
+            {plan.commands.map((row) => {
+              const skipped = draft.skip.includes(row.command) || draft.skip.includes(row.label);
+              const result = results?.find((item) => item.command === row.command);
+              return (
+                <Paper key={row.command} withBorder p="xs" style={{ opacity: skipped ? 0.6 : 1 }}>
+                  <Group justify="space-between" wrap="nowrap" align="flex-start">
+                    <Stack gap={2} style={{ minWidth: 0, flex: 1 }}>
+                      <Text size="sm" fw={600}>{row.label}</Text>
+                      <Code block>{`${shellPrefix} ${quoteForShell(row.command)}`}</Code>
+                      {result ? (
+                        <Group gap="xs">
+                          {result.skipped ? (
+                            <Badge size="xs" color="gray" variant="light">skipped</Badge>
+                          ) : result.timed_out ? (
+                            <Badge size="xs" color="orange" variant="light">timed out</Badge>
+                          ) : (
+                            <Badge size="xs" color={result.status === 0 ? 'green' : 'red'} variant="light">exit {result.status ?? -1}</Badge>
+                          )}
+                          {typeof result.duration_ms === 'number' && !result.skipped ? (
+                            <Text size="xs" c="dimmed">{(result.duration_ms / 1000).toFixed(1)}s</Text>
+                          ) : null}
+                        </Group>
+                      ) : null}
+                    </Stack>
+                    <Switch size="xs" label="Run" checked={!skipped} onChange={(event) => toggleSkip(row.command, event.currentTarget.checked)} />
+                  </Group>
+                </Paper>
+              );
+            })}
+          </>
+        ) : null}
+
+        <Group justify="space-between">
+          <Button size="xs" variant="default" leftSection={<IconRefresh size={14} />} loading={busy === 'simulate'} disabled={!runId} onClick={() => void simulate(draft)}>
+            Re-resolve
+          </Button>
+          <Group gap="xs">
+            <Button size="xs" variant="light" leftSection={<IconPlayerPlay size={14} />} loading={busy === 'run'} disabled={!runId || !plan?.commands.length || !!busy} onClick={() => void runNow()}>
+              Run now
+            </Button>
+            <Button size="xs" loading={busy === 'save'} disabled={!dirty || !!busy} onClick={() => void save()}>
+              Save
+            </Button>
+          </Group>
+        </Group>
+      </Stack>
+    </Drawer>
+  );
+}
@@ -0,0 +1,154 @@
+import { Fragment, useEffect, useState } from 'react';
+import { ActionIcon, Badge, Button, Code, Group, Modal, Stack, Table, Text, Tooltip } from '@mantine/core';
+import { IconPlayerStop, IconRefresh } from '@tabler/icons-react';
+import { killProcess, listProcesses, restartProcess, type ProcessInfo, type ProcessStatus } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type ProcessesModalProps = {
+  opened: boolean;
+  onClose: () => void;
+};
+
+const STATUS_COLORS: Record<ProcessStatus, string> = {
+  running: 'blue',
+  exited: 'gray',
+  killed: 'orange'
+};
+
+const POLL_MS = 1500;
+
+function formatRuntime(ms: number) {
+  const seconds = Math.floor(ms / 1000);
+  if (seconds < 60) return `${seconds}s`;
+  const minutes = Math.floor(seconds / 60);
+  if (minutes < 60) return `${minutes}m ${seconds % 60}s`;
+  return `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+}
+
+/** Child processes the app started (terminal panes, compile stages, save hooks, plugins), with kill and restart. */
+export function ProcessesModal(props: ProcessesModalProps) {
+  const { opened, onClose } = props;
+  const [processes, setProcesses] = useState<ProcessInfo[]>([]);
+  const [busyId, setBusyId] = useState<number | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  async function refresh() {
+    try {
+      setProcesses(await listProcesses());
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    if (!opened) return;
+    setError(null);
+    void refresh();
+    const timer = window.setInterval(() => void refresh(), POLL_MS);
+    return () => window.clearInterval(timer);
+  }, [opened]);
+
+  async function act(id: number, action: (id: number) => Promise<ProcessInfo>) {
+    setBusyId(id);
+    setError(null);
+    try {
+      await action(id);
+      await refresh();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusyId(null);
+    }
+  }
+
+  const running = processes.filter((process) => process.status === 'running').length;
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Processes" size="xl">
+      <Stack gap="sm">
+        <Group justify="space-between">
+          <Text size="xs" c="dimmed">
+            {running} running. Kill stops the whole process group, so commands a shell started go too; kill again to force it.
+          </Text>
+          <Button size="xs" variant="default" leftSection={<IconRefresh size={14} />} onClick={() => void refresh()}>
+            Refresh
+          </Button>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        {processes.length === 0 ? (
+          <Text size="sm" c="dimmed">Nothing has been started yet.</Text>
+        ) : (
+          <Table striped withTableBorder verticalSpacing={4} fz="xs">
+            <Table.Thead>
+              <Table.Tr>
+                <Table.Th>Command</Table.Th>
+                <Table.Th>Kind</Table.Th>
+                <Table.Th>Status</Table.Th>
+                <Table.Th>Runtime</Table.Th>
+                <Table.Th>CPU</Table.Th>
+                <Table.Th />
+              </Table.Tr>
+            </Table.Thead>
+            <Table.Tbody>
+              {processes.map((process) => (
+                <Fragment key={process.id}>
+                  <Table.Tr>
+                    <Table.Td maw={360}>
+                      <Text size="xs" fw={600} truncate>{process.label || process.command}</Text>
+                      <Text size="xs" c="dimmed" ff="monospace" truncate title={`${process.command}${process.cwd ? `\nin ${process.cwd}` : ''}`}>
+                        {process.command}
+                      </Text>
+                    </Table.Td>
+                    <Table.Td>{process.kind}</Table.Td>
+                    <Table.Td>
+                      <Badge size="xs" variant="light" color={STATUS_COLORS[process.status]}>
+                        {process.status === 'exited' && process.exit_code !== null ? `exit ${process.exit_code}` : process.status}
+                      </Badge>
+                    </Table.Td>
+                    <Table.Td>{formatRuntime(process.runtime_ms)}</Table.Td>
+                    <Table.Td>{process.cpu_percent === null ? '—' : `${process.cpu_percent.toFixed(1)}%`}</Table.Td>
+                    <Table.Td>
+                      <Group gap={4} wrap="nowrap" justify="flex-end">
+                        <Tooltip label="Kill">
+                          <ActionIcon
+                            size="sm"
+                            variant="subtle"
+                            color="red"
+                            aria-label="Kill"
+                            disabled={process.status !== 'running'}
+                            loading={busyId === process.id}
+                            onClick={() => void act(process.id, killProcess)}
+                          >
+                            <IconPlayerStop size={14} />
+                          </ActionIcon>
+                        </Tooltip>
+                        <Tooltip label="Restart">
+                          <ActionIcon
+                            size="sm"
+                            variant="subtle"
+                            aria-label="Restart"
+                            loading={busyId === process.id}
+                            onClick={() => void act(process.id, restartProcess)}
+                          >
+                            <IconRefresh size={14} />
+                          </ActionIcon>
+                        </Tooltip>
+                      </Group>
+                    </Table.Td>
+                  </Table.Tr>
+                  {process.output_tail ? (
+                    <Table.Tr>
+                      <Table.Td colSpan={6}>
+                        <Code block mah={160} style={{ overflow: 'auto' }}>{process.output_tail}</Code>
+                      </Table.Td>
+                    </Table.Tr>
+                  ) : null}
+                </Fragment>
+              ))}
+            </Table.Tbody>
+          </Table>
+        )}
+      </Stack>
+    </Modal>
+  );
+}
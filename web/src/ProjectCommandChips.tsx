@@ -0,0 +1,78 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Button, Group, Loader, Text, Tooltip } from '@mantine/core';
+import { IconRefresh } from '@tabler/icons-react';
+import { getProjectCommands, type ProjectCommand, type ProjectCommandKind } from './api';
+
+const KIND_COLORS: Record<ProjectCommandKind, string> = {
+  build: 'blue',
+  test: 'teal',
+  run: 'grape',
+  lint: 'yellow',
+  other: 'gray'
+};
+
+type ProjectCommandChipsProps = {
+  repoRef: string;
+  label: string;
+  /** Limits the chips to these kinds; all kinds when unset. */
+  kinds?: ProjectCommandKind[];
+  /** Commands already in use are shown disabled. */
+  disabledCommands?: string[];
+  busyCommand?: string | null;
+  onPick: (command: ProjectCommand) => void;
+};
+
+/** Commands detected from the repo's manifests as one-click chips. Detection is cached per repo on the server. */
+export function ProjectCommandChips(props: ProjectCommandChipsProps) {
+  const { repoRef, label, kinds, disabledCommands = [], busyCommand = null, onPick } = props;
+  const [commands, setCommands] = useState<ProjectCommand[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  async function load(refresh: boolean) {
+    if (!repoRef.trim()) return;
+    setLoading(true);
+    try {
+      setCommands((await getProjectCommands(repoRef, refresh)).commands);
+    } catch {
+      setCommands([]);
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  useEffect(() => {
+    setCommands([]);
+    void load(false);
+  }, [repoRef]);
+
+  const visible = kinds ? commands.filter((command) => kinds.includes(command.kind)) : commands;
+  if (!visible.length && !loading) return null;
+
+  return (
+    <Group gap={6} wrap="wrap">
+      <Text size="xs" c="dimmed">{label}</Text>
+      {loading && !visible.length ? <Loader size="xs" /> : null}
+      {visible.map((command) => (
+        <Tooltip key={command.command} label={`${command.command} — from ${command.source}`} openDelay={400}>
+          <Button
+            size="compact-xs"
+            variant="light"
+            radius="xl"
+            color={KIND_COLORS[command.kind]}
+            ff="monospace"
+            loading={busyCommand === command.command}
+            disabled={disabledCommands.includes(command.command)}
+            onClick={() => onPick(command)}
+          >
+            {command.label}
+          </Button>
+        </Tooltip>
+      ))}
+      <Tooltip label="Detect again">
+        <ActionIcon size="sm" variant="subtle" aria-label="Detect project commands again" loading={loading} onClick={() => void load(true)}>
+          <IconRefresh size={14} />
+        </ActionIcon>
+      </Tooltip>
+    </Group>
+  );
+}
@@ -0,0 +1,247 @@
+import { useEffect, useMemo, useState } from 'react';
+import { Badge, Button, Checkbox, Group, Loader, Modal, ScrollArea, Stack, Switch, Text, TextInput, UnstyledButton } from '@mantine/core';
+import { IconDownload, IconSearch } from '@tabler/icons-react';
+import {
+  applySearchReplace,
+  exportSearchResults,
+  previewSearchReplace,
+  searchProject,
+  type ProjectSearchQuery,
+  type ProjectSearchResults,
+  type ReplacePreview,
+} from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { SafePatchDiff } from './SafePatchDiff';
+
+type ProjectSearchModalProps = {
+  opened: boolean;
+  onClose: () => void;
+  repoRef: string;
+  /** Paths from the active tree filter; when set, the search can be limited to them. */
+  scopePaths: string[] | null;
+  readOnly: boolean;
+  onOpenMatch: (path: string, line: number, column: number) => void;
+  /** Called with the files a replacement rewrote so open buffers can be reloaded. */
+  onReplaced: (paths: string[]) => void;
+};
+
+function downloadTextFile(filename: string, contents: string, contentType: string) {
+  const blob = new Blob([contents], { type: contentType });
+  const url = URL.createObjectURL(blob);
+  const anchor = document.createElement('a');
+  anchor.href = url;
+  anchor.download = filename;
+  document.body.appendChild(anchor);
+  anchor.click();
+  anchor.remove();
+  URL.revokeObjectURL(url);
+}
+
+/** Project-wide search with Markdown/CSV export and a previewed replace that applies as one changeset. */
+export function ProjectSearchModal(props: ProjectSearchModalProps) {
+  const { opened, onClose, repoRef, scopePaths, readOnly, onOpenMatch, onReplaced } = props;
+  const [pattern, setPattern] = useState('');
+  const [regex, setRegex] = useState(false);
+  const [caseSensitive, setCaseSensitive] = useState(false);
+  const [useScope, setUseScope] = useState(true);
+  const [results, setResults] = useState<ProjectSearchResults | null>(null);
+  const [replacement, setReplacement] = useState('');
+  const [previews, setPreviews] = useState<ReplacePreview[] | null>(null);
+  const [excluded, setExcluded] = useState<Set<string>>(new Set());
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+
+  const query: ProjectSearchQuery = {
+    repo_ref: repoRef,
+    pattern,
+    regex,
+    case_sensitive: caseSensitive,
+    paths: useScope && scopePaths ? scopePaths : [],
+  };
+
+  useEffect(() => {
+    setResults(null);
+    setPreviews(null);
+  }, [repoRef, pattern, regex, caseSensitive, useScope, scopePaths]);
+
+  useEffect(() => {
+    setPreviews(null);
+  }, [replacement]);
+
+  const grouped = useMemo(() => {
+    const byPath = new Map<string, ProjectSearchResults['matches']>();
+    for (const match of results?.matches ?? []) {
+      byPath.set(match.path, [...(byPath.get(match.path) ?? []), match]);
+    }
+    return [...byPath.entries()];
+  }, [results]);
+
+  async function run(action: () => Promise<void>) {
+    setBusy(true);
+    setError(null);
+    setNotice(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  const search = () => run(async () => setResults(await searchProject(query)));
+
+  const exportResults = (format: 'md' | 'csv') =>
+    run(async () => {
+      const response = await exportSearchResults(query, format);
+      downloadTextFile(response.file_name, response.contents, format === 'csv' ? 'text/csv' : 'text/markdown');
+    });
+
+  const preview = () =>
+    run(async () => {
+      setExcluded(new Set());
+      setPreviews(await previewSearchReplace(query, replacement));
+    });
+
+  const selected = (previews ?? []).filter((item) => !excluded.has(item.path));
+
+  const apply = () =>
+    run(async () => {
+      const expected = Object.fromEntries(selected.map((item) => [item.path, item.fingerprint]));
+      const result = await applySearchReplace(query, replacement, expected);
+      if (result.ok === false) {
+        setError(result.status ?? result.summary ?? 'The replacement did not apply cleanly.');
+        return;
+      }
+      const paths = Object.keys(expected);
+      setNotice(`Replaced in ${paths.length} file${paths.length === 1 ? '' : 's'}.`);
+      setPreviews(null);
+      onReplaced(paths);
+      setResults(await searchProject(query));
+    });
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Search in files" size="xl">
+      <Stack gap="sm">
+        <Group gap="xs" align="flex-end" wrap="nowrap">
+          <TextInput
+            style={{ flex: 1 }}
+            label="Find"
+            value={pattern}
+            onChange={(event) => setPattern(event.currentTarget.value)}
+            onKeyDown={(event) => {
+              if (event.key === 'Enter' && pattern) void search();
+            }}
+            data-autofocus
+          />
+          <Button leftSection={<IconSearch size={14} />} onClick={() => void search()} disabled={!pattern || !repoRef} loading={busy && !previews}>
+            Search
+          </Button>
+        </Group>
+        <Group gap="md">
+          <Switch size="xs" label="Regex" checked={regex} onChange={(event) => setRegex(event.currentTarget.checked)} />
+          <Switch size="xs" label="Match case" checked={caseSensitive} onChange={(event) => setCaseSensitive(event.currentTarget.checked)} />
+          {scopePaths ? (
+            <Switch
+              size="xs"
+              label={`Only the ${scopePaths.length} filtered file${scopePaths.length === 1 ? '' : 's'}`}
+              checked={useScope}
+              onChange={(event) => setUseScope(event.currentTarget.checked)}
+            />
+          ) : null}
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        {notice ? <Text size="sm" c="green">{notice}</Text> : null}
+
+        {results ? (
+          <>
+            <Group justify="space-between">
+              <Text size="sm">
+                {results.matches.length} match{results.matches.length === 1 ? '' : 'es'} in {results.files} file{results.files === 1 ? '' : 's'}
+                {results.truncated ? ' (truncated)' : ''}
+              </Text>
+              <Group gap="xs">
+                <Button size="compact-xs" variant="default" leftSection={<IconDownload size={12} />} onClick={() => void exportResults('md')} disabled={!results.matches.length}>
+                  Export Markdown
+                </Button>
+                <Button size="compact-xs" variant="default" leftSection={<IconDownload size={12} />} onClick={() => void exportResults('csv')} disabled={!results.matches.length}>
+                  Export CSV
+                </Button>
+              </Group>
+            </Group>
+            <ScrollArea.Autosize mah={260}>
+              <Stack gap={6}>
+                {grouped.map(([path, matches]) => (
+                  <Stack key={path} gap={2}>
+                    <Group gap={6}>
+                      <Text size="sm" ff="monospace" fw={600}>{path}</Text>
+                      <Badge size="xs" variant="light">{matches.length}</Badge>
+                    </Group>
+                    {matches.map((match) => (
+                      <UnstyledButton key={`${match.line}:${match.column}`} onClick={() => onOpenMatch(match.path, match.line, match.column)} pl="md">
+                        <Text size="xs" ff="monospace" truncate>
+                          <Text span c="dimmed" size="xs">{match.line}:</Text> {match.text.trim()}
+                        </Text>
+                      </UnstyledButton>
+                    ))}
+                  </Stack>
+                ))}
+              </Stack>
+            </ScrollArea.Autosize>
+
+            <Group gap="xs" align="flex-end" wrap="nowrap">
+              <TextInput
+                style={{ flex: 1 }}
+                label="Replace with"
+                description={regex ? 'Use $1 or ${name} for capture groups' : undefined}
+                value={replacement}
+                onChange={(event) => setReplacement(event.currentTarget.value)}
+                disabled={readOnly}
+              />
+              <Button variant="default" onClick={() => void preview()} disabled={readOnly || !results.matches.length}>
+                Preview replace
+              </Button>
+            </Group>
+            {readOnly ? <Text size="xs" c="dimmed">Replacing is only available on the working tree.</Text> : null}
+          </>
+        ) : busy ? (
+          <Loader size="sm" />
+        ) : null}
+
+        {previews ? (
+          <Stack gap="xs">
+            {previews.length === 0 ? <Text size="sm" c="dimmed">Nothing would change.</Text> : null}
+            <ScrollArea.Autosize mah={360}>
+              <Stack gap="sm">
+                {previews.map((item) => (
+                  <Stack key={item.path} gap={4}>
+                    <Checkbox
+                      checked={!excluded.has(item.path)}
+                      onChange={(event) => {
+                        const include = event.currentTarget.checked;
+                        setExcluded((prev) => {
+                          const next = new Set(prev);
+                          if (include) next.delete(item.path);
+                          else next.add(item.path);
+                          return next;
+                        });
+                      }}
+                      label={<Text size="sm" ff="monospace">{item.path} ({item.replacements})</Text>}
+                    />
+                    <SafePatchDiff patch={item.patch} diffStyle="unified" />
+                  </Stack>
+                ))}
+              </Stack>
+            </ScrollArea.Autosize>
+            <Group justify="flex-end">
+              <Button onClick={() => void apply()} loading={busy} disabled={!selected.length}>
+                Replace in {selected.length} file{selected.length === 1 ? '' : 's'}
+              </Button>
+            </Group>
+          </Stack>
+        ) : null}
+      </Stack>
+    </Modal>
+  );
+}
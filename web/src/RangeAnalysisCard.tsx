@@ -0,0 +1,165 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, Group, NumberInput, ScrollArea, SimpleGrid, Stack, Table, Text, TextInput, Title } from '@mantine/core';
+import { analyzeRefRange, type RangeAnalysis } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type RangeAnalysisCardProps = {
+  repoRef: string;
+  /** When both refs are given the card follows them (e.g. a branch comparison) instead of showing its own inputs. */
+  fromRef?: string;
+  toRef?: string;
+  onOpenFile?: (path: string) => void;
+  /** Loads the files that still exist at `gitRef` into the repo context export. */
+  onExportContext?: (paths: string[], gitRef: string) => void;
+};
+
+/** What changed between two refs: churn per directory, authors involved and line deltas per file. */
+export function RangeAnalysisCard(props: RangeAnalysisCardProps) {
+  const { repoRef, fromRef, toRef, onOpenFile, onExportContext } = props;
+  const controlled = Boolean(fromRef && toRef);
+  const [from, setFrom] = useState(fromRef ?? 'HEAD~10');
+  const [to, setTo] = useState(toRef ?? 'HEAD');
+  const [depth, setDepth] = useState(1);
+  const [result, setResult] = useState<RangeAnalysis | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function analyze(nextFrom = from, nextTo = to, nextDepth = depth) {
+    if (!repoRef.trim() || !nextFrom.trim() || !nextTo.trim()) return;
+    setBusy(true);
+    setError(null);
+    try {
+      setResult(await analyzeRefRange(repoRef, nextFrom.trim(), nextTo.trim(), nextDepth));
+    } catch (err) {
+      setResult(null);
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  useEffect(() => {
+    setResult(null);
+    if (fromRef && toRef) {
+      setFrom(fromRef);
+      setTo(toRef);
+      void analyze(fromRef, toRef);
+    }
+  }, [repoRef, fromRef, toRef]);
+
+  const surviving = (result?.files ?? []).filter((file) => file.status !== 'D').map((file) => file.path);
+
+  return (
+    <Card withBorder>
+      <Stack gap="sm">
+        <Group justify="space-between" align="flex-start" wrap="wrap">
+          <Stack gap={2}>
+            <Title order={5}>Changes between refs</Title>
+            <Text size="xs" c="dimmed">Only files that differ in the range are analyzed.</Text>
+          </Stack>
+          {onExportContext && result ? (
+            <Button size="xs" variant="light" disabled={!surviving.length} onClick={() => onExportContext(surviving, result.to_ref)}>
+              Context export of this change ({surviving.length})
+            </Button>
+          ) : null}
+        </Group>
+
+        <Group gap="xs" align="flex-end">
+          {controlled ? null : (
+            <>
+              <TextInput size="xs" label="From" value={from} onChange={(event) => setFrom(event.currentTarget.value)} />
+              <TextInput size="xs" label="To" value={to} onChange={(event) => setTo(event.currentTarget.value)} />
+            </>
+          )}
+          <NumberInput
+            size="xs"
+            label="Directory depth"
+            min={1}
+            max={6}
+            value={depth}
+            onChange={(value) => {
+              const next = Number(value) || 1;
+              setDepth(next);
+              if (result) void analyze(from, to, next);
+            }}
+            w={120}
+          />
+          {controlled ? null : (
+            <Button size="xs" onClick={() => void analyze()} loading={busy} disabled={!repoRef.trim() || !from.trim() || !to.trim()}>
+              Analyze
+            </Button>
+          )}
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+
+        {result ? (
+          <>
+            <Group gap="xs">
+              <Badge variant="light">{result.commits} commits</Badge>
+              <Badge variant="light">{result.files.length} files</Badge>
+              <Badge variant="light" color="green">+{result.additions}</Badge>
+              <Badge variant="light" color="red">-{result.deletions}</Badge>
+              <Badge variant="light" color="gray">{result.authors.length} authors</Badge>
+            </Group>
+            <SimpleGrid cols={{ base: 1, md: 2 }}>
+              <Stack gap={4}>
+                <Text size="sm" fw={500}>Churn by directory</Text>
+                <Table>
+                  <Table.Tbody>
+                    {result.directories.map((row) => (
+                      <Table.Tr key={row.directory}>
+                        <Table.Td><Text size="xs" ff="monospace">{row.directory}</Text></Table.Td>
+                        <Table.Td><Text size="xs" c="dimmed">{row.files} files</Text></Table.Td>
+                        <Table.Td><Text size="xs" c="green">+{row.additions}</Text></Table.Td>
+                        <Table.Td><Text size="xs" c="red">-{row.deletions}</Text></Table.Td>
+                      </Table.Tr>
+                    ))}
+                  </Table.Tbody>
+                </Table>
+              </Stack>
+              <Stack gap={4}>
+                <Text size="sm" fw={500}>Authors</Text>
+                <Table>
+                  <Table.Tbody>
+                    {result.authors.map((author) => (
+                      <Table.Tr key={author.email}>
+                        <Table.Td><Text size="xs" title={author.email}>{author.name}</Text></Table.Td>
+                        <Table.Td><Text size="xs" c="dimmed">{author.commits} commits · {author.files} files</Text></Table.Td>
+                        <Table.Td><Text size="xs" c="green">+{author.additions}</Text></Table.Td>
+                        <Table.Td><Text size="xs" c="red">-{author.deletions}</Text></Table.Td>
+                      </Table.Tr>
+                    ))}
+                  </Table.Tbody>
+                </Table>
+                {!result.authors.length ? <Text size="xs" c="dimmed">No non-merge commits in the range.</Text> : null}
+              </Stack>
+            </SimpleGrid>
+            <ScrollArea.Autosize mah={260}>
+              <Stack gap={0}>
+                {result.files.map((file) => (
+                  <Group key={file.path} gap="xs" wrap="nowrap" justify="space-between" px={4} py={2}>
+                    <Group gap={6} wrap="nowrap" style={{ minWidth: 0 }}>
+                      <Badge size="xs" variant="outline">{file.status}</Badge>
+                      <Text
+                        size="xs"
+                        ff="monospace"
+                        truncate
+                        style={{ cursor: onOpenFile && file.status !== 'D' ? 'pointer' : undefined }}
+                        onClick={onOpenFile && file.status !== 'D' ? () => onOpenFile(file.path) : undefined}
+                      >
+                        {file.old_path ? `${file.old_path} → ` : ''}{file.path}
+                      </Text>
+                    </Group>
+                    <Text size="xs" c="dimmed" style={{ whiteSpace: 'nowrap' }}>
+                      {file.additions == null ? 'binary' : `+${file.additions} / -${file.deletions}`}
+                    </Text>
+                  </Group>
+                ))}
+              </Stack>
+            </ScrollArea.Autosize>
+          </>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
@@ -0,0 +1,236 @@
+import { useEffect, useState } from 'react';
+import { Alert, Badge, Box, Button, Card, Group, Loader, Modal, ScrollArea, SegmentedControl, Select, Stack, Switch, Text, TextInput } from '@mantine/core';
+import { IconGitBranch } from '@tabler/icons-react';
+import {
+  createReviewBranch,
+  getReviewCommitDiff,
+  getReviewCommitDiffManifest,
+  getReviewReflog,
+  listRepoFiles,
+  readWorkspaceFile,
+  type ReviewDiffManifestFileEntry,
+  type ReviewReflogEntry,
+} from './api';
+import { CodeBlock } from './MarkdownMessage';
+import { SafePatchDiff, type DiffStyle } from './SafePatchDiff';
+
+type ReflogPanelProps = {
+  repoRef: string;
+};
+
+type ReflogPreview =
+  | { kind: 'diff'; path: string; patch: string }
+  | { kind: 'file'; path: string; contents: string };
+
+const ACTION_COLORS: Record<string, string> = {
+  checkout: 'blue',
+  commit: 'green',
+  reset: 'red',
+  rebase: 'orange',
+  merge: 'grape',
+  'cherry-pick': 'teal',
+  pull: 'cyan',
+};
+
+function actionColor(action: string) {
+  return ACTION_COLORS[action.split(/[\s(]/)[0]] ?? 'gray';
+}
+
+export function ReflogPanel(props: ReflogPanelProps) {
+  const { repoRef } = props;
+  const [entries, setEntries] = useState<ReviewReflogEntry[]>([]);
+  const [selected, setSelected] = useState<ReviewReflogEntry | null>(null);
+  const [onlyUnreachable, setOnlyUnreachable] = useState(false);
+  const [files, setFiles] = useState<ReviewDiffManifestFileEntry[]>([]);
+  const [snapshotFiles, setSnapshotFiles] = useState<string[]>([]);
+  const [branchName, setBranchName] = useState('');
+  const [preview, setPreview] = useState<ReflogPreview | null>(null);
+  const [diffStyle, setDiffStyle] = useState<DiffStyle>('split');
+  const [busy, setBusy] = useState(false);
+  const [inspectBusy, setInspectBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+
+  async function load() {
+    if (!repoRef.trim()) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const response = await getReviewReflog({ repo_ref: repoRef, limit: 300 });
+      setEntries(response.entries);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  useEffect(() => {
+    setEntries([]);
+    setSelected(null);
+    setNotice(null);
+    void load();
+  }, [repoRef]);
+
+  useEffect(() => {
+    setFiles([]);
+    setSnapshotFiles([]);
+    setBranchName(selected ? `recovered-${selected.short_sha}` : '');
+    if (!selected) return;
+    setInspectBusy(true);
+    void Promise.all([
+      getReviewCommitDiffManifest({ repo_ref: repoRef, commit: selected.sha }),
+      listRepoFiles(repoRef, selected.sha, { skipBinary: true }),
+    ])
+      .then(([manifest, snapshot]) => {
+        setFiles(manifest.files);
+        setSnapshotFiles(snapshot.files);
+      })
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)))
+      .finally(() => setInspectBusy(false));
+  }, [selected?.sha, repoRef]);
+
+  async function openDiff(path: string) {
+    if (!selected) return;
+    try {
+      const response = await getReviewCommitDiff({ repo_ref: repoRef, commit: selected.sha, path });
+      setPreview({ kind: 'diff', path, patch: response.patch });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  async function openFile(path: string) {
+    if (!selected) return;
+    try {
+      const response = await readWorkspaceFile(repoRef, path, selected.sha);
+      setPreview({ kind: 'file', path, contents: response.contents });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  async function createBranch() {
+    if (!selected || !branchName.trim()) return;
+    setError(null);
+    setNotice(null);
+    try {
+      const response = await createReviewBranch({ repo_ref: repoRef, name: branchName.trim(), start_point: selected.sha });
+      setNotice(`Created branch ${response.name} at ${response.sha.slice(0, 7)}.`);
+      await load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  const visible = onlyUnreachable ? entries.filter((entry) => !entry.reachable) : entries;
+  const unreachableCount = entries.filter((entry) => !entry.reachable).length;
+
+  return (
+    <Stack gap="sm">
+      <Group justify="space-between">
+        <Group gap="xs">
+          <Badge variant="light">{entries.length} entries</Badge>
+          {unreachableCount > 0 ? <Badge variant="light" color="red">{unreachableCount} not on any branch</Badge> : null}
+        </Group>
+        <Group gap="xs">
+          <Switch size="xs" label="Only unreachable" checked={onlyUnreachable} onChange={(event) => setOnlyUnreachable(event.currentTarget.checked)} />
+          <Button size="xs" variant="default" onClick={() => void load()} loading={busy}>Refresh</Button>
+        </Group>
+      </Group>
+
+      {error ? <Alert color="red" withCloseButton onClose={() => setError(null)}>{error}</Alert> : null}
+      {notice ? <Alert color="teal" withCloseButton onClose={() => setNotice(null)}>{notice}</Alert> : null}
+
+      <Group align="flex-start" gap="sm" wrap="nowrap">
+        <Card withBorder padding="xs" style={{ flex: 1, minWidth: 0 }}>
+          <ScrollArea.Autosize mah={620} type="auto">
+            <Stack gap={2}>
+              {visible.length === 0 && !busy ? <Text size="xs" c="dimmed" p="xs">No reflog entries.</Text> : null}
+              {visible.map((entry) => (
+                <Box
+                  key={entry.selector}
+                  px={6}
+                  py={4}
+                  onClick={() => setSelected(entry)}
+                  style={{ cursor: 'pointer', borderRadius: 6, background: selected?.selector === entry.selector ? 'rgba(34, 139, 230, 0.12)' : undefined }}
+                >
+                  <Group gap={6} wrap="nowrap">
+                    <Text size="xs" ff="monospace" c="dimmed">{entry.selector}</Text>
+                    <Badge size="xs" variant="light" color={actionColor(entry.action)}>{entry.action || 'update'}</Badge>
+                    <Text size="xs" ff="monospace">{entry.short_sha}</Text>
+                    {!entry.reachable ? <Badge size="xs" color="red" variant="outline">unreachable</Badge> : null}
+                  </Group>
+                  <Text size="xs" truncate>{entry.message}</Text>
+                  <Text size="xs" c="dimmed">{entry.recorded_at.replace('T', ' ').slice(0, 19)}</Text>
+                </Box>
+              ))}
+            </Stack>
+          </ScrollArea.Autosize>
+        </Card>
+
+        <Card withBorder padding="sm" style={{ flex: 1, minWidth: 0 }}>
+          {!selected ? (
+            <Text size="sm" c="dimmed">Select an entry to inspect the repository at that point.</Text>
+          ) : (
+            <Stack gap="sm">
+              <Stack gap={2}>
+                <Text fw={600} size="sm">{selected.subject}</Text>
+                <Text size="xs" c="dimmed" ff="monospace">{selected.sha}</Text>
+              </Stack>
+              <Group gap="xs" align="end" wrap="nowrap">
+                <TextInput size="xs" label="Branch name" value={branchName} onChange={(event) => setBranchName(event.currentTarget.value)} style={{ flex: 1 }} />
+                <Button size="xs" leftSection={<IconGitBranch size={14} />} disabled={!branchName.trim()} onClick={() => void createBranch()}>
+                  Create branch here
+                </Button>
+              </Group>
+              <Select
+                size="xs"
+                label="Open file at this commit"
+                placeholder={inspectBusy ? 'Loading…' : `${snapshotFiles.length} files`}
+                searchable
+                data={snapshotFiles}
+                value={null}
+                onChange={(path) => {
+                  if (path) void openFile(path);
+                }}
+                limit={200}
+              />
+              <Text size="xs" fw={600}>Changed in this commit</Text>
+              {inspectBusy ? <Loader size="sm" /> : null}
+              <Stack gap={2}>
+                {files.map((file) => (
+                  <Group key={file.path} gap="xs" wrap="nowrap" justify="space-between" px={4} py={2} style={{ cursor: 'pointer' }} onClick={() => void openDiff(file.path)}>
+                    <Group gap={6} wrap="nowrap" style={{ minWidth: 0 }}>
+                      <Badge size="xs" variant="outline">{file.index_status}</Badge>
+                      <Text size="xs" truncate>{file.path}</Text>
+                    </Group>
+                    <Text size="xs" c="dimmed" style={{ whiteSpace: 'nowrap' }}>+{file.additions} / -{file.deletions}</Text>
+                  </Group>
+                ))}
+              </Stack>
+            </Stack>
+          )}
+        </Card>
+      </Group>
+
+      <Modal opened={!!preview} onClose={() => setPreview(null)} size="90%" title={`${preview?.path ?? ''} @ ${selected?.short_sha ?? ''}`}>
+        {preview?.kind === 'diff' ? (
+          <Stack gap="sm">
+            <Group justify="flex-end">
+              <SegmentedControl
+                size="xs"
+                value={diffStyle}
+                onChange={(value) => setDiffStyle(value as DiffStyle)}
+                data={[{ label: 'Split', value: 'split' }, { label: 'Unified', value: 'unified' }]}
+              />
+            </Group>
+            {preview.patch ? <SafePatchDiff patch={preview.patch} diffStyle={diffStyle} /> : <Text size="sm" c="dimmed">No textual changes.</Text>}
+          </Stack>
+        ) : preview?.kind === 'file' ? (
+          <CodeBlock language={preview.path.split('.').pop() ?? ''} text={preview.contents} />
+        ) : null}
+      </Modal>
+    </Stack>
+  );
+}
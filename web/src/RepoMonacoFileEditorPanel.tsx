@@ -1,30 +1,268 @@
-import { useEffect, useRef, useState } from 'react';
-import { Alert, Badge, Button, Card, Group, Loader, Stack, Switch, Text, Title, ActionIcon, Modal, TextInput, ScrollArea } from '@mantine/core';
+import { useEffect, useMemo, useRef, useState } from 'react';
+import { Alert, Badge, Button, Card, Group, Loader, Stack, Switch, Text, Title, ActionIcon, Modal, NumberInput, TextInput, ScrollArea, SegmentedControl } from '@mantine/core';
 import { Workspace, lazy as mountModernMonaco } from 'modern-monaco';
 import {
   createWorkspaceFile,
   createWorkspaceFolder,
   deleteWorkspacePath,
+  getAppSettings,
+  getEditorState,
+  getOwnershipMap,
   listRepoFiles,
   listRepoTree,
+  patchAppSettings,
   readWorkspaceFile,
+  saveEditorState,
+  stageReviewLines,
   writeWorkspaceFile,
+  type AiActionModel,
+  type LanguageHint,
+  type OwnershipMap,
+  type SaveHookSettings,
 } from './api';
-import { RepoExplorerTree, type RepoTreeEntry } from './RepoTree';
+import { buildTreeFromPaths, RepoExplorerTree, type RepoTreeEntry, type TreeBulkAction } from './RepoTree';
+import { TreeFilterBar } from './TreeFilterBar';
+import { GitStatusBadge, refreshRepoGitStatus, useRepoGitDecorations } from './gitFileStatus';
+import { TreeDropTarget } from './TreeDropTarget';
+import { ErrorPanel } from './ErrorPanel';
+import type { AskAiHandler } from './AskAi';
+import { AiSelectionEditBar, type AiSelectionEditTarget } from './AiSelectionEdit';
+import { DocCommentsModal } from './DocCommentsModal';
+import { ProjectSearchModal } from './ProjectSearchModal';
+import { describeFileFormat, FileFormatStatusBar, reindent, StatusBarToggle, type FileFormat, type IndentStyle } from './FileFormatStatus';
+import { toggleLineComments, TOGGLE_COMMENT_KEYBINDING } from './editorComments';
+import { ColumnSelectionToggle, distributeLines, PASTE_ACROSS_CURSORS_KEYBINDING } from './ColumnSelection';
 
 type RepoMonacoFileEditorPanelProps = {
   repoRef: string;
   gitRef?: string;
+  treeBulkActions?: TreeBulkAction[];
+  openRequest?: { path: string; nonce: number } | null;
+  onAskAi?: AskAiHandler;
+  /** Model used by "Edit selection with AI"; defaults to the inference defaults. */
+  aiModel?: AiActionModel;
+  /** Starts a "Generate tests" run for the file, or for one function when `symbol` is set. */
+  onGenerateTests?: (request: { repoRef: string; path: string; symbol: string | null }) => void;
+  /** Which viewer this is (main tab, side pane, pop-out); soft wrap is remembered per viewer. */
+  viewerId?: string;
 };
 
+type ViewerRefPin = 'follow' | 'worktree' | 'ref';
+
+type EditorRange = { startLineNumber: number; startColumn: number; endLineNumber: number; endColumn: number };
+type EditorSelection = EditorRange & { isEmpty?: () => boolean };
+type EditorModelLike = {
+  uri?: { path: string };
+  getValue?: () => string;
+  pushEOL?: (eol: 0 | 1) => void;
+  updateOptions?: (options: { insertSpaces?: boolean; tabSize?: number }) => void;
+  getLineCount: () => number;
+  getLineContent: (line: number) => string;
+  getLineMaxColumn?: (line: number) => number;
+  getValueInRange?: (range: EditorRange) => string;
+};
+type EditorOptions = {
+  columnSelection?: boolean;
+  multiCursorModifier?: 'alt' | 'ctrlCmd';
+  multiCursorPaste?: 'spread' | 'full';
+  matchBrackets?: 'always' | 'near' | 'never';
+  bracketPairColorization?: { enabled: boolean };
+  autoIndent?: 'none' | 'keep' | 'brackets' | 'advanced' | 'full';
+  autoClosingBrackets?: 'languageDefined' | 'never';
+  autoClosingQuotes?: 'languageDefined' | 'never';
+  wordWrap?: 'on' | 'off';
+  wrappingIndent?: 'none' | 'same' | 'indent';
+};
+type MonacoEditorLike = {
+  hasTextFocus?: () => boolean;
+  getSelection?: () => EditorSelection | null;
+  getSelections?: () => EditorSelection[] | null;
+  getModel?: () => EditorModelLike | null;
+  addAction?: (action: { id: string; label: string; contextMenuGroupId?: string; keybindings?: number[]; run: (editor: MonacoEditorLike) => void }) => unknown;
+  updateOptions?: (options: EditorOptions) => void;
+  executeEdits?: (source: string, edits: { range: EditorRange; text: string }[]) => boolean;
+  focus?: () => void;
+  getContainerDomNode?: () => HTMLElement;
+  saveViewState?: () => unknown;
+  restoreViewState?: (state: unknown) => void;
+  setPosition?: (position: { lineNumber: number; column: number }) => void;
+  revealLineInCenter?: (lineNumber: number) => void;
+};
+
+const ASK_AI_CONTEXT_LINES = 10;
+const askAiEditors = new WeakSet<MonacoEditorLike>();
+const aiActionEditors = new WeakSet<MonacoEditorLike>();
+const generateTestsEditors = new WeakSet<MonacoEditorLike>();
+const configuredEditors = new WeakSet<MonacoEditorLike>();
+/** Monaco `KeyMod.Alt | KeyCode.KeyZ`, the usual word wrap toggle. */
+const TOGGLE_SOFT_WRAP_KEYBINDING = 512 | 56;
+const DOC_COMMENT_EXTENSIONS = /\.(rs|py|pyi)$/;
+const TEST_GENERATION_EXTENSIONS = /\.(rs|py|go|[cm]?js|jsx|ts|tsx)$/;
+
+function monacoEditors(): MonacoEditorLike[] {
+  return (globalThis as { monaco?: { editor?: { getEditors?: () => MonacoEditorLike[] } } }).monaco?.editor?.getEditors?.() ?? [];
+}
+
+/** Best-effort read of the active Monaco selection; the lazy web component does not hand us the editor instance. */
+function activeEditorLineRange(editor?: MonacoEditorLike): { start: number; end: number } | null {
+  const editors = monacoEditors();
+  const target = editor ?? editors.find((candidate) => candidate.hasTextFocus?.()) ?? editors[0];
+  const selection = target?.getSelection?.();
+  if (!selection) return null;
+  const end = selection.endColumn === 1 && selection.endLineNumber > selection.startLineNumber ? selection.endLineNumber - 1 : selection.endLineNumber;
+  return { start: selection.startLineNumber, end };
+}
+
+/** Selected whole lines plus a few lines either side; an empty selection means the cursor line. */
+function activeEditorSnippet(editor?: MonacoEditorLike) {
+  const target = editor ?? monacoEditors().find((candidate) => candidate.hasTextFocus?.()) ?? monacoEditors()[0];
+  const model = target?.getModel?.();
+  const range = activeEditorLineRange(target);
+  if (!model || !range) return null;
+  const lines = (from: number, to: number) => {
+    const out: string[] = [];
+    for (let line = Math.max(1, from); line <= Math.min(model.getLineCount(), to); line += 1) out.push(model.getLineContent(line));
+    return out.join('\n');
+  };
+  return {
+    startLine: range.start,
+    endLine: range.end,
+    snippet: lines(range.start, range.end),
+    context: lines(range.start - ASK_AI_CONTEXT_LINES, range.end + ASK_AI_CONTEXT_LINES)
+  };
+}
+
+/** The exact selected range (or the cursor line when nothing is selected) with surrounding lines for an AI edit. */
+function activeEditorEditTarget(editor?: MonacoEditorLike) {
+  const target = editor ?? monacoEditors().find((candidate) => candidate.hasTextFocus?.()) ?? monacoEditors()[0];
+  const model = target?.getModel?.();
+  const selection = target?.getSelection?.();
+  if (!target || !model?.getValueInRange || !selection) return null;
+  const range: EditorRange = selection.isEmpty?.()
+    ? {
+        startLineNumber: selection.startLineNumber,
+        startColumn: 1,
+        endLineNumber: selection.startLineNumber,
+        endColumn: model.getLineMaxColumn?.(selection.startLineNumber) ?? model.getLineContent(selection.startLineNumber).length + 1
+      }
+    : { startLineNumber: selection.startLineNumber, startColumn: selection.startColumn, endLineNumber: selection.endLineNumber, endColumn: selection.endColumn };
+  const lines = (from: number, to: number) => {
+    const out: string[] = [];
+    for (let line = Math.max(1, from); line <= Math.min(model.getLineCount(), to); line += 1) out.push(model.getLineContent(line));
+    return out.join('\n');
+  };
+  return {
+    editor: target,
+    range,
+    startLine: range.startLineNumber,
+    endLine: range.endLineNumber,
+    text: model.getValueInRange(range),
+    before: lines(range.startLineNumber - ASK_AI_CONTEXT_LINES, range.startLineNumber - 1),
+    after: lines(range.endLineNumber + 1, range.endLineNumber + ASK_AI_CONTEXT_LINES)
+  };
+}
+
+/** The editor showing `path`, falling back to the focused one; several editor panels can be mounted at once. */
+function editorForPath(path: string) {
+  const editors = monacoEditors();
+  return editors.find((editor) => editor.getModel?.()?.uri?.path.endsWith(`/${path}`))
+    ?? editors.find((editor) => editor.hasTextFocus?.())
+    ?? editors[0];
+}
+
+/** Moves the cursor once the editor for `path` is showing it; a freshly opened file takes a moment to mount. */
+function revealInEditor(path: string, lineNumber: number, column: number, attempts = 20) {
+  const editor = editorForPath(path);
+  if (!editor?.getModel?.()?.uri?.path.endsWith(`/${path}`)) {
+    if (attempts > 0) window.setTimeout(() => revealInEditor(path, lineNumber, column, attempts - 1), 100);
+    return;
+  }
+  editor.setPosition?.({ lineNumber, column });
+  editor.revealLineInCenter?.(lineNumber);
+  editor.focus?.();
+}
+
+function autoCloseOptions(enabled: boolean): EditorOptions {
+  const mode = enabled ? 'languageDefined' : 'never';
+  return { autoClosingBrackets: mode, autoClosingQuotes: mode };
+}
+
+/** Replaces each selection, top to bottom, with the next clipboard line. */
+async function pasteAcrossCursors(editor: MonacoEditorLike) {
+  const selections = [...(editor.getSelections?.() ?? [])].sort(
+    (a, b) => a.startLineNumber - b.startLineNumber || a.startColumn - b.startColumn
+  );
+  if (!selections.length) return;
+  const lines = distributeLines(await navigator.clipboard.readText(), selections.length);
+  editor.executeEdits?.(
+    'mdev.pasteAcrossCursors',
+    selections.map((selection, index) => ({
+      range: {
+        startLineNumber: selection.startLineNumber,
+        startColumn: selection.startColumn,
+        endLineNumber: selection.endLineNumber,
+        endColumn: selection.endColumn,
+      },
+      text: lines[index],
+    }))
+  );
+  editor.focus?.();
+}
+
+/** Toggles line comments on every line the selections touch, using the file's comment tokens. */
+function toggleCommentsInEditor(editor: MonacoEditorLike, hint: LanguageHint) {
+  const model = editor.getModel?.();
+  const selections = editor.getSelections?.() ?? [];
+  if (!model || !selections.length) return;
+  const lineNumbers = new Set<number>();
+  for (const selection of selections) {
+    // A selection ending at column 1 doesn't include that line.
+    const end = selection.endLineNumber > selection.startLineNumber && selection.endColumn === 1 ? selection.endLineNumber - 1 : selection.endLineNumber;
+    for (let line = selection.startLineNumber; line <= end; line += 1) lineNumbers.add(line);
+  }
+  const sorted = [...lineNumbers].sort((a, b) => a - b);
+  const toggled = toggleLineComments(sorted.map((line) => model.getLineContent(line)), hint);
+  editor.executeEdits?.(
+    'mdev.toggleComment',
+    sorted.map((line, index) => ({
+      range: { startLineNumber: line, startColumn: 1, endLineNumber: line, endColumn: model.getLineContent(line).length + 1 },
+      text: toggled[index],
+    }))
+  );
+}
+
+/** Name of the `fn`, `def`, `func` or `function` on or just above the cursor line. */
+function functionNameAtCursor(editor?: MonacoEditorLike) {
+  const target = editor ?? monacoEditors().find((candidate) => candidate.hasTextFocus?.()) ?? monacoEditors()[0];
+  const model = target?.getModel?.();
+  const line = target?.getSelection?.()?.startLineNumber;
+  if (!model || !line) return null;
+  for (let current = line; current >= Math.max(1, line - 5); current -= 1) {
+    const match = /\b(?:fn|def|func|function)\s+([A-Za-z_][A-Za-z0-9_]*)/.exec(model.getLineContent(current));
+    if (match) return match[1];
+  }
+  return null;
+}
+
 const README_PATH = 'README.virtual.txt';
 const README_CONTENT = '// Select a file from the explorer to open it.\n';
 
 export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps) {
-  const { repoRef, gitRef = 'WORKTREE' } = props;
+  const { repoRef, gitRef = 'WORKTREE', treeBulkActions = [], openRequest = null, onAskAi, aiModel, onGenerateTests, viewerId = 'main' } = props;
+  const [refPin, setRefPin] = useState<ViewerRefPin>('follow');
+  const [pinnedRef, setPinnedRef] = useState('');
+  const [viewCommit, setViewCommit] = useState<string | null>(null);
+  const viewRef = refPin === 'worktree' ? 'WORKTREE' : refPin === 'ref' ? pinnedRef.trim() || gitRef : gitRef;
+  const readOnly = viewRef !== 'WORKTREE';
   const [rootEntries, setRootEntries] = useState<RepoTreeEntry[]>([]);
   const [childrenByParent, setChildrenByParent] = useState<Record<string, RepoTreeEntry[]>>({});
   const [loadingDirs, setLoadingDirs] = useState<Set<string>>(new Set());
+  const handledOpenRequest = useRef<number | null>(null);
+  const [filteredPaths, setFilteredPaths] = useState<string[] | null>(null);
+  const [ownership, setOwnership] = useState<OwnershipMap | null>(null);
+  // Status describes the worktree, so it is only shown while browsing it.
+  const gitStatus = useRepoGitDecorations(gitRef === 'WORKTREE' ? repoRef : '');
+  const filteredTree = useMemo(() => (filteredPaths ? buildTreeFromPaths(filteredPaths) : null), [filteredPaths]);
   const [busy, setBusy] = useState(false);
   const [saving, setSaving] = useState(false);
   const [opening, setOpening] = useState(false);
@@ -32,6 +270,7 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
   const [selectedPath, setSelectedPath] = useState<string | null>(null);
   const [hideBinary, setHideBinary] = useState(true);
   const [hideGitignored, setHideGitignored] = useState(true);
+  const [followSymlinks, setFollowSymlinks] = useState(false);
   const [workspaceFiles, setWorkspaceFiles] = useState<Record<string, string>>({});
   const [savedFiles, setSavedFiles] = useState<Record<string, string>>({});
   const [workspaceVersion, setWorkspaceVersion] = useState(0);
@@ -42,6 +281,31 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
   const [quickOpenIndex, setQuickOpenIndex] = useState<string[]>([]);
   const [quickOpenLoading, setQuickOpenLoading] = useState(false);
   const [quickOpenActiveIndex, setQuickOpenActiveIndex] = useState(0);
+  const [stageRange, setStageRange] = useState<{ start: number; end: number } | null>(null);
+  const [stagingLines, setStagingLines] = useState(false);
+  const [stageNotice, setStageNotice] = useState<string | null>(null);
+  const [searchOpen, setSearchOpen] = useState(false);
+  const [docRequest, setDocRequest] = useState<{ path: string; symbol: string | null } | null>(null);
+  const [fileFormat, setFileFormat] = useState<FileFormat | null>(null);
+  const [columnMode, setColumnMode] = useState(false);
+  const columnModeRef = useRef(columnMode);
+  columnModeRef.current = columnMode;
+  const [cursorCount, setCursorCount] = useState(1);
+  const [autoClose, setAutoClose] = useState(true);
+  const autoCloseRef = useRef(autoClose);
+  autoCloseRef.current = autoClose;
+  const languageHintsRef = useRef<Record<string, LanguageHint | null>>({});
+  const editorHostRef = useRef<HTMLDivElement | null>(null);
+  const ownsEditor = (editor: MonacoEditorLike) => {
+    const node = editor.getContainerDomNode?.();
+    return Boolean(node && editorHostRef.current?.contains(node));
+  };
+  const [softWrap, setSoftWrap] = useState(false);
+  const [saveHooks, setSaveHooks] = useState<SaveHookSettings | null>(null);
+  const softWrapRef = useRef(softWrap);
+  softWrapRef.current = softWrap;
+  const [eolAttributes, setEolAttributes] = useState<Record<string, 'lf' | 'crlf' | null>>({});
+  const [aiEdit, setAiEdit] = useState<(AiSelectionEditTarget & { editor: MonacoEditorLike; range: EditorRange }) | null>(null);
   const quickOpenInputRef = useRef<HTMLInputElement | null>(null);
   const [workspace, setWorkspace] = useState(
     () =>
@@ -225,6 +489,194 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     return next;
   }
 
+  const askAiRef = useRef<(editor?: MonacoEditorLike) => void>(() => undefined);
+  askAiRef.current = (editor?: MonacoEditorLike) => {
+    const snippet = selectedPath ? activeEditorSnippet(editor) : null;
+    if (!onAskAi || !selectedPath || !snippet) return;
+    onAskAi({ repoRef, path: normalizeWorkspacePath(selectedPath), kind: 'code', ...snippet });
+  };
+
+  const aiEditRef = useRef<(editor?: MonacoEditorLike) => void>(() => undefined);
+  aiEditRef.current = (editor?: MonacoEditorLike) => {
+    const target = selectedPath && !readOnly ? activeEditorEditTarget(editor) : null;
+    if (!selectedPath || !target) return;
+    setAiEdit({ ...target, path: normalizeWorkspacePath(selectedPath) });
+  };
+
+  useEffect(() => {
+    setAiEdit(null);
+  }, [selectedPath, readOnly]);
+
+  const docCommentsRef = useRef<(editor?: MonacoEditorLike, perSymbol?: boolean) => Promise<void>>(async () => undefined);
+  docCommentsRef.current = async (editor?: MonacoEditorLike, perSymbol = false) => {
+    if (!selectedPath || readOnly) return;
+    const normalizedPath = normalizeWorkspacePath(selectedPath);
+    if (!DOC_COMMENT_EXTENSIONS.test(normalizedPath)) {
+      setError('Doc comments can be generated for Rust and Python files.');
+      return;
+    }
+    const symbol = perSymbol ? functionNameAtCursor(editor) : null;
+    if (perSymbol && !symbol) {
+      setError('Place the cursor on a function signature to document it.');
+      return;
+    }
+    // The proposal is anchored to the file on disk, so unsaved edits have to land first.
+    if (dirtyPaths[normalizedPath]) await saveCurrentFile();
+    setDocRequest({ path: normalizedPath, symbol });
+  };
+
+  const generateTestsRef = useRef<(editor?: MonacoEditorLike, perSymbol?: boolean) => Promise<void>>(async () => undefined);
+  generateTestsRef.current = async (editor?: MonacoEditorLike, perSymbol = false) => {
+    if (!onGenerateTests || !selectedPath || readOnly) return;
+    const normalizedPath = normalizeWorkspacePath(selectedPath);
+    if (!TEST_GENERATION_EXTENSIONS.test(normalizedPath)) {
+      setError('Tests can be generated for Rust, Python, Go, JavaScript and TypeScript files.');
+      return;
+    }
+    const symbol = perSymbol ? functionNameAtCursor(editor) : null;
+    if (perSymbol && !symbol) {
+      setError('Place the cursor on a function signature to generate tests for it.');
+      return;
+    }
+    if (dirtyPaths[normalizedPath]) await saveCurrentFile();
+    onGenerateTests({ repoRef, path: normalizedPath, symbol });
+  };
+
+  function acceptAiEdit(replacement: string) {
+    if (!aiEdit) return;
+    const model = aiEdit.editor.getModel?.();
+    if (!model?.getValueInRange || model.getValueInRange(aiEdit.range) !== aiEdit.text) {
+      throw new Error('The selection changed while the edit was generated. Select it again and retry.');
+    }
+    aiEdit.editor.executeEdits?.('mdev.aiEdit', [{ range: aiEdit.range, text: replacement }]);
+    aiEdit.editor.focus?.();
+  }
+
+  useEffect(() => {
+    // Editors appear once the lazy component mounts, so keep looking for new ones and tag each once.
+    const timer = window.setInterval(() => {
+      for (const editor of monacoEditors()) {
+        if (!editor.addAction) continue;
+        if (!configuredEditors.has(editor) && ownsEditor(editor)) {
+          configuredEditors.add(editor);
+          editor.updateOptions?.({
+            // With Ctrl/Cmd adding cursors, Alt+drag is left free for block selection.
+            multiCursorModifier: 'ctrlCmd',
+            multiCursorPaste: 'spread',
+            columnSelection: columnModeRef.current,
+            matchBrackets: 'always',
+            bracketPairColorization: { enabled: true },
+            autoIndent: 'full',
+            ...autoCloseOptions(autoCloseRef.current),
+            // Wrapped rows are what clicks, Home/End and Up/Down move through.
+            wordWrap: softWrapRef.current ? 'on' : 'off',
+            wrappingIndent: 'same',
+          });
+          editor.addAction({
+            id: 'mdev.toggleSoftWrap',
+            label: 'Toggle soft wrap',
+            keybindings: [TOGGLE_SOFT_WRAP_KEYBINDING],
+            run: () => toggleSoftWrapRef.current(),
+          });
+          editor.addAction({
+            id: 'mdev.toggleLineComment',
+            label: 'Toggle line comment',
+            keybindings: [TOGGLE_COMMENT_KEYBINDING],
+            run: (target) => {
+              const modelPath = target.getModel?.()?.uri?.path ?? '';
+              const hint = Object.entries(languageHintsRef.current).find(([path]) => modelPath.endsWith(`/${path}`))?.[1];
+              if (hint) toggleCommentsInEditor(target, hint);
+            },
+          });
+          editor.addAction({
+            id: 'mdev.pasteAcrossCursors',
+            label: 'Paste lines across cursors',
+            keybindings: [PASTE_ACROSS_CURSORS_KEYBINDING],
+            run: (target) => void pasteAcrossCursors(target).catch((err) => setError(err instanceof Error ? err.message : String(err))),
+          });
+        }
+        if (onAskAi && !askAiEditors.has(editor)) {
+          askAiEditors.add(editor);
+          editor.addAction({
+            id: 'mdev.askAiAboutSelection',
+            label: 'Ask AI about this',
+            contextMenuGroupId: 'navigation',
+            run: (target) => askAiRef.current(target),
+          });
+        }
+        if (!aiActionEditors.has(editor)) {
+          aiActionEditors.add(editor);
+          editor.addAction({
+            id: 'mdev.editSelectionWithAi',
+            label: 'Edit selection with AI',
+            contextMenuGroupId: 'navigation',
+            run: (target) => aiEditRef.current(target),
+          });
+          editor.addAction({
+            id: 'mdev.docCommentsForFunction',
+            label: 'Generate doc comment for this function',
+            contextMenuGroupId: 'navigation',
+            run: (target) => void docCommentsRef.current(target, true),
+          });
+        }
+        if (onGenerateTests && !generateTestsEditors.has(editor)) {
+          generateTestsEditors.add(editor);
+          editor.addAction({
+            id: 'mdev.generateTestsForFunction',
+            label: 'Generate tests for this function',
+            contextMenuGroupId: 'navigation',
+            run: (target) => void generateTestsRef.current(target, true),
+          });
+        }
+      }
+    }, 1000);
+    return () => window.clearInterval(timer);
+  }, [Boolean(onAskAi), Boolean(onGenerateTests)]);
+
+  useEffect(() => {
+    for (const editor of monacoEditors().filter(ownsEditor)) editor.updateOptions?.({ columnSelection: columnMode });
+  }, [columnMode]);
+
+  useEffect(() => {
+    for (const editor of monacoEditors().filter(ownsEditor)) editor.updateOptions?.(autoCloseOptions(autoClose));
+  }, [autoClose]);
+
+  useEffect(() => {
+    void getAppSettings()
+      .then((settings) => setSoftWrap(Boolean(settings.editor?.soft_wrap_by_viewer?.[viewerId])))
+      .catch(() => undefined);
+  }, [viewerId]);
+
+  useEffect(() => {
+    for (const editor of monacoEditors().filter(ownsEditor)) editor.updateOptions?.({ wordWrap: softWrap ? 'on' : 'off' });
+  }, [softWrap]);
+
+  useEffect(() => {
+    setSaveHooks(null);
+    if (!repoRef.trim()) return;
+    void getAppSettings()
+      .then((settings) => setSaveHooks(settings.terminal?.save_hooks_by_repo?.[repoRef] ?? null))
+      .catch(() => undefined);
+  }, [repoRef]);
+
+  function toggleSaveHooks() {
+    if (!saveHooks) return;
+    const next = { ...saveHooks, enabled: !saveHooks.enabled };
+    setSaveHooks(next);
+    void patchAppSettings({ terminal: { save_hooks_by_repo: { [repoRef]: { enabled: next.enabled } } } }).catch((err) =>
+      setError(err instanceof Error ? err.message : String(err))
+    );
+  }
+
+  const toggleSoftWrapRef = useRef<() => void>(() => undefined);
+  toggleSoftWrapRef.current = () => {
+    const next = !softWrapRef.current;
+    setSoftWrap(next);
+    void patchAppSettings({ editor: { soft_wrap_by_viewer: { [viewerId]: next } } }).catch((err) =>
+      setError(err instanceof Error ? err.message : String(err))
+    );
+  };
+
   useEffect(() => {
     let cancelled = false;
 
@@ -306,9 +758,10 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     setBusy(true);
     setError(null);
     try {
-      const response = await listRepoTree(repoRef, gitRef, {
+      const response = await listRepoTree(repoRef, viewRef, {
         skipBinary: hideBinary,
         skipGitignore: hideGitignored,
+        followSymlinks,
       });
       setRootEntries(response.entries as RepoTreeEntry[]);
       setChildrenByParent({});
@@ -322,7 +775,7 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
 
   useEffect(() => {
     void loadRoot();
-  }, [repoRef, gitRef, hideBinary, hideGitignored]);
+  }, [repoRef, viewRef, hideBinary, hideGitignored, followSymlinks]);
 
   useEffect(() => {
     if (workspaceRepoRef.current === repoRef) {
@@ -355,6 +808,21 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     setSavedFiles({});
   }, [repoRef]);
 
+  useEffect(() => {
+    setOwnership(null);
+    if (!repoRef.trim()) return;
+    let cancelled = false;
+    // Folder tooltips only; a repo without history (or git errors) just shows none.
+    getOwnershipMap(repoRef)
+      .then((map) => {
+        if (!cancelled) setOwnership(map);
+      })
+      .catch(() => undefined);
+    return () => {
+      cancelled = true;
+    };
+  }, [repoRef]);
+
   useEffect(() => {
     setOpenTabs([]);
     setDirtyPaths({});
@@ -365,7 +833,7 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     setQuickOpenQuery('');
     setQuickOpenIndex([]);
     setQuickOpenActiveIndex(0);
-  }, [repoRef, gitRef, hideBinary, hideGitignored]);
+  }, [repoRef, viewRef, hideBinary, hideGitignored]);
 
   useEffect(() => {
     if (!quickOpenOpen) {
@@ -379,10 +847,11 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
 
     setLoadingDirs((prev) => new Set(prev).add(path));
     try {
-      const response = await listRepoTree(repoRef, gitRef, {
+      const response = await listRepoTree(repoRef, viewRef, {
         basePath: path,
         skipBinary: hideBinary,
         skipGitignore: hideGitignored,
+        followSymlinks,
       });
       setChildrenByParent((prev) => ({
         ...prev,
@@ -407,10 +876,11 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     setError(null);
 
     try {
-      const response = await readWorkspaceFile(repoRef, path);
+      const response = await readWorkspaceFile(repoRef, path, viewRef);
       if (requestId !== openRequestSeq.current) {
         return;
       }
+      setViewCommit(response.commit);
 
       const normalizedPath = normalizeWorkspacePath(response.path);
       const snapshotted = await snapshotCurrentEditorFiles(workspaceFiles);
@@ -439,6 +909,8 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
         ...prev,
         [normalizedPath]: normalizeEditorText(response.contents),
       }));
+      setEolAttributes((prev) => ({ ...prev, [normalizedPath]: response.eol_attribute }));
+      languageHintsRef.current[normalizedPath] = response.language_hint;
       ensureTabOpen(normalizedPath);
       markTabDirty(normalizedPath, false);
       setSelectedPath(normalizedPath);
@@ -456,6 +928,12 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     }
   }
 
+  async function openFiles(paths: string[]) {
+    for (const path of paths) {
+      await openFile(path);
+    }
+  }
+
   async function closeTab(path: string) {
     const normalizedPath = normalizeWorkspacePath(path);
     await syncCurrentEditorToState(selectedPath);
@@ -490,7 +968,7 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
 
     try {
       setQuickOpenLoading(true);
-      const response = await listRepoFiles(repoRef, gitRef, {
+      const response = await listRepoFiles(repoRef, viewRef, {
         skipBinary: hideBinary,
         skipGitignore: hideGitignored,
       });
@@ -550,14 +1028,21 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
       if (key === 'e') {
         event.preventDefault();
         void quickOpenBySearch();
+        return;
+      }
+
+      if (key === 'f') {
+        event.preventDefault();
+        setSearchOpen(true);
       }
     };
 
     window.addEventListener('keydown', handler);
     return () => window.removeEventListener('keydown', handler);
-  }, [selectedPath, quickOpenOpen, repoRef, gitRef, hideBinary, hideGitignored, quickOpenIndex, quickOpenLoading]);
+  }, [selectedPath, quickOpenOpen, repoRef, viewRef, hideBinary, hideGitignored, quickOpenIndex, quickOpenLoading]);
 
   useEffect(() => {
+    setFileFormat(null);
     if (!selectedPath) {
       return;
     }
@@ -576,9 +1061,20 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
         if (cancelled) {
           return;
         }
-        const text = normalizeEditorText(readWorkspaceText(contents));
+        const raw = readWorkspaceText(contents);
+        const text = normalizeEditorText(raw);
         const saved = normalizeEditorText(savedFiles[normalizedPath] ?? '');
         markTabDirty(normalizedPath, text !== saved);
+        setCursorCount(editorForPath(normalizedPath)?.getSelections?.()?.length || 1);
+        const format = describeFileFormat(raw);
+        setFileFormat((prev) => (
+          prev
+          && prev.lineEnding === format.lineEnding
+          && prev.indent.kind === format.indent.kind
+          && prev.indent.width === format.indent.width
+            ? prev
+            : format
+        ));
       } catch {
       }
     };
@@ -594,8 +1090,73 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     };
   }, [selectedPath, savedFiles, workspace]);
 
+  useEffect(() => {
+    if (!selectedPath || !repoRef.trim()) return;
+    const normalizedPath = normalizeWorkspacePath(selectedPath);
+    let cancelled = false;
+    let phase: 'waiting' | 'restoring' | 'tracking' = 'waiting';
+    let lastState = '';
+    let pendingState: string | null = null;
+    let saveTimer: number | undefined;
+
+    const flush = () => {
+      if (pendingState === null) return;
+      const state = JSON.parse(pendingState) as unknown;
+      pendingState = null;
+      void saveEditorState({ repo_ref: repoRef, path: normalizedPath, state }).catch(() => undefined);
+    };
+
+    // Restore once the file's model is showing (after openFile or a workspace load), then save changes debounced.
+    const interval = window.setInterval(() => {
+      const editor = monacoEditors()
+        .filter(ownsEditor)
+        .find((candidate) => candidate.getModel?.()?.uri?.path.endsWith(`/${normalizedPath}`));
+      if (!editor?.saveViewState || phase === 'restoring') return;
+      if (phase === 'waiting') {
+        phase = 'restoring';
+        void getEditorState(repoRef, normalizedPath)
+          .then((response) => {
+            if (!cancelled && response.state) editor.restoreViewState?.(response.state);
+          })
+          .catch(() => undefined)
+          .finally(() => {
+            lastState = JSON.stringify(editor.saveViewState?.() ?? null);
+            phase = 'tracking';
+          });
+        return;
+      }
+      const next = JSON.stringify(editor.saveViewState() ?? null);
+      if (next === lastState) return;
+      lastState = next;
+      pendingState = next;
+      window.clearTimeout(saveTimer);
+      saveTimer = window.setTimeout(flush, 1000);
+    }, 250);
+
+    return () => {
+      cancelled = true;
+      window.clearInterval(interval);
+      window.clearTimeout(saveTimer);
+      flush();
+    };
+  }, [selectedPath, repoRef, workspaceVersion]);
+
+  useEffect(() => {
+    if (selectedPath) {
+      void openFile(selectedPath);
+    } else {
+      setViewCommit(null);
+    }
+  }, [viewRef]);
+
+  useEffect(() => {
+    if (!openRequest || !workspace || handledOpenRequest.current === openRequest.nonce) return;
+    handledOpenRequest.current = openRequest.nonce;
+    void openFile(openRequest.path);
+  }, [openRequest?.nonce, workspace]);
+
   async function saveCurrentFile() {
-    if (!repoRef.trim() || !selectedPath) return;
+    if (!repoRef.trim() || !selectedPath || readOnly) return;
 
     const currentWorkspace = workspaceRef.current;
     if (!currentWorkspace) return;
@@ -622,6 +1183,7 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
         path: normalizedPath,
         contents: text,
       });
+      refreshRepoGitStatus(repoRef);
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
     } finally {
@@ -629,6 +1191,59 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
     }
   }
 
+  async function convertLineEndings(to: 'LF' | 'CRLF') {
+    const currentWorkspace = workspaceRef.current;
+    if (!selectedPath || readOnly || !currentWorkspace) return;
+    const normalizedPath = normalizeWorkspacePath(selectedPath);
+    try {
+      editorForPath(normalizedPath)?.getModel?.()?.pushEOL?.(to === 'CRLF' ? 1 : 0);
+      // The model only tracks one line ending, so a mixed buffer is rewritten directly as well.
+      const text = normalizeEditorText(readWorkspaceText(await Promise.resolve(currentWorkspace.fs.readFile(normalizedPath))));
+      await Promise.resolve(currentWorkspace.fs.writeFile(normalizedPath, to === 'CRLF' ? text.replace(/\n/g, '\r\n') : text));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  function reindentBuffer(from: IndentStyle, to: { kind: 'tabs' | 'spaces'; width: number }) {
+    if (!selectedPath || readOnly) return;
+    const editor = editorForPath(normalizeWorkspacePath(selectedPath));
+    const model = editor?.getModel?.();
+    if (!editor || !model?.getValue) return;
+    const lastLine = model.getLineCount();
+    const range = {
+      startLineNumber: 1,
+      startColumn: 1,
+      endLineNumber: lastLine,
+      endColumn: model.getLineMaxColumn?.(lastLine) ?? model.getLineContent(lastLine).length + 1,
+    };
+    editor.executeEdits?.('mdev.reindent', [{ range, text: reindent(model.getValue(), from, to) }]);
+    model.updateOptions?.({ insertSpaces: to.kind === 'spaces', tabSize: to.width });
+    editor.focus?.();
+  }
+
+  async function stageSelectedLines() {
+    if (!repoRef.trim() || !selectedPath || readOnly || !stageRange) return;
+    const normalizedPath = normalizeWorkspacePath(selectedPath);
+    try {
+      setStagingLines(true);
+      setError(null);
+      if (dirtyPaths[normalizedPath]) await saveCurrentFile();
+      const response = await stageReviewLines({
+        repo_ref: repoRef,
+        path: normalizedPath,
+        start_line: stageRange.start,
+        end_line: stageRange.end,
+      });
+      setStageNotice(`Staged ${response.staged_lines} changed line${response.staged_lines === 1 ? '' : 's'} from ${normalizedPath}:${stageRange.start}-${stageRange.end}.`);
+      setStageRange(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setStagingLines(false);
+    }
+  }
+
   async function handleCreateFile(parentPath: string | null) {
     if (!repoRef.trim()) return;
     const requested = window.prompt('New file path', parentPath ? `${parentPath}/new_file.txt` : 'new_file.txt');
@@ -812,6 +1427,52 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
           </ScrollArea.Autosize>
         </Stack>
       </Modal>
+      <Modal opened={!!stageRange} onClose={() => setStageRange(null)} title="Stage selection" size="sm">
+        <Stack gap="sm">
+          <Text size="sm" c="dimmed">
+            Stages only the worktree changes of {selectedPath} that fall within these lines. Unsaved edits are saved first.
+          </Text>
+          <Group grow>
+            <NumberInput
+              label="From line"
+              min={1}
+              value={stageRange?.start ?? 1}
+              onChange={(value) => setStageRange((prev) => (prev ? { ...prev, start: Math.max(1, Number(value) || 1) } : prev))}
+            />
+            <NumberInput
+              label="To line"
+              min={1}
+              value={stageRange?.end ?? 1}
+              onChange={(value) => setStageRange((prev) => (prev ? { ...prev, end: Math.max(1, Number(value) || 1) } : prev))}
+            />
+          </Group>
+          <Group justify="flex-end">
+            <Button variant="default" onClick={() => setStageRange(null)}>Cancel</Button>
+            <Button onClick={() => void stageSelectedLines()} loading={stagingLines}>Stage lines</Button>
+          </Group>
+        </Stack>
+      </Modal>
+      <DocCommentsModal
+        request={docRequest}
+        repoRef={repoRef}
+        aiModel={aiModel}
+        onClose={() => setDocRequest(null)}
+        onApplied={(path) => void openFile(path)}
+      />
+      <ProjectSearchModal
+        opened={searchOpen}
+        onClose={() => setSearchOpen(false)}
+        repoRef={repoRef}
+        scopePaths={filteredPaths}
+        readOnly={readOnly}
+        onOpenMatch={(path, line, column) => {
+          setSearchOpen(false);
+          void openFile(path).then(() => revealInEditor(normalizeWorkspacePath(path), line, column));
+        }}
+        onReplaced={(paths) => {
+          if (selectedPath && paths.includes(selectedPath)) void openFile(selectedPath);
+        }}
+      />
       <Card withBorder>
       <Stack gap="md">
         <Group justify="space-between" align="flex-start" wrap="wrap">
@@ -819,18 +1480,79 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
             <Group gap="xs">
               <Title order={4}>File editor</Title>
               <Badge variant="light">Modern Monaco</Badge>
+              <Badge variant="filled" color={readOnly ? 'violet' : 'teal'}>
+                {readOnly ? `${viewRef}${viewCommit ? ` @ ${viewCommit.slice(0, 8)}` : ''}` : 'WORKTREE'}
+              </Badge>
             </Group>
             <Text size="sm" c="dimmed">Explorer mode uses the shared tree core without fragment-selection checkboxes.</Text>
             <Text size="xs" c="dimmed">Repo: {repoRef || 'No repo selected'}</Text>
           </Stack>
-          <Button variant="default" disabled={!selectedPath} onClick={() => void saveCurrentFile()} loading={saving}>
-            Save file
-          </Button>
+          <Group gap="xs">
+            <Button variant="default" disabled={!repoRef.trim()} onClick={() => setSearchOpen(true)}>
+              Search
+            </Button>
+            <Button
+              variant="default"
+              disabled={!selectedPath || readOnly}
+              onClick={() => {
+                setStageNotice(null);
+                setStageRange(activeEditorLineRange() ?? { start: 1, end: 1 });
+              }}
+            >
+              Stage selection
+            </Button>
+            {onAskAi ? (
+              <Button variant="default" disabled={!selectedPath} onClick={() => askAiRef.current()}>
+                Ask AI
+              </Button>
+            ) : null}
+            <Button variant="default" disabled={!selectedPath || readOnly} onClick={() => aiEditRef.current()}>
+              Edit selection with AI
+            </Button>
+            <Button
+              variant="default"
+              disabled={!selectedPath || readOnly || !DOC_COMMENT_EXTENSIONS.test(selectedPath)}
+              onClick={() => void docCommentsRef.current()}
+            >
+              Document with AI
+            </Button>
+            {onGenerateTests ? (
+              <Button
+                variant="default"
+                disabled={!selectedPath || readOnly || !TEST_GENERATION_EXTENSIONS.test(selectedPath)}
+                onClick={() => void generateTestsRef.current()}
+              >
+                Generate tests
+              </Button>
+            ) : null}
+            <Button variant="default" disabled={!selectedPath || readOnly} onClick={() => void saveCurrentFile()} loading={saving}>
+              Save file
+            </Button>
+          </Group>
         </Group>
 
         <Group gap="md">
+          <SegmentedControl
+            size="xs"
+            value={refPin}
+            onChange={(value) => setRefPin(value as ViewerRefPin)}
+            data={[
+              { value: 'follow', label: 'Follow top bar' },
+              { value: 'worktree', label: 'WORKTREE' },
+              { value: 'ref', label: 'Ref / commit' },
+            ]}
+          />
+          {refPin === 'ref' ? (
+            <TextInput
+              size="xs"
+              placeholder="branch, tag or commit"
+              value={pinnedRef}
+              onChange={(event) => setPinnedRef(event.currentTarget.value)}
+            />
+          ) : null}
           <Switch label="Hide binary" checked={hideBinary} onChange={(event) => setHideBinary(event.currentTarget.checked)} />
           <Switch label="Hide gitignored" checked={hideGitignored} onChange={(event) => setHideGitignored(event.currentTarget.checked)} />
+          <Switch label="Follow symlinks" checked={followSymlinks} onChange={(event) => setFollowSymlinks(event.currentTarget.checked)} />
           <Button variant="default" size="xs" onClick={() => void loadRoot()} loading={busy}>Refresh</Button>
           {opening ? <Text size="xs" c="dimmed">Opening…</Text> : null}
         </Group>
@@ -839,7 +1561,8 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
           <Alert color="yellow">Select a workflow or provide a repo path first.</Alert>
         ) : null}
 
-        {error ? <Alert color="red">{error}</Alert> : null}
+        {error ? <ErrorPanel message={error} /> : null}
+        {stageNotice ? <Alert color="teal" withCloseButton onClose={() => setStageNotice(null)}>{stageNotice}</Alert> : null}
 
         <div
           style={{
@@ -856,33 +1579,42 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
                 <Text fw={600}>Explorer</Text>
                 {busy ? <Loader size="xs" /> : null}
               </Group>
+              <TreeFilterBar repoRef={repoRef} onResult={setFilteredPaths} />
               <RepoExplorerTree
-                rootEntries={rootEntries}
-                childrenByParent={childrenByParent}
+                rootEntries={filteredTree?.rootEntries ?? rootEntries}
+                childrenByParent={filteredTree?.childrenByParent ?? childrenByParent}
                 loadingDirs={loadingDirs}
+                expandAll={!!filteredTree}
                 activePath={selectedPath}
                 onLoadDir={(path) => void loadDir(path)}
                 onOpenFile={(path) => void openFile(path)}
                 onCreateFile={handleCreateFile}
                 onCreateFolder={handleCreateFolder}
                 onDeletePath={handleDeletePath}
+                bulkActions={[{ label: 'Open all', onRun: openFiles }, ...treeBulkActions]}
+                ownership={ownership}
+                gitStatus={gitStatus}
                 height={560}
               />
             </Stack>
           </Card>
 
+          <TreeDropTarget label="Drop to open" disabled={!repoRef.trim()} onDropPaths={(paths) => void openFiles(paths)}>
           <Card withBorder p={0} style={{ minHeight: 620, overflow: 'hidden' }}>
             <Stack gap={0} h="100%" style={{ minHeight: 620 }}>
               <Stack gap={0}>
                 <Group justify="space-between" p="sm" style={{ borderBottom: '1px solid rgba(255,255,255,0.08)' }}>
                   <div>
                     <Text fw={600}>{selectedPath ?? README_PATH}</Text>
-                    <Text size="xs" c="dimmed">Alt+S save · Alt+W close tab · Alt+E quick open</Text>
+                    <Text size="xs" c="dimmed">Alt+S save · Alt+W close tab · Alt+E quick open · Alt+F search</Text>
                   </div>
                 </Group>
                 <Group gap="xs" p="xs" style={{ borderBottom: '1px solid rgba(255,255,255,0.08)', overflowX: 'auto', flexWrap: 'nowrap' }}>
                   {openTabs.length === 0 ? (
-                    <Text size="xs" c="dimmed">No open tabs</Text>
+                    <Group gap="xs">
+                      <Text size="xs" c="dimmed">No open tabs</Text>
+                      <Button size="compact-xs" variant="light" onClick={() => void quickOpenBySearch()} disabled={!repoRef}>Quick open</Button>
+                    </Group>
                   ) : (
                     openTabs.map((tabPath) => {
                       const isActive = tabPath === selectedPath;
@@ -909,6 +1641,7 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
                           <Group gap={6} wrap="nowrap">
                             {isDirty ? <Text size="sm" c="yellow">●</Text> : null}
                             <Text size="sm">{label}</Text>
+                            <GitStatusBadge decoration={gitStatus.files.get(tabPath)} />
                           </Group>
                           <ActionIcon
                             size="sm"
@@ -926,15 +1659,56 @@ export function RepoMonacoFileEditorPanel(props: RepoMonacoFileEditorPanelProps)
                   )}
                 </Group>
               </Stack>
-              <div style={{ flex: 1, minHeight: 0, overflow: 'hidden' }}>
+              {aiEdit ? (
+                <AiSelectionEditBar
+                  key={`${aiEdit.path}:${aiEdit.startLine}:${aiEdit.endLine}:${aiEdit.text.length}`}
+                  target={aiEdit}
+                  aiModel={aiModel}
+                  onAccept={acceptAiEdit}
+                  onClose={() => setAiEdit(null)}
+                />
+              ) : null}
+              <div ref={editorHostRef} style={{ flex: 1, minHeight: 0, overflow: 'hidden' }}>
                 <monaco-editor
                   key={`workspace-${workspaceVersion}`}
                   theme="one-dark-pro"
                   style={{ display: 'block', width: '100%', height: '620px' }}
                 />
               </div>
+              {selectedPath ? (
+                <FileFormatStatusBar
+                  format={fileFormat}
+                  eolAttribute={eolAttributes[normalizeWorkspacePath(selectedPath)] ?? null}
+                  readOnly={readOnly}
+                  onConvertLineEndings={(to) => void convertLineEndings(to)}
+                  onReindent={(to) => fileFormat && reindentBuffer(fileFormat.indent, to)}
+                >
+                  <ColumnSelectionToggle enabled={columnMode} cursorCount={cursorCount} onToggle={() => setColumnMode((prev) => !prev)} />
+                  <StatusBarToggle
+                    label="Auto-close"
+                    enabled={autoClose}
+                    hint="Insert the closing bracket or quote as you type the opening one"
+                    onToggle={() => setAutoClose((prev) => !prev)}
+                  />
+                  <StatusBarToggle
+                    label="Soft wrap"
+                    enabled={softWrap}
+                    hint="Wrap long lines to the editor width (Alt+Z); remembered for this viewer"
+                    onToggle={() => toggleSoftWrapRef.current()}
+                  />
+                  {saveHooks?.rules?.length ? (
+                    <StatusBarToggle
+                      label="Run on save"
+                      enabled={saveHooks.enabled}
+                      hint={`Saving files that match ${saveHooks.rules.map((rule) => rule.glob).join(', ')} runs their commands in the terminal panes`}
+                      onToggle={toggleSaveHooks}
+                    />
+                  ) : null}
+                </FileFormatStatusBar>
+              ) : null}
             </Stack>
           </Card>
+          </TreeDropTarget>
         </div>
       </Stack>
     </Card>
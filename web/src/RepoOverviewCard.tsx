@@ -0,0 +1,116 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, CopyButton, Group, ScrollArea, Stack, Text, TextInput, Title } from '@mantine/core';
+import { IconSparkles } from '@tabler/icons-react';
+import { generateRepoOverview, saveRepoOverview, type AiActionModel, type RepoOverview } from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { MarkdownMessage } from './MarkdownMessage';
+
+type RepoOverviewCardProps = {
+  repoRef: string;
+  aiModel?: AiActionModel;
+  onOpenFile?: (path: string) => void;
+};
+
+const DEFAULT_REPORT_PATH = 'docs/OVERVIEW.md';
+
+/** One-click onboarding report: analysis stats, layout, entry points, commands and a model-written architecture summary. */
+export function RepoOverviewCard(props: RepoOverviewCardProps) {
+  const { repoRef, aiModel, onOpenFile } = props;
+  const [overview, setOverview] = useState<RepoOverview | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [saving, setSaving] = useState<'repo' | 'app_data' | null>(null);
+  const [reportPath, setReportPath] = useState(DEFAULT_REPORT_PATH);
+  const [savedTo, setSavedTo] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setOverview(null);
+    setSavedTo(null);
+    setError(null);
+  }, [repoRef]);
+
+  async function generate() {
+    setBusy(true);
+    setError(null);
+    setSavedTo(null);
+    try {
+      setOverview(await generateRepoOverview({ repo_ref: repoRef, ...aiModel }));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function save(destination: 'repo' | 'app_data') {
+    if (!overview) return;
+    setSaving(destination);
+    setError(null);
+    try {
+      const result = await saveRepoOverview({
+        repo_ref: repoRef,
+        markdown: overview.markdown,
+        destination,
+        path: destination === 'repo' ? reportPath.trim() || DEFAULT_REPORT_PATH : null
+      });
+      setSavedTo(result.path);
+      if (destination === 'repo') onOpenFile?.(reportPath.trim() || DEFAULT_REPORT_PATH);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setSaving(null);
+    }
+  }
+
+  return (
+    <Card withBorder>
+      <Stack gap="md">
+        <Group justify="space-between" align="flex-start" wrap="wrap">
+          <Stack gap={2}>
+            <Group gap="xs">
+              <Title order={4}>Repo overview</Title>
+              {overview ? <Badge size="xs" variant="light">{overview.model}</Badge> : null}
+            </Group>
+            <Text size="sm" c="dimmed">An onboarding report combining these stats, the layout, entry points and build commands with an AI-written architecture summary.</Text>
+          </Stack>
+          <Button
+            size="xs"
+            variant="default"
+            leftSection={<IconSparkles size={14} />}
+            loading={busy}
+            disabled={!repoRef.trim()}
+            onClick={() => void generate()}
+          >
+            {overview ? 'Regenerate' : 'Generate repo overview'}
+          </Button>
+        </Group>
+
+        {error ? <ErrorPanel message={error} /> : null}
+        {overview ? (
+          <>
+            <ScrollArea.Autosize mah={480} type="auto">
+              <MarkdownMessage text={overview.markdown} />
+            </ScrollArea.Autosize>
+            <Group gap="xs" align="flex-end" wrap="wrap">
+              <TextInput
+                size="xs"
+                label="Path in repository"
+                value={reportPath}
+                onChange={(event) => setReportPath(event.currentTarget.value)}
+                style={{ flex: 1, minWidth: 220 }}
+              />
+              <Button size="xs" loading={saving === 'repo'} onClick={() => void save('repo')}>Save to repo</Button>
+              <Button size="xs" variant="default" loading={saving === 'app_data'} onClick={() => void save('app_data')}>Save to app data</Button>
+              <CopyButton value={overview.markdown}>
+                {({ copied, copy }) => (
+                  <Button size="xs" variant="default" onClick={copy}>{copied ? 'Copied' : 'Copy Markdown'}</Button>
+                )}
+              </CopyButton>
+            </Group>
+            {savedTo ? <Text size="xs" c="dimmed">Saved to {savedTo}</Text> : null}
+          </>
+        ) : null}
+      </Stack>
+    </Card>
+  );
+}
@@ -1,12 +1,27 @@
-import { useMemo, useState } from 'react';
-import { ActionIcon, Box, Button, Checkbox, Group, Loader, ScrollArea, Stack, Text } from '@mantine/core';
-import { IconChevronDown, IconChevronRight, IconFile, IconFolder, IconFolderPlus, IconPlus, IconTrash } from '@tabler/icons-react';
+import { useEffect, useMemo, useState, type DragEvent, type MouseEvent } from 'react';
+import { ActionIcon, Badge, Box, Button, Checkbox, Group, Loader, ScrollArea, Stack, Text, Tooltip } from '@mantine/core';
+import { IconBrandGit, IconChevronDown, IconChevronRight, IconFile, IconFolder, IconFolderPlus, IconLink, IconPlus, IconTerminal2, IconTrash } from '@tabler/icons-react';
+import { writeDraggedPaths } from './TreeDropTarget';
+import { treeItemProps } from './accessibility';
+import type { OwnershipMap } from './api';
+import { GitStatusBadge, type RepoGitDecorations } from './gitFileStatus';
 
 export type RepoTreeEntry = {
   name: string;
   path: string;
   kind: 'file' | 'dir';
   has_children: boolean;
+  /** Symlinks and submodule gitlinks; absent for ordinary entries. */
+  special?: 'symlink' | 'submodule';
+  link_target?: string;
+  /** The link dangles or points outside the repo and is never followed. */
+  link_outside?: boolean;
+  executable?: boolean;
+};
+
+export type TreeBulkAction = {
+  label: string;
+  onRun: (paths: string[]) => void | Promise<void>;
 };
 
 type RepoTreeCoreProps = {
@@ -26,6 +41,10 @@ type RepoTreeCoreProps = {
   onCreateFile?: (parentPath: string | null) => void;
   onCreateFolder?: (parentPath: string | null) => void;
   onDeletePath?: (path: string) => void;
+  bulkActions?: TreeBulkAction[];
+  expandAll?: boolean;
+  ownership?: OwnershipMap | null;
+  gitStatus?: RepoGitDecorations | null;
 };
 
 type RepoFragmentTreeProps = {
@@ -39,6 +58,8 @@ type RepoFragmentTreeProps = {
   onToggleDir: (entry: RepoTreeEntry, checked: boolean) => void;
   onSetPaths: (paths: string[], checked: boolean) => void;
   height?: number;
+  /** Git status letters next to changed files, dots on folders containing them. */
+  gitStatus?: RepoGitDecorations | null;
 };
 
 type RepoExplorerTreeProps = {
@@ -51,7 +72,12 @@ type RepoExplorerTreeProps = {
   onCreateFile?: (parentPath: string | null) => void;
   onCreateFolder?: (parentPath: string | null) => void;
   onDeletePath?: (path: string) => void;
+  bulkActions?: TreeBulkAction[];
+  expandAll?: boolean;
   height?: number;
+  /** Top committers per directory, shown when hovering a folder. */
+  ownership?: OwnershipMap | null;
+  gitStatus?: RepoGitDecorations | null;
 };
 
 export function RepoTree(props: RepoFragmentTreeProps) {
@@ -68,6 +94,7 @@ export function RepoTree(props: RepoFragmentTreeProps) {
       onToggleDir={props.onToggleDir}
       onSetPaths={props.onSetPaths}
       height={props.height}
+      gitStatus={props.gitStatus}
     />
   );
 }
@@ -89,7 +116,11 @@ export function RepoExplorerTree(props: RepoExplorerTreeProps) {
       onCreateFile={props.onCreateFile}
       onCreateFolder={props.onCreateFolder}
       onDeletePath={props.onDeletePath}
+      bulkActions={props.bulkActions}
+      expandAll={props.expandAll}
       height={props.height}
+      ownership={props.ownership}
+      gitStatus={props.gitStatus}
     />
   );
 }
@@ -109,6 +140,45 @@ export function collectLoadedFilePaths(parentPath: string, childrenByParent: Rec
   return out;
 }
 
+/** Builds a fully loaded tree from a flat list of file paths, e.g. filter results. */
+export function buildTreeFromPaths(paths: string[]) {
+  const childrenByParent: Record<string, RepoTreeEntry[]> = {};
+  const seen = new Set<string>();
+  const add = (parent: string, entry: RepoTreeEntry) => {
+    if (seen.has(entry.path)) return;
+    seen.add(entry.path);
+    (childrenByParent[parent] ??= []).push(entry);
+  };
+
+  for (const path of paths) {
+    const parts = path.split('/');
+    parts.forEach((name, index) => {
+      const parent = parts.slice(0, index).join('/');
+      const current = parts.slice(0, index + 1).join('/');
+      const isFile = index === parts.length - 1;
+      add(parent, { name, path: current, kind: isFile ? 'file' : 'dir', has_children: !isFile });
+    });
+  }
+
+  for (const entries of Object.values(childrenByParent)) {
+    entries.sort((a, b) => (a.kind !== b.kind ? (a.kind === 'dir' ? -1 : 1) : a.name.localeCompare(b.name)));
+  }
+
+  return { rootEntries: childrenByParent[''] ?? [], childrenByParent };
+}
+
+function collectVisibleFilePaths(entries: RepoTreeEntry[], expanded: Set<string>, childrenByParent: Record<string, RepoTreeEntry[]>): string[] {
+  const out: string[] = [];
+  for (const entry of entries) {
+    if (entry.kind === 'file') {
+      out.push(entry.path);
+    } else if (expanded.has(entry.path)) {
+      out.push(...collectVisibleFilePaths(childrenByParent[entry.path] ?? [], expanded, childrenByParent));
+    }
+  }
+  return out;
+}
+
 function RepoTreeCore({
   rootEntries,
   childrenByParent,
@@ -126,9 +196,55 @@ function RepoTreeCore({
   onCreateFile,
   onCreateFolder,
   onDeletePath,
+  bulkActions = [],
+  expandAll = false,
+  ownership = null,
+  gitStatus = null,
 }: RepoTreeCoreProps) {
   const [expanded, setExpanded] = useState<Set<string>>(new Set());
 
+  useEffect(() => {
+    if (expandAll) setExpanded(new Set(Object.keys(childrenByParent).filter(Boolean)));
+  }, [expandAll, childrenByParent]);
+  const [multiSelected, setMultiSelected] = useState<Set<string>>(new Set());
+  const [selectionAnchor, setSelectionAnchor] = useState<string | null>(null);
+  const [bulkBusy, setBulkBusy] = useState(false);
+  const rowSelection = rowMode === 'explorer' ? multiSelected : selectedPaths;
+
+  const selectFile = (path: string, event: MouseEvent) => {
+    if (event.shiftKey && selectionAnchor) {
+      const visible = collectVisibleFilePaths(rootEntries, expanded, childrenByParent);
+      const from = visible.indexOf(selectionAnchor);
+      const to = visible.indexOf(path);
+      if (from >= 0 && to >= 0) {
+        setMultiSelected(new Set(visible.slice(Math.min(from, to), Math.max(from, to) + 1)));
+        return;
+      }
+    }
+    if (event.ctrlKey || event.metaKey) {
+      setMultiSelected((prev) => {
+        const next = new Set(prev);
+        if (next.has(path)) next.delete(path);
+        else next.add(path);
+        return next;
+      });
+      setSelectionAnchor(path);
+      return;
+    }
+    setMultiSelected(new Set([path]));
+    setSelectionAnchor(path);
+    onOpenFile?.(path);
+  };
+
+  const runBulkAction = async (action: TreeBulkAction) => {
+    setBulkBusy(true);
+    try {
+      await action.onRun(Array.from(multiSelected));
+    } finally {
+      setBulkBusy(false);
+    }
+  };
+
   const toggleExpanded = (entry: RepoTreeEntry) => {
     setExpanded((prev) => {
       const next = new Set(prev);
@@ -148,20 +264,38 @@ function RepoTreeCore({
     <Group justify="space-between" mb="xs">
       <Text size="sm" fw={600}>Repository</Text>
       <Group gap={4}>
-        <ActionIcon variant="subtle" size="sm" onClick={() => onCreateFile?.(null)} disabled={!onCreateFile}>
+        <ActionIcon variant="subtle" size="sm" aria-label="New file at repository root" onClick={() => onCreateFile?.(null)} disabled={!onCreateFile}>
           <IconPlus size={14} />
         </ActionIcon>
-        <ActionIcon variant="subtle" size="sm" onClick={() => onCreateFolder?.(null)} disabled={!onCreateFolder}>
+        <ActionIcon variant="subtle" size="sm" aria-label="New folder at repository root" onClick={() => onCreateFolder?.(null)} disabled={!onCreateFolder}>
           <IconFolderPlus size={14} />
         </ActionIcon>
       </Group>
     </Group>
   ) : null;
 
+  const bulkBar = rowMode === 'explorer' && multiSelected.size > 1 ? (
+    <Group gap={4} mb="xs" wrap="wrap">
+      <Badge variant="light">{multiSelected.size} selected</Badge>
+      {bulkActions.map((action) => (
+        <Button key={action.label} size="compact-xs" variant="light" disabled={bulkBusy} onClick={() => void runBulkAction(action)}>
+          {action.label}
+        </Button>
+      ))}
+      <Button size="compact-xs" variant="light" onClick={() => void navigator.clipboard.writeText(Array.from(multiSelected).join('\n'))}>
+        Copy paths
+      </Button>
+      <Button size="compact-xs" variant="subtle" color="gray" onClick={() => setMultiSelected(new Set())}>
+        Clear
+      </Button>
+    </Group>
+  ) : null;
+
   return (
     <ScrollArea h={height} offsetScrollbars>
-      <Stack gap={2}>
+      <Stack gap={2} role={rowMode === 'explorer' ? 'tree' : undefined} aria-label="Repository files">
         {toolbar}
+        {bulkBar}
         {rootEntries.map((entry) => (
           <RepoTreeRow
             key={entry.path}
@@ -171,10 +305,11 @@ function RepoTreeCore({
             childrenByParent={childrenByParent}
             loadingDirs={loadingDirs}
             rowMode={rowMode}
-            selectedPaths={selectedPaths}
+            selectedPaths={rowSelection}
             selectedDirs={selectedDirs}
             activePath={activePath}
             onToggleExpanded={toggleExpanded}
+            onSelectFile={rowMode === 'explorer' ? selectFile : undefined}
             onToggleFile={onToggleFile}
             onToggleDir={onToggleDir}
             onSetPaths={onSetPaths}
@@ -183,6 +318,8 @@ function RepoTreeCore({
             onCreateFile={onCreateFile}
             onCreateFolder={onCreateFolder}
             onDeletePath={onDeletePath}
+            ownership={ownership}
+            gitStatus={gitStatus}
           />
         ))}
       </Stack>
@@ -201,6 +338,7 @@ type RepoTreeRowProps = {
   selectedDirs: Set<string>;
   activePath: string | null;
   onToggleExpanded: (entry: RepoTreeEntry) => void;
+  onSelectFile?: (path: string, event: MouseEvent) => void;
   onToggleFile?: (path: string) => void;
   onToggleDir?: (entry: RepoTreeEntry, checked: boolean) => void;
   onSetPaths?: (paths: string[], checked: boolean) => void;
@@ -209,8 +347,56 @@ type RepoTreeRowProps = {
   onCreateFile?: (parentPath: string | null) => void;
   onCreateFolder?: (parentPath: string | null) => void;
   onDeletePath?: (path: string) => void;
+  ownership: OwnershipMap | null;
+  gitStatus: RepoGitDecorations | null;
 };
 
+function ownersLabel(ownership: OwnershipMap, path: string) {
+  const owners = ownership.directories[path];
+  if (!owners?.length) return null;
+  return (
+    <Stack gap={0}>
+      <Text size="xs" fw={600}>Top committers, last {ownership.window_days} days</Text>
+      {owners.map((owner) => (
+        <Text key={owner.email} size="xs">
+          {owner.name} · {owner.commits} commit{owner.commits === 1 ? '' : 's'}
+        </Text>
+      ))}
+    </Stack>
+  );
+}
+
+/** Folder or file icon, swapped for symlinks, submodules and executables, with a tooltip saying which. */
+function EntryIcon({ entry }: { entry: RepoTreeEntry }) {
+  if (entry.special === 'submodule') {
+    return (
+      <Tooltip label="Submodule (contents belong to another repository)">
+        <IconBrandGit size={14} />
+      </Tooltip>
+    );
+  }
+  if (entry.special === 'symlink') {
+    return (
+      <Tooltip label={`Symlink to ${entry.link_target ?? '?'}${entry.link_outside ? ' (outside the repository or missing; not followed)' : ''}`}>
+        <IconLink size={14} color={entry.link_outside ? 'var(--mantine-color-red-5)' : undefined} />
+      </Tooltip>
+    );
+  }
+  if (entry.executable) {
+    return (
+      <Tooltip label="Executable">
+        <IconTerminal2 size={14} />
+      </Tooltip>
+    );
+  }
+  return entry.kind === 'file' ? <IconFile size={14} /> : <IconFolder size={14} />;
+}
+
+function LinkTarget({ entry }: { entry: RepoTreeEntry }) {
+  if (entry.special !== 'symlink' || !entry.link_target) return null;
+  return <Text size="xs" c={entry.link_outside ? 'red' : 'dimmed'} ff="monospace" truncate style={{ flexShrink: 1 }}>→ {entry.link_target}</Text>;
+}
+
 function RepoTreeRow({
   entry,
   depth,
@@ -222,6 +408,7 @@ function RepoTreeRow({
   selectedDirs,
   activePath,
   onToggleExpanded,
+  onSelectFile,
   onToggleFile,
   onToggleDir,
   onSetPaths,
@@ -230,39 +417,58 @@ function RepoTreeRow({
   onCreateFile,
   onCreateFolder,
   onDeletePath,
+  ownership,
+  gitStatus,
 }: RepoTreeRowProps) {
   const isExpanded = expanded.has(entry.path);
   const isFile = entry.kind === 'file';
   const childEntries = childrenByParent[entry.path] ?? [];
   const descendantFiles = useMemo(() => collectLoadedFilePaths(entry.path, childrenByParent), [entry.path, childrenByParent]);
+  const dragProps = {
+    draggable: true,
+    onDragStart: (event: DragEvent) => {
+      const paths = selectedPaths.has(entry.path) ? Array.from(selectedPaths) : [entry.path];
+      writeDraggedPaths(event, paths);
+    },
+  };
 
   if (isFile) {
     if (rowMode === 'explorer') {
       const isActive = activePath === entry.path;
+      const isSelected = selectedPaths.has(entry.path);
       return (
         <Group
           gap={6}
           wrap="nowrap"
           justify="space-between"
+          {...dragProps}
           style={{
             paddingLeft: depth * 16,
             borderRadius: 6,
-            background: isActive ? 'rgba(76, 110, 245, 0.18)' : 'transparent',
+            background: isActive ? 'rgba(76, 110, 245, 0.18)' : isSelected ? 'rgba(76, 110, 245, 0.10)' : 'transparent',
           }}
         >
           <Group
             gap={6}
             wrap="nowrap"
-            style={{ flex: 1, minWidth: 0, cursor: 'pointer', padding: '4px 6px' }}
-            onClick={() => onOpenFile?.(entry.path)}
+            style={{ flex: 1, minWidth: 0, cursor: 'pointer', padding: '4px 6px', userSelect: 'none' }}
+            onClick={(event) => (onSelectFile ? onSelectFile(entry.path, event) : onOpenFile?.(entry.path))}
+            {...treeItemProps({
+              level: depth + 1,
+              label: entry.name,
+              selected: isActive || isSelected,
+              onActivate: (event) => (onSelectFile ? onSelectFile(entry.path, event as unknown as MouseEvent) : onOpenFile?.(entry.path)),
+            })}
           >
-            <ActionIcon variant="subtle" size="sm" disabled>
-              <IconFile size={14} />
+            <ActionIcon variant="subtle" size="sm" disabled aria-hidden>
+              <EntryIcon entry={entry} />
             </ActionIcon>
             <Text size="sm" ff="monospace" truncate>{entry.name}</Text>
+            <LinkTarget entry={entry} />
+            <GitStatusBadge decoration={gitStatus?.files.get(entry.path)} />
           </Group>
           <Group gap={2} wrap="nowrap">
-            <ActionIcon variant="subtle" size="sm" onClick={() => onDeletePath?.(entry.path)} disabled={!onDeletePath}>
+            <ActionIcon variant="subtle" size="sm" aria-label={`Delete ${entry.name}`} onClick={() => onDeletePath?.(entry.path)} disabled={!onDeletePath}>
               <IconTrash size={14} />
             </ActionIcon>
           </Group>
@@ -271,14 +477,20 @@ function RepoTreeRow({
     }
 
     return (
-      <Group gap={6} wrap="nowrap" style={{ paddingLeft: depth * 16 }}>
+      <Group gap={6} wrap="nowrap" {...dragProps} style={{ paddingLeft: depth * 16 }}>
         <ActionIcon variant="subtle" size="sm" disabled>
-          <IconFile size={14} />
+          <EntryIcon entry={entry} />
         </ActionIcon>
         <Checkbox
           checked={selectedPaths.has(entry.path)}
           onChange={() => onToggleFile?.(entry.path)}
-          label={<Text size="sm" ff="monospace">{entry.name}</Text>}
+          label={
+            <Group gap={6} wrap="nowrap">
+              <Text size="sm" ff="monospace">{entry.name}</Text>
+              <LinkTarget entry={entry} />
+              <GitStatusBadge decoration={gitStatus?.files.get(entry.path)} />
+            </Group>
+          }
         />
       </Group>
     );
@@ -302,7 +514,7 @@ function RepoTreeRow({
           }}
         >
           <Group gap={6} wrap="nowrap" style={{ flex: 1, minWidth: 0 }}>
-            <ActionIcon variant="subtle" size="sm" onClick={() => onToggleExpanded(entry)}>
+            <ActionIcon variant="subtle" size="sm" tabIndex={-1} aria-hidden onClick={() => onToggleExpanded(entry)}>
               {isExpanded ? <IconChevronDown size={14} /> : <IconChevronRight size={14} />}
             </ActionIcon>
             <Group
@@ -310,19 +522,31 @@ function RepoTreeRow({
               wrap="nowrap"
               style={{ flex: 1, minWidth: 0, cursor: 'pointer', padding: '4px 0' }}
               onClick={() => onToggleExpanded(entry)}
+              {...treeItemProps({
+                level: depth + 1,
+                label: `${entry.name} folder`,
+                selected: isDirActive,
+                expanded: isExpanded,
+                onActivate: () => onToggleExpanded(entry),
+                onToggle: () => onToggleExpanded(entry),
+              })}
             >
-              <IconFolder size={14} />
-              <Text size="sm" fw={600} truncate>{entry.name}</Text>
+              <EntryIcon entry={entry} />
+              <Tooltip label={ownership ? ownersLabel(ownership, entry.path) : null} disabled={!ownership?.directories[entry.path]?.length} openDelay={400} position="right" withinPortal>
+                <Text size="sm" fw={600} truncate>{entry.name}</Text>
+              </Tooltip>
+              <LinkTarget entry={entry} />
+              <GitStatusBadge decoration={gitStatus?.dirs.get(entry.path)} dir />
             </Group>
           </Group>
           <Group gap={2} wrap="nowrap">
-            <ActionIcon variant="subtle" size="sm" onClick={() => onCreateFile?.(entry.path)} disabled={!onCreateFile}>
+            <ActionIcon variant="subtle" size="sm" aria-label={`New file in ${entry.name}`} onClick={() => onCreateFile?.(entry.path)} disabled={!onCreateFile}>
               <IconPlus size={14} />
             </ActionIcon>
-            <ActionIcon variant="subtle" size="sm" onClick={() => onCreateFolder?.(entry.path)} disabled={!onCreateFolder}>
+            <ActionIcon variant="subtle" size="sm" aria-label={`New folder in ${entry.name}`} onClick={() => onCreateFolder?.(entry.path)} disabled={!onCreateFolder}>
               <IconFolderPlus size={14} />
             </ActionIcon>
-            <ActionIcon variant="subtle" size="sm" onClick={() => onDeletePath?.(entry.path)} disabled={!onDeletePath}>
+            <ActionIcon variant="subtle" size="sm" aria-label={`Delete ${entry.name}`} onClick={() => onDeletePath?.(entry.path)} disabled={!onDeletePath}>
               <IconTrash size={14} />
             </ActionIcon>
           </Group>
@@ -348,6 +572,7 @@ function RepoTreeRow({
             selectedDirs={selectedDirs}
             activePath={activePath}
             onToggleExpanded={onToggleExpanded}
+            onSelectFile={onSelectFile}
             onToggleFile={onToggleFile}
             onToggleDir={onToggleDir}
             onSetPaths={onSetPaths}
@@ -356,6 +581,8 @@ function RepoTreeRow({
             onCreateFile={onCreateFile}
             onCreateFolder={onCreateFolder}
             onDeletePath={onDeletePath}
+            ownership={ownership}
+            gitStatus={gitStatus}
           />
         ))}
       </>
@@ -365,7 +592,13 @@ function RepoTreeRow({
   return (
     <>
       <Group gap={6} wrap="nowrap" style={{ paddingLeft: depth * 16 }}>
-        <ActionIcon variant="subtle" size="sm" onClick={() => onToggleExpanded(entry)}>
+        <ActionIcon
+          variant="subtle"
+          size="sm"
+          aria-label={`${isExpanded ? 'Collapse' : 'Expand'} ${entry.name}`}
+          aria-expanded={isExpanded}
+          onClick={() => onToggleExpanded(entry)}
+        >
           {isExpanded ? <IconChevronDown size={14} /> : <IconChevronRight size={14} />}
         </ActionIcon>
 
@@ -375,8 +608,10 @@ function RepoTreeRow({
           onChange={(event) => onToggleDir?.(entry, event.currentTarget.checked)}
           label={
             <Group gap={6} wrap="nowrap">
-              <IconFolder size={14} />
+              <EntryIcon entry={entry} />
               <Text size="sm" fw={600}>{entry.name}</Text>
+              <LinkTarget entry={entry} />
+              <GitStatusBadge decoration={gitStatus?.dirs.get(entry.path)} dir />
               {descendantFiles.length > 0 ? <Text size="xs" c="dimmed">({descendantFiles.length})</Text> : null}
             </Group>
           }
@@ -403,6 +638,7 @@ function RepoTreeRow({
           selectedDirs={selectedDirs}
           activePath={activePath}
           onToggleExpanded={onToggleExpanded}
+          onSelectFile={onSelectFile}
           onToggleFile={onToggleFile}
           onToggleDir={onToggleDir}
           onSetPaths={onSetPaths}
@@ -411,6 +647,8 @@ function RepoTreeRow({
           onCreateFile={onCreateFile}
           onCreateFolder={onCreateFolder}
           onDeletePath={onDeletePath}
+          ownership={ownership}
+          gitStatus={gitStatus}
         />
       ))}
     </>
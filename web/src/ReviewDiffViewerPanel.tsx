@@ -1,4 +1,4 @@
-import { useEffect, useMemo, useRef, useState } from 'react';
+import { useEffect, useMemo, useRef, useState, type ReactNode } from 'react';
 import {
   Alert,
   Badge,
@@ -11,27 +11,43 @@ import {
   Loader,
   Modal,
   NumberInput,
+  PasswordInput,
   ScrollArea,
   SegmentedControl,
   Stack,
   Text,
+  TextInput,
   Tooltip,
 } from '@mantine/core';
-import { parsePatchFiles, type FileDiffMetadata } from '@pierre/diffs';
+import { IconChevronDown, IconChevronRight, IconFolder } from '@tabler/icons-react';
+import type { FileDiffMetadata } from '@pierre/diffs';
 import { FileDiff, PatchDiff, Virtualizer } from '@pierre/diffs/react';
 import {
   getReviewDiff,
   getReviewDiffManifest,
   getReviewFilePatch,
   getReviewStatus,
+  runReviewRemoteAction,
   stageReviewDiff,
+  summarizeDiffWithAi,
   unstageReviewDiff,
+  type AiActionModel,
+  type DiffWhitespaceMode,
   type ReviewDiffManifestFileEntry,
   type ReviewDiffManifestResponse,
   type ReviewDiffResponse,
   type ReviewDiffScope,
+  type GitRemoteAction,
+  type GitRemoteResult,
   type ReviewStatusFileEntry,
 } from './api';
+import { cachedPatchFiles } from './sharedRenderCache';
+import { DiscardChangesModal } from './DiscardChangesModal';
+import { PatchScreenReaderSummary } from './SafePatchDiff';
+import { AskAiHunkMenu, type AskAiHandler } from './AskAi';
+import { AiSummaryButton, AiSummaryCard, useAiSummary } from './AiSummary';
+import { PatchWhitespaceIssues, WhitespaceModeSelect, WhitespaceOnlyBadge } from './DiffWhitespace';
+import { publishRepoGitStatus } from './gitFileStatus';
 
 export type ReviewSourceControlState = {
   selected_scope: ReviewDiffScope;
@@ -40,13 +56,30 @@ export type ReviewSourceControlState = {
   only_changes: boolean;
   context_lines: number;
   whole_file: boolean;
+  whitespace_mode: DiffWhitespaceMode;
+  sort: SourceControlSort;
+  layout: SourceControlLayout;
 };
 
+/** Order of the Staged/Unstaged lists: by path, by lines changed (largest first), or grouped by status code. */
+export type SourceControlSort = 'path' | 'churn' | 'status';
+
+function parseSourceControlSort(value: unknown): SourceControlSort {
+  return value === 'churn' || value === 'status' ? value : 'path';
+}
+
+/** Flat file list, or files grouped under collapsible directories. */
+export type SourceControlLayout = 'flat' | 'tree';
+
 type ReviewDiffViewerPanelProps = {
   repoRef: string;
   state: ReviewSourceControlState;
   onPersistState: (next: ReviewSourceControlState) => Promise<void>;
   forceViewerOpen?: boolean;
+  /** Adds "Ask AI about this" to the right-click menu of rendered hunks. */
+  onAskAi?: AskAiHandler;
+  /** Model used by "Summarize pending changes"; defaults to the inference defaults. */
+  aiModel?: AiActionModel;
 };
 
 const MIN_SIDEBAR_WIDTH = 280;
@@ -89,6 +122,124 @@ function statusCode(file: ReviewStatusFileEntry): string {
   return code || 'M';
 }
 
+function sortFiles(files: ReviewStatusFileEntry[], sort: SourceControlSort): ReviewStatusFileEntry[] {
+  if (sort === 'path') {
+    return files;
+  }
+  return [...files].sort((a, b) => {
+    const primary = sort === 'churn'
+      ? (b.additions + b.deletions) - (a.additions + a.deletions)
+      : statusCode(a).localeCompare(statusCode(b));
+    return primary || a.path.localeCompare(b.path);
+  });
+}
+
+type FileTreeDir = {
+  name: string;
+  path: string;
+  dirs: FileTreeDir[];
+  files: ReviewStatusFileEntry[];
+  fileCount: number;
+  additions: number;
+  deletions: number;
+};
+
+/** Groups already-sorted files by directory. Chains of single-child directories are shown as one `a/b/c` row. */
+function buildFileTree(files: ReviewStatusFileEntry[], sort: SourceControlSort): FileTreeDir {
+  const root: FileTreeDir = { name: '', path: '', dirs: [], files: [], fileCount: 0, additions: 0, deletions: 0 };
+  for (const file of files) {
+    const parts = file.path.split('/');
+    let dir = root;
+    for (const part of parts.slice(0, -1)) {
+      const path = dir.path ? `${dir.path}/${part}` : part;
+      let next = dir.dirs.find((candidate) => candidate.path === path);
+      if (!next) {
+        next = { name: part, path, dirs: [], files: [], fileCount: 0, additions: 0, deletions: 0 };
+        dir.dirs.push(next);
+      }
+      dir = next;
+    }
+    dir.files.push(file);
+  }
+
+  function finish(dir: FileTreeDir): FileTreeDir {
+    let node = dir;
+    while (node !== root && node.files.length === 0 && node.dirs.length === 1) {
+      const child = node.dirs[0];
+      node = { ...child, name: `${node.name}/${child.name}` };
+    }
+    node.dirs = node.dirs.map(finish);
+    node.fileCount = node.files.length + node.dirs.reduce((sum, child) => sum + child.fileCount, 0);
+    const totals = sumCounts(node.files);
+    node.additions = totals.additions + node.dirs.reduce((sum, child) => sum + child.additions, 0);
+    node.deletions = totals.deletions + node.dirs.reduce((sum, child) => sum + child.deletions, 0);
+    node.dirs.sort((a, b) => (sort === 'churn' ? (b.additions + b.deletions) - (a.additions + a.deletions) : 0) || a.path.localeCompare(b.path));
+    return node;
+  }
+
+  return finish(root);
+}
+
+function collectTreeFiles(dir: FileTreeDir): ReviewStatusFileEntry[] {
+  return [...dir.dirs.flatMap(collectTreeFiles), ...dir.files];
+}
+
+function DirectoryRow(props: {
+  scope: ReviewDiffScope;
+  dir: FileTreeDir;
+  depth: number;
+  collapsed: boolean;
+  compactCounts: boolean;
+  actionBusy: boolean;
+  onToggle: () => void;
+  onStage: () => Promise<void>;
+  onUnstage: () => Promise<void>;
+  onDiscard?: () => void;
+}) {
+  const { scope, dir, depth, collapsed, compactCounts, actionBusy, onToggle, onStage, onUnstage, onDiscard } = props;
+  return (
+    <Box onClick={onToggle} style={{ cursor: 'pointer', padding: '4px 10px', paddingLeft: 10 + depth * 14 }}>
+      <Group justify="space-between" align="center" wrap="nowrap">
+        <Group gap={6} wrap="nowrap" style={{ minWidth: 0, flex: 1 }}>
+          {collapsed ? <IconChevronRight size={14} /> : <IconChevronDown size={14} />}
+          <IconFolder size={14} />
+          <Text size="sm" fw={600} style={{ wordBreak: 'break-word' }}>{dir.name}</Text>
+          <Badge size="sm" variant="light">{dir.fileCount}</Badge>
+        </Group>
+        <Group gap={6} wrap="nowrap">
+          {!compactCounts ? <Badge size="sm" color="green" variant="light">+{dir.additions}</Badge> : null}
+          {!compactCounts ? <Badge size="sm" color="red" variant="light">-{dir.deletions}</Badge> : null}
+          {onDiscard ? (
+            <Button
+              size="compact-xs"
+              variant="subtle"
+              color="red"
+              disabled={actionBusy}
+              onClick={(event) => {
+                event.stopPropagation();
+                onDiscard();
+              }}
+            >
+              Discard
+            </Button>
+          ) : null}
+          <Button
+            size="compact-xs"
+            variant="subtle"
+            loading={actionBusy}
+            onClick={(event) => {
+              event.stopPropagation();
+              void (scope === 'unstaged' ? onStage() : onUnstage());
+            }}
+          >
+            {scope === 'unstaged' ? 'Stage folder' : 'Unstage folder'}
+          </Button>
+        </Group>
+      </Group>
+    </Box>
+  );
+}
+
 function ScopeHeader(props: {
   title: string;
   active: boolean;
@@ -100,8 +251,9 @@ function ScopeHeader(props: {
   actionBusy: boolean;
   onSelect: () => void;
   onAction: () => Promise<void>;
+  extraAction?: ReactNode;
 }) {
-  const { title, active, fileCount, additions, deletions, compactCounts, buttonLabel, actionBusy, onSelect, onAction } = props;
+  const { title, active, fileCount, additions, deletions, compactCounts, buttonLabel, actionBusy, onSelect, onAction, extraAction } = props;
 
   return (
     <Group justify="space-between" align="center" wrap="nowrap">
@@ -122,6 +274,7 @@ function ScopeHeader(props: {
           {buttonLabel}
         </Button>
       </Tooltip>
+      {extraAction}
     </Group>
   );
 }
@@ -134,14 +287,20 @@ function FileRow(props: {
   onSelect: () => void;
   onStage: () => Promise<void>;
   onUnstage: () => Promise<void>;
+  onDiscard?: () => void;
+  whitespaceOnly?: 'eol' | 'whitespace' | null;
+  /** Tree layout shows just the file name, indented under its directory. */
+  label?: string;
+  depth?: number;
 }) {
-  const { scope, file, active, actionBusy, onSelect, onStage, onUnstage } = props;
+  const { scope, file, active, actionBusy, onSelect, onStage, onUnstage, onDiscard, whitespaceOnly, label, depth = 0 } = props;
   return (
     <Box
       onClick={onSelect}
       style={{
         cursor: 'pointer',
         padding: '8px 10px',
+        paddingLeft: 10 + depth * 14,
         borderRadius: 8,
         background: active ? 'rgba(34, 139, 230, 0.16)' : 'rgba(255,255,255,0.02)',
         border: active ? '1px solid rgba(34, 139, 230, 0.4)' : '1px solid rgba(255,255,255,0.05)'
@@ -150,13 +309,28 @@ function FileRow(props: {
       <Group justify="space-between" align="center" wrap="nowrap">
         <Group gap="xs" wrap="nowrap" style={{ minWidth: 0, flex: 1 }}>
           <Badge variant="outline">{statusCode(file)}</Badge>
-          <Text size="sm" fw={active ? 700 : 500} style={{ wordBreak: 'break-word' }}>
-            {file.path}
+          <Text size="sm" fw={active ? 700 : 500} style={{ wordBreak: 'break-word' }} title={label ? file.path : undefined}>
+            {label ?? file.path}
           </Text>
         </Group>
         <Group gap={6} wrap="nowrap">
+          <WhitespaceOnlyBadge kind={whitespaceOnly} />
           <Badge color="green" variant="light">+{file.additions}</Badge>
           <Badge color="red" variant="light">-{file.deletions}</Badge>
+          {onDiscard ? (
+            <Button
+              size="compact-xs"
+              variant="subtle"
+              color="red"
+              disabled={actionBusy}
+              onClick={(event) => {
+                event.stopPropagation();
+                onDiscard();
+              }}
+            >
+              Discard
+            </Button>
+          ) : null}
           {scope === 'unstaged' ? (
             <Button
               size="compact-xs"
@@ -189,10 +363,12 @@ function FileRow(props: {
 }
 
 export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
-  const { repoRef, state, onPersistState, forceViewerOpen = false } = props;
+  const { repoRef, state, onPersistState, forceViewerOpen = false, onAskAi, aiModel } = props;
+  const changesSummary = useAiSummary(`${repoRef}:${state.selected_scope}`);
   const [statusBusy, setStatusBusy] = useState(false);
   const [diffBusy, setDiffBusy] = useState(false);
   const [actionBusy, setActionBusy] = useState(false);
+  const [discardTarget, setDiscardTarget] = useState<{ paths: string[] | null } | null>(null);
   const [statusError, setStatusError] = useState<string | null>(null);
   const [diffError, setDiffError] = useState<string | null>(null);
   const [stagedFiles, setStagedFiles] = useState<ReviewStatusFileEntry[]>([]);
@@ -210,10 +386,19 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
   const [filePatchByPath, setFilePatchByPath] = useState<Record<string, string>>({});
   const [filePatchBusyByPath, setFilePatchBusyByPath] = useState<Record<string, boolean>>({});
   const [collapsedByPath, setCollapsedByPath] = useState<Record<string, boolean>>({});
+  const [remoteBusy, setRemoteBusy] = useState<GitRemoteAction | null>(null);
+  const [remoteResult, setRemoteResult] = useState<GitRemoteResult | null>(null);
+  const [credentialsAction, setCredentialsAction] = useState<GitRemoteAction | null>(null);
+  const [credentialUsername, setCredentialUsername] = useState('');
+  const [credentialPassword, setCredentialPassword] = useState('');
 
+  const [collapsedDirs, setCollapsedDirs] = useState<Set<string>>(() => new Set());
+  const sortedStagedFiles = useMemo(() => sortFiles(stagedFiles, state.sort), [stagedFiles, state.sort]);
+  const sortedUnstagedFiles = useMemo(() => sortFiles(unstagedFiles, state.sort), [unstagedFiles, state.sort]);
   const stagedTotals = useMemo(() => sumCounts(stagedFiles), [stagedFiles]);
   const unstagedTotals = useMemo(() => sumCounts(unstagedFiles), [unstagedFiles]);
   const allTotals = useMemo(() => sumCounts([...stagedFiles, ...unstagedFiles]), [stagedFiles, unstagedFiles]);
+  const changedPathCount = useMemo(() => new Set([...stagedFiles, ...unstagedFiles].map((file) => file.path)).size, [stagedFiles, unstagedFiles]);
   const selectedScopeFiles = useMemo(
     () => diffManifest?.files ?? (state.selected_scope === 'staged' ? stagedFiles : unstagedFiles),
     [diffManifest, state.selected_scope, stagedFiles, unstagedFiles]
@@ -231,6 +416,10 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
     () => selectedScopeFiles.map((file) => file.path),
     [selectedScopeFiles]
   );
+  const whitespaceOnlyByPath = useMemo(
+    () => Object.fromEntries((diffManifest?.files ?? []).map((file) => [`${diffManifest?.scope}:${file.path}`, file.whitespace_only])),
+    [diffManifest]
+  );
   const selectedScopeCountsByPath = useMemo(
     () => Object.fromEntries(selectedScopeFiles.map((file) => [file.path, file])),
     [selectedScopeFiles]
@@ -243,6 +432,11 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
     return diff.patch;
   }, [state.selected_path, diff?.patch]);
 
+  const whitespaceScanPatches = useMemo(
+    () => (state.selected_path ? [selectedFilePatch] : Object.values(filePatchByPath)),
+    [state.selected_path, selectedFilePatch, filePatchByPath]
+  );
+
   const selectedFilePayloadInfo = useMemo(() => {
     if (!state.selected_path || !diff?.patch?.trim()) {
       return {
@@ -254,7 +448,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
     }
 
     try {
-      const files = parsePatchFiles(diff.patch).flatMap((patch) => patch.files ?? []);
+      const files = cachedPatchFiles(diff.patch);
       const selected =
         files.find((file) => file.name === state.selected_path) ??
         (files.length === 1 ? files[0] : null);
@@ -283,7 +477,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
         continue;
       }
       try {
-        const parsed = parsePatchFiles(patch).flatMap((item) => item.files ?? []);
+        const parsed = cachedPatchFiles(patch);
         next[file.path] = parsed.find((entry) => entry.name === file.path) ?? parsed[0] ?? null;
       } catch {
         next[file.path] = null;
@@ -326,6 +520,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
       setStatusBusy(true);
       setStatusError(null);
       const json = await getReviewStatus(repoRef);
+      publishRepoGitStatus(repoRef, json);
       setStagedFiles(json.staged);
       setUnstagedFiles(json.unstaged);
       const pieces = [json.branch ?? 'HEAD'];
@@ -377,6 +572,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
             path: file.path,
             context_lines: nextState.whole_file ? 1000 : clampContextLines(nextState.context_lines),
             whole_file: nextState.whole_file,
+            whitespace: nextState.whitespace_mode,
           });
           return [file.path, json.patch] as const;
         } catch {
@@ -422,6 +618,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
           path: nextState.selected_path,
           context_lines: nextState.whole_file ? 1000 : clampContextLines(nextState.context_lines),
           whole_file: nextState.whole_file,
+          whitespace: nextState.whitespace_mode,
         });
         if (refreshDiffRequestIdRef.current !== requestId) {
           return;
@@ -462,7 +659,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
     }
   }
 
-  async function runStageAction(kind: 'stage' | 'unstage', scope: ReviewDiffScope, path: string | null) {
+  async function runStageAction(kind: 'stage' | 'unstage', scope: ReviewDiffScope, path: string | null, folder = false) {
     if (!repoRef.trim()) return;
     try {
       setActionBusy(true);
@@ -476,7 +673,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
       const nextState = {
         ...state,
         selected_scope: nextScope,
-        selected_path: path,
+        selected_path: folder ? null : path,
       };
       await onPersistState(nextState);
       await refreshStatus();
@@ -488,6 +685,26 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
     }
   }
 
+  async function runRemoteAction(action: GitRemoteAction, credentials?: { username: string; password: string }) {
+    if (!repoRef.trim()) return;
+    try {
+      setRemoteBusy(action);
+      setStatusError(null);
+      const result = await runReviewRemoteAction(action, { repo_ref: repoRef, credentials: credentials ?? null });
+      setRemoteResult(result);
+      if (result.needs_credentials && result.auth_kind !== 'ssh') {
+        setCredentialsAction(action);
+        return;
+      }
+      setCredentialsAction(null);
+      setCredentialPassword('');
+      await refreshStatus();
+    } catch (err) {
+      setStatusError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setRemoteBusy(null);
+    }
+  }
 
   useEffect(() => {
     void refreshStatus();
@@ -497,7 +714,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
     if (viewerOpen) {
       void refreshDiff(state);
     }
-  }, [repoRef, viewerOpen, state.selected_scope, state.selected_path, state.context_lines, state.whole_file]);
+  }, [repoRef, viewerOpen, state.selected_scope, state.selected_path, state.context_lines, state.whole_file, state.whitespace_mode]);
 
 
   useEffect(() => {
@@ -556,6 +773,62 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
     }
   }, [forceViewerOpen, viewerOpen]);
 
+  function renderFileRow(scope: ReviewDiffScope, file: ReviewStatusFileEntry, depth?: number) {
+    return (
+      <FileRow
+        key={`${scope}:${file.path}`}
+        scope={scope}
+        file={file}
+        active={state.selected_scope === scope && state.selected_path === file.path}
+        actionBusy={actionBusy}
+        onSelect={() => void patchState({ selected_scope: scope, selected_path: file.path })}
+        onStage={() => runStageAction('stage', scope, file.path)}
+        onUnstage={() => runStageAction('unstage', scope, file.path)}
+        onDiscard={scope === 'unstaged' ? () => setDiscardTarget({ paths: [file.path] }) : undefined}
+        whitespaceOnly={whitespaceOnlyByPath[`${scope}:${file.path}`]}
+        label={depth === undefined ? undefined : file.path.split('/').pop()}
+        depth={depth}
+      />
+    );
+  }
+
+  function renderTreeDir(scope: ReviewDiffScope, dir: FileTreeDir, depth: number): ReactNode[] {
+    return [
+      ...dir.dirs.flatMap((child) => {
+        const key = `${scope}:${child.path}`;
+        const collapsed = collapsedDirs.has(key);
+        return [
+          <DirectoryRow
+            key={`dir:${key}`}
+            scope={scope}
+            dir={child}
+            depth={depth}
+            collapsed={collapsed}
+            compactCounts={compactCounts}
+            actionBusy={actionBusy}
+            onToggle={() => setCollapsedDirs((current) => {
+              const next = new Set(current);
+              if (!next.delete(key)) next.add(key);
+              return next;
+            })}
+            onStage={() => runStageAction('stage', scope, child.path, true)}
+            onUnstage={() => runStageAction('unstage', scope, child.path, true)}
+            onDiscard={scope === 'unstaged' ? () => setDiscardTarget({ paths: collectTreeFiles(child).map((file) => file.path) }) : undefined}
+          />,
+          ...(collapsed ? [] : renderTreeDir(scope, child, depth + 1)),
+        ];
+      }),
+      ...dir.files.map((file) => renderFileRow(scope, file, depth)),
+    ];
+  }
+
+  function renderScopeFiles(scope: ReviewDiffScope, files: ReviewStatusFileEntry[]) {
+    if (state.layout === 'tree') {
+      return renderTreeDir(scope, buildFileTree(files, state.sort), 0);
+    }
+    return files.map((file) => renderFileRow(scope, file));
+  }
+
   const viewerContent = (
     <Box style={{ height: '100%', display: 'grid', gridTemplateColumns: showSidebar ? `1fr 8px ${effectiveSidebarWidth}px` : '1fr' }}>
       <Box p="sm" style={{ minHeight: 0, overflow: 'hidden', display: 'flex', flexDirection: 'column' }}>
@@ -566,6 +839,9 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
               <Button variant="default" onClick={() => setSidebarHidden((value) => !value)}>
                 {showSidebar ? 'Hide source control' : 'Show source control'}
               </Button>
+              <Button variant="default" loading={remoteBusy === 'fetch'} disabled={!!remoteBusy} onClick={() => void runRemoteAction('fetch')}>Fetch</Button>
+              <Button variant="default" loading={remoteBusy === 'pull'} disabled={!!remoteBusy} onClick={() => void runRemoteAction('pull')}>Pull</Button>
+              <Button variant="default" loading={remoteBusy === 'push'} disabled={!!remoteBusy} onClick={() => void runRemoteAction('push')}>Push</Button>
             </Group>
             <Group gap="xs">
               {branchSummary ? <Badge variant="light">{branchSummary}</Badge> : null}
@@ -583,6 +859,12 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
               ) : null}
             </Group>
             <Group gap="xs">
+              <AiSummaryButton
+                summary={changesSummary}
+                label="Summarize pending changes"
+                disabled={selectedScopeFiles.length === 0}
+                request={() => summarizeDiffWithAi({ repo_ref: repoRef, scope: state.selected_scope, ...aiModel })}
+              />
               {!state.selected_path && scopeDiffRows.length > 0 ? (
                 <>
                   <Button
@@ -608,6 +890,8 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
               ) : null}
             </Group>
           </Group>
+          <AiSummaryCard summary={changesSummary} title={`Summary of ${state.selected_scope} changes`} />
+          {!diffBusy && !diffError ? <PatchWhitespaceIssues patches={whitespaceScanPatches} /> : null}
           <Box style={{ flex: 1, minHeight: 0 }}>
             {diffBusy ? (
               <Group justify="center" py="xl"><Loader /></Group>
@@ -674,17 +958,22 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
                 </Alert>
               ) : selectedFilePatch ? (
                 <ScrollArea h="100%" type="auto">
-                  <Box p={0} style={{ overflow: 'hidden' }}>
-                    <PatchDiff
-                      patch={selectedFilePatch}
-                      options={{
-                        theme: {
-                          dark: 'pierre-dark',
-                          light: 'pierre-light'
-                        },
-                        diffStyle: state.diff_style
-                      }}
-                    />
+                  <Box p={0} style={{ overflow: 'hidden' }} role="region" aria-label={`Diff of ${state.selected_path ?? 'file'}`}>
+                    <PatchScreenReaderSummary patch={selectedFilePatch} />
+                    <AskAiHunkMenu repoRef={repoRef} path={state.selected_path ?? ''} patch={selectedFilePatch} onAskAi={onAskAi}>
+                      <div aria-hidden>
+                        <PatchDiff
+                          patch={selectedFilePatch}
+                          options={{
+                            theme: {
+                              dark: 'pierre-dark',
+                              light: 'pierre-light'
+                            },
+                            diffStyle: state.diff_style
+                          }}
+                        />
+                      </div>
+                    </AskAiHunkMenu>
                   </Box>
                 </ScrollArea>
               ) : (
@@ -722,6 +1011,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
                                 <Text size="sm" fw={600} style={{ wordBreak: 'break-word' }}>{file.path}</Text>
                               </Group>
                               <Group gap="xs" wrap="nowrap">
+                                <WhitespaceOnlyBadge kind={file.whitespace_only} />
                                 <Badge color="green" variant="light">+{status?.additions ?? file.additions}</Badge>
                                 <Badge color="red" variant="light">-{status?.deletions ?? file.deletions}</Badge>
                               </Group>
@@ -729,17 +1019,19 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
                           </Box>
                           {!collapsed ? (
                             parsed ? (
-                              <Box p={0} style={{ overflow: 'hidden' }}>
-                                <FileDiff
-                                  fileDiff={parsed}
-                                  options={{
-                                    theme: {
-                                      dark: 'pierre-dark',
-                                      light: 'pierre-light'
-                                    },
-                                    diffStyle: state.diff_style
-                                  }}
-                                />
+                              <Box p={0} style={{ overflow: 'hidden' }} role="region" aria-label={`Diff of ${file.path}`}>
+                                <AskAiHunkMenu repoRef={repoRef} path={file.path} patch={filePatchByPath[file.path] ?? ''} onAskAi={onAskAi}>
+                                  <FileDiff
+                                    fileDiff={parsed}
+                                    options={{
+                                      theme: {
+                                        dark: 'pierre-dark',
+                                        light: 'pierre-light'
+                                      },
+                                      diffStyle: state.diff_style
+                                    }}
+                                  />
+                                </AskAiHunkMenu>
                               </Box>
                             ) : filePatchBusyByPath[file.path] ? (
                               <Box p="md">
@@ -818,12 +1110,41 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
                 disabled={state.whole_file}
                 onChange={(value) => void patchState({ context_lines: clampContextLines(value) })}
               />
+              <WhitespaceModeSelect value={state.whitespace_mode} onChange={(value) => void patchState({ whitespace_mode: value })} />
             </Stack>
           </Card>
 
           <Box style={{ flex: 1, minHeight: 0 }}>
             <ScrollArea h="100%" type="auto">
               <Stack gap="md" pr="xs">
+                <Group justify="space-between" align="center" wrap="nowrap" gap="xs">
+                  <Group gap={6} wrap="nowrap">
+                    <Text size="xs" c="dimmed">{changedPathCount} changed file{changedPathCount === 1 ? '' : 's'}</Text>
+                    <Badge size="sm" color="green" variant="light">+{allTotals.additions}</Badge>
+                    <Badge size="sm" color="red" variant="light">-{allTotals.deletions}</Badge>
+                  </Group>
+                  <Group gap={6} wrap="nowrap">
+                    <SegmentedControl
+                      size="xs"
+                      value={state.layout}
+                      onChange={(value) => void patchState({ layout: value === 'tree' ? 'tree' : 'flat' })}
+                      data={[
+                        { label: 'List', value: 'flat' },
+                        { label: 'Tree', value: 'tree' }
+                      ]}
+                    />
+                    <SegmentedControl
+                      size="xs"
+                      value={state.sort}
+                      onChange={(value) => void patchState({ sort: parseSourceControlSort(value) })}
+                      data={[
+                        { label: 'Path', value: 'path' },
+                        { label: 'Churn', value: 'churn' },
+                        { label: 'Status', value: 'status' }
+                      ]}
+                    />
+                  </Group>
+                </Group>
                 <Card withBorder p="xs">
                   <Stack gap="xs">
                     <ScopeHeader
@@ -838,18 +1159,7 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
                       onSelect={() => void patchState({ selected_scope: 'staged', selected_path: null })}
                       onAction={() => runStageAction('unstage', 'staged', null)}
                     />
-                    {stagedFiles.length > 0 ? stagedFiles.map((file) => (
-                      <FileRow
-                        key={`staged:${file.path}`}
-                        scope="staged"
-                        file={file}
-                        active={state.selected_scope === 'staged' && state.selected_path === file.path}
-                        actionBusy={actionBusy}
-                        onSelect={() => void patchState({ selected_scope: 'staged', selected_path: file.path })}
-                        onStage={() => runStageAction('stage', 'staged', file.path)}
-                        onUnstage={() => runStageAction('unstage', 'staged', file.path)}
-                      />
-                    )) : (
+                    {stagedFiles.length > 0 ? renderScopeFiles('staged', sortedStagedFiles) : (
                       <Text c="dimmed" size="xs" px="xs" py={4}>No staged files.</Text>
                     )}
                   </Stack>
@@ -868,19 +1178,13 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
                       actionBusy={actionBusy}
                       onSelect={() => void patchState({ selected_scope: 'unstaged', selected_path: null })}
                       onAction={() => runStageAction('stage', 'unstaged', null)}
+                      extraAction={(
+                        <Button size="xs" variant="light" color="red" disabled={actionBusy || (unstagedFiles.length === 0 && stagedFiles.length === 0)} onClick={() => setDiscardTarget({ paths: null })}>
+                          Discard all
+                        </Button>
+                      )}
                     />
-                    {unstagedFiles.length > 0 ? unstagedFiles.map((file) => (
-                      <FileRow
-                        key={`unstaged:${file.path}`}
-                        scope="unstaged"
-                        file={file}
-                        active={state.selected_scope === 'unstaged' && state.selected_path === file.path}
-                        actionBusy={actionBusy}
-                        onSelect={() => void patchState({ selected_scope: 'unstaged', selected_path: file.path })}
-                        onStage={() => runStageAction('stage', 'unstaged', file.path)}
-                        onUnstage={() => runStageAction('unstage', 'unstaged', file.path)}
-                      />
-                    )) : (
+                    {unstagedFiles.length > 0 ? renderScopeFiles('unstaged', sortedUnstagedFiles) : (
                       <Text c="dimmed" size="xs" px="xs" py={4}>No unstaged files.</Text>
                     )}
                   </Stack>
@@ -896,6 +1200,48 @@ export function ReviewDiffViewerPanel(props: ReviewDiffViewerPanelProps) {
   return (
     <>
       {statusError ? <Alert color="red">{statusError}</Alert> : null}
+      {remoteResult ? (
+        <Alert
+          color={remoteResult.ok ? 'green' : 'red'}
+          withCloseButton
+          onClose={() => setRemoteResult(null)}
+          title={remoteResult.message ?? (remoteResult.ok ? 'Remote operation finished' : 'Remote operation failed')}
+        >
+          <Text size="xs" ff="monospace" style={{ whiteSpace: 'pre-wrap' }}>{remoteResult.log}</Text>
+        </Alert>
+      ) : null}
+
+      <DiscardChangesModal
+        repoRef={repoRef}
+        paths={discardTarget?.paths ?? null}
+        opened={!!discardTarget}
+        onClose={() => setDiscardTarget(null)}
+        onDiscarded={() => {
+          void refreshStatus();
+          if (viewerOpen) void refreshDiff(state);
+        }}
+      />
+
+      <Modal opened={!!credentialsAction} onClose={() => setCredentialsAction(null)} title="Git credentials required">
+        <Stack gap="sm">
+          <Text size="sm" c="dimmed">The remote asked for credentials. They are passed to git for this request only and are not stored.</Text>
+          <TextInput label="Username" value={credentialUsername} onChange={(event) => setCredentialUsername(event.currentTarget.value)} />
+          <PasswordInput label="Password or access token" value={credentialPassword} onChange={(event) => setCredentialPassword(event.currentTarget.value)} />
+          <Group justify="flex-end">
+            <Button variant="default" onClick={() => setCredentialsAction(null)}>Cancel</Button>
+            <Button
+              loading={!!remoteBusy}
+              onClick={() => {
+                if (credentialsAction) {
+                  void runRemoteAction(credentialsAction, { username: credentialUsername, password: credentialPassword });
+                }
+              }}
+            >
+              Retry {credentialsAction}
+            </Button>
+          </Group>
+        </Stack>
+      </Modal>
 
       {forceViewerOpen ? (
         <Box style={{ height: 'calc(100vh - 180px)', minHeight: 520 }}>
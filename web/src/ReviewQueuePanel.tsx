@@ -0,0 +1,139 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, Group, Modal, Stack, Table, Text, Textarea, Title } from '@mantine/core';
+import { IconRefresh } from '@tabler/icons-react';
+import { getReviewQueue, submitRunReview, type ReviewQueueItem } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type ReviewQueuePanelProps = {
+  refreshKey: unknown;
+  onOpenRun: (runId: string) => void;
+  onReviewed: () => Promise<void> | void;
+};
+
+export function ReviewQueuePanel(props: ReviewQueuePanelProps) {
+  const { refreshKey, onOpenRun, onReviewed } = props;
+  const [items, setItems] = useState<ReviewQueueItem[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [busyRunId, setBusyRunId] = useState<string | null>(null);
+  const [rejectTarget, setRejectTarget] = useState<ReviewQueueItem | null>(null);
+  const [rejectComment, setRejectComment] = useState('');
+
+  async function refresh() {
+    try {
+      setLoading(true);
+      setError(null);
+      setItems(await getReviewQueue());
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  useEffect(() => {
+    void refresh();
+  }, [refreshKey]);
+
+  async function submit(item: ReviewQueueItem, decision: 'approve' | 'reject', comment = '') {
+    try {
+      setBusyRunId(item.run_id);
+      setError(null);
+      await submitRunReview(item.run_id, decision, comment);
+      setRejectTarget(null);
+      setRejectComment('');
+      await refresh();
+      await onReviewed();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusyRunId(null);
+    }
+  }
+
+  return (
+    <Card withBorder>
+      <Stack>
+        <Group justify="space-between" align="center">
+          <Group gap="xs">
+            <Title order={4}>Needs review</Title>
+            <Badge variant="light" color={items.length > 0 ? 'yellow' : 'gray'}>{items.length}</Badge>
+          </Group>
+          <Button size="xs" variant="default" leftSection={<IconRefresh size={16} />} onClick={() => void refresh()} loading={loading}>
+            Refresh
+          </Button>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        {items.length === 0 ? (
+          <Text size="sm" c="dimmed">No workflows are awaiting review or failed.</Text>
+        ) : (
+          <Table striped highlightOnHover>
+            <Table.Thead>
+              <Table.Tr>
+                <Table.Th>Workflow</Table.Th>
+                <Table.Th>Reason</Table.Th>
+                <Table.Th>Step</Table.Th>
+                <Table.Th>Last event</Table.Th>
+                <Table.Th>Actions</Table.Th>
+              </Table.Tr>
+            </Table.Thead>
+            <Table.Tbody>
+              {items.map((item) => (
+                <Table.Tr key={item.run_id}>
+                  <Table.Td>
+                    <Text size="sm" fw={600}>{item.title}</Text>
+                    <Text size="xs" c="dimmed">{item.repo_ref}</Text>
+                  </Table.Td>
+                  <Table.Td>
+                    <Badge color={item.reason === 'failed' ? 'red' : 'yellow'} variant="light">
+                      {item.reason === 'failed' ? 'Failed' : 'Awaiting review'}
+                    </Badge>
+                  </Table.Td>
+                  <Table.Td>{item.current_step_name ?? item.current_step_id ?? '—'}</Table.Td>
+                  <Table.Td>
+                    <Text size="xs" lineClamp={2}>{item.last_message ?? '—'}</Text>
+                  </Table.Td>
+                  <Table.Td>
+                    <Group gap="xs" wrap="nowrap">
+                      <Button size="xs" variant="light" onClick={() => onOpenRun(item.run_id)}>Open</Button>
+                      <Button size="xs" color="green" variant="light" loading={busyRunId === item.run_id} onClick={() => void submit(item, 'approve')}>
+                        Approve
+                      </Button>
+                      <Button size="xs" color="red" variant="light" disabled={busyRunId === item.run_id} onClick={() => { setRejectTarget(item); setRejectComment(''); }}>
+                        Reject
+                      </Button>
+                    </Group>
+                  </Table.Td>
+                </Table.Tr>
+              ))}
+            </Table.Tbody>
+          </Table>
+        )}
+      </Stack>
+
+      <Modal opened={!!rejectTarget} onClose={() => setRejectTarget(null)} title={`Reject ${rejectTarget?.title ?? ''}`} centered>
+        <Stack>
+          <Textarea
+            label="Feedback"
+            description="Sent with the next inference turn of this workflow."
+            value={rejectComment}
+            onChange={(e) => setRejectComment(e.currentTarget.value)}
+            autosize
+            minRows={4}
+          />
+          <Group justify="flex-end">
+            <Button variant="default" onClick={() => setRejectTarget(null)}>Cancel</Button>
+            <Button
+              color="red"
+              disabled={!rejectComment.trim()}
+              loading={!!rejectTarget && busyRunId === rejectTarget.run_id}
+              onClick={() => rejectTarget && void submit(rejectTarget, 'reject', rejectComment)}
+            >
+              Reject with comment
+            </Button>
+          </Group>
+        </Stack>
+      </Modal>
+    </Card>
+  );
+}
@@ -0,0 +1,152 @@
+import { useEffect, useRef, useState } from 'react';
+import { Alert, Badge, Box, Button, Card, Group, SegmentedControl, Stack, Text, Textarea, Title } from '@mantine/core';
+import { saveRunNotes, type RunNotes } from './api';
+import { MarkdownMessage } from './MarkdownMessage';
+
+type RunNotesCardProps = {
+  runId: string | null;
+  initialText: string;
+  initialUpdatedAt: string;
+  canInject: boolean;
+  onInject: (text: string) => void;
+};
+
+const SAVE_DELAY_MS = 800;
+
+/** Keeps their lines in order and appends lines only present in ours. */
+function mergeNotes(theirs: string, ours: string) {
+  const known = new Set(theirs.split('\n'));
+  const added = ours.split('\n').filter((line) => line.trim() && !known.has(line));
+  return added.length ? `${theirs.trimEnd()}\n${added.join('\n')}` : theirs;
+}
+
+export function RunNotesCard(props: RunNotesCardProps) {
+  const { runId, initialText, initialUpdatedAt, canInject, onInject } = props;
+  const [text, setText] = useState(initialText);
+  const [mode, setMode] = useState<'edit' | 'preview'>('edit');
+  const [status, setStatus] = useState<'saved' | 'pending' | 'error' | 'conflict'>('saved');
+  const [conflict, setConflict] = useState<RunNotes | null>(null);
+  const timerRef = useRef<number | null>(null);
+  const baseRef = useRef(initialUpdatedAt);
+  // Edits not yet written, flushed immediately when switching runs or unmounting.
+  const pendingRef = useRef<{ runId: string; text: string } | null>(null);
+
+  async function save(targetRunId: string, next: string, force = false) {
+    pendingRef.current = null;
+    try {
+      const result = await saveRunNotes(targetRunId, { markdown: next, base_updated_at: baseRef.current, force });
+      if (result.conflict) {
+        setConflict(result.notes);
+        setStatus('conflict');
+        return;
+      }
+      baseRef.current = result.notes.updated_at ?? '';
+      setConflict(null);
+      setStatus('saved');
+    } catch {
+      setStatus('error');
+    }
+  }
+
+  function flush() {
+    if (timerRef.current) window.clearTimeout(timerRef.current);
+    const pending = pendingRef.current;
+    if (pending) void save(pending.runId, pending.text);
+  }
+
+  useEffect(() => {
+    flush();
+    setText(initialText);
+    baseRef.current = initialUpdatedAt;
+    setConflict(null);
+    setStatus('saved');
+  }, [runId]);
+
+  useEffect(() => () => flush(), []);
+
+  function update(next: string) {
+    setText(next);
+    if (!runId || status === 'conflict') {
+      if (runId) pendingRef.current = null;
+      return;
+    }
+    setStatus('pending');
+    pendingRef.current = { runId, text: next };
+    if (timerRef.current) window.clearTimeout(timerRef.current);
+    timerRef.current = window.setTimeout(() => void save(runId, next), SAVE_DELAY_MS);
+  }
+
+  function resolve(choice: 'mine' | 'theirs' | 'merge') {
+    if (!runId || !conflict) return;
+    const theirs = conflict.markdown ?? '';
+    baseRef.current = conflict.updated_at ?? '';
+    if (choice === 'theirs') {
+      setText(theirs);
+      setConflict(null);
+      setStatus('saved');
+      return;
+    }
+    const next = choice === 'merge' ? mergeNotes(theirs, text) : text;
+    setText(next);
+    setStatus('pending');
+    void save(runId, next, choice === 'mine');
+  }
+
+  return (
+    <Card withBorder>
+      <Stack gap="xs">
+        <Group justify="space-between">
+          <Group gap="xs">
+            <Title order={5}>Notes</Title>
+            <Badge
+              size="xs"
+              variant="light"
+              color={status === 'error' || status === 'conflict' ? 'red' : status === 'pending' ? 'yellow' : 'gray'}
+            >
+              {status === 'conflict' ? 'conflict' : status === 'error' ? 'save failed' : status === 'pending' ? 'saving…' : 'saved'}
+            </Badge>
+          </Group>
+          <Group gap="xs">
+            <SegmentedControl
+              size="xs"
+              value={mode}
+              onChange={(value) => setMode(value as 'edit' | 'preview')}
+              data={[{ label: 'Edit', value: 'edit' }, { label: 'Preview', value: 'preview' }]}
+            />
+            <Button size="xs" variant="light" disabled={!canInject || !text.trim()} onClick={() => onInject(text.trim())}>
+              Inject into stage input
+            </Button>
+          </Group>
+        </Group>
+        {conflict ? (
+          <Alert color="orange" title="Notes changed elsewhere">
+            <Stack gap="xs">
+              <Text size="sm">
+                Another tab or app instance saved these notes{conflict.updated_at ? ` at ${new Date(conflict.updated_at).toLocaleTimeString()}` : ''}. Your edits are not saved yet.
+              </Text>
+              <Group gap="xs">
+                <Button size="compact-xs" onClick={() => resolve('merge')}>Merge</Button>
+                <Button size="compact-xs" variant="light" color="red" onClick={() => resolve('mine')}>Overwrite with mine</Button>
+                <Button size="compact-xs" variant="default" onClick={() => resolve('theirs')}>Use theirs</Button>
+              </Group>
+            </Stack>
+          </Alert>
+        ) : null}
+        {!runId ? <Text size="sm" c="dimmed">Select a run to keep notes alongside it.</Text> : mode === 'edit' ? (
+          <Textarea
+            value={text}
+            onChange={(event) => update(event.currentTarget.value)}
+            placeholder={'Plans, TODOs and links for this run.\n\n- [ ] first step'}
+            minRows={6}
+            autosize
+            styles={{ input: { fontFamily: 'monospace', fontSize: 13 } }}
+          />
+        ) : (
+          <Box mih={120}>
+            {text.trim() ? <MarkdownMessage text={text} /> : <Text size="sm" c="dimmed">Nothing written yet.</Text>}
+          </Box>
+        )}
+      </Stack>
+    </Card>
+  );
+}
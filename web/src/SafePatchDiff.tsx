@@ -0,0 +1,67 @@
+import { Component } from 'react';
+import { Alert, Code, Stack, Text, VisuallyHidden } from '@mantine/core';
+import { PatchDiff } from '@pierre/diffs/react';
+import { describePatch } from './accessibility';
+
+export type DiffStyle = 'unified' | 'split';
+
+type SafePatchDiffProps = {
+  patch: string;
+  diffStyle: DiffStyle;
+};
+
+type SafePatchDiffState = {
+  error: string | null;
+};
+
+/** Text alternative for the canvas-like diff renderer, read out instead of individual rows. */
+export function PatchScreenReaderSummary(props: { patch: string }) {
+  const hunks = describePatch(props.patch);
+  return (
+    <VisuallyHidden>
+      <ul aria-label={`${hunks.length} changed region${hunks.length === 1 ? '' : 's'}`}>
+        {hunks.map((hunk, index) => <li key={index}>{hunk}</li>)}
+      </ul>
+    </VisuallyHidden>
+  );
+}
+
+export class SafePatchDiff extends Component<SafePatchDiffProps, SafePatchDiffState> {
+  state: SafePatchDiffState = { error: null };
+
+  static getDerivedStateFromError(error: unknown): SafePatchDiffState {
+    return { error: error instanceof Error ? error.message : String(error) };
+  }
+
+  componentDidCatch() {}
+
+  componentDidUpdate(prevProps: SafePatchDiffProps) {
+    if ((prevProps.patch !== this.props.patch || prevProps.diffStyle !== this.props.diffStyle) && this.state.error) {
+      this.setState({ error: null });
+    }
+  }
+
+  render() {
+    if (this.state.error) {
+      return (
+        <Stack gap="sm">
+          <Alert color="yellow">The rich diff renderer failed for this patch. Showing the raw patch instead.</Alert>
+          <Text size="xs" c="dimmed">{this.state.error}</Text>
+          <Code block>{this.props.patch}</Code>
+        </Stack>
+      );
+    }
+
+    return (
+      <div role="region" aria-label="Diff">
+        <PatchScreenReaderSummary patch={this.props.patch} />
+        <div aria-hidden>
+          <PatchDiff
+            patch={this.props.patch}
+            options={{ theme: { dark: 'pierre-dark', light: 'pierre-light' }, diffStyle: this.props.diffStyle }}
+          />
+        </div>
+      </div>
+    );
+  }
+}
@@ -0,0 +1,134 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Button, Group, Modal, NumberInput, Select, Stack, Switch, Text, TextInput } from '@mantine/core';
+import { IconPlus, IconX } from '@tabler/icons-react';
+import { patchAppSettings, type SaveHookRule, type SaveHookSettings, type TerminalPane } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type SaveHooksModalProps = {
+  opened: boolean;
+  onClose: () => void;
+  repoRef: string;
+  panes: TerminalPane[];
+  value: SaveHookSettings;
+  onSaved: (value: SaveHookSettings) => void;
+};
+
+export const EMPTY_SAVE_HOOKS: SaveHookSettings = { enabled: false, debounce_ms: 800, rules: [] };
+
+/** Per-repo rules that run a command in a terminal pane when matching files are saved through the app. */
+export function SaveHooksModal(props: SaveHooksModalProps) {
+  const { opened, onClose, repoRef, panes, value, onSaved } = props;
+  const [draft, setDraft] = useState<SaveHookSettings>(value);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (opened) {
+      setDraft(value);
+      setError(null);
+    }
+  }, [opened]);
+
+  function updateRule(index: number, patch: Partial<SaveHookRule>) {
+    setDraft((current) => ({ ...current, rules: current.rules.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)) }));
+  }
+
+  async function save() {
+    const next = { ...draft, rules: draft.rules.filter((rule) => rule.glob.trim() && rule.command.trim()) };
+    setSaving(true);
+    setError(null);
+    try {
+      await patchAppSettings({ terminal: { save_hooks_by_repo: { [repoRef]: next } } });
+      onSaved(next);
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  const paneOptions = panes.map((pane) => ({ value: pane.id, label: pane.label || pane.id }));
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Run on save" size="xl">
+      <Stack gap="sm">
+        <Text size="xs" c="dimmed">
+          When a file matching a pattern is saved from the editor or written by a ChangeSet apply, its command runs in the chosen pane once saves
+          have been quiet for the debounce period.
+        </Text>
+        <Group gap="md" align="end">
+          <Switch
+            label="Enabled"
+            checked={draft.enabled}
+            onChange={(event) => {
+              const enabled = event.currentTarget.checked;
+              setDraft((current) => ({ ...current, enabled }));
+            }}
+          />
+          <NumberInput
+            size="xs"
+            label="Debounce (ms)"
+            min={0}
+            max={60000}
+            step={100}
+            value={draft.debounce_ms}
+            onChange={(next) => setDraft((current) => ({ ...current, debounce_ms: typeof next === 'number' && next >= 0 ? next : 800 }))}
+            w={140}
+          />
+        </Group>
+        {draft.rules.map((rule, index) => (
+          <Group key={index} gap="xs" wrap="nowrap" align="end">
+            <TextInput
+              size="xs"
+              label={index === 0 ? 'Files' : undefined}
+              placeholder="*.rs"
+              ff="monospace"
+              value={rule.glob}
+              onChange={(event) => updateRule(index, { glob: event.currentTarget.value })}
+              w={180}
+            />
+            <TextInput
+              size="xs"
+              label={index === 0 ? 'Command' : undefined}
+              placeholder="cargo check"
+              ff="monospace"
+              value={rule.command}
+              onChange={(event) => updateRule(index, { command: event.currentTarget.value })}
+              style={{ flex: 1 }}
+            />
+            <Select
+              size="xs"
+              label={index === 0 ? 'Pane' : undefined}
+              data={paneOptions}
+              value={paneOptions.some((option) => option.value === rule.pane) ? rule.pane : paneOptions[0]?.value ?? null}
+              onChange={(pane) => updateRule(index, { pane: pane ?? '' })}
+              allowDeselect={false}
+              w={140}
+            />
+            <ActionIcon
+              variant="subtle"
+              color="gray"
+              aria-label="Remove rule"
+              onClick={() => setDraft((current) => ({ ...current, rules: current.rules.filter((_, i) => i !== index) }))}
+            >
+              <IconX size={14} />
+            </ActionIcon>
+          </Group>
+        ))}
+        <Group justify="space-between">
+          <Button
+            size="xs"
+            variant="subtle"
+            leftSection={<IconPlus size={14} />}
+            onClick={() => setDraft((current) => ({ ...current, rules: [...current.rules, { glob: '', command: '', pane: panes[0]?.id ?? '' }] }))}
+          >
+            Add rule
+          </Button>
+          <Button size="xs" onClick={() => void save()} loading={saving} disabled={!repoRef.trim()}>Save</Button>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+      </Stack>
+    </Modal>
+  );
+}
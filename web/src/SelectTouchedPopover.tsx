@@ -0,0 +1,83 @@
+import { useState } from 'react';
+import { Alert, Button, Group, Popover, SegmentedControl, Stack, Text, TextInput, Textarea } from '@mantine/core';
+import { getRepoTouchedPaths, type TouchedPathsSource } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type SelectTouchedPopoverProps = {
+  repoRef: string;
+  gitRef: string;
+  onSelect: (paths: string[]) => void;
+};
+
+export function SelectTouchedPopover(props: SelectTouchedPopoverProps) {
+  const { repoRef, gitRef, onSelect } = props;
+  const [opened, setOpened] = useState(false);
+  const [source, setSource] = useState<TouchedPathsSource>('range');
+  const [text, setText] = useState('HEAD~1..HEAD');
+  const [missing, setMissing] = useState<string[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function apply() {
+    setBusy(true);
+    setError(null);
+    setMissing([]);
+    try {
+      const response = await getRepoTouchedPaths(repoRef, gitRef, source, text);
+      onSelect(response.paths);
+      setMissing(response.missing);
+      if (!response.missing.length) setOpened(false);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Popover opened={opened} onChange={setOpened} width={460} position="bottom-start" withArrow shadow="md" zIndex={400}>
+      <Popover.Target>
+        <Button size="xs" variant="light" onClick={() => setOpened((value) => !value)} disabled={!repoRef.trim()}>
+          Select files touched by…
+        </Button>
+      </Popover.Target>
+      <Popover.Dropdown>
+        <Stack gap="xs">
+          <SegmentedControl
+            size="xs"
+            value={source}
+            onChange={(value) => {
+              setSource(value as TouchedPathsSource);
+              if (value === 'range' && !text.trim()) setText('HEAD~1..HEAD');
+              else if (value !== 'range' && text === 'HEAD~1..HEAD') setText('');
+            }}
+            data={[{ label: 'Ref range', value: 'range' }, { label: 'ChangeSet', value: 'changeset' }, { label: 'Patch', value: 'patch' }]}
+          />
+          {source === 'range' ? (
+            <TextInput size="xs" label="Range" placeholder="HEAD~3..HEAD" value={text} onChange={(event) => setText(event.currentTarget.value)} />
+          ) : (
+            <Textarea
+              size="xs"
+              label={source === 'changeset' ? 'ChangeSet payload' : 'Unified diff'}
+              placeholder={source === 'changeset' ? '{"version": 1, "operations": [...]}' : 'diff --git a/src/main.rs b/src/main.rs'}
+              value={text}
+              onChange={(event) => setText(event.currentTarget.value)}
+              minRows={6}
+              maxRows={14}
+              autosize
+              styles={{ input: { fontFamily: 'monospace' } }}
+            />
+          )}
+          <Text size="xs" c="dimmed">Replaces the current selection with exactly the touched paths that exist at {gitRef || 'WORKTREE'}.</Text>
+          {error ? <ErrorPanel message={error} /> : null}
+          {missing.length ? (
+            <Alert color="yellow">Skipped {missing.length} path{missing.length === 1 ? '' : 's'} not present: {missing.slice(0, 8).join(', ')}{missing.length > 8 ? '…' : ''}</Alert>
+          ) : null}
+          <Group justify="flex-end">
+            <Button size="xs" onClick={() => void apply()} loading={busy} disabled={!text.trim()}>Select</Button>
+          </Group>
+        </Stack>
+      </Popover.Dropdown>
+    </Popover>
+  );
+}
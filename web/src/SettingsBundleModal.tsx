@@ -0,0 +1,187 @@
+import { useEffect, useState } from 'react';
+import { Alert, Badge, Button, Checkbox, Divider, FileInput, Group, Modal, PasswordInput, Stack, Text } from '@mantine/core';
+import { exportSettingsBundle, importSettingsBundle, type SettingsBundle } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type SettingsBundleModalProps = {
+  opened: boolean;
+  onClose: () => void;
+};
+
+/** Mirrors the sensitive flags in the settings route: credentials, launched programs and exposed servers. */
+const SENSITIVE_SECTIONS = ['browser', 'network', 'terminal', 'updates', 'plugins', 'automation', 'mcp', 'debug'];
+
+function downloadJson(filename: string, value: unknown) {
+  const url = URL.createObjectURL(new Blob([JSON.stringify(value, null, 2)], { type: 'application/json' }));
+  const link = document.createElement('a');
+  link.href = url;
+  link.download = filename;
+  link.click();
+  URL.revokeObjectURL(url);
+}
+
+export function SettingsBundleModal(props: SettingsBundleModalProps) {
+  const { opened, onClose } = props;
+  const [includeSensitive, setIncludeSensitive] = useState(false);
+  const [exportPassphrase, setExportPassphrase] = useState('');
+  const [applySensitive, setApplySensitive] = useState(false);
+  const [importPassphrase, setImportPassphrase] = useState('');
+  const [includeTemplates, setIncludeTemplates] = useState(true);
+  const [bundle, setBundle] = useState<SettingsBundle | null>(null);
+  const [sections, setSections] = useState<string[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!opened) return;
+    setBundle(null);
+    setSections([]);
+    setExportPassphrase('');
+    setApplySensitive(false);
+    setImportPassphrase('');
+    setMessage(null);
+    setError(null);
+  }, [opened]);
+
+  async function handleExport() {
+    setBusy(true);
+    setError(null);
+    try {
+      const exported = await exportSettingsBundle({
+        include_sensitive: includeSensitive,
+        passphrase: includeSensitive && exportPassphrase ? exportPassphrase : null,
+        include_templates: includeTemplates
+      });
+      downloadJson(`mdev-settings-${exported.exported_at.slice(0, 10)}.json`, exported);
+      const sectionCount = Object.keys(exported.settings).length + (exported.encrypted?.sections.length ?? 0);
+      setMessage(`Exported ${sectionCount} sections${exported.encrypted ? ' (sensitive ones encrypted)' : ''} and ${exported.templates.length} templates.`);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleFile(file: File | null) {
+    setBundle(null);
+    setMessage(null);
+    setError(null);
+    if (!file) return;
+    try {
+      const parsed = JSON.parse(await file.text()) as SettingsBundle;
+      if (!parsed || typeof parsed.settings !== 'object') throw new Error('File does not contain a settings bundle.');
+      setBundle({ ...parsed, templates: parsed.templates ?? [] });
+      setSections([...Object.keys(parsed.settings), ...(parsed.encrypted?.sections ?? [])].filter((section) => !SENSITIVE_SECTIONS.includes(section)));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  async function handleImport() {
+    if (!bundle) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const result = await importSettingsBundle({
+        bundle,
+        sections,
+        include_sensitive: applySensitive,
+        passphrase: applySensitive && importPassphrase ? importPassphrase : null,
+        include_templates: includeTemplates
+      });
+      const skipped = result.skipped.length ? ` Skipped ${result.skipped.join(', ')}.` : '';
+      setMessage(`Imported ${result.sections.length} sections and ${result.templates.length} templates.${skipped} Reload to apply layout changes.`);
+      setBundle(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Settings bundle" size="lg" centered>
+      <Stack gap="sm">
+        {error ? <ErrorPanel message={error} /> : null}
+        {message ? <Alert color="green">{message}</Alert> : null}
+        <Text size="sm" c="dimmed">
+          Move workspace layouts, tree filters, canvas layouts, inference limits and workflow templates to another machine as a single JSON file.
+        </Text>
+        <Checkbox label="Include workflow templates" checked={includeTemplates} onChange={(event) => setIncludeTemplates(event.currentTarget.checked)} />
+
+        <Divider label="Export" labelPosition="left" />
+        <Checkbox
+          label="Include sensitive settings"
+          description="Network, terminal, browser, update, plugin, automation, MCP and debug settings can contain credentials or launch programs. Access tokens are never exported."
+          checked={includeSensitive}
+          onChange={(event) => setIncludeSensitive(event.currentTarget.checked)}
+        />
+        {includeSensitive ? (
+          <PasswordInput
+            size="sm"
+            label="Passphrase"
+            description="Encrypts the sensitive sections. Leave empty to write them in plain text."
+            value={exportPassphrase}
+            onChange={(event) => setExportPassphrase(event.currentTarget.value)}
+          />
+        ) : null}
+        <Group justify="flex-end">
+          <Button variant="light" loading={busy && !bundle} onClick={() => void handleExport()}>Export settings bundle</Button>
+        </Group>
+
+        <Divider label="Import" labelPosition="left" />
+        <FileInput size="sm" accept="application/json,.json" placeholder="Choose a bundle file" clearable onChange={(file) => void handleFile(file)} />
+        {bundle ? (
+          <Stack gap="xs">
+            <Group gap="xs">
+              <Badge variant="light">v{bundle.version}</Badge>
+              <Text size="xs" c="dimmed">Exported {new Date(bundle.exported_at).toLocaleString()}</Text>
+              {bundle.includes_sensitive ? <Badge color="orange" variant="light">contains sensitive sections</Badge> : null}
+            </Group>
+            <Group gap="md">
+              {[...Object.keys(bundle.settings), ...(bundle.encrypted?.sections ?? [])].map((section) => (
+                <Checkbox
+                  key={section}
+                  size="xs"
+                  label={SENSITIVE_SECTIONS.includes(section) ? `${section} (sensitive)` : section}
+                  disabled={SENSITIVE_SECTIONS.includes(section) && !applySensitive}
+                  checked={sections.includes(section)}
+                  onChange={(event) => {
+                    const checked = event.currentTarget.checked;
+                    setSections((prev) => (checked ? [...prev, section] : prev.filter((item) => item !== section)));
+                  }}
+                />
+              ))}
+            </Group>
+            {bundle.encrypted || Object.keys(bundle.settings).some((section) => SENSITIVE_SECTIONS.includes(section)) ? (
+              <Checkbox
+                size="xs"
+                color="orange"
+                label="Apply sensitive sections"
+                description="They can change which programs the app runs, where it downloads updates from and what it exposes. Only apply bundles you trust."
+                checked={applySensitive}
+                onChange={(event) => {
+                  const checked = event.currentTarget.checked;
+                  setApplySensitive(checked);
+                  if (!checked) setSections((prev) => prev.filter((section) => !SENSITIVE_SECTIONS.includes(section)));
+                }}
+              />
+            ) : null}
+            {bundle.encrypted && applySensitive ? (
+              <PasswordInput size="xs" label="Bundle passphrase" value={importPassphrase} onChange={(event) => setImportPassphrase(event.currentTarget.value)} />
+            ) : null}
+            <Text size="xs" c="dimmed">
+              {bundle.templates.length} template{bundle.templates.length === 1 ? '' : 's'}; templates with the same name are replaced.
+            </Text>
+            <Group justify="flex-end">
+              <Button loading={busy} disabled={!sections.length && !(includeTemplates && bundle.templates.length)} onClick={() => void handleImport()}>
+                Import bundle
+              </Button>
+            </Group>
+          </Stack>
+        ) : null}
+      </Stack>
+    </Modal>
+  );
+}
@@ -0,0 +1,340 @@
+import { useEffect, useMemo, useState } from 'react';
+import { Anchor, Badge, Box, Breadcrumbs, Button, Card, Group, Menu, SegmentedControl, Stack, Text, Title, Tooltip } from '@mantine/core';
+import { useElementSize } from '@mantine/hooks';
+import { exportAnalysisReport, getFileSizes, type AnalysisReportFormat, type FileSizeEntry } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type Metric = 'bytes' | 'lines';
+
+const REPORT_CONTENT_TYPES: Record<AnalysisReportFormat, string> = {
+  json: 'application/json',
+  html: 'text/html',
+  csv_extensions: 'text/csv',
+  csv_directories: 'text/csv',
+};
+
+function downloadTextFile(filename: string, contents: string, contentType: string) {
+  const blob = new Blob([contents], { type: contentType });
+  const url = URL.createObjectURL(blob);
+  const anchor = document.createElement('a');
+  anchor.href = url;
+  anchor.download = filename;
+  document.body.appendChild(anchor);
+  anchor.click();
+  anchor.remove();
+  URL.revokeObjectURL(url);
+}
+
+type TreemapNode = {
+  name: string;
+  path: string;
+  kind: 'dir' | 'file';
+  bytes: number;
+  lines: number;
+  language: string;
+  children: TreemapNode[];
+};
+
+type Rect = { x: number; y: number; w: number; h: number };
+type Placed = { node: TreemapNode; rect: Rect };
+
+const LANGUAGE_COLORS: Record<string, string> = {
+  Rust: 'var(--mantine-color-orange-8)',
+  TypeScript: 'var(--mantine-color-blue-8)',
+  JavaScript: 'var(--mantine-color-yellow-8)',
+  Python: 'var(--mantine-color-indigo-8)',
+  Go: 'var(--mantine-color-cyan-8)',
+  JVM: 'var(--mantine-color-red-8)',
+  'C/C++': 'var(--mantine-color-grape-8)',
+  'C#': 'var(--mantine-color-violet-8)',
+  Ruby: 'var(--mantine-color-pink-8)',
+  ABAP: 'var(--mantine-color-teal-8)',
+  Docs: 'var(--mantine-color-gray-7)',
+  Config: 'var(--mantine-color-lime-9)',
+  Web: 'var(--mantine-color-green-8)',
+  Shell: 'var(--mantine-color-dark-3)',
+  SQL: 'var(--mantine-color-cyan-9)',
+  Other: 'var(--mantine-color-dark-4)',
+};
+
+const HEADER_HEIGHT = 16;
+
+function formatBytes(bytes: number) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} B`;
+}
+
+function buildHierarchy(files: FileSizeEntry[]): TreemapNode {
+  const root: TreemapNode = { name: '', path: '', kind: 'dir', bytes: 0, lines: 0, language: 'Other', children: [] };
+  const dirs = new Map<string, TreemapNode>([['', root]]);
+
+  for (const file of files) {
+    const parts = file.path.split('/');
+    let parent = root;
+    for (let index = 0; index < parts.length - 1; index += 1) {
+      const path = parts.slice(0, index + 1).join('/');
+      let dir = dirs.get(path);
+      if (!dir) {
+        dir = { name: parts[index], path, kind: 'dir', bytes: 0, lines: 0, language: 'Other', children: [] };
+        dirs.set(path, dir);
+        parent.children.push(dir);
+      }
+      parent = dir;
+    }
+    parent.children.push({ name: parts[parts.length - 1], path: file.path, kind: 'file', bytes: file.bytes, lines: file.lines, language: file.language, children: [] });
+  }
+
+  const finalize = (node: TreemapNode): Record<string, number> => {
+    if (node.kind === 'file') return { [node.language]: node.bytes };
+    const weights: Record<string, number> = {};
+    for (const child of node.children) {
+      const childWeights = finalize(child);
+      node.bytes += child.bytes;
+      node.lines += child.lines;
+      for (const [language, weight] of Object.entries(childWeights)) weights[language] = (weights[language] ?? 0) + weight;
+    }
+    node.language = Object.entries(weights).sort((a, b) => b[1] - a[1])[0]?.[0] ?? 'Other';
+    return weights;
+  };
+  finalize(root);
+  return root;
+}
+
+/** Squarified treemap layout (Bruls et al.), biggest tiles first. */
+function squarify(nodes: TreemapNode[], metric: Metric, rect: Rect): Placed[] {
+  const items = nodes.filter((node) => node[metric] > 0).sort((a, b) => b[metric] - a[metric]);
+  const total = items.reduce((sum, node) => sum + node[metric], 0);
+  if (!total || rect.w <= 0 || rect.h <= 0) return [];
+
+  const scale = (rect.w * rect.h) / total;
+  const areas = items.map((node) => ({ node, area: node[metric] * scale }));
+  const out: Placed[] = [];
+  let free = { ...rect };
+  let row: typeof areas = [];
+
+  const worst = (candidate: typeof areas, side: number) => {
+    const sum = candidate.reduce((acc, item) => acc + item.area, 0);
+    const max = Math.max(...candidate.map((item) => item.area));
+    const min = Math.min(...candidate.map((item) => item.area));
+    return Math.max((side * side * max) / (sum * sum), (sum * sum) / (side * side * min));
+  };
+
+  const placeRow = () => {
+    const sum = row.reduce((acc, item) => acc + item.area, 0);
+    if (free.w >= free.h) {
+      const width = sum / free.h;
+      let y = free.y;
+      for (const item of row) {
+        const height = item.area / width;
+        out.push({ node: item.node, rect: { x: free.x, y, w: width, h: height } });
+        y += height;
+      }
+      free = { x: free.x + width, y: free.y, w: free.w - width, h: free.h };
+    } else {
+      const height = sum / free.w;
+      let x = free.x;
+      for (const item of row) {
+        const width = item.area / height;
+        out.push({ node: item.node, rect: { x, y: free.y, w: width, h: height } });
+        x += width;
+      }
+      free = { x: free.x, y: free.y + height, w: free.w, h: free.h - height };
+    }
+    row = [];
+  };
+
+  let index = 0;
+  while (index < areas.length) {
+    const side = Math.min(free.w, free.h);
+    const candidate = [...row, areas[index]];
+    if (row.length === 0 || worst(candidate, side) <= worst(row, side)) {
+      row = candidate;
+      index += 1;
+    } else {
+      placeRow();
+    }
+  }
+  if (row.length) placeRow();
+  return out;
+}
+
+type SizeTreemapProps = {
+  repoRef: string;
+  onOpenFile?: (path: string) => void;
+};
+
+export function SizeTreemap(props: SizeTreemapProps) {
+  const { repoRef, onOpenFile } = props;
+  const { ref, width } = useElementSize();
+  const [files, setFiles] = useState<FileSizeEntry[]>([]);
+  const [metric, setMetric] = useState<Metric>('bytes');
+  const [focusPath, setFocusPath] = useState('');
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const height = 420;
+
+  async function load() {
+    if (!repoRef.trim()) return;
+    setLoading(true);
+    setError(null);
+    try {
+      const response = await getFileSizes(repoRef);
+      setFiles(response.files);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  async function exportReport(format: AnalysisReportFormat) {
+    try {
+      setError(null);
+      const response = await exportAnalysisReport(repoRef, format);
+      downloadTextFile(response.file_name, response.contents, REPORT_CONTENT_TYPES[format]);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    setFiles([]);
+    setFocusPath('');
+    void load();
+  }, [repoRef]);
+
+  const root = useMemo(() => buildHierarchy(files), [files]);
+
+  const focus = useMemo(() => {
+    let node = root;
+    for (const part of focusPath ? focusPath.split('/') : []) {
+      const next = node.children.find((child) => child.kind === 'dir' && child.name === part);
+      if (!next) break;
+      node = next;
+    }
+    return node;
+  }, [root, focusPath]);
+
+  const placed = useMemo(() => squarify(focus.children, metric, { x: 0, y: 0, w: width, h: height }), [focus, metric, width]);
+
+  const crumbs = focusPath ? focusPath.split('/') : [];
+  const legend = useMemo(() => {
+    const totals: Record<string, number> = {};
+    for (const file of files) totals[file.language] = (totals[file.language] ?? 0) + file[metric];
+    return Object.entries(totals).sort((a, b) => b[1] - a[1]).slice(0, 8);
+  }, [files, metric]);
+
+  const describe = (node: TreemapNode) =>
+    `${node.path || '/'} · ${formatBytes(node.bytes)} · ${node.lines.toLocaleString()} lines · ${node.language}`;
+
+  const activate = (node: TreemapNode) => {
+    if (node.kind === 'dir') setFocusPath(node.path);
+    else onOpenFile?.(node.path);
+  };
+
+  const renderTile = (entry: Placed, nested: boolean) => {
+    const { node, rect } = entry;
+    const showChildren = !nested && node.kind === 'dir' && rect.w > 48 && rect.h > HEADER_HEIGHT + 24;
+    const inner = showChildren
+      ? squarify(node.children, metric, { x: 0, y: 0, w: rect.w - 4, h: rect.h - HEADER_HEIGHT - 4 })
+      : [];
+    return (
+      <Tooltip key={node.path} label={describe(node)} openDelay={300} withinPortal>
+        <Box
+          onClick={(event) => {
+            event.stopPropagation();
+            activate(node);
+          }}
+          style={{
+            position: 'absolute',
+            left: rect.x,
+            top: rect.y,
+            width: Math.max(rect.w - 1, 0),
+            height: Math.max(rect.h - 1, 0),
+            background: LANGUAGE_COLORS[node.language] ?? LANGUAGE_COLORS.Other,
+            border: '1px solid rgba(0,0,0,0.45)',
+            overflow: 'hidden',
+            cursor: node.kind === 'dir' || onOpenFile ? 'pointer' : 'default',
+            opacity: nested ? 0.85 : 1,
+          }}
+        >
+          {rect.w > 36 && rect.h > 14 ? (
+            <Text size="10px" fw={node.kind === 'dir' ? 700 : 400} px={3} truncate style={{ lineHeight: `${HEADER_HEIGHT}px` }}>
+              {node.name}{node.kind === 'dir' ? '/' : ''}
+            </Text>
+          ) : null}
+          {inner.length ? (
+            <Box style={{ position: 'absolute', left: 2, top: HEADER_HEIGHT, right: 2, bottom: 2 }}>
+              {inner.map((child) => renderTile(child, true))}
+            </Box>
+          ) : null}
+        </Box>
+      </Tooltip>
+    );
+  };
+
+  return (
+    <Card withBorder>
+      <Stack gap="md">
+        <Group justify="space-between" align="flex-start" wrap="wrap">
+          <Stack gap={2}>
+            <Group gap="xs">
+              <Title order={4}>Size treemap</Title>
+              <Badge variant="light">{files.length} files</Badge>
+            </Group>
+            <Text size="sm" c="dimmed">Click a directory to zoom in, a file to open it. Colour marks the dominant language.</Text>
+          </Stack>
+          <Group gap="xs">
+            <SegmentedControl
+              size="xs"
+              value={metric}
+              onChange={(value) => setMetric(value as Metric)}
+              data={[
+                { value: 'bytes', label: 'Bytes' },
+                { value: 'lines', label: 'Lines' },
+              ]}
+            />
+            <Button variant="default" size="xs" onClick={() => void load()} loading={loading}>Rescan</Button>
+            <Menu withinPortal position="bottom-end">
+              <Menu.Target>
+                <Button variant="default" size="xs" disabled={!files.length}>Export</Button>
+              </Menu.Target>
+              <Menu.Dropdown>
+                <Menu.Item onClick={() => void exportReport('html')}>HTML report</Menu.Item>
+                <Menu.Item onClick={() => void exportReport('json')}>JSON</Menu.Item>
+                <Menu.Item onClick={() => void exportReport('csv_extensions')}>CSV by extension</Menu.Item>
+                <Menu.Item onClick={() => void exportReport('csv_directories')}>CSV by directory</Menu.Item>
+              </Menu.Dropdown>
+            </Menu>
+          </Group>
+        </Group>
+
+        {error ? <ErrorPanel message={error} /> : null}
+
+        <Breadcrumbs separator="/">
+          <Anchor size="sm" onClick={() => setFocusPath('')}>root</Anchor>
+          {crumbs.map((part, index) => (
+            <Anchor key={index} size="sm" onClick={() => setFocusPath(crumbs.slice(0, index + 1).join('/'))}>{part}</Anchor>
+          ))}
+        </Breadcrumbs>
+
+        <Box ref={ref} style={{ position: 'relative', height, borderRadius: 6, overflow: 'hidden', background: 'var(--mantine-color-dark-7)' }}>
+          {placed.map((entry) => renderTile(entry, false))}
+          {!loading && placed.length === 0 ? (
+            <Text size="sm" c="dimmed" p="md">{files.length ? 'Nothing to show for this metric.' : 'No files scanned yet.'}</Text>
+          ) : null}
+        </Box>
+
+        <Group gap="xs">
+          {legend.map(([language, value]) => (
+            <Group key={language} gap={4} wrap="nowrap">
+              <Box w={10} h={10} style={{ borderRadius: 2, background: LANGUAGE_COLORS[language] ?? LANGUAGE_COLORS.Other }} />
+              <Text size="xs" c="dimmed">{language} {metric === 'bytes' ? formatBytes(value) : `${value.toLocaleString()} lines`}</Text>
+            </Group>
+          ))}
+        </Group>
+      </Stack>
+    </Card>
+  );
+}
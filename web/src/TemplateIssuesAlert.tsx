@@ -0,0 +1,62 @@
+import { useEffect, useState } from 'react';
+import { Alert, Button, Code, CopyButton, Group, Modal, ScrollArea, Stack, Text } from '@mantine/core';
+import { listTemplateIssues, type TemplateLoadIssue } from './api';
+
+type TemplateIssuesAlertProps = {
+  opened: boolean;
+};
+
+function prettyJson(raw: string) {
+  try {
+    return JSON.stringify(JSON.parse(raw), null, 2);
+  } catch {
+    return raw;
+  }
+}
+
+/** Lists saved templates whose definitions could not be migrated, with access to the raw JSON. */
+export function TemplateIssuesAlert(props: TemplateIssuesAlertProps) {
+  const { opened } = props;
+  const [issues, setIssues] = useState<TemplateLoadIssue[]>([]);
+  const [viewing, setViewing] = useState<TemplateLoadIssue | null>(null);
+
+  useEffect(() => {
+    if (!opened) return;
+    listTemplateIssues().then(setIssues).catch(() => setIssues([]));
+  }, [opened]);
+
+  if (issues.length === 0) return null;
+
+  return (
+    <>
+      <Alert color="orange" title={`${issues.length} template${issues.length === 1 ? '' : 's'} could not be loaded`}>
+        <Stack gap={6}>
+          {issues.map((issue) => (
+            <Group key={issue.id} justify="space-between" wrap="nowrap" gap="xs">
+              <Stack gap={0} style={{ minWidth: 0 }}>
+                <Text size="sm" fw={600}>{issue.name}</Text>
+                <Text size="xs" c="dimmed">{issue.error}</Text>
+              </Stack>
+              <Button size="compact-xs" variant="light" onClick={() => setViewing(issue)}>Open raw JSON</Button>
+            </Group>
+          ))}
+        </Stack>
+      </Alert>
+      <Modal opened={!!viewing} onClose={() => setViewing(null)} title={viewing ? `Raw definition: ${viewing.name}` : ''} size="xl" centered zIndex={310}>
+        {viewing ? (
+          <Stack gap="sm">
+            <Alert color="orange">{viewing.error}</Alert>
+            <ScrollArea.Autosize mah="60vh" type="auto">
+              <Code block>{prettyJson(viewing.raw)}</Code>
+            </ScrollArea.Autosize>
+            <Group justify="flex-end">
+              <CopyButton value={viewing.raw}>
+                {({ copied, copy }) => <Button variant="light" onClick={copy}>{copied ? 'Copied' : 'Copy JSON'}</Button>}
+              </CopyButton>
+            </Group>
+          </Stack>
+        ) : null}
+      </Modal>
+    </>
+  );
+}
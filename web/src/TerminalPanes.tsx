@@ -0,0 +1,290 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Badge, Button, Group, ScrollArea, Select, SimpleGrid, Stack, Switch, Text, TextInput, Tooltip } from '@mantine/core';
+import { IconLayoutColumns, IconX } from '@tabler/icons-react';
+import {
+  getAppSettings,
+  listSaveHookRuns,
+  patchAppSettings,
+  type ProjectCommand,
+  type SaveHookRun,
+  type SaveHookSettings,
+  type TerminalPane,
+  type TerminalPaneLayout,
+} from './api';
+import { AnsiOutput } from './AnsiOutput';
+import { ErrorPanel } from './ErrorPanel';
+import { ProjectCommandChips } from './ProjectCommandChips';
+import { EMPTY_SAVE_HOOKS, SaveHooksModal } from './SaveHooksModal';
+
+type CommandRow = Record<string, unknown>;
+
+type TerminalPanesProps = {
+  repoRef: string;
+  /** Output of the stage's own compile commands, shown in the first pane. */
+  stageRows: CommandRow[];
+  runCommand: (pane: TerminalPane, command: { command: string; label: string }) => Promise<CommandRow[]>;
+  onOpenFile?: (path: string) => void;
+};
+
+const MAX_PANES = 4;
+const SAVE_HOOK_POLL_MS = 1500;
+const SHELL_OPTIONS = [
+  { value: '', label: 'Default shell' },
+  ...['sh', 'bash', 'zsh', 'fish', 'cmd', 'powershell', 'pwsh'].map((shell) => ({ value: shell, label: shell })),
+];
+
+function newPane(index: number): TerminalPane {
+  return { id: crypto.randomUUID(), label: `Pane ${index + 1}`, shell: '', working_dir: '' };
+}
+
+const DEFAULT_LAYOUT: TerminalPaneLayout = { broadcast: false, panes: [{ id: 'main', label: 'Main', shell: '', working_dir: '' }] };
+
+/** Side-by-side command panes, each with its own shell and directory; broadcast sends one command to all of them. */
+export function TerminalPanes(props: TerminalPanesProps) {
+  const { repoRef, stageRows, runCommand, onOpenFile } = props;
+  const [layout, setLayout] = useState<TerminalPaneLayout>(DEFAULT_LAYOUT);
+  const [activePaneId, setActivePaneId] = useState(DEFAULT_LAYOUT.panes[0].id);
+  const [rowsByPane, setRowsByPane] = useState<Record<string, CommandRow[]>>({});
+  const [busyByPane, setBusyByPane] = useState<Record<string, string>>({});
+  const [errorByPane, setErrorByPane] = useState<Record<string, string>>({});
+  const [inputByPane, setInputByPane] = useState<Record<string, string>>({});
+  const [saveHooks, setSaveHooks] = useState<SaveHookSettings>(EMPTY_SAVE_HOOKS);
+  const [saveHookRuns, setSaveHookRuns] = useState<SaveHookRun[]>([]);
+  const [saveHooksOpen, setSaveHooksOpen] = useState(false);
+
+  useEffect(() => {
+    setLayout(DEFAULT_LAYOUT);
+    setSaveHooks(EMPTY_SAVE_HOOKS);
+    if (!repoRef.trim()) return;
+    void getAppSettings()
+      .then((settings) => {
+        const saved = settings.workspace?.terminal_panes?.[repoRef];
+        if (saved && Array.isArray(saved.panes) && saved.panes.length) {
+          setLayout({ broadcast: Boolean(saved.broadcast), panes: saved.panes.slice(0, MAX_PANES) });
+          setActivePaneId(saved.panes[0].id);
+        }
+        setSaveHooks({ ...EMPTY_SAVE_HOOKS, ...(settings.terminal?.save_hooks_by_repo?.[repoRef] ?? {}) });
+      })
+      .catch(() => undefined);
+  }, [repoRef]);
+
+  const hooksActive = saveHooks.enabled && saveHooks.rules.length > 0;
+
+  // Polls whenever rules exist, since the editor's status bar can switch them on without this pane knowing.
+  useEffect(() => {
+    if (!saveHooks.rules.length || !repoRef.trim()) return;
+    const poll = () => void listSaveHookRuns(repoRef).then(setSaveHookRuns).catch(() => undefined);
+    poll();
+    const timer = window.setInterval(poll, SAVE_HOOK_POLL_MS);
+    return () => window.clearInterval(timer);
+  }, [saveHooks.rules.length, repoRef]);
+
+  function updateLayout(next: TerminalPaneLayout) {
+    setLayout(next);
+    if (!repoRef.trim()) return;
+    void patchAppSettings({ workspace: { terminal_panes: { [repoRef]: next } } }).catch(() => undefined);
+  }
+
+  function updatePane(id: string, patch: Partial<TerminalPane>) {
+    updateLayout({ ...layout, panes: layout.panes.map((pane) => (pane.id === id ? { ...pane, ...patch } : pane)) });
+  }
+
+  function removePane(id: string) {
+    const panes = layout.panes.filter((pane) => pane.id !== id);
+    updateLayout({ ...layout, panes });
+    if (activePaneId === id && panes.length) setActivePaneId(panes[0].id);
+  }
+
+  async function runIn(pane: TerminalPane, command: { command: string; label: string }) {
+    setBusyByPane((current) => ({ ...current, [pane.id]: command.command }));
+    setErrorByPane(({ [pane.id]: _cleared, ...rest }) => rest);
+    try {
+      const rows = await runCommand(pane, command);
+      setRowsByPane((current) => ({ ...current, [pane.id]: [...rows, ...(current[pane.id] ?? [])] }));
+    } catch (err) {
+      setErrorByPane((current) => ({ ...current, [pane.id]: err instanceof Error ? err.message : String(err) }));
+    } finally {
+      setBusyByPane(({ [pane.id]: _done, ...rest }) => rest);
+    }
+  }
+
+  /** Runs in the given pane, or in every idle pane when broadcasting. */
+  function dispatch(pane: TerminalPane, command: { command: string; label: string }) {
+    const targets = layout.broadcast ? layout.panes.filter((item) => !busyByPane[item.id]) : [pane];
+    for (const target of targets) void runIn(target, command);
+  }
+
+  function submit(pane: TerminalPane) {
+    const command = (inputByPane[pane.id] ?? '').trim();
+    if (!command) return;
+    setInputByPane((current) => ({ ...current, [pane.id]: '' }));
+    dispatch(pane, { command, label: command });
+  }
+
+  const activePane = layout.panes.find((pane) => pane.id === activePaneId) ?? layout.panes[0];
+
+  /** Hook runs for a pane; runs for a pane that no longer exists show in the first one. */
+  function hookRunsFor(pane: TerminalPane, index: number) {
+    return saveHookRuns.filter((run) => run.pane === pane.id || (index === 0 && !layout.panes.some((item) => item.id === run.pane)));
+  }
+
+  return (
+    <Stack gap="xs" style={{ flex: 1, minHeight: 0 }}>
+      <Group justify="space-between" wrap="nowrap">
+        <ProjectCommandChips
+          repoRef={repoRef}
+          label={layout.broadcast ? 'Run in all panes' : `Run in ${activePane?.label ?? 'pane'}`}
+          busyCommand={activePane ? busyByPane[activePane.id] ?? null : null}
+          onPick={(command: ProjectCommand) => activePane && dispatch(activePane, { command: command.command, label: command.label })}
+        />
+        <Group gap="xs" wrap="nowrap">
+          <Button size="compact-xs" variant={hooksActive ? 'light' : 'subtle'} onClick={() => setSaveHooksOpen(true)} disabled={!repoRef.trim()}>
+            {hooksActive ? `On save: ${saveHooks.rules.length}` : 'On save…'}
+          </Button>
+          <Switch
+            size="xs"
+            label="Broadcast input"
+            checked={layout.broadcast}
+            onChange={(event) => updateLayout({ ...layout, broadcast: event.currentTarget.checked })}
+          />
+          <Tooltip label="Split: add a pane">
+            <ActionIcon
+              variant="light"
+              aria-label="Split terminal"
+              disabled={layout.panes.length >= MAX_PANES}
+              onClick={() => {
+                const pane = newPane(layout.panes.length);
+                updateLayout({ ...layout, panes: [...layout.panes, pane] });
+                setActivePaneId(pane.id);
+              }}
+            >
+              <IconLayoutColumns size={16} />
+            </ActionIcon>
+          </Tooltip>
+        </Group>
+      </Group>
+      <SimpleGrid cols={Math.max(layout.panes.length, 1)} spacing="xs" style={{ flex: 1, minHeight: 0 }}>
+        {layout.panes.map((pane, index) => {
+          const hookRuns = hookRunsFor(pane, index);
+          const hookRows = hookRuns
+            .filter((run) => run.status !== null)
+            .map((run) => ({ label: `on save ${run.paths.join(', ')}`, command: run.command, status: run.status, stdout: run.stdout, stderr: run.stderr }));
+          const rows = [...hookRows, ...(rowsByPane[pane.id] ?? []), ...(index === 0 ? stageRows : [])];
+          const busy = busyByPane[pane.id] ?? hookRuns.find((run) => run.status === null)?.command;
+          const active = pane.id === activePane?.id;
+          return (
+            <Stack
+              key={pane.id}
+              gap={6}
+              p={6}
+              onFocusCapture={() => setActivePaneId(pane.id)}
+              onMouseDown={() => setActivePaneId(pane.id)}
+              style={{
+                minWidth: 0,
+                minHeight: 0,
+                border: `1px solid var(--mantine-color-${active && layout.panes.length > 1 ? 'blue-5' : 'default-border'})`,
+                borderRadius: 'var(--mantine-radius-sm)',
+              }}
+            >
+              <Group gap={6} wrap="nowrap">
+                <TextInput
+                  size="xs"
+                  variant="unstyled"
+                  value={pane.label}
+                  onChange={(event) => updatePane(pane.id, { label: event.currentTarget.value })}
+                  aria-label="Pane name"
+                  style={{ flex: 1, minWidth: 0 }}
+                />
+                {busy ? <Badge size="xs" variant="light">running</Badge> : null}
+                {layout.panes.length > 1 ? (
+                  <ActionIcon size="xs" variant="subtle" color="gray" aria-label="Close pane" onClick={() => removePane(pane.id)}>
+                    <IconX size={12} />
+                  </ActionIcon>
+                ) : null}
+              </Group>
+              <Group gap={6} wrap="nowrap" grow>
+                <Select
+                  size="xs"
+                  data={SHELL_OPTIONS}
+                  value={pane.shell}
+                  onChange={(value) => updatePane(pane.id, { shell: value ?? '' })}
+                  allowDeselect={false}
+                  aria-label="Shell"
+                />
+                <TextInput
+                  size="xs"
+                  placeholder="Repo root"
+                  value={pane.working_dir}
+                  onChange={(event) => updatePane(pane.id, { working_dir: event.currentTarget.value })}
+                  aria-label="Working directory"
+                />
+              </Group>
+              <Group gap={6} wrap="nowrap">
+                <TextInput
+                  size="xs"
+                  ff="monospace"
+                  placeholder={layout.broadcast ? 'Command for all panes' : 'Command'}
+                  value={inputByPane[pane.id] ?? ''}
+                  onChange={(event) => {
+                    const value = event.currentTarget.value;
+                    setInputByPane((current) => ({ ...current, [pane.id]: value }));
+                  }}
+                  onKeyDown={(event) => {
+                    if (event.key === 'Enter') submit(pane);
+                  }}
+                  style={{ flex: 1 }}
+                />
+                <Button size="compact-xs" variant="light" loading={Boolean(busy)} onClick={() => submit(pane)}>Run</Button>
+              </Group>
+              {errorByPane[pane.id] ? <ErrorPanel message={errorByPane[pane.id]} /> : null}
+              <ScrollArea style={{ flex: 1, minHeight: 220 }} offsetScrollbars>
+                <Stack gap="sm">
+                  {!rows.length ? (
+                    <Text size="sm" c="dimmed">{index === 0 ? 'No command output yet. Run the stage or pick a detected command above.' : 'No command output yet.'}</Text>
+                  ) : null}
+                  {rows.map((row, rowIndex) => {
+                    const command = typeof row.command === 'string' ? row.command : '';
+                    const label = typeof row.label === 'string' && row.label.trim() ? row.label.trim() : command || 'compile command';
+                    const status = typeof row.status === 'number' ? row.status : Number(row.status ?? -1);
+                    return (
+                      <Stack key={rowIndex} gap={6}>
+                        <Group gap="xs">
+                          {row.skipped === true ? (
+                            <Badge color="gray" variant="light">skipped</Badge>
+                          ) : row.timed_out === true ? (
+                            <Badge color="orange" variant="light">timed out</Badge>
+                          ) : (
+                            <Badge color={status === 0 ? 'green' : 'red'} variant="light">exit {Number.isFinite(status) ? status : -1}</Badge>
+                          )}
+                          <Text size="sm" fw={600}>{label}</Text>
+                          {command && command !== label ? <Text size="xs" c="dimmed" ff="monospace">{command}</Text> : null}
+                          {typeof row.duration_ms === 'number' && row.skipped !== true ? (
+                            <Text size="xs" c="dimmed">{(row.duration_ms / 1000).toFixed(1)}s</Text>
+                          ) : null}
+                        </Group>
+                        {typeof row.stdout === 'string' && row.stdout.trim() ? (
+                          <AnsiOutput label="stdout" text={row.stdout} onOpenFile={onOpenFile} />
+                        ) : null}
+                        {typeof row.stderr === 'string' && row.stderr.trim() ? (
+                          <AnsiOutput label="stderr" text={row.stderr} onOpenFile={onOpenFile} />
+                        ) : null}
+                      </Stack>
+                    );
+                  })}
+                </Stack>
+              </ScrollArea>
+            </Stack>
+          );
+        })}
+      </SimpleGrid>
+      <SaveHooksModal
+        opened={saveHooksOpen}
+        onClose={() => setSaveHooksOpen(false)}
+        repoRef={repoRef}
+        panes={layout.panes}
+        value={saveHooks}
+        onSaved={setSaveHooks}
+      />
+    </Stack>
+  );
+}
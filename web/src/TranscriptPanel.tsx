@@ -0,0 +1,243 @@
+import { useEffect, useLayoutEffect, useMemo, useRef, useState } from 'react';
+import { ActionIcon, Badge, Box, Button, Group, Stack, Text, TextInput, Tooltip } from '@mantine/core';
+import { IconArrowDown, IconChevronDown, IconChevronRight, IconMarkdown, IconSearch } from '@tabler/icons-react';
+import type { WorkflowEvent } from './api';
+import { CodeBlock, MarkdownMessage } from './MarkdownMessage';
+import { getOrCompute } from './sharedRenderCache';
+
+export type TranscriptMessage = {
+  id: string;
+  role: 'user' | 'assistant';
+  stepId: string | null;
+  createdAt: string;
+  text: string;
+};
+
+type Segment = { kind: 'text'; text: string } | { kind: 'code'; language: string; text: string };
+
+const COLLAPSED_ROW_HEIGHT = 64;
+const ESTIMATED_EXPANDED_HEIGHT = 320;
+const OVERSCAN_PX = 600;
+const AUTO_COLLAPSE_CHARS = 2000;
+
+/** Pulls prompt/response pairs (and imported chat messages) out of stage events in chronological order. */
+export function buildTranscriptMessages(events: WorkflowEvent[]): TranscriptMessage[] {
+  const messages: TranscriptMessage[] = [];
+  for (const event of events) {
+    const payload = (event.payload ?? {}) as Record<string, unknown>;
+    if (event.kind === 'conversation_imported' && Array.isArray(payload.messages)) {
+      (payload.messages as Array<{ role?: string; text?: string }>).forEach((message, index) => {
+        if (typeof message.text !== 'string' || !message.text.trim()) return;
+        const role = message.role === 'assistant' ? 'assistant' : 'user';
+        messages.push({ id: `${event.id}:import:${index}`, role, stepId: event.step_id, createdAt: event.created_at, text: message.text });
+      });
+      continue;
+    }
+    const capabilityResults = Array.isArray(payload.capability_results)
+      ? (payload.capability_results as Array<Record<string, unknown>>)
+      : [];
+    capabilityResults
+      .filter((entry) => entry?.key === 'inference')
+      .forEach((entry, index) => {
+        const result = (entry.result ?? {}) as Record<string, unknown>;
+        const prompt = typeof result.prompt === 'string' ? result.prompt : '';
+        const nested = (result.result ?? {}) as Record<string, unknown>;
+        const response = typeof nested.text === 'string' ? nested.text : '';
+        if (prompt.trim()) {
+          messages.push({ id: `${event.id}:${index}:user`, role: 'user', stepId: event.step_id, createdAt: event.created_at, text: prompt });
+        }
+        if (response.trim()) {
+          messages.push({ id: `${event.id}:${index}:assistant`, role: 'assistant', stepId: event.step_id, createdAt: event.created_at, text: response });
+        }
+      });
+  }
+  return messages;
+}
+
+function splitSegments(text: string): Segment[] {
+  const segments: Segment[] = [];
+  const fence = /```([^\n`]*)\n([\s\S]*?)```/g;
+  let last = 0;
+  let match: RegExpExecArray | null;
+  while ((match = fence.exec(text)) !== null) {
+    if (match.index > last) segments.push({ kind: 'text', text: text.slice(last, match.index) });
+    segments.push({ kind: 'code', language: match[1].trim(), text: match[2].replace(/\n$/, '') });
+    last = match.index + match[0].length;
+  }
+  if (last < text.length) segments.push({ kind: 'text', text: text.slice(last) });
+  return segments;
+}
+
+function MessageBody(props: { text: string }) {
+  const segments = getOrCompute('transcript-segments', [], props.text, () => splitSegments(props.text));
+  return (
+    <Stack gap={6}>
+      {segments.map((segment, index) =>
+        segment.kind === 'code' ? (
+          <CodeBlock key={index} language={segment.language} text={segment.text} />
+        ) : (
+          <Text key={index} size="sm" style={{ whiteSpace: 'pre-wrap', overflowWrap: 'anywhere' }}>{segment.text}</Text>
+        )
+      )}
+    </Stack>
+  );
+}
+
+type TranscriptPanelProps = {
+  messages: TranscriptMessage[];
+  height?: number | string;
+};
+
+export function TranscriptPanel(props: TranscriptPanelProps) {
+  const { messages, height = 'calc(100vh - 220px)' } = props;
+  const [query, setQuery] = useState('');
+  const [toggled, setToggled] = useState<Record<string, boolean>>({});
+  const [raw, setRaw] = useState<Record<string, boolean>>({});
+  const [measured, setMeasured] = useState<Record<string, number>>({});
+  const [scrollTop, setScrollTop] = useState(0);
+  const [viewportHeight, setViewportHeight] = useState(600);
+  const [stickToBottom, setStickToBottom] = useState(true);
+  const scrollRef = useRef<HTMLDivElement | null>(null);
+
+  const visible = useMemo(() => {
+    const needle = query.trim().toLowerCase();
+    return needle ? messages.filter((message) => message.text.toLowerCase().includes(needle)) : messages;
+  }, [messages, query]);
+
+  const isCollapsed = (message: TranscriptMessage) => {
+    const byDefault = message.text.length > AUTO_COLLAPSE_CHARS;
+    return toggled[message.id] ? !byDefault : byDefault;
+  };
+
+  const offsets = useMemo(() => {
+    const out: number[] = [];
+    let top = 0;
+    for (const message of visible) {
+      out.push(top);
+      top += isCollapsed(message) ? COLLAPSED_ROW_HEIGHT : measured[message.id] ?? ESTIMATED_EXPANDED_HEIGHT;
+    }
+    out.push(top);
+    return out;
+  }, [visible, toggled, measured]);
+
+  const totalHeight = offsets[offsets.length - 1] ?? 0;
+  let first = 0;
+  while (first < visible.length && offsets[first + 1] < scrollTop - OVERSCAN_PX) first += 1;
+  let last = first;
+  while (last < visible.length && offsets[last] < scrollTop + viewportHeight + OVERSCAN_PX) last += 1;
+
+  useLayoutEffect(() => {
+    const node = scrollRef.current;
+    if (!node) return;
+    setViewportHeight(node.clientHeight);
+    if (stickToBottom) node.scrollTop = node.scrollHeight;
+  }, [totalHeight, stickToBottom]);
+
+  useEffect(() => {
+    setStickToBottom(!query.trim());
+  }, [query]);
+
+  function measure(id: string, node: HTMLDivElement | null) {
+    if (!node) return;
+    const next = node.offsetHeight;
+    if (next > 0 && measured[id] !== next) {
+      setMeasured((prev) => (prev[id] === next ? prev : { ...prev, [id]: next }));
+    }
+  }
+
+  function jumpToLatest() {
+    const node = scrollRef.current;
+    if (!node) return;
+    node.scrollTop = node.scrollHeight;
+    setStickToBottom(true);
+  }
+
+  return (
+    <Stack gap="xs">
+      <Group justify="space-between">
+        <TextInput
+          size="xs"
+          leftSection={<IconSearch size={14} />}
+          placeholder="Search messages"
+          value={query}
+          onChange={(e) => setQuery(e.currentTarget.value)}
+          style={{ flex: 1 }}
+        />
+        <Badge variant="light">{query.trim() ? `${visible.length} of ${messages.length}` : `${messages.length} messages`}</Badge>
+        <Button size="xs" variant="default" leftSection={<IconArrowDown size={14} />} onClick={jumpToLatest}>
+          Latest
+        </Button>
+      </Group>
+      <Box
+        ref={scrollRef}
+        onScroll={(e) => {
+          const node = e.currentTarget;
+          setScrollTop(node.scrollTop);
+          setStickToBottom(node.scrollHeight - node.scrollTop - node.clientHeight < 24);
+        }}
+        style={{ height, overflowY: 'auto', border: '1px solid var(--mantine-color-dark-4)', borderRadius: 12 }}
+      >
+        {visible.length === 0 ? (
+          <Text size="sm" c="dimmed" p="md">{messages.length === 0 ? 'No inference turns recorded for this run yet.' : 'No messages match the search.'}</Text>
+        ) : (
+          <Box style={{ height: totalHeight, position: 'relative' }}>
+            {visible.slice(first, last).map((message, offset) => {
+              const index = first + offset;
+              const collapsed = isCollapsed(message);
+              return (
+                <Box
+                  key={message.id}
+                  ref={(node: HTMLDivElement | null) => { if (!collapsed) measure(message.id, node); }}
+                  p="xs"
+                  style={{
+                    position: 'absolute',
+                    top: offsets[index],
+                    left: 0,
+                    right: 0,
+                    height: collapsed ? COLLAPSED_ROW_HEIGHT : undefined,
+                    overflow: 'hidden',
+                    borderBottom: '1px solid var(--mantine-color-dark-5)',
+                  }}
+                >
+                  <Group gap="xs" wrap="nowrap" mb={4}>
+                    <ActionIcon size="xs" variant="subtle" onClick={() => setToggled((prev) => ({ ...prev, [message.id]: !prev[message.id] }))}>
+                      {collapsed ? <IconChevronRight size={12} /> : <IconChevronDown size={12} />}
+                    </ActionIcon>
+                    <Badge size="xs" color={message.role === 'user' ? 'blue' : 'grape'} variant="light">{message.role}</Badge>
+                    {message.stepId ? <Text size="xs" c="dimmed">{message.stepId}</Text> : null}
+                    <Text size="xs" c="dimmed">{new Date(message.createdAt).toLocaleString()}</Text>
+                    <Text size="xs" c="dimmed">{message.text.length.toLocaleString()} chars</Text>
+                    {message.role === 'assistant' ? (
+                      <Tooltip label={raw[message.id] ? 'Render Markdown' : 'Show raw text'} withArrow>
+                        <ActionIcon
+                          size="xs"
+                          variant={raw[message.id] ? 'subtle' : 'light'}
+                          color={raw[message.id] ? 'gray' : 'grape'}
+                          ml="auto"
+                          onClick={() => setRaw((prev) => ({ ...prev, [message.id]: !prev[message.id] }))}
+                        >
+                          <IconMarkdown size={12} />
+                        </ActionIcon>
+                      </Tooltip>
+                    ) : null}
+                  </Group>
+                  {collapsed ? (
+                    <Text size="xs" c="dimmed" lineClamp={1}>{message.text}</Text>
+                  ) : message.role === 'assistant' ? (
+                    raw[message.id] ? (
+                      <Text size="sm" ff="monospace" style={{ whiteSpace: 'pre-wrap', overflowWrap: 'anywhere' }}>{message.text}</Text>
+                    ) : (
+                      <MarkdownMessage text={message.text} />
+                    )
+                  ) : (
+                    <MessageBody text={message.text} />
+                  )}
+                </Box>
+              );
+            })}
+          </Box>
+        )}
+      </Box>
+    </Stack>
+  );
+}
@@ -0,0 +1,129 @@
+import { useEffect, useState } from 'react';
+import { Alert, Badge, Button, Card, Checkbox, Group, Modal, NumberInput, ScrollArea, Stack, Text } from '@mantine/core';
+import { listTrash, patchAppSettings, purgeTrash, restoreTrashEntry, type TrashEntry } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+type TrashPanelProps = {
+  repoRef: string;
+  opened: boolean;
+  onClose: () => void;
+};
+
+export function TrashPanel(props: TrashPanelProps) {
+  const { repoRef, opened, onClose } = props;
+  const [entries, setEntries] = useState<TrashEntry[]>([]);
+  const [retentionDays, setRetentionDays] = useState<number | null>(null);
+  const [overwrite, setOverwrite] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  async function refresh() {
+    if (!repoRef.trim()) return;
+    try {
+      const response = await listTrash(repoRef);
+      setEntries(response.entries);
+      setRetentionDays(response.retention_days);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  useEffect(() => {
+    setMessage(null);
+    setError(null);
+    if (opened) void refresh();
+  }, [opened, repoRef]);
+
+  async function run(action: () => Promise<string>) {
+    setBusy(true);
+    setError(null);
+    try {
+      setMessage(await action());
+      await refresh();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  function restore(entry: TrashEntry, path?: string) {
+    void run(async () => {
+      const result = await restoreTrashEntry({ repo_ref: repoRef, id: entry.id, paths: path ? [path] : null, overwrite });
+      const skipped = result.skipped.length ? ` · skipped ${result.skipped.length} existing (enable overwrite to replace)` : '';
+      return `Restored ${result.restored.length} file${result.restored.length === 1 ? '' : 's'}${skipped}`;
+    });
+  }
+
+  function updateRetention(value: string | number) {
+    const days = Math.min(365, Math.max(1, Math.round(Number(value) || 1)));
+    setRetentionDays(days);
+    void patchAppSettings({ trash: { retention_days: days } }).catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }
+
+  function purge(entry?: TrashEntry) {
+    if (!entry && !window.confirm('Permanently delete every trash entry for this repository?')) return;
+    void run(async () => {
+      const result = await purgeTrash({ repo_ref: repoRef, id: entry?.id ?? null });
+      return `Purged ${result.purged} entr${result.purged === 1 ? 'y' : 'ies'}`;
+    });
+  }
+
+  return (
+    <Modal opened={opened} onClose={onClose} title="Trash" size="xl" centered>
+      <Stack gap="sm">
+        <Group justify="space-between">
+          <Text size="xs" c="dimmed">
+            Files deleted or discarded through the app{retentionDays ? `, kept for ${retentionDays} days` : ''}.
+          </Text>
+          <Group gap="xs">
+            <NumberInput
+              size="xs"
+              w={120}
+              aria-label="Days to keep trash entries"
+              suffix=" days"
+              min={1}
+              max={365}
+              value={retentionDays ?? ''}
+              onChange={updateRetention}
+            />
+            <Checkbox size="xs" label="Overwrite existing files" checked={overwrite} onChange={(event) => setOverwrite(event.currentTarget.checked)} />
+            <Button size="xs" variant="light" color="red" disabled={busy || entries.length === 0} onClick={() => purge()}>Empty trash</Button>
+          </Group>
+        </Group>
+        {error ? <ErrorPanel message={error} /> : null}
+        {message ? <Alert color="green" withCloseButton onClose={() => setMessage(null)}>{message}</Alert> : null}
+        {entries.length === 0 ? <Text size="sm" c="dimmed">Trash is empty.</Text> : null}
+        <ScrollArea.Autosize mah="60vh" type="auto">
+          <Stack gap="xs">
+            {entries.map((entry) => (
+              <Card key={entry.id} withBorder padding="xs">
+                <Stack gap={4}>
+                  <Group justify="space-between" wrap="nowrap">
+                    <Group gap="xs" wrap="nowrap">
+                      <Badge size="xs" variant="light">{entry.kind.replace('_', ' ')}</Badge>
+                      <Text size="sm" fw={600} truncate>{entry.label}</Text>
+                      <Text size="xs" c="dimmed">{new Date(entry.created_at).toLocaleString()}</Text>
+                    </Group>
+                    <Group gap="xs" wrap="nowrap">
+                      <Button size="compact-xs" variant="light" disabled={busy} onClick={() => restore(entry)}>Restore all</Button>
+                      <Button size="compact-xs" variant="subtle" color="red" disabled={busy} onClick={() => purge(entry)}>Purge</Button>
+                    </Group>
+                  </Group>
+                  {entry.files.map((file) => (
+                    <Group key={file.path} gap="xs" wrap="nowrap" pl="xs">
+                      <Text size="xs" ff="monospace" truncate style={{ flex: 1 }}>{file.path}</Text>
+                      <Text size="xs" c="dimmed">{file.bytes} B</Text>
+                      <Button size="compact-xs" variant="subtle" disabled={busy} onClick={() => restore(entry, file.path)}>Restore</Button>
+                    </Group>
+                  ))}
+                </Stack>
+              </Card>
+            ))}
+          </Stack>
+        </ScrollArea.Autosize>
+      </Stack>
+    </Modal>
+  );
+}
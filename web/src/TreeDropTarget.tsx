@@ -0,0 +1,90 @@
+import { useRef, useState, type DragEvent, type ReactNode } from 'react';
+import { Box, Text } from '@mantine/core';
+
+const TREE_PATHS_MIME = 'application/x-mdev-tree-paths';
+
+/** Plain-text paths ride along so drops into ordinary inputs still get something useful. */
+export function writeDraggedPaths(event: DragEvent, paths: string[]) {
+  event.dataTransfer.setData(TREE_PATHS_MIME, JSON.stringify(paths));
+  event.dataTransfer.setData('text/plain', paths.join('\n'));
+  event.dataTransfer.effectAllowed = 'copy';
+}
+
+function carriesTreePaths(event: DragEvent) {
+  return Array.from(event.dataTransfer.types).includes(TREE_PATHS_MIME);
+}
+
+function readDraggedPaths(event: DragEvent): string[] {
+  try {
+    const parsed = JSON.parse(event.dataTransfer.getData(TREE_PATHS_MIME) || '[]');
+    return Array.isArray(parsed) ? parsed.filter((value): value is string => typeof value === 'string') : [];
+  } catch {
+    return [];
+  }
+}
+
+type TreeDropTargetProps = {
+  label: string;
+  disabled?: boolean;
+  onDropPaths: (paths: string[]) => void;
+  children: ReactNode;
+};
+
+export function TreeDropTarget(props: TreeDropTargetProps) {
+  const { label, disabled = false, onDropPaths, children } = props;
+  const [active, setActive] = useState(false);
+  const depth = useRef(0);
+
+  const accepts = (event: DragEvent) => !disabled && carriesTreePaths(event);
+
+  return (
+    <Box
+      pos="relative"
+      onDragEnter={(event) => {
+        if (!accepts(event)) return;
+        event.preventDefault();
+        depth.current += 1;
+        setActive(true);
+      }}
+      onDragOver={(event) => {
+        if (!accepts(event)) return;
+        event.preventDefault();
+        event.dataTransfer.dropEffect = 'copy';
+      }}
+      onDragLeave={(event) => {
+        if (!accepts(event)) return;
+        depth.current = Math.max(0, depth.current - 1);
+        if (depth.current === 0) setActive(false);
+      }}
+      onDrop={(event) => {
+        if (!accepts(event)) return;
+        event.preventDefault();
+        event.stopPropagation();
+        depth.current = 0;
+        setActive(false);
+        const paths = readDraggedPaths(event);
+        if (paths.length > 0) onDropPaths(paths);
+      }}
+    >
+      {children}
+      {active ? (
+        <Box
+          style={{
+            position: 'absolute',
+            inset: 0,
+            borderRadius: 8,
+            border: '2px dashed var(--mantine-color-blue-5)',
+            background: 'rgba(59,130,246,0.12)',
+            display: 'flex',
+            alignItems: 'center',
+            justifyContent: 'center',
+            pointerEvents: 'none',
+            zIndex: 5,
+          }}
+        >
+          <Text size="sm" fw={600} c="blue.3">{label}</Text>
+        </Box>
+      ) : null}
+    </Box>
+  );
+}
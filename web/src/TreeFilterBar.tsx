@@ -0,0 +1,221 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Badge, Button, Chip, Collapse, Group, NumberInput, Select, Stack, Switch, Text, TextInput } from '@mantine/core';
+import { IconFilter, IconTrash } from '@tabler/icons-react';
+import { filterRepoTree, getAppSettings, patchAppSettings, type RepoTreeFilter, type RepoTreeFilterResponse } from './api';
+
+type TreeFilterBarProps = {
+  repoRef: string;
+  onResult: (paths: string[] | null) => void;
+};
+
+const EMPTY_FILTER: RepoTreeFilter = {
+  extensions: [],
+  min_size: null,
+  max_size: null,
+  changed_in_worktree: false,
+  changed_in_last_commits: null,
+};
+
+function activeCriteria(filter: RepoTreeFilter) {
+  return [
+    (filter.extensions?.length ?? 0) > 0,
+    filter.min_size != null,
+    filter.max_size != null,
+    !!filter.changed_in_worktree,
+    !!filter.changed_in_last_commits,
+  ].filter(Boolean).length;
+}
+
+function kbToBytes(value: string | number) {
+  const kb = typeof value === 'number' ? value : Number(value);
+  return value === '' || Number.isNaN(kb) ? null : Math.round(kb * 1024);
+}
+
+export function TreeFilterBar(props: TreeFilterBarProps) {
+  const { repoRef, onResult } = props;
+  const [open, setOpen] = useState(false);
+  const [filter, setFilter] = useState<RepoTreeFilter>(EMPTY_FILTER);
+  const [stats, setStats] = useState<RepoTreeFilterResponse['extensions']>([]);
+  const [saved, setSaved] = useState<Record<string, RepoTreeFilter>>({});
+  const [saveName, setSaveName] = useState('');
+  const [selectedSaved, setSelectedSaved] = useState<string | null>(null);
+  const [matchCount, setMatchCount] = useState<number | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setFilter(EMPTY_FILTER);
+    setMatchCount(null);
+    setSelectedSaved(null);
+    setStats([]);
+    onResult(null);
+    if (!repoRef.trim()) return;
+    void getAppSettings()
+      .then((settings) => setSaved(settings.tree_filters?.by_repo?.[repoRef] ?? {}))
+      .catch(() => setSaved({}));
+  }, [repoRef]);
+
+  useEffect(() => {
+    if (!open || !repoRef.trim() || stats.length > 0) return;
+    void filterRepoTree(repoRef, {})
+      .then((response) => setStats(response.extensions))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [open, repoRef]);
+
+  async function apply(next: RepoTreeFilter) {
+    if (activeCriteria(next) === 0) {
+      clear();
+      return;
+    }
+    setBusy(true);
+    setError(null);
+    try {
+      const response = await filterRepoTree(repoRef, next);
+      setStats(response.extensions);
+      setMatchCount(response.matches.length);
+      onResult(response.matches.map((entry) => entry.path));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  function clear() {
+    setFilter(EMPTY_FILTER);
+    setMatchCount(null);
+    setSelectedSaved(null);
+    onResult(null);
+  }
+
+  async function persist(name: string, value: RepoTreeFilter | null) {
+    const settings = await patchAppSettings({ tree_filters: { by_repo: { [repoRef]: { [name]: value } } } });
+    setSaved(settings.tree_filters?.by_repo?.[repoRef] ?? {});
+  }
+
+  const active = activeCriteria(filter);
+
+  return (
+    <Stack gap={6}>
+      <Group gap="xs" justify="space-between">
+        <Button
+          size="compact-xs"
+          variant={active > 0 ? 'light' : 'subtle'}
+          leftSection={<IconFilter size={12} />}
+          onClick={() => setOpen((prev) => !prev)}
+          disabled={!repoRef.trim()}
+        >
+          Filters{active > 0 ? ` (${active})` : ''}
+        </Button>
+        {matchCount != null ? <Badge size="xs" variant="light">{matchCount} matches</Badge> : null}
+      </Group>
+      <Collapse in={open}>
+        <Stack gap="xs">
+          <Group gap="xs" wrap="nowrap">
+            <Select
+              size="xs"
+              placeholder="Saved filters"
+              data={Object.keys(saved).sort()}
+              value={selectedSaved}
+              onChange={(name) => {
+                setSelectedSaved(name);
+                const next = name ? { ...EMPTY_FILTER, ...saved[name] } : EMPTY_FILTER;
+                setFilter(next);
+                void apply(next);
+              }}
+              clearable
+              style={{ flex: 1 }}
+            />
+            <ActionIcon
+              size="sm"
+              variant="subtle"
+              color="red"
+              disabled={!selectedSaved}
+              onClick={() => {
+                if (!selectedSaved) return;
+                void persist(selectedSaved, null).then(() => setSelectedSaved(null));
+              }}
+            >
+              <IconTrash size={14} />
+            </ActionIcon>
+          </Group>
+          {stats.length > 0 ? (
+            <Chip.Group
+              multiple
+              value={filter.extensions ?? []}
+              onChange={(extensions) => setFilter((prev) => ({ ...prev, extensions }))}
+            >
+              <Group gap={4}>
+                {stats.slice(0, 16).map((stat) => (
+                  <Chip key={stat.extension} size="xs" value={stat.extension}>
+                    {stat.extension || '(none)'} · {stat.files}
+                  </Chip>
+                ))}
+              </Group>
+            </Chip.Group>
+          ) : null}
+          <Group gap="xs" grow>
+            <NumberInput
+              size="xs"
+              label="Min KB"
+              min={0}
+              value={filter.min_size != null ? filter.min_size / 1024 : ''}
+              onChange={(value) => setFilter((prev) => ({ ...prev, min_size: kbToBytes(value) }))}
+            />
+            <NumberInput
+              size="xs"
+              label="Max KB"
+              min={0}
+              value={filter.max_size != null ? filter.max_size / 1024 : ''}
+              onChange={(value) => setFilter((prev) => ({ ...prev, max_size: kbToBytes(value) }))}
+            />
+          </Group>
+          <Switch
+            size="xs"
+            label="Changed in working tree"
+            checked={!!filter.changed_in_worktree}
+            onChange={(event) => {
+              const checked = event.currentTarget.checked;
+              setFilter((prev) => ({ ...prev, changed_in_worktree: checked }));
+            }}
+          />
+          <NumberInput
+            size="xs"
+            label="Changed in last N commits"
+            min={0}
+            value={filter.changed_in_last_commits ?? ''}
+            onChange={(value) => setFilter((prev) => ({ ...prev, changed_in_last_commits: value === '' ? null : Number(value) }))}
+          />
+          <Group gap="xs">
+            <Button size="compact-xs" onClick={() => void apply(filter)} loading={busy}>Apply</Button>
+            <Button size="compact-xs" variant="default" onClick={clear}>Clear</Button>
+          </Group>
+          <Group gap="xs" wrap="nowrap">
+            <TextInput
+              size="xs"
+              placeholder="Save as…"
+              value={saveName}
+              onChange={(event) => setSaveName(event.currentTarget.value)}
+              style={{ flex: 1 }}
+            />
+            <Button
+              size="compact-xs"
+              variant="light"
+              disabled={!saveName.trim() || active === 0}
+              onClick={() => {
+                const name = saveName.trim();
+                void persist(name, filter).then(() => {
+                  setSelectedSaved(name);
+                  setSaveName('');
+                });
+              }}
+            >
+              Save
+            </Button>
+          </Group>
+          {error ? <Text size="xs" c="red">{error}</Text> : null}
+        </Stack>
+      </Collapse>
+    </Stack>
+  );
+}
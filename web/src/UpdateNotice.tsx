@@ -0,0 +1,132 @@
+import { useEffect, useState } from 'react';
+import { ActionIcon, Anchor, Badge, Button, Group, Modal, Paper, ScrollArea, Select, Stack, Switch, Text } from '@mantine/core';
+import { IconX } from '@tabler/icons-react';
+import { checkForUpdates, getAppSettings, getUpdateStatus, patchAppSettings, stageUpdate, type AppSettings, type UpdateStatus } from './api';
+import { ErrorPanel } from './ErrorPanel';
+import { MarkdownMessage } from './MarkdownMessage';
+
+const STATUS_POLL_MS = 30 * 60 * 1000;
+
+const INTERVAL_OPTIONS = [
+  { value: '6', label: 'Every 6 hours' },
+  { value: '24', label: 'Daily' },
+  { value: '168', label: 'Weekly' },
+];
+
+type UpdateNoticeProps = {
+  opened: boolean;
+  onOpen: () => void;
+  onClose: () => void;
+};
+
+/** Small corner notice when the release feed has a newer version; details and settings open in a modal. */
+export function UpdateNotice(props: UpdateNoticeProps) {
+  const { opened, onOpen, onClose } = props;
+  const [status, setStatus] = useState<UpdateStatus | null>(null);
+  const [settings, setSettings] = useState<AppSettings['updates'] | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const load = () => void getUpdateStatus().then(setStatus).catch(() => undefined);
+    load();
+    void getAppSettings().then((value) => setSettings(value.updates)).catch(() => undefined);
+    const timer = window.setInterval(load, STATUS_POLL_MS);
+    return () => window.clearInterval(timer);
+  }, []);
+
+  async function run(action: () => Promise<UpdateStatus>) {
+    setBusy(true);
+    setError(null);
+    try {
+      setStatus(await action());
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  function updateSettings(patch: Partial<AppSettings['updates']>) {
+    setSettings((prev) => (prev ? { ...prev, ...patch } : prev));
+    void patchAppSettings({ updates: patch }).catch(() => undefined);
+  }
+
+  const latest = status?.latest_version ?? '';
+  const visible = !!status?.update_available && !!settings?.enabled && settings.dismissed_version !== latest;
+
+  return (
+    <>
+      {visible ? (
+        <Paper withBorder shadow="md" p="xs" style={{ position: 'fixed', left: 16, bottom: 16, zIndex: 140, width: 280 }}>
+          <Group justify="space-between" wrap="nowrap" gap="xs">
+            <Stack gap={0}>
+              <Text size="sm" fw={600}>Update available</Text>
+              <Text size="xs" c="dimmed">{status?.current_version} → {latest}</Text>
+            </Stack>
+            <Group gap={4} wrap="nowrap">
+              <Button size="compact-xs" variant="light" onClick={onOpen}>What's new</Button>
+              <ActionIcon size="sm" variant="subtle" aria-label="Dismiss update" onClick={() => updateSettings({ dismissed_version: latest })}>
+                <IconX size={14} />
+              </ActionIcon>
+            </Group>
+          </Group>
+        </Paper>
+      ) : null}
+      <Modal opened={opened} onClose={onClose} title="Updates" size="lg" centered>
+        <Stack gap="sm">
+          <Group gap="xs">
+            <Badge variant="light">Installed {status?.current_version}</Badge>
+            {latest ? <Badge variant="light" color={status?.update_available ? 'green' : 'gray'}>Latest {latest}</Badge> : null}
+            {status?.staged_version ? <Badge variant="light" color="blue">{status.staged_version} installs on next launch</Badge> : null}
+          </Group>
+          {error ? <ErrorPanel message={error} /> : null}
+          {status?.error ? <Text size="xs" c="red">Last check failed: {status.error}</Text> : null}
+          {status?.changelog ? (
+            <ScrollArea.Autosize mah={320} type="auto">
+              <MarkdownMessage text={status.changelog} />
+            </ScrollArea.Autosize>
+          ) : null}
+          {status?.release_url ? <Anchor size="xs" href={status.release_url} target="_blank" rel="noreferrer">Release page</Anchor> : null}
+          <Group justify="space-between" align="flex-end">
+            <Group gap="sm" align="flex-end">
+              <Switch
+                size="xs"
+                label="Check automatically"
+                checked={settings?.enabled ?? false}
+                onChange={(event) => updateSettings({ enabled: event.currentTarget.checked })}
+              />
+              <Select
+                size="xs"
+                w={140}
+                data={INTERVAL_OPTIONS}
+                value={String(settings?.check_interval_hours ?? 24)}
+                onChange={(value) => value && updateSettings({ check_interval_hours: Number(value) })}
+                allowDeselect={false}
+              />
+            </Group>
+            <Group gap="xs">
+              <Button size="xs" variant="default" loading={busy} onClick={() => void run(checkForUpdates)}>Check now</Button>
+              <Button
+                size="xs"
+                disabled={!status?.update_available || !status.asset?.signature_url || status.staged_version === latest}
+                loading={busy}
+                title={
+                  !status?.asset
+                    ? 'This release has no binary for this platform'
+                    : status.asset.signature_url
+                      ? status.asset.name
+                      : `${status.asset.name} is not signed`
+                }
+                onClick={() => void run(stageUpdate)}
+              >
+                Download and install on next launch
+              </Button>
+            </Group>
+          </Group>
+          {status?.checked_at ? <Text size="xs" c="dimmed">Last checked {new Date(status.checked_at).toLocaleString()}</Text> : null}
+        </Stack>
+      </Modal>
+    </>
+  );
+}
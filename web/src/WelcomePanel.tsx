@@ -0,0 +1,148 @@
+import { useEffect, useState } from 'react';
+import { Badge, Button, Card, Grid, Group, Stack, Text, TextInput, Title } from '@mantine/core';
+import { IconFolderOpen, IconGitBranch } from '@tabler/icons-react';
+import { cloneRepo, listRecentRepos, validateRepoRef, type RecentRepo } from './api';
+import { ErrorPanel } from './ErrorPanel';
+
+const COMPONENT_GUIDE: Array<{ name: string; text: string }> = [
+  { name: 'Workflow', text: 'Runs stages (code, compile, review) against a repository and shows live executions.' },
+  { name: 'Changes', text: 'Diff viewer and source control for staging, unstaging and reviewing edits.' },
+  { name: 'Commits', text: 'Commit history with per-commit diffs and net line-count analytics.' },
+  { name: 'Repository', text: 'File tree and editor with quick open (Alt+E).' },
+  { name: 'Capabilities', text: 'Inference connector, repo fragments, changeset schema and apply tools.' },
+  { name: 'Analysis', text: 'Dependency inventory and committed build artifacts.' },
+];
+
+type WelcomePanelProps = {
+  onUseRepo: (repoRef: string) => void;
+};
+
+export function WelcomePanel(props: WelcomePanelProps) {
+  const { onUseRepo } = props;
+  const [recent, setRecent] = useState<RecentRepo[]>([]);
+  const [folder, setFolder] = useState('');
+  const [cloneUrl, setCloneUrl] = useState('');
+  const [cloneDestination, setCloneDestination] = useState('');
+  const [busy, setBusy] = useState<'open' | 'clone' | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listRecentRepos()
+      .then(setRecent)
+      .catch(() => setRecent([]));
+  }, []);
+
+  async function openFolder() {
+    try {
+      setBusy('open');
+      setError(null);
+      const result = await validateRepoRef(folder.trim());
+      if (!result.ok) {
+        setError(result.message);
+        return;
+      }
+      onUseRepo(result.repo_ref);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(null);
+    }
+  }
+
+  async function clone() {
+    try {
+      setBusy('clone');
+      setError(null);
+      const result = await cloneRepo(cloneUrl.trim(), cloneDestination.trim());
+      if (!result.ok) {
+        setError(result.output || 'git clone failed');
+        return;
+      }
+      onUseRepo(result.repo_ref);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(null);
+    }
+  }
+
+  return (
+    <Card withBorder>
+      <Stack>
+        <div>
+          <Title order={3}>Get started</Title>
+          <Text size="sm" c="dimmed">Pick a repository to create your first workflow.</Text>
+        </div>
+        {error ? <ErrorPanel message={error} /> : null}
+        <Grid>
+          <Grid.Col span={{ base: 12, md: 4 }}>
+            <Stack gap="xs">
+              <Text fw={600}>Recent repositories</Text>
+              {recent.length === 0 ? (
+                <Text size="sm" c="dimmed">No recent repositories yet.</Text>
+              ) : (
+                recent.map((repo) => (
+                  <Group key={repo.repo_ref} justify="space-between" wrap="nowrap">
+                    <Stack gap={0} style={{ minWidth: 0 }}>
+                      <Text size="sm" truncate>{repo.repo_ref}</Text>
+                      <Text size="xs" c="dimmed">{repo.workflow_count} workflows</Text>
+                    </Stack>
+                    {repo.exists ? (
+                      <Button size="xs" variant="light" onClick={() => onUseRepo(repo.repo_ref)}>Use</Button>
+                    ) : (
+                      <Badge size="xs" color="gray" variant="light">missing</Badge>
+                    )}
+                  </Group>
+                ))
+              )}
+            </Stack>
+          </Grid.Col>
+          <Grid.Col span={{ base: 12, md: 4 }}>
+            <Stack gap="xs">
+              <Text fw={600}>Open folder</Text>
+              <TextInput
+                placeholder="C:/repo or /home/user/repo"
+                value={folder}
+                onChange={(e) => setFolder(e.currentTarget.value)}
+              />
+              <Button
+                leftSection={<IconFolderOpen size={16} />}
+                variant="default"
+                onClick={() => void openFolder()}
+                loading={busy === 'open'}
+                disabled={!folder.trim()}
+              >
+                Open folder
+              </Button>
+            </Stack>
+          </Grid.Col>
+          <Grid.Col span={{ base: 12, md: 4 }}>
+            <Stack gap="xs">
+              <Text fw={600}>Clone repo</Text>
+              <TextInput placeholder="https://github.com/org/repo.git" value={cloneUrl} onChange={(e) => setCloneUrl(e.currentTarget.value)} />
+              <TextInput placeholder="Destination folder (absolute path)" value={cloneDestination} onChange={(e) => setCloneDestination(e.currentTarget.value)} />
+              <Button
+                leftSection={<IconGitBranch size={16} />}
+                variant="default"
+                onClick={() => void clone()}
+                loading={busy === 'clone'}
+                disabled={!cloneUrl.trim() || !cloneDestination.trim()}
+              >
+                Clone repo
+              </Button>
+            </Stack>
+          </Grid.Col>
+        </Grid>
+        <Stack gap={4}>
+          <Text fw={600}>What's in the workspace</Text>
+          {COMPONENT_GUIDE.map((item) => (
+            <Text key={item.name} size="sm">
+              <Text span fw={600}>{item.name}</Text>
+              <Text span c="dimmed"> — {item.text}</Text>
+            </Text>
+          ))}
+        </Stack>
+      </Stack>
+    </Card>
+  );
+}
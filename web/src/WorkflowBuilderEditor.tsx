@@ -11,6 +11,7 @@ import {
   Loader,
   Menu,
   ScrollArea,
+  SegmentedControl,
   Select,
   Stack,
   Switch,
@@ -27,8 +28,10 @@ import {
   type WorkflowStageDescriptor,
   type WorkflowStageField,
   type WorkflowGovernanceConfig,
+  type WorkflowCanvasLayout,
   type WorkflowTemplateDefinition,
 } from './api';
+import { WorkflowPipelineCanvas } from './WorkflowPipelineCanvas';
 import { buildBuilderDocument, builderStepFromDescriptor, builderStepsFromDefinition, capabilityDisplayLabel, defaultGlobals, descriptorMap, ensureGovernanceConfig, governancePolicyMapFromCatalog, type BuilderStep } from './workflow_builder';
 
 type WorkflowBuilderEditorProps = {
@@ -37,6 +40,8 @@ type WorkflowBuilderEditorProps = {
   onCompiledDefinitionChange: (definition: WorkflowTemplateDefinition) => void;
   onError?: (message: string | null) => void;
   onOpenCapabilityConfig?: (capabilityKey: string) => void;
+  canvasLayout?: WorkflowCanvasLayout | null;
+  onCanvasLayoutChange?: (layout: WorkflowCanvasLayout) => void;
 };
 
 type CompileState = 'idle' | 'dirty' | 'compiling' | 'compiled' | 'error';
@@ -106,12 +111,13 @@ function isGlobalEditableCapability(capabilityKey: string) {
 }
 
 
-export function WorkflowBuilderEditor({ initialDefinition, builderGlobals, onCompiledDefinitionChange, onError, onOpenCapabilityConfig }: WorkflowBuilderEditorProps) {
+export function WorkflowBuilderEditor({ initialDefinition, builderGlobals, onCompiledDefinitionChange, onError, onOpenCapabilityConfig, canvasLayout, onCanvasLayoutChange }: WorkflowBuilderEditorProps) {
   const [catalog, setCatalog] = useState<Record<string, WorkflowStageDescriptor>>({});
   const [stageDescriptors, setStageDescriptors] = useState<WorkflowStageDescriptor[]>([]);
   const [steps, setSteps] = useState<BuilderStep[]>([]);
   const [governance, setGovernance] = useState<WorkflowGovernanceConfig>({});
   const [selectedStepId, setSelectedStepId] = useState<string | null>(null);
+  const [pipelineView, setPipelineView] = useState<'list' | 'canvas'>('list');
   const [globalsRevision, setGlobalsRevision] = useState(0);
   const [loading, setLoading] = useState(true);
   const [compileState, setCompileState] = useState<CompileState>('idle');
@@ -402,6 +408,12 @@ export function WorkflowBuilderEditor({ initialDefinition, builderGlobals, onCom
                 <Badge color={compileBadgeColor(compileState)} variant="light">
                   {compileState.toUpperCase()}
                 </Badge>
+                <SegmentedControl
+                  size="xs"
+                  value={pipelineView}
+                  onChange={(value) => setPipelineView(value as 'list' | 'canvas')}
+                  data={[{ value: 'list', label: 'List' }, { value: 'canvas', label: 'Canvas' }]}
+                />
               </Group>
               <Menu shadow="md" width={260}>
                 <Menu.Target>
@@ -450,69 +462,81 @@ export function WorkflowBuilderEditor({ initialDefinition, builderGlobals, onCom
               </Card>
             ) : null}
 
-            <ScrollArea h="100%" type="auto">
-              <Stack gap="sm">
-                {steps.map((step, index) => {
-                  const descriptor = catalog[step.stepType];
-                  const selected = step.id === selectedStepId;
-                  return (
-                    <Card
-                      key={step.id}
-                      withBorder
-                      padding="sm"
-                      style={{ cursor: 'pointer', ...stageCardTone(selected) }}
-                      onClick={() => setSelectedStepId(step.id)}
-                    >
-                      <Group justify="space-between" align="start">
-                        <Stack gap={4}>
-                          <Group gap={8}>
-                            <Badge variant="light">{index + 1}</Badge>
-                            <Text fw={600}>{step.name}</Text>
-                          </Group>
-                          <Group gap={8}>
-                            <Code>{step.stepType}</Code>
-                            {descriptor?.category ? <Badge variant="dot">{descriptor.category}</Badge> : null}
+            {pipelineView === 'canvas' ? (
+              <Box style={{ flex: 1, minHeight: 0 }}>
+                <WorkflowPipelineCanvas
+                  steps={steps}
+                  selectedStepId={selectedStepId}
+                  layout={canvasLayout ?? null}
+                  onSelect={setSelectedStepId}
+                  onLayoutChange={(layout) => onCanvasLayoutChange?.(layout)}
+                />
+              </Box>
+            ) : (
+              <ScrollArea h="100%" type="auto">
+                <Stack gap="sm">
+                  {steps.map((step, index) => {
+                    const descriptor = catalog[step.stepType];
+                    const selected = step.id === selectedStepId;
+                    return (
+                      <Card
+                        key={step.id}
+                        withBorder
+                        padding="sm"
+                        style={{ cursor: 'pointer', ...stageCardTone(selected) }}
+                        onClick={() => setSelectedStepId(step.id)}
+                      >
+                        <Group justify="space-between" align="start">
+                          <Stack gap={4}>
+                            <Group gap={8}>
+                              <Badge variant="light">{index + 1}</Badge>
+                              <Text fw={600}>{step.name}</Text>
+                            </Group>
+                            <Group gap={8}>
+                              <Code>{step.stepType}</Code>
+                              {descriptor?.category ? <Badge variant="dot">{descriptor.category}</Badge> : null}
+                            </Group>
+                          </Stack>
+                          <Group gap="xs">
+                            <Button
+                              variant="subtle"
+                              size="xs"
+                              onClick={(event) => {
+                                event.stopPropagation();
+                                moveStep(step.id, -1);
+                              }}
+                            >
+                              Left
+                            </Button>
+                            <Button
+                              variant="subtle"
+                              size="xs"
+                              onClick={(event) => {
+                                event.stopPropagation();
+                                moveStep(step.id, 1);
+                              }}
+                            >
+                              Right
+                            </Button>
+                            <Button
+                              color="red"
+                              variant="subtle"
+                              size="xs"
+                              onClick={(event) => {
+                                event.stopPropagation();
+                                removeStep(step.id);
+                              }}
+                            >
+                              Remove
+                            </Button>
                           </Group>
-                        </Stack>
-                        <Group gap="xs">
-                          <Button
-                            variant="subtle"
-                            size="xs"
-                            onClick={(event) => {
-                              event.stopPropagation();
-                              moveStep(step.id, -1);
-                            }}
-                          >
-                            Left
-                          </Button>
-                          <Button
-                            variant="subtle"
-                            size="xs"
-                            onClick={(event) => {
-                              event.stopPropagation();
-                              moveStep(step.id, 1);
-                            }}
-                          >
-                            Right
-                          </Button>
-                          <Button
-                            color="red"
-                            variant="subtle"
-                            size="xs"
-                            onClick={(event) => {
-                              event.stopPropagation();
-                              removeStep(step.id);
-                            }}
-                          >
-                            Remove
-                          </Button>
                         </Group>
-                      </Group>
-                    </Card>
-                  );
-                })}
-              </Stack>
-            </ScrollArea>
+                      </Card>
+                    );
+                  })}
+                </Stack>
+              </ScrollArea>
+            )}
           </Stack>
         </Card>
 
@@ -0,0 +1,99 @@
+import { useEffect, useMemo, useRef, useState } from 'react';
+import {
+  applyNodeChanges,
+  Background,
+  Controls,
+  MiniMap,
+  ReactFlow,
+  type Edge,
+  type Node,
+  type NodeChange,
+  type Viewport,
+} from '@xyflow/react';
+import type { WorkflowCanvasLayout } from './api';
+import type { BuilderStep } from './workflow_builder';
+
+const COLUMN_WIDTH = 260;
+const ROW_HEIGHT = 140;
+const STAGES_PER_ROW = 5;
+
+type WorkflowPipelineCanvasProps = {
+  steps: BuilderStep[];
+  selectedStepId: string | null;
+  layout: WorkflowCanvasLayout | null;
+  onSelect: (stepId: string) => void;
+  onLayoutChange: (layout: WorkflowCanvasLayout) => void;
+};
+
+function defaultPosition(index: number) {
+  return { x: (index % STAGES_PER_ROW) * COLUMN_WIDTH, y: Math.floor(index / STAGES_PER_ROW) * ROW_HEIGHT };
+}
+
+/**
+ * Pipeline stages on a pannable, zoomable canvas. Hold Space (or use the middle mouse button)
+ * and drag to pan, Ctrl+wheel to zoom; the wheel alone scrolls the canvas. Stage positions and
+ * the viewport are handed back through `onLayoutChange` for persistence.
+ */
+export function WorkflowPipelineCanvas(props: WorkflowPipelineCanvasProps) {
+  const { steps, selectedStepId, layout, onSelect, onLayoutChange } = props;
+  const layoutRef = useRef<WorkflowCanvasLayout>(layout ?? { viewport: null, positions: {} });
+  layoutRef.current = layout ?? layoutRef.current;
+  const [nodes, setNodes] = useState<Node[]>([]);
+
+  useEffect(() => {
+    setNodes((prev) => {
+      const current = Object.fromEntries(prev.map((node) => [node.id, node.position]));
+      return steps.map((step, index) => ({
+        id: step.id,
+        position: current[step.id] ?? layoutRef.current.positions[step.id] ?? defaultPosition(index),
+        data: { label: `${index + 1}. ${step.name}` },
+        selected: step.id === selectedStepId,
+        style: {
+          width: 200,
+          borderColor: step.id === selectedStepId ? 'var(--mantine-color-blue-5)' : undefined,
+          borderWidth: step.id === selectedStepId ? 2 : 1,
+        },
+      }));
+    });
+  }, [steps, selectedStepId]);
+
+  const edges = useMemo<Edge[]>(
+    () => steps.slice(1).map((step, index) => ({ id: `${steps[index].id}->${step.id}`, source: steps[index].id, target: step.id, animated: false })),
+    [steps]
+  );
+
+  function persist(patch: Partial<WorkflowCanvasLayout>) {
+    const next = { ...layoutRef.current, ...patch };
+    layoutRef.current = next;
+    onLayoutChange(next);
+  }
+
+  return (
+    <div style={{ height: '100%', minHeight: 420 }}>
+      <ReactFlow
+        nodes={nodes}
+        edges={edges}
+        onNodesChange={(changes: NodeChange[]) => setNodes((prev) => applyNodeChanges(changes, prev))}
+        onNodeClick={(_, node) => onSelect(node.id)}
+        onNodeDragStop={(_, node) => persist({ positions: { ...layoutRef.current.positions, [node.id]: node.position } })}
+        onMoveEnd={(_, viewport: Viewport) => persist({ viewport })}
+        defaultViewport={layout?.viewport ?? undefined}
+        fitView={!layout?.viewport}
+        panActivationKeyCode="Space"
+        panOnDrag={[1]}
+        panOnScroll
+        zoomOnScroll={false}
+        zoomActivationKeyCode="Control"
+        minZoom={0.2}
+        maxZoom={2}
+        nodesConnectable={false}
+        colorMode="dark"
+        proOptions={{ hideAttribution: true }}
+      >
+        <Background gap={20} />
+        <MiniMap pannable zoomable />
+        <Controls showInteractive={false} />
+      </ReactFlow>
+    </div>
+  );
+}
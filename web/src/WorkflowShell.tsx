@@ -15,7 +15,9 @@ import {
   JsonInput,
   Loader,
   Modal,
+  NumberInput,
   ScrollArea,
+  SegmentedControl,
   Select,
   SimpleGrid,
   Stack,
@@ -27,10 +29,17 @@ import {
   Textarea,
   Title
 } from '@mantine/core';
+import { useViewportSize } from '@mantine/hooks';
 import { IconPlayerPause, IconPlayerPlay, IconRefresh, IconTrash } from '@tabler/icons-react';
 import {
   createRun,
+  generateTestsTask,
+  getAppSettings,
+  patchAppSettings,
+  recordHistoryState,
   applyWorkflowChangeset,
+  ingestChangesetText,
+  lintWorkflowChangeset,
   executeWorkflowCapability,
   createTemplate,
   deleteRun,
@@ -38,18 +47,24 @@ import {
   getEventChainSummary,
   getWorkflowChangeset,
   getChangesetSchema,
+  getDoctorReport,
   getRun,
   openWorkflowRun,
   getStageExecutionChain,
   getWorkflowBuilderCatalog,
   listRepoTree,
   listWorkflowRepoTree,
+  readWorkflowFile,
+  readWorkspaceFile,
+  stageReviewDiff,
+  listExportDestinations,
   listRunEvents,
   validateRepoRef,
   listRuns,
   listWorkflowChangesets,
   listTemplates,
   openEventStream,
+  openInstanceRequestStream,
   patchWorkflowGlobalState,
   patchWorkflowStageState,
   pauseWorkflowRun,
@@ -60,18 +75,27 @@ import {
   runCurrentWorkflowStep,
   selectWorkflowStep,
   startWorkflowRun,
+  type AppSettings,
   type AutomationMode,
   type BrowserProbeResult,
   type ApplyChangesetResponse,
   type ChangesetAttemptSummary,
+  type ChangesetLintReport,
+  type DoctorCheck,
   type EventChainSummaryItem,
   type EventChainSummaryResponse,
+  type ExportDestination,
+  type InferenceGenerationSettings,
+  type AiActionModel,
+  type InferenceRequestRecord,
+  type SentInferenceRequest,
   type InferenceTransport,
   type RepoTreeResponse,
   type SapExportScanItem,
   type SapSearchObject,
   type StageExecutionChain,
   type StageExecutionEvent,
+  type TerminalPane,
   type WorkflowBuilderCatalog,
   type WorkflowEvent,
   type WorkflowRun,
@@ -80,14 +104,66 @@ import {
   type WorkflowStageField,
   type WorkflowStepDefinition,
   type WorkflowTemplate,
+  type WorkflowCanvasLayout,
   type WorkflowTemplateDefinition,
   type WorkflowTransition
 } from './api';
+import { AccessibilitySettingsModal } from './AccessibilitySettingsModal';
+import { PluginPalette } from './PluginPalette';
+import { AutomationSettingsModal } from './AutomationSettingsModal';
+import { McpServerModal } from './McpServerModal';
+import { DoctorModal } from './DoctorModal';
+import { ProcessesModal } from './ProcessesModal';
+import { CapabilityTracesModal } from './CapabilityTracesModal';
+import { useRepoGitDecorations } from './gitFileStatus';
+import { AutoRunGuardsModal } from './AutoRunGuardsModal';
+import { stripAnsi } from './AnsiOutput';
+import { ProjectCommandChips } from './ProjectCommandChips';
+import { PostprocessSimulationDrawer, type PostprocessOptions } from './PostprocessSimulationDrawer';
+import { ChangesetLintList } from './ChangesetLintList';
+import { ContextDriftPanel } from './ContextDriftPanel';
+import { ContextSymbolPicker } from './ContextSymbolPicker';
+import { ContextBudgetOverrides, parseFileBudgets, type FileBudget } from './ContextBudgetOverrides';
+import { ContextInjectionStrategy, DEFAULT_INJECTION_STRATEGY, parseInjectionStrategy, parseLastInjection, type InjectionStrategy } from './ContextInjectionStrategy';
+import { ContextExportRunner } from './ContextExportRunner';
+import { TerminalPanes } from './TerminalPanes';
+import { SelectTouchedPopover } from './SelectTouchedPopover';
+import { ExcludeRegexManager } from './ExcludeRegexManager';
+import { HistoryMenu } from './HistoryMenu';
+import { IndexLockOverlay } from './IndexLockOverlay';
+import { focusRegionProps, useFocusNavigation } from './focusNavigation';
+import { EnvProfilesPanel } from './EnvProfilesPanel';
+import { RunNotesCard } from './RunNotesCard';
+import { DebugMetricsOverlay } from './DebugMetricsOverlay';
 import { GlobalCapabilitiesPanel } from './GlobalCapabilitiesPanel';
-import { RepoTree, type RepoTreeEntry } from './RepoTree';
+import { TreeDropTarget } from './TreeDropTarget';
+import { InferenceGenerationFields, readGenerationSettings } from './InferenceGenerationFields';
+import { InferenceRequestViewer } from './InferenceRequestViewer';
+import { NetworkSettingsModal } from './NetworkSettingsModal';
+import { RepoTree, type RepoTreeEntry, type TreeBulkAction } from './RepoTree';
+import { ReviewQueuePanel } from './ReviewQueuePanel';
+import { SettingsBundleModal } from './SettingsBundleModal';
+import { TemplateIssuesAlert } from './TemplateIssuesAlert';
+import { UpdateNotice } from './UpdateNotice';
 import type { ReviewSourceControlState } from './ReviewDiffViewerPanel';
+import { parseWhitespaceMode } from './DiffWhitespace';
+import { buildTranscriptMessages, TranscriptPanel } from './TranscriptPanel';
+import { ConversationImportModal } from './ConversationImportModal';
+import { formatAskAiDraft, type AskAiSnippet } from './AskAi';
+import { WelcomePanel } from './WelcomePanel';
 import { WorkflowBuilderEditor } from './WorkflowBuilderEditor';
+import {
+  findWorkspaceTemplate,
+  popOutPane,
+  secondaryPaneForViewport,
+  WorkspaceSplit,
+  WorkspaceTemplateMenu,
+  WorkspaceWelcomeModal,
+  type PaneActivity,
+  type WorkspaceTemplate,
+} from './WorkspaceTemplates';
 import { defaultGlobals, descriptorMap, flattenStageFields } from './workflow_builder';
+import { ErrorPanel } from './ErrorPanel';
 
 const ReviewDiffViewerPanel = lazy(async () => {
   const mod = await import('./ReviewDiffViewerPanel');
@@ -104,6 +180,11 @@ const RepoMonacoFileEditorPanel = lazy(async () => {
   return { default: mod.RepoMonacoFileEditorPanel };
 });
 
+const AnalysisPanel = lazy(async () => {
+  const mod = await import('./AnalysisPanel');
+  return { default: mod.AnalysisPanel };
+});
+
 function openBuilderCapabilityConfig(
   capabilityKey: string,
   handlers: {
@@ -147,7 +228,9 @@ function openBuilderCapabilityConfig(
 type BuilderMode = 'builder' | 'json';
 type ShellView = 'builder' | 'monitor';
 type MonitorView = 'workflow_list' | 'workflow_detail';
-type WorkspaceTabKey = 'workflows' | 'diff' | 'commits' | 'files' | 'capabilities';
+type WorkspaceTabKey = 'workflows' | 'diff' | 'commits' | 'files' | 'capabilities' | 'analysis';
+type WorkspaceLayout = Pick<AppSettings['workspace'], 'split_percent' | 'snap_percent' | 'arrangement'>;
+type RepoWorkspace = AppSettings['workspace']['by_repo'][string];
 type EventTone = { color: string; label: string };
 
 type InferenceConnectionStatus = { color: string; label: string };
@@ -248,6 +331,37 @@ function extractInferenceTextFromPayload(payload: unknown): string {
   return '';
 }
 
+function extractInferenceRequestFromPayload(payload: unknown): SentInferenceRequest | null {
+  const fromResult = (result: Record<string, unknown> | undefined): SentInferenceRequest | null =>
+    result?.request && typeof result.request === 'object'
+      ? { request: result.request as InferenceRequestRecord, prompt: typeof result.prompt === 'string' ? result.prompt : '' }
+      : null;
+  const objectPayload = (payload ?? {}) as Record<string, unknown>;
+  const direct = fromResult(objectPayload.result as Record<string, unknown> | undefined);
+  if (direct) return direct;
+
+  const capabilityResults = Array.isArray(objectPayload.capability_results)
+    ? (objectPayload.capability_results as Array<Record<string, unknown>>)
+    : [];
+
+  for (let i = capabilityResults.length - 1; i >= 0; i -= 1) {
+    const entry = fromResult(capabilityResults[i]?.result as Record<string, unknown> | undefined);
+    if (entry) return entry;
+  }
+
+  return null;
+}
+
+function parseSymbolSelection(value: unknown): Record<string, string[]> {
+  if (!value || typeof value !== 'object' || Array.isArray(value)) return {};
+  const out: Record<string, string[]> = {};
+  for (const [path, names] of Object.entries(value as Record<string, unknown>)) {
+    const list = Array.isArray(names) ? names.filter((name): name is string => typeof name === 'string' && !!name.trim()) : [];
+    if (list.length) out[path] = list;
+  }
+  return out;
+}
+
 function extractCompileResultsFromPayload(payload: unknown): Array<Record<string, unknown>> {
   const objectPayload = (payload ?? {}) as Record<string, unknown>;
   const directResult = objectPayload.result as Record<string, unknown> | undefined;
@@ -284,12 +398,17 @@ function formatCompileStageStream(commandResults: Array<Record<string, unknown>>
       : (typeof row.command === 'string' ? row.command.trim() : 'compile command');
     const command = typeof row.command === 'string' ? row.command : '';
     const status = typeof row.status === 'number' ? row.status : Number(row.status ?? -1);
-    const stdout = typeof row.stdout === 'string' ? row.stdout.trim() : '';
-    const stderr = typeof row.stderr === 'string' ? row.stderr.trim() : '';
+    const stdout = typeof row.stdout === 'string' ? stripAnsi(row.stdout).trim() : '';
+    const stderr = typeof row.stderr === 'string' ? stripAnsi(row.stderr).trim() : '';
 
     parts.push(`#### ${label}`);
     if (command) parts.push(`COMMAND: ${command}`);
+    if (row.skipped === true) {
+      parts.push('STATUS: skipped');
+      continue;
+    }
     parts.push(`STATUS: ${Number.isFinite(status) ? status : -1}`);
+    if (typeof row.duration_ms === 'number') parts.push(`DURATION: ${(row.duration_ms / 1000).toFixed(1)}s`);
     parts.push(`STDOUT:\n${stdout || '(empty)'}`);
     parts.push(`STDERR:\n${stderr || '(empty)'}`);
   }
@@ -501,7 +620,10 @@ const BackendDrivenStageInputsPanel = memo(function BackendDrivenStageInputsPane
   onOpenSchemaConfig: () => void;
   onOpenApplyErrorConfig: () => void;
   onOpenCompileErrorConfig: () => void;
+  onOpenCompileSimulation: () => void;
   onOpenChanges: () => void;
+  onReadDroppedFile?: (path: string) => Promise<string>;
+  repoRef: string;
 }) {
   const {
     descriptor,
@@ -524,7 +646,10 @@ const BackendDrivenStageInputsPanel = memo(function BackendDrivenStageInputsPane
     onOpenSchemaConfig,
     onOpenApplyErrorConfig,
     onOpenCompileErrorConfig,
-    onOpenChanges
+    onOpenCompileSimulation,
+    onOpenChanges,
+    onReadDroppedFile,
+    repoRef
   } = props;
 
   const fields = useMemo(() => descriptor ? flattenStageFields(descriptor) : [], [descriptor]);
@@ -660,6 +785,20 @@ const BackendDrivenStageInputsPanel = memo(function BackendDrivenStageInputsPane
     onPatchSelectedStepConfig(field.bind_to, value);
   }
 
+  async function attachDroppedFiles(field: WorkflowStageField, current: string, paths: string[]) {
+    if (!onReadDroppedFile) return;
+    const blocks = await Promise.all(
+      paths.map(async (path) => {
+        try {
+          return `File: ${path}\n\`\`\`\n${(await onReadDroppedFile(path)).replace(/\n$/, '')}\n\`\`\``;
+        } catch (err) {
+          return `File: ${path}\n(unreadable: ${err instanceof Error ? err.message : String(err)})`;
+        }
+      })
+    );
+    updateField(field, [current.trimEnd(), ...blocks].filter(Boolean).join('\n\n'));
+  }
+
   function renderField(field: WorkflowStageField) {
     const value = field.key in fieldDrafts ? fieldDrafts[field.key] : valueForField(field);
 
@@ -688,17 +827,38 @@ const BackendDrivenStageInputsPanel = memo(function BackendDrivenStageInputsPane
     }
 
     if (field.type === 'multiline_text') {
+      const text = typeof value === 'string' ? value : String(value ?? field.default ?? '');
       return (
-        <Textarea
+        <TreeDropTarget
           key={field.key}
-          label={field.label}
-          description={field.description}
-          value={typeof value === 'string' ? value : String(value ?? field.default ?? '')}
-          onChange={(event) => updateField(field, event.currentTarget.value)}
-          minRows={4}
-          autosize
-          disabled={disabled}
-        />
+          label="Drop to attach file contents"
+          disabled={disabled || !onReadDroppedFile}
+          onDropPaths={(paths) => void attachDroppedFiles(field, text, paths)}
+        >
+          <Textarea
+            label={field.label}
+            description={field.description}
+            value={text}
+            onChange={(event) => updateField(field, event.currentTarget.value)}
+            minRows={4}
+            autosize
+            disabled={disabled}
+          />
+          {field.bind_to === 'execution.compile_checks.commands_text' && !disabled ? (
+            <Box mt={6}>
+              <ProjectCommandChips
+                repoRef={repoRef}
+                label="Suggested"
+                kinds={['build', 'test', 'lint']}
+                disabledCommands={text.split('\n').map((line) => line.trim())}
+                onPick={(command) => updateField(field, [text.trimEnd(), command.command].filter(Boolean).join('\n'))}
+              />
+              <Button size="compact-xs" variant="subtle" mt={4} onClick={onOpenCompileSimulation}>
+                Simulate…
+              </Button>
+            </Box>
+          ) : null}
+        </TreeDropTarget>
       );
     }
 
@@ -731,6 +891,7 @@ const BackendDrivenStageInputsPanel = memo(function BackendDrivenStageInputsPane
           </Button>
         </Group>
       ) : null}
+      {selectedWorkflowStep?.step_type === 'compile' ? <EnvProfilesPanel repoRef={repoRef} disabled={disabled} /> : null}
       <StageModifierActions actions={modifierActions} />
     </Stack>
   );
@@ -1091,6 +1252,16 @@ export function WorkflowShell() {
   const [builderMode, setBuilderMode] = useState<BuilderMode>('builder');
   const [monitorView, setMonitorView] = useState<MonitorView>('workflow_list');
   const [activeWorkspaceTab, setActiveWorkspaceTab] = useState<WorkspaceTabKey>('workflows');
+  const [workspaceTemplateId, setWorkspaceTemplateId] = useState<string | null>(null);
+  const [workspaceByRepo, setWorkspaceByRepo] = useState<Record<string, RepoWorkspace>>({});
+  const [workspaceAutoAssociate, setWorkspaceAutoAssociate] = useState(true);
+  const [workspaceSettingsLoaded, setWorkspaceSettingsLoaded] = useState(false);
+  const [canvasLayouts, setCanvasLayouts] = useState<Record<string, WorkflowCanvasLayout>>({});
+  const [workspaceLayout, setWorkspaceLayout] = useState<WorkspaceLayout>({ split_percent: null, snap_percent: 5, arrangement: 'columns' });
+  const [workspaceWelcomeOpen, setWorkspaceWelcomeOpen] = useState(false);
+  const [fileOpenRequest, setFileOpenRequest] = useState<{ path: string; nonce: number } | null>(null);
+  const [debugOverlayOpen, setDebugOverlayOpen] = useState(false);
+  const { width: viewportWidth } = useViewportSize();
   const [busy, setBusy] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
@@ -1110,6 +1281,24 @@ export function WorkflowShell() {
   const runEventStreamsRef = useRef<Record<string, EventSource>>({});
   const runRefreshTimersRef = useRef<Record<string, number>>({});
 
+  async function saveCompileCommandOptions(next: PostprocessOptions) {
+    if (!selectedRun) return;
+    const currentGlobalState = ((selectedRun.context?.workflow_engine as Record<string, unknown> | undefined)?.global_state as Record<string, unknown> | undefined) ?? {};
+    const currentCapabilities = (currentGlobalState.capabilities as Record<string, unknown> | undefined) ?? {};
+    const currentCompileCommands = (currentCapabilities.compile_commands as Record<string, unknown> | undefined) ?? {};
+    await patchWorkflowGlobalState(selectedRun.id, {
+      ...currentGlobalState,
+      capabilities: {
+        ...currentCapabilities,
+        compile_commands: {
+          ...currentCompileCommands,
+          skip: next.skip,
+          timeout_secs: next.timeout_secs
+        }
+      }
+    });
+    await refreshRunDetails(selectedRun.id);
+  }
 
   function patchSelectedStepDescriptorField(bindTo: string, value: unknown) {
     if (!selectedRunId || !selectedWorkflowStep) return;
@@ -1215,6 +1404,8 @@ export function WorkflowShell() {
   const [manualCapabilityResponse, setManualCapabilityResponse] = useState('');
 
   const [inferenceTransport, setInferenceTransport] = useState<InferenceTransport>('api');
+  const [inferenceModel, setInferenceModel] = useState('gpt-4.1');
+  const [inferenceGeneration, setInferenceGeneration] = useState<InferenceGenerationSettings>({});
   const [browserTargetUrl, setBrowserTargetUrl] = useState('https://website.com/');
   const [browserCdpUrl, setBrowserCdpUrl] = useState('');
   const [browserSessionId, setBrowserSessionId] = useState('');
@@ -1225,13 +1416,24 @@ export function WorkflowShell() {
   const [inferenceConnected, setInferenceConnected] = useState(false);
 
   const [stageUserInput, setStageUserInput] = useState('');
+  const pendingAskAiRef = useRef<{ runId: string; stepId: string; text: string } | null>(null);
+  const [askAiNonce, setAskAiNonce] = useState(0);
   const [stageIncludeRepoContext, setStageIncludeRepoContext] = useState(false);
   const [stageRepoContextGitRef, setStageRepoContextGitRef] = useState('WORKTREE');
   const [stageRepoContextIncludeFilesText, setStageRepoContextIncludeFilesText] = useState('');
   const [stageRepoContextExcludeRegexText, setStageRepoContextExcludeRegexText] = useState('');
   const [stageRepoContextSavePath, setStageRepoContextSavePath] = useState('/tmp/repo_context.txt');
   const [stageRepoContextSkipBinary, setStageRepoContextSkipBinary] = useState(true);
+  const [stageRepoContextFormat, setStageRepoContextFormat] = useState<'text' | 'zip'>('text');
+  const [stageRepoContextIfExists, setStageRepoContextIfExists] = useState<'overwrite' | 'new_version'>('overwrite');
+  const [exportDestinations, setExportDestinations] = useState<ExportDestination[]>([]);
+  const [stageRepoContextSymbols, setStageRepoContextSymbols] = useState<Record<string, string[]>>({});
   const [stageRepoContextSkipGitignore, setStageRepoContextSkipGitignore] = useState(true);
+  const [stageRepoContextMaxBytesPerFile, setStageRepoContextMaxBytesPerFile] = useState<number | null>(null);
+  const [stageRepoContextKeepSignatures, setStageRepoContextKeepSignatures] = useState(false);
+  const [stageRepoContextInjection, setStageRepoContextInjection] = useState<InjectionStrategy>(DEFAULT_INJECTION_STRATEGY);
+  const [stageRepoContextRefreshOnApply, setStageRepoContextRefreshOnApply] = useState(true);
+  const [stageRepoContextFileBudgets, setStageRepoContextFileBudgets] = useState<Record<string, Record<string, FileBudget>>>({});
   const [stageRepoContextIncludeStagedDiff, setStageRepoContextIncludeStagedDiff] = useState(false);
   const [stageRepoContextIncludeUnstagedDiff, setStageRepoContextIncludeUnstagedDiff] = useState(false);
   const [stageIncludeChangesetSchema, setStageIncludeChangesetSchema] = useState(true);
@@ -1254,10 +1456,28 @@ export function WorkflowShell() {
   const [globalApplyChangesetOpen, setGlobalApplyChangesetOpen] = useState(false);
   const [globalApplyChangesetText, setGlobalApplyChangesetText] = useState('');
   const [globalApplyChangesetResult, setGlobalApplyChangesetResult] = useState<ApplyChangesetResponse | null>(null);
+  const [globalChangesetLint, setGlobalChangesetLint] = useState<ChangesetLintReport | null>(null);
+  const [globalChangesetLintBusy, setGlobalChangesetLintBusy] = useState(false);
+  const [globalChangesetStageAfterApply, setGlobalChangesetStageAfterApply] = useState(false);
+  const [globalChangesetCommitAfterApply, setGlobalChangesetCommitAfterApply] = useState(false);
   const [globalApplyChangesetPanelMode, setGlobalApplyChangesetPanelMode] = useState<'input' | 'output'>('input');
   const [globalApplyChangesetHistory, setGlobalApplyChangesetHistory] = useState<ChangesetAttemptSummary[]>([]);
   const [globalApplyChangesetHistoryBusy, setGlobalApplyChangesetHistoryBusy] = useState(false);
   const [gitPatchPayloadOpen, setGitPatchPayloadOpen] = useState(false);
+  const [networkSettingsOpen, setNetworkSettingsOpen] = useState(false);
+  const [settingsBundleOpen, setSettingsBundleOpen] = useState(false);
+  const [updatesOpen, setUpdatesOpen] = useState(false);
+  const [accessibilityOpen, setAccessibilityOpen] = useState(false);
+  const [pluginPaletteOpen, setPluginPaletteOpen] = useState(false);
+  const [automationOpen, setAutomationOpen] = useState(false);
+  const [mcpServerOpen, setMcpServerOpen] = useState(false);
+  const [doctorOpen, setDoctorOpen] = useState(false);
+  const [processesOpen, setProcessesOpen] = useState(false);
+  const [compileSimulationOpen, setCompileSimulationOpen] = useState(false);
+  const [capabilityTracesOpen, setCapabilityTracesOpen] = useState(false);
+  const [doctorFailures, setDoctorFailures] = useState<DoctorCheck[]>([]);
+  const [doctorWarningDismissed, setDoctorWarningDismissed] = useState(false);
+  const [conversationImportOpen, setConversationImportOpen] = useState(false);
   const [gitPatchPayloadMode, setGitPatchPayloadMode] = useState<'generate' | 'apply'>('generate');
   const [gitPatchPayloadScope, setGitPatchPayloadScope] = useState<'staged' | 'unstaged' | 'both'>('both');
   const [gitPatchPayloadText, setGitPatchPayloadText] = useState('');
@@ -1265,6 +1485,9 @@ export function WorkflowShell() {
   const [gitPatchPayloadBusy, setGitPatchPayloadBusy] = useState(false);
   const [gitPatchPayloadStatus, setGitPatchPayloadStatus] = useState<string | null>(null);
   const [responseViewerOpen, setResponseViewerOpen] = useState(false);
+  const [transcriptOpen, setTranscriptOpen] = useState(false);
+  const [requestViewerOpen, setRequestViewerOpen] = useState(false);
+  const [autoRunGuardsOpen, setAutoRunGuardsOpen] = useState(false);
   const [compileErrorConfigOpen, setCompileErrorConfigOpen] = useState(false);
   const [runContextOpen, setRunContextOpen] = useState(false);
   const [previewViewerMode, setPreviewViewerMode] = useState<'prompt' | 'response' | 'stream'>('stream');
@@ -1299,7 +1522,51 @@ export function WorkflowShell() {
     };
   }, []);
 
+  useEffect(() => {
+    void getDoctorReport()
+      .then((checks) => setDoctorFailures(checks.filter((check) => check.status === 'fail')))
+      .catch(() => undefined);
+  }, []);
+
+  const openRequestedRepoRef = useRef<(path: string) => void>(() => undefined);
+  openRequestedRepoRef.current = (path) => {
+    setRepoRef(path);
+    void openBuilder();
+    window.focus();
+  };
+
+  useEffect(() => {
+    const stream = openInstanceRequestStream();
+    stream.addEventListener('open_repo', (event) => {
+      const path = JSON.parse((event as MessageEvent<string>).data)?.path;
+      if (typeof path === 'string' && path) openRequestedRepoRef.current(path);
+    });
+    return () => stream.close();
+  }, []);
+
   const selectedRun = useMemo(() => runs.find((run) => run.id === selectedRunId) ?? null, [runs, selectedRunId]);
+  const contextTreeGitStatus = useRepoGitDecorations(repoContextConfigOpen ? (view === 'builder' ? repoRef : (selectedRun?.repo_ref ?? repoRef)) : '');
+  const selectedRunNotes = useMemo(() => {
+    const globalState = ((selectedRun?.context?.workflow_engine as Record<string, unknown> | undefined)?.global_state ?? {}) as Record<string, unknown>;
+    const notes = (globalState.notes ?? {}) as Record<string, unknown>;
+    return {
+      markdown: typeof notes.markdown === 'string' ? notes.markdown : '',
+      updatedAt: typeof notes.updated_at === 'string' ? notes.updated_at : '',
+    };
+  }, [selectedRun]);
+  const lastContextInjection = useMemo(() => {
+    const globalState = ((selectedRun?.context?.workflow_engine as Record<string, unknown> | undefined)?.global_state as Record<string, unknown> | undefined) ?? {};
+    const contextExport = ((globalState.capabilities as Record<string, unknown> | undefined)?.context_export as Record<string, unknown> | undefined) ?? {};
+    return parseLastInjection(contextExport.last_injection);
+  }, [selectedRun]);
+  const compileCommandOptions = useMemo<PostprocessOptions>(() => {
+    const globalState = ((selectedRun?.context?.workflow_engine as Record<string, unknown> | undefined)?.global_state as Record<string, unknown> | undefined) ?? {};
+    const compileCommands = ((globalState.capabilities as Record<string, unknown> | undefined)?.compile_commands as Record<string, unknown> | undefined) ?? {};
+    return {
+      skip: Array.isArray(compileCommands.skip) ? compileCommands.skip.filter((entry): entry is string => typeof entry === 'string') : [],
+      timeout_secs: typeof compileCommands.timeout_secs === 'number' && compileCommands.timeout_secs > 0 ? compileCommands.timeout_secs : null
+    };
+  }, [selectedRun]);
   const isInteractiveMode = selectedRun?.status === 'paused' || selectedRun?.status === 'waiting' || selectedRun?.status === 'draft';
   const isManualMode = isInteractiveMode;
   const isBackendRunLocked = Boolean(
@@ -1504,6 +1771,10 @@ export function WorkflowShell() {
     const inference = (capabilities.inference ?? null) as Record<string, unknown> | null;
     return inference;
   }, [selectedRun?.context]);
+  const aiActionModel = useMemo<AiActionModel>(() => ({
+    model: typeof sharedInferenceState?.model === 'string' ? sharedInferenceState.model : undefined,
+    generation: sharedInferenceState ? readGenerationSettings(sharedInferenceState.generation) : undefined
+  }), [sharedInferenceState]);
   const selectedStageState = useMemo(() => {
     const workflowEngine = (selectedRun?.context as Record<string, unknown> | undefined)?.workflow_engine as Record<string, unknown> | undefined;
     const stageOverrides = (workflowEngine?.stage_overrides ?? {}) as Record<string, unknown>;
@@ -1533,7 +1804,10 @@ export function WorkflowShell() {
       diff_style: sourceControl.diff_style === 'split' ? 'split' : 'unified',
       only_changes: sourceControl.only_changes !== false,
       context_lines: typeof sourceControl.context_lines === 'number' ? sourceControl.context_lines : 10,
-      whole_file: Boolean(sourceControl.whole_file)
+      whole_file: Boolean(sourceControl.whole_file),
+      whitespace_mode: parseWhitespaceMode(sourceControl.whitespace_mode),
+      sort: sourceControl.sort === 'churn' || sourceControl.sort === 'status' ? sourceControl.sort : 'path',
+      layout: sourceControl.layout === 'tree' ? 'tree' : 'flat'
     };
   }, [selectedStageState]);
   const [localReviewSourceControlState, setLocalReviewSourceControlState] = useState<ReviewSourceControlState>({
@@ -1542,7 +1816,10 @@ export function WorkflowShell() {
     diff_style: 'unified',
     only_changes: true,
     context_lines: 10,
-    whole_file: false
+    whole_file: false,
+    whitespace_mode: 'none',
+    sort: 'path',
+    layout: 'flat'
   });
   useEffect(() => {
     if (selectedWorkflowStep?.step_type === 'review') {
@@ -1699,6 +1976,8 @@ export function WorkflowShell() {
     const inference = (sharedInferenceState ?? null) as Record<string, unknown> | null;
     if (!inference) {
       setInferenceTransport('api');
+      setInferenceModel('gpt-4.1');
+      setInferenceGeneration({});
       setBrowserTargetUrl('https://website.com/');
       setBrowserCdpUrl('');
       setBrowserSessionId('');
@@ -1707,6 +1986,8 @@ export function WorkflowShell() {
     }
 
     setInferenceTransport((inference.transport as InferenceTransport) ?? 'api');
+    setInferenceModel(typeof inference.model === 'string' && inference.model.trim() ? inference.model : 'gpt-4.1');
+    setInferenceGeneration(readGenerationSettings(inference.generation));
 
     const browser = (inference.browser ?? {}) as Record<string, unknown>;
     setBrowserTargetUrl(typeof browser.target_url === 'string' ? browser.target_url : 'https://website.com/');
@@ -1843,11 +2124,89 @@ export function WorkflowShell() {
         : '/tmp/repo_context.txt'
     );
     setStageRepoContextSkipBinary(typeof repoContext.skip_binary === 'boolean' ? repoContext.skip_binary : true);
+    setStageRepoContextFormat(repoContext.format === 'zip' ? 'zip' : 'text');
+    setStageRepoContextIfExists(repoContext.if_exists === 'new_version' ? 'new_version' : 'overwrite');
+    setStageRepoContextSymbols(parseSymbolSelection(repoContext.symbols));
     setStageRepoContextSkipGitignore(typeof repoContext.skip_gitignore === 'boolean' ? repoContext.skip_gitignore : true);
+    setStageRepoContextMaxBytesPerFile(typeof repoContext.max_bytes_per_file === 'number' && repoContext.max_bytes_per_file > 0 ? repoContext.max_bytes_per_file : null);
+    setStageRepoContextKeepSignatures(Boolean(repoContext.keep_signatures));
+    setStageRepoContextInjection(parseInjectionStrategy(repoContext.injection));
+    setStageRepoContextRefreshOnApply(repoContext.refresh_on_apply !== false);
+    setStageRepoContextFileBudgets(parseFileBudgets(repoContext.file_budgets_by_ref));
     setStageRepoContextIncludeStagedDiff(Boolean(repoContext.include_staged_diff));
     setStageRepoContextIncludeUnstagedDiff(Boolean(repoContext.include_unstaged_diff));
   }, [selectedStageHydrationKey, selectedRun?.context, selectedStageState]);
 
+  // Runs after hydration above so the question is appended to, not replaced by, the stored draft.
+  useEffect(() => {
+    const pending = pendingAskAiRef.current;
+    if (!pending || pending.runId !== selectedRunId || pending.stepId !== selectedWorkflowStep?.id) return;
+    pendingAskAiRef.current = null;
+    setStageUserInput((current) => [current.trimEnd(), pending.text].filter(Boolean).join('\n\n'));
+  }, [selectedRunId, selectedWorkflowStep?.id, askAiNonce]);
+
+  /** Puts a diff hunk or editor selection into the user input of the selected (or latest) inference loop for the repo. */
+  async function handleAskAi(snippet: AskAiSnippet) {
+    const repo = snippet.repoRef.trim();
+    const isLoopStep = (step: { step_type: string }) => step.step_type === 'design' || step.step_type === 'code';
+    try {
+      setError(null);
+      let run = selectedRun && selectedRun.repo_ref.trim() === repo && selectedRun.definition?.steps.some(isLoopStep) ? selectedRun : null;
+      run ??= runs
+        .filter((candidate) => candidate.repo_ref.trim() === repo && candidate.definition?.steps.some(isLoopStep))
+        .sort((a, b) => b.updated_at.localeCompare(a.updated_at))[0] ?? null;
+      if (!run) {
+        const template = templates.find((candidate) => candidate.repo_ref.trim() === repo && candidate.definition.steps.some(isLoopStep))
+          ?? templates.find((candidate) => candidate.definition.steps.some(isLoopStep));
+        if (!template) {
+          setError('Create a workflow with a design or code stage to ask questions about this repository.');
+          return;
+        }
+        run = await createRun({
+          template_id: template.id,
+          title: `Question about ${snippet.path}`,
+          repo_ref: repo,
+          definition: template.definition,
+          context: { workflow_engine: {} }
+        });
+        await refreshRunsAndTemplates(run.id);
+      }
+      const target = run;
+      const steps = target.definition?.steps ?? [];
+      const step = (target.id === selectedRunId && selectedWorkflowStep && isLoopStep(selectedWorkflowStep) ? selectedWorkflowStep : null)
+        ?? steps.find((candidate) => candidate.id === target.current_step_id && isLoopStep(candidate))
+        ?? steps.find(isLoopStep);
+      if (!step) return;
+      pendingAskAiRef.current = { runId: target.id, stepId: step.id, text: formatAskAiDraft(snippet) };
+      setSelectedRunId(target.id);
+      setSelectedStepId(step.id);
+      setView('monitor');
+      setMonitorView('workflow_detail');
+      setAskAiNonce((value) => value + 1);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
+  /** Creates a "Generate tests" run for a file or function and opens it in the monitor. */
+  async function handleGenerateTests(request: { repoRef: string; path: string; symbol: string | null }) {
+    try {
+      setError(null);
+      const task = await generateTestsTask({ repo_ref: request.repoRef, path: request.path, symbol: request.symbol, ...aiActionModel });
+      const run = await createRun({
+        title: task.title,
+        repo_ref: request.repoRef,
+        definition: task.definition,
+        context: task.context
+      });
+      await refreshRunsAndTemplates(run.id);
+      setView('monitor');
+      setMonitorView('workflow_detail');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }
+
   function buildInteractiveGlobalStatePayload() {
     const includeFiles = stageRepoContextIncludeFilesText
       .split('\n')
@@ -1918,6 +2277,14 @@ export function WorkflowShell() {
           save_path: stageRepoContextSavePath || '/tmp/repo_context.txt',
           skip_binary: stageRepoContextSkipBinary,
           skip_gitignore: stageRepoContextSkipGitignore,
+          format: stageRepoContextFormat,
+          if_exists: stageRepoContextIfExists,
+          symbols: stageRepoContextSymbols,
+          max_bytes_per_file: stageRepoContextMaxBytesPerFile,
+          keep_signatures: stageRepoContextKeepSignatures,
+          injection: stageRepoContextInjection,
+          refresh_on_apply: stageRepoContextRefreshOnApply,
+          file_budgets_by_ref: stageRepoContextFileBudgets,
           include_staged_diff: stageRepoContextIncludeStagedDiff,
           include_unstaged_diff: stageRepoContextIncludeUnstagedDiff
         },
@@ -1955,6 +2322,21 @@ export function WorkflowShell() {
     stageRepoContextSkipGitignore
   ]);
 
+  useEffect(() => {
+    if (!repoContextConfigOpen) return;
+    void listExportDestinations()
+      .then((destinations) => {
+        setExportDestinations(destinations);
+        // A never-changed save path picks up where the last export of this format left off.
+        const last = destinations.find((item) => item.exporter === `context_export.${stageRepoContextFormat}`);
+        if (last && last.save_path && stageRepoContextSavePath === '/tmp/repo_context.txt') {
+          setStageRepoContextSavePath(last.save_path);
+          setStageRepoContextIfExists(last.if_exists === 'new_version' ? 'new_version' : 'overwrite');
+        }
+      })
+      .catch(() => setExportDestinations([]));
+  }, [repoContextConfigOpen]);
+
   useEffect(() => {
     if (!repoContextConfigOpen) return;
     setTreeRootData(null);
@@ -2804,7 +3186,15 @@ export function WorkflowShell() {
         : '/tmp/repo_context.txt'
     );
     setStageRepoContextSkipBinary(typeof contextExport.skip_binary === 'boolean' ? contextExport.skip_binary : true);
+    setStageRepoContextFormat(contextExport.format === 'zip' ? 'zip' : 'text');
+    setStageRepoContextIfExists(contextExport.if_exists === 'new_version' ? 'new_version' : 'overwrite');
+    setStageRepoContextSymbols(parseSymbolSelection(contextExport.symbols));
     setStageRepoContextSkipGitignore(typeof contextExport.skip_gitignore === 'boolean' ? contextExport.skip_gitignore : true);
+    setStageRepoContextMaxBytesPerFile(typeof contextExport.max_bytes_per_file === 'number' && contextExport.max_bytes_per_file > 0 ? contextExport.max_bytes_per_file : null);
+    setStageRepoContextKeepSignatures(Boolean(contextExport.keep_signatures));
+    setStageRepoContextInjection(parseInjectionStrategy(contextExport.injection));
+    setStageRepoContextRefreshOnApply(contextExport.refresh_on_apply !== false);
+    setStageRepoContextFileBudgets(parseFileBudgets(contextExport.file_budgets_by_ref));
     setStageRepoContextIncludeStagedDiff(Boolean(contextExport.include_staged_diff));
     setStageRepoContextIncludeUnstagedDiff(Boolean(contextExport.include_unstaged_diff));
   }
@@ -2967,15 +3357,35 @@ export function WorkflowShell() {
       const json = await applyWorkflowChangeset(selectedRun.workflow_key || selectedRun.id, {
         git_ref: 'WORKTREE',
         payload_text: globalApplyChangesetText,
+        stage_after_apply: globalChangesetStageAfterApply || globalChangesetCommitAfterApply,
+        commit_after_apply: globalChangesetCommitAfterApply,
       });
       const cleanJson = changesetOutputWithoutPayload(json);
       setGlobalApplyChangesetResult(cleanJson);
+      setGlobalChangesetLint(json.lint ?? null);
       setGlobalApplyChangesetPanelMode('output');
       await refreshChangesetHistory();
       return cleanJson;
     }, 'Changeset applied.');
   }
 
+  async function handleLintGlobalChangeset() {
+    if (!selectedRun?.id) return;
+    try {
+      setGlobalChangesetLintBusy(true);
+      setManualCapabilityStatus(null);
+      const report = await lintWorkflowChangeset(selectedRun.workflow_key || selectedRun.id, {
+        payload_text: globalApplyChangesetText,
+      });
+      setGlobalChangesetLint(report);
+    } catch (err) {
+      setGlobalChangesetLint(null);
+      setManualCapabilityStatus(`Lint failed: ${err instanceof Error ? err.message : String(err)}`);
+    } finally {
+      setGlobalChangesetLintBusy(false);
+    }
+  }
+
   function globalApplyResultText() {
     if (!globalApplyChangesetResult) return '';
     return JSON.stringify(changesetOutputWithoutPayload(globalApplyChangesetResult), null, 2);
@@ -3046,8 +3456,27 @@ export function WorkflowShell() {
     setManualCapabilityStatus(`${label} copied.`);
   }
 
+  async function pasteGlobalChangesetFromClipboard() {
+    try {
+      const text = await navigator.clipboard.readText();
+      const report = await ingestChangesetText({ payload_text: text });
+      setGlobalApplyChangesetText(report.cleaned_text);
+      setGlobalChangesetLint(null);
+      setGlobalApplyChangesetPanelMode('input');
+      const strippedNote = report.stripped.length ? ` Stripped ${report.stripped.join(', ')}.` : '';
+      setManualCapabilityStatus(
+        report.error
+          ? `Pasted changeset has an error at line ${report.error.line}, column ${report.error.column}: ${report.error.message}.${strippedNote}`
+          : `Pasted a valid changeset from the clipboard.${strippedNote}`
+      );
+    } catch (err) {
+      setManualCapabilityStatus(`Clipboard paste failed: ${err instanceof Error ? err.message : String(err)}`);
+    }
+  }
+
   function newGlobalChangeset() {
     setGlobalApplyChangesetText('');
+    setGlobalChangesetLint(null);
     setGlobalApplyChangesetResult(null);
     setGlobalApplyChangesetPanelMode('input');
     setManualCapabilityStatus(null);
@@ -3303,33 +3732,72 @@ export function WorkflowShell() {
     return '';
   }, [events, selectedStepId, selectedLiveExecutionState, selectedLiveStageTrail]);
 
+  const lastGuardEvent = useMemo(() => {
+    for (let i = events.length - 1; i >= 0; i -= 1) {
+      if (events[i].kind === 'automatic_run_started') return null;
+      if (events[i].kind === 'automatic_run_guard_fired') return events[i];
+    }
+    return null;
+  }, [events]);
+
+  const lastInferenceRequest = useMemo(() => {
+    const executionItems = selectedLiveExecutionState?.chain?.items ?? [];
+    for (let i = executionItems.length - 1; i >= 0; i -= 1) {
+      const request = extractInferenceRequestFromPayload(executionItems[i].payload);
+      if (request) return request;
+    }
+
+    const stageEvents = selectedStepId ? events.filter((event) => event.step_id === selectedStepId) : events;
+    for (let i = stageEvents.length - 1; i >= 0; i -= 1) {
+      const request = extractInferenceRequestFromPayload(stageEvents[i].payload);
+      if (request) return request;
+    }
+    return null;
+  }, [events, selectedStepId, selectedLiveExecutionState]);
+
+  const transcriptMessages = useMemo(() => buildTranscriptMessages(events), [events]);
+
+  const compileResults = useMemo(() => {
+    if (selectedWorkflowStep?.step_type !== 'compile') return [];
+    const executionItems = selectedLiveExecutionState?.chain?.items ?? [];
+    for (let i = executionItems.length - 1; i >= 0; i -= 1) {
+      const rows = extractCompileResultsFromPayload(executionItems[i].payload);
+      if (rows.length > 0) return rows;
+    }
+
+    const stageEvents = selectedStepId ? events.filter((event) => event.step_id === selectedStepId) : events;
+    for (let i = stageEvents.length - 1; i >= 0; i -= 1) {
+      const rows = extractCompileResultsFromPayload(stageEvents[i].payload);
+      if (rows.length > 0) return rows;
+    }
+    return [];
+  }, [events, selectedLiveExecutionState, selectedStepId, selectedWorkflowStep?.id]);
+
+  const workspacePaneActivity = useMemo(() => {
+    const workflows: PaneActivity[] = [];
+    const diff: PaneActivity[] = [];
+    for (const event of events) {
+      const at = Date.parse(event.created_at);
+      const failedCommand = extractCompileResultsFromPayload(event.payload).find((row) => Number(row.status ?? -1) !== 0);
+      if (event.level === 'error' || failedCommand) {
+        const label = failedCommand ? `${String(failedCommand.label ?? failedCommand.command ?? 'command')} exited ${String(failedCommand.status ?? -1)}` : event.message;
+        workflows.push({ id: event.id, tone: 'failure', label, at });
+      } else if (event.kind.includes('changeset') || event.kind.includes('apply')) {
+        const item: PaneActivity = { id: event.id, tone: 'apply', label: event.message, at };
+        workflows.push(item);
+        diff.push(item);
+      } else if (event.kind === 'stage_execution_completed') {
+        workflows.push({ id: event.id, tone: 'message', label: event.message, at });
+      }
+    }
+    return { workflows, diff };
+  }, [events]);
+
   const stageStreamContent = useMemo(() => {
     const parts: string[] = [];
     if (composedInferencePrompt.trim()) parts.push(`### INPUT\n${composedInferencePrompt}`);
 
     if (selectedWorkflowStep?.id === 'compile') {
-      const executionItems = selectedLiveExecutionState?.chain?.items ?? [];
-      let compileResults: Array<Record<string, unknown>> = [];
-
-      for (let i = executionItems.length - 1; i >= 0; i -= 1) {
-        const rows = extractCompileResultsFromPayload(executionItems[i].payload);
-        if (rows.length > 0) {
-          compileResults = rows;
-          break;
-        }
-      }
-
-      if (compileResults.length === 0) {
-        const stageEvents = selectedStepId ? events.filter((event) => event.step_id === selectedStepId) : events;
-        for (let i = stageEvents.length - 1; i >= 0; i -= 1) {
-          const rows = extractCompileResultsFromPayload(stageEvents[i].payload);
-          if (rows.length > 0) {
-            compileResults = rows;
-            break;
-          }
-        }
-      }
-
       if (compileResults.length > 0) {
         parts.push(formatCompileStageStream(compileResults));
       } else if (selectedLiveExecutionState?.loading) {
@@ -3343,7 +3811,7 @@ export function WorkflowShell() {
 
     if (inferenceResponse.trim()) parts.push(`### OUTPUT\n${inferenceResponse}`);
     return parts.join('\n\n');
-  }, [composedInferencePrompt, events, inferenceResponse, selectedLiveExecutionState, selectedStepId, selectedWorkflowStep?.id]);
+  }, [compileResults, composedInferencePrompt, inferenceResponse, selectedLiveExecutionState, selectedWorkflowStep?.id]);
 
   function getBoolean(value: unknown): boolean | null {
   return typeof value === 'boolean' ? value : null;
@@ -3685,6 +4153,21 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
           <Text fw={600}>{title}</Text>
           <Group gap="xs">
             <Badge variant="light">{content ? `${content.length.toLocaleString()} chars` : 'empty'}</Badge>
+            {mode !== 'prompt' && lastInferenceRequest ? (
+              <Button size="xs" variant="default" onClick={() => setRequestViewerOpen(true)}>
+                Sent request
+              </Button>
+            ) : null}
+            {mode !== 'prompt' && transcriptMessages.length > 0 ? (
+              <Button size="xs" variant="default" onClick={() => setTranscriptOpen(true)}>
+                Transcript
+              </Button>
+            ) : null}
+            {mode !== 'prompt' && selectedRunId ? (
+              <Button size="xs" variant="default" onClick={() => setConversationImportOpen(true)}>
+                Import chat
+              </Button>
+            ) : null}
             <Button size="xs" variant="light" onClick={() => { setPreviewViewerMode(mode); setResponseViewerOpen(true); }}>
               Full screen
             </Button>
@@ -3697,6 +4180,44 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
     );
   }
 
+  function openCompileOutputFile(path: string) {
+    const root = resolveRepoRefForRun(selectedRun).replace(/\\/g, '/').replace(/\/+$/, '');
+    const normalized = path.replace(/\\/g, '/');
+    openInFileViewer(root && normalized.startsWith(`${root}/`) ? normalized.slice(root.length + 1) : normalized.replace(/^\.\//, ''));
+  }
+
+  /** Runs one command in a terminal pane through the compile_commands capability, outside the stage's own commands. */
+  async function runTerminalCommand(pane: TerminalPane, command: { command: string; label: string }) {
+    if (!selectedRun) throw new Error('Select a run first.');
+    const response = await executeWorkflowCapability(selectedRun.id, 'compile_commands', {
+      commands: [{ command: command.command, label: command.label }],
+      working_dir: pane.working_dir.trim() || undefined,
+      shell: pane.shell || undefined
+    });
+    const results = Array.isArray(response.results) ? (response.results as Array<Record<string, unknown>>) : [];
+    return results.flatMap((item) => extractCompileResultsFromPayload({ result: item.payload }));
+  }
+
+  function renderCompileOutputPanel() {
+    return (
+      <Stack gap="xs" h="100%" {...focusRegionProps('terminal')}>
+        <Group justify="space-between" align="center">
+          <Text fw={600}>Compile output</Text>
+          <Button size="xs" variant="light" onClick={() => { setPreviewViewerMode('stream'); setResponseViewerOpen(true); }}>
+            Full screen
+          </Button>
+        </Group>
+        <TerminalPanes
+          key={selectedRunId ?? ''}
+          repoRef={resolveRepoRefForRun(selectedRun)}
+          stageRows={compileResults}
+          runCommand={runTerminalCommand}
+          onOpenFile={openCompileOutputFile}
+        />
+      </Stack>
+    );
+  }
+
   function resolveRepoRefForRun(run: WorkflowRun | null): string {
     const workflowEngine = (run?.context as Record<string, unknown> | undefined)?.workflow_engine as Record<string, unknown> | undefined;
     const globalState = (workflowEngine?.global_state ?? {}) as Record<string, unknown>;
@@ -3742,6 +4263,8 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
             repoRef={resolveRepoRefForRun(selectedRun)}
             state={reviewSourceControlState}
             onPersistState={persistReviewSourceControlState}
+            onAskAi={(snippet) => void handleAskAi(snippet)}
+            aiModel={aiActionModel}
           />
         </Suspense>
       );
@@ -3749,6 +4272,9 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
     if (selectedWorkflowStep?.step_type === 'sap_export') {
       return <></>;
     }
+    if (selectedWorkflowStep?.step_type === 'compile') {
+      return renderCompileOutputPanel();
+    }
     return renderPreviewPanel('Stage stream', stageStreamContent, emptyText, 'stream');
   }
 
@@ -3961,6 +4487,8 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
     const inference = ((capabilities.inference as Record<string, unknown> | undefined) ?? {}) as Record<string, unknown>;
     const browser = ((inference.browser as Record<string, unknown> | undefined) ?? {}) as Record<string, unknown>;
     setInferenceTransport(((typeof inference.transport === 'string' ? inference.transport : 'api') as InferenceTransport) ?? 'api');
+    setInferenceModel(typeof inference.model === 'string' && inference.model.trim() ? inference.model : 'gpt-4.1');
+    setInferenceGeneration(readGenerationSettings(inference.generation));
     setBrowserTargetUrl(typeof browser.target_url === 'string' ? browser.target_url : '');
     setBrowserCdpUrl(typeof browser.cdp_url === 'string' ? browser.cdp_url : '');
     setBrowserSessionId(typeof browser.session_id === 'string' ? browser.session_id : '');
@@ -4097,7 +4625,8 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
     patchBuilderCapability(capabilityKey, patch);
   }
 
-  async function handleSaveBuilderRepoContext() {
+  /** The context_export settings as currently edited in the repo context modal. */
+  function stagedContextExportConfig() {
     const includeFiles = stageRepoContextIncludeFilesText
       .split('\n')
       .map((item) => item.trim())
@@ -4106,18 +4635,32 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
       .split('\n')
       .map((item) => item.trim())
       .filter(Boolean);
+    return {
+      git_ref: stageRepoContextGitRef.trim() || 'WORKTREE',
+      include_files: includeFiles,
+      exclude_regex: excludeRegex,
+      save_path: stageRepoContextSavePath.trim() || '/tmp/repo_context.txt',
+      skip_binary: stageRepoContextSkipBinary,
+      skip_gitignore: stageRepoContextSkipGitignore,
+      include_staged_diff: stageRepoContextIncludeStagedDiff,
+      include_unstaged_diff: stageRepoContextIncludeUnstagedDiff,
+      format: stageRepoContextFormat,
+      if_exists: stageRepoContextIfExists,
+      symbols: stageRepoContextSymbols,
+      max_bytes_per_file: stageRepoContextMaxBytesPerFile,
+      keep_signatures: stageRepoContextKeepSignatures,
+      injection: stageRepoContextInjection,
+      refresh_on_apply: stageRepoContextRefreshOnApply,
+      file_budgets_by_ref: stageRepoContextFileBudgets,
+    };
+  }
+
+  async function handleSaveBuilderRepoContext() {
+    const config = stagedContextExportConfig();
+    const includeFiles = config.include_files;
 
     if (view === 'builder') {
-      saveBuilderCapability('context_export', {
-        git_ref: stageRepoContextGitRef.trim() || 'WORKTREE',
-        include_files: includeFiles,
-        exclude_regex: excludeRegex,
-        save_path: stageRepoContextSavePath.trim() || '/tmp/repo_context.txt',
-        skip_binary: stageRepoContextSkipBinary,
-        skip_gitignore: stageRepoContextSkipGitignore,
-        include_staged_diff: stageRepoContextIncludeStagedDiff,
-        include_unstaged_diff: stageRepoContextIncludeUnstagedDiff,
-      });
+      saveBuilderCapability('context_export', config);
       syncRepoSelectionState(includeFiles);
       setRepoContextConfigOpen(false);
       return;
@@ -4142,6 +4685,10 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
       }
       const inferencePatch = {
         transport: inferenceTransport,
+        model: inferenceModel.trim() || 'gpt-4.1',
+        generation: Object.fromEntries(
+          Object.entries(inferenceGeneration).filter(([, value]) => value !== null && value !== undefined)
+        ),
         browser: browserPatch,
       };
       if (view === 'builder') {
@@ -4231,6 +4778,23 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
     toggleExpandedSet(setExpandedStageIds, stepId);
   }
 
+  useFocusNavigation();
+
+  useEffect(() => {
+    const handler = (event: KeyboardEvent) => {
+      if (event.altKey && !event.ctrlKey && !event.metaKey && !event.shiftKey && event.key === '0') {
+        event.preventDefault();
+        setDebugOverlayOpen((prev) => !prev);
+      }
+      if ((event.ctrlKey || event.metaKey) && event.shiftKey && !event.altKey && event.key.toLowerCase() === 'p') {
+        event.preventDefault();
+        setPluginPaletteOpen(true);
+      }
+    };
+    window.addEventListener('keydown', handler);
+    return () => window.removeEventListener('keydown', handler);
+  }, []);
+
   useEffect(() => {
     if (view === 'builder' || monitorView !== 'workflow_detail') {
       return;
@@ -4278,6 +4842,13 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
 
       if (key === '5') {
         setActiveWorkspaceTab('capabilities');
+        return;
+      }
+
+      if (key === '6') {
+        if (hasRepoRef) {
+          setActiveWorkspaceTab('analysis');
+        }
       }
     };
 
@@ -4285,593 +4856,934 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
     return () => window.removeEventListener('keydown', handler);
   }, [view, monitorView, selectedRun?.repo_ref, repoRef]);
 
+  useEffect(() => {
+    getAppSettings()
+      .then((settings) => {
+        const template = findWorkspaceTemplate(settings.workspace?.template);
+        setWorkspaceTemplateId(template?.id ?? null);
+        if (template) {
+          setActiveWorkspaceTab(template.primary);
+        }
+        setCanvasLayouts(settings.canvas?.by_workflow ?? {});
+        if (settings.workspace) {
+          setWorkspaceLayout({
+            split_percent: settings.workspace.split_percent ?? null,
+            snap_percent: settings.workspace.snap_percent ?? 5,
+            arrangement: settings.workspace.arrangement ?? 'columns',
+          });
+        }
+        setWorkspaceByRepo(settings.workspace?.by_repo ?? {});
+        setWorkspaceAutoAssociate(settings.workspace?.auto_associate ?? true);
+        setWorkspaceSettingsLoaded(true);
+        setWorkspaceWelcomeOpen(!settings.workspace?.welcome_dismissed);
+      })
+      .catch(() => undefined);
+  }, []);
+
+  const workspaceRepoRef = (selectedRun?.repo_ref ?? repoRef ?? '').trim();
+  const associatedRepoRef = useRef<string | null>(null);
+
+  /** Remembers the current layout for the active repository so it is restored on return. */
+  function rememberRepoWorkspace(patch: Partial<RepoWorkspace>) {
+    if (!workspaceAutoAssociate || !workspaceRepoRef) return;
+    const next: RepoWorkspace = {
+      template: workspaceTemplateId ?? '',
+      split_percent: workspaceLayout.split_percent,
+      arrangement: workspaceLayout.arrangement,
+      ...patch,
+    };
+    setWorkspaceByRepo((prev) => ({ ...prev, [workspaceRepoRef]: next }));
+    void patchAppSettings({ workspace: { by_repo: { [workspaceRepoRef]: next } } }).catch(() => undefined);
+  }
+
+  useEffect(() => {
+    if (!workspaceSettingsLoaded || !workspaceRepoRef || associatedRepoRef.current === workspaceRepoRef) return;
+    associatedRepoRef.current = workspaceRepoRef;
+    const saved = workspaceByRepo[workspaceRepoRef];
+    if (!workspaceAutoAssociate || !saved || workspaceWelcomeOpen) return;
+    const template = findWorkspaceTemplate(saved.template);
+    setWorkspaceTemplateId(template?.id ?? null);
+    if (template) {
+      setActiveWorkspaceTab(template.primary);
+    }
+    setWorkspaceLayout((prev) => ({ ...prev, split_percent: saved.split_percent, arrangement: saved.arrangement }));
+    void patchAppSettings({
+      workspace: { template: template?.id ?? '', split_percent: saved.split_percent, arrangement: saved.arrangement },
+    }).catch(() => undefined);
+  }, [workspaceRepoRef, workspaceSettingsLoaded]);
+
+  function toggleWorkspaceAutoAssociate(enabled: boolean) {
+    setWorkspaceAutoAssociate(enabled);
+    void patchAppSettings({ workspace: { auto_associate: enabled } }).catch((err) => {
+      setError(err instanceof Error ? err.message : String(err));
+    });
+  }
+
+  function selectWorkspaceTemplate(template: WorkspaceTemplate | null, record = true) {
+    const previous = workspaceTemplateId;
+    if (record && !workspaceWelcomeOpen && previous !== (template?.id ?? null)) {
+      void recordHistoryState({
+        kind: 'layout',
+        label: `Layout change from ${findWorkspaceTemplate(previous)?.label ?? 'single pane'}`,
+        state: { template: previous },
+      }).catch(() => undefined);
+    }
+    setWorkspaceWelcomeOpen(false);
+    setWorkspaceTemplateId(template?.id ?? null);
+    if (template) {
+      setActiveWorkspaceTab(template.primary);
+    }
+    void patchAppSettings({ workspace: { template: template?.id ?? '', welcome_dismissed: true } }).catch((err) => {
+      setError(err instanceof Error ? err.message : String(err));
+    });
+    rememberRepoWorkspace({ template: template?.id ?? '' });
+  }
+
+  function updateWorkspaceLayout(patch: Partial<WorkspaceLayout>, record = true) {
+    if (record && (patch.arrangement !== undefined || patch.split_percent !== undefined)) {
+      void recordHistoryState({
+        kind: 'layout',
+        label: patch.arrangement !== undefined ? 'Arrange panes' : 'Resize panes',
+        state: { template: workspaceTemplateId, ...workspaceLayout },
+      }).catch(() => undefined);
+    }
+    setWorkspaceLayout((prev) => ({ ...prev, ...patch }));
+    void patchAppSettings({ workspace: patch }).catch((err) => {
+      setError(err instanceof Error ? err.message : String(err));
+    });
+    if (patch.arrangement !== undefined || patch.split_percent !== undefined) {
+      const { split_percent, arrangement } = patch;
+      rememberRepoWorkspace({
+        ...(split_percent !== undefined ? { split_percent } : {}),
+        ...(arrangement !== undefined ? { arrangement } : {}),
+      });
+    }
+  }
+
+  const workflowCanvasKey = workflowName.trim() || 'untitled';
+  function openInFileViewer(path: string) {
+    setFileOpenRequest({ path, nonce: Date.now() });
+    if (workspaceSecondaryPane !== 'files') setActiveWorkspaceTab('files');
+  }
+
+  /** Points the repo context export at `paths` as of `gitRef` and opens its settings for review. */
+  function exportChangeContext(paths: string[], gitRef: string) {
+    setStageRepoContextGitRef(gitRef);
+    setSelectedRepoDirs(new Set());
+    syncRepoSelectionState(paths);
+    setRepoContextConfigOpen(true);
+  }
+
+  const treeBulkActions: TreeBulkAction[] = [
+    { label: 'Add to context', onRun: (paths) => setPaths(paths, true) },
+    { label: 'Remove from context', onRun: (paths) => setPaths(paths, false) },
+    {
+      label: 'Exclude',
+      onRun: (paths) => {
+        const pattern = `^(${paths.map((path) => path.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')).join('|')})$`;
+        setStageRepoContextExcludeRegexText((prev) => {
+          const lines = prev.split('\n').map((line) => line.trim()).filter(Boolean);
+          return lines.includes(pattern) ? prev : [...lines, pattern].join('\n');
+        });
+      },
+    },
+    {
+      label: 'Stage',
+      onRun: async (paths) => {
+        try {
+          for (const path of paths) {
+            await stageReviewDiff({ repo_ref: workspaceRepoRef, scope: 'unstaged', path });
+          }
+        } catch (err) {
+          setError(err instanceof Error ? err.message : String(err));
+        }
+      },
+    },
+  ];
+  const workspaceTemplate = findWorkspaceTemplate(workspaceTemplateId);
+  const workspaceSecondaryPane = view !== 'builder' && monitorView === 'workflow_detail' && workspaceRepoRef
+    ? secondaryPaneForViewport(workspaceTemplate, viewportWidth)
+    : null;
+
+  function renderWorkspaceSecondaryPane() {
+    if (!workspaceSecondaryPane || workspaceSecondaryPane === activeWorkspaceTab) return null;
+    const fallback = <Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading…</Text></Group></Card>;
+    switch (workspaceSecondaryPane) {
+      case 'diff':
+        return (
+          <Suspense fallback={fallback}>
+            <ReviewDiffViewerPanel repoRef={workspaceRepoRef} state={reviewSourceControlState} onPersistState={persistReviewSourceControlState} forceViewerOpen onAskAi={(snippet) => void handleAskAi(snippet)} aiModel={aiActionModel} />
+          </Suspense>
+        );
+      case 'files':
+        return (
+          <Suspense fallback={fallback}>
+            <RepoMonacoFileEditorPanel repoRef={workspaceRepoRef} viewerId="pane" treeBulkActions={treeBulkActions} openRequest={fileOpenRequest} onAskAi={(snippet) => void handleAskAi(snippet)} aiModel={aiActionModel} onGenerateTests={(request) => void handleGenerateTests(request)} />
+          </Suspense>
+        );
+      case 'commits':
+        return (
+          <Suspense fallback={fallback}>
+            <CommitSummaryPanel repoRef={workspaceRepoRef} onAskAi={(snippet) => void handleAskAi(snippet)} aiModel={aiActionModel} onExportContext={exportChangeContext} />
+          </Suspense>
+        );
+      case 'analysis':
+        return (
+          <Suspense fallback={fallback}>
+            <AnalysisPanel
+              repoRef={workspaceRepoRef}
+              onOpenFile={openInFileViewer}
+              aiModel={aiActionModel}
+              onExportContext={exportChangeContext}
+              onAddExcludeRegex={(pattern) => {
+                setStageRepoContextExcludeRegexText((prev) => {
+                  const lines = prev.split('\n').map((line) => line.trim()).filter(Boolean);
+                  return lines.includes(pattern) ? prev : [...lines, pattern].join('\n');
+                });
+              }}
+            />
+          </Suspense>
+        );
+      default:
+        return null;
+    }
+  }
+
+  const workspaceSecondaryNode = renderWorkspaceSecondaryPane();
 
   return (
     <AppShell padding="md">
       <AppShell.Main>
         <Stack>
-          {error ? <Alert color="red">{error}</Alert> : null}
+          {error ? <ErrorPanel message={error} /> : null}
+          {doctorFailures.length && !doctorWarningDismissed ? (
+            <Alert color="red" withCloseButton onClose={() => setDoctorWarningDismissed(true)} title="Environment problems found">
+              <Group justify="space-between" wrap="nowrap">
+                <Text size="sm">{doctorFailures.map((check) => check.label).join(', ')} failed. Runs that depend on them will stop partway.</Text>
+                <Button size="xs" variant="light" color="red" onClick={() => setDoctorOpen(true)}>Open doctor</Button>
+              </Group>
+            </Alert>
+          ) : null}
+
+          <WorkspaceWelcomeModal opened={workspaceWelcomeOpen} onSelect={(template) => selectWorkspaceTemplate(template)} />
+          <DebugMetricsOverlay opened={debugOverlayOpen} onClose={() => setDebugOverlayOpen(false)} />
+          <IndexLockOverlay />
 
           {view !== 'builder' && monitorView === 'workflow_detail' ? (
-            <Tabs value={activeWorkspaceTab} onChange={(value) => setActiveWorkspaceTab((value as WorkspaceTabKey) ?? 'workflows')}>
-              <Tabs.List>
-                <Tabs.Tab value="workflows">Workflow (Alt+1)</Tabs.Tab>
-                <Tabs.Tab value="diff" disabled={!((selectedRun?.repo_ref ?? repoRef ?? '').trim())}>Changes (Alt+2)</Tabs.Tab>
-                <Tabs.Tab value="commits" disabled={!((selectedRun?.repo_ref ?? repoRef ?? '').trim())}>Commits (Alt+3)</Tabs.Tab>
-                <Tabs.Tab value="files" disabled={!((selectedRun?.repo_ref ?? repoRef ?? '').trim())}>Repository (Alt+4)</Tabs.Tab>
-                <Tabs.Tab value="capabilities">Capabilities (Alt+5)</Tabs.Tab>
-              </Tabs.List>
-            </Tabs>
+            <Group justify="space-between" align="flex-end" wrap="nowrap" {...focusRegionProps('tabs')}>
+              <Tabs style={{ flex: 1 }} value={activeWorkspaceTab} onChange={(value) => setActiveWorkspaceTab((value as WorkspaceTabKey) ?? 'workflows')}>
+                <Tabs.List>
+                  <Tabs.Tab value="workflows">Workflow (Alt+1)</Tabs.Tab>
+                  <Tabs.Tab value="diff" disabled={!((selectedRun?.repo_ref ?? repoRef ?? '').trim())}>Changes (Alt+2)</Tabs.Tab>
+                  <Tabs.Tab value="commits" disabled={!((selectedRun?.repo_ref ?? repoRef ?? '').trim())}>Commits (Alt+3)</Tabs.Tab>
+                  <Tabs.Tab value="files" disabled={!((selectedRun?.repo_ref ?? repoRef ?? '').trim())}>Repository (Alt+4)</Tabs.Tab>
+                  <Tabs.Tab value="capabilities">Capabilities (Alt+5)</Tabs.Tab>
+                  <Tabs.Tab value="analysis" disabled={!((selectedRun?.repo_ref ?? repoRef ?? '').trim())}>Analysis (Alt+6)</Tabs.Tab>
+                </Tabs.List>
+              </Tabs>
+              <Group gap="xs" wrap="nowrap">
+                <HistoryMenu
+                  repoRef={workspaceRepoRef}
+                  onError={setError}
+                  onUndone={(result) => {
+                    if (result.entry.kind === 'layout' && result.state) {
+                      selectWorkspaceTemplate(findWorkspaceTemplate(result.state.template as string | null), false);
+                      if (result.state.arrangement !== undefined) {
+                        const { split_percent, snap_percent, arrangement } = result.state as WorkspaceLayout;
+                        updateWorkspaceLayout({ split_percent, snap_percent, arrangement }, false);
+                      }
+                    }
+                  }}
+                />
+                <WorkspaceTemplateMenu
+                  activeTemplateId={workspaceTemplateId}
+                  onSelect={(template) => selectWorkspaceTemplate(template)}
+                  arrangement={workspaceLayout.arrangement}
+                  snapPercent={workspaceLayout.snap_percent}
+                  hasCustomSplit={workspaceLayout.split_percent !== null}
+                  onArrange={(arrangement) => updateWorkspaceLayout({ arrangement })}
+                  onSnapChange={(snap_percent) => updateWorkspaceLayout({ snap_percent })}
+                  onResetSplit={() => updateWorkspaceLayout({ split_percent: null })}
+                  autoAssociate={workspaceAutoAssociate}
+                  onAutoAssociateChange={toggleWorkspaceAutoAssociate}
+                />
+              </Group>
+            </Group>
           ) : null}
 
-          {view === 'builder' ? (
-            <Modal
-              opened={view === 'builder'}
-              onClose={() => setView('monitor')}
-              title="Workflow Builder"
-              size="calc(100vw - 32px)"
-              centered
-              fullScreen
-              padding="md"
-              zIndex={200}
-              styles={{
-                body: { paddingTop: 0, height: 'calc(100vh - 72px)' },
-                content: { background: 'var(--mantine-color-body)' }
-              }}
-            >
-              <Stack h="100%" gap="sm">
-                <Card withBorder p="sm">
-                  <Stack gap="sm">
-                    <Group justify="space-between" align="flex-start" wrap="wrap">
-                      <Stack gap={2}>
-                        <Title order={3}>Create workflow</Title>
-                        <Text c="dimmed" size="sm">Build the workflow on the canvas, then load or save templates from this panel.</Text>
-                      </Stack>
-                      <Group>
-                        <Button variant="default" onClick={() => setLoadTemplateOpen(true)} disabled={templates.length === 0}>Load template</Button>
-                        <Button variant="light" onClick={() => setTemplateModalOpen(true)}>Save template</Button>
-                        <Button variant="default" onClick={() => setView('monitor')}>Close</Button>
+          <WorkspaceSplit
+            primaryPane={view !== 'builder' && monitorView === 'workflow_detail' ? activeWorkspaceTab : null}
+            secondaryPane={workspaceSecondaryPane}
+            onPopOut={(pane) => popOutPane(pane, workspaceRepoRef)}
+            activity={workspacePaneActivity}
+            secondary={workspaceSecondaryNode}
+            primarySpan={workspaceTemplate?.primarySpan ?? 12}
+            splitPercent={workspaceLayout.split_percent}
+            snapPercent={workspaceLayout.snap_percent}
+            arrangement={workspaceLayout.arrangement}
+            onSplitChange={(split_percent) => updateWorkspaceLayout({ split_percent })}
+          >
+            {view === 'builder' ? (
+              <Modal
+                opened={view === 'builder'}
+                onClose={() => setView('monitor')}
+                title="Workflow Builder"
+                size="calc(100vw - 32px)"
+                centered
+                fullScreen
+                padding="md"
+                zIndex={200}
+                styles={{
+                  body: { paddingTop: 0, height: 'calc(100vh - 72px)' },
+                  content: { background: 'var(--mantine-color-body)' }
+                }}
+              >
+                <Stack h="100%" gap="sm">
+                  <Card withBorder p="sm">
+                    <Stack gap="sm">
+                      <Group justify="space-between" align="flex-start" wrap="wrap">
+                        <Stack gap={2}>
+                          <Title order={3}>Create workflow</Title>
+                          <Text c="dimmed" size="sm">Build the workflow on the canvas, then load or save templates from this panel.</Text>
+                        </Stack>
+                        <Group>
+                          <Button variant="default" onClick={() => setLoadTemplateOpen(true)} disabled={templates.length === 0}>Load template</Button>
+                          <Button variant="light" onClick={() => setTemplateModalOpen(true)}>Save template</Button>
+                          <Button variant="default" onClick={() => setView('monitor')}>Close</Button>
+                        </Group>
                       </Group>
-                    </Group>
 
-                    <Grid gutter="sm" align="end">
-                      <Grid.Col span={{ base: 12, md: 3 }}>
-                        <TextInput label="Workflow name" value={workflowName} onChange={(e) => setWorkflowName(e.currentTarget.value)} />
-                      </Grid.Col>
-                      <Grid.Col span={{ base: 12, md: 3 }}>
-                        <TextInput label="Repo path" placeholder="C:/repo or /home/user/repo" value={repoRef} onChange={(e) => setRepoRef(e.currentTarget.value)} />
-                      </Grid.Col>
-                      <Grid.Col span={{ base: 12, md: 4 }}>
-                        <TextInput label="Description" value={workflowDescription} onChange={(e) => setWorkflowDescription(e.currentTarget.value)} />
-                      </Grid.Col>
-                      <Grid.Col span={{ base: 12, md: 2 }}>
-                        <Button fullWidth onClick={() => void handleCreateWorkflow()} loading={busy}>
-                          Create workflow
-                        </Button>
-                      </Grid.Col>
-                    </Grid>
-                  </Stack>
-                </Card>
+                      <Grid gutter="sm" align="end">
+                        <Grid.Col span={{ base: 12, md: 3 }}>
+                          <TextInput label="Workflow name" value={workflowName} onChange={(e) => setWorkflowName(e.currentTarget.value)} />
+                        </Grid.Col>
+                        <Grid.Col span={{ base: 12, md: 3 }}>
+                          <TextInput label="Repo path" placeholder="C:/repo or /home/user/repo" value={repoRef} onChange={(e) => setRepoRef(e.currentTarget.value)} />
+                        </Grid.Col>
+                        <Grid.Col span={{ base: 12, md: 4 }}>
+                          <TextInput label="Description" value={workflowDescription} onChange={(e) => setWorkflowDescription(e.currentTarget.value)} />
+                        </Grid.Col>
+                        <Grid.Col span={{ base: 12, md: 2 }}>
+                          <Button fullWidth onClick={() => void handleCreateWorkflow()} loading={busy}>
+                            Create workflow
+                          </Button>
+                        </Grid.Col>
+                      </Grid>
+                    </Stack>
+                  </Card>
 
-                <Card withBorder p={0} style={{ overflow: 'hidden', flex: 1, minHeight: 0 }}>
-                  <WorkflowBuilderEditor
-                    key={`builder-load-${builderLoadRevision}`}
-                    initialDefinition={loadedTemplateDefinition}
-                    builderGlobals={builderGlobals}
-                    onCompiledDefinitionChange={(next) => {
-                      const withGlobals = applyBuilderGlobalsToDefinition(next, builderGlobals);
-                      if (!withGlobals) {
-                        return;
-                      }
-                      setCompiledBuilderDefinition(withGlobals);
-                      setJsonDraft(JSON.stringify(withGlobals, null, 2));
-                    }}
-                    onError={setError}
-                    onOpenCapabilityConfig={(capabilityKey) => {
-                      openBuilderCapabilityConfig(capabilityKey, {
-                        openRepo: () => {
-                          setError(null);
-                          syncBuilderRepoResource();
-                          loadBuilderRepoContextConfig();
-                          setRepoContextConfigOpen(true);
-                        },
-                        openInference: () => {
-                          setError(null);
-                          syncBuilderRepoResource();
-                          openGlobalInferenceConfig();
-                        },
-                        openSchema: () => {
-                          setError(null);
-                          syncBuilderRepoResource();
-                          loadBuilderChangesetSchemaConfig();
-                          setChangesetSchemaConfigOpen(true);
-                        },
-                        openApplyChangeset: () => {
-                          setError(null);
-                          syncBuilderRepoResource();
-                          loadBuilderApplyChangesetConfig();
-                          setGlobalApplyChangesetOpen(true);
-                        },
-                        openGitPatchPayload: () => {
-                          setError(null);
-                          syncBuilderRepoResource();
-                          loadBuilderGitPatchPayloadConfig();
-                          setGitPatchPayloadOpen(true);
-                        },
-                      });
+                  <Card withBorder p={0} style={{ overflow: 'hidden', flex: 1, minHeight: 0 }}>
+                    <WorkflowBuilderEditor
+                      key={`builder-load-${builderLoadRevision}`}
+                      initialDefinition={loadedTemplateDefinition}
+                      builderGlobals={builderGlobals}
+                      onCompiledDefinitionChange={(next) => {
+                        const withGlobals = applyBuilderGlobalsToDefinition(next, builderGlobals);
+                        if (!withGlobals) {
+                          return;
+                        }
+                        setCompiledBuilderDefinition(withGlobals);
+                        setJsonDraft(JSON.stringify(withGlobals, null, 2));
+                      }}
+                      onError={setError}
+                      canvasLayout={canvasLayouts[workflowCanvasKey] ?? null}
+                      onCanvasLayoutChange={(layout) => {
+                        setCanvasLayouts((prev) => ({ ...prev, [workflowCanvasKey]: layout }));
+                        void patchAppSettings({ canvas: { by_workflow: { [workflowCanvasKey]: layout } } }).catch(() => undefined);
+                      }}
+                      onOpenCapabilityConfig={(capabilityKey) => {
+                        openBuilderCapabilityConfig(capabilityKey, {
+                          openRepo: () => {
+                            setError(null);
+                            syncBuilderRepoResource();
+                            loadBuilderRepoContextConfig();
+                            setRepoContextConfigOpen(true);
+                          },
+                          openInference: () => {
+                            setError(null);
+                            syncBuilderRepoResource();
+                            openGlobalInferenceConfig();
+                          },
+                          openSchema: () => {
+                            setError(null);
+                            syncBuilderRepoResource();
+                            loadBuilderChangesetSchemaConfig();
+                            setChangesetSchemaConfigOpen(true);
+                          },
+                          openApplyChangeset: () => {
+                            setError(null);
+                            syncBuilderRepoResource();
+                            loadBuilderApplyChangesetConfig();
+                            setGlobalApplyChangesetOpen(true);
+                          },
+                          openGitPatchPayload: () => {
+                            setError(null);
+                            syncBuilderRepoResource();
+                            loadBuilderGitPatchPayloadConfig();
+                            setGitPatchPayloadOpen(true);
+                          },
+                        });
+                      }}
+                    />
+                  </Card>
+                </Stack>
+              </Modal>
+            ) : activeWorkspaceTab === 'diff' ? (
+              <Suspense fallback={<Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading changes view…</Text></Group></Card>}>
+                <ReviewDiffViewerPanel
+                  repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim()}
+                  state={reviewSourceControlState}
+                  onPersistState={persistReviewSourceControlState}
+                  forceViewerOpen
+                  onAskAi={(snippet) => void handleAskAi(snippet)}
+                  aiModel={aiActionModel}
+                />
+              </Suspense>
+            ) : activeWorkspaceTab === 'commits' ? (
+              <Suspense fallback={<Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading commit summary…</Text></Group></Card>}>
+                <CommitSummaryPanel repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim()} onAskAi={(snippet) => void handleAskAi(snippet)} aiModel={aiActionModel} onExportContext={exportChangeContext} />
+              </Suspense>
+            ) : activeWorkspaceTab === 'files' ? (
+              <Suspense fallback={<Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading repository view…</Text></Group></Card>}>
+                <RepoMonacoFileEditorPanel repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim()} treeBulkActions={treeBulkActions} openRequest={fileOpenRequest} onAskAi={(snippet) => void handleAskAi(snippet)} aiModel={aiActionModel} onGenerateTests={(request) => void handleGenerateTests(request)} />
+              </Suspense>
+            ) : activeWorkspaceTab === 'analysis' ? (
+              <Suspense fallback={<Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading analysis…</Text></Group></Card>}>
+                <AnalysisPanel
+                  repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim()}
+                  onOpenFile={openInFileViewer}
+                  aiModel={aiActionModel}
+                  onExportContext={exportChangeContext}
+              onAddExcludeRegex={(pattern) => {
+                    setStageRepoContextExcludeRegexText((prev) => {
+                      const lines = prev.split('\n').map((line) => line.trim()).filter(Boolean);
+                      return lines.includes(pattern) ? prev : [...lines, pattern].join('\n');
+                    });
+                  }}
+                />
+              </Suspense>
+            ) : activeWorkspaceTab === 'capabilities' ? (
+              <Card withBorder>
+                <GlobalCapabilitiesPanel
+                  repoContextArmed={!!sharedInferenceState?.repo_context_armed}
+                  changesetSchemaArmed={!!sharedInferenceState?.changeset_schema_armed}
+                  onOpenInference={() => {
+                    openGlobalInferenceConfig();
+                  }}
+                  onOpenRepoFragment={() => {
+                    setRepoContextConfigOpen(true);
+                  }}
+                  onOpenChangesetSchema={() => {
+                    setChangesetSchemaConfigOpen(true);
+                  }}
+                  onOpenApplyChangeset={() => {
+                    setGlobalApplyChangesetOpen(true);
+                  }}
+                  onOpenGitPatchPayload={() => {
+                    setGitPatchPayloadOpen(true);
+                  }}
+                  onOpenNetworkSettings={() => {
+                    setNetworkSettingsOpen(true);
+                  }}
+                  onOpenSettingsBundle={() => {
+                    setSettingsBundleOpen(true);
+                  }}
+                  onOpenUpdates={() => {
+                    setUpdatesOpen(true);
+                  }}
+                  onOpenAccessibility={() => {
+                    setAccessibilityOpen(true);
+                  }}
+                  onOpenPlugins={() => {
+                    setPluginPaletteOpen(true);
+                  }}
+                  onOpenAutomation={() => {
+                    setAutomationOpen(true);
+                  }}
+                  onOpenMcpServer={() => {
+                    setMcpServerOpen(true);
+                  }}
+                  onOpenDoctor={() => {
+                    setDoctorOpen(true);
+                  }}
+                  onOpenProcesses={() => {
+                    setProcessesOpen(true);
+                  }}
+                  onOpenCapabilityTraces={() => {
+                    setCapabilityTracesOpen(true);
+                  }}
+                  onDropRepoFragmentPaths={(paths) => {
+                    setPaths(paths, true);
+                    setRepoContextConfigOpen(true);
+                  }}
+                />
+              </Card>
+            ) : monitorView === 'workflow_list' ? (
+              <Stack>
+                {runs.length === 0 ? (
+                  <WelcomePanel
+                    onUseRepo={(nextRepoRef) => {
+                      setRepoRef(nextRepoRef);
+                      void openBuilder();
                     }}
                   />
-                </Card>
-              </Stack>
-            </Modal>
-          ) : activeWorkspaceTab === 'diff' ? (
-            <Suspense fallback={<Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading changes view…</Text></Group></Card>}>
-              <ReviewDiffViewerPanel
-                repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim()}
-                state={reviewSourceControlState}
-                onPersistState={persistReviewSourceControlState}
-                forceViewerOpen
-              />
-            </Suspense>
-          ) : activeWorkspaceTab === 'commits' ? (
-            <Suspense fallback={<Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading commit summary…</Text></Group></Card>}>
-              <CommitSummaryPanel repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim()} />
-            </Suspense>
-          ) : activeWorkspaceTab === 'files' ? (
-            <Suspense fallback={<Card withBorder p="lg"><Group gap="xs"><Loader size="sm" /><Text size="sm" c="dimmed">Loading repository view…</Text></Group></Card>}>
-              <RepoMonacoFileEditorPanel repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim()} />
-            </Suspense>
-          ) : activeWorkspaceTab === 'capabilities' ? (
-            <Card withBorder>
-              <GlobalCapabilitiesPanel
-                repoContextArmed={!!sharedInferenceState?.repo_context_armed}
-                changesetSchemaArmed={!!sharedInferenceState?.changeset_schema_armed}
-                onOpenInference={() => {
-                  openGlobalInferenceConfig();
-                }}
-                onOpenRepoFragment={() => {
-                  setRepoContextConfigOpen(true);
-                }}
-                onOpenChangesetSchema={() => {
-                  setChangesetSchemaConfigOpen(true);
-                }}
-                onOpenApplyChangeset={() => {
-                  setGlobalApplyChangesetOpen(true);
-                }}
-                onOpenGitPatchPayload={() => {
-                  setGitPatchPayloadOpen(true);
-                }}
-              />
-            </Card>
-          ) : monitorView === 'workflow_list' ? (
-            <Stack>
-              <Card withBorder>
-                <Stack>
-                  <Group justify="space-between" align="center" wrap="wrap">
-                    <Title order={4}>Workflow list</Title>
-                    <Group>
-                      <Button size="xs" onClick={() => void openBuilder()} loading={busy}>
-                        New workflow
-                      </Button>
-                      <Button
-                        size="xs"
-                        variant="default"
-                        leftSection={<IconRefresh size={16} />}
-                        onClick={() => void refreshRunsAndTemplates()}
-                      >
-                        Refresh
-                      </Button>
+                ) : null}
+                <ReviewQueuePanel
+                  refreshKey={runs}
+                  onOpenRun={(runId) => void openWorkflow(runId)}
+                  onReviewed={() => refreshRunsAndTemplates()}
+                />
+                <Card withBorder>
+                  <Stack>
+                    <Group justify="space-between" align="center" wrap="wrap">
+                      <Title order={4}>Workflow list</Title>
+                      <Group>
+                        <Button size="xs" onClick={() => void openBuilder()} loading={busy}>
+                          New workflow
+                        </Button>
+                        <Button
+                          size="xs"
+                          variant="default"
+                          leftSection={<IconRefresh size={16} />}
+                          onClick={() => void refreshRunsAndTemplates()}
+                        >
+                          Refresh
+                        </Button>
+                      </Group>
                     </Group>
-                  </Group>
-                  <Table striped highlightOnHover>
-                    <Table.Thead>
-                      <Table.Tr>
-                        <Table.Th>Workflow</Table.Th>
-                        <Table.Th>Status</Table.Th>
-                        <Table.Th>Current step</Table.Th>
-                        <Table.Th>Repo</Table.Th>
-                        <Table.Th>Updated</Table.Th>
-                        <Table.Th>Actions</Table.Th>
-                      </Table.Tr>
-                    </Table.Thead>
-                    <Table.Tbody>
-                      {runs.map((run) => (
-                        <Table.Tr key={run.id} onClick={() => void openWorkflow(run.id)} style={{ cursor: 'pointer' }}>
-                          <Table.Td>{run.title}</Table.Td>
-                          <Table.Td><Badge color={statusColor(run.status)}>{run.status}</Badge></Table.Td>
-                          <Table.Td><Code>{run.current_step_id ?? '—'}</Code></Table.Td>
-                          <Table.Td><Code>{run.repo_ref}</Code></Table.Td>
-                          <Table.Td>{formatTimestamp(run.updated_at)}</Table.Td>
-                          <Table.Td>
-                            <Group gap="xs">
-                              <Button size="xs" variant="light" onClick={(e) => { e.stopPropagation(); void openWorkflow(run.id); }}>Open</Button>
-                              <ActionIcon color="red" variant="subtle" onClick={(e) => { e.stopPropagation(); void handleDeleteRun(run.id); }}><IconTrash size={16} /></ActionIcon>
-                            </Group>
-                          </Table.Td>
+                    <Table striped highlightOnHover>
+                      <Table.Thead>
+                        <Table.Tr>
+                          <Table.Th>Workflow</Table.Th>
+                          <Table.Th>Status</Table.Th>
+                          <Table.Th>Current step</Table.Th>
+                          <Table.Th>Repo</Table.Th>
+                          <Table.Th>Updated</Table.Th>
+                          <Table.Th>Actions</Table.Th>
                         </Table.Tr>
-                      ))}
-                    </Table.Tbody>
-                  </Table>
-                </Stack>
-              </Card>
+                      </Table.Thead>
+                      <Table.Tbody>
+                        {runs.map((run) => (
+                          <Table.Tr key={run.id} onClick={() => void openWorkflow(run.id)} style={{ cursor: 'pointer' }}>
+                            <Table.Td>{run.title}</Table.Td>
+                            <Table.Td><Badge color={statusColor(run.status)}>{run.status}</Badge></Table.Td>
+                            <Table.Td><Code>{run.current_step_id ?? '—'}</Code></Table.Td>
+                            <Table.Td><Code>{run.repo_ref}</Code></Table.Td>
+                            <Table.Td>{formatTimestamp(run.updated_at)}</Table.Td>
+                            <Table.Td>
+                              <Group gap="xs">
+                                <Button size="xs" variant="light" onClick={(e) => { e.stopPropagation(); void openWorkflow(run.id); }}>Open</Button>
+                                <ActionIcon color="red" variant="subtle" onClick={(e) => { e.stopPropagation(); void handleDeleteRun(run.id); }}><IconTrash size={16} /></ActionIcon>
+                              </Group>
+                            </Table.Td>
+                          </Table.Tr>
+                        ))}
+                      </Table.Tbody>
+                    </Table>
+                  </Stack>
+                </Card>
 
-              <Card withBorder>
-                <Stack>
-                  <Group justify="space-between">
-                    <Title order={4}>Global summary</Title>
-                    <Button variant="light" size="xs" onClick={() => void refreshRunsAndTemplates()}>Refresh summary</Button>
-                  </Group>
-                  {Object.keys(allWorkflowEvents).length === 0 ? (
-                    <Text c="dimmed">No active workflow summaries yet.</Text>
-                  ) : (
-                    <Stack>
-                      {runs.filter((run) => allWorkflowEvents[run.id]?.length).map((run) => {
-                        const latestEvent = allWorkflowEvents[run.id][allWorkflowEvents[run.id].length - 1] ?? null;
-                        return (
-                          <Card key={run.id} withBorder>
-                            <Group justify="space-between" align="flex-start">
-                              <Stack gap={4}>
-                                <Text fw={600}>{run.title}</Text>
-                                <Text size="sm" c="dimmed">{run.repo_ref}</Text>
-                                <Group gap="xs">
-                                  <Badge color={statusColor(run.status)}>{run.status}</Badge>
-                                  <Code>{run.current_step_id ?? '-'}</Code>
-                                </Group>
-                              </Stack>
-                              <Stack gap={4} align="flex-end">
-                                <Text size="xs" c="dimmed">{latestEvent ? formatTimestamp(latestEvent.created_at) : '-'}</Text>
-                                <Text size="sm">{latestEvent ? summarizeEvent(latestEvent) : 'No events'}</Text>
-                              </Stack>
-                            </Group>
-                          </Card>
-                        );
-                      })}
-                    </Stack>
-                  )}
-                </Stack>
-              </Card>
-            </Stack>
-          ) : (
-            <Grid align="start">
-              <Grid.Col span={{ base: 12, xl: 7 }}>
-                <Stack>
-                  <Card withBorder>
-                    {selectedRun ? (
+                <Card withBorder>
+                  <Stack>
+                    <Group justify="space-between">
+                      <Title order={4}>Global summary</Title>
+                      <Button variant="light" size="xs" onClick={() => void refreshRunsAndTemplates()}>Refresh summary</Button>
+                    </Group>
+                    {Object.keys(allWorkflowEvents).length === 0 ? (
+                      <Group justify="space-between">
+                        <Text c="dimmed">No active workflow summaries yet.</Text>
+                        <Button size="xs" variant="light" onClick={() => void openBuilder()} loading={busy}>New workflow</Button>
+                      </Group>
+                    ) : (
                       <Stack>
-                        <Group justify="space-between">
-                          <Group>
-                            <Button variant="light" onClick={backToWorkflowList}>Back to workflows</Button>
-                            <div>
-                              <Title order={4}>{selectedRun.title}</Title>
-                              <Text c="dimmed">{selectedRun.repo_ref}</Text>
-                            </div>
-                          </Group>
-                          <Badge color={statusColor(selectedRun.status)}>{selectedRun.status}</Badge>
-                        </Group>
-                        <Stack gap="md">
-                          <Group justify="space-between" align="flex-start" wrap="wrap">
+                        {runs.filter((run) => allWorkflowEvents[run.id]?.length).map((run) => {
+                          const latestEvent = allWorkflowEvents[run.id][allWorkflowEvents[run.id].length - 1] ?? null;
+                          return (
+                            <Card key={run.id} withBorder>
+                              <Group justify="space-between" align="flex-start">
+                                <Stack gap={4}>
+                                  <Text fw={600}>{run.title}</Text>
+                                  <Text size="sm" c="dimmed">{run.repo_ref}</Text>
+                                  <Group gap="xs">
+                                    <Badge color={statusColor(run.status)}>{run.status}</Badge>
+                                    <Code>{run.current_step_id ?? '-'}</Code>
+                                  </Group>
+                                </Stack>
+                                <Stack gap={4} align="flex-end">
+                                  <Text size="xs" c="dimmed">{latestEvent ? formatTimestamp(latestEvent.created_at) : '-'}</Text>
+                                  <Text size="sm">{latestEvent ? summarizeEvent(latestEvent) : 'No events'}</Text>
+                                </Stack>
+                              </Group>
+                            </Card>
+                          );
+                        })}
+                      </Stack>
+                    )}
+                  </Stack>
+                </Card>
+              </Stack>
+            ) : (
+              <Grid align="start">
+                <Grid.Col span={{ base: 12, xl: 7 }}>
+                  <Stack>
+                    <Card withBorder>
+                      {selectedRun ? (
+                        <Stack>
+                          <Group justify="space-between">
                             <Group>
-                              <Button leftSection={<IconPlayerPlay size={16} />} onClick={() => void handleStartRun()} loading={busy} disabled={!selectedRunId || !canRunCurrentStageAutomatically || isBackendRunLocked}>Run autonomously</Button>
-                              <Button variant="default" leftSection={<IconPlayerPause size={16} />} onClick={() => void handlePauseRun()} loading={busy && canRequestRunPause} disabled={!canRequestRunPause}>Pause after stage</Button>
-                              <Button variant="default" leftSection={<IconRefresh size={16} />} onClick={() => selectedRunId && void refreshRunDetails(selectedRunId)}>Refresh run</Button>
-                              <Button variant="default" onClick={() => void handleForceWaitRun()} disabled={!selectedRunId || selectedRun?.status !== 'running'}>Force unlock</Button>
+                              <Button variant="light" onClick={backToWorkflowList}>Back to workflows</Button>
+                              <div>
+                                <Title order={4}>{selectedRun.title}</Title>
+                                <Text c="dimmed">{selectedRun.repo_ref}</Text>
+                              </div>
                             </Group>
-                            <Stack gap={2} align="flex-end">
-                              <Text size="xs" c="dimmed">Created: {formatTimestamp(selectedRun.created_at)}</Text>
-                              <Text size="xs" c="dimmed">Updated: {formatTimestamp(selectedRun.updated_at)}</Text>
-                            </Stack>
+                            <Badge color={statusColor(selectedRun.status)}>{selectedRun.status}</Badge>
                           </Group>
-                          <Card withBorder>
-                            <Stack gap="md">
-                              <Group justify="space-between" align="center">
-                                <Title order={6}>Workflow controls</Title>
-                              </Group>
+                          <Stack gap="md">
+                            <Group justify="space-between" align="flex-start" wrap="wrap">
                               <Group>
-                                <Button variant="default" onClick={() => void handleManualPatchStageState()} disabled={!isInteractiveMode || !selectedRunStepId || isBackendRunLocked}>Save stage inputs</Button>
-                                <Button onClick={() => void handleManualRunWithPatchedState()} disabled={!isInteractiveMode || !selectedRunStepId || isBackendRunLocked} loading={manualCapabilityBusy}>Run stage</Button>
-                                <Button variant="light" onClick={() => setRunContextOpen(true)} disabled={!selectedRun}>View run context</Button>
+                                <Button leftSection={<IconPlayerPlay size={16} />} onClick={() => void handleStartRun()} loading={busy} disabled={!selectedRunId || !canRunCurrentStageAutomatically || isBackendRunLocked}>Run autonomously</Button>
+                                <Button variant="default" leftSection={<IconPlayerPause size={16} />} onClick={() => void handlePauseRun()} loading={busy && canRequestRunPause} disabled={!canRequestRunPause}>Pause after stage</Button>
+                                <Button variant="default" leftSection={<IconRefresh size={16} />} onClick={() => selectedRunId && void refreshRunDetails(selectedRunId)}>Refresh run</Button>
+                                <Button variant="default" onClick={() => void handleForceWaitRun()} disabled={!selectedRunId || selectedRun?.status !== 'running'}>Force unlock</Button>
+                                <Button variant="subtle" onClick={() => setAutoRunGuardsOpen(true)} disabled={!selectedRunId}>Stop conditions</Button>
                               </Group>
+                              <Stack gap={2} align="flex-end">
+                                <Text size="xs" c="dimmed">Created: {formatTimestamp(selectedRun.created_at)}</Text>
+                                <Text size="xs" c="dimmed">Updated: {formatTimestamp(selectedRun.updated_at)}</Text>
+                              </Stack>
+                            </Group>
+                            {selectedRun.status === 'paused' && lastGuardEvent ? (
+                              <Alert color="yellow" title={`Stop condition reached: ${String(lastGuardEvent.payload.guard ?? 'guard')}`}>
+                                {lastGuardEvent.message}
+                              </Alert>
+                            ) : null}
+                            <Card withBorder>
+                              <Stack gap="md">
+                                <Group justify="space-between" align="center">
+                                  <Title order={6}>Workflow controls</Title>
+                                </Group>
+                                <Group>
+                                  <Button variant="default" onClick={() => void handleManualPatchStageState()} disabled={!isInteractiveMode || !selectedRunStepId || isBackendRunLocked}>Save stage inputs</Button>
+                                  <Button onClick={() => void handleManualRunWithPatchedState()} disabled={!isInteractiveMode || !selectedRunStepId || isBackendRunLocked} loading={manualCapabilityBusy}>Run stage</Button>
+                                  <Button variant="light" onClick={() => setRunContextOpen(true)} disabled={!selectedRun}>View run context</Button>
+                                </Group>
+                              </Stack>
+                            </Card>
+                          </Stack>
+                          <Card withBorder>
+                            <Stack gap="md">
+                              <Text fw={600}>Workflow progress</Text>
+                              {selectedRunDefinition ? (
+                                <Group gap="sm" wrap="wrap" align="stretch">
+                                    {selectedRunDefinition.steps.map((step, index) => {
+                                      const isCurrent = selectedRun?.current_step_id === step.id;
+                                      const currentIndex = selectedRunDefinition.steps.findIndex((item) => item.id === selectedRun?.current_step_id);
+                                      const isCompleted = currentIndex >= 0 && index < currentIndex;
+                                      const isUnknownCurrentStep = Boolean(selectedRun?.current_step_id) && currentIndex < 0;
+                                      const color = isCurrent ? 'blue' : isCompleted ? 'green' : 'gray';
+                                      return (
+                                        <Group key={step.id} gap="sm" wrap="nowrap" align="center">
+                                          <Box
+                                            p="md"
+                                            onClick={() => handleStageCardClick(step.id)}
+                                            style={{
+                                              minWidth: 180,
+                                              borderRadius: 12,
+                                              border: `1px solid var(--mantine-color-${color}-6)`,
+                                              background: isCurrent
+                                                ? 'rgba(34, 139, 230, 0.14)'
+                                                : isCompleted
+                                                  ? 'rgba(64, 192, 87, 0.12)'
+                                                  : 'rgba(255,255,255,0.02)',
+                                              cursor: 'pointer'
+                                            }}
+                                          >
+                                            <Stack gap={6}>
+                                              <Badge color={color} variant={isCurrent ? 'filled' : 'light'} style={{ alignSelf: 'flex-start' }}>
+                                                {index + 1}
+                                              </Badge>
+                                              <Text fw={600}>{step.name}</Text>
+                                              <Text size="xs" c="dimmed">{step.automation_mode}</Text>
+                                              <Badge color={color} variant={isCurrent ? 'filled' : 'light'} style={{ alignSelf: 'flex-start' }}>
+                                                {isCurrent ? 'ACTIVE' : isCompleted ? 'DONE' : 'UP NEXT'}
+                                              </Badge>
+                                            </Stack>
+                                          </Box>
+                                          {index < selectedRunDefinition.steps.length - 1 ? <Text c="dimmed" fw={700}>→</Text> : null}
+                                        </Group>
+                                      );
+                                    })}
+                                    {Boolean(selectedRun?.current_step_id) && selectedRunDefinition.steps.findIndex((item) => item.id === selectedRun.current_step_id) < 0 ? (
+                                      <Alert color="yellow" title="Current stage not in displayed definition">
+                                        Current step id: {selectedRun.current_step_id}
+                                      </Alert>
+                                    ) : null}
+                                  </Group>
+                              ) : (
+                                <Text c="dimmed">The selected run is not linked to a loaded template.</Text>
+                              )}
                             </Stack>
                           </Card>
                         </Stack>
-                        <Card withBorder>
-                          <Stack gap="md">
-                            <Text fw={600}>Workflow progress</Text>
-                            {selectedRunDefinition ? (
-                              <Group gap="sm" wrap="wrap" align="stretch">
-                                  {selectedRunDefinition.steps.map((step, index) => {
-                                    const isCurrent = selectedRun?.current_step_id === step.id;
-                                    const currentIndex = selectedRunDefinition.steps.findIndex((item) => item.id === selectedRun?.current_step_id);
-                                    const isCompleted = currentIndex >= 0 && index < currentIndex;
-                                    const isUnknownCurrentStep = Boolean(selectedRun?.current_step_id) && currentIndex < 0;
-                                    const color = isCurrent ? 'blue' : isCompleted ? 'green' : 'gray';
-                                    return (
-                                      <Group key={step.id} gap="sm" wrap="nowrap" align="center">
-                                        <Box
-                                          p="md"
-                                          onClick={() => handleStageCardClick(step.id)}
-                                          style={{
-                                            minWidth: 180,
-                                            borderRadius: 12,
-                                            border: `1px solid var(--mantine-color-${color}-6)`,
-                                            background: isCurrent
-                                              ? 'rgba(34, 139, 230, 0.14)'
-                                              : isCompleted
-                                                ? 'rgba(64, 192, 87, 0.12)'
-                                                : 'rgba(255,255,255,0.02)',
-                                            cursor: 'pointer'
-                                          }}
-                                        >
-                                          <Stack gap={6}>
-                                            <Badge color={color} variant={isCurrent ? 'filled' : 'light'} style={{ alignSelf: 'flex-start' }}>
-                                              {index + 1}
-                                            </Badge>
-                                            <Text fw={600}>{step.name}</Text>
-                                            <Text size="xs" c="dimmed">{step.automation_mode}</Text>
-                                            <Badge color={color} variant={isCurrent ? 'filled' : 'light'} style={{ alignSelf: 'flex-start' }}>
-                                              {isCurrent ? 'ACTIVE' : isCompleted ? 'DONE' : 'UP NEXT'}
-                                            </Badge>
-                                          </Stack>
-                                        </Box>
-                                        {index < selectedRunDefinition.steps.length - 1 ? <Text c="dimmed" fw={700}>→</Text> : null}
-                                      </Group>
-                                    );
-                                  })}
-                                  {Boolean(selectedRun?.current_step_id) && selectedRunDefinition.steps.findIndex((item) => item.id === selectedRun.current_step_id) < 0 ? (
-                                    <Alert color="yellow" title="Current stage not in displayed definition">
-                                      Current step id: {selectedRun.current_step_id}
-                                    </Alert>
-                                  ) : null}
-                                </Group>
-                            ) : (
-                              <Text c="dimmed">The selected run is not linked to a loaded template.</Text>
-                            )}
-                          </Stack>
-                        </Card>
-                      </Stack>
-                    ) : (
-                      <Text c="dimmed">No workflow selected.</Text>
-                    )}
-                  </Card>
+                      ) : (
+                        <Text c="dimmed">No workflow selected.</Text>
+                      )}
+                    </Card>
 
-                  <Card withBorder>
-                    <Stack>
-                      <Grid align="stretch">
-                        <Grid.Col span={{ base: 12, xl: 4 }}>
-                          <Stack>
-                            {!inferenceRequiredForSelectedStep || !inferenceRequiresConnection || inferenceReady ? (
-                              <>
-                                {selectedWorkflowStep?.step_type === 'sap_import' ? (
-                                  <SapImportStageControlsPanel
-                                    status={sapImportStatus}
-                                    packageName={sapImportPackageName}
-                                    includeSubpackages={sapImportIncludeSubpackages}
-                                    includeXmlArtifacts={sapImportIncludeXmlArtifacts}
-                                    searchBusy={sapImportSearchBusy || sapImportApplyBusy}
-                                    checkedCount={sapImportCheckedUris.size}
-                                    onLoad={() => void handleSapImportSearch()}
-                                    onApplySelection={() => void applySapImportSelection()}
-                                    onPackageNameChange={(value) => {
-                                      setSapImportPackageName(value);
-                                      patchSelectedStepDescriptorField('config.sap_import.package_name', value);
-                                    }}
-                                    onIncludeSubpackagesChange={(value) => {
-                                      setSapImportIncludeSubpackages(value);
-                                      patchSelectedStepDescriptorField('config.sap_import.include_subpackages', value);
-                                    }}
-                                    onIncludeXmlArtifactsChange={(value) => {
-                                      setSapImportIncludeXmlArtifacts(value);
-                                      patchSelectedStepDescriptorField('config.sap_import.include_xml_artifacts', value);
-                                    }}
-                                  />
-                                ) : selectedWorkflowStep?.step_type === 'sap_export' ? (
-                                  <SapExportStageInputsPanel
-                                    selectedWorkflowStep={selectedWorkflowStep ?? null}
-                                    repoRef={selectedRun?.repo_ref ?? ''}
-                                    onPatchSelectedStepConfig={patchSelectedStepDescriptorField}
-                                  />
-                                ) : (
-                                  <BackendDrivenStageInputsPanel
-                                    descriptor={selectedStageDescriptor}
-                                    selectedWorkflowStep={selectedWorkflowStep ?? null}
-                                    repoFragmentSummary={repoFragmentSummary}
-                                    stageApplyError={stageApplyError}
-                                    stageCompileError={stageCompileError}
-                                    stageCompileCommandsText={stageCompileCommandsText}
+                    <Card withBorder>
+                      <Stack>
+                        <Grid align="stretch">
+                          <Grid.Col span={{ base: 12, xl: 4 }}>
+                            <Stack>
+                              {!inferenceRequiredForSelectedStep || !inferenceRequiresConnection || inferenceReady ? (
+                                <>
+                                  {selectedWorkflowStep?.step_type === 'sap_import' ? (
+                                    <SapImportStageControlsPanel
+                                      status={sapImportStatus}
+                                      packageName={sapImportPackageName}
+                                      includeSubpackages={sapImportIncludeSubpackages}
+                                      includeXmlArtifacts={sapImportIncludeXmlArtifacts}
+                                      searchBusy={sapImportSearchBusy || sapImportApplyBusy}
+                                      checkedCount={sapImportCheckedUris.size}
+                                      onLoad={() => void handleSapImportSearch()}
+                                      onApplySelection={() => void applySapImportSelection()}
+                                      onPackageNameChange={(value) => {
+                                        setSapImportPackageName(value);
+                                        patchSelectedStepDescriptorField('config.sap_import.package_name', value);
+                                      }}
+                                      onIncludeSubpackagesChange={(value) => {
+                                        setSapImportIncludeSubpackages(value);
+                                        patchSelectedStepDescriptorField('config.sap_import.include_subpackages', value);
+                                      }}
+                                      onIncludeXmlArtifactsChange={(value) => {
+                                        setSapImportIncludeXmlArtifacts(value);
+                                        patchSelectedStepDescriptorField('config.sap_import.include_xml_artifacts', value);
+                                      }}
+                                    />
+                                  ) : selectedWorkflowStep?.step_type === 'sap_export' ? (
+                                    <SapExportStageInputsPanel
+                                      selectedWorkflowStep={selectedWorkflowStep ?? null}
+                                      repoRef={selectedRun?.repo_ref ?? ''}
+                                      onPatchSelectedStepConfig={patchSelectedStepDescriptorField}
+                                    />
+                                  ) : (
+                                    <BackendDrivenStageInputsPanel
+                                      descriptor={selectedStageDescriptor}
+                                      selectedWorkflowStep={selectedWorkflowStep ?? null}
+                                      repoFragmentSummary={repoFragmentSummary}
+                                      stageApplyError={stageApplyError}
+                                      stageCompileError={stageCompileError}
+                                      stageCompileCommandsText={stageCompileCommandsText}
+                                      inferenceConnectionStatus={inferenceConnectionStatus}
+                                      inferenceTransport={inferenceTransport}
+                                      sharedInferenceState={sharedInferenceState}
+                                      stageIncludeRepoContext={stageIncludeRepoContext}
+                                      stageIncludeChangesetSchema={stageIncludeChangesetSchema}
+                                      disabled={isBackendRunLocked}
+                                      onToggleSharedRepoContext={onToggleSharedRepoContext}
+                                      onToggleSharedChangesetSchema={onToggleSharedChangesetSchema}
+                                      onPatchSelectedStepConfig={patchSelectedStepDescriptorField}
+                                      onOpenInferenceConfig={openGlobalInferenceConfig}
+                                      onOpenRepoConfig={() => setRepoContextConfigOpen(true)}
+                                      onOpenSchemaConfig={() => setChangesetSchemaConfigOpen(true)}
+                                      onOpenApplyErrorConfig={() => setApplyErrorConfigOpen(true)}
+                                      onOpenCompileErrorConfig={() => setCompileErrorConfigOpen(true)}
+                                      onOpenCompileSimulation={() => setCompileSimulationOpen(true)}
+                                      onOpenChanges={() => setActiveWorkspaceTab('diff')}
+                                      onReadDroppedFile={async (path) => {
+                                        if (selectedRun) return (await readWorkflowFile(selectedRun.id, path)).contents;
+                                        return (await readWorkspaceFile(repoRef, path)).contents;
+                                      }}
+                                      repoRef={resolveRepoRefForRun(selectedRun)}
+                                    />
+                                  )}
+                                  <InferenceConnectionCard
                                     inferenceConnectionStatus={inferenceConnectionStatus}
+                                    inferenceReady={inferenceReady}
+                                    inferenceSummaryText={inferenceSummaryText}
                                     inferenceTransport={inferenceTransport}
-                                    sharedInferenceState={sharedInferenceState}
-                                    stageIncludeRepoContext={stageIncludeRepoContext}
-                                    stageIncludeChangesetSchema={stageIncludeChangesetSchema}
-                                    disabled={isBackendRunLocked}
-                                    onToggleSharedRepoContext={onToggleSharedRepoContext}
-                                    onToggleSharedChangesetSchema={onToggleSharedChangesetSchema}
-                                    onPatchSelectedStepConfig={patchSelectedStepDescriptorField}
-                                    onOpenInferenceConfig={openGlobalInferenceConfig}
-                                    onOpenRepoConfig={() => setRepoContextConfigOpen(true)}
-                                    onOpenSchemaConfig={() => setChangesetSchemaConfigOpen(true)}
-                                    onOpenApplyErrorConfig={() => setApplyErrorConfigOpen(true)}
-                                    onOpenCompileErrorConfig={() => setCompileErrorConfigOpen(true)}
-                                    onOpenChanges={() => setActiveWorkspaceTab('diff')}
+                                    browserTargetUrl={browserTargetUrl}
+                                    browserCdpUrl={browserCdpUrl}
+                                    inferenceBusy={inferenceBusy}
+                                    inferenceStatus={inferenceStatus}
+
+                                    hideInlineCard
+                                    onOpenConfig={openGlobalInferenceConfig}
+                                    onTransportChange={(value) => setInferenceTransport(value)}
+                                    onBrowserTargetUrlChange={setBrowserTargetUrl}
+                                    onBrowserCdpUrlChange={setBrowserCdpUrl}
+                                    onSaveConfig={() => void handleSaveGlobalInference()}
                                   />
-                                )}
-                                <InferenceConnectionCard
-                                  inferenceConnectionStatus={inferenceConnectionStatus}
-                                  inferenceReady={inferenceReady}
-                                  inferenceSummaryText={inferenceSummaryText}
-                                  inferenceTransport={inferenceTransport}
-                                  browserTargetUrl={browserTargetUrl}
-                                  browserCdpUrl={browserCdpUrl}
-                                  inferenceBusy={inferenceBusy}
-                                  inferenceStatus={inferenceStatus}
-
-                                  hideInlineCard
-                                  onOpenConfig={openGlobalInferenceConfig}
-                                  onTransportChange={(value) => setInferenceTransport(value)}
-                                  onBrowserTargetUrlChange={setBrowserTargetUrl}
-                                  onBrowserCdpUrlChange={setBrowserCdpUrl}
-                                  onSaveConfig={() => void handleSaveGlobalInference()}
-                                />
-                              </>
-                            ) : null}
-                          </Stack>
-                        </Grid.Col>
-                        <Grid.Col span={{ base: 12, xl: 8 }} style={{ display: 'flex' }}>
-                          <Box style={{ flex: 1 }}>
-                            {showStageStream ? <StageStreamPanel renderStageStreamPanel={renderStageStreamPanel} /> : null}
-                          </Box>
-                        </Grid.Col>
-                      </Grid>
+                                </>
+                              ) : null}
+                            </Stack>
+                          </Grid.Col>
+                          <Grid.Col span={{ base: 12, xl: 8 }} style={{ display: 'flex' }}>
+                            <Box style={{ flex: 1 }}>
+                              {showStageStream ? <StageStreamPanel renderStageStreamPanel={renderStageStreamPanel} /> : null}
+                            </Box>
+                          </Grid.Col>
+                        </Grid>
 
 
-                      {manualCapabilityStatus ? <Alert color={manualCapabilityStatus.toLowerCase().includes('error') ? 'red' : 'blue'}>{manualCapabilityStatus}</Alert> : null}
+                        {manualCapabilityStatus ? <Alert color={manualCapabilityStatus.toLowerCase().includes('error') ? 'red' : 'blue'}>{manualCapabilityStatus}</Alert> : null}
+                      </Stack>
+                    </Card>
+
+                    <RunNotesCard
+                      runId={selectedRunId}
+                      initialText={selectedRunNotes.markdown}
+                      initialUpdatedAt={selectedRunNotes.updatedAt}
+                      canInject={!!selectedWorkflowStep && !isBackendRunLocked}
+                      onInject={(text) => patchSelectedStepDescriptorField('prompt.user_input', [stageUserInput.trimEnd(), `### NOTES\n${text}`].filter(Boolean).join('\n\n'))}
+                    />
                     </Stack>
-                  </Card>
-                  </Stack>
-                </Grid.Col>
+                  </Grid.Col>
 
-                <Grid.Col span={{ base: 12, xl: 5 }}>
-                  <Card withBorder style={{ height: '100%' }}>
-                    <Stack h="100%">
-                      <Group justify="space-between">
-                        <Group gap="xs">
-                          <Title order={5}>Live workflow events</Title>
-                          <Badge color={eventStreamStatus.color} variant="light">Stream {eventStreamStatus.label}</Badge>
+                  <Grid.Col span={{ base: 12, xl: 5 }}>
+                    <Card withBorder style={{ height: '100%' }}>
+                      <Stack h="100%">
+                        <Group justify="space-between">
+                          <Group gap="xs">
+                            <Title order={5}>Live workflow events</Title>
+                            <Badge color={eventStreamStatus.color} variant="light">Stream {eventStreamStatus.label}</Badge>
+                          </Group>
+                          <Button variant="light" size="xs" onClick={() => selectedRunId && void refreshLiveMonitor(selectedRunId)}>Refresh events</Button>
                         </Group>
-                        <Button variant="light" size="xs" onClick={() => selectedRunId && void refreshLiveMonitor(selectedRunId)}>Refresh events</Button>
-                      </Group>
-                      {liveExecutionTrails.length > 0 ? (
-                        <Stack gap="xs">
-                          {liveExecutionTrails.map((trail, index) => {
-                            const trailExpanded = isLiveExecutionExpanded(trail);
-                            const executionState = liveExecutionChains[trail.key] ?? { loading: false, error: null, chain: null, latestCreatedAt: null };
-                            const rawEvents = (executionState.chain?.items ?? []).slice().sort((a, b) => b.sequence_no - a.sequence_no);
-                            return (
-                              <Box
-                                key={trail.key}
-                                p="sm"
-                                style={{
-                                  border: `1px solid var(--mantine-color-${liveStageTone(trail)}-4)`,
-                                  borderRadius: 10,
-                                  background: trail.isCurrent
-                                    ? 'rgba(34, 139, 230, 0.08)'
-                                    : trail.isActive
-                                      ? 'rgba(250, 176, 5, 0.08)'
-                                      : liveStageTone(trail) === 'green'
-                                        ? 'rgba(64, 192, 87, 0.08)'
-                                        : liveStageTone(trail) === 'red'
-                                          ? 'rgba(250, 82, 82, 0.08)'
-                                          : liveStageTone(trail) === 'yellow'
-                                            ? 'rgba(250, 176, 5, 0.08)'
-                                            : 'rgba(255,255,255,0.02)'
-                                }}
-                              >
-                                <Group justify="space-between" align="center" wrap="nowrap">
-                                  <Group gap="xs" wrap="wrap" style={{ flex: 1 }}>
-                                    <Badge color={trail.isCurrent ? 'blue' : trail.isActive ? 'yellow' : liveStageTone(trail)}>
-                                      {trail.isCurrent ? 'RUNNING' : trail.isActive ? 'ACTIVE' : liveStageTone(trail) === 'red' ? 'FAILED' : liveStageTone(trail) === 'yellow' ? 'WARN' : 'COMPLETE'}
-                                    </Badge>
-                                    <Badge variant="light">{trail.stepId !== '__ungrouped__' ? trail.stepId : trail.label}</Badge>
+                        {liveExecutionTrails.length > 0 ? (
+                          <Stack gap="xs">
+                            {liveExecutionTrails.map((trail, index) => {
+                              const trailExpanded = isLiveExecutionExpanded(trail);
+                              const executionState = liveExecutionChains[trail.key] ?? { loading: false, error: null, chain: null, latestCreatedAt: null };
+                              const rawEvents = (executionState.chain?.items ?? []).slice().sort((a, b) => b.sequence_no - a.sequence_no);
+                              return (
+                                <Box
+                                  key={trail.key}
+                                  p="sm"
+                                  style={{
+                                    border: `1px solid var(--mantine-color-${liveStageTone(trail)}-4)`,
+                                    borderRadius: 10,
+                                    background: trail.isCurrent
+                                      ? 'rgba(34, 139, 230, 0.08)'
+                                      : trail.isActive
+                                        ? 'rgba(250, 176, 5, 0.08)'
+                                        : liveStageTone(trail) === 'green'
+                                          ? 'rgba(64, 192, 87, 0.08)'
+                                          : liveStageTone(trail) === 'red'
+                                            ? 'rgba(250, 82, 82, 0.08)'
+                                            : liveStageTone(trail) === 'yellow'
+                                              ? 'rgba(250, 176, 5, 0.08)'
+                                              : 'rgba(255,255,255,0.02)'
+                                  }}
+                                >
+                                  <Group justify="space-between" align="center" wrap="nowrap">
+                                    <Group gap="xs" wrap="wrap" style={{ flex: 1 }}>
+                                      <Badge color={trail.isCurrent ? 'blue' : trail.isActive ? 'yellow' : liveStageTone(trail)}>
+                                        {trail.isCurrent ? 'RUNNING' : trail.isActive ? 'ACTIVE' : liveStageTone(trail) === 'red' ? 'FAILED' : liveStageTone(trail) === 'yellow' ? 'WARN' : 'COMPLETE'}
+                                      </Badge>
+                                      <Badge variant="light">{trail.stepId !== '__ungrouped__' ? trail.stepId : trail.label}</Badge>
+                                    </Group>
+                                    <Group gap="md" align="center" wrap="nowrap">
+                                      <Stack gap={0} align="flex-end">
+                                        <Text size="sm" fw={600}>{formatTimestamp(trail.latestCreatedAt)}</Text>
+                                        <Text size="sm" fw={600}>{formatDurationMs(trail.durationMs, null, null)}</Text>
+                                      </Stack>
+                                      <Button
+                                        size="xs"
+                                        variant="subtle"
+                                        onClick={() => {
+                                          toggleLiveExecutionExpanded(trail);
+                                          if (!trailExpanded) {
+                                            void ensureLiveExecutionChainLoaded(trail);
+                                          }
+                                        }}
+                                      >
+                                        {trailExpanded ? 'Collapse execution' : 'Expand execution'}
+                                      </Button>
+                                    </Group>
                                   </Group>
-                                  <Group gap="md" align="center" wrap="nowrap">
-                                    <Stack gap={0} align="flex-end">
-                                      <Text size="sm" fw={600}>{formatTimestamp(trail.latestCreatedAt)}</Text>
-                                      <Text size="sm" fw={600}>{formatDurationMs(trail.durationMs, null, null)}</Text>
-                                    </Stack>
-                                    <Button
-                                      size="xs"
-                                      variant="subtle"
-                                      onClick={() => {
-                                        toggleLiveExecutionExpanded(trail);
-                                        if (!trailExpanded) {
-                                          void ensureLiveExecutionChainLoaded(trail);
+                                  {trailExpanded ? (
+                                    <Stack gap="xs" mt="sm">
+                                      <Divider label="Capabilities" labelPosition="left" />
+
+                                      {executionState.loading ? <Loader size="sm" /> : null}
+                                      {executionState.error ? <Alert color="red">{executionState.error}</Alert> : null}
+                                      {!executionState.loading && !executionState.error && rawEvents.length === 0 ? (
+                                        <Text size="sm" c="dimmed">No execution events loaded.</Text>
+                                      ) : null}
+
+                                      {(() => {
+                                        const capabilityCards = buildLiveCapabilitiesFromEvents(trail, rawEvents);
+                                        if (!executionState.loading && !executionState.error && capabilityCards.length === 0) {
+                                          return <Text size="sm" c="dimmed">No capability executions found.</Text>;
                                         }
-                                      }}
-                                    >
-                                      {trailExpanded ? 'Collapse execution' : 'Expand execution'}
-                                    </Button>
-                                  </Group>
-                                </Group>
-                                {trailExpanded ? (
-                                  <Stack gap="xs" mt="sm">
-                                    <Divider label="Capabilities" labelPosition="left" />
-
-                                    {executionState.loading ? <Loader size="sm" /> : null}
-                                    {executionState.error ? <Alert color="red">{executionState.error}</Alert> : null}
-                                    {!executionState.loading && !executionState.error && rawEvents.length === 0 ? (
-                                      <Text size="sm" c="dimmed">No execution events loaded.</Text>
-                                    ) : null}
-
-                                    {(() => {
-                                      const capabilityCards = buildLiveCapabilitiesFromEvents(trail, rawEvents);
-                                      if (!executionState.loading && !executionState.error && capabilityCards.length === 0) {
-                                        return <Text size="sm" c="dimmed">No capability executions found.</Text>;
-                                      }
-                                      return capabilityCards.map((capability) => {
-                                        const eventExpanded = expandedLiveEventIds.has(capability.key);
-                                        return (
-                                          <Box
-                                            key={capability.key}
-                                            p="sm"
-                                            style={{
-                                              ...livePulseStyle(capability.isActive, capability.isNew),
-                                              border: `1px solid var(--mantine-color-${capabilityTone(capability)}-4)`,
-                                              borderRadius: 8,
-                                              background: capability.isActive
-                                                ? 'rgba(34, 139, 230, 0.08)'
-                                                : capabilityTone(capability) === 'green'
-                                                  ? 'rgba(64, 192, 87, 0.08)'
-                                                  : capabilityTone(capability) === 'red'
-                                                    ? 'rgba(250, 82, 82, 0.08)'
-                                                    : capabilityTone(capability) === 'yellow'
-                                                      ? 'rgba(250, 176, 5, 0.08)'
-                                                      : 'rgba(255,255,255,0.02)'
-                                            }}
-                                          >
-                                            <Box style={liveProgressBar(capability.isActive, capabilityTone(capability))} />
-                                            <Group justify="space-between" align="flex-start" wrap="nowrap" style={{ position: 'relative', zIndex: 1 }}>
-                                              <Group align="flex-start" justify="space-between" wrap="nowrap" style={{ flex: 1 }}>
-                                                <Stack gap={4} style={{ flex: 1 }}>
-                                                  <Group gap="xs" wrap="wrap">
-                                                    <Badge color={capabilityTone(capability)}>{capability.statusLabel}</Badge>
-                                                    <Badge variant="light">{capability.name}</Badge>
-                                                    <Text size="xs" c="dimmed">events {capability.eventCount}</Text>
-                                                  </Group>
-                                                  <Text size="sm">{capability.message}</Text>
-                                                </Stack>
-                                                <Stack gap={2} align="flex-end" style={{ minWidth: 220 }}>
-                                                  <Text size="sm" fw={600}>{capability.startedAtText}</Text>
-                                                  <Text size="sm" fw={600}>{capability.isActive ? formatDuration(capability.startedAtRaw ?? capability.latestCreatedAt, new Date(liveNow).toISOString()) : capability.durationText}</Text>
-                                                </Stack>
+                                        return capabilityCards.map((capability) => {
+                                          const eventExpanded = expandedLiveEventIds.has(capability.key);
+                                          return (
+                                            <Box
+                                              key={capability.key}
+                                              p="sm"
+                                              style={{
+                                                ...livePulseStyle(capability.isActive, capability.isNew),
+                                                border: `1px solid var(--mantine-color-${capabilityTone(capability)}-4)`,
+                                                borderRadius: 8,
+                                                background: capability.isActive
+                                                  ? 'rgba(34, 139, 230, 0.08)'
+                                                  : capabilityTone(capability) === 'green'
+                                                    ? 'rgba(64, 192, 87, 0.08)'
+                                                    : capabilityTone(capability) === 'red'
+                                                      ? 'rgba(250, 82, 82, 0.08)'
+                                                      : capabilityTone(capability) === 'yellow'
+                                                        ? 'rgba(250, 176, 5, 0.08)'
+                                                        : 'rgba(255,255,255,0.02)'
+                                              }}
+                                            >
+                                              <Box style={liveProgressBar(capability.isActive, capabilityTone(capability))} />
+                                              <Group justify="space-between" align="flex-start" wrap="nowrap" style={{ position: 'relative', zIndex: 1 }}>
+                                                <Group align="flex-start" justify="space-between" wrap="nowrap" style={{ flex: 1 }}>
+                                                  <Stack gap={4} style={{ flex: 1 }}>
+                                                    <Group gap="xs" wrap="wrap">
+                                                      <Badge color={capabilityTone(capability)}>{capability.statusLabel}</Badge>
+                                                      <Badge variant="light">{capability.name}</Badge>
+                                                      <Text size="xs" c="dimmed">events {capability.eventCount}</Text>
+                                                    </Group>
+                                                    <Text size="sm">{capability.message}</Text>
+                                                  </Stack>
+                                                  <Stack gap={2} align="flex-end" style={{ minWidth: 220 }}>
+                                                    <Text size="sm" fw={600}>{capability.startedAtText}</Text>
+                                                    <Text size="sm" fw={600}>{capability.isActive ? formatDuration(capability.startedAtRaw ?? capability.latestCreatedAt, new Date(liveNow).toISOString()) : capability.durationText}</Text>
+                                                  </Stack>
+                                                </Group>
+                                                <Button size="xs" variant="subtle" onClick={() => toggleLiveEventExpanded(capability.key)}>
+                                                  {eventExpanded ? 'Hide raw JSON' : 'Show raw JSON'}
+                                                </Button>
                                               </Group>
-                                              <Button size="xs" variant="subtle" onClick={() => toggleLiveEventExpanded(capability.key)}>
-                                                {eventExpanded ? 'Hide raw JSON' : 'Show raw JSON'}
-                                              </Button>
-                                            </Group>
-                                            {eventExpanded ? (
-                                              <ScrollArea mt="sm" offsetScrollbars>
-                                                <Code block>{JSON.stringify(capabilityIoPayload(capability), null, 2)}</Code>
-                                              </ScrollArea>
-                                            ) : null}
-                                          </Box>
-                                        );
-                                      });
-                                    })()}
-                                  </Stack>
-                                ) : null}
-                              </Box>
-                            );
-                          })}
-                        </Stack>
-                      ) : null}
-                      {liveExecutionTrails.length === 0 ? (
-                        <Text c="dimmed">No live executions yet.</Text>
-                      ) : null}
-                    </Stack>
-                  </Card>
-                </Grid.Col>
-              </Grid>
-            )}
+                                              {eventExpanded ? (
+                                                <ScrollArea mt="sm" offsetScrollbars>
+                                                  <Code block>{JSON.stringify(capabilityIoPayload(capability), null, 2)}</Code>
+                                                </ScrollArea>
+                                              ) : null}
+                                            </Box>
+                                          );
+                                        });
+                                      })()}
+                                    </Stack>
+                                  ) : null}
+                                </Box>
+                              );
+                            })}
+                          </Stack>
+                        ) : null}
+                        {liveExecutionTrails.length === 0 ? (
+                          <Text c="dimmed">No live executions yet.</Text>
+                        ) : null}
+                      </Stack>
+                    </Card>
+                  </Grid.Col>
+                </Grid>
+              )}
+          </WorkspaceSplit>
           </Stack>
 
         <Modal
@@ -4890,13 +5802,100 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
         >
           <Stack h="100%" gap="md">
             <TextInput label="Git ref" value={stageRepoContextGitRef} onChange={(e) => setStageRepoContextGitRef(e.currentTarget.value)} placeholder="WORKTREE" />
-            <TextInput label="Save path" value={stageRepoContextSavePath} onChange={(e) => setStageRepoContextSavePath(e.currentTarget.value)} placeholder="/tmp/repo_context.txt" />
+            <Group align="end" gap="md">
+              <TextInput
+                label="Save path"
+                description="Use {repo}, {ref}, {timestamp} and {date} in the name; a folder ending in / gets {repo}-{ref}-{timestamp}.md"
+                value={stageRepoContextSavePath}
+                onChange={(e) => setStageRepoContextSavePath(e.currentTarget.value)}
+                placeholder="/tmp/repo_context.txt"
+                style={{ flex: 1 }}
+              />
+              <SegmentedControl
+                value={stageRepoContextFormat}
+                onChange={(value) => setStageRepoContextFormat(value === 'zip' ? 'zip' : 'text')}
+                data={[{ label: 'Text', value: 'text' }, { label: 'Zip archive', value: 'zip' }]}
+              />
+              <SegmentedControl
+                value={stageRepoContextIfExists}
+                onChange={(value) => setStageRepoContextIfExists(value === 'new_version' ? 'new_version' : 'overwrite')}
+                data={[{ label: 'Overwrite', value: 'overwrite' }, { label: 'New version', value: 'new_version' }]}
+              />
+            </Group>
+            {(() => {
+              const last = exportDestinations.find((item) => item.exporter === `context_export.${stageRepoContextFormat}`);
+              if (!last || !last.save_path || last.save_path === stageRepoContextSavePath.trim()) return null;
+              return (
+                <Group gap="xs" wrap="nowrap">
+                  <Text size="xs" c="dimmed" truncate>Last {stageRepoContextFormat} export went to {last.save_path}</Text>
+                  <Button
+                    size="compact-xs"
+                    variant="subtle"
+                    onClick={() => {
+                      setStageRepoContextSavePath(last.save_path);
+                      setStageRepoContextIfExists(last.if_exists === 'new_version' ? 'new_version' : 'overwrite');
+                    }}
+                  >
+                    Use it
+                  </Button>
+                </Group>
+              );
+            })()}
+            <ContextExportRunner
+              buildPayload={() => ({ ...stagedContextExportConfig(), repo_ref: (view === 'builder' ? repoRef : (selectedRun?.repo_ref ?? repoRef)).trim() })}
+              disabled={!(view === 'builder' ? repoRef : (selectedRun?.repo_ref ?? repoRef)).trim()}
+            />
             <SimpleGrid cols={{ base: 1, md: 2 }}>
               <Switch label="Skip binary" checked={stageRepoContextSkipBinary} onChange={(e) => setStageRepoContextSkipBinary(e.currentTarget.checked)} />
               <Switch label="Skip .gitignore" checked={stageRepoContextSkipGitignore} onChange={(e) => setStageRepoContextSkipGitignore(e.currentTarget.checked)} />
               <Switch label="Include staged diff" checked={stageRepoContextIncludeStagedDiff} onChange={(e) => setStageRepoContextIncludeStagedDiff(e.currentTarget.checked)} />
               <Switch label="Include unstaged diff" checked={stageRepoContextIncludeUnstagedDiff} onChange={(e) => setStageRepoContextIncludeUnstagedDiff(e.currentTarget.checked)} />
+              <Switch
+                label="Refresh applied files"
+                description="After an automatic apply, send the model current copies of files it was shown that the apply changed"
+                checked={stageRepoContextRefreshOnApply}
+                onChange={(e) => setStageRepoContextRefreshOnApply(e.currentTarget.checked)}
+              />
             </SimpleGrid>
+            <Group align="end" gap="md">
+              <NumberInput
+                label="Max bytes per file"
+                description="Larger text files keep their first and last lines around an elision marker. Empty exports files whole."
+                value={stageRepoContextMaxBytesPerFile ?? ''}
+                onChange={(value) => setStageRepoContextMaxBytesPerFile(typeof value === 'number' && value > 0 ? value : null)}
+                min={0}
+                step={10000}
+                thousandSeparator=","
+                placeholder="No limit"
+                style={{ flex: 1 }}
+              />
+              <Switch
+                label="Keep imports and signatures"
+                description="For code, elide function bodies first"
+                checked={stageRepoContextKeepSignatures}
+                onChange={(e) => setStageRepoContextKeepSignatures(e.currentTarget.checked)}
+              />
+            </Group>
+            <ContextSymbolPicker
+              repoRef={(view === 'builder' ? repoRef : (selectedRun?.repo_ref ?? repoRef)).trim()}
+              gitRef={stageRepoContextGitRef.trim() || 'WORKTREE'}
+              files={selectedRepoPaths}
+              value={stageRepoContextSymbols}
+              onChange={setStageRepoContextSymbols}
+            />
+            <ContextBudgetOverrides
+              gitRef={stageRepoContextGitRef.trim() || 'WORKTREE'}
+              files={selectedRepoPaths}
+              value={stageRepoContextFileBudgets}
+              onChange={setStageRepoContextFileBudgets}
+            />
+            <ContextInjectionStrategy
+              files={selectedRepoPaths}
+              value={stageRepoContextInjection}
+              onChange={setStageRepoContextInjection}
+              last={view === 'builder' ? null : lastContextInjection}
+            />
+            <ContextDriftPanel repoRef={(view === 'builder' ? repoRef : (selectedRun?.repo_ref ?? repoRef)).trim()} defaultPath={stageRepoContextSavePath} />
             <Group justify="space-between">
               <Group>
                 <Button
@@ -4922,6 +5921,11 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
                 }}>
                   Select loaded files
                 </Button>
+                <SelectTouchedPopover
+                  repoRef={(view === 'builder' ? repoRef : (selectedRun?.repo_ref ?? repoRef)).trim()}
+                  gitRef={stageRepoContextGitRef.trim() || 'WORKTREE'}
+                  onSelect={(paths) => { setSelectedRepoDirs(new Set()); syncRepoSelectionState(paths); }}
+                />
               </Group>
               <Text size="sm">Selected files: <Code>{selectedRepoPaths.length}</Code></Text>
             </Group>
@@ -4948,13 +5952,19 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
                 }}
                 onSetPaths={setPaths}
                 height={360}
+                gitStatus={contextTreeGitStatus}
               />
             )}
             <Textarea label="Include files" minRows={8} value={stageRepoContextIncludeFilesText} onChange={(e) => {
               const value = e.currentTarget.value;
               syncRepoSelectionState(value.split('\n').map((item) => item.trim()).filter(Boolean));
             }} placeholder={"src/main.rs\nsrc/lib.rs"} />
-            <Textarea label="Exclude regex" minRows={6} value={stageRepoContextExcludeRegexText} onChange={(e) => setStageRepoContextExcludeRegexText(e.currentTarget.value)} placeholder={"target/.*\nnode_modules/.*"} />
+            <ExcludeRegexManager
+              value={stageRepoContextExcludeRegexText}
+              onChange={setStageRepoContextExcludeRegexText}
+              repoRef={(view === 'builder' ? repoRef : (selectedRun?.repo_ref ?? repoRef)).trim()}
+              gitRef={stageRepoContextGitRef.trim() || 'WORKTREE'}
+            />
             <Group justify="flex-end">
               <Button size="xs" variant="default" onClick={() => setRepoContextConfigOpen(false)}>Cancel</Button>
               <Button size="xs" onClick={handleSaveBuilderRepoContext}>Save</Button>
@@ -5016,7 +6026,12 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
                 <Alert color="blue">Only backend-owned inference fields are persisted here. Browser defaults and runtime session behavior stay on the backend.</Alert>
               </Stack>
             ) : (
-              <Alert color="blue">API mode only persists the transport choice. Model, max tokens, temperature, provider, and system prompt are not stored in workflow global state.</Alert>
+              <InferenceGenerationFields
+                model={inferenceModel}
+                generation={inferenceGeneration}
+                onModelChange={setInferenceModel}
+                onGenerationChange={setInferenceGeneration}
+              />
             )}
 
             {inferenceStatus ? <Alert color={inferenceStatus.toLowerCase().includes('saved') ? 'green' : 'red'}>{inferenceStatus}</Alert> : null}
@@ -5064,6 +6079,32 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
           </Stack>
         </Modal>
 
+        <NetworkSettingsModal opened={networkSettingsOpen} onClose={() => setNetworkSettingsOpen(false)} />
+        <SettingsBundleModal opened={settingsBundleOpen} onClose={() => setSettingsBundleOpen(false)} />
+        <AccessibilitySettingsModal opened={accessibilityOpen} onClose={() => setAccessibilityOpen(false)} />
+        <AutomationSettingsModal opened={automationOpen} onClose={() => setAutomationOpen(false)} />
+        <McpServerModal opened={mcpServerOpen} onClose={() => setMcpServerOpen(false)} />
+        <DoctorModal
+          opened={doctorOpen}
+          onClose={() => setDoctorOpen(false)}
+          onReport={(checks) => setDoctorFailures(checks.filter((check) => check.status === 'fail'))}
+        />
+        <ProcessesModal opened={processesOpen} onClose={() => setProcessesOpen(false)} />
+        <CapabilityTracesModal opened={capabilityTracesOpen} onClose={() => setCapabilityTracesOpen(false)} runId={selectedRun?.id ?? null} />
+        <PostprocessSimulationDrawer
+          opened={compileSimulationOpen}
+          onClose={() => setCompileSimulationOpen(false)}
+          runId={selectedRun?.id ?? null}
+          value={compileCommandOptions}
+          onSave={saveCompileCommandOptions}
+        />
+        <PluginPalette
+          opened={pluginPaletteOpen}
+          onClose={() => setPluginPaletteOpen(false)}
+          repoRef={(selectedRun?.repo_ref ?? repoRef ?? '').trim() || null}
+        />
+        <UpdateNotice opened={updatesOpen} onOpen={() => setUpdatesOpen(true)} onClose={() => setUpdatesOpen(false)} />
+
         <Modal
           opened={gitPatchPayloadOpen}
           onClose={() => setGitPatchPayloadOpen(false)}
@@ -5166,6 +6207,7 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
                 <Button size="xs" variant="default" onClick={() => setGlobalApplyChangesetPanelMode(globalApplyChangesetPanelMode === 'input' ? 'output' : 'input')} disabled={!globalApplyChangesetResult && globalApplyChangesetPanelMode === 'input'}>
                   {globalApplyChangesetPanelMode === 'input' ? 'Show output' : 'Show input'}
                 </Button>
+                <Button size="xs" variant="light" onClick={() => void pasteGlobalChangesetFromClipboard()}>Paste from clipboard</Button>
                 <Button size="xs" variant="default" onClick={newGlobalChangeset}>Clear / new</Button>
                 <Button size="xs" variant="light" onClick={() => void copyTextToClipboard(visibleGlobalChangesetPanelText(), 'Visible text')} disabled={!visibleGlobalChangesetPanelText().trim()}>Copy visible</Button>
                 <Button size="xs" variant="light" onClick={() => void copyTextToClipboard(globalApplyChangesetText, 'Last changeset')} disabled={!globalApplyChangesetText.trim()}>Copy changeset</Button>
@@ -5217,12 +6259,46 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
                     </Box>
                   )}
 
+                  <ChangesetLintList report={globalChangesetLint} />
+
+                  {globalApplyChangesetResult?.git ? (
+                    <Alert
+                      color={globalApplyChangesetResult.git.error || globalApplyChangesetResult.git.commit?.ok === false ? 'red' : globalApplyChangesetResult.git.skipped ? 'yellow' : 'green'}
+                      variant="light"
+                      style={{ flex: '0 0 auto' }}
+                    >
+                      <Text size="xs">
+                        {globalApplyChangesetResult.git.skipped
+                          || globalApplyChangesetResult.git.error
+                          || (globalApplyChangesetResult.git.commit
+                            ? globalApplyChangesetResult.git.commit.ok
+                              ? `Committed ${(globalApplyChangesetResult.git.commit.sha ?? '').slice(0, 10)}: ${globalApplyChangesetResult.git.commit.message}`
+                              : `Commit failed: ${globalApplyChangesetResult.git.commit.output}`
+                            : `Staged ${globalApplyChangesetResult.git.staged?.length ?? 0} paths.`)}
+                      </Text>
+                    </Alert>
+                  ) : null}
+
                   <Group justify="flex-end" style={{ flex: '0 0 auto' }}>
+                    <Checkbox
+                      size="xs"
+                      label="Stage changed paths"
+                      checked={globalChangesetStageAfterApply || globalChangesetCommitAfterApply}
+                      disabled={globalChangesetCommitAfterApply}
+                      onChange={(e) => setGlobalChangesetStageAfterApply(e.currentTarget.checked)}
+                    />
+                    <Checkbox
+                      size="xs"
+                      label="Commit with description"
+                      checked={globalChangesetCommitAfterApply}
+                      onChange={(e) => setGlobalChangesetCommitAfterApply(e.currentTarget.checked)}
+                    />
                     <Button size="xs" variant="default" onClick={() => setGlobalApplyChangesetOpen(false)}>Close</Button>
                     <Button size="xs" variant="default" onClick={() => setGlobalApplyChangesetPanelMode(globalApplyChangesetPanelMode === 'input' ? 'output' : 'input')} disabled={!globalApplyChangesetResult && globalApplyChangesetPanelMode === 'input'}>
                       {globalApplyChangesetPanelMode === 'input' ? 'Output' : 'Input'}
                     </Button>
                     <Button size="xs" variant="light" onClick={() => void handleSaveGlobalApplyChangeset()} loading={busy} disabled={globalApplyChangesetPanelMode !== 'input'}>Save draft</Button>
+                    <Button size="xs" variant="light" onClick={() => void handleLintGlobalChangeset()} loading={globalChangesetLintBusy} disabled={!globalApplyChangesetText.trim() || globalApplyChangesetPanelMode !== 'input'}>Lint</Button>
                     <Button size="xs" onClick={() => void handleApplyGlobalChangeset()} loading={manualCapabilityBusy} disabled={!globalApplyChangesetText.trim() || globalApplyChangesetPanelMode !== 'input'}>Apply changeset</Button>
                   </Group>
                 </Stack>
@@ -5321,6 +6397,31 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
         </Modal>
 
 
+        <AutoRunGuardsModal
+          opened={autoRunGuardsOpen}
+          onClose={() => setAutoRunGuardsOpen(false)}
+          run={selectedRun}
+          onSaved={() => (selectedRunId ? refreshRunDetails(selectedRunId) : undefined)}
+        />
+        <InferenceRequestViewer
+          opened={requestViewerOpen}
+          onClose={() => setRequestViewerOpen(false)}
+          request={lastInferenceRequest?.request ?? null}
+          prompt={lastInferenceRequest?.prompt ?? ''}
+        />
+        <Modal opened={transcriptOpen} onClose={() => setTranscriptOpen(false)} title="Transcript" size="min(1200px, 96vw)" centered>
+          <TranscriptPanel messages={transcriptMessages} />
+        </Modal>
+        <ConversationImportModal
+          opened={conversationImportOpen}
+          onClose={() => setConversationImportOpen(false)}
+          runId={selectedRunId}
+          onImported={() => {
+            if (selectedRunId) void refreshRunDetails(selectedRunId);
+            setTranscriptOpen(true);
+          }}
+        />
+
         <Modal opened={responseViewerOpen} onClose={() => setResponseViewerOpen(false)} title={previewViewerMode === 'stream' ? 'Stage stream' : previewViewerMode === 'prompt' ? 'Composed prompt preview' : 'Inference response'} size="min(1200px, 96vw)" centered>
           <Stack gap="md">
             <Group justify="space-between" align="center">
@@ -5359,6 +6460,7 @@ function renderPreviewPanel(title: string, content: string, emptyText: string, m
 
         <Modal opened={loadTemplateOpen} onClose={() => setLoadTemplateOpen(false)} title="Load template" size="lg" centered zIndex={300}>
           <Stack>
+            <TemplateIssuesAlert opened={loadTemplateOpen} />
             {templates.length === 0 ? (
               <Text c="dimmed" size="sm">No saved templates yet.</Text>
             ) : (
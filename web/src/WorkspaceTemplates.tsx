@@ -0,0 +1,434 @@
+import { useEffect, useRef, useState, type CSSProperties, type KeyboardEvent as ReactKeyboardEvent, type PointerEvent as ReactPointerEvent, type ReactNode } from 'react';
+import { ActionIcon, Badge, Box, Button, Card, Group, Menu, Modal, SimpleGrid, Stack, Switch, Text, Title, Tooltip } from '@mantine/core';
+import { IconExternalLink, IconLayoutColumns, IconMaximize, IconMinimize, IconMinus } from '@tabler/icons-react';
+import type { WorkspaceArrangement } from './api';
+import { focusRegionProps } from './focusNavigation';
+
+export type WorkspacePane = 'workflows' | 'diff' | 'commits' | 'files' | 'capabilities' | 'analysis';
+
+export type WorkspaceTemplate = {
+  id: string;
+  label: string;
+  description: string;
+  primary: WorkspacePane;
+  secondary: WorkspacePane | null;
+  primarySpan: number;
+  minSplitWidth: number;
+};
+
+export const WORKSPACE_TEMPLATES: WorkspaceTemplate[] = [
+  {
+    id: 'review',
+    label: 'Review',
+    description: 'Diff viewer and source control next to the repository tree and file viewer.',
+    primary: 'diff',
+    secondary: 'files',
+    primarySpan: 7,
+    minSplitWidth: 1400,
+  },
+  {
+    id: 'agent_run',
+    label: 'Agent run',
+    description: 'Workflow monitor with live changes alongside it.',
+    primary: 'workflows',
+    secondary: 'diff',
+    primarySpan: 6,
+    minSplitWidth: 1500,
+  },
+  {
+    id: 'exploration',
+    label: 'Exploration',
+    description: 'Repository tree and file viewer next to the analysis view.',
+    primary: 'files',
+    secondary: 'analysis',
+    primarySpan: 7,
+    minSplitWidth: 1400,
+  },
+];
+
+export const PANE_LABELS: Record<WorkspacePane, string> = {
+  workflows: 'Workflow',
+  diff: 'Changes',
+  commits: 'Commits',
+  files: 'Repository',
+  capabilities: 'Capabilities',
+  analysis: 'Analysis',
+};
+
+export function findWorkspaceTemplate(id: string | null | undefined) {
+  return WORKSPACE_TEMPLATES.find((template) => template.id === id) ?? null;
+}
+
+/** Drops the secondary pane when the viewport is too narrow to split. */
+export function secondaryPaneForViewport(template: WorkspaceTemplate | null, viewportWidth: number) {
+  if (!template?.secondary || viewportWidth < template.minSplitWidth) return null;
+  return template.secondary;
+}
+
+const MIN_PANE_PERCENT = 20;
+const GUIDE_SNAP_DISTANCE = 2;
+
+/**
+ * Resolves a dragged split position: clamps to the pane minimum, snaps to the nearest guide
+ * (edges, the template default, the middle) when close, otherwise rounds to the snap grid.
+ */
+export function snapSplitPercent(raw: number, gridPercent: number, guides: number[]) {
+  const clamped = Math.min(100 - MIN_PANE_PERCENT, Math.max(MIN_PANE_PERCENT, raw));
+  const candidates = [MIN_PANE_PERCENT, 100 - MIN_PANE_PERCENT, ...guides];
+  const guide = candidates.find((candidate) => Math.abs(candidate - clamped) <= GUIDE_SNAP_DISTANCE);
+  if (guide !== undefined) return { percent: guide, guide };
+  const percent = gridPercent > 0 ? Math.round(clamped / gridPercent) * gridPercent : clamped;
+  return { percent: Math.min(100 - MIN_PANE_PERCENT, Math.max(MIN_PANE_PERCENT, percent)), guide: null };
+}
+
+/** Panes that only need a repository and can render on their own in a separate window. */
+export const POPOUT_PANES: WorkspacePane[] = ['diff', 'commits', 'files', 'analysis'];
+
+export function popOutPane(pane: WorkspacePane, repoRef: string) {
+  const params = new URLSearchParams({ popout: pane, repo: repoRef });
+  window.open(`${window.location.pathname}?${params}`, `mdev-${pane}`, 'popup,width=1400,height=900');
+}
+
+type PaneSlot = 'primary' | 'secondary';
+
+/** Something that happened in a pane, shown as a badge on its taskbar chip while minimized. */
+export type PaneActivity = {
+  id: string;
+  tone: 'message' | 'apply' | 'failure';
+  label: string;
+  at: number;
+};
+
+const ACTIVITY_BADGES: Record<PaneActivity['tone'], { color: string; noun: string }> = {
+  message: { color: 'blue', noun: 'new' },
+  apply: { color: 'green', noun: 'applied' },
+  failure: { color: 'red', noun: 'failed' },
+};
+
+type PaneFrameProps = {
+  slot: PaneSlot;
+  pane: WorkspacePane | null;
+  maximized: boolean;
+  onToggleMaximize: () => void;
+  onMinimize?: () => void;
+  onPopOut?: (pane: WorkspacePane) => void;
+  style?: CSSProperties;
+  children: ReactNode;
+};
+
+/** Wraps a pane with maximize / pop-out controls. Maximizing only restyles the frame, so the
+ * pane keeps its state and the split it came from. */
+function PaneFrame(props: PaneFrameProps) {
+  const { slot, pane, maximized, onToggleMaximize, onMinimize, onPopOut, style, children } = props;
+  const canPopOut = Boolean(onPopOut && pane && POPOUT_PANES.includes(pane));
+  const frameStyle: CSSProperties = maximized
+    ? { position: 'fixed', inset: 0, zIndex: 150, overflow: 'auto', padding: 16, background: 'var(--mantine-color-body)' }
+    : style ?? {};
+
+  return (
+    <div style={frameStyle} {...focusRegionProps(slot)}>
+      <Group gap={4} justify="flex-end" mb={4}>
+        {canPopOut && pane ? (
+          <Tooltip label="Open in a new window">
+            <ActionIcon size="sm" variant="default" aria-label="Pop out" onClick={() => onPopOut?.(pane)}>
+              <IconExternalLink size={14} />
+            </ActionIcon>
+          </Tooltip>
+        ) : null}
+        {onMinimize && !maximized ? (
+          <Tooltip label="Minimize to taskbar">
+            <ActionIcon size="sm" variant="default" aria-label="Minimize" onClick={onMinimize}>
+              <IconMinus size={14} />
+            </ActionIcon>
+          </Tooltip>
+        ) : null}
+        <Tooltip label={maximized ? 'Restore (Esc)' : 'Maximize'}>
+          <ActionIcon size="sm" variant="default" aria-label={maximized ? 'Restore' : 'Maximize'} onClick={onToggleMaximize}>
+            {maximized ? <IconMinimize size={14} /> : <IconMaximize size={14} />}
+          </ActionIcon>
+        </Tooltip>
+      </Group>
+      {children}
+    </div>
+  );
+}
+
+type WorkspaceSplitProps = {
+  primaryPane: WorkspacePane | null;
+  secondaryPane: WorkspacePane | null;
+  onPopOut?: (pane: WorkspacePane) => void;
+  activity?: Partial<Record<WorkspacePane, PaneActivity[]>>;
+  secondary: ReactNode | null;
+  primarySpan: number;
+  /** Overrides the template's `primarySpan`, as a percentage of the workspace width. */
+  splitPercent: number | null;
+  snapPercent: number;
+  arrangement: WorkspaceArrangement;
+  onSplitChange: (percent: number | null) => void;
+  children: ReactNode;
+};
+
+export function WorkspaceSplit(props: WorkspaceSplitProps) {
+  const { primaryPane, secondaryPane, onPopOut, activity, secondary, primarySpan, splitPercent, snapPercent, arrangement, onSplitChange, children } = props;
+  const containerRef = useRef<HTMLDivElement>(null);
+  const [dragPercent, setDragPercent] = useState<number | null>(null);
+  const [guide, setGuide] = useState<number | null>(null);
+  const [maximized, setMaximized] = useState<PaneSlot | null>(null);
+  const [minimized, setMinimized] = useState<{ slot: PaneSlot; at: number } | null>(null);
+
+  useEffect(() => {
+    if (!maximized) return;
+    const handler = (event: KeyboardEvent) => {
+      if (event.key === 'Escape' && !event.defaultPrevented) setMaximized(null);
+    };
+    window.addEventListener('keydown', handler);
+    return () => window.removeEventListener('keydown', handler);
+  }, [maximized]);
+
+  const activeMaximized = maximized === 'secondary' && !secondary ? null : maximized;
+  // Minimized panes stay mounted but hidden, so running work keeps its state.
+  const activeMinimized = secondary ? minimized : null;
+  const frame = (slot: PaneSlot, content: ReactNode, style?: CSSProperties) => (
+    <PaneFrame
+      slot={slot}
+      pane={slot === 'primary' ? primaryPane : secondaryPane}
+      maximized={activeMaximized === slot}
+      onToggleMaximize={() => setMaximized((prev) => (prev === slot ? null : slot))}
+      onMinimize={secondary ? () => setMinimized({ slot, at: Date.now() }) : undefined}
+      onPopOut={onPopOut}
+      style={activeMinimized?.slot === slot ? { display: 'none' } : style}
+    >
+      {content}
+    </PaneFrame>
+  );
+
+  const minimizedPane = activeMinimized ? (activeMinimized.slot === 'primary' ? primaryPane : secondaryPane) : null;
+  const minimizedActivity = activeMinimized && minimizedPane
+    ? (activity?.[minimizedPane] ?? []).filter((item) => item.at >= activeMinimized.at)
+    : [];
+  const taskbar = activeMinimized ? (
+    <Group
+      gap="xs"
+      px="md"
+      py={6}
+      style={{
+        position: 'fixed',
+        left: 0,
+        right: 0,
+        bottom: 0,
+        zIndex: 140,
+        background: 'var(--mantine-color-body)',
+        borderTop: '1px solid var(--mantine-color-default-border)',
+      }}
+    >
+      <Tooltip label={minimizedActivity.length ? minimizedActivity[minimizedActivity.length - 1].label : 'Restore'}>
+        <Button size="compact-sm" variant="light" onClick={() => setMinimized(null)}>
+          <Group gap={6} wrap="nowrap">
+            <Text size="sm">{minimizedPane ? PANE_LABELS[minimizedPane] : 'Pane'}</Text>
+            {(Object.keys(ACTIVITY_BADGES) as PaneActivity['tone'][]).map((tone) => {
+              const count = minimizedActivity.filter((item) => item.tone === tone).length;
+              if (!count) return null;
+              return (
+                <Badge key={tone} size="xs" variant="filled" color={ACTIVITY_BADGES[tone].color}>
+                  {count} {ACTIVITY_BADGES[tone].noun}
+                </Badge>
+              );
+            })}
+          </Group>
+        </Button>
+      </Tooltip>
+    </Group>
+  ) : null;
+
+  if (!secondary) return frame('primary', children);
+
+  if (arrangement === 'rows') {
+    return (
+      <>
+        <Stack>
+          {frame('primary', children)}
+          {frame('secondary', secondary)}
+        </Stack>
+        {taskbar}
+      </>
+    );
+  }
+
+  const defaultPercent = (primarySpan / 12) * 100;
+  const percent = dragPercent ?? splitPercent ?? defaultPercent;
+  const guides = [defaultPercent, 50];
+
+  function startDrag(event: ReactPointerEvent<HTMLDivElement>) {
+    const rect = containerRef.current?.getBoundingClientRect();
+    if (!rect || event.button !== 0) return;
+    event.preventDefault();
+    let latest = percent;
+    const move = (moveEvent: PointerEvent) => {
+      const snapped = snapSplitPercent(((moveEvent.clientX - rect.left) / rect.width) * 100, snapPercent, guides);
+      latest = snapped.percent;
+      setDragPercent(snapped.percent);
+      setGuide(snapped.guide);
+    };
+    const up = () => {
+      window.removeEventListener('pointermove', move);
+      setDragPercent(null);
+      setGuide(null);
+      onSplitChange(latest);
+    };
+    window.addEventListener('pointermove', move);
+    window.addEventListener('pointerup', up, { once: true });
+  }
+
+  function nudge(event: ReactKeyboardEvent<HTMLDivElement>) {
+    if (event.key !== 'ArrowLeft' && event.key !== 'ArrowRight') return;
+    event.preventDefault();
+    const step = (snapPercent || 1) * (event.key === 'ArrowLeft' ? -1 : 1);
+    onSplitChange(snapSplitPercent(percent + step, snapPercent, []).percent);
+  }
+
+  return (
+    <>
+      <div ref={containerRef} style={{ display: 'flex', alignItems: 'flex-start', position: 'relative' }}>
+        {frame('primary', children, activeMinimized ? { flex: 1, minWidth: 0 } : { flex: `0 0 calc(${percent}% - 6px)`, minWidth: 0 })}
+        <div
+          role="separator"
+          aria-orientation="vertical"
+          aria-valuenow={Math.round(percent)}
+          tabIndex={0}
+          title="Drag to resize, double-click to reset"
+          onPointerDown={startDrag}
+          onDoubleClick={() => onSplitChange(null)}
+          onKeyDown={nudge}
+          style={{ flex: '0 0 12px', alignSelf: 'stretch', cursor: 'col-resize', display: activeMinimized ? 'none' : 'flex', justifyContent: 'center' }}
+        >
+          <div style={{ width: 2, background: dragPercent === null ? 'var(--mantine-color-gray-3)' : 'var(--mantine-color-blue-5)' }} />
+        </div>
+        {frame('secondary', secondary, { flex: 1, minWidth: 0 })}
+        {guide !== null ? (
+          <div
+            style={{
+              position: 'absolute',
+              top: 0,
+              bottom: 0,
+              left: `${guide}%`,
+              borderLeft: '1px dashed var(--mantine-color-blue-4)',
+              pointerEvents: 'none',
+            }}
+          />
+        ) : null}
+      </div>
+      {taskbar}
+    </>
+  );
+}
+
+const SNAP_GRID_OPTIONS = [0, 5, 10];
+
+type WorkspaceTemplateMenuProps = {
+  activeTemplateId: string | null;
+  onSelect: (template: WorkspaceTemplate | null) => void;
+  arrangement: WorkspaceArrangement;
+  snapPercent: number;
+  hasCustomSplit: boolean;
+  onArrange: (arrangement: WorkspaceArrangement) => void;
+  onSnapChange: (percent: number) => void;
+  onResetSplit: () => void;
+  autoAssociate: boolean;
+  onAutoAssociateChange: (enabled: boolean) => void;
+};
+
+export function WorkspaceTemplateMenu(props: WorkspaceTemplateMenuProps) {
+  const { activeTemplateId, onSelect, arrangement, snapPercent, hasCustomSplit, onArrange, onSnapChange, onResetSplit, autoAssociate, onAutoAssociateChange } = props;
+  const active = findWorkspaceTemplate(activeTemplateId);
+
+  return (
+    <Menu shadow="md" width={300} position="bottom-end">
+      <Menu.Target>
+        <Button size="xs" variant="default" leftSection={<IconLayoutColumns size={16} />}>
+          {active ? `Layout: ${active.label}` : 'Layout'}
+        </Button>
+      </Menu.Target>
+      <Menu.Dropdown>
+        <Menu.Label>Workspace templates</Menu.Label>
+        {WORKSPACE_TEMPLATES.map((template) => (
+          <Menu.Item key={template.id} onClick={() => onSelect(template)}>
+            <Text size="sm" fw={template.id === activeTemplateId ? 700 : 500}>{template.label}</Text>
+            <Text size="xs" c="dimmed">{template.description}</Text>
+          </Menu.Item>
+        ))}
+        <Menu.Divider />
+        <Menu.Item onClick={() => onSelect(null)}>Single pane</Menu.Item>
+        <Menu.Divider />
+        <Menu.Label>Arrange panes</Menu.Label>
+        <Menu.Item disabled={!active?.secondary} onClick={() => onArrange('columns')}>
+          <Text size="sm" fw={arrangement === 'columns' ? 700 : 500}>Side by side</Text>
+        </Menu.Item>
+        <Menu.Item disabled={!active?.secondary} onClick={() => onArrange('rows')}>
+          <Text size="sm" fw={arrangement === 'rows' ? 700 : 500}>Stacked</Text>
+        </Menu.Item>
+        <Menu.Item disabled={!hasCustomSplit} onClick={onResetSplit}>Reset split to template</Menu.Item>
+        <Menu.Label>Snap grid</Menu.Label>
+        <Group gap={4} px="sm" pb="xs">
+          {SNAP_GRID_OPTIONS.map((option) => (
+            <Button
+              key={option}
+              size="compact-xs"
+              variant={option === snapPercent ? 'filled' : 'default'}
+              onClick={() => onSnapChange(option)}
+            >
+              {option ? `${option}%` : 'Off'}
+            </Button>
+          ))}
+        </Group>
+        <Menu.Divider />
+        <Box px="sm" py="xs">
+          <Switch
+            size="xs"
+            label="Remember layout per repository"
+            checked={autoAssociate}
+            onChange={(event) => onAutoAssociateChange(event.currentTarget.checked)}
+          />
+        </Box>
+      </Menu.Dropdown>
+    </Menu>
+  );
+}
+
+type WorkspaceWelcomeModalProps = {
+  opened: boolean;
+  onSelect: (template: WorkspaceTemplate | null) => void;
+};
+
+export function WorkspaceWelcomeModal(props: WorkspaceWelcomeModalProps) {
+  const { opened, onSelect } = props;
+
+  return (
+    <Modal opened={opened} onClose={() => onSelect(null)} title="Welcome" size="lg" centered>
+      <Stack>
+        <Text size="sm" c="dimmed">
+          Pick a starting layout. You can switch templates later from the Layout menu above the workspace tabs.
+        </Text>
+        <SimpleGrid cols={{ base: 1, sm: 3 }}>
+          {WORKSPACE_TEMPLATES.map((template) => (
+            <Card key={template.id} withBorder padding="sm">
+              <Stack gap="xs" h="100%" justify="space-between">
+                <Stack gap={4}>
+                  <Title order={5}>{template.label}</Title>
+                  <Text size="xs" c="dimmed">{template.description}</Text>
+                  <Group gap={4}>
+                    <Badge size="xs" variant="light">{PANE_LABELS[template.primary]}</Badge>
+                    {template.secondary ? <Badge size="xs" variant="light" color="gray">{PANE_LABELS[template.secondary]}</Badge> : null}
+                  </Group>
+                </Stack>
+                <Button size="xs" onClick={() => onSelect(template)}>Use {template.label}</Button>
+              </Stack>
+            </Card>
+          ))}
+        </SimpleGrid>
+        <Group justify="flex-end">
+          <Button variant="default" onClick={() => onSelect(null)}>Skip</Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
@@ -0,0 +1,99 @@
+import type { KeyboardEvent } from 'react';
+import type { AppSettings } from './api';
+
+export type AccessibilityPreferences = AppSettings['accessibility'];
+
+/** Makes a clickable non-button element focusable and operable with Enter / Space. */
+export function pressableProps(onActivate: () => void, label?: string) {
+  return {
+    role: 'button',
+    tabIndex: 0,
+    'aria-label': label,
+    onClick: onActivate,
+    onKeyDown: (event: KeyboardEvent) => {
+      if (event.target !== event.currentTarget) return;
+      if (event.key === 'Enter' || event.key === ' ') {
+        event.preventDefault();
+        onActivate();
+      }
+    },
+  };
+}
+
+/**
+ * ARIA tree item wiring for repo tree rows: Enter / Space activates, Right expands and Left
+ * collapses folders. Up / Down move between visible rows of the same tree.
+ */
+export function treeItemProps(options: {
+  level: number;
+  label: string;
+  selected?: boolean;
+  expanded?: boolean;
+  onActivate: (event: KeyboardEvent<HTMLElement>) => void;
+  onToggle?: () => void;
+}) {
+  const { level, label, selected, expanded, onActivate, onToggle } = options;
+  return {
+    role: 'treeitem',
+    tabIndex: 0,
+    'aria-level': level,
+    'aria-label': label,
+    'aria-selected': selected ?? false,
+    'aria-expanded': onToggle ? !!expanded : undefined,
+    onKeyDown: (event: KeyboardEvent<HTMLElement>) => {
+      if (event.target !== event.currentTarget) return;
+      if (event.key === 'Enter' || event.key === ' ') {
+        event.preventDefault();
+        onActivate(event);
+      } else if (onToggle && ((event.key === 'ArrowRight' && !expanded) || (event.key === 'ArrowLeft' && expanded))) {
+        event.preventDefault();
+        onToggle();
+      } else if (event.key === 'ArrowDown' || event.key === 'ArrowUp') {
+        const tree = event.currentTarget.closest('[role="tree"]');
+        const items = tree ? Array.from(tree.querySelectorAll<HTMLElement>('[role="treeitem"]')) : [];
+        const next = items[items.indexOf(event.currentTarget) + (event.key === 'ArrowDown' ? 1 : -1)];
+        if (next) {
+          event.preventDefault();
+          next.focus();
+        }
+      }
+    },
+  };
+}
+
+/** Screen-reader summary of a unified patch: one line per file and hunk. */
+export function describePatch(patch: string) {
+  const lines: string[] = [];
+  let file = '';
+  let added = 0;
+  let removed = 0;
+  let hunk = '';
+  const flush = () => {
+    if (hunk) lines.push(`${file}, ${hunk}: ${added} added, ${removed} removed`);
+    added = 0;
+    removed = 0;
+  };
+  for (const line of patch.split('\n')) {
+    if (line.startsWith('+++ ')) {
+      file = line.slice(4).replace(/^b\//, '');
+    } else if (line.startsWith('@@')) {
+      flush();
+      const match = /\+(\d+)/.exec(line);
+      hunk = match ? `change at line ${match[1]}` : 'change';
+    } else if (line.startsWith('+') && !line.startsWith('+++')) {
+      added += 1;
+    } else if (line.startsWith('-') && !line.startsWith('---')) {
+      removed += 1;
+    }
+  }
+  flush();
+  return lines;
+}
+
+/** Applies the high-contrast preset and minimum font size to the document root. */
+export function applyAccessibilityPreferences(preferences: AccessibilityPreferences) {
+  const root = document.documentElement;
+  if (preferences.high_contrast) root.dataset.highContrast = 'true';
+  else delete root.dataset.highContrast;
+  root.style.setProperty('--mdev-min-font-size', `${preferences.min_font_size}px`);
+}
@@ -324,6 +324,12 @@ export type RepoTreeEntry = {
   path: string;
   kind: 'file' | 'dir';
   has_children: boolean;
+  /** Symlinks and submodule gitlinks; absent for ordinary entries. */
+  special?: 'symlink' | 'submodule';
+  link_target?: string;
+  /** The link dangles or points outside the repo and is never followed. */
+  link_outside?: boolean;
+  executable?: boolean;
 };
 
 export type RepoTreeResponse = {
@@ -347,16 +353,76 @@ export type RepoValidateResponse = {
   exists: boolean;
   is_dir: boolean;
   git_repo: boolean;
+  bare: boolean;
   message: string;
 };
 
+export type IndexLockInfo = {
+  repo_ref: string;
+  lock_path: string;
+  age_secs: number | null;
+  holders: { pid: number; name: string; cmdline: string }[];
+  stale: boolean;
+};
+
+/** Structured error body every failing API route responds with. */
+export type AppErrorInfo = {
+  kind: string;
+  title: string;
+  message: string;
+  detail: string;
+  hint: string | null;
+  component: string;
+};
+
+export class ApiError extends Error {
+  info: AppErrorInfo;
+
+  constructor(info: AppErrorInfo) {
+    super(info.message);
+    this.name = 'ApiError';
+    this.info = info;
+  }
+}
+
+// Components mostly keep only `err.message`; this lets the error panel find the details again.
+const recentApiErrors = new Map<string, AppErrorInfo>();
+const RECENT_API_ERROR_LIMIT = 50;
+
+function rememberApiError(info: AppErrorInfo) {
+  recentApiErrors.delete(info.message);
+  recentApiErrors.set(info.message, info);
+  if (recentApiErrors.size > RECENT_API_ERROR_LIMIT) {
+    recentApiErrors.delete(recentApiErrors.keys().next().value as string);
+  }
+}
+
+export function apiErrorInfoFor(message: string): AppErrorInfo | null {
+  return recentApiErrors.get(message) ?? null;
+}
+
+/** Dispatched on `window` when a git index operation gives up on a held `index.lock`. */
+export const INDEX_LOCKED_EVENT = 'mdev:index-locked';
+export type IndexLockedDetail = { lock: IndexLockInfo; retry: () => Promise<unknown> };
+
 async function fetchJson<T>(path: string, init?: RequestInit): Promise<T> {
   const res = await fetch(path, {
     headers: { 'Content-Type': 'application/json' },
     ...init
   });
   if (!res.ok) {
-    throw new Error(await res.text());
+    const text = await res.text();
+    const body = (() => { try { return JSON.parse(text); } catch { return null; } })();
+    if (res.status === 423 && body?.error === 'index_locked') {
+      const detail: IndexLockedDetail = { lock: body.lock, retry: () => fetchJson(path, init) };
+      window.dispatchEvent(new CustomEvent(INDEX_LOCKED_EVENT, { detail }));
+      throw new Error(body.message);
+    }
+    if (typeof body?.kind === 'string' && typeof body?.message === 'string') {
+      rememberApiError(body as AppErrorInfo);
+      throw new ApiError(body as AppErrorInfo);
+    }
+    throw new Error(text);
   }
   return res.json() as Promise<T>;
 }
@@ -365,6 +431,17 @@ export function listTemplates() {
   return fetchJson<WorkflowTemplate[]>('/api/workflow-templates');
 }
 
+export type TemplateLoadIssue = {
+  id: string;
+  name: string;
+  error: string;
+  raw: string;
+};
+
+export function listTemplateIssues() {
+  return fetchJson<TemplateLoadIssue[]>('/api/workflow-templates/issues');
+}
+
 export function getWorkflowBuilderContract() {
   return fetchJson<WorkflowBuilderContract>('/api/workflow-builder-contract');
 }
@@ -383,14 +460,15 @@ export function compileWorkflowBuilderDocument(document: WorkflowBuilderDocument
 export function listRepoTree(
   repoRef: string,
   gitRef = 'WORKTREE',
-  options?: { basePath?: string; skipBinary?: boolean; skipGitignore?: boolean }
+  options?: { basePath?: string; skipBinary?: boolean; skipGitignore?: boolean; followSymlinks?: boolean }
 ) {
   const params = new URLSearchParams({
     repo_ref: repoRef,
     git_ref: gitRef,
     base_path: options?.basePath ?? '',
     skip_binary: String(Boolean(options?.skipBinary)),
-    skip_gitignore: String(Boolean(options?.skipGitignore))
+    skip_gitignore: String(Boolean(options?.skipGitignore)),
+    follow_symlinks: String(Boolean(options?.followSymlinks))
   });
   return fetchJson<RepoTreeResponse>(`/api/repo-tree?${params.toString()}`);
 }
@@ -416,11 +494,80 @@ export function validateRepoRef(repoRef: string) {
   return fetchJson<RepoValidateResponse>(`/api/repo/validate?${params.toString()}`);
 }
 
+export type RecentRepo = {
+  repo_ref: string;
+  last_used_at: string;
+  workflow_count: number;
+  exists: boolean;
+};
+
+export function listRecentRepos() {
+  return fetchJson<RecentRepo[]>('/api/repo/recent');
+}
+
+export function cloneRepo(url: string, destination: string) {
+  return fetchJson<{ ok: boolean; repo_ref: string; output: string }>('/api/repo/clone', {
+    method: 'POST',
+    body: JSON.stringify({ url, destination })
+  });
+}
+
+export type RepoTreeFilter = {
+  extensions?: string[];
+  min_size?: number | null;
+  max_size?: number | null;
+  changed_in_worktree?: boolean;
+  changed_in_last_commits?: number | null;
+};
+
+export type RepoTreeFilterResponse = {
+  repo_ref: string;
+  matches: Array<{ path: string; size: number }>;
+  extensions: Array<{ extension: string; files: number; bytes: number }>;
+  scanned: number;
+  refreshed_at: string;
+};
+
+export function filterRepoTree(repoRef: string, filter: RepoTreeFilter) {
+  return fetchJson<RepoTreeFilterResponse>('/api/repo/tree/filter', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, ...filter })
+  });
+}
+
+export type TouchedPathsSource = 'changeset' | 'patch' | 'range';
+
+export type RepoTouchedPathsResponse = {
+  repo_ref: string;
+  git_ref: string;
+  paths: string[];
+  missing: string[];
+};
+
+export function getRepoTouchedPaths(repoRef: string, gitRef: string, source: TouchedPathsSource, text: string) {
+  return fetchJson<RepoTouchedPathsResponse>('/api/repo/tree/touched', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, git_ref: gitRef, source, text })
+  });
+}
+
 export type FileContentsResponse = {
   ok: boolean;
   repo_ref: string;
   path: string;
+  git_ref: string;
+  commit: string | null;
+  read_only: boolean;
   contents: string;
+  /** Line endings `.gitattributes` enforces on save, if any. */
+  eol_attribute: 'lf' | 'crlf' | null;
+  language_hint: LanguageHint | null;
+};
+
+export type LanguageHint = {
+  language: string;
+  line_comment: string | null;
+  block_comment: [string, string] | null;
 };
 
 export type MutatePathResponse = {
@@ -431,14 +578,28 @@ export type MutatePathResponse = {
   bytes: number;
 };
 
-export function readWorkspaceFile(repoRef: string, path: string) {
+export function readWorkspaceFile(repoRef: string, path: string, gitRef = 'WORKTREE') {
   const params = new URLSearchParams({
     repo_ref: repoRef,
     path,
+    git_ref: gitRef,
   });
   return fetchJson<FileContentsResponse>(`/api/file?${params.toString()}`);
 }
 
+/** Saved cursor, selections, scroll and folds for a file, in the editor's own view-state format. */
+export function getEditorState(repoRef: string, path: string) {
+  const params = new URLSearchParams({ repo_ref: repoRef, path });
+  return fetchJson<{ state: unknown | null }>(`/api/editor-state?${params.toString()}`);
+}
+
+export function saveEditorState(body: { repo_ref: string; path: string; state: unknown }) {
+  return fetchJson<{ state: unknown | null }>('/api/editor-state', {
+    method: 'PUT',
+    body: JSON.stringify(body),
+  });
+}
+
 export function writeWorkspaceFile(body: { repo_ref: string; path: string; contents: string }) {
   return fetchJson<MutatePathResponse>('/api/file', {
     method: 'PUT',
@@ -446,6 +607,38 @@ export function writeWorkspaceFile(body: { repo_ref: string; path: string; conte
   });
 }
 
+export type BatchFileResult = {
+  path: string;
+  ok: boolean;
+  bytes: number;
+  contents?: string;
+  error?: string;
+};
+
+export type BatchFilesResponse = {
+  ok: boolean;
+  repo_ref: string;
+  succeeded: number;
+  failed: number;
+  duration_ms: number;
+  results: BatchFileResult[];
+};
+
+/** Writes many files in one request; an invalid path rejects the whole batch before anything is written. */
+export function writeManyWorkspaceFiles(body: { repo_ref: string; files: Array<{ path: string; contents: string }> }) {
+  return fetchJson<BatchFilesResponse>('/api/files/write-many', {
+    method: 'POST',
+    body: JSON.stringify(body),
+  });
+}
+
+export function readManyWorkspaceFiles(body: { repo_ref: string; paths: string[]; git_ref?: string }) {
+  return fetchJson<BatchFilesResponse>('/api/files/read-many', {
+    method: 'POST',
+    body: JSON.stringify(body),
+  });
+}
+
 export function createWorkspaceFile(body: { repo_ref: string; path: string; contents?: string }) {
   return fetchJson<MutatePathResponse>('/api/file', {
     method: 'POST',
@@ -470,6 +663,69 @@ export function deleteWorkspacePath(repoRef: string, path: string) {
   });
 }
 
+export type HistoryEntry = {
+  id: string;
+  kind: string;
+  label: string;
+  repo_ref: string;
+  paths: string[];
+  created_at: string;
+  undone_at: string | null;
+};
+
+export type HistoryUndoResult = {
+  entry: HistoryEntry;
+  restored: string[];
+  removed: string[];
+  state: Record<string, unknown> | null;
+};
+
+export function listHistory() {
+  return fetchJson<HistoryEntry[]>('/api/history');
+}
+
+export function recordHistoryState(body: { kind: string; label: string; repo_ref?: string; state: Record<string, unknown> }) {
+  return fetchJson<{ ok: boolean; id: string }>('/api/history', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function undoHistory(id?: string) {
+  return fetchJson<HistoryUndoResult>('/api/history/undo', {
+    method: 'POST',
+    body: JSON.stringify({ id: id ?? null })
+  });
+}
+
+export type TrashEntry = {
+  id: string;
+  repo_ref: string;
+  kind: string;
+  label: string;
+  created_at: string;
+  files: Array<{ path: string; bytes: number }>;
+};
+
+export function listTrash(repoRef: string) {
+  const params = new URLSearchParams({ repo_ref: repoRef });
+  return fetchJson<{ repo_ref: string; retention_days: number; entries: TrashEntry[] }>(`/api/trash?${params.toString()}`);
+}
+
+export function restoreTrashEntry(body: { repo_ref: string; id: string; paths?: string[] | null; overwrite?: boolean }) {
+  return fetchJson<{ restored: string[]; skipped: string[] }>('/api/trash/restore', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function purgeTrash(body: { repo_ref: string; id?: string | null }) {
+  return fetchJson<{ ok: boolean; purged: number }>('/api/trash/purge', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
 export function createTemplate(body: { name: string; description: string; repo_ref: string; definition: WorkflowTemplateDefinition }) {
   return fetchJson<WorkflowTemplate>('/api/workflow-templates', {
     method: 'POST',
@@ -528,6 +784,11 @@ export function openEventStream(runId: string, afterSequence = 0): EventSource {
   return new EventSource(`/api/workflow-runs/${runId}/events/stream?after_sequence=${afterSequence}`);
 }
 
+/** Repositories handed over by a second launch (`workflow-api open <path>`); emits `open_repo` events. */
+export function openInstanceRequestStream(): EventSource {
+  return new EventSource('/api/instance/open-requests/stream');
+}
+
 export function sendRunAction(runId: string, body: { action: string; step_id?: string | null; payload?: Record<string, unknown> }) {
   return fetchJson<WorkflowRunActionResult>(`/api/workflow-runs/${runId}/actions`, {
     method: 'POST',
@@ -583,6 +844,34 @@ export function patchWorkflowGlobalState(runId: string, payload: Record<string,
   return sendRunAction(runId, { action: 'patch_global_state', payload });
 }
 
+export type RunNotes = {
+  markdown?: string;
+  updated_at?: string;
+};
+
+/** Saves run notes if nobody else changed them since `base_updated_at`; `force` overwrites. */
+export function saveRunNotes(runId: string, body: { markdown: string; base_updated_at: string; force?: boolean }) {
+  return sendRunAction(runId, { action: 'save_notes', payload: body }) as unknown as Promise<{ ok: boolean; conflict: boolean; notes: RunNotes }>;
+}
+
+export type ImportedConversation = {
+  source: 'chatgpt' | 'claude';
+  title: string;
+  messages: { role: 'user' | 'assistant'; text: string; created_at: string | null }[];
+};
+
+/** Parses a ChatGPT or Claude conversations.json export. */
+export function previewConversationImport(exportJson: unknown) {
+  return fetchJson<ImportedConversation[]>('/api/conversation-import/preview', {
+    method: 'POST',
+    body: JSON.stringify(exportJson)
+  });
+}
+
+export function importConversation(runId: string, conversation: ImportedConversation, seedPrompt: boolean) {
+  return sendRunAction(runId, { action: 'import_conversation', payload: { conversation, seed_prompt: seedPrompt } });
+}
+
 export function getPayloadGatewaySchema() {
   return fetchJson<{
     ok: boolean;
@@ -599,6 +888,85 @@ export function getChangesetSchema() {
   }>(`/api/capabilities/changeset-schema`);
 }
 
+export type ContextDriftEntry = {
+  path: string;
+  status: 'modified' | 'deleted';
+};
+
+export type ContextDriftReport = {
+  repo_ref: string;
+  export_ref: string;
+  current_ref: string;
+  files_checked: number;
+  unchanged: number;
+  changed: ContextDriftEntry[];
+  stale: boolean;
+};
+
+export function checkContextDrift(body: { path?: string; text?: string; repo_ref?: string; git_ref?: string }) {
+  return fetchJson<ContextDriftReport>('/api/capabilities/context-export/drift', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type ExcludePatternReport = {
+  pattern: string;
+  enabled: boolean;
+  error: string | null;
+  error_offset: number | null;
+  excluded: number;
+};
+
+export type ExcludePreview = {
+  candidate_files: number;
+  excluded_files: number;
+  patterns: ExcludePatternReport[];
+};
+
+export function previewContextExcludes(body: { repo_ref: string; git_ref?: string; patterns: string[] }) {
+  return fetchJson<ExcludePreview>('/api/capabilities/context-export/exclude-preview', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type ContextExportProgress = {
+  id: string;
+  status: 'running' | 'done' | 'cancelled' | 'failed';
+  files_done: number;
+  files_total: number;
+  bytes_written: number;
+  output_path: string | null;
+  error: string | null;
+};
+
+export function startContextExport(payload: Record<string, unknown>) {
+  return fetchJson<ContextExportProgress>('/api/capabilities/context-export/jobs', {
+    method: 'POST',
+    body: JSON.stringify(payload)
+  });
+}
+
+export function getContextExportProgress(id: string) {
+  return fetchJson<ContextExportProgress>(`/api/capabilities/context-export/jobs/${id}`);
+}
+
+export function cancelContextExport(id: string) {
+  return fetchJson<ContextExportProgress>(`/api/capabilities/context-export/jobs/${id}`, { method: 'DELETE' });
+}
+
+export type ExportDestination = {
+  exporter: string;
+  save_path: string;
+  if_exists: 'overwrite' | 'new_version';
+  updated_at: string;
+};
+
+export function listExportDestinations() {
+  return fetchJson<ExportDestination[]>('/api/capabilities/context-export/destinations');
+}
+
 export function listWorkflowCapabilities(runId: string) {
   return fetchJson<Record<string, unknown>>(`/api/workflow-runs/${runId}/capabilities`);
 }
@@ -683,6 +1051,16 @@ export type ApplyChangesetResponse = Record<string, unknown> & {
   normalized_payload?: string;
   changeset_attempt_id?: string;
   stats?: Record<string, unknown>;
+  lint?: ChangesetLintReport;
+  git?: ChangesetGitResult;
+};
+
+export type ChangesetGitResult = {
+  staged?: string[];
+  stage_output?: string;
+  skipped?: string;
+  error?: string;
+  commit?: { ok: boolean; sha?: string | null; message: string; output: string };
 };
 
 export function listWorkflowChangesets(workflowKey: string, limit = 50) {
@@ -694,13 +1072,53 @@ export function getWorkflowChangeset(workflowKey: string, attemptId: string) {
   return fetchJson<ChangesetAttemptDetail>(`/api/workflows/${encodeURIComponent(workflowKey)}/changesets/${encodeURIComponent(attemptId)}`);
 }
 
-export function applyWorkflowChangeset(workflowKey: string, body: { git_ref?: string; payload_text: string }) {
+export function applyWorkflowChangeset(
+  workflowKey: string,
+  body: { git_ref?: string; payload_text: string; stage_after_apply?: boolean; commit_after_apply?: boolean; commit_message?: string }
+) {
   return fetchJson<ApplyChangesetResponse>(`/api/workflows/${encodeURIComponent(workflowKey)}/changesets/apply`, {
     method: 'POST',
     body: JSON.stringify(body)
   });
 }
 
+export type ChangesetLintFinding = {
+  severity: 'error' | 'warning';
+  code: string;
+  operation_index: number;
+  action_index: number | null;
+  path: string | null;
+  message: string;
+};
+
+export type ChangesetLintReport = {
+  ok: boolean;
+  error_count: number;
+  warning_count: number;
+  findings: ChangesetLintFinding[];
+};
+
+export function lintWorkflowChangeset(workflowKey: string, body: { payload_text: string }) {
+  return fetchJson<ChangesetLintReport>(`/api/workflows/${encodeURIComponent(workflowKey)}/changesets/lint`, {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type ChangesetIngestReport = {
+  ok: boolean;
+  cleaned_text: string;
+  stripped: string[];
+  error?: { message: string; line: number; column: number } | null;
+};
+
+export function ingestChangesetText(body: { payload_text: string }) {
+  return fetchJson<ChangesetIngestReport>('/api/changesets/ingest', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
 export type ReviewDiffScope = 'staged' | 'unstaged';
 
 export type GitPatchScope = 'staged' | 'unstaged' | 'both';
@@ -712,6 +1130,7 @@ export type ReviewStatusFileEntry = {
   index_status: string;
   worktree_status: string;
   untracked: boolean;
+  conflicted: boolean;
 };
 
 export type ReviewStatusResponse = {
@@ -749,12 +1168,16 @@ export function getReviewStatus(repoRef: string) {
   });
 }
 
+/** Local equivalents of git's --ignore-cr-at-eol, -b and -w. */
+export type DiffWhitespaceMode = 'none' | 'eol' | 'change' | 'all';
+
 export function getReviewDiff(body: {
   repo_ref: string;
   scope: ReviewDiffScope;
   path?: string | null;
   context_lines?: number;
   whole_file?: boolean;
+  whitespace?: DiffWhitespaceMode;
 }) {
   return fetchJson<ReviewDiffResponse>('/api/review/diff', {
     method: 'POST',
@@ -781,6 +1204,7 @@ export type ReviewDiffManifestFileEntry = {
   index_status: string;
   worktree_status: string;
   untracked: boolean;
+  whitespace_only?: 'eol' | 'whitespace' | null;
 };
 
 export type ReviewDiffManifestResponse = {
@@ -816,6 +1240,7 @@ export function getReviewFilePatch(body: {
   path: string;
   context_lines?: number;
   whole_file?: boolean;
+  whitespace?: DiffWhitespaceMode;
 }) {
   return fetchJson<ReviewFilePatchResponse>('/api/review/diff/file', {
     method: 'POST',
@@ -878,11 +1303,22 @@ export type ReviewCommitReportBucket = {
   groups: ReviewCommitReportGroupBucket[];
 };
 
+export type ReviewCommitReportContributor = {
+  key: string;
+  label: string;
+  commits: number;
+  additions: number;
+  deletions: number;
+  files_changed: number;
+};
+
 export type ReviewCommitReportResponse = {
   ok: boolean;
   commits: ReviewCommitSummary[];
   months: ReviewCommitReportMonthBucket[];
   buckets?: ReviewCommitReportBucket[];
+  authors?: ReviewCommitReportContributor[];
+  directories?: ReviewCommitReportContributor[];
   aggregation_window?: string;
   color_by?: string;
   exclude_regex: string[];
@@ -919,6 +1355,47 @@ export type ReviewCommitDiffResponse = {
   patch: string;
 };
 
+export type ReviewCompareResponse = {
+  ok: boolean;
+  base: string;
+  head: string;
+  merge_base?: string | null;
+  ahead: number;
+  behind: number;
+  ahead_commits: ReviewCommitSummary[];
+  behind_commits: ReviewCommitSummary[];
+  files: ReviewDiffManifestFileEntry[];
+};
+
+export type ReviewCherryPickResponse = {
+  ok: boolean;
+  commit: string;
+  head?: string | null;
+  message: string;
+};
+
+export type ReviewReflogEntry = {
+  selector: string;
+  sha: string;
+  short_sha: string;
+  action: string;
+  message: string;
+  subject: string;
+  recorded_at: string;
+  reachable: boolean;
+};
+
+export type ReviewReflogResponse = {
+  ok: boolean;
+  entries: ReviewReflogEntry[];
+};
+
+export type ReviewCreateBranchResponse = {
+  ok: boolean;
+  name: string;
+  sha: string;
+};
+
 export function getReviewCommits(body: {
   repo_ref: string;
   limit?: number;
@@ -978,6 +1455,7 @@ export function getReviewCommitDiff(body: {
   path?: string | null;
   context_lines?: number;
   whole_file?: boolean;
+  whitespace?: DiffWhitespaceMode;
 }) {
   return fetchJson<ReviewCommitDiffResponse>('/api/review/commit/diff', {
     method: 'POST',
@@ -985,43 +1463,377 @@ export function getReviewCommitDiff(body: {
   });
 }
 
-export function stageReviewDiff(body: {
-  repo_ref: string;
-  scope: ReviewDiffScope;
-  path?: string | null;
-}) {
-  return fetchJson<{ ok: boolean }>('/api/review/stage', {
+export function compareReviewRefs(body: { repo_ref: string; base: string; head: string }) {
+  return fetchJson<ReviewCompareResponse>('/api/review/compare', {
     method: 'POST',
     body: JSON.stringify(body)
   });
 }
 
-export function unstageReviewDiff(body: {
+export function getReviewCompareDiff(body: {
   repo_ref: string;
-  scope: ReviewDiffScope;
+  base: string;
+  head: string;
   path?: string | null;
+  context_lines?: number;
+  whole_file?: boolean;
+  whitespace?: DiffWhitespaceMode;
 }) {
-  return fetchJson<{ ok: boolean }>('/api/review/unstage', {
+  return fetchJson<ReviewCommitDiffResponse>('/api/review/compare/diff', {
     method: 'POST',
     body: JSON.stringify(body)
   });
 }
 
-export function getWorkflowReviewStatus(runId: string) {
-  return fetchJson<ReviewStatusResponse>(`/api/workflow-runs/${runId}/review/status`);
-}
+export type CodeReviewComment = {
+  id: string;
+  path: string;
+  line: number;
+  side: 'new' | 'old';
+  line_text: string;
+  body: string;
+  created_at: string;
+};
 
-export function getWorkflowReviewDiff(runId: string, body: {
-  scope: ReviewDiffScope;
-  path?: string | null;
-  context_lines?: number;
-  whole_file?: boolean;
+export type CodeReview = {
+  id: string;
+  repo_ref: string;
+  base: string;
+  head: string;
+  viewed: string[];
+  comments: CodeReviewComment[];
+  created_at: string;
+  updated_at: string;
+};
+
+export function listCodeReviews(repoRef: string) {
+  return fetchJson<CodeReview[]>(`/api/code-reviews?${new URLSearchParams({ repo_ref: repoRef }).toString()}`);
+}
+
+export function openCodeReview(body: { repo_ref: string; base: string; head: string }) {
+  return fetchJson<CodeReview>('/api/code-reviews', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function deleteCodeReview(id: string) {
+  return fetchJson<{ ok: boolean }>(`/api/code-reviews/${id}`, {
+    method: 'DELETE'
+  });
+}
+
+export function setCodeReviewFileViewed(id: string, path: string, viewed: boolean) {
+  return fetchJson<CodeReview>(`/api/code-reviews/${id}/viewed`, {
+    method: 'POST',
+    body: JSON.stringify({ path, viewed })
+  });
+}
+
+export function addCodeReviewComment(id: string, body: { path: string; line: number; side: 'new' | 'old'; line_text: string; body: string }) {
+  return fetchJson<CodeReview>(`/api/code-reviews/${id}/comments`, {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function deleteCodeReviewComment(id: string, commentId: string) {
+  return fetchJson<CodeReview>(`/api/code-reviews/${id}/comments/${commentId}`, {
+    method: 'DELETE'
+  });
+}
+
+export function exportCodeReview(id: string) {
+  return fetchJson<{ ok: boolean; markdown: string }>(`/api/code-reviews/${id}/export`);
+}
+
+/** Model settings for one-off AI actions; omitted fields use the inference defaults. */
+export type AiActionModel = {
+  model?: string;
+  generation?: InferenceGenerationSettings;
+};
+
+export type AiActionResult = {
+  text: string;
+  model: string;
+  truncated: boolean;
+  total_tokens?: number | null;
+};
+
+export function explainCommitWithAi(body: { repo_ref: string; commit: string } & AiActionModel) {
+  return fetchJson<AiActionResult>('/api/ai/explain-commit', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function summarizeDiffWithAi(body: { repo_ref: string; scope: ReviewDiffScope } & AiActionModel) {
+  return fetchJson<AiActionResult>('/api/ai/summarize-diff', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+/** Asks for a replacement of just `selection`; `before`/`after` are surrounding lines sent as context. */
+export function editSelectionWithAi(body: { path: string; selection: string; instruction: string; before: string; after: string } & AiActionModel) {
+  return fetchJson<AiActionResult>('/api/ai/edit-selection', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type DocCommentProposal = {
+  path: string;
+  payload_text: string;
+  patch: string;
+  documented: string[];
+  model: string;
+  total_tokens?: number | null;
+};
+
+export function proposeDocComments(body: { repo_ref: string; path: string; symbol?: string | null } & AiActionModel) {
+  return fetchJson<DocCommentProposal>('/api/ai/doc-comments', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function applyDocComments(body: { repo_ref: string; payload_text: string }) {
+  return fetchJson<ApplyChangesetResponse>('/api/ai/doc-comments/apply', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type ProjectSearchQuery = {
+  repo_ref: string;
+  pattern: string;
+  regex?: boolean;
+  case_sensitive?: boolean;
+  /** Limits the search to these files or directories, e.g. the current tree filter. */
+  paths?: string[];
+  max_results?: number;
+};
+
+export type ProjectSearchMatch = { path: string; line: number; column: number; text: string };
+
+export type ProjectSearchResults = {
+  matches: ProjectSearchMatch[];
+  files: number;
+  truncated: boolean;
+};
+
+export type ReplacePreview = {
+  path: string;
+  replacements: number;
+  patch: string;
+  fingerprint: string;
+};
+
+export function searchProject(query: ProjectSearchQuery) {
+  return fetchJson<ProjectSearchResults>('/api/search', {
+    method: 'POST',
+    body: JSON.stringify(query)
+  });
+}
+
+export function exportSearchResults(query: ProjectSearchQuery, format: 'md' | 'csv') {
+  return fetchJson<{ file_name: string; contents: string }>('/api/search/export', {
+    method: 'POST',
+    body: JSON.stringify({ ...query, format })
+  });
+}
+
+export function previewSearchReplace(query: ProjectSearchQuery, replacement: string) {
+  return fetchJson<ReplacePreview[]>('/api/search/replace/preview', {
+    method: 'POST',
+    body: JSON.stringify({ ...query, replacement })
+  });
+}
+
+/** `expected` maps each path to its preview fingerprint; files changed since the preview are refused. */
+export function applySearchReplace(query: ProjectSearchQuery, replacement: string, expected: Record<string, string>) {
+  return fetchJson<ApplyChangesetResponse>('/api/search/replace', {
+    method: 'POST',
+    body: JSON.stringify({ ...query, replacement, expected })
+  });
+}
+
+export type TestGenerationPlan = {
+  path: string;
+  symbol?: string | null;
+  language: string;
+  layout: 'inline_module' | 'tests_dir' | 'sibling_file';
+  test_path: string;
+  test_command: string;
+  reference_test?: string | null;
+  instructions: string;
+};
+
+export type GenerateTestsTask = {
+  title: string;
+  plan: TestGenerationPlan;
+  definition: WorkflowTemplateDefinition;
+  context: Record<string, unknown>;
+};
+
+export function generateTestsTask(body: { repo_ref: string; path: string; symbol?: string | null } & AiActionModel) {
+  return fetchJson<GenerateTestsTask>('/api/workflow-builder/task-templates/generate-tests', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type RepoOverview = {
+  markdown: string;
+  model: string;
+  total_tokens?: number | null;
+};
+
+export function generateRepoOverview(body: { repo_ref: string } & AiActionModel) {
+  return fetchJson<RepoOverview>('/api/ai/repo-overview', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function saveRepoOverview(body: { repo_ref: string; markdown: string; destination: 'repo' | 'app_data'; path?: string | null }) {
+  return fetchJson<{ ok: boolean; path: string }>('/api/ai/repo-overview/save', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function cherryPickReviewCommit(body: { repo_ref: string; commit: string }) {
+  return fetchJson<ReviewCherryPickResponse>('/api/review/cherry-pick', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function getReviewReflog(body: { repo_ref: string; limit?: number }) {
+  return fetchJson<ReviewReflogResponse>('/api/review/reflog', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function createReviewBranch(body: { repo_ref: string; name: string; start_point: string }) {
+  return fetchJson<ReviewCreateBranchResponse>('/api/review/branch', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function stageReviewDiff(body: {
+  repo_ref: string;
+  scope: ReviewDiffScope;
+  path?: string | null;
+}) {
+  return fetchJson<{ ok: boolean }>('/api/review/stage', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function stageReviewLines(body: {
+  repo_ref: string;
+  path: string;
+  start_line: number;
+  end_line: number;
+}) {
+  return fetchJson<{ ok: boolean; staged_lines: number }>('/api/review/stage-lines', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function unstageReviewDiff(body: {
+  repo_ref: string;
+  scope: ReviewDiffScope;
+  path?: string | null;
+}) {
+  return fetchJson<{ ok: boolean }>('/api/review/unstage', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function inspectIndexLock(body: { repo_ref: string }) {
+  return fetchJson<IndexLockInfo | null>('/api/review/index-lock', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function removeStaleIndexLock(body: { repo_ref: string }) {
+  return fetchJson<{ ok: boolean; removed: boolean }>('/api/review/index-lock/remove', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type ReviewDiscardResponse = {
+  plan: { restore: string[]; added: string[]; untracked: string[] };
+  total: number;
+  untracked_bytes: number;
+  history_id: string | null;
+};
+
+export function previewReviewDiscard(body: { repo_ref: string; paths?: string[] | null }) {
+  return fetchJson<ReviewDiscardResponse>('/api/review/discard/preview', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function discardReviewChanges(body: { repo_ref: string; paths?: string[] | null; expected_total: number }) {
+  return fetchJson<ReviewDiscardResponse>('/api/review/discard', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type GitRemoteAction = 'fetch' | 'pull' | 'push';
+
+export type GitRemoteResult = {
+  ok: boolean;
+  exit_code: number;
+  log: string;
+  auth_kind: 'https' | 'ssh' | 'none' | string;
+  needs_credentials: boolean;
+  message: string | null;
+};
+
+export function runReviewRemoteAction(action: GitRemoteAction, body: {
+  repo_ref: string;
+  remote?: string | null;
+  branch?: string | null;
+  credentials?: { username?: string; password?: string } | null;
+}) {
+  return fetchJson<GitRemoteResult>(`/api/review/remote/${action}`, {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function getWorkflowReviewStatus(runId: string) {
+  return fetchJson<ReviewStatusResponse>(`/api/workflow-runs/${runId}/review/status`);
+}
+
+export function getWorkflowReviewDiff(runId: string, body: {
+  scope: ReviewDiffScope;
+  path?: string | null;
+  context_lines?: number;
+  whole_file?: boolean;
+  whitespace?: DiffWhitespaceMode;
 }) {
   const params = new URLSearchParams({
     scope: body.scope,
     path: body.path ?? '',
     context_lines: String(body.context_lines ?? ''),
-    whole_file: String(Boolean(body.whole_file))
+    whole_file: String(Boolean(body.whole_file)),
+    whitespace: body.whitespace ?? 'none'
   });
   return fetchJson<ReviewDiffResponse>(`/api/workflow-runs/${runId}/review/diff?${params.toString()}`);
 }
@@ -1132,3 +1944,749 @@ export function sapScanExportCandidates(repoRef: string) {
 }
 
 
+
+export type DependencyEntry = {
+  ecosystem: 'cargo' | 'npm' | 'pypi' | string;
+  name: string;
+  version: string;
+  license: string | null;
+  dev: boolean;
+  source_file: string;
+};
+
+export type DependencyInventoryResponse = {
+  ok: boolean;
+  repo_ref: string;
+  count: number;
+  dependencies: DependencyEntry[];
+  /** Lockfiles that could not be read or parsed and were left out. */
+  warnings: string[];
+};
+
+export type AnalysisExportResponse = {
+  ok: boolean;
+  format: string;
+  file_name: string;
+  contents: string;
+};
+
+export function getDependencyInventory(repoRef: string) {
+  return fetchJson<DependencyInventoryResponse>('/api/analysis/dependencies', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef })
+  });
+}
+
+export function exportDependencyInventory(repoRef: string, format: 'csv' | 'json') {
+  return fetchJson<AnalysisExportResponse>('/api/analysis/dependencies/export', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, format })
+  });
+}
+
+export type AnalysisReportFormat = 'json' | 'html' | 'csv_extensions' | 'csv_directories';
+
+/** Size analysis as JSON, an HTML report, or a CSV table of per-extension or per-directory stats. */
+export function exportAnalysisReport(repoRef: string, format: AnalysisReportFormat) {
+  const [kind, table] = format.startsWith('csv_') ? ['csv', format.slice(4)] : [format, undefined];
+  return fetchJson<AnalysisExportResponse>('/api/analysis/sizes/export', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, format: kind, table })
+  });
+}
+
+export type ArtifactFinding = {
+  path: string;
+  kind: 'large_blob' | 'build_artifact_dir' | string;
+  bytes: number;
+  file_count: number;
+  tracked: boolean;
+  gitignored: boolean;
+  exclude_regex: string;
+  gitignore_pattern: string;
+};
+
+export type ArtifactScanResponse = {
+  ok: boolean;
+  repo_ref: string;
+  large_blob_bytes: number;
+  findings: ArtifactFinding[];
+};
+
+export function getArtifactFindings(repoRef: string, largeBlobBytes?: number) {
+  return fetchJson<ArtifactScanResponse>('/api/analysis/artifacts', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, large_blob_bytes: largeBlobBytes })
+  });
+}
+
+export function appendGitignorePattern(repoRef: string, pattern: string) {
+  return fetchJson<{ ok: boolean; repo_ref: string; pattern: string; added: boolean }>('/api/analysis/gitignore', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, pattern })
+  });
+}
+
+export type FileSizeEntry = {
+  path: string;
+  bytes: number;
+  lines: number;
+  language: string;
+};
+
+export type FileSizesResponse = {
+  ok: boolean;
+  repo_ref: string;
+  total_bytes: number;
+  total_lines: number;
+  files: FileSizeEntry[];
+};
+
+export function getFileSizes(repoRef: string) {
+  return fetchJson<FileSizesResponse>('/api/analysis/sizes', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef })
+  });
+}
+
+export type RangeAnalysis = {
+  from_ref: string;
+  to_ref: string;
+  commits: number;
+  additions: number;
+  deletions: number;
+  files: { path: string; old_path: string | null; status: string; additions: number | null; deletions: number | null; language: string }[];
+  directories: { directory: string; files: number; additions: number; deletions: number }[];
+  authors: { name: string; email: string; commits: number; additions: number; deletions: number; files: number }[];
+};
+
+export function analyzeRefRange(repoRef: string, fromRef: string, toRef: string, depth = 1) {
+  return fetchJson<RangeAnalysis>('/api/analysis/range', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, from_ref: fromRef, to_ref: toRef, depth })
+  });
+}
+
+export type BrokenDocLink = {
+  source: string;
+  line: number;
+  target: string;
+  resolved: string | null;
+  image: boolean;
+};
+
+export type DocLinkReport = {
+  markdown_files: number;
+  broken: BrokenDocLink[];
+  orphans: string[];
+};
+
+export function scanDocLinks(repoRef: string) {
+  return fetchJson<DocLinkReport>('/api/analysis/doc-links', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef })
+  });
+}
+
+export type ExactDuplicateCluster = {
+  blob: string;
+  bytes: number;
+  paths: string[];
+};
+
+export type SimilarPair = {
+  left: string;
+  right: string;
+  similarity: number;
+};
+
+export type NearDuplicateCluster = {
+  paths: string[];
+  pairs: SimilarPair[];
+};
+
+export type DuplicateReport = {
+  threshold: number;
+  files_scanned: number;
+  exact: ExactDuplicateCluster[];
+  near: NearDuplicateCluster[];
+};
+
+export function findDuplicateFiles(repoRef: string, threshold?: number) {
+  return fetchJson<DuplicateReport>('/api/analysis/duplicates', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, threshold })
+  });
+}
+
+export function diffDuplicateFiles(repoRef: string, left: string, right: string) {
+  return fetchJson<{ ok: boolean; patch: string }>('/api/analysis/duplicates/diff', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, left, right })
+  });
+}
+
+export type DirectoryOwner = {
+  name: string;
+  email: string;
+  commits: number;
+  lines: number;
+};
+
+export type OwnershipMap = {
+  window_days: number;
+  /** Keyed by directory path; `.` is the repository root. */
+  directories: Record<string, DirectoryOwner[]>;
+};
+
+export type ReviewerSuggestions = {
+  window_days: number;
+  changed_paths: string[];
+  reviewers: { name: string; email: string; score: number; paths: string[] }[];
+};
+
+export function getOwnershipMap(repoRef: string, windowDays?: number) {
+  return fetchJson<OwnershipMap>('/api/analysis/ownership', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, window_days: windowDays })
+  });
+}
+
+export function suggestReviewers(repoRef: string, windowDays?: number) {
+  return fetchJson<ReviewerSuggestions>('/api/analysis/ownership/reviewers', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, window_days: windowDays })
+  });
+}
+
+export type AnalysisSnapshot = {
+  id: string;
+  repo_ref: string;
+  name: string;
+  git_ref: string;
+  commit: string | null;
+  total_files: number;
+  total_bytes: number;
+  total_lines: number;
+  created_at: string;
+};
+
+export type SnapshotComparison = {
+  base: AnalysisSnapshot;
+  head: AnalysisSnapshot;
+  added: FileSizeEntry[];
+  removed: FileSizeEntry[];
+  modified: number;
+  directories: { directory: string; base_files: number; head_files: number; base_lines: number; head_lines: number }[];
+  languages: { language: string; base_lines: number; head_lines: number; base_share: number; head_share: number }[];
+};
+
+export function listAnalysisSnapshots(repoRef: string) {
+  return fetchJson<AnalysisSnapshot[]>(`/api/analysis/snapshots?repo_ref=${encodeURIComponent(repoRef)}`);
+}
+
+export function createAnalysisSnapshot(repoRef: string, name: string, gitRef: string) {
+  return fetchJson<AnalysisSnapshot>('/api/analysis/snapshots', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, name, git_ref: gitRef })
+  });
+}
+
+export function compareAnalysisSnapshots(baseId: string, headId: string, depth = 1) {
+  return fetchJson<SnapshotComparison>('/api/analysis/snapshots/compare', {
+    method: 'POST',
+    body: JSON.stringify({ base_id: baseId, head_id: headId, depth })
+  });
+}
+
+export function deleteAnalysisSnapshot(id: string) {
+  return fetchJson<{ ok: boolean; deleted: boolean }>(`/api/analysis/snapshots/${encodeURIComponent(id)}`, {
+    method: 'DELETE'
+  });
+}
+
+export type ProjectCommandKind = 'build' | 'test' | 'run' | 'lint' | 'other';
+
+export type ProjectCommand = {
+  label: string;
+  command: string;
+  kind: ProjectCommandKind;
+  source: string;
+};
+
+export type DetectedProjectCommands = {
+  repo_ref: string;
+  detected_at: string;
+  cached: boolean;
+  commands: ProjectCommand[];
+};
+
+export function getProjectCommands(repoRef: string, refresh = false) {
+  return fetchJson<DetectedProjectCommands>('/api/analysis/commands', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, refresh })
+  });
+}
+
+export type OutlineSymbol = {
+  name: string;
+  qualified_name: string;
+  kind: string;
+  start_line: number;
+  end_line: number;
+  signature: string;
+};
+
+export function getFileOutline(repoRef: string, path: string, gitRef?: string) {
+  return fetchJson<{ ok: boolean; path: string; symbols: OutlineSymbol[] }>('/api/analysis/outline', {
+    method: 'POST',
+    body: JSON.stringify({ repo_ref: repoRef, path, git_ref: gitRef ?? null })
+  });
+}
+
+export type WorkspaceArrangement = 'columns' | 'rows';
+
+export type AppSettings = {
+  browser: Record<string, unknown>;
+  bridges: Record<string, unknown>;
+  git: Record<string, unknown>;
+  network: {
+    proxy_url: string;
+    no_proxy: string;
+    ca_cert_path: string;
+  };
+  inference: InferenceLimits;
+  workspace: {
+    template: string;
+    welcome_dismissed: boolean;
+    split_percent: number | null;
+    snap_percent: number;
+    arrangement: WorkspaceArrangement;
+    auto_associate: boolean;
+    by_repo: Record<string, { template: string; split_percent: number | null; arrangement: WorkspaceArrangement }>;
+    terminal_panes: Record<string, TerminalPaneLayout>;
+  };
+  tree_filters: {
+    by_repo: Record<string, Record<string, RepoTreeFilter>>;
+  };
+  terminal: {
+    by_repo: Record<string, TerminalRepoSettings>;
+    save_hooks_by_repo: Record<string, SaveHookSettings>;
+  };
+  canvas: {
+    by_workflow: Record<string, WorkflowCanvasLayout>;
+  };
+  updates: {
+    enabled: boolean;
+    check_interval_hours: number;
+    feed_url: string;
+    dismissed_version: string;
+  };
+  accessibility: {
+    high_contrast: boolean;
+    /** Pixel floor for UI text; 0 keeps the theme sizes. */
+    min_font_size: number;
+  };
+  plugins: {
+    enabled: boolean;
+    /** Programs plugin scripts may launch through `run()`. */
+    allowed_commands: string[];
+  };
+  automation: {
+    enabled: boolean;
+    /** Whether a bearer token for /api/automation/* exists; the token itself is only returned by `rotateAccessToken`. */
+    token_set: boolean;
+  };
+  mcp: {
+    enabled: boolean;
+    /** Whether a bearer token for the HTTP endpoint at /mcp exists; stdio clients (`workflow-api mcp`) need none. */
+    token_set: boolean;
+    tools: string[];
+    /** Repository roots MCP clients may read; empty allows any. */
+    allowed_repos: string[];
+  };
+  editor: {
+    /** Keyed by the file viewer's `viewerId`, so the main tab, side pane and pop-out wrap independently. */
+    soft_wrap_by_viewer: Record<string, boolean>;
+  };
+  trash: {
+    /** Days a trash entry is kept before it is pruned (1–365). */
+    retention_days: number;
+  };
+  debug: {
+    /** JSONL file every capability trace is appended to; empty keeps traces in memory only. */
+    capability_trace_file: string;
+  };
+};
+
+export type WorkflowCanvasLayout = {
+  viewport: { x: number; y: number; zoom: number } | null;
+  positions: Record<string, { x: number; y: number }>;
+};
+
+export type TerminalEnvProfile = {
+  env: Record<string, string>;
+  working_dir: string;
+};
+
+export type TerminalPane = {
+  id: string;
+  label: string;
+  /** Empty uses the platform default (`sh` or `cmd`). */
+  shell: string;
+  /** Relative to the repo root, or absolute; empty is the repo root. */
+  working_dir: string;
+};
+
+export type TerminalPaneLayout = {
+  broadcast: boolean;
+  panes: TerminalPane[];
+};
+
+export type SaveHookRule = {
+  /** Gitignore-style: `*.rs` matches at any depth, a leading `/` anchors at the repo root. */
+  glob: string;
+  command: string;
+  /** Terminal pane id; its shell and directory are used and its output shows there. */
+  pane: string;
+};
+
+export type SaveHookSettings = {
+  enabled: boolean;
+  debounce_ms: number;
+  rules: SaveHookRule[];
+};
+
+export type SaveHookRun = {
+  id: string;
+  glob: string;
+  command: string;
+  pane: string;
+  paths: string[];
+  started_at: string;
+  /** Null while the command is still running. */
+  status: number | null;
+  stdout: string;
+  stderr: string;
+};
+
+export function listSaveHookRuns(repoRef: string) {
+  return fetchJson<SaveHookRun[]>(`/api/capabilities/save-hooks/runs?repo_ref=${encodeURIComponent(repoRef)}`);
+}
+
+export type TerminalRepoSettings = {
+  active_profile: string | null;
+  profiles: Record<string, TerminalEnvProfile>;
+};
+
+export type InferenceLimits = {
+  max_concurrent_requests: number;
+  requests_per_minute: number;
+};
+
+export type InferenceQueueResponse = {
+  ok: boolean;
+  limits: InferenceLimits;
+  queue: {
+    active: number;
+    active_by_run: Record<string, number>;
+    requests_last_minute: number;
+    waiting: Array<{ ticket: number; run_id: string; position: number; waiting_ms: number }>;
+  };
+};
+
+export type NetworkTestResponse = {
+  ok: boolean;
+  url: string;
+  status: number | null;
+  elapsed_ms: number;
+  error: string | null;
+};
+
+export function getAppSettings() {
+  return fetchJson<AppSettings>('/api/app-settings');
+}
+
+export function patchAppSettings(patch: Record<string, unknown>) {
+  return fetchJson<AppSettings>('/api/app-settings', {
+    method: 'PATCH',
+    body: JSON.stringify({ patch })
+  });
+}
+
+/** Replaces the section's bearer token; the response is the only place the new token is shown. */
+export function rotateAccessToken(section: 'automation' | 'mcp') {
+  return fetchJson<{ token: string }>(`/api/app-settings/${section}/token`, { method: 'POST' });
+}
+
+export type UpdateStatus = {
+  current_version: string;
+  latest_version: string | null;
+  update_available: boolean;
+  release_name: string | null;
+  release_url: string | null;
+  changelog: string | null;
+  published_at: string | null;
+  /** `signature_url` points at the `<asset>.sig` the download is verified against; unsigned binaries are not installed. */
+  asset: { name: string; url: string; size: number; signature_url: string | null; checksum_url: string | null } | null;
+  checked_at: string | null;
+  error: string | null;
+  staged_version: string | null;
+};
+
+export function getUpdateStatus() {
+  return fetchJson<UpdateStatus>('/api/updates');
+}
+
+export function checkForUpdates() {
+  return fetchJson<UpdateStatus>('/api/updates/check', { method: 'POST' });
+}
+
+/** Downloads the new binary; it replaces the current one on next launch. */
+export function stageUpdate() {
+  return fetchJson<UpdateStatus>('/api/updates/stage', { method: 'POST' });
+}
+
+export type PluginEntry = {
+  id: string;
+  title: string;
+};
+
+export type PluginInfo = {
+  id: string;
+  path: string;
+  description: string | null;
+  commands: PluginEntry[];
+  panels: PluginEntry[];
+  error: string | null;
+};
+
+export type PluginCatalog = {
+  dir: string;
+  enabled: boolean;
+  allowed_commands: string[];
+  plugins: PluginInfo[];
+};
+
+export type PluginOutput = {
+  output: string;
+  notifications: { level: string; message: string }[];
+};
+
+export function listPlugins() {
+  return fetchJson<PluginCatalog>('/api/plugins');
+}
+
+export function runPluginCommand(plugin: string, id: string, repoRef?: string | null) {
+  return fetchJson<PluginOutput>('/api/plugins/commands/run', {
+    method: 'POST',
+    body: JSON.stringify({ plugin, id, repo_ref: repoRef || null })
+  });
+}
+
+export function renderPluginPanel(plugin: string, id: string, repoRef?: string | null) {
+  return fetchJson<PluginOutput>('/api/plugins/panels/render', {
+    method: 'POST',
+    body: JSON.stringify({ plugin, id, repo_ref: repoRef || null })
+  });
+}
+
+export type SettingsBundle = {
+  format: string;
+  version: number;
+  exported_at: string;
+  includes_sensitive: boolean;
+  settings: Record<string, unknown>;
+  /** Sensitive sections sealed with the export passphrase. */
+  encrypted?: { sections: string[]; kdf: string; iterations: number; salt: string; nonce: string; ciphertext: string };
+  templates: Array<{ name: string; description: string; repo_ref: string; definition: unknown }>;
+};
+
+export function exportSettingsBundle(body: { include_sensitive: boolean; passphrase: string | null; include_templates: boolean }) {
+  return fetchJson<SettingsBundle>('/api/app-settings/bundle/export', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function importSettingsBundle(body: {
+  bundle: SettingsBundle;
+  sections: string[] | null;
+  include_sensitive: boolean;
+  passphrase: string | null;
+  include_templates: boolean;
+}) {
+  return fetchJson<{ sections: string[]; skipped: string[]; templates: string[]; settings: AppSettings }>('/api/app-settings/bundle/import', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export function testNetworkSettings(body: { network?: Partial<AppSettings['network']>; url?: string | null }) {
+  return fetchJson<NetworkTestResponse>('/api/app-settings/network/test', {
+    method: 'POST',
+    body: JSON.stringify(body)
+  });
+}
+
+export type DebugMetricsResponse = {
+  collected_at: string;
+  process: { resident_bytes: number | null };
+  git: { total: number; last_second: number; per_second_avg: number; window_secs: number };
+  inference_queue: InferenceQueueResponse['queue'];
+  buffers: Record<string, { count: number; bytes: number }>;
+};
+
+export function getDebugMetrics() {
+  return fetchJson<DebugMetricsResponse>('/api/debug/metrics');
+}
+
+export type DoctorCheckStatus = 'ok' | 'warn' | 'fail' | 'skip';
+
+export type DoctorCheck = {
+  id: string;
+  label: string;
+  status: DoctorCheckStatus;
+  detail: string;
+  hint: string | null;
+};
+
+export function getDoctorReport() {
+  return fetchJson<DoctorCheck[]>('/api/doctor');
+}
+
+export type ProcessStatus = 'running' | 'exited' | 'killed';
+
+export type ProcessInfo = {
+  id: number;
+  pid: number;
+  kind: string;
+  label: string;
+  command: string;
+  cwd: string | null;
+  started_at: string;
+  runtime_ms: number;
+  status: ProcessStatus;
+  exit_code: number | null;
+  cpu_percent: number | null;
+  output_tail: string | null;
+};
+
+export async function listProcesses() {
+  const response = await fetchJson<{ processes: ProcessInfo[] }>('/api/processes');
+  return response.processes;
+}
+
+export function killProcess(id: number) {
+  return fetchJson<ProcessInfo>(`/api/processes/${id}/kill`, { method: 'POST' });
+}
+
+export function restartProcess(id: number) {
+  return fetchJson<ProcessInfo>(`/api/processes/${id}/restart`, { method: 'POST' });
+}
+
+export type CapabilityTrace = {
+  id: number;
+  at: string;
+  run_id: string;
+  step_id: string;
+  capability: string;
+  invocation_id: string;
+  ok: boolean;
+  duration_ms: number;
+  request_bytes: number;
+  response_bytes: number;
+  request: unknown;
+  /** `{ preview }` holding the first 64 KiB when `response_truncated`. */
+  response: unknown;
+  response_truncated: boolean;
+  error: string | null;
+};
+
+export function listCapabilityTraces(runId?: string | null) {
+  return fetchJson<CapabilityTrace[]>(`/api/capabilities/traces${runId ? `?run_id=${encodeURIComponent(runId)}` : ''}`);
+}
+
+export function clearCapabilityTraces() {
+  return fetchJson<{ ok: boolean }>('/api/capabilities/traces', { method: 'DELETE' });
+}
+
+export function replayCapabilityTrace(id: number) {
+  return fetchJson<Record<string, unknown>>(`/api/capabilities/traces/${id}/replay`, { method: 'POST' });
+}
+
+export function getInferenceQueue() {
+  return fetchJson<InferenceQueueResponse>('/api/capabilities/inference/queue');
+}
+
+/** `start`/`end` are UTF-8 byte offsets into the prompt recorded next to the request. */
+export type InferenceRequestSection = {
+  name: string;
+  start: number;
+  end: number;
+  bytes: number;
+  approx_tokens: number;
+};
+
+export type InferenceRequestRecord = {
+  transport: 'api' | 'browser';
+  model: string;
+  conversation_id: string | null;
+  parameters: Record<string, unknown>;
+  messages: Array<{ role: string; bytes: number; approx_tokens: number }>;
+  sections: InferenceRequestSection[];
+  total_bytes: number;
+  approx_total_tokens: number;
+  recorded_at: string;
+};
+
+export type SentInferenceRequest = {
+  request: InferenceRequestRecord;
+  prompt: string;
+};
+
+export type InferenceGenerationSettings = {
+  temperature?: number | null;
+  top_p?: number | null;
+  max_output_tokens?: number | null;
+  reasoning_effort?: 'minimal' | 'low' | 'medium' | 'high' | null;
+};
+
+export type AutoStopConditions = {
+  max_iterations?: number | null;
+  max_total_tokens?: number | null;
+  max_cost_usd?: number | null;
+  usd_per_million_tokens?: number | null;
+  max_wall_clock_secs?: number | null;
+  stop_after_consecutive_passes?: number | null;
+};
+
+export type AutoBudget = {
+  started_at: string;
+  iterations: number;
+  total_tokens: number;
+  consecutive_passes: number;
+};
+
+export type ReviewQueueItem = {
+  run_id: string;
+  title: string;
+  repo_ref: string;
+  status: string;
+  reason: 'awaiting_review' | 'failed';
+  current_step_id: string | null;
+  current_step_name: string | null;
+  last_message: string | null;
+  updated_at: string;
+};
+
+export function getReviewQueue() {
+  return fetchJson<ReviewQueueItem[]>('/api/review-queue');
+}
+
+export function submitRunReview(runId: string, decision: 'approve' | 'reject', comment = '') {
+  return fetchJson<{ ok: boolean; run_id: string; decision: string }>(`/api/workflow-runs/${runId}/review`, {
+    method: 'POST',
+    body: JSON.stringify({ decision, comment }),
+  });
+}
@@ -0,0 +1,32 @@
+import type { LanguageHint } from './api';
+
+/** Monaco `KeyMod.CtrlCmd | KeyCode.Slash`. */
+export const TOGGLE_COMMENT_KEYBINDING = 2048 | 90;
+
+/**
+ * Comments every non-blank line at the block's shallowest indent, or uncomments them when they all already
+ * are. Languages with only block comments (CSS, HTML) wrap each line instead.
+ */
+export function toggleLineComments(lines: string[], hint: LanguageHint): string[] {
+  const [open, close] = hint.line_comment ? [hint.line_comment, ''] : hint.block_comment ?? ['', ''];
+  if (!open) return lines;
+  const code = lines.filter((line) => line.trim());
+  if (!code.length) return lines;
+
+  const commented = code.every((line) => line.trimStart().startsWith(open) && line.trimEnd().endsWith(close));
+  if (commented) {
+    return lines.map((line) => {
+      if (!line.trim()) return line;
+      const indent = line.length - line.trimStart().length;
+      let body = line.slice(indent + open.length);
+      if (close) body = body.trimEnd().slice(0, -close.length).replace(/ $/, '');
+      return `${line.slice(0, indent)}${body.replace(/^ /, '')}`;
+    });
+  }
+
+  const indent = Math.min(...code.map((line) => line.length - line.trimStart().length));
+  return lines.map((line) => {
+    if (!line.trim()) return line;
+    return `${line.slice(0, indent)}${open} ${line.slice(indent)}${close ? ` ${close}` : ''}`;
+  });
+}
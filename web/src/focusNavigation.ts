@@ -0,0 +1,60 @@
+import { useEffect } from 'react';
+
+/** Marks an element as a keyboard focus region. The value names the region kind. */
+export const FOCUS_REGION_ATTR = 'data-focus-region';
+
+export function focusRegionProps(kind: string) {
+  return { [FOCUS_REGION_ATTR]: kind, tabIndex: -1 } as Record<string, string | number>;
+}
+
+function visibleRegions() {
+  return Array.from(document.querySelectorAll<HTMLElement>(`[${FOCUS_REGION_ATTR}]`)).filter(
+    (element) => element.offsetParent !== null || element.getClientRects().length > 0,
+  );
+}
+
+function focusRegion(element: HTMLElement | undefined) {
+  if (!element) return;
+  element.focus({ preventScroll: true });
+  element.scrollIntoView({ block: 'nearest', behavior: 'smooth' });
+}
+
+/**
+ * Global focus shortcuts: F6 / Shift+F6 cycle regions, Ctrl+1..9 jump to the Nth region and
+ * Ctrl+` jumps to the most recent terminal-like output region.
+ */
+export function useFocusNavigation() {
+  useEffect(() => {
+    const handler = (event: KeyboardEvent) => {
+      if (event.key === 'F6' && !event.ctrlKey && !event.altKey && !event.metaKey) {
+        const regions = visibleRegions();
+        if (!regions.length) return;
+        event.preventDefault();
+        const current = regions.findIndex((region) => region.contains(document.activeElement));
+        const step = event.shiftKey ? -1 : 1;
+        const next = current < 0 ? (event.shiftKey ? regions.length - 1 : 0) : (current + step + regions.length) % regions.length;
+        focusRegion(regions[next]);
+        return;
+      }
+
+      if (!event.ctrlKey || event.altKey || event.metaKey || event.shiftKey) return;
+
+      if (/^[1-9]$/.test(event.key)) {
+        const region = visibleRegions()[Number(event.key) - 1];
+        if (!region) return;
+        event.preventDefault();
+        focusRegion(region);
+        return;
+      }
+
+      if (event.key === '`' || event.code === 'Backquote') {
+        const terminals = visibleRegions().filter((region) => region.getAttribute(FOCUS_REGION_ATTR) === 'terminal');
+        if (!terminals.length) return;
+        event.preventDefault();
+        focusRegion(terminals[terminals.length - 1]);
+      }
+    };
+    window.addEventListener('keydown', handler);
+    return () => window.removeEventListener('keydown', handler);
+  }, []);
+}
@@ -0,0 +1,128 @@
+import { useCallback, useSyncExternalStore } from 'react';
+import { Badge, Box, Tooltip } from '@mantine/core';
+import { getReviewStatus, type ReviewStatusFileEntry, type ReviewStatusResponse } from './api';
+
+export type GitFileDecoration = 'modified' | 'added' | 'untracked' | 'deleted' | 'conflicted';
+
+export type RepoGitDecorations = {
+  files: ReadonlyMap<string, GitFileDecoration>;
+  /** Strongest status among each directory's changed descendants. */
+  dirs: ReadonlyMap<string, GitFileDecoration>;
+};
+
+const DECORATION_STYLE: Record<GitFileDecoration, { letter: string; color: string; label: string }> = {
+  modified: { letter: 'M', color: 'yellow', label: 'Modified' },
+  added: { letter: 'A', color: 'green', label: 'Added' },
+  untracked: { letter: 'U', color: 'teal', label: 'Untracked' },
+  deleted: { letter: 'D', color: 'gray', label: 'Deleted' },
+  conflicted: { letter: '!', color: 'red', label: 'Conflicted' }
+};
+
+const RANK: Record<GitFileDecoration, number> = { deleted: 0, untracked: 1, added: 2, modified: 3, conflicted: 4 };
+
+const POLL_MS = 5000;
+const EMPTY: RepoGitDecorations = { files: new Map(), dirs: new Map() };
+
+const snapshots = new Map<string, RepoGitDecorations>();
+const signatures = new Map<string, string>();
+const listeners = new Map<string, Set<() => void>>();
+const timers = new Map<string, number>();
+
+function decorate(file: ReviewStatusFileEntry): GitFileDecoration {
+  if (file.conflicted) return 'conflicted';
+  if (file.untracked) return 'untracked';
+  if (file.index_status === 'A') return 'added';
+  if (file.index_status === 'D' || file.worktree_status === 'D') return 'deleted';
+  return 'modified';
+}
+
+function strongest(a: GitFileDecoration | undefined, b: GitFileDecoration) {
+  return a && RANK[a] >= RANK[b] ? a : b;
+}
+
+/**
+ * Feeds a status result into the shared decorations, so the tree and editor tabs update whenever the
+ * Source Control panel refreshes, not only on their own poll.
+ */
+export function publishRepoGitStatus(repoRef: string, status: ReviewStatusResponse) {
+  const key = repoRef.trim();
+  const files = new Map<string, GitFileDecoration>();
+  for (const file of [...status.staged, ...status.unstaged]) {
+    files.set(file.path, strongest(files.get(file.path), decorate(file)));
+  }
+  const signature = JSON.stringify(Array.from(files.entries()).sort());
+  if (signatures.get(key) === signature) return;
+
+  const dirs = new Map<string, GitFileDecoration>();
+  for (const [path, decoration] of files) {
+    const parts = path.split('/');
+    for (let index = 1; index < parts.length; index += 1) {
+      const dir = parts.slice(0, index).join('/');
+      dirs.set(dir, strongest(dirs.get(dir), decoration));
+    }
+  }
+  signatures.set(key, signature);
+  snapshots.set(key, { files, dirs });
+  listeners.get(key)?.forEach((listener) => listener());
+}
+
+function poll(repoRef: string) {
+  if (document.hidden) return;
+  refreshRepoGitStatus(repoRef);
+}
+
+/** Re-reads status now instead of waiting for the next poll, e.g. right after a save. */
+export function refreshRepoGitStatus(repoRef: string) {
+  if (!repoRef.trim() || !listeners.has(repoRef.trim())) return;
+  getReviewStatus(repoRef.trim())
+    .then((status) => publishRepoGitStatus(repoRef, status))
+    .catch(() => undefined);
+}
+
+function subscribe(repoRef: string, listener: () => void) {
+  let set = listeners.get(repoRef);
+  if (!set) {
+    set = new Set();
+    listeners.set(repoRef, set);
+  }
+  set.add(listener);
+  if (!timers.has(repoRef)) {
+    poll(repoRef);
+    timers.set(repoRef, window.setInterval(() => poll(repoRef), POLL_MS));
+  }
+  return () => {
+    set.delete(listener);
+    if (set.size === 0) {
+      window.clearInterval(timers.get(repoRef));
+      timers.delete(repoRef);
+      listeners.delete(repoRef);
+    }
+  };
+}
+
+/** Git status per path for `repoRef`, polled while any component is using it. */
+export function useRepoGitDecorations(repoRef: string): RepoGitDecorations {
+  const key = repoRef.trim();
+  const subscribeToRepo = useCallback((listener: () => void) => (key ? subscribe(key, listener) : () => undefined), [key]);
+  return useSyncExternalStore(subscribeToRepo, () => (key ? snapshots.get(key) ?? EMPTY : EMPTY));
+}
+
+/** Letter badge for a file; directories get a small dot in the colour of their strongest change. */
+export function GitStatusBadge({ decoration, dir = false }: { decoration?: GitFileDecoration | null; dir?: boolean }) {
+  if (!decoration) return null;
+  const style = DECORATION_STYLE[decoration];
+  return (
+    <Tooltip label={dir ? `Contains ${style.label.toLowerCase()} files` : style.label} openDelay={300} withinPortal>
+      {dir ? (
+        <Box
+          aria-label={`Contains ${style.label.toLowerCase()} files`}
+          style={{ width: 6, height: 6, borderRadius: '50%', flexShrink: 0, background: `var(--mantine-color-${style.color}-filled)` }}
+        />
+      ) : (
+        <Badge size="xs" variant="light" color={style.color} aria-label={style.label} style={{ flexShrink: 0 }}>
+          {style.letter}
+        </Badge>
+      )}
+    </Tooltip>
+  );
+}
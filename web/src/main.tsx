@@ -3,6 +3,8 @@ import ReactDOM from 'react-dom/client';
 import { MantineProvider } from '@mantine/core';
 import '@mantine/core/styles.css';
 import '@xyflow/react/dist/style.css';
+import './focus.css';
+import './accessibility.css';
 import App from './App';
 
 ReactDOM.createRoot(document.getElementById('root')!).render(
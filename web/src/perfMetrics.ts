@@ -0,0 +1,18 @@
+type CacheCounter = { hits: number; misses: number; entries: number };
+
+const caches = new Map<string, CacheCounter>();
+
+export function recordCacheLookup(name: string, hit: boolean, entries: number) {
+  const counter = caches.get(name) ?? { hits: 0, misses: 0, entries: 0 };
+  if (hit) counter.hits += 1;
+  else counter.misses += 1;
+  counter.entries = entries;
+  caches.set(name, counter);
+}
+
+export function cacheStats() {
+  return Array.from(caches.entries()).map(([name, counter]) => {
+    const lookups = counter.hits + counter.misses;
+    return { name, ...counter, hitRate: lookups ? counter.hits / lookups : null };
+  });
+}
@@ -0,0 +1,54 @@
+import { parsePatchFiles, type FileDiffMetadata } from '@pierre/diffs';
+import { recordCacheLookup } from './perfMetrics';
+
+const MAX_ENTRIES = 600;
+const MAX_TOTAL_CHARS = 16 * 1024 * 1024;
+
+type Entry = { value: unknown; size: number };
+
+const entries = new Map<string, Entry>();
+let totalChars = 0;
+
+function hashContent(content: string) {
+  let hash = 0x811c9dc5;
+  for (let i = 0; i < content.length; i += 1) {
+    hash ^= content.charCodeAt(i);
+    hash = Math.imul(hash, 0x01000193);
+  }
+  return (hash >>> 0).toString(16);
+}
+
+function evict() {
+  while (entries.size > MAX_ENTRIES || totalChars > MAX_TOTAL_CHARS) {
+    const oldest = entries.keys().next();
+    if (oldest.done) return;
+    totalChars -= entries.get(oldest.value)?.size ?? 0;
+    entries.delete(oldest.value);
+  }
+}
+
+/**
+ * Returns the cached result for identical content across every viewer, so the
+ * same patch or code block is parsed once and keeps a stable object identity.
+ */
+export function getOrCompute<T>(namespace: string, keyParts: string[], content: string, compute: () => T): T {
+  const key = `${namespace}:${keyParts.join(':')}:${content.length}:${hashContent(content)}`;
+  const hit = entries.get(key);
+  if (hit) {
+    entries.delete(key);
+    entries.set(key, hit);
+    recordCacheLookup(namespace, true, entries.size);
+    return hit.value as T;
+  }
+
+  const value = compute();
+  entries.set(key, { value, size: content.length });
+  totalChars += content.length;
+  evict();
+  recordCacheLookup(namespace, false, entries.size);
+  return value;
+}
+
+export function cachedPatchFiles(patch: string): FileDiffMetadata[] {
+  return getOrCompute('diff-parse', [], patch, () => parsePatchFiles(patch).flatMap((item) => item.files ?? []));
+}